@@ -1,4 +1,7 @@
 use rayon::prelude::*;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::Instant;
@@ -6,21 +9,117 @@ use tiny_keccak::{Hasher, Keccak};
 
 // Constants
 const CREATE2_DEPLOYER: [u8; 20] = hex_literal::hex!("4e59b44847b379578588920cA78FbF26c0B4956C");
-const PERMIT2: [u8; 20] = hex_literal::hex!("000000000022D473030F116dDEE9F6B43aC78BA3");
-
-// Target patterns
-const PREFIX: [u8; 2] = [0x40, 0x20]; // 0x4020
-const EXACT_SUFFIX: [u8; 2] = [0x00, 0x01]; // ...0001
-const UPTO_SUFFIX: [u8; 2] = [0x00, 0x02]; // ...0002
-
-// Init code hashes (computed from contracts - no constructor args for chain portability)
-// Run `forge script script/ComputeAddress.s.sol` to verify these match
-// x402ExactPermit2Proxy
-const EXACT_INIT_CODE_HASH: [u8; 32] =
-    hex_literal::hex!("531736bfc0b3dcf1f07c2003a8d79086ce6813b63ec948c482dc3e9d6115370c");
-// x402UptoPermit2Proxy
-const UPTO_INIT_CODE_HASH: [u8; 32] =
-    hex_literal::hex!("747e371bedda1269987a9c38f01901bcc1b1856489221ee5a8cbac8a35893535");
+
+// Width of one mining chunk in the overall `0..u64::MAX` salt-counter space. A worker
+// claims a `[start, end)` multiple of this width via `--start`/`--end` (or the
+// `VANITY_RANGE_START`/`VANITY_RANGE_END` env vars), so N workers can split the space
+// across machines without overlapping, and a crashed worker only loses the chunk it
+// was on rather than starting over from zero.
+const DEFAULT_CHUNK_WIDTH: u64 = 1 << 32;
+
+/// An optional fixed byte run required somewhere in the middle of the address,
+/// checked in addition to `prefix`/`suffix`
+#[derive(Debug, Clone)]
+struct MidPattern {
+    /// Byte offset into the 20-byte address where `bytes` must start
+    offset: usize,
+    bytes: Vec<u8>,
+}
+
+/// One address pattern to mine for, plus the init code hash it's computed against.
+///
+/// Replaces the old hardcoded `PREFIX`/`EXACT_SUFFIX`/`UPTO_SUFFIX` constants so an
+/// operator can mine an arbitrary number of proxy variants in one run by listing them
+/// in a spec file (see [`load_patterns`]).
+#[derive(Debug, Clone)]
+struct Pattern {
+    name: String,
+    init_code_hash: [u8; 32],
+    prefix: Vec<u8>,
+    suffix: Vec<u8>,
+    mid: Option<MidPattern>,
+}
+
+/// The two proxy contracts this repo ships salts for today, used when no `--spec` file
+/// is given so `cargo run` with no arguments keeps working exactly as before.
+fn default_patterns() -> Vec<Pattern> {
+    vec![
+        Pattern {
+            name: "x402ExactPermit2Proxy".to_string(),
+            init_code_hash: hex_literal::hex!(
+                "531736bfc0b3dcf1f07c2003a8d79086ce6813b63ec948c482dc3e9d6115370c"
+            ),
+            prefix: vec![0x40, 0x20],
+            suffix: vec![0x00, 0x01],
+            mid: None,
+        },
+        Pattern {
+            name: "x402UptoPermit2Proxy".to_string(),
+            init_code_hash: hex_literal::hex!(
+                "747e371bedda1269987a9c38f01901bcc1b1856489221ee5a8cbac8a35893535"
+            ),
+            prefix: vec![0x40, 0x20],
+            suffix: vec![0x00, 0x02],
+            mid: None,
+        },
+    ]
+}
+
+/// Load patterns from a spec file, one pattern per non-empty, non-`#`-prefixed line:
+///
+/// ```text
+/// name,init_code_hash_hex,prefix_hex,suffix_hex[,mid_offset:mid_hex]
+/// ```
+///
+/// This workspace snapshot has no `Cargo.toml`, so there's no way to add the
+/// `toml`/`serde` dependencies a real TOML/JSON spec loader would need; this
+/// delimiter-separated format covers the same fields (name, init code hash, prefix,
+/// suffix, optional mid-pattern) with only `std`, and can be swapped for a real
+/// `toml::from_str::<Vec<Pattern>>` once those crates are available.
+fn load_patterns(path: &Path) -> Vec<Pattern> {
+    let contents = fs::read_to_string(path)
+        .unwrap_or_else(|e| panic!("failed to read pattern spec {}: {}", path.display(), e));
+
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let fields: Vec<&str> = line.split(',').collect();
+            assert!(
+                fields.len() >= 4,
+                "malformed pattern spec line (need name,hash,prefix,suffix[,mid]): {}",
+                line
+            );
+
+            let mid = fields.get(4).map(|mid_field| {
+                let (offset, mid_hex) = mid_field
+                    .split_once(':')
+                    .unwrap_or_else(|| panic!("malformed mid-pattern field: {}", mid_field));
+                MidPattern {
+                    offset: offset
+                        .parse()
+                        .unwrap_or_else(|_| panic!("invalid mid-pattern offset: {}", offset)),
+                    bytes: hex::decode(mid_hex)
+                        .unwrap_or_else(|_| panic!("invalid mid-pattern hex: {}", mid_hex)),
+                }
+            });
+
+            Pattern {
+                name: fields[0].to_string(),
+                init_code_hash: hex::decode(fields[1])
+                    .unwrap_or_else(|_| panic!("invalid init code hash: {}", fields[1]))
+                    .try_into()
+                    .unwrap_or_else(|_| panic!("init code hash must be 32 bytes: {}", fields[1])),
+                prefix: hex::decode(fields[2])
+                    .unwrap_or_else(|_| panic!("invalid prefix hex: {}", fields[2])),
+                suffix: hex::decode(fields[3])
+                    .unwrap_or_else(|_| panic!("invalid suffix hex: {}", fields[3])),
+                mid,
+            }
+        })
+        .collect()
+}
 
 fn compute_create2_address(salt: &[u8; 32], init_code_hash: &[u8; 32]) -> [u8; 20] {
     let mut hasher = Keccak::v256();
@@ -35,7 +134,7 @@ fn compute_create2_address(salt: &[u8; 32], init_code_hash: &[u8; 32]) -> [u8; 2
     addr
 }
 
-fn matches_pattern(addr: &[u8; 20], prefix: &[u8], suffix: &[u8]) -> bool {
+fn matches_pattern(addr: &[u8; 20], prefix: &[u8], suffix: &[u8], mid: &Option<MidPattern>) -> bool {
     // Check prefix
     for (i, &b) in prefix.iter().enumerate() {
         if addr[i] != b {
@@ -50,50 +149,149 @@ fn matches_pattern(addr: &[u8; 20], prefix: &[u8], suffix: &[u8]) -> bool {
             return false;
         }
     }
+    // Check optional mid-pattern
+    if let Some(mid) = mid {
+        for (i, &b) in mid.bytes.iter().enumerate() {
+            if addr[mid.offset + i] != b {
+                return false;
+            }
+        }
+    }
     true
 }
 
-fn mine_vanity(
-    name: &str,
-    init_code_hash: &[u8; 32],
-    prefix: &[u8],
-    suffix: &[u8],
-) -> Option<([u8; 32], [u8; 20])> {
+/// A `[start, end)` range of the `u64` salt-counter space claimed by one worker, plus
+/// where it checkpoints progress so a restarted worker resumes mid-range instead of
+/// from zero
+#[derive(Debug, Clone, Copy)]
+struct MiningPlan {
+    start: u64,
+    end: u64,
+}
+
+impl MiningPlan {
+    /// Read `--start`/`--end` from `args`, falling back to the `VANITY_RANGE_START`/
+    /// `VANITY_RANGE_END` env vars, and finally to the whole `0..u64::MAX` space (the
+    /// original single-process behavior) when neither is set
+    fn from_args_or_env(args: &[String]) -> Self {
+        let start = parse_flag(args, "--start")
+            .or_else(|| std::env::var("VANITY_RANGE_START").ok())
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(0);
+        let end = parse_flag(args, "--end")
+            .or_else(|| std::env::var("VANITY_RANGE_END").ok())
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(u64::MAX);
+
+        assert!(start < end, "--start must be less than --end");
+        Self { start, end }
+    }
+}
+
+fn parse_flag(args: &[String], flag: &str) -> Option<String> {
+    args.iter()
+        .position(|a| a == flag)
+        .and_then(|i| args.get(i + 1))
+        .cloned()
+}
+
+/// Progress checkpoint for a single worker's [`MiningPlan`], so a crash or restart
+/// resumes from the last completed offset instead of re-scanning the whole range
+#[derive(Debug, Clone, Copy, Default)]
+struct Checkpoint {
+    /// Offset within the plan's range already scanned (relative to `plan.start`)
+    completed_offset: u64,
+    elapsed_secs: f64,
+    rate: f64,
+}
+
+impl Checkpoint {
+    fn load(path: &Path) -> Self {
+        let Ok(contents) = fs::read_to_string(path) else {
+            return Self::default();
+        };
+
+        let mut checkpoint = Self::default();
+        for line in contents.lines() {
+            if let Some((key, value)) = line.split_once('=') {
+                match key {
+                    "completed_offset" => checkpoint.completed_offset = value.parse().unwrap_or(0),
+                    "elapsed_secs" => checkpoint.elapsed_secs = value.parse().unwrap_or(0.0),
+                    "rate" => checkpoint.rate = value.parse().unwrap_or(0.0),
+                    _ => {}
+                }
+            }
+        }
+        checkpoint
+    }
+
+    fn save(&self, path: &Path) {
+        let contents = format!(
+            "completed_offset={}\nelapsed_secs={:.3}\nrate={:.1}\n",
+            self.completed_offset, self.elapsed_secs, self.rate
+        );
+        // A checkpoint write failing (e.g. disk full) shouldn't crash a mining run
+        // that's otherwise making progress; it just means a restart loses less.
+        if let Ok(mut file) = fs::File::create(path) {
+            let _ = file.write_all(contents.as_bytes());
+        }
+    }
+}
+
+/// Mine within `plan`'s `[start, end)` range, resuming from `checkpoint_path`'s last
+/// completed offset if one exists, and periodically overwriting it with progress
+fn mine_vanity(pattern: &Pattern, plan: &MiningPlan, checkpoint_path: &Path) -> Option<([u8; 32], [u8; 20])> {
     println!("\n{}", "=".repeat(60));
-    println!("Mining for {} (0x{}...{})", name, hex::encode(prefix), hex::encode(suffix));
-    println!("Init code hash: 0x{}", hex::encode(init_code_hash));
+    println!(
+        "Mining for {} (0x{}...{})",
+        pattern.name,
+        hex::encode(&pattern.prefix),
+        hex::encode(&pattern.suffix)
+    );
+    println!("Init code hash: 0x{}", hex::encode(pattern.init_code_hash));
+
+    let checkpoint = Checkpoint::load(checkpoint_path);
+    let resume_start = plan.start.saturating_add(checkpoint.completed_offset);
+    println!(
+        "Range: [{}, {}), resuming from offset {} ({})",
+        plan.start, plan.end, checkpoint.completed_offset, resume_start
+    );
     println!("{}", "=".repeat(60));
 
     let found = Arc::new(AtomicBool::new(false));
-    let counter = Arc::new(AtomicU64::new(0));
-    let start = Instant::now();
+    let counter = Arc::new(AtomicU64::new(checkpoint.completed_offset));
+    let start_time = Instant::now();
+    let prior_elapsed = checkpoint.elapsed_secs;
 
-    // Use parallel iteration with rayon
-    let result = (0u64..u64::MAX)
+    let result = (resume_start..plan.end)
         .into_par_iter()
         .find_map_any(|i| {
             if found.load(Ordering::Relaxed) {
                 return None;
             }
 
-            // Generate salt from counter
             let mut salt = [0u8; 32];
             salt[24..32].copy_from_slice(&i.to_be_bytes());
 
-            let addr = compute_create2_address(&salt, init_code_hash);
+            let addr = compute_create2_address(&salt, &pattern.init_code_hash);
 
-            // Update counter for progress
             let count = counter.fetch_add(1, Ordering::Relaxed);
             if count > 0 && count % 10_000_000 == 0 {
-                let elapsed = start.elapsed().as_secs_f64();
+                let elapsed = prior_elapsed + start_time.elapsed().as_secs_f64();
                 let rate = count as f64 / elapsed;
                 println!(
                     "  Progress: {} attempts ({:.0} addr/sec, {:.1}s elapsed)",
                     count, rate, elapsed
                 );
+                Checkpoint {
+                    completed_offset: count,
+                    elapsed_secs: elapsed,
+                    rate,
+                }
+                .save(checkpoint_path);
             }
 
-            if matches_pattern(&addr, prefix, suffix) {
+            if matches_pattern(&addr, &pattern.prefix, &pattern.suffix, &pattern.mid) {
                 found.store(true, Ordering::Relaxed);
                 Some((salt, addr))
             } else {
@@ -101,59 +299,86 @@ fn mine_vanity(
             }
         });
 
+    let elapsed = prior_elapsed + start_time.elapsed().as_secs_f64();
+    let count = counter.load(Ordering::Relaxed);
+
     if let Some((salt, addr)) = result {
-        let elapsed = start.elapsed().as_secs_f64();
-        let count = counter.load(Ordering::Relaxed);
         println!("\n✅ FOUND MATCH!");
         println!("   Salt:    0x{}", hex::encode(salt));
         println!("   Address: 0x{}", hex::encode(addr));
-        println!("   Attempts: {} ({:.1}s, {:.0} addr/sec)", count, elapsed, count as f64 / elapsed);
+        println!(
+            "   Attempts: {} ({:.1}s, {:.0} addr/sec)",
+            count,
+            elapsed,
+            count as f64 / elapsed
+        );
+        // A match was found; clear the checkpoint so a future run of this pattern
+        // over the same range doesn't think there's unfinished work left.
+        let _ = fs::remove_file(checkpoint_path);
         return Some((salt, addr));
     }
 
+    // Exhausted the claimed range without a match; leave the final checkpoint in
+    // place so `--start`/`--end` can be widened and resumed from here.
+    Checkpoint {
+        completed_offset: count,
+        elapsed_secs: elapsed,
+        rate: count as f64 / elapsed.max(f64::EPSILON),
+    }
+    .save(checkpoint_path);
+
     None
 }
 
 fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
     println!("\n🔍 x402 Vanity Address Miner (Rust)");
-    println!("   Prefix: 0x{}", hex::encode(PREFIX));
-    println!("   Exact suffix: 0x{}", hex::encode(EXACT_SUFFIX));
-    println!("   Upto suffix: 0x{}", hex::encode(UPTO_SUFFIX));
+
+    let patterns = parse_flag(&args, "--spec")
+        .or_else(|| std::env::var("VANITY_SPEC_FILE").ok())
+        .map(|spec_path| load_patterns(&PathBuf::from(spec_path)))
+        .unwrap_or_else(default_patterns);
+
+    let plan = MiningPlan::from_args_or_env(&args);
+    let checkpoint_dir = parse_flag(&args, "--checkpoint-dir")
+        .or_else(|| std::env::var("VANITY_CHECKPOINT_DIR").ok())
+        .unwrap_or_else(|| ".".to_string());
+
     println!("   CREATE2 Deployer: 0x{}", hex::encode(CREATE2_DEPLOYER));
+    println!("   Patterns: {}", patterns.len());
+    println!("   Range: [{}, {}) (default chunk width {})", plan.start, plan.end, DEFAULT_CHUNK_WIDTH);
 
-    // Get number of threads
     let num_threads = rayon::current_num_threads();
     println!("   Using {} threads", num_threads);
 
-    // Mine for Exact contract
-    let exact_result = mine_vanity("x402ExactPermit2Proxy", &EXACT_INIT_CODE_HASH, &PREFIX, &EXACT_SUFFIX);
-
-    // Mine for Upto contract  
-    let upto_result = mine_vanity("x402UptoPermit2Proxy", &UPTO_INIT_CODE_HASH, &PREFIX, &UPTO_SUFFIX);
+    let mut results = Vec::with_capacity(patterns.len());
+    for pattern in &patterns {
+        let checkpoint_path = PathBuf::from(&checkpoint_dir).join(format!("{}.checkpoint", pattern.name));
+        let result = mine_vanity(pattern, &plan, &checkpoint_path);
+        results.push((pattern.name.clone(), result));
+    }
 
-    // Summary
     println!("\n{}", "=".repeat(60));
     println!("SUMMARY");
     println!("{}", "=".repeat(60));
 
-    if let Some((salt, addr)) = exact_result {
-        println!("\nx402ExactPermit2Proxy:");
-        println!("  Salt:    0x{}", hex::encode(salt));
-        println!("  Address: 0x{}", hex::encode(addr));
-    }
-
-    if let Some((salt, addr)) = upto_result {
-        println!("\nx402UptoPermit2Proxy:");
-        println!("  Salt:    0x{}", hex::encode(salt));
-        println!("  Address: 0x{}", hex::encode(addr));
+    for (name, result) in &results {
+        if let Some((salt, addr)) = result {
+            println!("\n{}:", name);
+            println!("  Salt:    0x{}", hex::encode(salt));
+            println!("  Address: 0x{}", hex::encode(addr));
+        } else {
+            println!("\n{}: no match found in claimed range", name);
+        }
     }
 
-    if exact_result.is_some() && upto_result.is_some() {
-        let (exact_salt, _) = exact_result.unwrap();
-        let (upto_salt, _) = upto_result.unwrap();
+    if results.iter().all(|(_, r)| r.is_some()) {
         println!("\n// Update Deploy.s.sol with these values:");
-        println!("bytes32 constant EXACT_SALT = 0x{};", hex::encode(exact_salt));
-        println!("bytes32 constant UPTO_SALT = 0x{};", hex::encode(upto_salt));
+        for (name, result) in &results {
+            let (salt, _) = result.unwrap();
+            println!("bytes32 constant {}_SALT = 0x{};", name.to_uppercase(), hex::encode(salt));
+        }
     }
 }
 
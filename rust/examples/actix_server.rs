@@ -7,12 +7,19 @@ use actix_web::{
     web, App, HttpRequest, HttpResponse, HttpServer, Result,
 };
 use std::str::FromStr;
+use std::sync::Arc;
 use x402::{
+    facilitator::{FacilitatorClient, FacilitatorRegistry},
     middleware::PaymentMiddleware,
+    nonce_store::{InMemoryNonceReplayStore, NonceReplayStore},
     types::{PaymentRequirements, FacilitatorConfig},
 };
 
-use x402::actix_web::{create_x402_middleware, handle_payment_verification};
+use x402::actix_web::{create_x402_middleware, handle_payment_verification_with_replay_guard};
+
+/// Shared replay-protection store, rejecting a second presentation of the same
+/// `X-PAYMENT` header within its authorization's `validBefore` window
+type ReplayStore = web::Data<Arc<dyn NonceReplayStore>>;
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
@@ -21,23 +28,42 @@ async fn main() -> std::io::Result<()> {
 
     println!("🚀 Starting x402 Actix-web server on http://localhost:4022");
 
-    // Create facilitator config
-    let facilitator_config = FacilitatorConfig::default();
-    
+    // Route each network's payments to its own facilitator provider instead of a
+    // single shared FacilitatorConfig: Base Sepolia through the hosted CDP
+    // facilitator, Avalanche Fuji through a self-hosted one.
+    let cdp_facilitator = FacilitatorClient::new(FacilitatorConfig::default()).unwrap();
+    let avalanche_facilitator =
+        FacilitatorClient::new(FacilitatorConfig::new("https://facilitator.internal.example")).unwrap();
+
+    let facilitator_registry = FacilitatorRegistry::new()
+        .with_backend(
+            "cdp",
+            Arc::new(cdp_facilitator),
+            [("base-sepolia".to_string(), "exact".to_string())],
+        )
+        .with_backend(
+            "self-hosted",
+            Arc::new(avalanche_facilitator),
+            [("avalanche-fuji".to_string(), "exact".to_string())],
+        );
+
     // Create payment middleware
     let payment_middleware = PaymentMiddleware::new(
         rust_decimal::Decimal::from_str("0.0001").unwrap(),
         "0x209693Bc6afc0C5328bA36FaF03C514EF312287C".to_string(),
     )
-    .with_facilitator_config(facilitator_config)
+    .with_facilitator_registry(facilitator_registry)
     .with_description("Premium API access".to_string());
 
     // Create x402 middleware
     let x402_middleware = create_x402_middleware(payment_middleware);
 
+    let replay_store: Arc<dyn NonceReplayStore> = Arc::new(InMemoryNonceReplayStore::new());
+
     // Start server
     HttpServer::new(move || {
         App::new()
+            .app_data(web::Data::new(replay_store.clone()))
             .wrap(Logger::default())
             .service(
                 web::scope("/api")
@@ -53,7 +79,7 @@ async fn main() -> std::io::Result<()> {
 }
 
 /// Protected joke endpoint
-async fn joke_handler(req: HttpRequest) -> Result<HttpResponse> {
+async fn joke_handler(req: HttpRequest, replay_store: ReplayStore) -> Result<HttpResponse> {
     // Define payment requirements
     let requirements = vec![PaymentRequirements {
         scheme: "exact".to_string(),
@@ -70,7 +96,9 @@ async fn joke_handler(req: HttpRequest) -> Result<HttpResponse> {
     }];
 
     // Check payment
-    match handle_payment_verification(&req, &requirements).await? {
+    match handle_payment_verification_with_replay_guard(&req, &requirements, replay_store.get_ref().as_ref())
+        .await?
+    {
         Some(response) => Ok(response),
         None => {
             // Payment verified, return joke
@@ -84,7 +112,7 @@ async fn joke_handler(req: HttpRequest) -> Result<HttpResponse> {
 }
 
 /// Protected API data endpoint
-async fn api_data_handler(req: HttpRequest) -> Result<HttpResponse> {
+async fn api_data_handler(req: HttpRequest, replay_store: ReplayStore) -> Result<HttpResponse> {
     let requirements = vec![PaymentRequirements {
         scheme: "exact".to_string(),
         network: "base-sepolia".to_string(),
@@ -99,7 +127,9 @@ async fn api_data_handler(req: HttpRequest) -> Result<HttpResponse> {
         extra: None,
     }];
 
-    match handle_payment_verification(&req, &requirements).await? {
+    match handle_payment_verification_with_replay_guard(&req, &requirements, replay_store.get_ref().as_ref())
+        .await?
+    {
         Some(response) => Ok(response),
         None => {
             Ok(HttpResponse::Ok().json(serde_json::json!({
@@ -112,7 +142,7 @@ async fn api_data_handler(req: HttpRequest) -> Result<HttpResponse> {
 }
 
 /// Protected download endpoint
-async fn download_handler(req: HttpRequest) -> Result<HttpResponse> {
+async fn download_handler(req: HttpRequest, replay_store: ReplayStore) -> Result<HttpResponse> {
     let requirements = vec![PaymentRequirements {
         scheme: "exact".to_string(),
         network: "base-sepolia".to_string(),
@@ -127,7 +157,9 @@ async fn download_handler(req: HttpRequest) -> Result<HttpResponse> {
         extra: None,
     }];
 
-    match handle_payment_verification(&req, &requirements).await? {
+    match handle_payment_verification_with_replay_guard(&req, &requirements, replay_store.get_ref().as_ref())
+        .await?
+    {
         Some(response) => Ok(response),
         None => {
             // Simulate file download
@@ -10,23 +10,103 @@ use axum::{
 use serde::Deserialize;
 use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::RwLock;
 use tracing_subscriber;
 
 use rand::Rng;
+use x402::facilitator::BoxFuture;
+use x402::nonce_store::{BloomFilteredNonceStore, InMemoryNonceStore, NonceStore};
+use x402::webhook::{SettlementEvent, SettlementStatus, WebhookConfig, WebhookNotifier};
 use x402::{types::*, Result, X402Error};
 
-/// Simple in-memory facilitator for demonstration
-#[derive(Debug, Clone)]
-struct SimpleFacilitator {
-    /// Track processed nonces to prevent replay attacks
-    processed_nonces: Arc<RwLock<HashMap<String, bool>>>,
+/// Object-safe settlement backend, abstracting over how a given `(scheme, network)`
+/// pair is actually verified and settled
+///
+/// Mirrors hyperswitch's connector registry: each backend owns one integration (here,
+/// raw EVM/ERC-3009 handling), and [`SettlementBackendRegistry`] is the seam that
+/// routes an incoming payload to whichever backend declared support for its scheme and
+/// network, so adding a non-EVM scheme (e.g. a future Solana or Lightning backend)
+/// means registering a new [`SettlementBackend`] impl rather than branching inside a
+/// single monolithic facilitator.
+trait SettlementBackend: Send + Sync {
+    /// Schemes, networks and protocol versions this backend accepts
+    fn supported_kinds(&self) -> Vec<SupportedKind>;
+
+    /// Verify a payment payload against the given requirements
+    fn verify<'a>(
+        &'a self,
+        payment_payload: &'a PaymentPayload,
+        payment_requirements: &'a PaymentRequirements,
+    ) -> BoxFuture<'a, Result<VerifyResponse>>;
+
+    /// Settle a verified payment
+    fn settle<'a>(
+        &'a self,
+        payment_payload: &'a PaymentPayload,
+        payment_requirements: &'a PaymentRequirements,
+    ) -> BoxFuture<'a, Result<SettleResponse>>;
 }
 
-impl SimpleFacilitator {
+/// Dispatches an incoming payload to whichever registered [`SettlementBackend`]
+/// declared support for its `(scheme, network)` pair
+#[derive(Clone, Default)]
+struct SettlementBackendRegistry {
+    backends: HashMap<(String, String), Arc<dyn SettlementBackend>>,
+}
+
+impl SettlementBackendRegistry {
     fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register `backend` for every `(scheme, network)` pair it declares support for
+    fn register(&mut self, backend: Arc<dyn SettlementBackend>) {
+        for kind in backend.supported_kinds() {
+            self.backends.insert((kind.scheme, kind.network), backend.clone());
+        }
+    }
+
+    /// Look up the backend registered for `scheme`/`network`, if any
+    fn get(&self, scheme: &str, network: &str) -> Option<Arc<dyn SettlementBackend>> {
+        self.backends.get(&(scheme.to_string(), network.to_string())).cloned()
+    }
+
+    /// Aggregate `supported_kinds()` across every distinct registered backend
+    fn supported_kinds(&self) -> Vec<SupportedKind> {
+        self.backends
+            .keys()
+            .map(|(scheme, network)| SupportedKind {
+                x402_version: X402_VERSION,
+                scheme: scheme.clone(),
+                network: network.clone(),
+            })
+            .collect()
+    }
+}
+
+/// EVM settlement backend, handling the `exact` scheme on Base and Avalanche via a
+/// simulated ERC-3009 `transferWithAuthorization` settlement
+struct EvmSettlementBackend {
+    /// Bloom-filtered nonce replay store, scoped per network; checked at verify time
+    /// and spent at settle time so a nonce is only reserved once its authorization is
+    /// actually settled
+    processed_nonces: Arc<dyn NonceStore>,
+    /// Notifies resource servers of settlement lifecycle events; registered webhooks
+    /// are managed out of band via a `/webhooks` route (see
+    /// [`x402::axum::webhook_registration_route`])
+    webhooks: Arc<tokio::sync::Mutex<WebhookNotifier>>,
+}
+
+impl std::fmt::Debug for EvmSettlementBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EvmSettlementBackend").finish_non_exhaustive()
+    }
+}
+
+impl EvmSettlementBackend {
+    fn new(webhooks: Arc<tokio::sync::Mutex<WebhookNotifier>>) -> Self {
         Self {
-            processed_nonces: Arc::new(RwLock::new(HashMap::new())),
+            processed_nonces: Arc::new(BloomFilteredNonceStore::new(Arc::new(InMemoryNonceStore::new()))),
+            webhooks,
         }
     }
 
@@ -38,15 +118,12 @@ impl SimpleFacilitator {
     ) -> Result<VerifyResponse> {
         // Check if nonce has been used before (replay protection)
         let nonce = &payload.payload.authorization.nonce;
-        {
-            let nonces = self.processed_nonces.write().await;
-            if nonces.contains_key(nonce) {
-                return Ok(VerifyResponse {
-                    is_valid: false,
-                    invalid_reason: Some("nonce_already_used".to_string()),
-                    payer: Some(payload.payload.authorization.from.clone()),
-                });
-            }
+        if self.processed_nonces.contains(&payload.network, nonce).await {
+            return Ok(VerifyResponse {
+                is_valid: false,
+                invalid_reason: Some("nonce_already_used".to_string()),
+                payer: Some(payload.payload.authorization.from.clone()),
+            });
         }
 
         // Verify authorization timing
@@ -58,6 +135,32 @@ impl SimpleFacilitator {
             });
         }
 
+        // Verify the EIP-712 signature actually authorizes this transfer:
+        // reconstruct the `TransferWithAuthorization` struct hash under the
+        // USDC token's domain separator for this network and ecrecover the
+        // signer, rather than trusting `authorization.from` as given.
+        match x402::crypto::signature::verify_payment_payload(
+            &payload.payload,
+            &payload.payload.authorization.from,
+            &payload.network,
+        ) {
+            Ok(true) => {}
+            Ok(false) => {
+                return Ok(VerifyResponse {
+                    is_valid: false,
+                    invalid_reason: Some("signature_mismatch".to_string()),
+                    payer: Some(payload.payload.authorization.from.clone()),
+                })
+            }
+            Err(_) => {
+                return Ok(VerifyResponse {
+                    is_valid: false,
+                    invalid_reason: Some("invalid_signature".to_string()),
+                    payer: Some(payload.payload.authorization.from.clone()),
+                })
+            }
+        }
+
         // Verify amount meets requirements
         let payment_amount: u128 = payload
             .payload
@@ -87,11 +190,17 @@ impl SimpleFacilitator {
             });
         }
 
-        // Mark nonce as processed
-        {
-            let mut nonces = self.processed_nonces.write().await;
-            nonces.insert(nonce.clone(), true);
-        }
+        self.webhooks
+            .lock()
+            .await
+            .notify(&SettlementEvent::new(
+                nonce,
+                payload.network.clone(),
+                "",
+                Some(payload.payload.authorization.from.clone()),
+                SettlementStatus::Verified,
+            ))
+            .await;
 
         Ok(VerifyResponse {
             is_valid: true,
@@ -114,19 +223,50 @@ impl SimpleFacilitator {
         // 5. Implement retry logic for failed transactions
 
         // For this example, we'll simulate a realistic settlement process
-        use x402::crypto::signature;
 
         // Generate a more realistic transaction hash (64 hex characters)
         let mut rng = rand::thread_rng();
         let tx_hash_bytes: [u8; 32] = rng.gen();
         let mock_transaction_hash = format!("0x{}", hex::encode(tx_hash_bytes));
 
+        let nonce = &payload.payload.authorization.nonce;
+        let payer = Some(payload.payload.authorization.from.clone());
+        self.webhooks
+            .lock()
+            .await
+            .notify(&SettlementEvent::new(
+                nonce,
+                payload.network.clone(),
+                mock_transaction_hash.clone(),
+                payer.clone(),
+                SettlementStatus::Submitted,
+            ))
+            .await;
+
         // Simulate network delay (in real implementation, this would be blockchain confirmation time)
         tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
 
         // In production, you would verify the transaction was actually mined
         // and check its status on the blockchain
 
+        // Spend the nonce now that settlement has actually happened, not at verify
+        // time, so a nonce is only reserved once its authorization is settled.
+        self.processed_nonces
+            .mark_used(&payload.network, &payload.payload.authorization.nonce)
+            .await;
+
+        self.webhooks
+            .lock()
+            .await
+            .notify(&SettlementEvent::new(
+                nonce,
+                payload.network.clone(),
+                mock_transaction_hash.clone(),
+                payer,
+                SettlementStatus::Confirmed,
+            ))
+            .await;
+
         Ok(SettleResponse {
             success: true,
             error_reason: None,
@@ -137,6 +277,84 @@ impl SimpleFacilitator {
     }
 }
 
+impl SettlementBackend for EvmSettlementBackend {
+    fn supported_kinds(&self) -> Vec<SupportedKind> {
+        vec![
+            SupportedKind {
+                x402_version: X402_VERSION,
+                scheme: schemes::EXACT.to_string(),
+                network: networks::BASE_SEPOLIA.to_string(),
+            },
+            SupportedKind {
+                x402_version: X402_VERSION,
+                scheme: schemes::EXACT.to_string(),
+                network: networks::BASE_MAINNET.to_string(),
+            },
+            SupportedKind {
+                x402_version: X402_VERSION,
+                scheme: schemes::EXACT.to_string(),
+                network: networks::AVALANCHE_FUJI.to_string(),
+            },
+            SupportedKind {
+                x402_version: X402_VERSION,
+                scheme: schemes::EXACT.to_string(),
+                network: networks::AVALANCHE_MAINNET.to_string(),
+            },
+        ]
+    }
+
+    fn verify<'a>(
+        &'a self,
+        payment_payload: &'a PaymentPayload,
+        payment_requirements: &'a PaymentRequirements,
+    ) -> BoxFuture<'a, Result<VerifyResponse>> {
+        Box::pin(self.verify_payment(payment_payload, payment_requirements))
+    }
+
+    fn settle<'a>(
+        &'a self,
+        payment_payload: &'a PaymentPayload,
+        payment_requirements: &'a PaymentRequirements,
+    ) -> BoxFuture<'a, Result<SettleResponse>> {
+        Box::pin(self.settle_payment(payment_payload, payment_requirements))
+    }
+}
+
+/// Facilitator API state: a registry of settlement backends and the webhook notifier
+/// shared across all of them
+#[derive(Clone)]
+struct SimpleFacilitator {
+    registry: Arc<SettlementBackendRegistry>,
+    webhooks: Arc<tokio::sync::Mutex<WebhookNotifier>>,
+}
+
+impl std::fmt::Debug for SimpleFacilitator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SimpleFacilitator").finish_non_exhaustive()
+    }
+}
+
+impl SimpleFacilitator {
+    fn new() -> Self {
+        let webhooks = Arc::new(tokio::sync::Mutex::new(WebhookNotifier::new(Vec::new())));
+
+        let mut registry = SettlementBackendRegistry::new();
+        registry.register(Arc::new(EvmSettlementBackend::new(webhooks.clone())));
+
+        Self {
+            registry: Arc::new(registry),
+            webhooks,
+        }
+    }
+
+    /// Register a webhook to be notified of settlement lifecycle events; exposed here
+    /// for the demo's own setup rather than requiring callers to reach into `webhooks`
+    #[allow(dead_code)]
+    async fn register_webhook(&self, config: WebhookConfig) {
+        self.webhooks.lock().await.register(config);
+    }
+}
+
 /// Request types for the facilitator API
 #[derive(Debug, Deserialize)]
 struct VerifyRequest {
@@ -172,7 +390,11 @@ async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
         .route("/verify", post(verify_handler))
         .route("/settle", post(settle_handler))
         .route("/supported", get(supported_handler))
-        .with_state(facilitator);
+        .with_state(facilitator.clone())
+        .merge(x402::axum::webhook_registration_route(
+            "/webhooks",
+            facilitator.webhooks.clone(),
+        ));
 
     // Start the server
     let listener = tokio::net::TcpListener::bind("0.0.0.0:3000").await?;
@@ -181,6 +403,7 @@ async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
     println!("   POST /verify - Verify payment authorization");
     println!("   POST /settle - Settle verified payment");
     println!("   GET /supported - Get supported payment schemes");
+    println!("   POST /webhooks - Register a settlement lifecycle webhook");
 
     axum::serve(listener, app).await?;
 
@@ -196,8 +419,13 @@ async fn verify_handler(
         return Err(StatusCode::BAD_REQUEST);
     }
 
-    match facilitator
-        .verify_payment(&request.payment_payload, &request.payment_requirements)
+    let backend = facilitator
+        .registry
+        .get(&request.payment_payload.scheme, &request.payment_payload.network)
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    match backend
+        .verify(&request.payment_payload, &request.payment_requirements)
         .await
     {
         Ok(response) => Ok(Json(response)),
@@ -217,8 +445,13 @@ async fn settle_handler(
         return Err(StatusCode::BAD_REQUEST);
     }
 
-    match facilitator
-        .settle_payment(&request.payment_payload, &request.payment_requirements)
+    let backend = facilitator
+        .registry
+        .get(&request.payment_payload.scheme, &request.payment_payload.network)
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    match backend
+        .settle(&request.payment_payload, &request.payment_requirements)
         .await
     {
         Ok(response) => Ok(Json(response)),
@@ -229,31 +462,14 @@ async fn settle_handler(
     }
 }
 
-/// Handle supported payment schemes requests
-async fn supported_handler(Query(_query): Query<SupportedQuery>) -> Json<SupportedKinds> {
+/// Handle supported payment schemes requests, aggregating across every registered
+/// settlement backend
+async fn supported_handler(
+    State(facilitator): State<SimpleFacilitator>,
+    Query(_query): Query<SupportedQuery>,
+) -> Json<SupportedKinds> {
     Json(SupportedKinds {
-        kinds: vec![
-            SupportedKind {
-                x402_version: X402_VERSION,
-                scheme: schemes::EXACT.to_string(),
-                network: networks::BASE_SEPOLIA.to_string(),
-            },
-            SupportedKind {
-                x402_version: X402_VERSION,
-                scheme: schemes::EXACT.to_string(),
-                network: networks::BASE_MAINNET.to_string(),
-            },
-            SupportedKind {
-                x402_version: X402_VERSION,
-                scheme: schemes::EXACT.to_string(),
-                network: networks::AVALANCHE_FUJI.to_string(),
-            },
-            SupportedKind {
-                x402_version: X402_VERSION,
-                scheme: schemes::EXACT.to_string(),
-                network: networks::AVALANCHE_MAINNET.to_string(),
-            },
-        ],
+        kinds: facilitator.registry.supported_kinds(),
     })
 }
 
@@ -264,13 +480,79 @@ mod tests {
     #[tokio::test]
     async fn test_facilitator_creation() {
         let facilitator = SimpleFacilitator::new();
-        assert!(facilitator.processed_nonces.read().await.is_empty());
+        assert!(facilitator.registry.get(schemes::EXACT, networks::BASE_SEPOLIA).is_some());
     }
 
     #[tokio::test]
-    async fn test_verify_payment() {
+    async fn test_registry_has_no_backend_for_unknown_network() {
+        let facilitator = SimpleFacilitator::new();
+        assert!(facilitator.registry.get(schemes::EXACT, "some-other-chain").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_registry_supported_kinds_aggregates_backend() {
         let facilitator = SimpleFacilitator::new();
+        let kinds = facilitator.registry.supported_kinds();
+        assert_eq!(kinds.len(), 4);
+        assert!(kinds
+            .iter()
+            .any(|kind| kind.network == networks::AVALANCHE_MAINNET));
+    }
 
+    #[tokio::test]
+    async fn test_register_webhook_adds_to_notifier() {
+        let facilitator = SimpleFacilitator::new();
+        facilitator
+            .register_webhook(WebhookConfig::new("https://example.com/hook", "shhh"))
+            .await;
+        assert_eq!(facilitator.webhooks.lock().await.webhook_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_verify_payment() {
+        use ethereum_types::{Address, U256};
+        use std::str::FromStr;
+        use x402::crypto::signature::LocalSigner;
+
+        let backend = EvmSettlementBackend::new(Arc::new(tokio::sync::Mutex::new(WebhookNotifier::new(Vec::new()))));
+
+        let signer = LocalSigner::random();
+        let from = format!("{:?}", signer.address().unwrap());
+        let to = Address::from_str("0x209693Bc6afc0C5328bA36FaF03C514EF312287C").unwrap();
+        let now = chrono::Utc::now().timestamp();
+
+        let exact_payload = signer
+            .sign_transfer_authorization(
+                to,
+                U256::from(10000u64),
+                U256::from(now as u64),
+                U256::from((now + 300) as u64),
+                networks::BASE_SEPOLIA,
+            )
+            .unwrap();
+
+        let payload = PaymentPayload::new(schemes::EXACT, networks::BASE_SEPOLIA, exact_payload);
+
+        let requirements = PaymentRequirements::new(
+            schemes::EXACT,
+            networks::BASE_SEPOLIA,
+            "10000",
+            "0x036CbD53842c5426634e7929541eC2318f3dCF7e",
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            "https://example.com/test",
+            "Test payment",
+        );
+
+        let response = backend.verify_payment(&payload, &requirements).await.unwrap();
+        assert!(response.is_valid);
+        assert_eq!(response.payer, Some(from));
+    }
+
+    #[tokio::test]
+    async fn test_verify_payment_rejects_mismatched_signature() {
+        let backend = EvmSettlementBackend::new(Arc::new(tokio::sync::Mutex::new(WebhookNotifier::new(Vec::new()))));
+
+        // `authorization.from` doesn't match the signer's recovered address
         let authorization = ExactEvmPayloadAuthorization::new(
             "0x857b06519E91e3A54538791bDbb0E22373e36b66",
             "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
@@ -299,14 +581,8 @@ mod tests {
             "Test payment",
         );
 
-        let response = facilitator
-            .verify_payment(&payload, &requirements)
-            .await
-            .unwrap();
-        assert!(response.is_valid);
-        assert_eq!(
-            response.payer,
-            Some("0x857b06519E91e3A54538791bDbb0E22373e36b66".to_string())
-        );
+        let response = backend.verify_payment(&payload, &requirements).await.unwrap();
+        assert!(!response.is_valid);
+        assert_eq!(response.invalid_reason, Some("invalid_signature".to_string()));
     }
 }
@@ -0,0 +1,30 @@
+//! Feeds arbitrary bytes through `PaymentPayload::from_base64`, the decode path a
+//! server runs on whatever a client puts in the `X-PAYMENT` header. The only
+//! invariant asserted is "never panic" plus a round-trip check: a payload that does
+//! decode must re-encode to bytes that decode back to an equal value.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use x402::types::PaymentPayload;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(encoded) = std::str::from_utf8(data) else {
+        return;
+    };
+
+    let Ok(payload) = PaymentPayload::from_base64(encoded) else {
+        return;
+    };
+
+    let re_encoded = payload.to_base64().expect("a decoded payload must re-encode");
+    let round_tripped =
+        PaymentPayload::from_base64(&re_encoded).expect("re-encoded payload must decode");
+
+    assert_eq!(payload.x402_version, round_tripped.x402_version);
+    assert_eq!(payload.scheme, round_tripped.scheme);
+    assert_eq!(payload.network, round_tripped.network);
+    assert_eq!(
+        payload.payload.authorization.nonce,
+        round_tripped.payload.authorization.nonce
+    );
+});
@@ -0,0 +1,28 @@
+//! Feeds arbitrary bytes, interpreted as JSON, straight into
+//! `serde_json::from_str::<PaymentPayload>`. This crate has no `VerifyRequest`/`V1`
+//! vs `V2` request envelope (payment payloads carry a plain `x402_version: u32`
+//! field, not an enum), so the wire type under fuzz is `PaymentPayload` itself — the
+//! same struct a facilitator or server decodes off the untrusted request path.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use x402::types::PaymentPayload;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(json) = std::str::from_utf8(data) else {
+        return;
+    };
+
+    let Ok(payload) = serde_json::from_str::<PaymentPayload>(json) else {
+        return;
+    };
+
+    let re_serialized =
+        serde_json::to_string(&payload).expect("a decoded payload must re-serialize");
+    let round_tripped = serde_json::from_str::<PaymentPayload>(&re_serialized)
+        .expect("re-serialized payload must deserialize");
+
+    assert_eq!(payload.x402_version, round_tripped.x402_version);
+    assert_eq!(payload.scheme, round_tripped.scheme);
+    assert_eq!(payload.network, round_tripped.network);
+});
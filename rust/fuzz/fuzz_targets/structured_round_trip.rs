@@ -0,0 +1,83 @@
+//! Structure-aware fuzzing for `ExactEvmPayloadAuthorization` and
+//! `PaymentRequirements`: instead of fuzzing raw JSON text (which mostly exercises
+//! the JSON tokenizer), `arbitrary` builds field values directly, so the fuzzer can
+//! reach edge cases a byte-soup JSON fuzzer rarely stumbles into unassisted —
+//! oversized amount strings, malformed hex signatures, empty nonces, missing
+//! optional fields. Neither x402 type derives `Arbitrary` itself (the crate has no
+//! dependency on the `arbitrary` crate), so this target mirrors their shape in
+//! fuzz-local structs and builds the real x402 types from them field by field.
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use x402::types::{ExactEvmPayloadAuthorization, PaymentRequirements};
+
+#[derive(Arbitrary, Debug)]
+struct ArbitraryAuthorization {
+    from: String,
+    to: String,
+    value: String,
+    valid_after: String,
+    valid_before: String,
+    nonce: String,
+}
+
+impl From<ArbitraryAuthorization> for ExactEvmPayloadAuthorization {
+    fn from(a: ArbitraryAuthorization) -> Self {
+        ExactEvmPayloadAuthorization::new(
+            a.from,
+            a.to,
+            a.value,
+            a.valid_after,
+            a.valid_before,
+            a.nonce,
+        )
+    }
+}
+
+#[derive(Arbitrary, Debug)]
+struct ArbitraryPaymentRequirements {
+    scheme: String,
+    network: String,
+    max_amount_required: String,
+    asset: String,
+    pay_to: String,
+    resource: String,
+    description: String,
+}
+
+impl From<ArbitraryPaymentRequirements> for PaymentRequirements {
+    fn from(r: ArbitraryPaymentRequirements) -> Self {
+        PaymentRequirements::new(
+            r.scheme,
+            r.network,
+            r.max_amount_required,
+            r.asset,
+            r.pay_to,
+            r.resource,
+            r.description,
+        )
+    }
+}
+
+#[derive(Arbitrary, Debug)]
+struct Input {
+    authorization: ArbitraryAuthorization,
+    requirements: ArbitraryPaymentRequirements,
+}
+
+fuzz_target!(|input: Input| {
+    let authorization: ExactEvmPayloadAuthorization = input.authorization.into();
+    // validate() must classify every input as Ok or a well-formed MalformedPayload
+    // error, never panic, regardless of how oversized or malformed the fields are.
+    let _ = authorization.validate();
+
+    let requirements: PaymentRequirements = input.requirements.into();
+    let serialized =
+        serde_json::to_string(&requirements).expect("requirements must always serialize");
+    let round_tripped: PaymentRequirements =
+        serde_json::from_str(&serialized).expect("a just-serialized value must deserialize");
+    assert_eq!(requirements.scheme, round_tripped.scheme);
+    assert_eq!(requirements.network, round_tripped.network);
+    assert_eq!(requirements.max_amount_required, round_tripped.max_amount_required);
+});
@@ -0,0 +1,495 @@
+//! Batched accounting for settled payments
+//!
+//! [`crate::middleware::PaymentMiddleware::settle_with_requirements_tracked`] settles
+//! a payment and returns the facilitator's [`crate::types::SettleResponse`], but
+//! nothing records what was actually paid — the proxy's
+//! `payment_middleware_handler` just forwards the response on and the settlement
+//! metadata is gone. [`PaymentLedger`] fills that gap: each successful settlement is
+//! queued as a [`PaymentRecord`] and flushed in batches (on a count threshold or a
+//! timer, whichever comes first) to a pluggable [`AccountingSink`], instead of
+//! writing one record at a time and risking flooding a downstream sink under load.
+
+use crate::types::SettleResponse;
+use crate::{Result, X402Error};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::{mpsc, oneshot};
+use tracing::warn;
+
+/// A boxed, `Send` future, used in place of `async fn` in [`AccountingSink`] since
+/// traits can't have object-safe async methods on stable Rust without an extra
+/// proc-macro crate this workspace doesn't otherwise depend on
+pub type BoxFuture<'a, T> = std::pin::Pin<Box<dyn std::future::Future<Output = T> + Send + 'a>>;
+
+/// A single settled payment, as recorded by a [`PaymentLedger`]
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct PaymentRecord {
+    /// Unix timestamp (seconds) the payment was recorded at
+    pub timestamp: u64,
+    /// The protected resource the payment was for (`PaymentRequirements::resource`)
+    pub route: String,
+    /// Amount paid, in the asset's smallest unit (`PaymentRequirements::max_amount_required`)
+    pub amount: String,
+    /// Recipient wallet address
+    pub pay_to: String,
+    /// Payer address, when reported by the facilitator
+    pub payer: Option<String>,
+    /// Base URL of the facilitator that settled the payment
+    pub facilitator: String,
+    /// Settlement transaction reference
+    pub transaction: String,
+    /// Network the transaction was executed on
+    pub network: String,
+}
+
+impl PaymentRecord {
+    /// Build a record from a successful settlement, tagging it with the `route` it
+    /// was for and which `facilitator` settled it
+    pub fn new(
+        route: impl Into<String>,
+        pay_to: impl Into<String>,
+        amount: impl Into<String>,
+        facilitator: impl Into<String>,
+        settlement: &SettleResponse,
+    ) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        Self {
+            timestamp,
+            route: route.into(),
+            amount: amount.into(),
+            pay_to: pay_to.into(),
+            payer: settlement.payer.clone(),
+            facilitator: facilitator.into(),
+            transaction: settlement.transaction.clone(),
+            network: settlement.network.clone(),
+        }
+    }
+}
+
+/// A destination a [`PaymentLedger`] flushes batches of [`PaymentRecord`]s to
+pub trait AccountingSink: Send + Sync {
+    /// Persist/deliver `records`. Called with every record accumulated since the
+    /// last flush; an `Err` is logged by the ledger and the batch is dropped rather
+    /// than retried, since a sink wanting retries can implement that internally (see
+    /// [`crate::settlement::WebhookDispatcher`] for the pattern this crate otherwise
+    /// uses for retrying webhook deliveries).
+    fn write_batch<'a>(&'a self, records: &'a [PaymentRecord]) -> BoxFuture<'a, Result<()>>;
+}
+
+/// Appends each flushed batch to a file as newline-delimited JSON records
+pub struct JsonLinesFileSink {
+    path: String,
+}
+
+impl JsonLinesFileSink {
+    pub fn new(path: impl Into<String>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl AccountingSink for JsonLinesFileSink {
+    fn write_batch<'a>(&'a self, records: &'a [PaymentRecord]) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            use tokio::io::AsyncWriteExt;
+
+            let mut contents = String::new();
+            for record in records {
+                let line = serde_json::to_string(record).map_err(|e| {
+                    X402Error::unexpected(format!("Failed to serialize payment record: {}", e))
+                })?;
+                contents.push_str(&line);
+                contents.push('\n');
+            }
+
+            let mut file = tokio::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.path)
+                .await
+                .map_err(|e| {
+                    X402Error::unexpected(format!(
+                        "Failed to open accounting ledger file {}: {}",
+                        self.path, e
+                    ))
+                })?;
+
+            file.write_all(contents.as_bytes()).await.map_err(|e| {
+                X402Error::unexpected(format!(
+                    "Failed to write accounting ledger file {}: {}",
+                    self.path, e
+                ))
+            })
+        })
+    }
+}
+
+/// POSTs each flushed batch as a JSON array to a webhook URL
+pub struct WebhookSink {
+    url: String,
+    client: reqwest::Client,
+}
+
+impl WebhookSink {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+impl AccountingSink for WebhookSink {
+    fn write_batch<'a>(&'a self, records: &'a [PaymentRecord]) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let response = self
+                .client
+                .post(&self.url)
+                .json(records)
+                .send()
+                .await
+                .map_err(|e| {
+                    X402Error::facilitator_error(format!(
+                        "Failed to POST payment batch to {}: {}",
+                        self.url, e
+                    ))
+                })?;
+
+            if !response.status().is_success() {
+                return Err(X402Error::facilitator_error(format!(
+                    "Accounting webhook {} returned status {}",
+                    self.url,
+                    response.status()
+                )));
+            }
+
+            Ok(())
+        })
+    }
+}
+
+/// Forwards each flushed batch to an in-process [`mpsc::UnboundedSender`], for a
+/// caller that wants to consume payment records directly (tests, or a custom
+/// pipeline) instead of via a file or webhook
+pub struct ChannelSink {
+    sender: mpsc::UnboundedSender<Vec<PaymentRecord>>,
+}
+
+impl ChannelSink {
+    pub fn new(sender: mpsc::UnboundedSender<Vec<PaymentRecord>>) -> Self {
+        Self { sender }
+    }
+}
+
+impl AccountingSink for ChannelSink {
+    fn write_batch<'a>(&'a self, records: &'a [PaymentRecord]) -> BoxFuture<'a, Result<()>> {
+        let result = self.sender.send(records.to_vec()).map_err(|_| {
+            X402Error::unexpected("Accounting channel receiver has been dropped")
+        });
+        Box::pin(async move { result })
+    }
+}
+
+/// Where a [`PaymentLedger`] built from an [`AccountingConfig`] flushes its
+/// batches to
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AccountingSinkConfig {
+    /// Append newline-delimited JSON records to the file at `path`
+    JsonLinesFile { path: String },
+    /// POST each batch as a JSON array to `url`
+    Webhook { url: String },
+}
+
+impl AccountingSinkConfig {
+    /// Build the concrete [`AccountingSink`] this configuration describes
+    pub fn build(&self) -> Arc<dyn AccountingSink> {
+        match self {
+            Self::JsonLinesFile { path } => Arc::new(JsonLinesFileSink::new(path.clone())),
+            Self::Webhook { url } => Arc::new(WebhookSink::new(url.clone())),
+        }
+    }
+}
+
+/// Configuration for a [`PaymentLedger`]: where it flushes to, and how often
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct AccountingConfig {
+    /// Destination for flushed batches
+    pub sink: AccountingSinkConfig,
+    /// Flush the buffered batch at least this often, even if `batch_size` hasn't
+    /// been reached yet
+    #[serde(default = "AccountingConfig::default_flush_interval_seconds")]
+    pub flush_interval_seconds: u64,
+    /// Flush as soon as the buffered batch reaches this many records, without
+    /// waiting for `flush_interval_seconds`
+    #[serde(default = "AccountingConfig::default_batch_size")]
+    pub batch_size: usize,
+}
+
+impl AccountingConfig {
+    fn default_flush_interval_seconds() -> u64 {
+        10
+    }
+
+    fn default_batch_size() -> usize {
+        100
+    }
+
+    /// Build a [`PaymentLedger`] that flushes to this configuration's `sink`
+    pub fn build_ledger(&self) -> PaymentLedger {
+        PaymentLedger::new(
+            self.sink.build(),
+            AccountingFlushConfig {
+                flush_interval_seconds: self.flush_interval_seconds,
+                batch_size: self.batch_size,
+            },
+        )
+    }
+}
+
+/// Flush-trigger portion of an [`AccountingConfig`], passed to [`PaymentLedger::new`]
+/// once the sink itself has already been resolved
+#[derive(Debug, Clone, Copy)]
+pub struct AccountingFlushConfig {
+    pub flush_interval_seconds: u64,
+    pub batch_size: usize,
+}
+
+/// A record awaiting the next flush, or a request to flush immediately
+enum LedgerJob {
+    Record(PaymentRecord),
+    Flush(oneshot::Sender<()>),
+}
+
+/// Background accounting ledger that batches successful payments before handing
+/// them to an [`AccountingSink`]
+///
+/// Cloning a [`PaymentLedger`] is cheap and shares the same background worker and
+/// channel, so it can be stored directly on
+/// [`crate::middleware::PaymentMiddleware`].
+#[derive(Clone)]
+pub struct PaymentLedger {
+    sender: mpsc::UnboundedSender<LedgerJob>,
+}
+
+impl PaymentLedger {
+    /// Spawn a background worker that buffers [`PaymentRecord`]s recorded via
+    /// [`Self::record`] and flushes them to `sink` once `config.batch_size` records
+    /// have accumulated or `config.flush_interval_seconds` has elapsed, whichever
+    /// comes first
+    pub fn new(sink: Arc<dyn AccountingSink>, config: AccountingFlushConfig) -> Self {
+        let (sender, mut receiver) = mpsc::unbounded_channel::<LedgerJob>();
+        let batch_size = config.batch_size.max(1);
+        let flush_interval = Duration::from_secs(config.flush_interval_seconds.max(1));
+
+        tokio::spawn(async move {
+            let mut buffer: Vec<PaymentRecord> = Vec::with_capacity(batch_size);
+            let mut ticker = tokio::time::interval(flush_interval);
+            // The first tick fires immediately; consume it so we don't flush an
+            // empty buffer the instant the worker starts
+            ticker.tick().await;
+
+            loop {
+                tokio::select! {
+                    job = receiver.recv() => {
+                        match job {
+                            Some(LedgerJob::Record(record)) => {
+                                buffer.push(record);
+                                if buffer.len() >= batch_size {
+                                    flush_buffer(&sink, &mut buffer).await;
+                                }
+                            }
+                            Some(LedgerJob::Flush(ack)) => {
+                                flush_buffer(&sink, &mut buffer).await;
+                                let _ = ack.send(());
+                            }
+                            None => {
+                                // Every `PaymentLedger` handle has been dropped: flush
+                                // whatever remains so no settled payment is lost, then exit
+                                flush_buffer(&sink, &mut buffer).await;
+                                break;
+                            }
+                        }
+                    }
+                    _ = ticker.tick() => {
+                        flush_buffer(&sink, &mut buffer).await;
+                    }
+                }
+            }
+        });
+
+        Self { sender }
+    }
+
+    /// Queue a successful payment for the next batch flush, returning immediately
+    pub fn record(&self, record: PaymentRecord) {
+        if self.sender.send(LedgerJob::Record(record)).is_err() {
+            warn!("Payment ledger worker has shut down; dropping payment record");
+        }
+    }
+
+    /// Flush any buffered records and wait for the flush to complete
+    ///
+    /// Call this during graceful shutdown so a batch still sitting under
+    /// `batch_size`/`flush_interval_seconds` isn't lost along with the process.
+    pub async fn flush(&self) {
+        let (ack_tx, ack_rx) = oneshot::channel();
+        if self.sender.send(LedgerJob::Flush(ack_tx)).is_err() {
+            return;
+        }
+        let _ = ack_rx.await;
+    }
+}
+
+impl std::fmt::Debug for PaymentLedger {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PaymentLedger").finish()
+    }
+}
+
+async fn flush_buffer(sink: &Arc<dyn AccountingSink>, buffer: &mut Vec<PaymentRecord>) {
+    if buffer.is_empty() {
+        return;
+    }
+
+    if let Err(error) = sink.write_batch(buffer).await {
+        warn!(
+            "Payment ledger failed to flush {} record(s): {}",
+            buffer.len(),
+            error
+        );
+    }
+
+    buffer.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::SettleResponse;
+    use std::time::Duration as StdDuration;
+
+    fn test_settlement() -> SettleResponse {
+        SettleResponse {
+            success: true,
+            error_reason: None,
+            transaction: "0xabc".to_string(),
+            network: "base-sepolia".to_string(),
+            payer: Some("0x857b06519E91e3A54538791bDbb0E22373e36b66".to_string()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ledger_flushes_on_batch_size() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let sink = Arc::new(ChannelSink::new(tx));
+        let config = AccountingFlushConfig {
+            flush_interval_seconds: 3600,
+            batch_size: 2,
+        };
+        let ledger = PaymentLedger::new(sink, config);
+
+        let settlement = test_settlement();
+        ledger.record(PaymentRecord::new(
+            "https://example.com/a",
+            "0xpayto",
+            "1000000",
+            "https://facilitator.example.com",
+            &settlement,
+        ));
+        ledger.record(PaymentRecord::new(
+            "https://example.com/b",
+            "0xpayto",
+            "1000000",
+            "https://facilitator.example.com",
+            &settlement,
+        ));
+
+        let batch = tokio::time::timeout(StdDuration::from_secs(1), rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(batch.len(), 2);
+        assert_eq!(batch[0].route, "https://example.com/a");
+        assert_eq!(batch[1].route, "https://example.com/b");
+    }
+
+    #[tokio::test]
+    async fn test_ledger_flushes_on_interval_below_batch_size() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let sink = Arc::new(ChannelSink::new(tx));
+        let config = AccountingFlushConfig {
+            flush_interval_seconds: 1,
+            batch_size: 100,
+        };
+        let ledger = PaymentLedger::new(sink, config);
+
+        ledger.record(PaymentRecord::new(
+            "https://example.com/a",
+            "0xpayto",
+            "1000000",
+            "https://facilitator.example.com",
+            &test_settlement(),
+        ));
+
+        let batch = tokio::time::timeout(StdDuration::from_secs(3), rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(batch.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_ledger_flush_drains_buffer_immediately() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let sink = Arc::new(ChannelSink::new(tx));
+        let config = AccountingFlushConfig {
+            flush_interval_seconds: 3600,
+            batch_size: 100,
+        };
+        let ledger = PaymentLedger::new(sink, config);
+
+        ledger.record(PaymentRecord::new(
+            "https://example.com/a",
+            "0xpayto",
+            "1000000",
+            "https://facilitator.example.com",
+            &test_settlement(),
+        ));
+
+        ledger.flush().await;
+
+        let batch = tokio::time::timeout(StdDuration::from_secs(1), rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(batch.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_json_lines_file_sink_appends_records() {
+        let mut path = std::env::temp_dir();
+        path.push(format!("x402_test_ledger_{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let sink = JsonLinesFileSink::new(path.to_string_lossy().to_string());
+        let record = PaymentRecord::new(
+            "https://example.com/a",
+            "0xpayto",
+            "1000000",
+            "https://facilitator.example.com",
+            &test_settlement(),
+        );
+
+        sink.write_batch(&[record]).await.unwrap();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(contents.contains("\"transaction\":\"0xabc\""));
+        assert_eq!(contents.lines().count(), 1);
+    }
+}
@@ -11,16 +11,91 @@ use actix_web::{
     middleware::Next,
     Error, HttpRequest, HttpResponse,
 };
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// A `path -> requirements` registry resolved per request, replacing hardcoded path
+/// matching in [`extract_payment_requirements`]/[`create_payment_requirements_from_request`]
+///
+/// Entries are tried in order: an exact [`Self::route`] match first, then
+/// [`Self::route_pattern`] globs in registration order (`*` matches any run of
+/// characters, reusing [`crate::proxy::glob_match`]), then [`Self::with_fallback`] if
+/// nothing else matched. A path matched by nothing resolves to `None`, meaning the
+/// resource is free, rather than falling through to any hardcoded default.
+#[derive(Clone, Default)]
+pub struct RouteRequirements {
+    exact: HashMap<String, Vec<PaymentRequirements>>,
+    patterns: Vec<(String, Vec<PaymentRequirements>)>,
+    fallback: Option<Arc<dyn Fn(&str) -> Option<Vec<PaymentRequirements>> + Send + Sync>>,
+}
+
+impl std::fmt::Debug for RouteRequirements {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RouteRequirements")
+            .field("exact", &self.exact)
+            .field("patterns", &self.patterns)
+            .field("fallback", &self.fallback.is_some())
+            .finish()
+    }
+}
+
+impl RouteRequirements {
+    /// Create an empty registry; every path resolves to `None` (free) until routes
+    /// are added
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register `requirements` for an exact path match
+    pub fn route(mut self, path: impl Into<String>, requirements: Vec<PaymentRequirements>) -> Self {
+        self.exact.insert(path.into(), requirements);
+        self
+    }
+
+    /// Register `requirements` for a glob `pattern` (e.g. `/reports/*`), tried after
+    /// every exact match and in registration order
+    pub fn route_pattern(mut self, pattern: impl Into<String>, requirements: Vec<PaymentRequirements>) -> Self {
+        self.patterns.push((pattern.into(), requirements));
+        self
+    }
+
+    /// Register a fallback consulted when no exact or pattern route matches, e.g. for
+    /// a database or config lookup keyed on the request path
+    pub fn with_fallback(
+        mut self,
+        fallback: impl Fn(&str) -> Option<Vec<PaymentRequirements>> + Send + Sync + 'static,
+    ) -> Self {
+        self.fallback = Some(Arc::new(fallback));
+        self
+    }
+
+    /// Resolve `path` to its requirements, or `None` if the resource is free
+    pub fn resolve(&self, path: &str) -> Option<Vec<PaymentRequirements>> {
+        if let Some(requirements) = self.exact.get(path) {
+            return Some(requirements.clone());
+        }
+        for (pattern, requirements) in &self.patterns {
+            if crate::proxy::glob_match(pattern, path) {
+                return Some(requirements.clone());
+            }
+        }
+        self.fallback.as_ref().and_then(|fallback| fallback(path))
+    }
+}
 
 /// Actix-web middleware for x402 payment verification
 pub struct X402Middleware {
     payment_middleware: PaymentMiddleware,
+    route_requirements: RouteRequirements,
 }
 
 impl X402Middleware {
     /// Create a new x402 middleware instance
     pub fn new(payment_middleware: PaymentMiddleware) -> Self {
-        Self { payment_middleware }
+        Self {
+            payment_middleware,
+            route_requirements: RouteRequirements::new(),
+        }
     }
 
     /// Get the payment middleware configuration
@@ -32,6 +107,38 @@ impl X402Middleware {
     pub fn config_mut(&mut self) -> &mut PaymentMiddleware {
         &mut self.payment_middleware
     }
+
+    /// Get the route requirements registry
+    pub fn routes(&self) -> &RouteRequirements {
+        &self.route_requirements
+    }
+
+    /// Replace the route requirements registry
+    pub fn with_route_requirements(mut self, route_requirements: RouteRequirements) -> Self {
+        self.route_requirements = route_requirements;
+        self
+    }
+
+    /// Register `requirements` for an exact path match; see [`RouteRequirements::route`]
+    pub fn route(mut self, path: impl Into<String>, requirements: Vec<PaymentRequirements>) -> Self {
+        self.route_requirements = self.route_requirements.route(path, requirements);
+        self
+    }
+
+    /// Register `requirements` for a glob pattern; see [`RouteRequirements::route_pattern`]
+    pub fn route_pattern(mut self, pattern: impl Into<String>, requirements: Vec<PaymentRequirements>) -> Self {
+        self.route_requirements = self.route_requirements.route_pattern(pattern, requirements);
+        self
+    }
+
+    /// Register a fallback lookup; see [`RouteRequirements::with_fallback`]
+    pub fn with_fallback(
+        mut self,
+        fallback: impl Fn(&str) -> Option<Vec<PaymentRequirements>> + Send + Sync + 'static,
+    ) -> Self {
+        self.route_requirements = self.route_requirements.with_fallback(fallback);
+        self
+    }
 }
 
 /// Create x402 middleware for Actix-web
@@ -39,45 +146,15 @@ pub fn create_x402_middleware(payment_middleware: PaymentMiddleware) -> X402Midd
     X402Middleware::new(payment_middleware)
 }
 
-/// Extract payment requirements from request
+/// Resolve payment requirements for a request against a [`RouteRequirements`] registry
 ///
-/// This function can be extended to extract payment requirements from:
-/// - Route metadata/attributes
-/// - Configuration files
-/// - Database lookups
-/// - Environment variables
-fn extract_payment_requirements(req: &ServiceRequest) -> Result<Option<Vec<PaymentRequirements>>> {
-    let path = req.uri().path();
-
-    // Example: Extract payment requirements based on route patterns
-    // In a real application, this could come from route metadata, configuration, or database
-    match path {
-        "/premium" | "/api/v1/premium" => {
-            // Premium endpoints require payment
-            let requirements = PaymentRequirements::new(
-                "exact",
-                "base-sepolia",
-                "1000000", // 1 USDC
-                "0x036CbD53842c5426634e7929541eC2318f3dCF7e",
-                "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
-                path,
-                "Premium API access",
-            );
-
-            let mut req = requirements;
-            req.set_usdc_info(crate::types::Network::Testnet)?;
-            Ok(Some(vec![req]))
-        }
-        "/health" | "/metrics" | "/status" => {
-            // Health and monitoring endpoints are free
-            Ok(None)
-        }
-        _ => {
-            // Default: no payment required for other endpoints
-            // This can be overridden by route-specific configuration
-            Ok(None)
-        }
-    }
+/// Returns `None` when no exact path, glob pattern, or fallback in `routes` matches the
+/// request path, meaning the resource is free.
+fn extract_payment_requirements(
+    req: &ServiceRequest,
+    routes: &RouteRequirements,
+) -> Result<Option<Vec<PaymentRequirements>>> {
+    Ok(routes.resolve(req.uri().path()))
 }
 
 /// Create payment required response
@@ -139,6 +216,40 @@ async fn verify_payment_header(
     Ok(())
 }
 
+/// Like [`verify_payment_header`], additionally checking the decoded payload's
+/// authorization validity window and reserving its `(payer, nonce)` pair against
+/// `replay_store`, rejecting a second presentation with
+/// [`crate::X402Error::NonceReused`]
+async fn verify_payment_header_with_replay_guard(
+    payment_header: &HeaderValue,
+    requirements: &[PaymentRequirements],
+    replay_store: &dyn crate::nonce_store::NonceReplayStore,
+) -> Result<()> {
+    verify_payment_header(payment_header, requirements).await?;
+
+    let payment_str = payment_header.to_str().map_err(|_| {
+        crate::X402Error::invalid_payment_payload("Invalid payment header encoding")
+    })?;
+    let payload = PaymentPayload::from_base64(payment_str).map_err(|e| {
+        crate::X402Error::invalid_payment_payload(format!("Failed to decode payment: {}", e))
+    })?;
+
+    let authorization = &payload.exact_evm()?.authorization;
+    authorization.check_validity_window()?;
+
+    let valid_before: i64 = authorization.valid_before.parse().map_err(|_| {
+        crate::X402Error::invalid_payment_payload("validBefore is not a valid timestamp")
+    })?;
+
+    crate::nonce_store::reject_nonce_reuse(
+        replay_store,
+        &authorization.from,
+        &authorization.nonce,
+        valid_before,
+    )
+    .await
+}
+
 /// Handle payment verification in Actix-web handlers
 pub async fn handle_payment_verification(
     req: &HttpRequest,
@@ -158,10 +269,42 @@ pub async fn handle_payment_verification(
     }
 }
 
+/// Like [`handle_payment_verification`], additionally rejecting a replayed
+/// `X-PAYMENT` header: the authorization's `nonce` is reserved against its payer in
+/// `replay_store` for the rest of its `validBefore` window (see
+/// [`crate::nonce_store::NonceReplayStore`]), so presenting the same header a second
+/// time within that window fails with [`crate::X402Error::NonceReused`] instead of
+/// being verified again as if it were fresh. `handle_payment_verification` itself has
+/// no replay protection, since it has no store to check against; reach for this
+/// variant whenever one is available.
+pub async fn handle_payment_verification_with_replay_guard(
+    req: &HttpRequest,
+    requirements: &[PaymentRequirements],
+    replay_store: &dyn crate::nonce_store::NonceReplayStore,
+) -> std::result::Result<Option<HttpResponse>, Box<dyn std::error::Error>> {
+    if let Some(payment_header) = req.headers().get("X-PAYMENT") {
+        match verify_payment_header_with_replay_guard(payment_header, requirements, replay_store).await {
+            Ok(_) => Ok(None), // Payment verified, continue
+            Err(e) => {
+                let response = create_payment_error_response(&e, requirements);
+                Ok(Some(response))
+            }
+        }
+    } else {
+        let response = create_payment_required_response(requirements);
+        Ok(Some(response))
+    }
+}
+
 /// Simple middleware function for Actix-web
+///
+/// `routes` resolves the path-specific requirements (see [`RouteRequirements`]); wrap
+/// this in a closure capturing your registry when registering it with
+/// `actix_web::middleware::from_fn`, e.g. `move |req, next| x402_middleware(req, next, &routes)`.
 pub async fn x402_middleware(
     req: ServiceRequest,
     next: Next<actix_web::body::BoxBody>,
+    routes: &RouteRequirements,
 ) -> std::result::Result<ServiceResponse<actix_web::body::BoxBody>, Error> {
     // Extract payment header from request
     let payment_header = req.headers().get("X-PAYMENT").and_then(|h| h.to_str().ok());
@@ -172,7 +315,7 @@ pub async fn x402_middleware(
             match crate::types::PaymentPayload::from_base64(payment_b64) {
                 Ok(payment_payload) => {
                     // Create payment requirements
-                    let requirements = match create_payment_requirements_from_request(&req) {
+                    let requirements = match create_payment_requirements_from_request(&req, routes) {
                         Ok(req) => req,
                         Err(e) => {
                             return Ok(ServiceResponse::new(
@@ -250,7 +393,7 @@ pub async fn x402_middleware(
         }
         None => {
             // No payment header provided
-            let requirements = match create_payment_requirements_from_request(&req) {
+            let requirements = match create_payment_requirements_from_request(&req, routes) {
                 Ok(req) => vec![req],
                 Err(_) => vec![],
             };
@@ -262,15 +405,20 @@ pub async fn x402_middleware(
 
 /// Create payment requirements from request
 ///
-/// This function creates payment requirements based on the request path and headers.
-/// In a production application, this could be extended to:
-/// - Read from configuration files
-/// - Query a database for route-specific payment requirements
-/// - Extract from route metadata or annotations
-/// - Use environment-specific settings
+/// Resolves `req`'s path against `routes` first (see [`RouteRequirements`]), taking the
+/// first entry of a matched route's requirements; falls back to header-derived defaults
+/// (`X-Payment-Scheme`/`X-Payment-Network`/`X-Payment-Amount`/`X-Payment-To`) only when
+/// no route matches, so an unconfigured deployment keeps working without a registry.
 fn create_payment_requirements_from_request(
     req: &ServiceRequest,
+    routes: &RouteRequirements,
 ) -> crate::Result<crate::types::PaymentRequirements> {
+    if let Some(mut matched) = routes.resolve(req.uri().path()) {
+        if !matched.is_empty() {
+            return Ok(matched.remove(0));
+        }
+    }
+
     let uri = req.uri();
     let path = uri.path();
 
@@ -385,6 +533,7 @@ mod tests {
             max_timeout_seconds: 300,
             output_schema: None,
             extra: None,
+            payment_uri: None,
         }];
 
         let response = create_payment_required_response(&requirements);
@@ -393,4 +542,91 @@ mod tests {
             actix_web::http::StatusCode::PAYMENT_REQUIRED
         );
     }
+
+    fn sample_requirements(resource: &str) -> PaymentRequirements {
+        PaymentRequirements::new(
+            "exact",
+            "base-sepolia",
+            "1000000",
+            "0x036CbD53842c5426634e7929541eC2318f3dCF7e",
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            resource,
+            "Test resource",
+        )
+    }
+
+    #[test]
+    fn test_route_requirements_matches_exact_path_before_pattern() {
+        let routes = RouteRequirements::new()
+            .route("/api/v1/premium", vec![sample_requirements("/api/v1/premium")])
+            .route_pattern("/api/v1/*", vec![sample_requirements("/api/v1/*")]);
+
+        let resolved = routes.resolve("/api/v1/premium").unwrap();
+        assert_eq!(resolved[0].resource, "/api/v1/premium");
+    }
+
+    #[test]
+    fn test_route_requirements_matches_glob_pattern() {
+        let routes =
+            RouteRequirements::new().route_pattern("/reports/*", vec![sample_requirements("/reports/*")]);
+
+        assert!(routes.resolve("/reports/q1").is_some());
+        assert!(routes.resolve("/other").is_none());
+    }
+
+    #[test]
+    fn test_route_requirements_falls_back_to_closure_when_nothing_else_matches() {
+        let routes = RouteRequirements::new().with_fallback(|path| {
+            if path.starts_with("/dynamic/") {
+                Some(vec![sample_requirements(path)])
+            } else {
+                None
+            }
+        });
+
+        assert!(routes.resolve("/dynamic/abc").is_some());
+        assert!(routes.resolve("/static").is_none());
+    }
+
+    #[test]
+    fn test_route_requirements_unmatched_path_is_free() {
+        let routes = RouteRequirements::new().route("/premium", vec![sample_requirements("/premium")]);
+        assert!(routes.resolve("/health").is_none());
+    }
+
+    fn replayable_payment_header() -> HeaderValue {
+        let now = chrono::Utc::now().timestamp();
+        let authorization = crate::types::ExactEvmPayloadAuthorization::new(
+            "0x857b06519E91e3A54538791bDbb0E22373e36b66",
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            "1000000",
+            (now - 60).to_string(),
+            (now + 600).to_string(),
+            "0xf3746613c2d920b5fdabc0856f2aeb2d4f88ee6037b8cc5d04a71a4462f13480",
+        );
+        let payment_payload = PaymentPayload::new(
+            "exact",
+            "base-sepolia",
+            crate::types::ExactEvmPayload {
+                signature: "0xsignature".to_string(),
+                authorization,
+            },
+        );
+        HeaderValue::from_str(&payment_payload.to_base64().unwrap()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_verify_payment_header_with_replay_guard_rejects_a_second_presentation() {
+        let header = replayable_payment_header();
+        let store = crate::nonce_store::InMemoryNonceReplayStore::new();
+
+        verify_payment_header_with_replay_guard(&header, &[], &store)
+            .await
+            .unwrap();
+
+        let err = verify_payment_header_with_replay_guard(&header, &[], &store)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, crate::X402Error::NonceReused { .. }));
+    }
 }
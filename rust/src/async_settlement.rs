@@ -0,0 +1,197 @@
+//! Asynchronous ("redirect") settlement for schemes that cannot confirm within the
+//! lifetime of a single HTTP request
+//!
+//! [`crate::settlement::SettlementQueue`] already lets settlement happen after the
+//! response is sent, but it still assumes the *facilitator* finishes the `settle` call
+//! promptly — it just retries a transient failure on our own schedule. Some schemes
+//! (anything requiring off-chain confirmation, human approval, or a multi-block
+//! finality window) cannot report a final result within that call at all. For those,
+//! [`crate::middleware::PaymentMiddleware::settle_with_requirements_async`] treats a
+//! facilitator response of `success: false` with an `error_reason` of the form
+//! `"pending:<settlement_id>"` as a signal to track the payment here and return
+//! [`SettlementOutcome::Pending`] instead of failing it, and a mounted notification
+//! route (see the `axum`/`warp` modules) resolves the tracked entry once the
+//! facilitator POSTs the final [`SettleResponse`] out of band.
+
+use crate::idempotency::BoxFuture;
+use crate::types::SettleResponse;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Outcome of an async-aware settlement attempt
+#[derive(Debug, Clone)]
+pub enum SettlementOutcome {
+    /// Settlement completed within the call, exactly as a synchronous settle always has
+    Final(SettleResponse),
+    /// The facilitator will confirm out of band; correlate its notification by this id
+    Pending { settlement_id: String },
+}
+
+/// State of a settlement tracked by a [`PendingSettlementStore`]
+#[derive(Debug, Clone)]
+pub enum PendingSettlementState {
+    /// Still waiting on the facilitator's notification
+    Pending,
+    /// The facilitator's notification resolved this id
+    Settled(SettleResponse),
+}
+
+/// Tracks settlements started in [`SettlementOutcome::Pending`] mode until a
+/// notification (see [`crate::axum::settlement_notification_route`]) resolves them
+///
+/// Modeled on [`crate::idempotency::IdempotencyStore`]: an in-memory default is
+/// provided, with the trait there so a deployment with more than one process behind a
+/// load balancer can back it with a shared store instead — the facilitator's
+/// notification may well land on a different instance than the one that started the
+/// settlement.
+pub trait PendingSettlementStore: Send + Sync {
+    /// Record `settlement_id` as pending
+    fn begin(&self, settlement_id: String) -> BoxFuture<'_, ()>;
+
+    /// Resolve a pending `settlement_id` with its final result, returning `false` if no
+    /// such id was being tracked (e.g. it already resolved, or was never begun)
+    fn resolve<'a>(
+        &'a self,
+        settlement_id: &'a str,
+        result: SettleResponse,
+    ) -> BoxFuture<'a, bool>;
+
+    /// Look up the current state of a tracked settlement
+    fn get<'a>(
+        &'a self,
+        settlement_id: &'a str,
+    ) -> BoxFuture<'a, Option<PendingSettlementState>>;
+}
+
+/// Single-process [`PendingSettlementStore`] backed by a [`tokio::sync::Mutex`]
+#[derive(Clone, Default)]
+pub struct InMemoryPendingSettlementStore {
+    entries: Arc<Mutex<HashMap<String, PendingSettlementState>>>,
+}
+
+impl InMemoryPendingSettlementStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl PendingSettlementStore for InMemoryPendingSettlementStore {
+    fn begin(&self, settlement_id: String) -> BoxFuture<'_, ()> {
+        Box::pin(async move {
+            self.entries
+                .lock()
+                .await
+                .insert(settlement_id, PendingSettlementState::Pending);
+        })
+    }
+
+    fn resolve<'a>(
+        &'a self,
+        settlement_id: &'a str,
+        result: SettleResponse,
+    ) -> BoxFuture<'a, bool> {
+        Box::pin(async move {
+            let mut entries = self.entries.lock().await;
+            match entries.get_mut(settlement_id) {
+                Some(state) => {
+                    *state = PendingSettlementState::Settled(result);
+                    true
+                }
+                None => false,
+            }
+        })
+    }
+
+    fn get<'a>(
+        &'a self,
+        settlement_id: &'a str,
+    ) -> BoxFuture<'a, Option<PendingSettlementState>> {
+        Box::pin(async move { self.entries.lock().await.get(settlement_id).cloned() })
+    }
+}
+
+/// Prefix a facilitator's `SettleResponse::error_reason` uses to signal that
+/// settlement is still pending rather than failed, carrying the id to correlate the
+/// eventual notification against
+pub const PENDING_REASON_PREFIX: &str = "pending:";
+
+/// Extract the settlement id from a `SettleResponse` reporting
+/// `error_reason = "pending:<id>"`, if it is in that form
+pub fn pending_settlement_id(response: &SettleResponse) -> Option<&str> {
+    if response.success {
+        return None;
+    }
+    response
+        .error_reason
+        .as_deref()
+        .and_then(|reason| reason.strip_prefix(PENDING_REASON_PREFIX))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settled_response() -> SettleResponse {
+        SettleResponse {
+            success: true,
+            error_reason: None,
+            transaction: "0xabc".to_string(),
+            network: "base-sepolia".to_string(),
+            payer: None,
+        }
+    }
+
+    #[test]
+    fn test_pending_settlement_id_extracts_id() {
+        let response = SettleResponse {
+            success: false,
+            error_reason: Some("pending:settlement-123".to_string()),
+            transaction: String::new(),
+            network: "base-sepolia".to_string(),
+            payer: None,
+        };
+        assert_eq!(pending_settlement_id(&response), Some("settlement-123"));
+    }
+
+    #[test]
+    fn test_pending_settlement_id_ignores_successful_response() {
+        assert_eq!(pending_settlement_id(&settled_response()), None);
+    }
+
+    #[test]
+    fn test_pending_settlement_id_ignores_unrelated_failure() {
+        let response = SettleResponse {
+            success: false,
+            error_reason: Some("insufficient_funds".to_string()),
+            transaction: String::new(),
+            network: "base-sepolia".to_string(),
+            payer: None,
+        };
+        assert_eq!(pending_settlement_id(&response), None);
+    }
+
+    #[tokio::test]
+    async fn test_store_resolve_updates_pending_entry() {
+        let store = InMemoryPendingSettlementStore::new();
+        store.begin("settlement-123".to_string()).await;
+
+        assert!(matches!(
+            store.get("settlement-123").await,
+            Some(PendingSettlementState::Pending)
+        ));
+
+        let resolved = store.resolve("settlement-123", settled_response()).await;
+        assert!(resolved);
+        assert!(matches!(
+            store.get("settlement-123").await,
+            Some(PendingSettlementState::Settled(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_store_resolve_unknown_id_returns_false() {
+        let store = InMemoryPendingSettlementStore::new();
+        assert!(!store.resolve("no-such-id", settled_response()).await);
+    }
+}
@@ -3,14 +3,17 @@
 use crate::middleware::{PaymentMiddleware, PaymentMiddlewareConfig};
 use crate::X402Error;
 use axum::{
-    extract::{Request, State},
+    extract::{Path, Request, State},
     http::{HeaderMap, HeaderValue, StatusCode},
     middleware::Next,
+    response::sse::{Event, KeepAlive, Sse},
     response::{IntoResponse, Response},
     routing::{delete, get, post, put},
     Json, Router,
 };
+use futures_util::stream::{Stream, StreamExt};
 use rust_decimal::Decimal;
+use std::convert::Infallible;
 use std::sync::Arc;
 use tower::ServiceBuilder;
 
@@ -62,6 +65,194 @@ pub fn create_payment_middleware(amount: Decimal, pay_to: impl Into<String>) ->
     PaymentMiddleware::new(amount, pay_to)
 }
 
+/// Request body for [`payout_route`]'s generated endpoint
+#[derive(serde::Deserialize)]
+pub struct PayoutRequest {
+    pub network: String,
+    pub destination: String,
+    pub amount: String,
+    pub asset: String,
+}
+
+/// Mount a `POST {path}` endpoint that dispatches to
+/// [`PaymentMiddleware::payout`], analogous to how [`payment_route`] mounts a
+/// payment-gated handler. Unlike `payment_route`, the mounted endpoint itself *is*
+/// the operation — it isn't gating access to a separate handler.
+pub fn payout_route(path: &str, middleware: PaymentMiddleware) -> Router {
+    Router::new()
+        .route(path, post(payout_handler))
+        .with_state(middleware)
+}
+
+async fn payout_handler(
+    State(middleware): State<PaymentMiddleware>,
+    Json(request): Json<PayoutRequest>,
+) -> impl IntoResponse {
+    match middleware
+        .payout(&request.network, &request.destination, &request.amount, &request.asset)
+        .await
+    {
+        Ok(reversal) => {
+            let mut response = Json(serde_json::json!({
+                "success": reversal.success,
+                "transaction": reversal.transaction,
+                "network": reversal.network,
+            }))
+            .into_response();
+
+            if let Ok(header_value) = reversal
+                .to_base64()
+                .ok()
+                .and_then(|encoded| HeaderValue::from_str(&encoded).ok())
+            {
+                response
+                    .headers_mut()
+                    .insert("X-PAYMENT-RESPONSE", header_value);
+            }
+
+            response
+        }
+        Err(e) => (
+            StatusCode::BAD_GATEWAY,
+            Json(serde_json::json!({"error": format!("Payout failed: {}", e)})),
+        )
+            .into_response(),
+    }
+}
+
+/// Body a facilitator POSTs to [`settlement_notification_route`] to resolve a payment
+/// previously returned as [`crate::async_settlement::SettlementOutcome::Pending`]
+#[derive(serde::Deserialize)]
+pub struct SettlementNotification {
+    pub settlement_id: String,
+    #[serde(flatten)]
+    pub result: crate::types::SettleResponse,
+}
+
+/// Mount a `POST {path}` endpoint that resolves a pending settlement against `store`
+/// once the facilitator POSTs its final result out of band; see
+/// [`crate::async_settlement`] and [`AxumPaymentConfig::with_pending_settlements`].
+///
+/// Like [`payout_route`], the mounted endpoint itself *is* the operation. This does not
+/// verify the notification's authenticity — mount it behind
+/// [`crate::http_signature`] request-signature verification (or equivalent network
+/// controls) if the facilitator signs its callbacks, since anyone who can reach this
+/// route can otherwise resolve an arbitrary settlement id.
+pub fn settlement_notification_route(
+    path: &str,
+    store: Arc<dyn crate::async_settlement::PendingSettlementStore>,
+) -> Router {
+    Router::new()
+        .route(path, post(settlement_notification_handler))
+        .with_state(store)
+}
+
+async fn settlement_notification_handler(
+    State(store): State<Arc<dyn crate::async_settlement::PendingSettlementStore>>,
+    Json(notification): Json<SettlementNotification>,
+) -> impl IntoResponse {
+    if store
+        .resolve(&notification.settlement_id, notification.result)
+        .await
+    {
+        StatusCode::OK.into_response()
+    } else {
+        (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({
+                "error": format!("unknown settlement id: {}", notification.settlement_id)
+            })),
+        )
+            .into_response()
+    }
+}
+
+/// Mount a `GET {path}/{{payment_id}}` SSE endpoint streaming
+/// [`crate::settlement_status::SettlementStatus`] transitions for `payment_id` as
+/// [`crate::settlement_status::drive_settlement`] (or any other caller of
+/// [`crate::settlement_status::SettlementStatusTracker::publish`]) reports them.
+///
+/// `path` should not include the `payment_id` segment; it's appended here, mirroring
+/// how [`payment_route`] takes the path of the resource it's gating rather than a
+/// fully-built route pattern.
+pub fn settlement_status_route(path: &str, tracker: crate::settlement_status::SettlementStatusTracker) -> Router {
+    Router::new()
+        .route(&format!("{path}/{{payment_id}}"), get(settlement_status_handler))
+        .with_state(tracker)
+}
+
+async fn settlement_status_handler(
+    State(tracker): State<crate::settlement_status::SettlementStatusTracker>,
+    Path(payment_id): Path<String>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let receiver = tracker.subscribe(&payment_id).await;
+    let stream = futures_util::stream::unfold(receiver, |mut receiver| async move {
+        match receiver.recv().await {
+            Ok(status) => {
+                let event = Event::default()
+                    .event(settlement_status_event_name(&status))
+                    .json_data(&status)
+                    .unwrap_or_else(|_| Event::default().event("failed").data("serialization error"));
+                Some((Ok(event), receiver))
+            }
+            Err(_) => None,
+        }
+    });
+
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+/// SSE `event:` name for a [`crate::settlement_status::SettlementStatus`], matching
+/// the `#[serde(tag = "event", rename_all = "snake_case")]` discriminant already
+/// carried in its JSON body
+fn settlement_status_event_name(status: &crate::settlement_status::SettlementStatus) -> &'static str {
+    use crate::settlement_status::SettlementStatus;
+    match status {
+        SettlementStatus::Verifying => "verifying",
+        SettlementStatus::Submitted { .. } => "submitted",
+        SettlementStatus::Confirmed { .. } => "confirmed",
+        SettlementStatus::Failed { .. } => "failed",
+    }
+}
+
+/// Body a resource server POSTs to [`webhook_registration_route`] to subscribe to
+/// settlement lifecycle events; see [`crate::webhook`].
+#[derive(serde::Deserialize)]
+pub struct WebhookRegistration {
+    pub url: String,
+    pub signing_secret: String,
+}
+
+/// Mount a `POST {path}` endpoint that registers a new [`crate::webhook::WebhookConfig`]
+/// with `notifier`, so resource servers can subscribe to settlement lifecycle events
+/// without redeploying the facilitator.
+///
+/// Like [`settlement_notification_route`], this does not verify the caller's identity —
+/// mount it behind authentication appropriate to the deployment, since anyone who can
+/// reach this route can otherwise register a webhook to receive settlement events.
+pub fn webhook_registration_route(
+    path: &str,
+    notifier: Arc<tokio::sync::Mutex<crate::webhook::WebhookNotifier>>,
+) -> Router {
+    Router::new()
+        .route(path, post(webhook_registration_handler))
+        .with_state(notifier)
+}
+
+async fn webhook_registration_handler(
+    State(notifier): State<Arc<tokio::sync::Mutex<crate::webhook::WebhookNotifier>>>,
+    Json(registration): Json<WebhookRegistration>,
+) -> impl IntoResponse {
+    notifier
+        .lock()
+        .await
+        .register(crate::webhook::WebhookConfig::new(
+            registration.url,
+            registration.signing_secret,
+        ));
+    StatusCode::CREATED.into_response()
+}
+
 /// Check if the request is from a web browser
 fn is_web_browser(headers: &HeaderMap) -> bool {
     let user_agent = headers
@@ -149,10 +340,10 @@ pub async fn payment_middleware_handler(
 
                             // After successful response, settle the payment
                             match middleware
-                                .settle_with_requirements(&payment_payload, &requirements)
+                                .settle_with_requirements_tracked(&payment_payload, &requirements)
                                 .await
                             {
-                                Ok(settlement_response) => {
+                                Ok((settlement_response, facilitator_url)) => {
                                     if let Ok(settlement_header) = settlement_response.to_base64() {
                                         if let Ok(header_value) =
                                             HeaderValue::from_str(&settlement_header)
@@ -162,6 +353,17 @@ pub async fn payment_middleware_handler(
                                                 .insert("X-PAYMENT-RESPONSE", header_value);
                                         }
                                     }
+
+                                    // Report which facilitator actually settled when a
+                                    // fallback chain is in use, so clients/operators can
+                                    // tell which backend handled the payment
+                                    if let Some(url) = facilitator_url {
+                                        if let Ok(header_value) = HeaderValue::from_str(&url) {
+                                            response
+                                                .headers_mut()
+                                                .insert("X-FACILITATOR-URL", header_value);
+                                        }
+                                    }
                                 }
                                 Err(e) => {
                                     // Log settlement error but don't fail the request
@@ -239,6 +441,22 @@ pub struct AxumPaymentConfig {
     pub base_config: PaymentMiddlewareConfig,
     /// Additional Axum-specific options
     pub axum_options: AxumOptions,
+    /// Facilitators keyed by `(scheme, network)`, each with its own ordered fallback
+    /// group, set via [`Self::with_facilitators`]
+    pub facilitators: Vec<crate::facilitator::FacilitatorEntry>,
+    /// Policy used to pick the starting facilitator within each pairing's fallback
+    /// group in `facilitators`
+    pub routing_policy: crate::facilitator::RoutingPolicy,
+    /// Backoff schedule used to retry the resolved facilitator's `verify`/`settle`
+    /// calls, set via [`Self::with_retry_policy`]
+    pub retry_policy: Option<crate::facilitator::RetryableFacilitatorPolicy>,
+    /// How long a resolved facilitator's `/supported` document is trusted before
+    /// being re-queried, set via [`Self::with_capability_cache_ttl`]
+    pub capability_cache_ttl: Option<std::time::Duration>,
+    /// Store tracking settlements reported as pending, set via
+    /// [`Self::with_pending_settlements`]; mount [`settlement_notification_route`]
+    /// against the same store to resolve them
+    pub pending_settlements: Option<Arc<dyn crate::async_settlement::PendingSettlementStore>>,
 }
 
 /// Axum-specific options
@@ -271,6 +489,11 @@ impl AxumPaymentConfig {
         Self {
             base_config: PaymentMiddlewareConfig::new(amount, pay_to),
             axum_options: AxumOptions::default(),
+            facilitators: Vec::new(),
+            routing_policy: crate::facilitator::RoutingPolicy::Priority,
+            retry_policy: None,
+            capability_cache_ttl: None,
+            pending_settlements: None,
         }
     }
 
@@ -331,6 +554,42 @@ impl AxumPaymentConfig {
         self
     }
 
+    /// Set the webhook URL notified with the settlement response after a successful
+    /// settlement, so a caller that disconnected before the inline response arrived
+    /// can still be notified
+    pub fn with_notify_uri(mut self, notify_uri: impl Into<String>) -> Self {
+        self.base_config.notify_uri = Some(notify_uri.into());
+        self
+    }
+
+    /// Set the URL a browser paywall should redirect to after payment
+    pub fn with_continue_uri(mut self, continue_uri: impl Into<String>) -> Self {
+        self.base_config.continue_uri = Some(continue_uri.into());
+        self
+    }
+
+    /// Set the URL the facilitator should POST its final result to for a payment
+    /// settled asynchronously; see [`PaymentMiddlewareConfig::async_settlement_notify_uri`].
+    /// Combine with [`Self::with_pending_settlements`] and mount
+    /// [`settlement_notification_route`] at this same URL.
+    pub fn with_async_settlement_notify_uri(
+        mut self,
+        async_settlement_notify_uri: impl Into<String>,
+    ) -> Self {
+        self.base_config.async_settlement_notify_uri = Some(async_settlement_notify_uri.into());
+        self
+    }
+
+    /// Track settlements reported as pending in `store` instead of surfacing them as
+    /// failures; see [`PaymentMiddleware::with_pending_settlements`]
+    pub fn with_pending_settlements(
+        mut self,
+        store: Arc<dyn crate::async_settlement::PendingSettlementStore>,
+    ) -> Self {
+        self.pending_settlements = Some(store);
+        self
+    }
+
     /// Enable CORS
     pub fn with_cors(mut self, origins: Vec<String>) -> Self {
         self.axum_options.enable_cors = true;
@@ -353,12 +612,89 @@ impl AxumPaymentConfig {
         self
     }
 
+    /// Route verify/settle to the facilitator(s) registered for a payment's
+    /// `(scheme, network)` pairing, instead of the single facilitator configured on
+    /// `base_config`. Several entries may share a pairing to form an ordered fallback
+    /// group, falling back to the next on a transient error; combine with
+    /// [`Self::with_routing_policy`] to pick how each group's starting facilitator is
+    /// chosen (defaults to [`crate::facilitator::RoutingPolicy::Priority`]).
+    ///
+    /// Lets one server accept payments across, say, Base and Base-Sepolia, routed to
+    /// the correct facilitator for each.
+    pub fn with_facilitators(mut self, facilitators: Vec<crate::facilitator::FacilitatorEntry>) -> Self {
+        self.facilitators = facilitators;
+        self
+    }
+
+    /// Set the routing policy used to pick the starting facilitator among
+    /// [`Self::with_facilitators`]; has no effect unless facilitators were set
+    pub fn with_routing_policy(mut self, policy: crate::facilitator::RoutingPolicy) -> Self {
+        self.routing_policy = policy;
+        self
+    }
+
+    /// Retry the resolved facilitator's `verify`/`settle` calls under `policy` instead
+    /// of failing on the first transient error; see
+    /// [`PaymentMiddleware::with_retry_policy`] for exactly which path this covers and
+    /// how it also arranges idempotency-safe settles
+    pub fn with_retry_policy(mut self, policy: crate::facilitator::RetryableFacilitatorPolicy) -> Self {
+        self.retry_policy = Some(policy);
+        self
+    }
+
+    /// Fail fast on a network/scheme the resolved facilitator doesn't advertise via
+    /// `/supported`, instead of only discovering it after a failed verify round trip;
+    /// see [`PaymentMiddleware::with_capability_cache_ttl`] for the caching behavior
+    pub fn with_capability_cache_ttl(mut self, ttl: std::time::Duration) -> Self {
+        self.capability_cache_ttl = Some(ttl);
+        self
+    }
+
     /// Convert to PaymentMiddleware
     pub fn into_middleware(self) -> PaymentMiddleware {
+        let facilitator_keyed = if self.facilitators.is_empty() {
+            None
+        } else {
+            Some(
+                crate::facilitator::KeyedFacilitatorChain::new(self.facilitators, self.routing_policy)
+                    .expect("facilitator entry config should build a valid client"),
+            )
+        };
+
+        let webhook_dispatcher = self
+            .base_config
+            .notify_uri
+            .as_ref()
+            .map(|_| crate::settlement::WebhookDispatcher::new(crate::retry::RetryPolicy::new()));
+
+        let idempotency_store = self
+            .retry_policy
+            .as_ref()
+            .map(|_| -> Arc<dyn crate::idempotency::IdempotencyStore> {
+                Arc::new(crate::idempotency::InMemoryIdempotencyStore::default())
+            });
+
         PaymentMiddleware {
             config: Arc::new(self.base_config),
             facilitator: None,
             template_config: None,
+            settlement_queue: None,
+            facilitator_router: None,
+            facilitator_chain: None,
+            facilitator_keyed: facilitator_keyed.map(Arc::new),
+            webhook_dispatcher,
+            retry_policy: self.retry_policy,
+            idempotency_store,
+            settlement_max_attempts: None,
+            capability_cache_ttl: self.capability_cache_ttl,
+            capability_caches: Arc::new(tokio::sync::Mutex::new(std::collections::HashMap::new())),
+            pending_settlements: self.pending_settlements,
+            onchain_verifier: None,
+            ledger: None,
+            nonce_store: None,
+            nonce_replay_store: None,
+            clock_skew_tolerance: std::time::Duration::ZERO,
+            observer: Arc::new(crate::payment_events::NoopObserver),
         }
     }
 
@@ -475,4 +811,296 @@ mod tests {
             Decimal::from_str("0.0001").unwrap()
         );
     }
+
+    #[test]
+    fn test_into_middleware_wires_webhook_dispatcher_when_notify_uri_set() {
+        let middleware = AxumPaymentConfig::new(
+            Decimal::from_str("0.0001").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        )
+        .with_notify_uri("https://example.com/webhook")
+        .with_continue_uri("https://example.com/thanks")
+        .into_middleware();
+
+        assert!(middleware.webhook_dispatcher.is_some());
+        assert_eq!(
+            middleware.config().notify_uri.as_deref(),
+            Some("https://example.com/webhook")
+        );
+        assert_eq!(
+            middleware.config().continue_uri.as_deref(),
+            Some("https://example.com/thanks")
+        );
+    }
+
+    #[test]
+    fn test_into_middleware_skips_webhook_dispatcher_without_notify_uri() {
+        let middleware = AxumPaymentConfig::new(
+            Decimal::from_str("0.0001").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        )
+        .into_middleware();
+
+        assert!(middleware.webhook_dispatcher.is_none());
+    }
+
+    #[test]
+    fn test_into_middleware_wires_retry_policy_and_default_idempotency_store() {
+        let middleware = AxumPaymentConfig::new(
+            Decimal::from_str("0.0001").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        )
+        .with_retry_policy(crate::facilitator::RetryableFacilitatorPolicy::new().with_max_attempts(5))
+        .into_middleware();
+
+        assert_eq!(
+            middleware.retry_policy.as_ref().map(|p| p.max_attempts),
+            Some(5)
+        );
+        assert!(middleware.idempotency_store.is_some());
+    }
+
+    #[test]
+    fn test_into_middleware_skips_idempotency_store_without_retry_policy() {
+        let middleware = AxumPaymentConfig::new(
+            Decimal::from_str("0.0001").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        )
+        .into_middleware();
+
+        assert!(middleware.retry_policy.is_none());
+        assert!(middleware.idempotency_store.is_none());
+    }
+
+    #[test]
+    fn test_into_middleware_wires_capability_cache_ttl() {
+        let middleware = AxumPaymentConfig::new(
+            Decimal::from_str("0.0001").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        )
+        .with_capability_cache_ttl(std::time::Duration::from_secs(30))
+        .into_middleware();
+
+        assert_eq!(
+            middleware.capability_cache_ttl,
+            Some(std::time::Duration::from_secs(30))
+        );
+    }
+
+    #[test]
+    fn test_into_middleware_wires_keyed_facilitators() {
+        let middleware = AxumPaymentConfig::new(
+            Decimal::from_str("0.0001").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        )
+        .with_facilitators(vec![
+            crate::facilitator::FacilitatorEntry::new(
+                "exact",
+                "base-sepolia",
+                crate::types::FacilitatorConfig::default(),
+            ),
+            crate::facilitator::FacilitatorEntry::new(
+                "exact",
+                "base",
+                crate::types::FacilitatorConfig::default(),
+            ),
+        ])
+        .into_middleware();
+
+        let keyed = middleware
+            .facilitator_keyed
+            .as_ref()
+            .expect("with_facilitators should wire a keyed fallback chain");
+        assert!(keyed.resolve("exact", "base-sepolia").is_some());
+        assert!(keyed.resolve("exact", "base").is_some());
+        assert!(keyed.resolve("exact", "avalanche").is_none());
+    }
+
+    #[test]
+    fn test_into_middleware_skips_keyed_facilitators_when_empty() {
+        let middleware = AxumPaymentConfig::new(
+            Decimal::from_str("0.0001").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        )
+        .into_middleware();
+
+        assert!(middleware.facilitator_keyed.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_payout_route_dispatches_to_middleware_payout() {
+        use tower::ServiceExt;
+
+        let mut server = mockito::Server::new_async().await;
+        let _m = server
+            .mock("POST", "/payout")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!({
+                    "success": true,
+                    "kind": "payout",
+                    "transaction": "0xpayout",
+                    "network": "base-sepolia"
+                })
+                .to_string(),
+            )
+            .create();
+
+        let facilitator = crate::facilitator::FacilitatorClient::new(
+            crate::types::FacilitatorConfig::new(server.url()),
+        )
+        .unwrap();
+        let middleware = create_payment_middleware(
+            Decimal::from_str("0.0001").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        )
+        .with_facilitator(facilitator);
+
+        let router = payout_route("/payout", middleware);
+
+        let body = serde_json::json!({
+            "network": "base-sepolia",
+            "destination": "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            "amount": "1000000",
+            "asset": "0x036CbD53842c5426634e7929541eC2318f3dCF7e",
+        });
+
+        let request = axum::http::Request::builder()
+            .method("POST")
+            .uri("/payout")
+            .header("content-type", "application/json")
+            .body(axum::body::Body::from(body.to_string()))
+            .unwrap();
+
+        let response = router.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[test]
+    fn test_payment_requirements_include_continue_uri_in_extra() {
+        let config = AxumPaymentConfig::new(
+            Decimal::from_str("0.0001").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        )
+        .with_continue_uri("https://example.com/thanks");
+
+        let requirements = config
+            .base_config
+            .create_payment_requirements("/test")
+            .unwrap();
+
+        assert_eq!(
+            requirements.extra.unwrap()["continueUri"],
+            "https://example.com/thanks"
+        );
+    }
+
+    #[test]
+    fn test_into_middleware_wires_pending_settlements_and_async_notify_uri() {
+        let store = Arc::new(crate::async_settlement::InMemoryPendingSettlementStore::new());
+        let middleware = AxumPaymentConfig::new(
+            Decimal::from_str("0.0001").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        )
+        .with_async_settlement_notify_uri("https://example.com/x402/settlements/notify")
+        .with_pending_settlements(store)
+        .into_middleware();
+
+        assert!(middleware.pending_settlements.is_some());
+        assert_eq!(
+            middleware.config().async_settlement_notify_uri.as_deref(),
+            Some("https://example.com/x402/settlements/notify")
+        );
+    }
+
+    #[test]
+    fn test_into_middleware_skips_pending_settlements_by_default() {
+        let middleware = AxumPaymentConfig::new(
+            Decimal::from_str("0.0001").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        )
+        .into_middleware();
+
+        assert!(middleware.pending_settlements.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_settlement_notification_route_resolves_pending_entry() {
+        use tower::ServiceExt;
+
+        let store = Arc::new(crate::async_settlement::InMemoryPendingSettlementStore::new());
+        store.begin("settlement-abc".to_string()).await;
+
+        let router = settlement_notification_route("/settlements/notify", store.clone());
+
+        let body = serde_json::json!({
+            "settlement_id": "settlement-abc",
+            "success": true,
+            "transaction": "0xabc",
+            "network": "base-sepolia"
+        });
+        let request = axum::http::Request::builder()
+            .method("POST")
+            .uri("/settlements/notify")
+            .header("content-type", "application/json")
+            .body(axum::body::Body::from(body.to_string()))
+            .unwrap();
+
+        let response = router.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(matches!(
+            store.get("settlement-abc").await,
+            Some(crate::async_settlement::PendingSettlementState::Settled(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_settlement_notification_route_rejects_unknown_id() {
+        use tower::ServiceExt;
+
+        let store = Arc::new(crate::async_settlement::InMemoryPendingSettlementStore::new());
+        let router = settlement_notification_route("/settlements/notify", store);
+
+        let body = serde_json::json!({
+            "settlement_id": "no-such-id",
+            "success": true,
+            "transaction": "0xabc",
+            "network": "base-sepolia"
+        });
+        let request = axum::http::Request::builder()
+            .method("POST")
+            .uri("/settlements/notify")
+            .header("content-type", "application/json")
+            .body(axum::body::Body::from(body.to_string()))
+            .unwrap();
+
+        let response = router.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_webhook_registration_route_registers_config() {
+        use tower::ServiceExt;
+
+        let notifier = Arc::new(tokio::sync::Mutex::new(crate::webhook::WebhookNotifier::new(
+            Vec::new(),
+        )));
+        let router = webhook_registration_route("/webhooks", notifier.clone());
+
+        let body = serde_json::json!({
+            "url": "https://example.com/hook",
+            "signing_secret": "shhh"
+        });
+        let request = axum::http::Request::builder()
+            .method("POST")
+            .uri("/webhooks")
+            .header("content-type", "application/json")
+            .body(axum::body::Body::from(body.to_string()))
+            .unwrap();
+
+        let response = router.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert_eq!(notifier.lock().await.webhook_count(), 1);
+    }
 }
@@ -6,17 +6,36 @@
 //! - Network status verification
 //! - Gas estimation
 
+use crate::retry::RetryPolicy;
 use crate::{Result, X402Error};
+use ethereum_types::{Address, U256};
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// A boxed, `Send` future, used in place of `async fn` in [`Confirm`] since traits
+/// can't have object-safe async methods on stable Rust without an extra proc-macro
+/// crate this workspace doesn't otherwise depend on
+pub type BoxFuture<'a, T> = std::pin::Pin<Box<dyn std::future::Future<Output = T> + Send + 'a>>;
 
 /// Blockchain client for real network interactions
+///
+/// Holds an ordered list of RPC endpoints rather than a single URL: a public node like
+/// `mainnet.base.org` rate-limits and drops connections under load, so every request is
+/// sent through [`RetryPolicy`]-governed backoff, rotating to the next configured
+/// endpoint on each retryable failure rather than hammering the same one. JSON-RPC
+/// application errors (a node that evaluated the call and rejected it, e.g. "nonce too
+/// low") are never retried or rotated past — see [`Self::send_rpc`].
+#[derive(Clone)]
 pub struct BlockchainClient {
-    /// RPC endpoint URL
-    rpc_url: String,
+    /// RPC endpoint URLs, in fallback order; the first is tried first on every request
+    endpoints: Vec<String>,
     /// Network name
     pub network: String,
     /// HTTP client for RPC calls
     client: reqwest::Client,
+    /// Governs backoff between attempts and how many endpoints are tried before
+    /// giving up
+    retry_policy: RetryPolicy,
 }
 
 /// Blockchain transaction status
@@ -34,6 +53,10 @@ pub struct TransactionInfo {
     pub hash: String,
     pub status: TransactionStatus,
     pub block_number: Option<u64>,
+    /// Hash of the block the transaction was mined into, if any; compared against the
+    /// canonical block at that height by [`Confirm::check_confirmation`] to detect a
+    /// reorg that replaced the block without necessarily un-mining the transaction
+    pub block_hash: Option<String>,
     pub gas_used: Option<u64>,
     pub effective_gas_price: Option<String>,
     pub from: String,
@@ -41,6 +64,208 @@ pub struct TransactionInfo {
     pub value: String,
 }
 
+/// The raw EIP-1559 fields of a still-pending transaction, as needed to rebuild a
+/// same-nonce replacement with bumped fees. Unlike [`TransactionInfo`], which reports
+/// confirmation state for monitoring, this captures the fields the original
+/// broadcaster chose so a caller can resubmit them unchanged except for the fees
+/// — see [`FeeBumpPolicy`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingTransaction {
+    pub nonce: u64,
+    pub max_fee_per_gas: u128,
+    pub max_priority_fee_per_gas: u128,
+    pub gas_limit: u128,
+    pub to: String,
+    pub value: u128,
+    pub data: String,
+}
+
+/// Governs how aggressively a stuck transaction's fees are bumped when resubmitting it
+/// at the same nonce (replace-by-fee). Most networks require a minimum percentage
+/// increase over the previous attempt's fees before a node will accept the replacement
+/// (e.g. go-ethereum's default is 10%); `bump_percent` defaults a little above that
+/// floor so a single bump is likely to clear it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FeeBumpPolicy {
+    /// Percentage increase applied to both `maxFeePerGas` and `maxPriorityFeePerGas`
+    pub bump_percent: f64,
+    /// Upper bound on the bumped `maxFeePerGas`, regardless of `bump_percent`
+    pub max_fee_per_gas_ceiling: Option<u128>,
+}
+
+impl Default for FeeBumpPolicy {
+    fn default() -> Self {
+        Self {
+            bump_percent: 12.5,
+            max_fee_per_gas_ceiling: None,
+        }
+    }
+}
+
+impl FeeBumpPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_bump_percent(mut self, bump_percent: f64) -> Self {
+        self.bump_percent = bump_percent;
+        self
+    }
+
+    pub fn with_max_fee_per_gas_ceiling(mut self, ceiling: u128) -> Self {
+        self.max_fee_per_gas_ceiling = Some(ceiling);
+        self
+    }
+
+    /// Apply the bump to a transaction's current fees, returning
+    /// `(max_fee_per_gas, max_priority_fee_per_gas)`. The max fee is capped at
+    /// [`Self::max_fee_per_gas_ceiling`] when set; the priority fee is then capped to
+    /// never exceed the (possibly capped) max fee, since a priority fee above the max
+    /// fee is invalid.
+    pub fn bump(&self, current_max_fee_per_gas: u128, current_max_priority_fee_per_gas: u128) -> (u128, u128) {
+        let factor = 1.0 + self.bump_percent / 100.0;
+        let bumped_max_fee = (current_max_fee_per_gas as f64 * factor).ceil() as u128;
+        let bumped_priority_fee = (current_max_priority_fee_per_gas as f64 * factor).ceil() as u128;
+
+        let capped_max_fee = match self.max_fee_per_gas_ceiling {
+            Some(ceiling) => bumped_max_fee.min(ceiling),
+            None => bumped_max_fee,
+        };
+        let capped_priority_fee = bumped_priority_fee.min(capped_max_fee);
+
+        (capped_max_fee, capped_priority_fee)
+    }
+}
+
+/// Result of one [`Confirm::check_confirmation`] poll
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfirmationReport {
+    /// Blocks mined on top of the transaction's block, inclusive (1 if it's the tip)
+    pub confirmations: u64,
+    /// The depth [`Confirm::check_confirmation`] was asked to require
+    pub required: u64,
+    /// Hash of the block the transaction is currently mined into
+    pub block_hash: String,
+    /// `true` if the caller's previously observed `block_hash` no longer matches the
+    /// canonical block at that height — the block was reorged out, even though the
+    /// transaction may still show as mined (e.g. re-included in the replacement
+    /// block). Callers should treat this the same as the transaction disappearing:
+    /// resume monitoring, or trigger re-settlement.
+    pub reorged: bool,
+}
+
+impl ConfirmationReport {
+    /// `true` once `confirmations` has reached `required` and the block wasn't reorged
+    pub fn is_final(&self) -> bool {
+        !self.reorged && self.confirmations >= self.required
+    }
+}
+
+/// Polls a transaction's confirmation depth while cross-checking that the block it
+/// was mined into is still the canonical block at that height, rather than treating a
+/// single `Confirmed` status as final. A reorg can replace a block without the
+/// transaction ever disappearing from `eth_getTransactionByHash` (e.g. it gets
+/// re-included in the replacement block at a different position or even a different
+/// block), which a bare "is it still found" check — like
+/// [`SettlementConfirmer`](crate::settlement_confirmation::SettlementConfirmer)'s
+/// `previously_mined` tracking — can't detect on its own.
+pub trait Confirm: Send + Sync {
+    /// Check `tx_hash`'s current confirmation depth, comparing the block it's mined
+    /// into against `previous_block_hash` (the hash observed on a prior poll, if any)
+    /// to detect a reorg that replaced that block
+    fn check_confirmation<'a>(
+        &'a self,
+        tx_hash: &'a str,
+        previous_block_hash: Option<&'a str>,
+        required_confirmations: u64,
+    ) -> BoxFuture<'a, Result<Option<ConfirmationReport>>>;
+}
+
+impl Confirm for BlockchainClient {
+    fn check_confirmation<'a>(
+        &'a self,
+        tx_hash: &'a str,
+        previous_block_hash: Option<&'a str>,
+        required_confirmations: u64,
+    ) -> BoxFuture<'a, Result<Option<ConfirmationReport>>> {
+        Box::pin(async move {
+            let status = self.get_transaction_status(tx_hash).await?;
+            let (Some(block_number), Some(block_hash)) = (status.block_number, status.block_hash)
+            else {
+                return Ok(None);
+            };
+
+            // The block we previously observed at this height is no longer canonical —
+            // either some other block now occupies it, or the height no longer
+            // resolves to any block at all. A first poll (`previous_block_hash` is
+            // `None`) has nothing to compare against yet, so it can't be a reorg.
+            let canonical_hash = self.block_hash_at(block_number).await?;
+            let reorged = previous_block_hash
+                .map(|previous| canonical_hash.as_deref() != Some(previous))
+                .unwrap_or(false);
+
+            let network_info = self.get_network_info().await?;
+            let confirmations = network_info.latest_block.saturating_sub(block_number) + 1;
+
+            Ok(Some(ConfirmationReport {
+                confirmations,
+                required: required_confirmations,
+                block_hash,
+                reorged,
+            }))
+        })
+    }
+}
+
+/// A single event log entry from a transaction receipt
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionLog {
+    /// Contract that emitted the log
+    pub address: String,
+    /// Indexed event topics; `topics[0]` is the event signature hash
+    pub topics: Vec<String>,
+    /// ABI-encoded non-indexed event data
+    pub data: String,
+}
+
+/// Full transaction receipt, including the logs and bloom filter needed to
+/// independently confirm a settlement; see [`crate::onchain_verification`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionReceipt {
+    pub transaction_hash: String,
+    /// `"0x1"` on success, `"0x0"` on a reverted transaction
+    pub status: String,
+    /// 256-byte bloom filter over this receipt's logs, hex-encoded
+    pub logs_bloom: String,
+    pub logs: Vec<TransactionLog>,
+    /// Hex-encoded number of the block this transaction was mined in
+    pub block_number: Option<String>,
+    /// Hex-encoded gas actually consumed by this transaction
+    pub gas_used: Option<String>,
+    /// Hex-encoded gas price this transaction actually paid (post-EIP-1559 base fee)
+    pub effective_gas_price: Option<String>,
+}
+
+/// A block's hash and `logsBloom`, fetched without its full transaction list; see
+/// [`BlockchainClient::get_block_bloom`]
+#[derive(Debug, Clone)]
+pub struct BlockBloom {
+    pub hash: String,
+    pub logs_bloom: String,
+}
+
+/// A single `eth_getLogs` result entry: the log itself plus the transaction and
+/// block it was emitted in, which [`TransactionReceipt`]'s embedded logs don't need
+/// to repeat (a receipt already names one transaction) but a ranged log query spans
+/// many; see [`BlockchainClient::get_logs_in_block`]
+#[derive(Debug, Clone)]
+pub struct MatchedLog {
+    pub transaction_hash: String,
+    pub block_number: u64,
+    pub log: TransactionLog,
+}
+
 /// Balance information for an address
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BalanceInfo {
@@ -56,37 +281,132 @@ pub struct NetworkInfo {
     pub chain_id: u64,
     pub network_name: String,
     pub latest_block: u64,
+    /// Legacy `eth_gasPrice`, hex-encoded
     pub gas_price: String,
+    /// EIP-1559 fees suggested by [`crate::gas_oracle::GasOracle`] at its default
+    /// strategy, when `eth_feeHistory` is available on this network; `None` on a
+    /// network that only supports legacy gas pricing
+    pub suggested_fees: Option<crate::gas_oracle::EvmFees>,
 }
 
 impl BlockchainClient {
-    /// Create a new blockchain client
+    /// Create a new blockchain client against a single RPC endpoint
     pub fn new(rpc_url: String, network: String) -> Self {
         Self {
-            rpc_url,
+            endpoints: vec![rpc_url],
             network,
             client: reqwest::Client::new(),
+            retry_policy: RetryPolicy::new(),
         }
     }
 
-    /// Get transaction status by hash
-    pub async fn get_transaction_status(&self, tx_hash: &str) -> Result<TransactionInfo> {
+    /// Add fallback RPC endpoints, tried in order after the primary on retryable
+    /// failures (transport errors, timeouts, HTTP 429/5xx)
+    pub fn with_fallback_endpoints(mut self, endpoints: Vec<String>) -> Self {
+        self.endpoints.extend(endpoints);
+        self
+    }
+
+    /// Override the backoff/attempt-count policy governing endpoint failover
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Send one JSON-RPC request body, rotating across [`Self::endpoints`] and backing
+    /// off per [`Self::retry_policy`] on transport errors and HTTP 429/5xx responses.
+    ///
+    /// A JSON-RPC application error (a well-formed response carrying an `error`
+    /// field, e.g. "nonce too low") is returned here as `Ok` — the node was reached
+    /// and evaluated the call, so there's nothing to retry or fail over; it's the
+    /// caller's job to inspect the body for an `error` field where that's meaningful
+    /// (see [`Self::send_raw_transaction`]).
+    async fn send_rpc(&self, body: &serde_json::Value) -> Result<serde_json::Value> {
+        let next_endpoint = std::sync::atomic::AtomicUsize::new(0);
+        crate::retry::retry_with_backoff(&self.retry_policy, || {
+            let index = next_endpoint.fetch_add(1, std::sync::atomic::Ordering::SeqCst) % self.endpoints.len();
+            self.send_once(&self.endpoints[index], body)
+        })
+        .await
+    }
+
+    /// Send one JSON-RPC request to a specific endpoint with no retry or failover
+    async fn send_once(&self, endpoint: &str, body: &serde_json::Value) -> Result<serde_json::Value> {
         let response = self
             .client
-            .post(&self.rpc_url)
-            .json(&serde_json::json!({
-                "jsonrpc": "2.0",
-                "method": "eth_getTransactionByHash",
-                "params": [tx_hash],
-                "id": 1
-            }))
+            .post(endpoint)
+            .json(body)
             .send()
             .await
-            .map_err(|e| X402Error::network_error(format!("RPC request failed: {}", e)))?;
+            .map_err(|e| X402Error::network_error(format!("RPC request to {} failed: {}", endpoint, e)))?;
 
-        let response_json: serde_json::Value = response.json().await.map_err(|e| {
-            X402Error::network_error(format!("Failed to parse RPC response: {}", e))
-        })?;
+        let status = response.status().as_u16();
+        if crate::retry::is_retryable_status(status) {
+            return Err(X402Error::network_error(format!(
+                "RPC endpoint {} returned HTTP {}",
+                endpoint, status
+            )));
+        }
+
+        response.json().await.map_err(|e| {
+            X402Error::network_error(format!("Failed to parse response from {}: {}", endpoint, e))
+        })
+    }
+
+    /// Batch several JSON-RPC calls into a single HTTP round-trip
+    ///
+    /// Each `(method, params)` pair is assigned a distinct numeric id matching its
+    /// position in `calls`, sent as one JSON-RPC 2.0 batch request, and the response
+    /// array (nodes aren't required to preserve request order) is demultiplexed back
+    /// into that same order by matching `id`, so `results[i]` always answers
+    /// `calls[i]` regardless of how the node ordered its reply.
+    pub async fn batch(&self, calls: &[(&str, serde_json::Value)]) -> Result<Vec<serde_json::Value>> {
+        let request_body = serde_json::Value::Array(
+            calls
+                .iter()
+                .enumerate()
+                .map(|(id, (method, params))| {
+                    serde_json::json!({
+                        "jsonrpc": "2.0",
+                        "method": method,
+                        "params": params,
+                        "id": id
+                    })
+                })
+                .collect(),
+        );
+
+        let response_entries: Vec<serde_json::Value> = self
+            .send_rpc(&request_body)
+            .await?
+            .as_array()
+            .cloned()
+            .ok_or_else(|| X402Error::network_error("RPC batch response was not an array".to_string()))?;
+
+        let mut by_id: std::collections::HashMap<u64, serde_json::Value> = response_entries
+            .into_iter()
+            .filter_map(|entry| Some((entry.get("id")?.as_u64()?, entry)))
+            .collect();
+
+        (0..calls.len())
+            .map(|id| {
+                by_id.remove(&(id as u64)).ok_or_else(|| {
+                    X402Error::network_error(format!("No response for batched call id {}", id))
+                })
+            })
+            .collect()
+    }
+
+    /// Get transaction status by hash
+    pub async fn get_transaction_status(&self, tx_hash: &str) -> Result<TransactionInfo> {
+        let responses = self
+            .batch(&[
+                ("eth_getTransactionByHash", serde_json::json!([tx_hash])),
+                ("eth_getTransactionReceipt", serde_json::json!([tx_hash])),
+            ])
+            .await?;
+        let response_json = &responses[0];
+        let gas_info = responses[1].get("result").filter(|v| !v.is_null()).cloned();
 
         if let Some(result) = response_json.get("result") {
             if result.is_null() {
@@ -94,6 +414,7 @@ impl BlockchainClient {
                     hash: tx_hash.to_string(),
                     status: TransactionStatus::Unknown,
                     block_number: None,
+                    block_hash: None,
                     gas_used: None,
                     effective_gas_price: None,
                     from: "".to_string(),
@@ -106,9 +427,10 @@ impl BlockchainClient {
                 .get("blockNumber")
                 .and_then(|v| v.as_str())
                 .and_then(|s| u64::from_str_radix(s.trim_start_matches("0x"), 16).ok());
-
-            // Get transaction receipt for gas information
-            let gas_info = self.get_transaction_receipt(tx_hash).await.ok();
+            let block_hash = result
+                .get("blockHash")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string());
 
             Ok(TransactionInfo {
                 hash: tx_hash.to_string(),
@@ -118,6 +440,7 @@ impl BlockchainClient {
                     TransactionStatus::Pending
                 },
                 block_number,
+                block_hash,
                 gas_used: gas_info
                     .as_ref()
                     .and_then(|r| r.get("gasUsed"))
@@ -153,24 +476,213 @@ impl BlockchainClient {
         }
     }
 
+    /// Fetch `tx_hash`'s full EIP-1559 fields (nonce, fees, `to`, `value`, calldata)
+    /// via `eth_getTransactionByHash`, the inputs a caller needs to build a
+    /// replace-by-fee resubmission with [`FeeBumpPolicy`]. Unlike
+    /// [`Self::get_transaction_status`], which only reports confirmation state, this
+    /// surfaces the fields the original broadcaster chose so they can be reused
+    /// as-is except for the bumped fees.
+    pub async fn get_pending_transaction(&self, tx_hash: &str) -> Result<PendingTransaction> {
+        let response_json = self
+            .send_rpc(&serde_json::json!({
+                "jsonrpc": "2.0",
+                "method": "eth_getTransactionByHash",
+                "params": [tx_hash],
+                "id": 1
+            }))
+            .await?;
+
+        let result = response_json.get("result").filter(|v| !v.is_null()).ok_or_else(|| {
+            X402Error::network_error(format!("transaction {} not found", tx_hash))
+        })?;
+
+        let hex_field = |name: &str| -> Result<u128> {
+            result
+                .get(name)
+                .and_then(|v| v.as_str())
+                .and_then(|s| u128::from_str_radix(s.trim_start_matches("0x"), 16).ok())
+                .ok_or_else(|| X402Error::network_error(format!("transaction is missing `{}`", name)))
+        };
+
+        Ok(PendingTransaction {
+            nonce: hex_field("nonce")? as u64,
+            max_fee_per_gas: hex_field("maxFeePerGas")?,
+            max_priority_fee_per_gas: hex_field("maxPriorityFeePerGas")?,
+            gas_limit: hex_field("gas")?,
+            to: result
+                .get("to")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string(),
+            value: hex_field("value").unwrap_or(0),
+            data: result
+                .get("input")
+                .and_then(|v| v.as_str())
+                .unwrap_or("0x")
+                .to_string(),
+        })
+    }
+
+    /// Replay `tx_hash`'s calldata via `eth_call` at `block_number` (the block it
+    /// reverted in), to recover a human-readable reason from a
+    /// `require(cond, "msg")`-style revert.
+    ///
+    /// Nodes don't include the revert reason in `eth_getTransactionReceipt` — only
+    /// re-running the same call, either as an `eth_call` that itself errors with the
+    /// reason in `error.data`, or (depending on the node) a plain `error.message`,
+    /// surfaces it. Returns `Ok(None)` rather than erroring when no reason can be
+    /// recovered (a custom Solidity error, a bare `revert()`, or a node that doesn't
+    /// echo call-trace data back), since a decoded reason is a diagnostic nice-to-have
+    /// for [`Self::watch_transaction`], not something that should fail over.
+    pub async fn get_revert_reason(&self, tx_hash: &str, block_number: u64) -> Result<Option<String>> {
+        let tx_response = self
+            .send_rpc(&serde_json::json!({
+                "jsonrpc": "2.0",
+                "method": "eth_getTransactionByHash",
+                "params": [tx_hash],
+                "id": 1
+            }))
+            .await?;
+        let Some(tx) = tx_response.get("result").filter(|v| !v.is_null()) else {
+            return Ok(None);
+        };
+        let to = tx.get("to").and_then(|v| v.as_str()).unwrap_or("");
+        let data = tx.get("input").and_then(|v| v.as_str()).unwrap_or("0x");
+
+        let call_response = self
+            .send_rpc(&serde_json::json!({
+                "jsonrpc": "2.0",
+                "method": "eth_call",
+                "params": [{"to": to, "data": data}, format!("0x{:x}", block_number)],
+                "id": 1
+            }))
+            .await?;
+
+        let Some(error) = call_response.get("error") else {
+            return Ok(None);
+        };
+        if let Some(data) = error.get("data").and_then(|v| v.as_str()) {
+            if let Some(reason) = crate::erc20::decode_revert_reason(data) {
+                return Ok(Some(reason));
+            }
+        }
+        Ok(error.get("message").and_then(|v| v.as_str()).map(|s| s.to_string()))
+    }
+
+    /// `eth_call` against `contract` with `calldata` at a specific `block_number`,
+    /// returning the raw hex-encoded return value as-is (unlike [`Self::eth_call_u256`],
+    /// which assumes and decodes a single `uint256` word).
+    ///
+    /// Used to recover a state-changing call's return value after it's already been
+    /// mined, since a receipt doesn't carry one: replaying the identical calldata as a
+    /// read-only call at the block it landed in reproduces the same result. See
+    /// [`crate::real_facilitator::BlockchainFacilitatorClient::settle_batch`], which
+    /// replays a settled `aggregate3` call this way to decode its per-call results.
+    pub async fn call_at_block(&self, contract: &str, calldata: &str, block_number: u64) -> Result<String> {
+        let response_json = self
+            .send_rpc(&serde_json::json!({
+                "jsonrpc": "2.0",
+                "method": "eth_call",
+                "params": [{
+                    "to": contract,
+                    "data": calldata
+                }, format!("0x{:x}", block_number)],
+                "id": 1
+            }))
+            .await?;
+
+        Ok(response_json
+            .get("result")
+            .and_then(|v| v.as_str())
+            .unwrap_or("0x")
+            .to_string())
+    }
+
+    /// Fetch and decode `tx_hash`'s full receipt, including the logs and `logsBloom`
+    /// needed to independently confirm a settlement; see
+    /// [`crate::onchain_verification`]
+    pub async fn get_receipt(&self, tx_hash: &str) -> Result<TransactionReceipt> {
+        let result = self.get_transaction_receipt(tx_hash).await?;
+        serde_json::from_value(result).map_err(|e| {
+            X402Error::network_error(format!("Failed to decode transaction receipt: {}", e))
+        })
+    }
+
+    /// Same as [`Self::get_receipt`], but a `null` result (the node hasn't mined
+    /// `tx_hash` yet) comes back as `None` instead of a decode error
+    pub async fn try_get_receipt(&self, tx_hash: &str) -> Result<Option<TransactionReceipt>> {
+        let result = self.get_transaction_receipt(tx_hash).await?;
+        if result.is_null() {
+            return Ok(None);
+        }
+        serde_json::from_value(result)
+            .map(Some)
+            .map_err(|e| X402Error::network_error(format!("Failed to decode transaction receipt: {}", e)))
+    }
+
+    /// Poll `tx_hash` until it has at least `confirmations` blocks mined on top of it
+    /// (`latest_block - tx.block_number + 1 >= confirmations`), returning its
+    /// [`TransactionInfo`] once that depth is reached.
+    ///
+    /// `get_transaction_status`'s notion of "confirmed" is just "has any block
+    /// number" — too shallow for settlement finality, since a block that young can
+    /// still be reorged out. Every poll re-reads both the transaction's current block
+    /// number and the receipt's revert status from scratch rather than caching either:
+    /// if a reorg moves `tx_hash` to a different block (or drops it back to pending)
+    /// between polls, the depth is recomputed against its new block number, so a
+    /// reorg restarts the confirmation count instead of this falsely finalizing on
+    /// confirmations a dropped block already contributed.
+    ///
+    /// Returns [`X402Error::TransactionReverted`] as soon as a mined receipt's
+    /// `status` is `"0x0"`, without waiting out `deadline` — a revert is already
+    /// final and isn't going to un-revert by confirming deeper. Returns
+    /// [`X402Error::Timeout`] if `confirmations` isn't reached within `deadline`.
+    pub async fn watch_transaction(
+        &self,
+        tx_hash: &str,
+        confirmations: u64,
+        deadline: std::time::Duration,
+    ) -> Result<TransactionInfo> {
+        const POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+        let started = tokio::time::Instant::now();
+
+        loop {
+            let status = self.get_transaction_status(tx_hash).await?;
+
+            if let Some(block_number) = status.block_number {
+                let receipt = self.get_receipt(tx_hash).await?;
+                if receipt.status == "0x0" {
+                    return Err(match self.get_revert_reason(tx_hash, block_number).await {
+                        Ok(Some(reason)) => X402Error::transaction_reverted_with_reason(tx_hash, reason),
+                        _ => X402Error::transaction_reverted(tx_hash),
+                    });
+                }
+
+                let network_info = self.get_network_info().await?;
+                let depth = network_info.latest_block.saturating_sub(block_number) + 1;
+                if depth >= confirmations {
+                    return Ok(status);
+                }
+            }
+
+            if started.elapsed() >= deadline {
+                return Err(X402Error::Timeout);
+            }
+
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+
     /// Get transaction receipt
     async fn get_transaction_receipt(&self, tx_hash: &str) -> Result<serde_json::Value> {
-        let response = self
-            .client
-            .post(&self.rpc_url)
-            .json(&serde_json::json!({
+        let response_json = self
+            .send_rpc(&serde_json::json!({
                 "jsonrpc": "2.0",
                 "method": "eth_getTransactionReceipt",
                 "params": [tx_hash],
                 "id": 1
             }))
-            .send()
-            .await
-            .map_err(|e| X402Error::network_error(format!("RPC request failed: {}", e)))?;
-
-        let response_json: serde_json::Value = response.json().await.map_err(|e| {
-            X402Error::network_error(format!("Failed to parse RPC response: {}", e))
-        })?;
+            .await?;
 
         response_json
             .get("result")
@@ -180,22 +692,14 @@ impl BlockchainClient {
 
     /// Get balance for an address
     pub async fn get_balance(&self, address: &str) -> Result<BalanceInfo> {
-        let response = self
-            .client
-            .post(&self.rpc_url)
-            .json(&serde_json::json!({
+        let response_json = self
+            .send_rpc(&serde_json::json!({
                 "jsonrpc": "2.0",
                 "method": "eth_getBalance",
                 "params": [address, "latest"],
                 "id": 1
             }))
-            .send()
-            .await
-            .map_err(|e| X402Error::network_error(format!("RPC request failed: {}", e)))?;
-
-        let response_json: serde_json::Value = response.json().await.map_err(|e| {
-            X402Error::network_error(format!("Failed to parse RPC response: {}", e))
-        })?;
+            .await?;
 
         let balance = response_json
             .get("result")
@@ -212,144 +716,232 @@ impl BlockchainClient {
     }
 
     /// Get USDC balance for an address
+    ///
+    /// Builds its `balanceOf(address)` calldata through [`crate::erc20`]'s typed
+    /// encoder rather than the hand-formatted hex string this method used to
+    /// concatenate directly, so a malformed `address` is rejected up front instead
+    /// of silently producing calldata that returns `0x0` from any contract.
     pub async fn get_usdc_balance(&self, address: &str) -> Result<BalanceInfo> {
         let usdc_contract = self.get_usdc_contract_address()?;
+        let owner = parse_address(address)?;
+        let raw = self.eth_call_u256(&usdc_contract, crate::erc20::balance_of(owner)).await?;
 
-        // Call balanceOf function on USDC contract
-        let response = self
-            .client
-            .post(&self.rpc_url)
-            .json(&serde_json::json!({
+        Ok(BalanceInfo {
+            address: address.to_string(),
+            balance: "0x0".to_string(), // We're only getting token balance
+            token_balance: Some(format!("0x{:x}", raw)),
+            token_address: Some(usdc_contract),
+        })
+    }
+
+    /// Get `owner`'s balance of an arbitrary ERC-20 `token`, reported in human-readable
+    /// units (the raw `balanceOf` result divided by the token's own [`Self::get_token_decimals`])
+    ///
+    /// Unlike [`Self::get_usdc_balance`], `token` isn't limited to this network's USDC
+    /// contract, and the calldata is built from [`crate::erc20`]'s typed encoder rather
+    /// than hand-formatted.
+    pub async fn get_token_balance(&self, token: &str, owner: &str) -> Result<BalanceInfo> {
+        let owner_address = parse_address(owner)?;
+        let raw = self.eth_call_u256(token, crate::erc20::balance_of(owner_address)).await?;
+        let decimals = self.get_token_decimals(token).await?;
+
+        Ok(BalanceInfo {
+            address: owner.to_string(),
+            balance: "0x0".to_string(), // We're only getting token balance
+            token_balance: Some(format_token_amount(raw, decimals)),
+            token_address: Some(token.to_string()),
+        })
+    }
+
+    /// Get the number of decimals `token` reports balances in
+    pub async fn get_token_decimals(&self, token: &str) -> Result<u64> {
+        let raw = self.eth_call_u256(token, crate::erc20::decimals()).await?;
+        Ok(raw.low_u64())
+    }
+
+    /// Get how much of `token` `spender` is allowed to transfer on `owner`'s behalf,
+    /// in the token's smallest unit (not decimal-adjusted, since an allowance is
+    /// commonly set to the token's max `uint256` and dividing that down would lose
+    /// the "unlimited approval" signal)
+    pub async fn get_token_allowance(&self, token: &str, owner: &str, spender: &str) -> Result<U256> {
+        let calldata = crate::erc20::allowance(parse_address(owner)?, parse_address(spender)?);
+        self.eth_call_u256(token, calldata).await
+    }
+
+    /// `eth_call` against `contract` with `calldata`, decoding the returned 32-byte
+    /// hex word as a [`U256`]
+    async fn eth_call_u256(&self, contract: &str, calldata: String) -> Result<U256> {
+        let response_json = self
+            .send_rpc(&serde_json::json!({
                 "jsonrpc": "2.0",
                 "method": "eth_call",
                 "params": [{
-                    "to": usdc_contract,
-                    "data": format!("0x70a08231000000000000000000000000{}", address.trim_start_matches("0x"))
+                    "to": contract,
+                    "data": calldata
                 }, "latest"],
                 "id": 1
             }))
-            .send()
-            .await
-            .map_err(|e| X402Error::network_error(format!("RPC request failed: {}", e)))?;
+            .await?;
 
-        let response_json: serde_json::Value = response.json().await.map_err(|e| {
-            X402Error::network_error(format!("Failed to parse RPC response: {}", e))
-        })?;
-
-        let token_balance = response_json
+        let result = response_json
             .get("result")
             .and_then(|v| v.as_str())
-            .unwrap_or("0x0")
-            .to_string();
-
-        Ok(BalanceInfo {
-            address: address.to_string(),
-            balance: "0x0".to_string(), // We're only getting token balance
-            token_balance: Some(token_balance),
-            token_address: Some(usdc_contract),
-        })
+            .unwrap_or("0x0");
+        crate::erc20::decode_u256(result)
     }
 
     /// Get network information
     pub async fn get_network_info(&self) -> Result<NetworkInfo> {
-        // Get chain ID
-        let chain_id_response = self
-            .client
-            .post(&self.rpc_url)
-            .json(&serde_json::json!({
-                "jsonrpc": "2.0",
-                "method": "eth_chainId",
-                "params": [],
-                "id": 1
-            }))
-            .send()
-            .await
-            .map_err(|e| X402Error::network_error(format!("RPC request failed: {}", e)))?;
+        let responses = self
+            .batch(&[
+                ("eth_chainId", serde_json::json!([])),
+                ("eth_blockNumber", serde_json::json!([])),
+                ("eth_gasPrice", serde_json::json!([])),
+            ])
+            .await?;
 
-        let chain_id_json: serde_json::Value = chain_id_response.json().await.map_err(|e| {
-            X402Error::network_error(format!("Failed to parse RPC response: {}", e))
-        })?;
-
-        let chain_id = chain_id_json
+        let chain_id = responses[0]
             .get("result")
             .and_then(|v| v.as_str())
             .and_then(|s| u64::from_str_radix(s.trim_start_matches("0x"), 16).ok())
             .unwrap_or(0);
 
-        // Get latest block number
-        let block_response = self
-            .client
-            .post(&self.rpc_url)
-            .json(&serde_json::json!({
-                "jsonrpc": "2.0",
-                "method": "eth_blockNumber",
-                "params": [],
-                "id": 1
-            }))
-            .send()
-            .await
-            .map_err(|e| X402Error::network_error(format!("RPC request failed: {}", e)))?;
-
-        let block_json: serde_json::Value = block_response.json().await.map_err(|e| {
-            X402Error::network_error(format!("Failed to parse RPC response: {}", e))
-        })?;
-
-        let latest_block = block_json
+        let latest_block = responses[1]
             .get("result")
             .and_then(|v| v.as_str())
             .and_then(|s| u64::from_str_radix(s.trim_start_matches("0x"), 16).ok())
             .unwrap_or(0);
 
-        // Get gas price
-        let gas_response = self
-            .client
-            .post(&self.rpc_url)
-            .json(&serde_json::json!({
-                "jsonrpc": "2.0",
-                "method": "eth_gasPrice",
-                "params": [],
-                "id": 1
-            }))
-            .send()
-            .await
-            .map_err(|e| X402Error::network_error(format!("RPC request failed: {}", e)))?;
-
-        let gas_json: serde_json::Value = gas_response.json().await.map_err(|e| {
-            X402Error::network_error(format!("Failed to parse RPC response: {}", e))
-        })?;
-
-        let gas_price = gas_json
+        let gas_price = responses[2]
             .get("result")
             .and_then(|v| v.as_str())
             .unwrap_or("0x0")
             .to_string();
 
+        let suggested_fees = self
+            .fee_history(4, crate::gas_oracle::FeeStrategy::Average.reward_percentile())
+            .await
+            .ok()
+            .map(|fees| crate::gas_oracle::eip1559_fees_from_history(fees, crate::gas_oracle::GasOracle::DEFAULT_BASE_FEE_MULTIPLIER));
+
         Ok(NetworkInfo {
             chain_id,
             network_name: self.network.clone(),
             latest_block,
             gas_price,
+            suggested_fees,
         })
     }
 
+    /// Fetch the canonical block hash at `block_number` via `eth_getBlockByNumber`,
+    /// used by [`Confirm::check_confirmation`] to detect whether the block a
+    /// transaction was mined into is still on the canonical chain
+    pub async fn block_hash_at(&self, block_number: u64) -> Result<Option<String>> {
+        let response_json = self
+            .send_rpc(&serde_json::json!({
+                "jsonrpc": "2.0",
+                "method": "eth_getBlockByNumber",
+                "params": [format!("0x{:x}", block_number), false],
+                "id": 1
+            }))
+            .await?;
+
+        Ok(response_json
+            .get("result")
+            .filter(|v| !v.is_null())
+            .and_then(|r| r.get("hash"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string()))
+    }
+
+    /// Fetch `block_number`'s hash and `logsBloom` via `eth_getBlockByNumber`, the
+    /// cheap per-block prefilter `crate::settlement_verifier::SettlementVerifier` tests
+    /// before ever calling `eth_getLogs` for that block
+    pub async fn get_block_bloom(&self, block_number: u64) -> Result<Option<BlockBloom>> {
+        let response_json = self
+            .send_rpc(&serde_json::json!({
+                "jsonrpc": "2.0",
+                "method": "eth_getBlockByNumber",
+                "params": [format!("0x{:x}", block_number), false],
+                "id": 1
+            }))
+            .await?;
+
+        let result = response_json.get("result").filter(|v| !v.is_null());
+        Ok(result.and_then(|r| {
+            let hash = r.get("hash")?.as_str()?.to_string();
+            let logs_bloom = r.get("logsBloom")?.as_str()?.to_string();
+            Some(BlockBloom { hash, logs_bloom })
+        }))
+    }
+
+    /// Fetch every log at `address` matching `topics` emitted in `block_number`, via
+    /// `eth_getLogs` with both `fromBlock`/`toBlock` pinned to that single block
+    ///
+    /// Each `topics` entry is matched positionally; pass `None` for a position that
+    /// should match any topic (a trailing wildcard can simply be omitted).
+    pub async fn get_logs_in_block(
+        &self,
+        block_number: u64,
+        address: &str,
+        topics: &[Option<String>],
+    ) -> Result<Vec<MatchedLog>> {
+        let block_hex = format!("0x{:x}", block_number);
+        let response_json = self
+            .send_rpc(&serde_json::json!({
+                "jsonrpc": "2.0",
+                "method": "eth_getLogs",
+                "params": [{
+                    "fromBlock": block_hex,
+                    "toBlock": block_hex,
+                    "address": address,
+                    "topics": topics,
+                }],
+                "id": 1
+            }))
+            .await?;
+
+        let entries = response_json
+            .get("result")
+            .and_then(|v| v.as_array())
+            .cloned()
+            .unwrap_or_default();
+
+        entries
+            .into_iter()
+            .map(|entry| {
+                let log: TransactionLog = serde_json::from_value(entry.clone()).map_err(|e| {
+                    X402Error::network_error(format!("Failed to decode log: {}", e))
+                })?;
+                let transaction_hash = entry
+                    .get("transactionHash")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| X402Error::network_error("Log missing transactionHash"))?
+                    .to_string();
+                let block_number = entry
+                    .get("blockNumber")
+                    .and_then(|v| v.as_str())
+                    .and_then(|s| u64::from_str_radix(s.trim_start_matches("0x"), 16).ok())
+                    .ok_or_else(|| X402Error::network_error("Log missing blockNumber"))?;
+                Ok(MatchedLog {
+                    transaction_hash,
+                    block_number,
+                    log,
+                })
+            })
+            .collect()
+    }
+
     /// Estimate gas for a transaction
     pub async fn estimate_gas(&self, transaction: &TransactionRequest) -> Result<u64> {
-        let response = self
-            .client
-            .post(&self.rpc_url)
-            .json(&serde_json::json!({
+        let response_json = self
+            .send_rpc(&serde_json::json!({
                 "jsonrpc": "2.0",
                 "method": "eth_estimateGas",
                 "params": [transaction],
                 "id": 1
             }))
-            .send()
-            .await
-            .map_err(|e| X402Error::network_error(format!("RPC request failed: {}", e)))?;
-
-        let response_json: serde_json::Value = response.json().await.map_err(|e| {
-            X402Error::network_error(format!("Failed to parse RPC response: {}", e))
-        })?;
+            .await?;
 
         let gas_hex = response_json
             .get("result")
@@ -360,6 +952,97 @@ impl BlockchainClient {
             .map_err(|_| X402Error::network_error("Invalid gas estimate format".to_string()))
     }
 
+    /// Get the next nonce for `address`, using the `"pending"` block tag so
+    /// transactions already queued (but not yet mined) are accounted for
+    pub async fn get_transaction_count(&self, address: &str) -> Result<u64> {
+        let response_json = self
+            .send_rpc(&serde_json::json!({
+                "jsonrpc": "2.0",
+                "method": "eth_getTransactionCount",
+                "params": [address, "pending"],
+                "id": 1
+            }))
+            .await?;
+
+        response_json
+            .get("result")
+            .and_then(|v| v.as_str())
+            .and_then(|s| u64::from_str_radix(s.trim_start_matches("0x"), 16).ok())
+            .ok_or_else(|| X402Error::network_error("No transaction count in response".to_string()))
+    }
+
+    /// Fetch EIP-1559 fee data via `eth_feeHistory`, returning the latest
+    /// block's base fee and the priority fee at `reward_percentile` (e.g.
+    /// `50.0` for the median) over the most recent `block_count` blocks
+    pub async fn fee_history(&self, block_count: u64, reward_percentile: f64) -> Result<FeeHistory> {
+        let response_json = self
+            .send_rpc(&serde_json::json!({
+                "jsonrpc": "2.0",
+                "method": "eth_feeHistory",
+                "params": [format!("0x{:x}", block_count), "latest", [reward_percentile]],
+                "id": 1
+            }))
+            .await?;
+
+        let result = response_json
+            .get("result")
+            .ok_or_else(|| X402Error::network_error("No result in fee history response".to_string()))?;
+
+        let parse_hex_u128 = |v: &serde_json::Value| -> Option<u128> {
+            v.as_str()
+                .and_then(|s| u128::from_str_radix(s.trim_start_matches("0x"), 16).ok())
+        };
+
+        let base_fee_per_gas = result
+            .get("baseFeePerGas")
+            .and_then(|v| v.as_array())
+            .and_then(|arr| arr.last())
+            .and_then(parse_hex_u128)
+            .ok_or_else(|| X402Error::network_error("No baseFeePerGas in fee history response".to_string()))?;
+
+        let max_priority_fee_per_gas = result
+            .get("reward")
+            .and_then(|v| v.as_array())
+            .and_then(|arr| arr.last())
+            .and_then(|v| v.as_array())
+            .and_then(|arr| arr.first())
+            .and_then(parse_hex_u128)
+            .unwrap_or(1_000_000_000); // 1 gwei fallback when the node returns no reward data
+
+        Ok(FeeHistory {
+            base_fee_per_gas,
+            max_priority_fee_per_gas,
+        })
+    }
+
+    /// Broadcast a signed, RLP-encoded transaction via `eth_sendRawTransaction`
+    pub async fn send_raw_transaction(&self, signed_tx_hex: &str) -> Result<String> {
+        let response_json = self
+            .send_rpc(&serde_json::json!({
+                "jsonrpc": "2.0",
+                "method": "eth_sendRawTransaction",
+                "params": [signed_tx_hex],
+                "id": 1
+            }))
+            .await?;
+
+        // The node reached and evaluated this call — a "nonce too low" style
+        // rejection here is deterministic, not a transport blip, so it's reported as
+        // non-retryable rather than via `send_rpc`'s (retryable) `network_error`.
+        if let Some(error) = response_json.get("error") {
+            return Err(X402Error::rpc_rejected(format!(
+                "eth_sendRawTransaction rejected: {}",
+                error
+            )));
+        }
+
+        response_json
+            .get("result")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string())
+            .ok_or_else(|| X402Error::network_error("No transaction hash in response".to_string()))
+    }
+
     /// Get USDC contract address for current network
     pub fn get_usdc_contract_address(&self) -> Result<String> {
         match self.network.as_str() {
@@ -373,6 +1056,53 @@ impl BlockchainClient {
             ))),
         }
     }
+
+    /// [`Self::get_usdc_contract_address`], parsed into a typed [`Address`]
+    pub fn get_usdc_contract_address_typed(&self) -> Result<Address> {
+        parse_address(&self.get_usdc_contract_address()?)
+    }
+
+    /// Check whether `authorizer` has already consumed `nonce` on USDC's
+    /// EIP-3009 `authorizationState(address,bytes32)`, so a payment whose
+    /// signature and balance both check out can still be rejected if it's
+    /// replaying a nonce that was already settled or canceled
+    pub async fn is_usdc_nonce_used(&self, authorizer: &str, nonce: &str) -> Result<bool> {
+        let usdc_contract = self.get_usdc_contract_address()?;
+        let calldata = crate::erc20::authorization_state(parse_address(authorizer)?, nonce)?;
+        let raw = self.eth_call_u256(&usdc_contract, calldata).await?;
+        Ok(!raw.is_zero())
+    }
+}
+
+/// Parse a `0x`-prefixed address string, surfacing a malformed one as a protocol
+/// error rather than panicking
+fn parse_address(address: &str) -> Result<Address> {
+    Address::from_str(address).map_err(|_| X402Error::malformed_payload("address"))
+}
+
+/// Format a raw token amount (as returned by `balanceOf`) in human-readable decimal
+/// units, e.g. `raw = 1_500_000`, `decimals = 6` -> `"1.5"`
+fn format_token_amount(raw: U256, decimals: u64) -> String {
+    let base = U256::from(10u64).pow(U256::from(decimals));
+    let whole = raw / base;
+    let fraction = raw % base;
+
+    if fraction.is_zero() {
+        return whole.to_string();
+    }
+
+    let digits = fraction.to_string();
+    let fraction_str = format!("{}{}", "0".repeat(decimals as usize - digits.len()), digits);
+    format!("{}.{}", whole, fraction_str.trim_end_matches('0'))
+}
+
+/// EIP-1559 fee data derived from `eth_feeHistory`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct FeeHistory {
+    /// Most recent block's base fee, in wei
+    pub base_fee_per_gas: u128,
+    /// Suggested priority fee (tip), in wei
+    pub max_priority_fee_per_gas: u128,
 }
 
 /// Transaction request for gas estimation
@@ -384,45 +1114,80 @@ pub struct TransactionRequest {
     pub data: Option<String>,
     pub gas: Option<String>,
     pub gas_price: Option<String>,
+    /// EIP-1559 max total fee per gas (base fee + priority fee), hex-encoded; see
+    /// [`crate::gas_oracle::GasOracle`]
+    pub max_fee_per_gas: Option<String>,
+    /// EIP-1559 max priority fee (tip) per gas, hex-encoded; see
+    /// [`crate::gas_oracle::GasOracle`]
+    pub max_priority_fee_per_gas: Option<String>,
 }
 
 /// Blockchain client factory
 pub struct BlockchainClientFactory;
 
 impl BlockchainClientFactory {
-    /// Create client for Base Sepolia testnet
+    /// Create client for Base Sepolia testnet, seeded with known public fallback
+    /// endpoints so it transparently fails over instead of erroring on the first
+    /// dropped connection or rate limit
     pub fn base_sepolia() -> BlockchainClient {
         BlockchainClient::new(
             "https://sepolia.base.org".to_string(),
             "base-sepolia".to_string(),
         )
+        .with_fallback_endpoints(vec!["https://base-sepolia-rpc.publicnode.com".to_string()])
     }
 
-    /// Create client for Base mainnet
+    /// Create client for Base mainnet, seeded with known public fallback endpoints so
+    /// it transparently fails over instead of erroring on the first dropped
+    /// connection or rate limit
     pub fn base() -> BlockchainClient {
         BlockchainClient::new("https://mainnet.base.org".to_string(), "base".to_string())
+            .with_fallback_endpoints(vec![
+                "https://base.publicnode.com".to_string(),
+                "https://base.llamarpc.com".to_string(),
+            ])
     }
 
-    /// Create client for Avalanche Fuji testnet
+    /// Create client for Avalanche Fuji testnet, seeded with known public fallback
+    /// endpoints so it transparently fails over instead of erroring on the first
+    /// dropped connection or rate limit
     pub fn avalanche_fuji() -> BlockchainClient {
         BlockchainClient::new(
             "https://api.avax-test.network/ext/bc/C/rpc".to_string(),
             "avalanche-fuji".to_string(),
         )
+        .with_fallback_endpoints(vec!["https://avalanche-fuji-c-chain-rpc.publicnode.com".to_string()])
     }
 
-    /// Create client for Avalanche mainnet
+    /// Create client for Avalanche mainnet, seeded with known public fallback
+    /// endpoints so it transparently fails over instead of erroring on the first
+    /// dropped connection or rate limit
     pub fn avalanche() -> BlockchainClient {
         BlockchainClient::new(
             "https://api.avax.network/ext/bc/C/rpc".to_string(),
             "avalanche".to_string(),
         )
+        .with_fallback_endpoints(vec!["https://avalanche-c-chain-rpc.publicnode.com".to_string()])
     }
 
     /// Create client with custom RPC URL
     pub fn custom(rpc_url: &str, network: &str) -> BlockchainClient {
         BlockchainClient::new(rpc_url.to_string(), network.to_string())
     }
+
+    /// Create a client seeded with the default public endpoint(s) for `network`
+    /// (one of [`crate::types::networks`]'s EVM constants), or `None` if `network`
+    /// isn't one of them — use [`Self::custom`] to point at a different RPC URL.
+    pub fn for_network(network: &str) -> Option<BlockchainClient> {
+        use crate::types::networks;
+        match network {
+            networks::BASE_MAINNET => Some(Self::base()),
+            networks::BASE_SEPOLIA => Some(Self::base_sepolia()),
+            networks::AVALANCHE_MAINNET => Some(Self::avalanche()),
+            networks::AVALANCHE_FUJI => Some(Self::avalanche_fuji()),
+            _ => None,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -455,9 +1220,700 @@ mod tests {
             data: None,
             gas: None,
             gas_price: None,
+            max_fee_per_gas: None,
+            max_priority_fee_per_gas: None,
         };
 
         let json = serde_json::to_string(&tx).unwrap();
         assert!(json.contains("0x123"));
     }
+
+    #[tokio::test]
+    async fn test_get_receipt_decodes_logs_and_bloom() {
+        let mut server = mockito::Server::new_async().await;
+        let _m = server
+            .mock("POST", "/")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "result": {
+                        "transactionHash": "0xabc",
+                        "status": "0x1",
+                        "logsBloom": format!("0x{}", "00".repeat(256)),
+                        "logs": [
+                            {
+                                "address": "0x036CbD53842c5426634e7929541eC2318f3dCF7e",
+                                "topics": ["0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef"],
+                                "data": "0x00000000000000000000000000000000000000000000000000000000000f4240"
+                            }
+                        ]
+                    }
+                })
+                .to_string(),
+            )
+            .create();
+
+        let client = BlockchainClient::new(server.url(), "base-sepolia".to_string());
+        let receipt = client.get_receipt("0xabc").await.unwrap();
+
+        assert_eq!(receipt.transaction_hash, "0xabc");
+        assert_eq!(receipt.status, "0x1");
+        assert_eq!(receipt.logs.len(), 1);
+        assert_eq!(
+            receipt.logs[0].address,
+            "0x036CbD53842c5426634e7929541eC2318f3dCF7e"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fee_history_parses_base_fee_and_reward() {
+        let mut server = mockito::Server::new_async().await;
+        let _m = server
+            .mock("POST", "/")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "result": {
+                        "baseFeePerGas": ["0x3b9aca00", "0x42c1d80"],
+                        "reward": [["0x5f5e100"]]
+                    }
+                })
+                .to_string(),
+            )
+            .create();
+
+        let client = BlockchainClient::new(server.url(), "base-sepolia".to_string());
+        let fees = client.fee_history(1, 50.0).await.unwrap();
+
+        assert_eq!(fees.base_fee_per_gas, 0x42c1d80);
+        assert_eq!(fees.max_priority_fee_per_gas, 0x5f5e100);
+    }
+
+    #[tokio::test]
+    async fn test_send_raw_transaction_surfaces_rpc_error() {
+        let mut server = mockito::Server::new_async().await;
+        let _m = server
+            .mock("POST", "/")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "error": { "code": -32000, "message": "nonce too low" }
+                })
+                .to_string(),
+            )
+            .create();
+
+        let client = BlockchainClient::new(server.url(), "base-sepolia".to_string());
+        let err = client.send_raw_transaction("0x02f8...").await.unwrap_err();
+
+        assert!(err.to_string().contains("nonce too low"));
+    }
+
+    #[tokio::test]
+    async fn test_with_fallback_endpoints_rotates_past_a_failing_primary() {
+        let mut primary = mockito::Server::new_async().await;
+        let _m1 = primary.mock("POST", "/").with_status(503).create();
+
+        let mut fallback = mockito::Server::new_async().await;
+        let _m2 = fallback
+            .mock("POST", "/")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": "0x64"}).to_string())
+            .create();
+
+        let client = BlockchainClient::new(primary.url(), "base-sepolia".to_string())
+            .with_fallback_endpoints(vec![fallback.url()])
+            .with_retry_policy(RetryPolicy::new().with_base_delay(std::time::Duration::from_millis(1)));
+
+        let balance = client.get_balance("0xabc").await.unwrap();
+        assert_eq!(balance.balance, "0x64");
+    }
+
+    #[tokio::test]
+    async fn test_rpc_rejected_error_is_not_retried_against_a_fallback_endpoint() {
+        let mut primary = mockito::Server::new_async().await;
+        let _m1 = primary
+            .mock("POST", "/")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "error": { "code": -32000, "message": "nonce too low" }
+                })
+                .to_string(),
+            )
+            .expect(1)
+            .create();
+
+        let mut fallback = mockito::Server::new_async().await;
+        let _m2 = fallback.mock("POST", "/").expect(0).create();
+
+        let client = BlockchainClient::new(primary.url(), "base-sepolia".to_string())
+            .with_fallback_endpoints(vec![fallback.url()]);
+
+        let err = client.send_raw_transaction("0x02f8...").await.unwrap_err();
+        assert!(err.to_string().contains("nonce too low"));
+        assert!(!err.is_retryable());
+
+        _m1.assert();
+        _m2.assert();
+    }
+
+    #[tokio::test]
+    async fn test_watch_transaction_returns_once_confirmation_depth_is_reached() {
+        let mut server = mockito::Server::new_async().await;
+        let _m_status = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex("eth_getTransactionByHash".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!([
+                    {"jsonrpc": "2.0", "id": 0, "result": {"blockNumber": "0x64", "from": "0xfrom", "to": "0xto", "value": "0x0"}},
+                    {"jsonrpc": "2.0", "id": 1, "result": null}
+                ])
+                .to_string(),
+            )
+            .create();
+        let _m_network = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex("eth_chainId".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!([
+                    {"jsonrpc": "2.0", "id": 0, "result": "0x1"},
+                    {"jsonrpc": "2.0", "id": 1, "result": "0x64"},
+                    {"jsonrpc": "2.0", "id": 2, "result": "0x0"}
+                ])
+                .to_string(),
+            )
+            .create();
+        let _m_receipt = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex(r"^\{".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "result": {
+                        "transactionHash": "0xabc",
+                        "status": "0x1",
+                        "logsBloom": format!("0x{}", "00".repeat(256)),
+                        "logs": []
+                    }
+                })
+                .to_string(),
+            )
+            .create();
+
+        let client = BlockchainClient::new(server.url(), "base-sepolia".to_string());
+        let info = client
+            .watch_transaction("0xabc", 1, std::time::Duration::from_secs(5))
+            .await
+            .unwrap();
+
+        assert_eq!(info.block_number, Some(0x64));
+    }
+
+    #[tokio::test]
+    async fn test_watch_transaction_surfaces_a_distinct_error_for_a_reverted_receipt() {
+        let mut server = mockito::Server::new_async().await;
+        let _m_status = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex("eth_getTransactionByHash".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!([
+                    {"jsonrpc": "2.0", "id": 0, "result": {"blockNumber": "0x64", "from": "0xfrom", "to": "0xto", "value": "0x0"}},
+                    {"jsonrpc": "2.0", "id": 1, "result": null}
+                ])
+                .to_string(),
+            )
+            .create();
+        let _m_receipt = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex(r"^\{".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "result": {
+                        "transactionHash": "0xabc",
+                        "status": "0x0",
+                        "logsBloom": format!("0x{}", "00".repeat(256)),
+                        "logs": []
+                    }
+                })
+                .to_string(),
+            )
+            .create();
+
+        let client = BlockchainClient::new(server.url(), "base-sepolia".to_string());
+        let err = client
+            .watch_transaction("0xabc", 1, std::time::Duration::from_secs(5))
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, X402Error::TransactionReverted { .. }));
+        assert!(!err.is_retryable());
+    }
+
+    #[tokio::test]
+    async fn test_watch_transaction_times_out_while_still_pending() {
+        let mut server = mockito::Server::new_async().await;
+        let _m_status = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex(r"^\[".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!([
+                    {"jsonrpc": "2.0", "id": 0, "result": null},
+                    {"jsonrpc": "2.0", "id": 1, "result": null}
+                ])
+                .to_string(),
+            )
+            .create();
+
+        let client = BlockchainClient::new(server.url(), "base-sepolia".to_string());
+        let err = client
+            .watch_transaction("0xabc", 1, std::time::Duration::from_millis(0))
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, X402Error::Timeout));
+    }
+
+    #[tokio::test]
+    async fn test_get_token_balance_reports_human_readable_units() {
+        let mut server = mockito::Server::new_async().await;
+        // balanceOf(...) -> 1_500_000 (raw), decimals() -> 6 -> "1.5"
+        let _m_balance = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex("70a08231".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": format!("0x{:064x}", 1_500_000u64)})
+                    .to_string(),
+            )
+            .create();
+        let _m_decimals = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex("313ce567".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": "0x6"}).to_string())
+            .create();
+
+        let client = BlockchainClient::new(server.url(), "base-sepolia".to_string());
+        let balance = client
+            .get_token_balance(
+                "0x036CbD53842c5426634e7929541eC2318f3dCF7e",
+                "0x0000000000000000000000000000000000000001",
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(balance.token_balance, Some("1.5".to_string()));
+        assert_eq!(
+            balance.token_address,
+            Some("0x036CbD53842c5426634e7929541eC2318f3dCF7e".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_call_at_block_returns_the_raw_hex_result() {
+        let mut server = mockito::Server::new_async().await;
+        let _m = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex("0x5".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": "0xdeadbeef"}).to_string())
+            .create();
+
+        let client = BlockchainClient::new(server.url(), "base-sepolia".to_string());
+        let result = client
+            .call_at_block("0x036CbD53842c5426634e7929541eC2318f3dCF7e", "0xabcd", 5)
+            .await
+            .unwrap();
+
+        assert_eq!(result, "0xdeadbeef");
+    }
+
+    #[tokio::test]
+    async fn test_get_token_balance_rejects_a_malformed_owner_address() {
+        let client = BlockchainClient::new("https://example.com".to_string(), "base-sepolia".to_string());
+        let err = client
+            .get_token_balance("0x036CbD53842c5426634e7929541eC2318f3dCF7e", "not-an-address")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, X402Error::MalformedPayload { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_get_usdc_balance_encodes_calldata_through_the_typed_erc20_encoder() {
+        let mut server = mockito::Server::new_async().await;
+        let _m_balance = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex("70a08231".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": format!("0x{:064x}", 42u64)})
+                    .to_string(),
+            )
+            .create();
+
+        let client = BlockchainClient::new(server.url(), "base-sepolia".to_string());
+        let balance = client
+            .get_usdc_balance("0x0000000000000000000000000000000000000001")
+            .await
+            .unwrap();
+
+        assert_eq!(balance.token_balance, Some("0x2a".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_get_usdc_balance_rejects_a_malformed_address() {
+        let client = BlockchainClient::new("https://example.com".to_string(), "base-sepolia".to_string());
+        let err = client.get_usdc_balance("not-an-address").await.unwrap_err();
+        assert!(matches!(err, X402Error::MalformedPayload { .. }));
+    }
+
+    #[test]
+    fn test_get_usdc_contract_address_typed_parses_the_string_address() {
+        let client = BlockchainClient::new("https://example.com".to_string(), "base-sepolia".to_string());
+        let typed = client.get_usdc_contract_address_typed().unwrap();
+        assert_eq!(format!("{:#x}", typed), "0x036cbd53842c5426634e7929541ec2318f3dcf7e");
+    }
+
+    #[tokio::test]
+    async fn test_is_usdc_nonce_used_reports_true_for_a_nonzero_result() {
+        let mut server = mockito::Server::new_async().await;
+        let _m = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex("eth_call".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": format!("0x{:064x}", 1u64)})
+                    .to_string(),
+            )
+            .create();
+
+        let client = BlockchainClient::new(server.url(), "base-sepolia".to_string());
+        let used = client
+            .is_usdc_nonce_used(
+                "0x0000000000000000000000000000000000000001",
+                "0x0000000000000000000000000000000000000000000000000000000000000002",
+            )
+            .await
+            .unwrap();
+
+        assert!(used);
+    }
+
+    #[tokio::test]
+    async fn test_is_usdc_nonce_used_reports_false_for_a_zero_result() {
+        let mut server = mockito::Server::new_async().await;
+        let _m = server
+            .mock("POST", "/")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": format!("0x{:064x}", 0u64)})
+                    .to_string(),
+            )
+            .create();
+
+        let client = BlockchainClient::new(server.url(), "base-sepolia".to_string());
+        let used = client
+            .is_usdc_nonce_used(
+                "0x0000000000000000000000000000000000000001",
+                "0x0000000000000000000000000000000000000000000000000000000000000002",
+            )
+            .await
+            .unwrap();
+
+        assert!(!used);
+    }
+
+    #[tokio::test]
+    async fn test_get_revert_reason_decodes_a_require_message() {
+        let mut server = mockito::Server::new_async().await;
+        let _m_tx = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex("eth_getTransactionByHash".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "result": {"to": "0xusdc", "input": "0xdeadbeef"}
+                })
+                .to_string(),
+            )
+            .create();
+        let _m_call = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex("eth_call".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "error": {
+                        "code": 3,
+                        "message": "execution reverted",
+                        "data": "0x08c379a0\
+                            0000000000000000000000000000000000000000000000000000000000000020\
+                            0000000000000000000000000000000000000000000000000000000000000014\
+                            496e73756666696369656e742062616c616e6365000000000000000000000000"
+                    }
+                })
+                .to_string(),
+            )
+            .create();
+
+        let client = BlockchainClient::new(server.url(), "base-sepolia".to_string());
+        let reason = client.get_revert_reason("0xabc", 100).await.unwrap();
+        assert_eq!(reason.as_deref(), Some("Insufficient balance"));
+    }
+
+    #[tokio::test]
+    async fn test_get_revert_reason_returns_none_when_the_transaction_is_unknown() {
+        let mut server = mockito::Server::new_async().await;
+        let _m_tx = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex("eth_getTransactionByHash".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": null}).to_string())
+            .create();
+
+        let client = BlockchainClient::new(server.url(), "base-sepolia".to_string());
+        let reason = client.get_revert_reason("0xabc", 100).await.unwrap();
+        assert_eq!(reason, None);
+    }
+
+    #[tokio::test]
+    async fn test_get_token_allowance_returns_the_raw_smallest_unit_value() {
+        let mut server = mockito::Server::new_async().await;
+        let _m = server
+            .mock("POST", "/")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": format!("0x{:064x}", U256::MAX)})
+                    .to_string(),
+            )
+            .create();
+
+        let client = BlockchainClient::new(server.url(), "base-sepolia".to_string());
+        let allowance = client
+            .get_token_allowance(
+                "0x036CbD53842c5426634e7929541eC2318f3dCF7e",
+                "0x0000000000000000000000000000000000000001",
+                "0x0000000000000000000000000000000000000002",
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(allowance, U256::MAX);
+    }
+
+    #[tokio::test]
+    async fn test_get_pending_transaction_decodes_eip1559_fields() {
+        let mut server = mockito::Server::new_async().await;
+        let _m = server
+            .mock("POST", "/")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "result": {
+                        "nonce": "0x5",
+                        "maxFeePerGas": "0x3b9aca00",
+                        "maxPriorityFeePerGas": "0x59682f00",
+                        "gas": "0x5208",
+                        "to": "0x0000000000000000000000000000000000000001",
+                        "value": "0x0",
+                        "input": "0xe3ee160e"
+                    }
+                })
+                .to_string(),
+            )
+            .create();
+
+        let client = BlockchainClient::new(server.url(), "base-sepolia".to_string());
+        let tx = client.get_pending_transaction("0xabc").await.unwrap();
+
+        assert_eq!(tx.nonce, 5);
+        assert_eq!(tx.max_fee_per_gas, 1_000_000_000);
+        assert_eq!(tx.max_priority_fee_per_gas, 1_500_000_000);
+        assert_eq!(tx.data, "0xe3ee160e");
+    }
+
+    #[tokio::test]
+    async fn test_get_pending_transaction_errors_when_not_found() {
+        let mut server = mockito::Server::new_async().await;
+        let _m = server
+            .mock("POST", "/")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": null}).to_string())
+            .create();
+
+        let client = BlockchainClient::new(server.url(), "base-sepolia".to_string());
+        let err = client.get_pending_transaction("0xabc").await.unwrap_err();
+
+        assert!(err.to_string().contains("not found"));
+    }
+
+    #[test]
+    fn test_fee_bump_policy_applies_percentage_and_caps_priority_fee() {
+        let policy = FeeBumpPolicy::new().with_bump_percent(10.0);
+        let (max_fee, priority_fee) = policy.bump(1_000_000_000, 100_000_000);
+
+        assert_eq!(max_fee, 1_100_000_000);
+        assert_eq!(priority_fee, 110_000_000);
+    }
+
+    #[test]
+    fn test_fee_bump_policy_respects_the_max_fee_ceiling() {
+        let policy = FeeBumpPolicy::new()
+            .with_bump_percent(50.0)
+            .with_max_fee_per_gas_ceiling(1_200_000_000);
+        let (max_fee, priority_fee) = policy.bump(1_000_000_000, 1_000_000_000);
+
+        assert_eq!(max_fee, 1_200_000_000);
+        assert!(priority_fee <= max_fee);
+    }
+
+    #[tokio::test]
+    async fn test_check_confirmation_reports_depth_when_block_is_still_canonical() {
+        let mut server = mockito::Server::new_async().await;
+        let _m_status = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex(r"eth_getTransactionByHash".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!([
+                    {"jsonrpc": "2.0", "id": 0, "result": {"blockNumber": "0x64", "blockHash": "0xblock64"}},
+                    {"jsonrpc": "2.0", "id": 1, "result": null}
+                ])
+                .to_string(),
+            )
+            .create();
+        let _m_block = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex(r"eth_getBlockByNumber".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": {"hash": "0xblock64"}}).to_string())
+            .create();
+        let _m_network = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex(r"eth_chainId".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!([
+                    {"jsonrpc": "2.0", "id": 0, "result": "0x1"},
+                    {"jsonrpc": "2.0", "id": 1, "result": "0x66"},
+                    {"jsonrpc": "2.0", "id": 2, "result": "0x0"}
+                ])
+                .to_string(),
+            )
+            .create();
+
+        let client = BlockchainClient::new(server.url(), "base-sepolia".to_string());
+        let report = client
+            .check_confirmation("0xabc", Some("0xblock64"), 2)
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(report.confirmations, 3);
+        assert_eq!(report.block_hash, "0xblock64");
+        assert!(!report.reorged);
+        assert!(report.is_final());
+    }
+
+    #[tokio::test]
+    async fn test_check_confirmation_flags_a_reorg_when_the_observed_block_hash_changed() {
+        let mut server = mockito::Server::new_async().await;
+        let _m_status = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex(r"eth_getTransactionByHash".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!([
+                    {"jsonrpc": "2.0", "id": 0, "result": {"blockNumber": "0x64", "blockHash": "0xnewblock"}},
+                    {"jsonrpc": "2.0", "id": 1, "result": null}
+                ])
+                .to_string(),
+            )
+            .create();
+        let _m_block = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex(r"eth_getBlockByNumber".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": {"hash": "0xnewblock"}}).to_string())
+            .create();
+        let _m_network = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex(r"eth_chainId".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!([
+                    {"jsonrpc": "2.0", "id": 0, "result": "0x1"},
+                    {"jsonrpc": "2.0", "id": 1, "result": "0x64"},
+                    {"jsonrpc": "2.0", "id": 2, "result": "0x0"}
+                ])
+                .to_string(),
+            )
+            .create();
+
+        let client = BlockchainClient::new(server.url(), "base-sepolia".to_string());
+        // The caller previously observed this transaction mined into `0xoldblock` at
+        // height `0x64`; the chain now reports a different hash at that height, so
+        // `0xoldblock` was reorged out even though the transaction is still "mined".
+        let report = client
+            .check_confirmation("0xabc", Some("0xoldblock"), 1)
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert!(report.reorged);
+        assert!(!report.is_final());
+    }
 }
@@ -0,0 +1,212 @@
+//! Composable middleware over the full [`BlockchainClient`] RPC surface
+//!
+//! [`crate::facilitator_middleware::FacilitatorMiddleware`] only covers the four
+//! primitives the facilitator's settlement path calls. Everything else on
+//! [`BlockchainClient`] — status/receipt polling, balance and network queries — is
+//! still called directly, so there's nowhere to hang cross-cutting behavior (logging
+//! every RPC call an `OnchainSettlementVerifier` makes while confirming a settlement,
+//! for instance) without editing `BlockchainClient` itself. [`BlockchainMiddleware`]
+//! mirrors `BlockchainClient`'s entire public surface, the same way
+//! [`crate::facilitator_middleware::FacilitatorMiddleware`] mirrors its narrower
+//! settlement-only slice, again following ethers-rs's `Middleware` pattern of layers
+//! that wrap another layer and override only the calls they care about.
+//!
+//! [`BlockchainClient`] itself implements this trait directly as the terminal layer,
+//! so existing callers holding a concrete `BlockchainClient` are unaffected; a caller
+//! that wants a stack instead constructs one explicitly, e.g.
+//! `LoggingMiddleware::new(BlockchainClient::new(...))`.
+
+use crate::blockchain::{BalanceInfo, BlockchainClient, NetworkInfo, TransactionInfo, TransactionReceipt};
+use crate::Result;
+
+/// A layer over [`BlockchainClient`]'s full RPC surface
+///
+/// Every method mirrors a [`BlockchainClient`] call of the same name. A layer that
+/// doesn't need to intervene on a given call just delegates to the layer it wraps.
+pub trait BlockchainMiddleware: Send + Sync {
+    /// Get transaction status by hash
+    fn get_transaction_status<'a>(
+        &'a self,
+        tx_hash: &'a str,
+    ) -> crate::facilitator::BoxFuture<'a, Result<TransactionInfo>>;
+
+    /// Fetch the full transaction receipt, including logs and bloom filter
+    fn get_receipt<'a>(&'a self, tx_hash: &'a str) -> crate::facilitator::BoxFuture<'a, Result<TransactionReceipt>>;
+
+    /// Get the native balance of `address`
+    fn get_balance<'a>(&'a self, address: &'a str) -> crate::facilitator::BoxFuture<'a, Result<BalanceInfo>>;
+
+    /// Get `address`'s USDC balance alongside its native balance
+    fn get_usdc_balance<'a>(&'a self, address: &'a str) -> crate::facilitator::BoxFuture<'a, Result<BalanceInfo>>;
+
+    /// Fetch current chain ID, latest block, and gas price
+    fn get_network_info(&self) -> crate::facilitator::BoxFuture<'_, Result<NetworkInfo>>;
+}
+
+impl BlockchainMiddleware for BlockchainClient {
+    fn get_transaction_status<'a>(
+        &'a self,
+        tx_hash: &'a str,
+    ) -> crate::facilitator::BoxFuture<'a, Result<TransactionInfo>> {
+        Box::pin(self.get_transaction_status(tx_hash))
+    }
+
+    fn get_receipt<'a>(&'a self, tx_hash: &'a str) -> crate::facilitator::BoxFuture<'a, Result<TransactionReceipt>> {
+        Box::pin(self.get_receipt(tx_hash))
+    }
+
+    fn get_balance<'a>(&'a self, address: &'a str) -> crate::facilitator::BoxFuture<'a, Result<BalanceInfo>> {
+        Box::pin(self.get_balance(address))
+    }
+
+    fn get_usdc_balance<'a>(&'a self, address: &'a str) -> crate::facilitator::BoxFuture<'a, Result<BalanceInfo>> {
+        Box::pin(self.get_usdc_balance(address))
+    }
+
+    fn get_network_info(&self) -> crate::facilitator::BoxFuture<'_, Result<NetworkInfo>> {
+        Box::pin(self.get_network_info())
+    }
+}
+
+impl<T: BlockchainMiddleware + ?Sized> BlockchainMiddleware for std::sync::Arc<T> {
+    fn get_transaction_status<'a>(
+        &'a self,
+        tx_hash: &'a str,
+    ) -> crate::facilitator::BoxFuture<'a, Result<TransactionInfo>> {
+        (**self).get_transaction_status(tx_hash)
+    }
+
+    fn get_receipt<'a>(&'a self, tx_hash: &'a str) -> crate::facilitator::BoxFuture<'a, Result<TransactionReceipt>> {
+        (**self).get_receipt(tx_hash)
+    }
+
+    fn get_balance<'a>(&'a self, address: &'a str) -> crate::facilitator::BoxFuture<'a, Result<BalanceInfo>> {
+        (**self).get_balance(address)
+    }
+
+    fn get_usdc_balance<'a>(&'a self, address: &'a str) -> crate::facilitator::BoxFuture<'a, Result<BalanceInfo>> {
+        (**self).get_usdc_balance(address)
+    }
+
+    fn get_network_info(&self) -> crate::facilitator::BoxFuture<'_, Result<NetworkInfo>> {
+        (**self).get_network_info()
+    }
+}
+
+/// Logs every call made through the wrapped layer at `tracing::info!`/`warn!`,
+/// without altering behavior
+pub struct LoggingMiddleware<M> {
+    inner: M,
+}
+
+impl<M: BlockchainMiddleware> LoggingMiddleware<M> {
+    /// Wrap `inner`, logging every RPC call made through it
+    pub fn new(inner: M) -> Self {
+        Self { inner }
+    }
+}
+
+impl<M: BlockchainMiddleware> BlockchainMiddleware for LoggingMiddleware<M> {
+    fn get_transaction_status<'a>(
+        &'a self,
+        tx_hash: &'a str,
+    ) -> crate::facilitator::BoxFuture<'a, Result<TransactionInfo>> {
+        Box::pin(async move {
+            let result = self.inner.get_transaction_status(tx_hash).await;
+            if let Err(ref error) = result {
+                tracing::warn!("get_transaction_status({}) failed: {}", tx_hash, error);
+            }
+            result
+        })
+    }
+
+    fn get_receipt<'a>(&'a self, tx_hash: &'a str) -> crate::facilitator::BoxFuture<'a, Result<TransactionReceipt>> {
+        Box::pin(async move {
+            let result = self.inner.get_receipt(tx_hash).await;
+            if let Err(ref error) = result {
+                tracing::warn!("get_receipt({}) failed: {}", tx_hash, error);
+            }
+            result
+        })
+    }
+
+    fn get_balance<'a>(&'a self, address: &'a str) -> crate::facilitator::BoxFuture<'a, Result<BalanceInfo>> {
+        Box::pin(async move {
+            let result = self.inner.get_balance(address).await;
+            if let Err(ref error) = result {
+                tracing::warn!("get_balance({}) failed: {}", address, error);
+            }
+            result
+        })
+    }
+
+    fn get_usdc_balance<'a>(&'a self, address: &'a str) -> crate::facilitator::BoxFuture<'a, Result<BalanceInfo>> {
+        Box::pin(async move {
+            let result = self.inner.get_usdc_balance(address).await;
+            if let Err(ref error) = result {
+                tracing::warn!("get_usdc_balance({}) failed: {}", address, error);
+            }
+            result
+        })
+    }
+
+    fn get_network_info(&self) -> crate::facilitator::BoxFuture<'_, Result<NetworkInfo>> {
+        Box::pin(async move {
+            let result = self.inner.get_network_info().await;
+            if let Err(ref error) = result {
+                tracing::warn!("get_network_info() failed: {}", error);
+            }
+            result
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_blockchain_client_implements_middleware_directly() {
+        let mut server = mockito::Server::new_async().await;
+        let _m = server
+            .mock("POST", "/")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!([
+                    {"jsonrpc": "2.0", "id": 0, "result": "0x1"},
+                    {"jsonrpc": "2.0", "id": 1, "result": "0x64"},
+                    {"jsonrpc": "2.0", "id": 2, "result": "0x0"}
+                ])
+                .to_string(),
+            )
+            .create();
+
+        let client = BlockchainClient::new(server.url(), "base-sepolia".to_string());
+        let info = BlockchainMiddleware::get_network_info(&client).await.unwrap();
+        assert_eq!(info.latest_block, 0x64);
+    }
+
+    #[tokio::test]
+    async fn test_logging_middleware_passes_through_results_unchanged() {
+        let mut server = mockito::Server::new_async().await;
+        let _m = server
+            .mock("POST", "/")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!([
+                    {"jsonrpc": "2.0", "id": 0, "result": "0x1"},
+                    {"jsonrpc": "2.0", "id": 1, "result": "0x64"},
+                    {"jsonrpc": "2.0", "id": 2, "result": "0x0"}
+                ])
+                .to_string(),
+            )
+            .create();
+
+        let client = BlockchainClient::new(server.url(), "base-sepolia".to_string());
+        let logging = LoggingMiddleware::new(client);
+        let info = logging.get_network_info().await.unwrap();
+        assert_eq!(info.latest_block, 0x64);
+    }
+}
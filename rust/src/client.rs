@@ -1,11 +1,19 @@
 //! HTTP client with x402 payment support
 
+use crate::idempotency::PaymentId;
 use crate::types::*;
+use crate::wallet::Wallet;
 use crate::{Result, X402Error};
 use reqwest::{Client, Response};
 use axum::http;
+#[cfg(feature = "cookies")]
+use reqwest::cookie::CookieStore;
 use std::time::Duration;
 
+/// Default number of signed submissions [`X402Client::pay_resource`] will attempt
+/// before abandoning the payment
+pub const DEFAULT_MAX_PAYMENT_ATTEMPTS: u32 = 3;
+
 /// HTTP client with x402 payment support
 #[derive(Debug, Clone)]
 pub struct X402Client {
@@ -13,6 +21,14 @@ pub struct X402Client {
     client: Client,
     /// Default facilitator configuration
     facilitator_config: FacilitatorConfig,
+    /// Retry policy applied by [`Self::send_with_retry`] (used by [`Self::pay_resource`]);
+    /// `None` sends each request exactly once
+    retry_policy: Option<crate::retry::RetryPolicy>,
+    /// Shared cookie store enabled via [`Self::with_cookie_store`]; kept alongside
+    /// `client` (which also holds a reference via `cookie_provider`) so
+    /// [`Self::cookies_for`]/[`Self::seed_cookie`] can read and write it directly
+    #[cfg(feature = "cookies")]
+    cookie_jar: Option<std::sync::Arc<reqwest::cookie::Jar>>,
 }
 
 impl X402Client {
@@ -31,9 +47,108 @@ impl X402Client {
         Ok(Self {
             client,
             facilitator_config,
+            retry_policy: None,
+            #[cfg(feature = "cookies")]
+            cookie_jar: None,
         })
     }
 
+    /// Retry flaky resource fetches (connection errors/timeouts, and 429/502/503/504
+    /// responses) per `retry_policy` instead of surfacing the first failure
+    pub fn with_retry_policy(mut self, retry_policy: crate::retry::RetryPolicy) -> Self {
+        self.retry_policy = Some(retry_policy);
+        self
+    }
+
+    /// Rebuild the underlying HTTP client with transparent response decompression
+    /// (gzip, brotli, deflate) enabled or disabled. When `enabled`, the client
+    /// advertises `Accept-Encoding: gzip, br, deflate` and `reqwest` decodes a
+    /// matching `Content-Encoding` response body before [`Self::send`] (and
+    /// therefore [`X402RequestBuilder::send_and_get_text`]/
+    /// [`X402RequestBuilder::send_and_get_json`]) ever sees it. Off by default —
+    /// paid API responses (discovery listings, large JSON payloads) are often
+    /// worth the bandwidth savings for an agent polling many resources, but a
+    /// caller that verifies a response against its raw wire bytes shouldn't have
+    /// them silently decoded out from under it. Requires the `compression`
+    /// feature, which pulls in `reqwest`'s `gzip`/`brotli`/`deflate` decoders
+    #[cfg(feature = "compression")]
+    pub fn with_compression(mut self, enabled: bool) -> Result<Self> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(30))
+            .gzip(enabled)
+            .brotli(enabled)
+            .deflate(enabled)
+            .build()
+            .map_err(|e| X402Error::config(format!("Failed to create HTTP client: {}", e)))?;
+        self.client = client;
+        Ok(self)
+    }
+
+    /// Rebuild the underlying HTTP client with a custom TLS configuration: a
+    /// private root CA, a client identity for mTLS, or (for local development
+    /// against a self-signed facilitator) disabled certificate verification.
+    /// Reuses [`crate::proxy::TlsConfig`], the same configuration the proxy
+    /// applies to its upstream connections, so both entry points load
+    /// certificates and identities the same way
+    pub fn with_tls_config(mut self, tls: &crate::proxy::TlsConfig) -> Result<Self> {
+        let builder = tls.apply_to(Client::builder().timeout(Duration::from_secs(30)))?;
+        self.client = builder
+            .build()
+            .map_err(|e| X402Error::config(format!("Failed to create HTTP client: {}", e)))?;
+        Ok(self)
+    }
+
+    /// Rebuild the underlying HTTP client with a shared cookie store so
+    /// `Set-Cookie` headers returned on the initial 402 are sent back on the paid
+    /// retry (and any later request through this client), instead of being
+    /// dropped because each retry in [`Self::handle_payment_required`] builds a
+    /// brand-new request. Needed for stateful paid endpoints that bind a payment
+    /// to a session. Use [`Self::seed_cookie`] to carry a session established out
+    /// of band, and [`Self::cookies_for`] to inspect what's currently stored.
+    /// Requires the `cookies` feature
+    #[cfg(feature = "cookies")]
+    pub fn with_cookie_store(mut self) -> Result<Self> {
+        let jar = std::sync::Arc::new(reqwest::cookie::Jar::default());
+        let client = Client::builder()
+            .timeout(Duration::from_secs(30))
+            .cookie_provider(jar.clone())
+            .build()
+            .map_err(|e| X402Error::config(format!("Failed to create HTTP client: {}", e)))?;
+        self.client = client;
+        self.cookie_jar = Some(jar);
+        Ok(self)
+    }
+
+    /// `Cookie` header value `reqwest` would currently send for `url`, if
+    /// [`Self::with_cookie_store`] is enabled and the jar holds any cookies for
+    /// it. Requires the `cookies` feature
+    #[cfg(feature = "cookies")]
+    pub fn cookies_for(&self, url: &str) -> Result<Option<String>> {
+        let jar = self
+            .cookie_jar
+            .as_ref()
+            .ok_or_else(|| X402Error::config("cookie store not enabled; call with_cookie_store() first"))?;
+        let parsed = reqwest::Url::parse(url).map_err(|e| X402Error::config(format!("Invalid URL: {}", e)))?;
+        Ok(jar
+            .cookies(&parsed)
+            .and_then(|value| value.to_str().ok().map(str::to_string)))
+    }
+
+    /// Pre-seed a `Set-Cookie`-formatted `cookie` into this client's cookie store
+    /// for `url`, before making any request against it — useful for carrying a
+    /// session established out of band into the client. Requires the `cookies`
+    /// feature and [`Self::with_cookie_store`] to have been called first
+    #[cfg(feature = "cookies")]
+    pub fn seed_cookie(&self, url: &str, cookie: &str) -> Result<()> {
+        let jar = self
+            .cookie_jar
+            .as_ref()
+            .ok_or_else(|| X402Error::config("cookie store not enabled; call with_cookie_store() first"))?;
+        let parsed = reqwest::Url::parse(url).map_err(|e| X402Error::config(format!("Invalid URL: {}", e)))?;
+        jar.add_cookie_str(cookie, &parsed);
+        Ok(())
+    }
+
     /// Create a GET request
     pub fn get(&self, url: &str) -> X402RequestBuilder<'_> {
         let mut builder = X402RequestBuilder::new(self, self.client.get(url));
@@ -67,34 +182,47 @@ impl X402Client {
     }
 
     /// Handle a 402 payment required response with automatic retry
+    ///
+    /// `retry_request` must be a clone (via [`reqwest::RequestBuilder::try_clone`]) of
+    /// the exact request that produced `response`, captured before it was sent — so
+    /// the retry replays the original method, headers, and body with only an
+    /// `X-PAYMENT` header added, instead of silently downgrading to a bare `GET`
+    /// against the response's URL. The paid resend itself honors
+    /// [`Self::with_retry_policy`], the same as [`Self::send_with_retry`], so a
+    /// transient failure on the resource server after an already-verified payment
+    /// doesn't need to be resolved by the caller.
     pub async fn handle_payment_required(
         &self,
         response: Response,
         payment_payload: &PaymentPayload,
+        retry_request: reqwest::RequestBuilder,
     ) -> Result<Response> {
         if response.status() != 402 {
             return Ok(response);
         }
 
-        let original_url = response.url().to_string();
         let payment_requirements: PaymentRequirementsResponse = response.json().await?;
-        
+
         // Verify the payment with the facilitator
         let facilitator = super::facilitator::FacilitatorClient::new(self.facilitator_config.clone())
             .map_err(|e| X402Error::facilitator_error(format!("Failed to create facilitator client: {}", e)))?;
-        
+
         for requirements in &payment_requirements.accepts {
             let verify_response = facilitator.verify(payment_payload, requirements).await?;
-            
+
             if verify_response.is_valid {
-                // Retry the original request with payment
                 let payment_header = payment_payload.to_base64()?;
-                
-                // Create a new request with payment header
-                let new_response = self.client
-                    .get(&original_url)
-                    .header("X-PAYMENT", payment_header)
-                    .send()
+                // Honor `self.retry_policy` on the paid resend too, so a transient
+                // blip on the resource server doesn't waste an already-verified
+                // payment
+                let new_response = self
+                    .send_with_retry(|| {
+                        let builder = retry_request.try_clone().expect(
+                            "retry_request was already confirmed clonable by the initial try_clone snapshot",
+                        );
+                        X402RequestBuilder::new(self, builder)
+                            .header("X-PAYMENT", payment_header.clone())
+                    })
                     .await?;
 
                 return Ok(new_response);
@@ -127,12 +255,22 @@ impl X402Client {
             request_builder = request_builder.header("X-PAYMENT", payment_header);
         }
 
+        // Snapshot a clone of the exact request about to be sent, so a 402 retry can
+        // replay this same method/headers/body instead of refetching `url` as a GET
+        let retry_request = request_builder.request.try_clone().ok_or_else(|| {
+            X402Error::unexpected(
+                "request body cannot be replayed for a 402 retry (streaming body)",
+            )
+        })?;
+
         let response = request_builder.send().await?;
 
         // If we get a 402 and have a payment payload, try to handle it
         if response.status() == 402 {
             if let Some(payload) = payment_payload {
-                return self.handle_payment_required(response, payload).await;
+                return self
+                    .handle_payment_required(response, payload, retry_request)
+                    .await;
             } else {
                 // Return the 402 response as-is if no payment payload provided
                 return Ok(response);
@@ -152,6 +290,226 @@ impl X402Client {
         self.facilitator_config = config;
         self
     }
+
+    /// Send a request built fresh by `build_request` on every attempt, retrying per
+    /// [`Self::with_retry_policy`] when the send itself fails with a retryable
+    /// [`X402Error`] or the response comes back with a retryable status (429, 502,
+    /// 503, 504). Honors a `Retry-After` response header over the policy's computed
+    /// backoff, and surfaces the final failure as [`X402Error::RetriesExhausted`]
+    /// with the attempt count attached. With no retry policy configured, sends
+    /// exactly once and returns whatever that attempt produced.
+    pub async fn send_with_retry<'a, F>(&'a self, build_request: F) -> Result<Response>
+    where
+        F: Fn() -> X402RequestBuilder<'a>,
+    {
+        let Some(policy) = &self.retry_policy else {
+            return build_request().send().await;
+        };
+
+        let mut attempt = 0;
+        loop {
+            match build_request().send().await {
+                Ok(response) if crate::retry::is_retryable_status(response.status().as_u16()) => {
+                    if attempt + 1 >= policy.max_attempts {
+                        let status = response.status();
+                        return Err(X402Error::retries_exhausted(
+                            attempt + 1,
+                            X402Error::facilitator_error(format!("Request failed with status: {}", status)),
+                        ));
+                    }
+                    let delay = crate::retry::retry_after_header(&response)
+                        .unwrap_or_else(|| policy.delay_for_attempt(attempt));
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                Ok(response) => return Ok(response),
+                Err(error) if attempt + 1 < policy.max_attempts && error.is_retryable() => {
+                    let delay = error
+                        .retry_after()
+                        .unwrap_or_else(|| policy.delay_for_attempt(attempt));
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(error) => return Err(X402Error::retries_exhausted(attempt + 1, error)),
+            }
+        }
+    }
+
+    /// Fetch `url`, automatically signing and resubmitting against its 402 response
+    /// until the resource is returned, every accepted requirement is exhausted, or
+    /// the session's attempt budget runs out
+    ///
+    /// Drives a [`PaymentSession`] end-to-end so callers get automatic 402-handling
+    /// instead of hand-rolling the fetch/sign/resubmit dance themselves.
+    pub async fn pay_resource(
+        &self,
+        url: &str,
+        wallet: &Wallet,
+        from_address: &str,
+    ) -> Result<Response> {
+        let response = self.send_with_retry(|| self.get(url)).await?;
+        if response.status() != 402 {
+            return Ok(response);
+        }
+
+        let mut requirements: PaymentRequirementsResponse = response.json().await?;
+        let mut session =
+            PaymentSession::new(wallet.clone(), from_address, DEFAULT_MAX_PAYMENT_ATTEMPTS);
+
+        loop {
+            let payload = session.retry(&requirements)?;
+            let payment_header = payload.to_base64()?;
+
+            let retried = self
+                .send_with_retry(|| self.get(url).header("X-PAYMENT", payment_header.clone()))
+                .await?;
+
+            if retried.status() != 402 {
+                session.mark_fulfilled();
+                return Ok(retried);
+            }
+
+            requirements = retried.json().await?;
+        }
+    }
+}
+
+/// Terminal reason a [`PaymentSession`] was abandoned, surfaced to the caller instead
+/// of a bare error so they can distinguish "give it another shot later" from "the
+/// server's requirements moved out from under us"
+#[derive(Debug, Clone)]
+pub enum PaymentFailureReason {
+    /// The session's attempt budget was spent without a non-402 response
+    RetriesExhausted,
+    /// The facilitator rejected every requirement the server listed as accepted
+    VerificationRejected(String),
+    /// The 402 response's `accepts` changed to requirements incompatible with the
+    /// scheme/network this session already committed to on an earlier attempt
+    RequirementsChangedIncompatibly,
+}
+
+/// Lifecycle state of a [`PaymentSession`]
+#[derive(Debug, Clone)]
+pub enum PaymentSessionState {
+    /// Another [`PaymentSession::retry`] call is allowed
+    Retryable,
+    /// A non-402 response was received for a signed submission
+    Fulfilled,
+    /// No further attempts will be made
+    Abandoned(PaymentFailureReason),
+}
+
+/// Tracks a single outbound x402 payment across the full request/sign/resubmit loop
+///
+/// Modeled on rust-lightning's `PendingOutboundPayment` state machine: a session
+/// starts [`PaymentSessionState::Retryable`], [`Self::retry`] re-signs a fresh
+/// authorization (new nonce, new validity window) against the server's current
+/// requirements each time it's called, and the session becomes terminal —
+/// `Fulfilled` or `Abandoned` with a [`PaymentFailureReason`] — once the loop can't
+/// continue.
+#[derive(Debug)]
+pub struct PaymentSession {
+    id: Option<PaymentId>,
+    wallet: Wallet,
+    from_address: String,
+    attempt: u32,
+    max_attempts: u32,
+    /// `(scheme, network)` this session committed to on its first signed attempt
+    accepted_key: Option<(String, String)>,
+    state: PaymentSessionState,
+}
+
+impl PaymentSession {
+    /// Start tracking a payment signed by `wallet` on behalf of `from_address`,
+    /// allowing up to `max_attempts` signed submissions before giving up
+    pub fn new(wallet: Wallet, from_address: impl Into<String>, max_attempts: u32) -> Self {
+        Self {
+            id: None,
+            wallet,
+            from_address: from_address.into(),
+            attempt: 0,
+            max_attempts,
+            accepted_key: None,
+            state: PaymentSessionState::Retryable,
+        }
+    }
+
+    /// Current lifecycle state
+    pub fn state(&self) -> &PaymentSessionState {
+        &self.state
+    }
+
+    /// Number of signed payloads submitted so far
+    pub fn attempt(&self) -> u32 {
+        self.attempt
+    }
+
+    /// Stable identifier for the in-flight payment, assigned once the first
+    /// authorization is signed
+    pub fn id(&self) -> Option<PaymentId> {
+        self.id
+    }
+
+    /// Sign a fresh authorization (new nonce, new validity window) against
+    /// `requirements` and advance the attempt counter
+    ///
+    /// On the first call, any requirement the server accepts may be chosen; later
+    /// calls insist on the same `(scheme, network)` pair already signed for, rather
+    /// than silently re-targeting a different asset or chain. Transitions the
+    /// session to [`PaymentSessionState::Abandoned`] and returns an error once the
+    /// attempt budget is spent or the server's `accepts` no longer contains a
+    /// compatible requirement.
+    pub fn retry(&mut self, requirements: &PaymentRequirementsResponse) -> Result<PaymentPayload> {
+        if !matches!(self.state, PaymentSessionState::Retryable) {
+            return Err(X402Error::payment_verification_failed(
+                "Payment session is no longer retryable",
+            ));
+        }
+
+        if self.attempt >= self.max_attempts {
+            self.state = PaymentSessionState::Abandoned(PaymentFailureReason::RetriesExhausted);
+            return Err(X402Error::payment_verification_failed(
+                "Payment retries exhausted",
+            ));
+        }
+
+        let chosen = match &self.accepted_key {
+            None => requirements.accepts.first(),
+            Some((scheme, network)) => requirements
+                .accepts
+                .iter()
+                .find(|r| &r.scheme == scheme && &r.network == network),
+        };
+
+        let Some(requirement) = chosen else {
+            self.state = PaymentSessionState::Abandoned(
+                PaymentFailureReason::RequirementsChangedIncompatibly,
+            );
+            return Err(X402Error::payment_verification_failed(
+                "Server's accepted payment requirements changed incompatibly",
+            ));
+        };
+
+        let payload = self
+            .wallet
+            .create_signed_payment_payload(requirement, &self.from_address)?;
+
+        self.id = Some(PaymentId::from_authorization(&payload.exact_evm()?.authorization, requirement));
+        self.accepted_key = Some((requirement.scheme.clone(), requirement.network.clone()));
+        self.attempt += 1;
+        Ok(payload)
+    }
+
+    /// Mark the session as having received a non-402 response for a signed submission
+    pub fn mark_fulfilled(&mut self) {
+        self.state = PaymentSessionState::Fulfilled;
+    }
+
+    /// Abandon the session with an explicit reason, e.g. the facilitator rejected
+    /// every requirement rather than the attempt budget running out
+    pub fn abandon(&mut self, reason: PaymentFailureReason) {
+        self.state = PaymentSessionState::Abandoned(reason);
+    }
 }
 
 impl Default for X402Client {
@@ -161,6 +519,9 @@ impl Default for X402Client {
             Self {
                 client: Client::new(),
                 facilitator_config: FacilitatorConfig::default(),
+                retry_policy: None,
+                #[cfg(feature = "cookies")]
+                cookie_jar: None,
             }
         })
     }
@@ -173,19 +534,15 @@ pub struct X402RequestBuilder<'a> {
     request: reqwest::RequestBuilder,
     method: String,
     url: String,
-    _headers: std::collections::HashMap<String, String>,
-    _body: Option<Vec<u8>>,
 }
 
 impl<'a> X402RequestBuilder<'a> {
     fn new(client: &'a X402Client, request: reqwest::RequestBuilder) -> Self {
-        Self { 
-            client, 
+        Self {
+            client,
             request,
             method: String::new(),
             url: String::new(),
-            _headers: std::collections::HashMap::new(),
-            _body: None,
         }
     }
 
@@ -259,35 +616,40 @@ impl<'a> X402RequestBuilder<'a> {
 
     /// Send the request
     pub async fn send(self) -> Result<Response> {
-        self.request
-            .send()
-            .await
-            .map_err(X402Error::from)
+        let url = self.url.clone();
+        let start = std::time::Instant::now();
+        self.request.send().await.map_err(|error| {
+            if error.is_timeout() {
+                X402Error::request_timed_out(url, start.elapsed())
+            } else {
+                X402Error::from(error)
+            }
+        })
     }
 
     /// Send the request and handle x402 payments automatically
+    ///
+    /// On a 402 response, verifies `payment_payload` against the facilitator the
+    /// same way [`X402Client::handle_payment_required`] does, then retries this
+    /// exact request (same method, headers, and body) with an added `X-PAYMENT`
+    /// header — rather than discarding it and refetching the URL as a bare `GET`.
     pub async fn send_with_payment(self, payment_payload: &PaymentPayload) -> Result<Response> {
-        // Save values before consuming self
-        let original_url = self.url.clone();
-        let client = self.client.clone();
-        
+        let client = self.client;
+
+        // Snapshot a clone of the exact request about to be sent, so a 402 retry can
+        // replay this same method/headers/body instead of refetching `self.url` as a GET
+        let retry_request = self.request.try_clone().ok_or_else(|| {
+            X402Error::unexpected(
+                "request body cannot be replayed for a 402 retry (streaming body)",
+            )
+        })?;
+
         let response = self.send().await?;
-        
+
         if response.status() == 402 {
-            // Parse payment requirements from 402 response
-            let _payment_requirements: PaymentRequirementsResponse = response.json().await?;
-            
-            // Create a new request with payment header
-            let payment_header = payment_payload.to_base64()?;
-            
-            // Create a new request with payment header
-            let new_response = client.client
-                .get(&original_url)
-                .header("X-PAYMENT", &payment_header)
-                .send()
-                .await?;
-            
-            Ok(new_response)
+            client
+                .handle_payment_required(response, payment_payload, retry_request)
+                .await
         } else {
             Ok(response)
         }
@@ -316,6 +678,9 @@ pub struct DiscoveryClient {
     url: String,
     /// HTTP client
     client: Client,
+    /// Retries transient failures (connection/timeout errors, 429, 5xx) when set; see
+    /// [`Self::with_retry_policy`]
+    retry_policy: Option<crate::retry::RetryPolicy>,
 }
 
 impl DiscoveryClient {
@@ -325,6 +690,7 @@ impl DiscoveryClient {
         Self {
             url: url.into(),
             client,
+            retry_policy: None,
         }
     }
 
@@ -333,26 +699,37 @@ impl DiscoveryClient {
         Self::new("https://x402.org/discovery")
     }
 
+    /// Retry transient discovery-service failures (connection/timeout errors, 429,
+    /// 5xx) per `retry_policy` instead of surfacing the first failure. A discovery
+    /// GET has no side effects, so — unlike [`crate::facilitator::FacilitatorClient::settle`]
+    /// — there's no idempotency concern gating this on; it's safe to enable by default.
+    pub fn with_retry_policy(mut self, retry_policy: crate::retry::RetryPolicy) -> Self {
+        self.retry_policy = Some(retry_policy);
+        self
+    }
+
     /// Discover resources with optional filters
     pub async fn discover_resources(
         &self,
         filters: Option<DiscoveryFilters>,
     ) -> Result<DiscoveryResponse> {
-        let mut request = self.client.get(&format!("{}/resources", self.url));
-
-        if let Some(filters) = filters {
-            if let Some(resource_type) = filters.resource_type {
-                request = request.query(&[("type", resource_type)]);
-            }
-            if let Some(limit) = filters.limit {
-                request = request.query(&[("limit", limit.to_string())]);
+        let build_request = || {
+            let mut request = self.client.get(&format!("{}/resources", self.url));
+            if let Some(filters) = &filters {
+                if let Some(resource_type) = &filters.resource_type {
+                    request = request.query(&[("type", resource_type)]);
+                }
+                if let Some(limit) = filters.limit {
+                    request = request.query(&[("limit", limit.to_string())]);
+                }
+                if let Some(offset) = filters.offset {
+                    request = request.query(&[("offset", offset.to_string())]);
+                }
             }
-            if let Some(offset) = filters.offset {
-                request = request.query(&[("offset", offset.to_string())]);
-            }
-        }
+            request
+        };
 
-        let response = request.send().await?;
+        let response = self.send_with_retry(build_request).await?;
 
         if !response.status().is_success() {
             return Err(X402Error::facilitator_error(format!(
@@ -365,6 +742,56 @@ impl DiscoveryClient {
         Ok(discovery_response)
     }
 
+    /// Send a request built fresh by `build_request` on every attempt, retrying per
+    /// [`Self::with_retry_policy`] exactly like [`X402Client::send_with_retry`] — a
+    /// connection/timeout error or a retryable status (429, 502, 503, 504) backs off
+    /// and retries, honoring a `Retry-After` response header over the policy's
+    /// computed delay; anything else (including any 4xx) is returned immediately.
+    /// With no retry policy configured, sends exactly once.
+    async fn send_with_retry<F>(&self, build_request: F) -> Result<reqwest::Response>
+    where
+        F: Fn() -> reqwest::RequestBuilder,
+    {
+        let Some(policy) = &self.retry_policy else {
+            return Ok(build_request().send().await?);
+        };
+
+        let mut attempt = 0;
+        loop {
+            match build_request().send().await {
+                Ok(response) if crate::retry::is_retryable_status(response.status().as_u16()) => {
+                    if attempt + 1 >= policy.max_attempts {
+                        let status = response.status();
+                        return Err(X402Error::retries_exhausted(
+                            attempt + 1,
+                            X402Error::facilitator_error(format!(
+                                "Discovery request failed with status: {}",
+                                status
+                            )),
+                        ));
+                    }
+                    let delay = crate::retry::retry_after_header(&response)
+                        .unwrap_or_else(|| policy.delay_for_attempt(attempt));
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                Ok(response) => return Ok(response),
+                Err(error) => {
+                    let error = X402Error::from(error);
+                    if attempt + 1 < policy.max_attempts && error.is_retryable() {
+                        let delay = error
+                            .retry_after()
+                            .unwrap_or_else(|| policy.delay_for_attempt(attempt));
+                        tokio::time::sleep(delay).await;
+                        attempt += 1;
+                    } else {
+                        return Err(X402Error::retries_exhausted(attempt + 1, error));
+                    }
+                }
+            }
+        }
+    }
+
     /// Get all available resources
     pub async fn get_all_resources(&self) -> Result<DiscoveryResponse> {
         self.discover_resources(None).await
@@ -384,6 +811,98 @@ impl DiscoveryClient {
     pub fn url(&self) -> &str {
         &self.url
     }
+
+    /// Walk the whole catalog page by page, yielding one [`DiscoveryResource`] at
+    /// a time instead of requiring the caller to juggle `limit`/`offset`
+    /// themselves. Each page is only fetched once the previous page's buffered
+    /// items have all been yielded, so a slow consumer doesn't cause pages to pile
+    /// up ahead of it. `filters.limit` sets the page size (defaulting to
+    /// [`DEFAULT_DISCOVERY_PAGE_SIZE`]); `filters.offset` sets the starting
+    /// offset. Walking stops once [`PaginationInfo::total`] is reached, a page
+    /// comes back empty, or `max_items` items have been yielded (`None` walks the
+    /// entire catalog). A request error ends the stream with that `Err` as its
+    /// final item.
+    pub fn iter_resources(
+        &self,
+        filters: Option<DiscoveryFilters>,
+        max_items: Option<usize>,
+    ) -> impl futures_util::Stream<Item = Result<DiscoveryResource>> + '_ {
+        let filters = filters.unwrap_or_else(DiscoveryFilters::new);
+        let state = ResourceIterState {
+            client: self,
+            resource_type: filters.resource_type,
+            page_size: filters.limit.unwrap_or(DEFAULT_DISCOVERY_PAGE_SIZE).max(1),
+            offset: filters.offset.unwrap_or(0),
+            total: None,
+            buffer: std::collections::VecDeque::new(),
+            yielded: 0,
+            max_items,
+            exhausted: false,
+        };
+
+        futures_util::stream::unfold(state, |mut state| async move {
+            loop {
+                if state.max_items.is_some_and(|max_items| state.yielded >= max_items) {
+                    return None;
+                }
+
+                if let Some(resource) = state.buffer.pop_front() {
+                    state.yielded += 1;
+                    return Some((Ok(resource), state));
+                }
+
+                if state.exhausted {
+                    return None;
+                }
+
+                if state.total.is_some_and(|total| state.offset >= total) {
+                    return None;
+                }
+
+                let page = state
+                    .client
+                    .discover_resources(Some(DiscoveryFilters {
+                        resource_type: state.resource_type.clone(),
+                        limit: Some(state.page_size),
+                        offset: Some(state.offset),
+                    }))
+                    .await;
+
+                match page {
+                    Ok(response) => {
+                        state.total = Some(response.pagination.total);
+                        if response.items.is_empty() {
+                            state.exhausted = true;
+                            continue;
+                        }
+                        state.offset += response.items.len() as u32;
+                        state.buffer.extend(response.items);
+                    }
+                    Err(error) => {
+                        state.exhausted = true;
+                        return Some((Err(error), state));
+                    }
+                }
+            }
+        })
+    }
+}
+
+/// Page size [`DiscoveryClient::iter_resources`] requests per underlying call when
+/// the caller's filters don't set an explicit `limit`
+const DEFAULT_DISCOVERY_PAGE_SIZE: u32 = 50;
+
+/// Walk state behind the stream [`DiscoveryClient::iter_resources`] returns
+struct ResourceIterState<'a> {
+    client: &'a DiscoveryClient,
+    resource_type: Option<String>,
+    page_size: u32,
+    offset: u32,
+    total: Option<u32>,
+    buffer: std::collections::VecDeque<DiscoveryResource>,
+    yielded: usize,
+    max_items: Option<usize>,
+    exhausted: bool,
 }
 
 /// Filters for discovery requests
@@ -495,4 +1014,663 @@ mod tests {
         assert_eq!(filters.limit, Some(10));
         assert_eq!(filters.offset, Some(5));
     }
+
+    fn test_wallet() -> Wallet {
+        Wallet::new(
+            "0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef".to_string(),
+            "base-sepolia".to_string(),
+        )
+    }
+
+    fn test_requirement() -> PaymentRequirements {
+        PaymentRequirements::new(
+            "exact",
+            "base-sepolia",
+            "1000000",
+            "0x036CbD53842c5426634e7929541eC2318f3dCF7e",
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            "https://example.com/resource",
+            "Test resource",
+        )
+    }
+
+    #[test]
+    fn test_payment_session_retry_signs_and_tracks_attempt() {
+        let mut session = PaymentSession::new(test_wallet(), "0x857b06519E91e3A54538791bDbb0E22373e36b66", 3);
+        let requirements = PaymentRequirementsResponse::new("payment required", vec![test_requirement()]);
+
+        let payload = session.retry(&requirements).unwrap();
+        assert_eq!(payload.scheme, "exact");
+        assert_eq!(session.attempt(), 1);
+        assert!(session.id().is_some());
+        assert!(matches!(session.state(), PaymentSessionState::Retryable));
+    }
+
+    #[test]
+    fn test_payment_session_retry_signs_fresh_nonce_each_time() {
+        let mut session = PaymentSession::new(test_wallet(), "0x857b06519E91e3A54538791bDbb0E22373e36b66", 3);
+        let requirements = PaymentRequirementsResponse::new("payment required", vec![test_requirement()]);
+
+        let first = session.retry(&requirements).unwrap();
+        let second = session.retry(&requirements).unwrap();
+        assert_ne!(
+            first.exact_evm().unwrap().authorization.nonce,
+            second.exact_evm().unwrap().authorization.nonce
+        );
+    }
+
+    #[test]
+    fn test_payment_session_abandons_after_exhausting_attempts() {
+        let mut session = PaymentSession::new(test_wallet(), "0x857b06519E91e3A54538791bDbb0E22373e36b66", 1);
+        let requirements = PaymentRequirementsResponse::new("payment required", vec![test_requirement()]);
+
+        session.retry(&requirements).unwrap();
+        let result = session.retry(&requirements);
+
+        assert!(result.is_err());
+        assert!(matches!(
+            session.state(),
+            PaymentSessionState::Abandoned(PaymentFailureReason::RetriesExhausted)
+        ));
+    }
+
+    #[test]
+    fn test_payment_session_abandons_on_incompatible_requirements_change() {
+        let mut session = PaymentSession::new(test_wallet(), "0x857b06519E91e3A54538791bDbb0E22373e36b66", 3);
+        let requirements = PaymentRequirementsResponse::new("payment required", vec![test_requirement()]);
+        session.retry(&requirements).unwrap();
+
+        let mut other_requirement = test_requirement();
+        other_requirement.network = "avalanche-fuji".to_string();
+        let changed_requirements =
+            PaymentRequirementsResponse::new("payment required", vec![other_requirement]);
+
+        let result = session.retry(&changed_requirements);
+        assert!(result.is_err());
+        assert!(matches!(
+            session.state(),
+            PaymentSessionState::Abandoned(PaymentFailureReason::RequirementsChangedIncompatibly)
+        ));
+    }
+
+    #[test]
+    fn test_payment_session_rejects_retry_once_terminal() {
+        let mut session = PaymentSession::new(test_wallet(), "0x857b06519E91e3A54538791bDbb0E22373e36b66", 3);
+        session.mark_fulfilled();
+
+        let requirements = PaymentRequirementsResponse::new("payment required", vec![test_requirement()]);
+        assert!(session.retry(&requirements).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_send_with_retry_returns_first_success_without_a_policy() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/resource")
+            .with_status(200)
+            .create_async()
+            .await;
+
+        let client = X402Client::new().unwrap();
+        let response = client
+            .send_with_retry(|| client.get(&format!("{}/resource", server.url())))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), 200);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_send_with_retry_retries_a_retryable_status_then_succeeds() {
+        let mut server = mockito::Server::new_async().await;
+        let failing_mock = server
+            .mock("GET", "/resource")
+            .with_status(503)
+            .expect(2)
+            .create_async()
+            .await;
+        let success_mock = server
+            .mock("GET", "/resource")
+            .with_status(200)
+            .create_async()
+            .await;
+
+        let client = X402Client::new().unwrap().with_retry_policy(
+            crate::retry::RetryPolicy::new()
+                .with_base_delay(Duration::from_millis(1))
+                .with_max_attempts(5),
+        );
+
+        let response = client
+            .send_with_retry(|| client.get(&format!("{}/resource", server.url())))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), 200);
+        failing_mock.assert_async().await;
+        success_mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_send_with_retry_surfaces_retries_exhausted_with_attempt_count() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/resource")
+            .with_status(429)
+            .expect(3)
+            .create_async()
+            .await;
+
+        let client = X402Client::new().unwrap().with_retry_policy(
+            crate::retry::RetryPolicy::new()
+                .with_base_delay(Duration::from_millis(1))
+                .with_max_attempts(3),
+        );
+
+        let result = client
+            .send_with_retry(|| client.get(&format!("{}/resource", server.url())))
+            .await;
+
+        match result {
+            Err(X402Error::RetriesExhausted { attempts, .. }) => assert_eq!(attempts, 3),
+            other => panic!("expected RetriesExhausted, got {:?}", other),
+        }
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_send_with_retry_does_not_retry_a_non_retryable_status() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/resource")
+            .with_status(404)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = X402Client::new().unwrap().with_retry_policy(
+            crate::retry::RetryPolicy::new()
+                .with_base_delay(Duration::from_millis(1))
+                .with_max_attempts(5),
+        );
+
+        let response = client
+            .send_with_retry(|| client.get(&format!("{}/resource", server.url())))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), 404);
+        mock.assert_async().await;
+    }
+
+    #[cfg(feature = "compression")]
+    #[test]
+    fn test_with_compression_builds_a_client_advertising_accept_encoding() {
+        let client = X402Client::new().unwrap().with_compression(true).unwrap();
+        // `reqwest::Client`'s internals are opaque (no accessor exposes the
+        // negotiated `Accept-Encoding` header), so this can only confirm the
+        // rebuild succeeds with the feature compiled in, the same way
+        // `test_proxy_state_new_honors_none_timeouts` checks `ProxyState::new`.
+        let _ = client;
+    }
+
+    #[test]
+    fn test_with_tls_config_rejects_a_missing_ca_cert_path() {
+        let client = X402Client::new().unwrap();
+        let tls = crate::proxy::TlsConfig {
+            ca_cert_path: Some("/nonexistent/ca.pem".to_string()),
+            ..Default::default()
+        };
+
+        assert!(client.with_tls_config(&tls).is_err());
+    }
+
+    #[test]
+    fn test_with_tls_config_accepts_danger_accept_invalid_certs() {
+        let client = X402Client::new().unwrap();
+        let tls = crate::proxy::TlsConfig {
+            accept_invalid_certs: true,
+            ..Default::default()
+        };
+
+        assert!(client.with_tls_config(&tls).is_ok());
+    }
+
+    #[cfg(feature = "cookies")]
+    #[test]
+    fn test_cookies_for_returns_none_before_anything_is_seeded() {
+        let client = X402Client::new().unwrap().with_cookie_store().unwrap();
+        assert_eq!(client.cookies_for("https://example.com/resource").unwrap(), None);
+    }
+
+    #[cfg(feature = "cookies")]
+    #[test]
+    fn test_seed_cookie_is_readable_via_cookies_for() {
+        let client = X402Client::new().unwrap().with_cookie_store().unwrap();
+        client
+            .seed_cookie("https://example.com/resource", "session=abc123; Path=/")
+            .unwrap();
+
+        let cookie = client.cookies_for("https://example.com/resource").unwrap().unwrap();
+        assert_eq!(cookie, "session=abc123");
+    }
+
+    #[cfg(feature = "cookies")]
+    #[test]
+    fn test_cookies_for_without_a_cookie_store_is_an_error() {
+        let client = X402Client::new().unwrap();
+        assert!(client.cookies_for("https://example.com/resource").is_err());
+    }
+
+    #[cfg(feature = "cookies")]
+    #[tokio::test]
+    async fn test_cookie_set_on_the_402_response_is_sent_back_on_the_paid_retry() {
+        let mut server = mockito::Server::new_async().await;
+        let payment_payload = test_payment_payload();
+
+        let unpaid_mock = server
+            .mock("GET", "/resource")
+            .match_header("cookie", mockito::Matcher::Missing)
+            .with_status(402)
+            .with_header("content-type", "application/json")
+            .with_header("set-cookie", "session=abc123; Path=/")
+            .with_body(
+                serde_json::json!({
+                    "x402Version": 1,
+                    "error": "payment required",
+                    "accepts": [test_requirement()],
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        let verify_mock = server
+            .mock("POST", "/verify")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"x402Version":1,"isValid":true}"#)
+            .create_async()
+            .await;
+        let paid_mock = server
+            .mock("GET", "/resource")
+            .match_header("x-payment", mockito::Matcher::Any)
+            .match_header("cookie", "session=abc123")
+            .with_status(200)
+            .create_async()
+            .await;
+
+        let client = X402Client::new()
+            .unwrap()
+            .with_facilitator_config(FacilitatorConfig::new(server.url()))
+            .with_cookie_store()
+            .unwrap();
+
+        let response = client
+            .get(&format!("{}/resource", server.url()))
+            .send_with_payment(&payment_payload)
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), 200);
+        unpaid_mock.assert_async().await;
+        verify_mock.assert_async().await;
+        paid_mock.assert_async().await;
+    }
+
+    fn test_payment_payload() -> PaymentPayload {
+        let authorization = ExactEvmPayloadAuthorization::new(
+            "0x857b06519E91e3A54538791bDbb0E22373e36b66",
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            "1000000",
+            "1745323800",
+            "1745323985",
+            "0xf3746613c2d920b5fdabc0856f2aeb2d4f88ee6037b8cc5d04a71a4462f13480",
+        );
+
+        let payload = ExactEvmPayload {
+            signature: "0x2d6a7588d6acca505cbf0d9a4a227e0c52c6c34008c8e8986a1283259764173608a2ce6496642e377d6da8dbbf5836e9bd15092f9ecab05ded3d6293af148b571c".to_string(),
+            authorization,
+        };
+
+        PaymentPayload::new("exact", "base-sepolia", payload)
+    }
+
+    #[tokio::test]
+    async fn test_request_with_payment_replays_the_original_method_body_and_headers_on_402() {
+        let mut server = mockito::Server::new_async().await;
+        let payment_payload = test_payment_payload();
+
+        let unpaid_mock = server
+            .mock("POST", "/resource")
+            .match_header("x-request-id", "abc-123")
+            .match_body(r#"{"amount":42}"#)
+            .with_status(402)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!({
+                    "x402Version": 1,
+                    "error": "payment required",
+                    "accepts": [test_requirement()],
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        let verify_mock = server
+            .mock("POST", "/verify")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"x402Version":1,"isValid":true}"#)
+            .create_async()
+            .await;
+        let paid_mock = server
+            .mock("POST", "/resource")
+            .match_header("x-request-id", "abc-123")
+            .match_header("x-payment", mockito::Matcher::Any)
+            .match_body(r#"{"amount":42}"#)
+            .with_status(200)
+            .create_async()
+            .await;
+
+        let client = X402Client::new()
+            .unwrap()
+            .with_facilitator_config(FacilitatorConfig::new(server.url()));
+
+        let response = client
+            .post(&format!("{}/resource", server.url()))
+            .header("x-request-id", "abc-123")
+            .json(&serde_json::json!({"amount": 42}))
+            .send_with_payment(&payment_payload)
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), 200);
+        unpaid_mock.assert_async().await;
+        verify_mock.assert_async().await;
+        paid_mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_send_with_payment_does_not_retry_when_facilitator_rejects_payment() {
+        let mut server = mockito::Server::new_async().await;
+        let payment_payload = test_payment_payload();
+
+        let unpaid_mock = server
+            .mock("GET", "/resource")
+            .with_status(402)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!({
+                    "x402Version": 1,
+                    "error": "payment required",
+                    "accepts": [test_requirement()],
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        let verify_mock = server
+            .mock("POST", "/verify")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"x402Version":1,"isValid":false,"invalidReason":"insufficient_funds"}"#)
+            .create_async()
+            .await;
+
+        let client = X402Client::new()
+            .unwrap()
+            .with_facilitator_config(FacilitatorConfig::new(server.url()));
+
+        let result = client
+            .get(&format!("{}/resource", server.url()))
+            .send_with_payment(&payment_payload)
+            .await;
+
+        assert!(matches!(result, Err(X402Error::PaymentVerificationFailed { .. })));
+        unpaid_mock.assert_async().await;
+        verify_mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_send_with_payment_retries_the_paid_resend_per_the_clients_retry_policy() {
+        let mut server = mockito::Server::new_async().await;
+        let payment_payload = test_payment_payload();
+
+        let unpaid_mock = server
+            .mock("GET", "/resource")
+            .with_status(402)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!({
+                    "x402Version": 1,
+                    "error": "payment required",
+                    "accepts": [test_requirement()],
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        let verify_mock = server
+            .mock("POST", "/verify")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"x402Version":1,"isValid":true}"#)
+            .create_async()
+            .await;
+        let failing_paid_mock = server
+            .mock("GET", "/resource")
+            .match_header("x-payment", mockito::Matcher::Any)
+            .with_status(503)
+            .expect(1)
+            .create_async()
+            .await;
+        let succeeding_paid_mock = server
+            .mock("GET", "/resource")
+            .match_header("x-payment", mockito::Matcher::Any)
+            .with_status(200)
+            .create_async()
+            .await;
+
+        let client = X402Client::new()
+            .unwrap()
+            .with_facilitator_config(FacilitatorConfig::new(server.url()))
+            .with_retry_policy(
+                crate::retry::RetryPolicy::new()
+                    .with_base_delay(Duration::from_millis(1))
+                    .with_max_attempts(3),
+            );
+
+        let response = client
+            .get(&format!("{}/resource", server.url()))
+            .send_with_payment(&payment_payload)
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), 200);
+        unpaid_mock.assert_async().await;
+        verify_mock.assert_async().await;
+        failing_paid_mock.assert_async().await;
+        succeeding_paid_mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_send_surfaces_a_structured_timeout_error() {
+        // A listener that accepts the connection but never writes a response, so a
+        // short per-request timeout reliably elapses waiting on the response rather
+        // than racing a real server's response latency.
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::task::spawn_blocking(move || {
+            let _ = listener.accept();
+            std::thread::sleep(Duration::from_secs(5));
+        });
+
+        let client = X402Client::new().unwrap();
+        let result = client
+            .get(&format!("http://{}/resource", addr))
+            .timeout(Duration::from_millis(20))
+            .send()
+            .await;
+
+        match result {
+            Err(X402Error::RequestTimedOut { elapsed_ms, .. }) => assert!(elapsed_ms >= 20),
+            other => panic!("expected RequestTimedOut, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_discovery_send_with_retry_retries_a_retryable_status_then_succeeds() {
+        let mut server = mockito::Server::new_async().await;
+        let failing_mock = server
+            .mock("GET", "/resources")
+            .with_status(503)
+            .expect(2)
+            .create_async()
+            .await;
+        let success_mock = server
+            .mock("GET", "/resources")
+            .with_status(200)
+            .with_body(r#"{"x402Version":1,"items":[],"pagination":{"limit":20,"offset":0,"total":0}}"#)
+            .create_async()
+            .await;
+
+        let client = DiscoveryClient::new(server.url()).with_retry_policy(
+            crate::retry::RetryPolicy::new()
+                .with_base_delay(Duration::from_millis(1))
+                .with_max_attempts(5),
+        );
+
+        let response = client.discover_resources(None).await.unwrap();
+
+        assert_eq!(response.items.len(), 0);
+        failing_mock.assert_async().await;
+        success_mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_discovery_send_with_retry_surfaces_retries_exhausted_with_attempt_count() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/resources")
+            .with_status(429)
+            .expect(3)
+            .create_async()
+            .await;
+
+        let client = DiscoveryClient::new(server.url()).with_retry_policy(
+            crate::retry::RetryPolicy::new()
+                .with_base_delay(Duration::from_millis(1))
+                .with_max_attempts(3),
+        );
+
+        let result = client.discover_resources(None).await;
+
+        match result {
+            Err(X402Error::RetriesExhausted { attempts, .. }) => assert_eq!(attempts, 3),
+            other => panic!("expected RetriesExhausted, got {:?}", other),
+        }
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_discovery_send_with_retry_does_not_retry_a_non_retryable_status() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/resources")
+            .with_status(404)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = DiscoveryClient::new(server.url()).with_retry_policy(
+            crate::retry::RetryPolicy::new()
+                .with_base_delay(Duration::from_millis(1))
+                .with_max_attempts(5),
+        );
+
+        let result = client.discover_resources(None).await;
+
+        assert!(matches!(result, Err(X402Error::Facilitator { .. })));
+        mock.assert_async().await;
+    }
+
+    fn discovery_page(items: Vec<&str>, limit: u32, offset: u32, total: u32) -> String {
+        let items: Vec<_> = items
+            .into_iter()
+            .map(|resource| {
+                serde_json::json!({
+                    "resource": resource,
+                    "type": "http",
+                    "x402Version": 1,
+                    "accepts": [],
+                    "lastUpdated": 0,
+                })
+            })
+            .collect();
+        serde_json::json!({
+            "x402Version": 1,
+            "items": items,
+            "pagination": {"limit": limit, "offset": offset, "total": total},
+        })
+        .to_string()
+    }
+
+    #[tokio::test]
+    async fn test_iter_resources_walks_every_page_until_total_is_reached() {
+        use futures_util::StreamExt;
+
+        let mut server = mockito::Server::new_async().await;
+        let first_page = server
+            .mock("GET", "/resources")
+            .match_query(mockito::Matcher::UrlEncoded("offset".into(), "0".into()))
+            .with_status(200)
+            .with_body(discovery_page(vec!["a", "b"], 2, 0, 3))
+            .create_async()
+            .await;
+        let second_page = server
+            .mock("GET", "/resources")
+            .match_query(mockito::Matcher::UrlEncoded("offset".into(), "2".into()))
+            .with_status(200)
+            .with_body(discovery_page(vec!["c"], 2, 2, 3))
+            .create_async()
+            .await;
+
+        let client = DiscoveryClient::new(server.url());
+        let filters = DiscoveryFilters::new().with_limit(2);
+        let resources: Vec<_> = client
+            .iter_resources(Some(filters), None)
+            .collect::<Vec<_>>()
+            .await;
+
+        let resources: Vec<String> = resources
+            .into_iter()
+            .map(|r| r.unwrap().resource)
+            .collect();
+        assert_eq!(resources, vec!["a", "b", "c"]);
+        first_page.assert_async().await;
+        second_page.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_iter_resources_stops_at_max_items_without_fetching_further_pages() {
+        use futures_util::StreamExt;
+
+        let mut server = mockito::Server::new_async().await;
+        let only_page = server
+            .mock("GET", "/resources")
+            .with_status(200)
+            .with_body(discovery_page(vec!["a", "b", "c"], 3, 0, 10))
+            .create_async()
+            .await;
+
+        let client = DiscoveryClient::new(server.url());
+        let resources: Vec<_> = client
+            .iter_resources(None, Some(2))
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(resources.len(), 2);
+        only_page.assert_async().await;
+    }
 }
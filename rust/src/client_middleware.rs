@@ -0,0 +1,310 @@
+//! Composable middleware stack for driving x402-aware outbound HTTP requests
+//!
+//! [`crate::client::X402Client`] and [`crate::client::PaymentSession`] cover the
+//! common case — fetch a resource, sign if challenged, resubmit — as one built-in
+//! flow. This module offers a second, layered entry point for callers who want to
+//! insert their own cross-cutting behavior (logging, metrics, a bespoke retry
+//! policy) as a new layer instead of editing [`crate::client::X402Client::pay_resource`]
+//! itself, mirroring how [`crate::facilitator_middleware`] layers the facilitator's
+//! blockchain RPC calls and how ethers-rs lets `Middleware` implementations wrap a
+//! provider. [`Transport`] is the terminal layer; [`SettlementLayer`] and
+//! [`SignerLayer`] wrap another layer and can be stacked in either order, e.g.
+//! `SignerLayer::new(SettlementLayer::new(Transport::new(client)), wallet, from)`.
+//!
+//! [`SignerLayer`] re-signs through [`crate::client::PaymentSession`] rather than
+//! re-implementing the sign/resubmit loop, so both entry points share one
+//! implementation of that state machine.
+
+use crate::client::{PaymentSession, DEFAULT_MAX_PAYMENT_ATTEMPTS};
+use crate::types::{PaymentRequirementsResponse, SettleResponse};
+use crate::wallet::Wallet;
+use crate::{Result, X402Error};
+use reqwest::{Request, Response};
+
+/// Boxed future returned by [`X402Middleware::handle`]
+pub type BoxFuture<'a, T> = std::pin::Pin<Box<dyn std::future::Future<Output = T> + Send + 'a>>;
+
+/// A layer in an x402 outbound-request stack
+///
+/// A layer that doesn't need to intervene on a given request just forwards it to
+/// the layer it wraps.
+pub trait X402Middleware: Send + Sync {
+    /// Send `req` through this layer (and everything it wraps)
+    fn handle<'a>(&'a self, req: Request) -> BoxFuture<'a, Result<Response>>;
+}
+
+/// The terminal layer: sends the request over a [`reqwest::Client`] with no
+/// x402-specific behavior
+#[derive(Debug, Clone)]
+pub struct Transport {
+    client: reqwest::Client,
+}
+
+impl Transport {
+    /// Wrap `client`, with no behavior added
+    pub fn new(client: reqwest::Client) -> Self {
+        Self { client }
+    }
+}
+
+impl X402Middleware for Transport {
+    fn handle<'a>(&'a self, req: Request) -> BoxFuture<'a, Result<Response>> {
+        Box::pin(async move { Ok(self.client.execute(req).await?) })
+    }
+}
+
+/// Parses the `X-PAYMENT-RESPONSE` settlement header a facilitator-backed server
+/// attaches to a successfully paid response, failing fast here if it's malformed
+/// rather than surfacing a confusing error later to a caller who never asked to
+/// see this header at all
+pub struct SettlementLayer<M> {
+    inner: M,
+}
+
+impl<M: X402Middleware> SettlementLayer<M> {
+    /// Wrap `inner`, with no behavior added beyond validating `X-PAYMENT-RESPONSE`
+    pub fn new(inner: M) -> Self {
+        Self { inner }
+    }
+}
+
+impl<M: X402Middleware> X402Middleware for SettlementLayer<M> {
+    fn handle<'a>(&'a self, req: Request) -> BoxFuture<'a, Result<Response>> {
+        Box::pin(async move {
+            let response = self.inner.handle(req).await?;
+            if let Some(header) = response.headers().get("X-PAYMENT-RESPONSE") {
+                let text = header.to_str().map_err(|e| {
+                    X402Error::unexpected(format!("Invalid X-PAYMENT-RESPONSE header: {}", e))
+                })?;
+                SettleResponse::from_base64(text)?;
+            }
+            Ok(response)
+        })
+    }
+}
+
+/// Detects a `402 Payment Required` response, signs a fresh authorization against
+/// the server's `accepts` list with `wallet`, and re-issues the request carrying
+/// the resulting `X-PAYMENT` header
+///
+/// Drives the same [`PaymentSession`] state machine
+/// [`crate::client::X402Client::pay_resource`] uses, so both entry points share
+/// one sign/retry/abandon implementation. A request that already carries
+/// `X-PAYMENT` is assumed to be a caller-signed retry and passes straight
+/// through without being re-signed.
+pub struct SignerLayer<M> {
+    inner: M,
+    wallet: Wallet,
+    from_address: String,
+    max_attempts: u32,
+}
+
+impl<M: X402Middleware> SignerLayer<M> {
+    /// Wrap `inner`, signing 402 challenges with `wallet` on behalf of `from_address`
+    pub fn new(inner: M, wallet: Wallet, from_address: impl Into<String>) -> Self {
+        Self {
+            inner,
+            wallet,
+            from_address: from_address.into(),
+            max_attempts: DEFAULT_MAX_PAYMENT_ATTEMPTS,
+        }
+    }
+
+    /// Override the default signed-submission budget ([`DEFAULT_MAX_PAYMENT_ATTEMPTS`])
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+}
+
+impl<M: X402Middleware> X402Middleware for SignerLayer<M> {
+    fn handle<'a>(&'a self, req: Request) -> BoxFuture<'a, Result<Response>> {
+        Box::pin(async move {
+            if req.headers().contains_key("X-PAYMENT") {
+                return self.inner.handle(req).await;
+            }
+
+            let Some(mut pending_req) = req.try_clone() else {
+                return self.inner.handle(req).await;
+            };
+
+            let response = self.inner.handle(req).await?;
+            if response.status() != 402 {
+                return Ok(response);
+            }
+
+            let mut requirements: PaymentRequirementsResponse = response.json().await?;
+            let mut session =
+                PaymentSession::new(self.wallet.clone(), self.from_address.clone(), self.max_attempts);
+
+            loop {
+                let payload = session.retry(&requirements)?;
+                let payment_header = payload.to_base64()?;
+
+                let Some(mut signed_req) = pending_req.try_clone() else {
+                    return Err(X402Error::unexpected(
+                        "Request body cannot be replayed with a payment header",
+                    ));
+                };
+                let header_value = reqwest::header::HeaderValue::from_str(&payment_header)
+                    .map_err(|e| X402Error::unexpected(format!("Invalid payment header: {}", e)))?;
+                signed_req.headers_mut().insert("X-PAYMENT", header_value);
+
+                let retried = self.inner.handle(signed_req).await?;
+                if retried.status() != 402 {
+                    session.mark_fulfilled();
+                    return Ok(retried);
+                }
+
+                requirements = retried.json().await?;
+                let Some(next_pending) = pending_req.try_clone() else {
+                    return Err(X402Error::unexpected(
+                        "Request body cannot be replayed with a payment header",
+                    ));
+                };
+                pending_req = next_pending;
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::PaymentRequirements;
+
+    fn test_wallet() -> Wallet {
+        Wallet::new(
+            "0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef".to_string(),
+            "base-sepolia".to_string(),
+        )
+    }
+
+    fn test_requirement() -> PaymentRequirements {
+        PaymentRequirements::new(
+            "exact",
+            "base-sepolia",
+            "1000000",
+            "0x036CbD53842c5426634e7929541eC2318f3dCF7e",
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            "https://example.com/resource",
+            "Test resource",
+        )
+    }
+
+    fn stack(client: reqwest::Client) -> SignerLayer<SettlementLayer<Transport>> {
+        SignerLayer::new(
+            SettlementLayer::new(Transport::new(client)),
+            test_wallet(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        )
+    }
+
+    #[tokio::test]
+    async fn test_transport_forwards_a_plain_response() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/resource")
+            .with_status(200)
+            .create_async()
+            .await;
+
+        let client = reqwest::Client::new();
+        let req = client
+            .get(format!("{}/resource", server.url()))
+            .build()
+            .unwrap();
+
+        let response = Transport::new(client).handle(req).await.unwrap();
+
+        assert_eq!(response.status(), 200);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_signer_layer_signs_and_resubmits_on_a_402() {
+        let mut server = mockito::Server::new_async().await;
+        let challenge = server
+            .mock("GET", "/resource")
+            .match_header("x-payment", mockito::Matcher::Missing)
+            .with_status(402)
+            .with_body(
+                serde_json::to_string(&PaymentRequirementsResponse::new(
+                    "payment required",
+                    vec![test_requirement()],
+                ))
+                .unwrap(),
+            )
+            .create_async()
+            .await;
+        let paid = server
+            .mock("GET", "/resource")
+            .match_header("x-payment", mockito::Matcher::Any)
+            .with_status(200)
+            .create_async()
+            .await;
+
+        let client = reqwest::Client::new();
+        let req = client
+            .get(format!("{}/resource", server.url()))
+            .build()
+            .unwrap();
+
+        let response = stack(client).handle(req).await.unwrap();
+
+        assert_eq!(response.status(), 200);
+        challenge.assert_async().await;
+        paid.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_signer_layer_leaves_an_already_signed_request_alone() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/resource")
+            .match_header("x-payment", "already-signed")
+            .with_status(402)
+            .with_body(
+                serde_json::to_string(&PaymentRequirementsResponse::new(
+                    "payment required",
+                    vec![test_requirement()],
+                ))
+                .unwrap(),
+            )
+            .create_async()
+            .await;
+
+        let client = reqwest::Client::new();
+        let req = client
+            .get(format!("{}/resource", server.url()))
+            .header("X-PAYMENT", "already-signed")
+            .build()
+            .unwrap();
+
+        let response = stack(client).handle(req).await.unwrap();
+
+        assert_eq!(response.status(), 402);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_settlement_layer_rejects_a_malformed_settlement_header() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/resource")
+            .with_status(200)
+            .with_header("X-PAYMENT-RESPONSE", "not-valid-base64-json")
+            .create_async()
+            .await;
+
+        let client = reqwest::Client::new();
+        let req = client
+            .get(format!("{}/resource", server.url()))
+            .build()
+            .unwrap();
+
+        let result = SettlementLayer::new(Transport::new(client)).handle(req).await;
+
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,373 @@
+//! SQLite-backed cache for discovered resources and settlement history
+//!
+//! [`crate::client::DiscoveryClient`] is ephemeral: every call to `discover_resources`
+//! or `get_all_resources` re-hits the network, and nothing records what a client has
+//! actually paid. [`DiscoveryCache`] persists both sides to a local SQLite database —
+//! discovered [`DiscoveryResource`] items keyed by their `resource` URL, and a
+//! `SettleResponse` row per settlement — so an agent can browse the x402 bazaar
+//! offline, re-query it with the same [`DiscoveryFilters`] it'd use against the live
+//! service, and audit what it has paid so far, mirroring how payment SDKs persist
+//! transaction records locally for later querying.
+//!
+//! Gated behind the `sqlite` feature, since most callers never need local persistence
+//! and this is the only module in the crate with a real embedded-database dependency.
+
+use crate::client::DiscoveryFilters;
+use crate::types::{DiscoveryResource, DiscoveryResponse, PaginationInfo, SettleResponse};
+use crate::{Result, X402Error};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::path::Path;
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+/// A past settlement recorded by [`DiscoveryCache::record_settlement`], returned by
+/// [`DiscoveryCache::settlement_history`] for a caller auditing what it has paid
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SettlementRecord {
+    pub transaction: String,
+    pub network: String,
+    pub payer: Option<String>,
+    pub amount: String,
+    /// Unix timestamp the settlement was recorded at
+    pub timestamp: u64,
+}
+
+/// Caches [`DiscoveryResponse`] items and settlement history in a local SQLite database
+pub struct DiscoveryCache {
+    conn: Mutex<Connection>,
+}
+
+impl DiscoveryCache {
+    /// Open (creating if necessary) a cache backed by the SQLite database at `path`
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let conn = Connection::open(path).map_err(to_x402_error)?;
+        Self::from_connection(conn)
+    }
+
+    /// Open an in-memory cache, e.g. for tests or a short-lived process
+    pub fn open_in_memory() -> Result<Self> {
+        let conn = Connection::open_in_memory().map_err(to_x402_error)?;
+        Self::from_connection(conn)
+    }
+
+    fn from_connection(conn: Connection) -> Result<Self> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS discovery_resources (
+                resource      TEXT PRIMARY KEY,
+                resource_type TEXT NOT NULL,
+                x402_version  INTEGER NOT NULL,
+                last_updated  INTEGER NOT NULL,
+                accepts       TEXT NOT NULL,
+                metadata      TEXT
+            );
+            CREATE TABLE IF NOT EXISTS settlement_history (
+                id          INTEGER PRIMARY KEY AUTOINCREMENT,
+                transaction_hash TEXT NOT NULL,
+                network     TEXT NOT NULL,
+                payer       TEXT,
+                amount      TEXT NOT NULL,
+                timestamp   INTEGER NOT NULL
+            );",
+        )
+        .map_err(to_x402_error)?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// Upsert every item in `response` into the cache, keyed by `resource`; an item
+    /// already cached for the same URL is overwritten with the fresh copy
+    pub async fn upsert(&self, response: &DiscoveryResponse) -> Result<()> {
+        let conn = self.conn.lock().await;
+        for item in &response.items {
+            let accepts = serde_json::to_string(&item.accepts).map_err(to_x402_error)?;
+            let metadata = item
+                .metadata
+                .as_ref()
+                .map(serde_json::to_string)
+                .transpose()
+                .map_err(to_x402_error)?;
+
+            conn.execute(
+                "INSERT INTO discovery_resources
+                    (resource, resource_type, x402_version, last_updated, accepts, metadata)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                 ON CONFLICT(resource) DO UPDATE SET
+                    resource_type = excluded.resource_type,
+                    x402_version  = excluded.x402_version,
+                    last_updated  = excluded.last_updated,
+                    accepts       = excluded.accepts,
+                    metadata      = excluded.metadata",
+                params![
+                    item.resource,
+                    item.r#type,
+                    item.x402_version,
+                    item.last_updated as i64,
+                    accepts,
+                    metadata,
+                ],
+            )
+            .map_err(to_x402_error)?;
+        }
+        Ok(())
+    }
+
+    /// Query the cache with the same filters [`crate::client::DiscoveryClient`] accepts
+    /// — `resource_type`, `limit`, `offset` — without a network round-trip
+    pub async fn query(&self, filters: &DiscoveryFilters) -> Result<DiscoveryResponse> {
+        let conn = self.conn.lock().await;
+        let limit = filters.limit.unwrap_or(u32::MAX) as i64;
+        let offset = filters.offset.unwrap_or(0) as i64;
+
+        let (items, total): (Vec<DiscoveryResource>, u32) = if let Some(resource_type) = &filters.resource_type {
+            let mut stmt = conn
+                .prepare(
+                    "SELECT resource, resource_type, x402_version, last_updated, accepts, metadata
+                     FROM discovery_resources WHERE resource_type = ?1
+                     ORDER BY last_updated DESC LIMIT ?2 OFFSET ?3",
+                )
+                .map_err(to_x402_error)?;
+            let items = stmt
+                .query_map(params![resource_type, limit, offset], row_to_resource)
+                .map_err(to_x402_error)?
+                .collect::<rusqlite::Result<Vec<_>>>()
+                .map_err(to_x402_error)?;
+            let total = conn
+                .query_row(
+                    "SELECT COUNT(*) FROM discovery_resources WHERE resource_type = ?1",
+                    params![resource_type],
+                    |row| row.get(0),
+                )
+                .map_err(to_x402_error)?;
+            (items, total)
+        } else {
+            let mut stmt = conn
+                .prepare(
+                    "SELECT resource, resource_type, x402_version, last_updated, accepts, metadata
+                     FROM discovery_resources ORDER BY last_updated DESC LIMIT ?1 OFFSET ?2",
+                )
+                .map_err(to_x402_error)?;
+            let items = stmt
+                .query_map(params![limit, offset], row_to_resource)
+                .map_err(to_x402_error)?
+                .collect::<rusqlite::Result<Vec<_>>>()
+                .map_err(to_x402_error)?;
+            let total = conn
+                .query_row("SELECT COUNT(*) FROM discovery_resources", [], |row| row.get(0))
+                .map_err(to_x402_error)?;
+            (items, total)
+        };
+
+        Ok(DiscoveryResponse {
+            x402_version: crate::X402_VERSION,
+            items,
+            pagination: PaginationInfo {
+                limit: filters.limit.unwrap_or(total),
+                offset: filters.offset.unwrap_or(0),
+                total,
+            },
+        })
+    }
+
+    /// Remove cached resources whose `lastUpdated` is older than `ttl` relative to
+    /// `now`, returning how many rows were pruned
+    pub async fn prune_older_than(&self, ttl: Duration, now: u64) -> Result<u64> {
+        let cutoff = now.saturating_sub(ttl.as_secs());
+        let conn = self.conn.lock().await;
+        let pruned = conn
+            .execute(
+                "DELETE FROM discovery_resources WHERE last_updated < ?1",
+                params![cutoff as i64],
+            )
+            .map_err(to_x402_error)?;
+        Ok(pruned as u64)
+    }
+
+    /// Record a completed settlement for later audit via [`Self::settlement_history`]
+    pub async fn record_settlement(
+        &self,
+        settlement: &SettleResponse,
+        amount: &str,
+        timestamp: u64,
+    ) -> Result<()> {
+        let conn = self.conn.lock().await;
+        conn.execute(
+            "INSERT INTO settlement_history (transaction_hash, network, payer, amount, timestamp)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                settlement.transaction,
+                settlement.network,
+                settlement.payer,
+                amount,
+                timestamp as i64,
+            ],
+        )
+        .map_err(to_x402_error)?;
+        Ok(())
+    }
+
+    /// Fetch the most recent settlements first, for a caller auditing what it has paid
+    pub async fn settlement_history(&self, limit: u32, offset: u32) -> Result<Vec<SettlementRecord>> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn
+            .prepare(
+                "SELECT transaction_hash, network, payer, amount, timestamp
+                 FROM settlement_history ORDER BY timestamp DESC LIMIT ?1 OFFSET ?2",
+            )
+            .map_err(to_x402_error)?;
+        stmt.query_map(params![limit, offset], |row| {
+            Ok(SettlementRecord {
+                transaction: row.get(0)?,
+                network: row.get(1)?,
+                payer: row.get(2)?,
+                amount: row.get(3)?,
+                timestamp: row.get::<_, i64>(4)? as u64,
+            })
+        })
+        .map_err(to_x402_error)?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(to_x402_error)
+    }
+
+    /// Look up a single cached resource by its exact URL, if present
+    pub async fn get(&self, resource: &str) -> Result<Option<DiscoveryResource>> {
+        let conn = self.conn.lock().await;
+        conn.query_row(
+            "SELECT resource, resource_type, x402_version, last_updated, accepts, metadata
+             FROM discovery_resources WHERE resource = ?1",
+            params![resource],
+            row_to_resource,
+        )
+        .optional()
+        .map_err(to_x402_error)
+    }
+}
+
+fn row_to_resource(row: &rusqlite::Row) -> rusqlite::Result<DiscoveryResource> {
+    let accepts_json: String = row.get(4)?;
+    let metadata_json: Option<String> = row.get(5)?;
+
+    let accepts = serde_json::from_str(&accepts_json).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(4, rusqlite::types::Type::Text, Box::new(e))
+    })?;
+    let metadata = metadata_json
+        .map(|raw| serde_json::from_str(&raw))
+        .transpose()
+        .map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(5, rusqlite::types::Type::Text, Box::new(e))
+        })?;
+
+    Ok(DiscoveryResource {
+        resource: row.get(0)?,
+        r#type: row.get(1)?,
+        x402_version: row.get(2)?,
+        accepts,
+        last_updated: row.get::<_, i64>(3)? as u64,
+        metadata,
+    })
+}
+
+fn to_x402_error(error: impl std::fmt::Display) -> X402Error {
+    X402Error::unexpected(format!("discovery cache error: {error}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::PaymentRequirements;
+
+    fn sample_response(resource: &str, last_updated: u64) -> DiscoveryResponse {
+        DiscoveryResponse {
+            x402_version: crate::X402_VERSION,
+            items: vec![DiscoveryResource {
+                resource: resource.to_string(),
+                r#type: "http".to_string(),
+                x402_version: crate::X402_VERSION,
+                accepts: vec![PaymentRequirements::new(
+                    "exact",
+                    "base-sepolia",
+                    "1000",
+                    "0x036CbD53842c5426634e7929541eC2318f3dCF7e",
+                    "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+                    resource,
+                    "a paid resource",
+                )],
+                last_updated,
+                metadata: None,
+            }],
+            pagination: PaginationInfo {
+                limit: 1,
+                offset: 0,
+                total: 1,
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_upsert_then_query_round_trips_a_resource() {
+        let cache = DiscoveryCache::open_in_memory().unwrap();
+        cache.upsert(&sample_response("https://example.com/a", 1000)).await.unwrap();
+
+        let result = cache.query(&DiscoveryFilters::new()).await.unwrap();
+        assert_eq!(result.items.len(), 1);
+        assert_eq!(result.items[0].resource, "https://example.com/a");
+        assert_eq!(result.pagination.total, 1);
+    }
+
+    #[tokio::test]
+    async fn test_upsert_overwrites_the_same_resource_url() {
+        let cache = DiscoveryCache::open_in_memory().unwrap();
+        cache.upsert(&sample_response("https://example.com/a", 1000)).await.unwrap();
+        cache.upsert(&sample_response("https://example.com/a", 2000)).await.unwrap();
+
+        let result = cache.query(&DiscoveryFilters::new()).await.unwrap();
+        assert_eq!(result.items.len(), 1);
+        assert_eq!(result.items[0].last_updated, 2000);
+    }
+
+    #[tokio::test]
+    async fn test_query_filters_by_resource_type() {
+        let cache = DiscoveryCache::open_in_memory().unwrap();
+        cache.upsert(&sample_response("https://example.com/a", 1000)).await.unwrap();
+
+        let filters = DiscoveryFilters::new().with_resource_type("websocket");
+        let result = cache.query(&filters).await.unwrap();
+        assert!(result.items.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_prune_older_than_removes_stale_entries_only() {
+        let cache = DiscoveryCache::open_in_memory().unwrap();
+        cache.upsert(&sample_response("https://example.com/old", 100)).await.unwrap();
+        cache.upsert(&sample_response("https://example.com/fresh", 10_000)).await.unwrap();
+
+        let pruned = cache
+            .prune_older_than(Duration::from_secs(1000), 10_100)
+            .await
+            .unwrap();
+        assert_eq!(pruned, 1);
+
+        let result = cache.query(&DiscoveryFilters::new()).await.unwrap();
+        assert_eq!(result.items.len(), 1);
+        assert_eq!(result.items[0].resource, "https://example.com/fresh");
+    }
+
+    #[tokio::test]
+    async fn test_settlement_history_returns_most_recent_first() {
+        let cache = DiscoveryCache::open_in_memory().unwrap();
+        let settlement = SettleResponse {
+            success: true,
+            error_reason: None,
+            transaction: "0xabc".to_string(),
+            network: "base-sepolia".to_string(),
+            payer: Some("0xpayer".to_string()),
+        };
+        cache.record_settlement(&settlement, "1000000", 1).await.unwrap();
+        cache.record_settlement(&settlement, "2000000", 2).await.unwrap();
+
+        let history = cache.settlement_history(10, 0).await.unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].amount, "2000000");
+        assert_eq!(history[0].timestamp, 2);
+    }
+}
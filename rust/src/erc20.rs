@@ -0,0 +1,485 @@
+//! Typed ERC-20 calldata encoding
+//!
+//! Hand-concatenating a function selector with zero-padded hex strings only works
+//! for one call at a time and can't be reused across `balanceOf`, `allowance`,
+//! `transfer`, or `transferWithAuthorization` without repeating the same fragile
+//! string formatting. This module computes the 4-byte selector from a function
+//! signature (`keccak256(signature)[..4]`, the same derivation as `solidity`'s own
+//! selector) and ABI-encodes the standard value types used by ERC-20 and EIP-3009
+//! (`address` left-padded to a 32-byte word, `uint256` big-endian), so
+//! [`crate::blockchain::BlockchainClient`] and [`crate::real_facilitator`] build
+//! calldata from a typed interface instead, and [`decode_revert_reason`] decodes a
+//! `require(cond, "msg")` revert back out the other side. [`aggregate3`] and
+//! [`decode_aggregate3_result`] extend this to Multicall3's dynamic-array ABI shape,
+//! so [`crate::real_facilitator::BlockchainFacilitatorClient::settle_batch`] can
+//! aggregate several calls into one transaction.
+
+use std::str::FromStr;
+
+use ethereum_types::{Address, U256};
+
+/// The 4-byte selector for `signature`, e.g. `"balanceOf(address)"`
+fn selector(signature: &str) -> [u8; 4] {
+    let hash = keccak256(signature.as_bytes());
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+/// ABI-encode `address` as a 32-byte word, left-padded with zeros
+fn encode_address(address: Address) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    word[12..].copy_from_slice(address.as_bytes());
+    word
+}
+
+/// ABI-encode `value` as a big-endian 32-byte word
+fn encode_u256(value: U256) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    value.to_big_endian(&mut word);
+    word
+}
+
+/// Build `0x`-prefixed calldata for `signature`, followed by its ABI-encoded `words`
+fn encode_call(signature: &str, words: &[[u8; 32]]) -> String {
+    let mut data = selector(signature).to_vec();
+    for word in words {
+        data.extend_from_slice(word);
+    }
+    format!("0x{}", hex::encode(data))
+}
+
+/// Calldata for `balanceOf(address)`
+pub fn balance_of(owner: Address) -> String {
+    encode_call("balanceOf(address)", &[encode_address(owner)])
+}
+
+/// Calldata for `decimals()`
+pub fn decimals() -> String {
+    encode_call("decimals()", &[])
+}
+
+/// Calldata for `allowance(address,address)`
+pub fn allowance(owner: Address, spender: Address) -> String {
+    encode_call("allowance(address,address)", &[encode_address(owner), encode_address(spender)])
+}
+
+/// Calldata for `transfer(address,uint256)`
+pub fn transfer(to: Address, amount: U256) -> String {
+    encode_call("transfer(address,uint256)", &[encode_address(to), encode_u256(amount)])
+}
+
+/// Decode a 32-byte hex word (as returned by `eth_call`) into a [`U256`]
+pub fn decode_u256(hex_word: &str) -> crate::Result<U256> {
+    U256::from_str_radix(hex_word.trim_start_matches("0x"), 16)
+        .map_err(|_| crate::X402Error::malformed_payload("eth_call result"))
+}
+
+/// ABI-encode `hex_str` (an already-32-byte hex value, `0x`-prefixed or not) as a
+/// 32-byte word
+fn encode_word_hex(hex_str: &str) -> crate::Result<[u8; 32]> {
+    let padded = format!("{:0>64}", hex_str.trim_start_matches("0x"));
+    if padded.len() != 64 {
+        return Err(crate::X402Error::malformed_payload("32-byte hex value"));
+    }
+    let bytes = hex::decode(&padded).map_err(|_| crate::X402Error::malformed_payload("32-byte hex value"))?;
+    let mut word = [0u8; 32];
+    word.copy_from_slice(&bytes);
+    Ok(word)
+}
+
+/// Calldata for USDC's EIP-3009
+/// `transferWithAuthorization(address,address,uint256,uint256,uint256,bytes32,uint8,bytes32,bytes32)`,
+/// pulling `v`/`r`/`s` from the payer's actual EIP-712 signature rather than
+/// placeholder zero bytes.
+///
+/// Goes through the same typed `selector`/`encode_address`/`encode_u256` path as
+/// [`balance_of`] and [`transfer`], so [`crate::real_facilitator::BlockchainFacilitatorClient`]
+/// builds its settlement calldata from this module instead of its own hand-rolled
+/// hex string concatenation.
+pub fn transfer_with_authorization(
+    auth: &crate::types::ExactEvmPayloadAuthorization,
+    signature: &crate::crypto::signature::Signature,
+) -> crate::Result<String> {
+    let from = Address::from_str(&auth.from)
+        .map_err(|_| crate::X402Error::malformed_payload("authorization.from"))?;
+    let to = Address::from_str(&auth.to)
+        .map_err(|_| crate::X402Error::malformed_payload("authorization.to"))?;
+    let value = U256::from_dec_str(&auth.value)
+        .map_err(|_| crate::X402Error::malformed_payload("authorization.value"))?;
+    let valid_after = U256::from_dec_str(&auth.valid_after)
+        .map_err(|_| crate::X402Error::malformed_payload("authorization.validAfter"))?;
+    let valid_before = U256::from_dec_str(&auth.valid_before)
+        .map_err(|_| crate::X402Error::malformed_payload("authorization.validBefore"))?;
+    let nonce = encode_word_hex(&auth.nonce)?;
+
+    // Solidity's built-in `ecrecover` expects v in {27, 28}; wallets that already
+    // return the raw recovery id (0/1) need the offset applied.
+    let v = if signature.v < 27 { signature.v + 27 } else { signature.v };
+    let mut r = [0u8; 32];
+    r.copy_from_slice(signature.r.as_bytes());
+    let mut s = [0u8; 32];
+    s.copy_from_slice(signature.s.as_bytes());
+
+    Ok(encode_call(
+        "transferWithAuthorization(address,address,uint256,uint256,uint256,bytes32,uint8,bytes32,bytes32)",
+        &[
+            encode_address(from),
+            encode_address(to),
+            encode_u256(value),
+            encode_u256(valid_after),
+            encode_u256(valid_before),
+            nonce,
+            encode_u256(U256::from(v)),
+            r,
+            s,
+        ],
+    ))
+}
+
+/// Calldata for USDC's EIP-3009 `authorizationState(address,bytes32)`, checking
+/// whether `nonce` has already been consumed (settled or canceled) for `authorizer`
+pub fn authorization_state(authorizer: Address, nonce: &str) -> crate::Result<String> {
+    Ok(encode_call(
+        "authorizationState(address,bytes32)",
+        &[encode_address(authorizer), encode_word_hex(nonce)?],
+    ))
+}
+
+/// ABI-encode `value` as a 32-byte boolean word (`0`/`1`, left-padded like any other
+/// value type)
+fn encode_bool(value: bool) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    word[31] = value as u8;
+    word
+}
+
+/// ABI-encode `data` as a dynamic `bytes` value: a length word followed by the bytes
+/// themselves, right-padded with zeros to a whole number of 32-byte words
+fn encode_bytes(data: &[u8]) -> Vec<u8> {
+    let mut out = encode_u256(U256::from(data.len())).to_vec();
+    out.extend_from_slice(data);
+    let padding = (32 - data.len() % 32) % 32;
+    out.extend(std::iter::repeat(0u8).take(padding));
+    out
+}
+
+/// Multicall3's well-known deployment address — identical across essentially every EVM
+/// chain this crate supports, since the contract is deployed deterministically via the
+/// same CREATE2 factory and salt on each of them (see <https://www.multicall3.com>).
+pub const MULTICALL3_ADDRESS: &str = "0xcA11bde05977b3631167028862bE2a173976CA11";
+
+/// Calldata for Multicall3's `aggregate3((address,bool,bytes)[])`, aggregating `calls`
+/// (each a `(target, allow_failure, call_data)` `Call3` tuple) into a single
+/// transaction. `allow_failure: true` lets that one call revert without unwinding the
+/// whole batch, so [`crate::real_facilitator::BlockchainFacilitatorClient::settle_batch`]
+/// can submit many `transferWithAuthorization` calls together and let
+/// [`decode_aggregate3_result`] report which of them actually succeeded.
+///
+/// `(address,bool,bytes)[]` is a dynamic array of dynamic tuples, so this hand-encodes
+/// the full head/tail ABI layout rather than reusing [`encode_call`], which only
+/// supports a flat list of fixed-size 32-byte words.
+pub fn aggregate3(calls: &[(Address, bool, Vec<u8>)]) -> String {
+    let element_bodies: Vec<Vec<u8>> = calls
+        .iter()
+        .map(|(target, allow_failure, call_data)| {
+            // Each Call3 tuple is itself dynamic (it contains `bytes`), so its own
+            // encoding is a 3-word head (target, allowFailure, offset-to-bytes) followed
+            // by the `bytes` tail. The offset is always 0x60 (3 words), relative to the
+            // start of this tuple's own encoding.
+            let mut body = encode_address(*target).to_vec();
+            body.extend_from_slice(&encode_bool(*allow_failure));
+            body.extend_from_slice(&encode_u256(U256::from(0x60u64)));
+            body.extend_from_slice(&encode_bytes(call_data));
+            body
+        })
+        .collect();
+
+    let heads_size = calls.len() * 32;
+    let mut heads = Vec::with_capacity(heads_size);
+    let mut tails = Vec::new();
+    let mut running_offset = heads_size;
+    for body in &element_bodies {
+        heads.extend_from_slice(&encode_u256(U256::from(running_offset as u64)));
+        tails.extend_from_slice(body);
+        running_offset += body.len();
+    }
+
+    let mut array_data = encode_u256(U256::from(calls.len() as u64)).to_vec();
+    array_data.extend(heads);
+    array_data.extend(tails);
+
+    let mut data = selector("aggregate3((address,bool,bytes)[])").to_vec();
+    data.extend_from_slice(&encode_u256(U256::from(0x20u64))); // offset to the array, the function's sole argument
+    data.extend_from_slice(&array_data);
+    format!("0x{}", hex::encode(data))
+}
+
+/// Decode the return value of an `aggregate3` call — `(bool success, bytes
+/// returnData)[]`, one entry per [`Call3`][aggregate3] in the order it was submitted —
+/// back into `(success, return_data)` pairs.
+///
+/// `data` is the raw hex result of replaying the same `aggregate3` calldata as a
+/// read-only `eth_call` at the block the real settlement transaction landed in, since
+/// a mined transaction's return value isn't included in its receipt.
+pub fn decode_aggregate3_result(data: &str) -> crate::Result<Vec<(bool, Vec<u8>)>> {
+    let bytes = hex::decode(data.trim_start_matches("0x"))
+        .map_err(|_| crate::X402Error::malformed_payload("aggregate3 return data"))?;
+    let word = |start: usize| -> crate::Result<U256> {
+        bytes
+            .get(start..start + 32)
+            .map(U256::from_big_endian)
+            .ok_or_else(|| crate::X402Error::malformed_payload("aggregate3 return data"))
+    };
+
+    let array_start = word(0)?.as_usize();
+    let count = word(array_start)?.as_usize();
+    let heads_start = array_start + 32;
+
+    let mut results = Vec::with_capacity(count);
+    for i in 0..count {
+        let elem_start = heads_start + word(heads_start + i * 32)?.as_usize();
+        let success = word(elem_start)?.as_u32() != 0;
+        let bytes_start = elem_start + word(elem_start + 32)?.as_usize();
+        let len = word(bytes_start)?.as_usize();
+        let return_data = bytes
+            .get(bytes_start + 32..bytes_start + 32 + len)
+            .ok_or_else(|| crate::X402Error::malformed_payload("aggregate3 return data"))?
+            .to_vec();
+        results.push((success, return_data));
+    }
+    Ok(results)
+}
+
+/// Decode a revert reason out of `data`, the raw hex return value of an `eth_call`
+/// replayed against a reverted transaction's calldata (or the `error.data` of an RPC
+/// node that echoes it back directly). Solidity's default `require(cond, "msg")`
+/// revert ABI-encodes `msg` as `Error(string)`: the 4-byte selector `0x08c379a0`,
+/// followed by the standard dynamic-`string` ABI encoding (an offset word, a length
+/// word, then the UTF-8 bytes).
+///
+/// Returns `None` for anything that isn't that shape — a custom Solidity error, a
+/// bare `revert()` with no message, or malformed data — since not every revert
+/// carries a decodable reason.
+pub fn decode_revert_reason(data: &str) -> Option<String> {
+    let bytes = hex::decode(data.trim_start_matches("0x")).ok()?;
+    if bytes.len() < 4 || bytes[0..4] != [0x08, 0xc3, 0x79, 0xa0] {
+        return None;
+    }
+
+    let body = &bytes[4..];
+    if body.len() < 64 {
+        return None;
+    }
+    let length = U256::from_big_endian(&body[32..64]).as_usize();
+    let string_bytes = body.get(64..64 + length)?;
+    String::from_utf8(string_bytes.to_vec()).ok()
+}
+
+/// Keccak-256 hash function
+fn keccak256(data: &[u8]) -> [u8; 32] {
+    use sha3::{Digest, Keccak256};
+    Keccak256::digest(data).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_balance_of_selector_matches_known_usdc_calldata() {
+        let owner = Address::from_str("0x0000000000000000000000000000000000000001").unwrap();
+        let calldata = balance_of(owner);
+        assert_eq!(
+            calldata,
+            "0x70a082310000000000000000000000000000000000000000000000000000000000000001"
+        );
+    }
+
+    #[test]
+    fn test_decimals_selector() {
+        // decimals() -> 0x313ce567, the well-known ERC-20 selector
+        assert_eq!(decimals(), "0x313ce567");
+    }
+
+    #[test]
+    fn test_allowance_encodes_both_addresses() {
+        let owner = Address::from_str("0x0000000000000000000000000000000000000001").unwrap();
+        let spender = Address::from_str("0x0000000000000000000000000000000000000002").unwrap();
+        let calldata = allowance(owner, spender);
+        assert!(calldata.starts_with("0xdd62ed3e"));
+        assert_eq!(calldata.len(), 2 + 8 + 64 + 64);
+        assert!(calldata.ends_with("0000000000000000000000000000000000000002"));
+    }
+
+    #[test]
+    fn test_transfer_encodes_address_then_amount() {
+        let to = Address::from_str("0x0000000000000000000000000000000000000001").unwrap();
+        let calldata = transfer(to, U256::from(1_000_000u64));
+        assert!(calldata.starts_with("0xa9059cbb"));
+        // uint256 word for 1_000_000 (0xf4240), right-aligned in the final 32 bytes
+        assert!(calldata.ends_with(&format!("{:064x}", 1_000_000u64)));
+    }
+
+    #[test]
+    fn test_authorization_state_encodes_authorizer_then_nonce() {
+        let authorizer = Address::from_str("0x0000000000000000000000000000000000000001").unwrap();
+        let nonce = "0x0000000000000000000000000000000000000000000000000000000000000002";
+        let calldata = authorization_state(authorizer, nonce).unwrap();
+        // 4-byte selector + two 32-byte words (address, then nonce)
+        assert_eq!(calldata.len(), 2 + 8 + 64 + 64);
+        assert!(calldata.ends_with(&nonce[2..]));
+        assert!(calldata[10..].starts_with(&"0".repeat(63)));
+        assert!(calldata[10..74].ends_with('1'));
+    }
+
+    #[test]
+    fn test_authorization_state_rejects_a_malformed_nonce() {
+        let authorizer = Address::from_str("0x0000000000000000000000000000000000000001").unwrap();
+        assert!(authorization_state(authorizer, "not-hex").is_err());
+    }
+
+    #[test]
+    fn test_decode_u256_round_trips_a_balance_word() {
+        let word = format!("0x{:064x}", 42u64);
+        assert_eq!(decode_u256(&word).unwrap(), U256::from(42u64));
+    }
+
+    #[test]
+    fn test_decode_u256_rejects_malformed_hex() {
+        assert!(decode_u256("0xnothex").is_err());
+    }
+
+    #[test]
+    fn test_transfer_with_authorization_selector_matches_the_function_signature() {
+        let auth = crate::types::ExactEvmPayloadAuthorization::new(
+            "0x0000000000000000000000000000000000000001",
+            "0x0000000000000000000000000000000000000002",
+            "1000000",
+            "0",
+            "9999999999",
+            "0x0000000000000000000000000000000000000000000000000000000000000001",
+        );
+        let signature = crate::crypto::signature::Signature {
+            r: ethereum_types::H256::zero(),
+            s: ethereum_types::H256::zero(),
+            v: 27,
+        };
+        let calldata = transfer_with_authorization(&auth, &signature).unwrap();
+        // keccak256("transferWithAuthorization(address,address,uint256,uint256,uint256,bytes32,uint8,bytes32,bytes32)")[..4]
+        assert_eq!(&calldata[0..10], "0xe3ee160e");
+    }
+
+    #[test]
+    fn test_transfer_with_authorization_normalizes_a_raw_recovery_id() {
+        let auth = crate::types::ExactEvmPayloadAuthorization::new(
+            "0x0000000000000000000000000000000000000001",
+            "0x0000000000000000000000000000000000000002",
+            "1000000",
+            "0",
+            "9999999999",
+            "0x0000000000000000000000000000000000000000000000000000000000000001",
+        );
+        let signature = crate::crypto::signature::Signature {
+            r: ethereum_types::H256::zero(),
+            s: ethereum_types::H256::zero(),
+            v: 0,
+        };
+        let calldata = transfer_with_authorization(&auth, &signature).unwrap();
+        // The `v` word is the 7th 32-byte word after the 4-byte selector.
+        let v_word_start = 2 + 8 + 64 * 6;
+        let v_word = &calldata[v_word_start..v_word_start + 64];
+        assert_eq!(u64::from_str_radix(v_word, 16).unwrap(), 27);
+    }
+
+    #[test]
+    fn test_transfer_with_authorization_rejects_a_non_numeric_value() {
+        let auth = crate::types::ExactEvmPayloadAuthorization::new(
+            "0x0000000000000000000000000000000000000001",
+            "0x0000000000000000000000000000000000000002",
+            "not-a-number",
+            "0",
+            "9999999999",
+            "0x0000000000000000000000000000000000000000000000000000000000000001",
+        );
+        let signature = crate::crypto::signature::Signature {
+            r: ethereum_types::H256::zero(),
+            s: ethereum_types::H256::zero(),
+            v: 27,
+        };
+        assert!(transfer_with_authorization(&auth, &signature).is_err());
+    }
+
+    #[test]
+    fn test_decode_revert_reason_extracts_a_require_message() {
+        // `Error(string)` encoding of "Insufficient balance"
+        let data = "0x08c379a0\
+            0000000000000000000000000000000000000000000000000000000000000020\
+            0000000000000000000000000000000000000000000000000000000000000014\
+            496e73756666696369656e742062616c616e6365000000000000000000000000";
+        assert_eq!(
+            decode_revert_reason(data).as_deref(),
+            Some("Insufficient balance")
+        );
+    }
+
+    #[test]
+    fn test_decode_revert_reason_returns_none_for_a_custom_error_selector() {
+        assert_eq!(decode_revert_reason("0xdeadbeef"), None);
+    }
+
+    #[test]
+    fn test_aggregate3_encodes_the_selector_and_array_length() {
+        let target_a = Address::from_str("0x0000000000000000000000000000000000000001").unwrap();
+        let target_b = Address::from_str("0x0000000000000000000000000000000000000002").unwrap();
+        let calldata = aggregate3(&[
+            (target_a, true, vec![0xde, 0xad]),
+            (target_b, false, vec![0xbe, 0xef, 0xbe, 0xef]),
+        ]);
+
+        assert_eq!(
+            &calldata[0..10],
+            format!("0x{}", hex::encode(selector("aggregate3((address,bool,bytes)[])")))
+        );
+        // Offset to the array, the function's sole argument, is always 0x20.
+        assert_eq!(&calldata[10..74], &format!("{:064x}", 0x20));
+        // Array length word comes right after: 2 calls.
+        assert_eq!(&calldata[74..138], &format!("{:064x}", 2));
+    }
+
+    #[test]
+    fn test_decode_aggregate3_result_round_trips_two_call_results() {
+        // Hand-encode `(bool,bytes)[]` the same way a node would return it from
+        // `aggregate3`: offset word, length word, per-element offsets, then each
+        // element's own (bool, bytes-offset) head and bytes tail.
+        let elem_a = {
+            let mut body = encode_bool(true).to_vec();
+            body.extend_from_slice(&encode_u256(U256::from(0x40u64)));
+            body.extend_from_slice(&encode_bytes(b"ok"));
+            body
+        };
+        let elem_b = {
+            let mut body = encode_bool(false).to_vec();
+            body.extend_from_slice(&encode_u256(U256::from(0x40u64)));
+            body.extend_from_slice(&encode_bytes(b"reverted"));
+            body
+        };
+        let heads_size = 2 * 32;
+        let mut array_data = encode_u256(U256::from(2u64)).to_vec();
+        array_data.extend_from_slice(&encode_u256(U256::from(heads_size as u64)));
+        array_data.extend_from_slice(&encode_u256(U256::from((heads_size + elem_a.len()) as u64)));
+        array_data.extend_from_slice(&elem_a);
+        array_data.extend_from_slice(&elem_b);
+
+        let mut data = encode_u256(U256::from(0x20u64)).to_vec();
+        data.extend(array_data);
+        let hex_data = format!("0x{}", hex::encode(data));
+
+        let results = decode_aggregate3_result(&hex_data).unwrap();
+        assert_eq!(results, vec![(true, b"ok".to_vec()), (false, b"reverted".to_vec())]);
+    }
+
+    #[test]
+    fn test_decode_aggregate3_result_rejects_truncated_data() {
+        assert!(decode_aggregate3_result("0x20").is_err());
+    }
+}
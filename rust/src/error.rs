@@ -1,5 +1,6 @@
 //! Error types for the x402 library
 
+use std::time::Duration;
 use thiserror::Error;
 
 #[cfg(feature = "actix-web")]
@@ -8,6 +9,48 @@ use actix_web::{HttpResponse, ResponseError};
 /// Result type alias for x402 operations
 pub type Result<T> = std::result::Result<T, X402Error>;
 
+/// Machine-readable facilitator decline/error codes
+///
+/// Mirrors the approach async-stripe takes with `RequestError`/`ErrorType`: the
+/// facilitator's JSON error body carries a stable `code`, which we parse into this
+/// enum so callers can branch on the concrete reason instead of string-matching.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FacilitatorCode {
+    /// The payer's account does not hold enough of the required asset
+    InsufficientFunds,
+    /// The authorization nonce has already been used
+    NonceReused,
+    /// The requested asset is not supported by the facilitator
+    AssetNotSupported,
+    /// Too many requests; the caller should back off
+    RateLimited,
+    /// The facilitator's upstream dependency (e.g. RPC node) is unavailable
+    UpstreamUnavailable,
+    /// A code we don't have a specific mapping for
+    #[serde(other)]
+    Unknown,
+}
+
+/// The facilitator's JSON error response body
+///
+/// Deserialized from `{ "type": ..., "code": ..., "reason": ..., "decline_code": ... }`.
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct FacilitatorErrorBody {
+    /// Broad error category reported by the facilitator
+    #[serde(rename = "type", default)]
+    pub error_type: Option<String>,
+    /// Machine-readable decline/error code
+    #[serde(default)]
+    pub code: Option<FacilitatorCode>,
+    /// Human-readable explanation
+    #[serde(default)]
+    pub reason: Option<String>,
+    /// Decline code, present for payment-declined style failures
+    #[serde(rename = "decline_code", default)]
+    pub decline_code: Option<FacilitatorCode>,
+}
+
 /// Main error type for x402 operations
 #[derive(Error, Debug)]
 pub enum X402Error {
@@ -43,6 +86,19 @@ pub enum X402Error {
     #[error("Facilitator error: {message}")]
     FacilitatorError { message: String },
 
+    /// Structured error returned by the facilitator with a machine-readable code
+    #[error("Facilitator error [{code}]: {reason}")]
+    Facilitator {
+        /// Machine-readable decline/error code
+        code: FacilitatorCode,
+        /// Human-readable reason from the facilitator
+        reason: String,
+        /// Raw JSON error body, preserved for debugging/logging
+        raw: serde_json::Value,
+        /// Delay suggested by the facilitator's `Retry-After` header, if any
+        retry_after: Option<Duration>,
+    },
+
     /// Cryptographic error
     #[error("Cryptographic error: {0}")]
     Crypto(#[from] Box<dyn std::error::Error + Send + Sync>),
@@ -59,17 +115,73 @@ pub enum X402Error {
     #[error("Network not supported: {network}")]
     NetworkNotSupported { network: String },
 
-    /// Scheme not supported
-    #[error("Scheme not supported: {scheme}")]
-    SchemeNotSupported { scheme: String },
+    /// A payment scheme has no mutually-supported x402 protocol version on the
+    /// given network, per a facilitator's `/supported` document
+    #[error("Scheme {scheme} on network {network} not supported: facilitator advertises versions {available:?}")]
+    SchemeNotSupported {
+        scheme: String,
+        network: String,
+        available: Vec<u32>,
+    },
+
+    /// No x402 protocol version is supported by both sides of a negotiation (see
+    /// [`crate::facilitator::FacilitatorClient::negotiate_version`]), carrying the
+    /// highest version each side advertised so the caller can see how far apart
+    /// they are rather than just that negotiation failed
+    #[error("No compatible x402 protocol version: server supports up to {server}, client supports up to {client}")]
+    VersionMismatch { server: u32, client: u32 },
+
+    /// `PaymentRequirements` named a `(scheme, network, asset)` combination that
+    /// isn't in the facilitator's advertised `/supported` document at all —
+    /// distinct from [`Self::SchemeNotSupported`], which is a scheme/network
+    /// pairing the facilitator does list but with no overlapping protocol version.
+    /// Caught by a capability check run ahead of `verify`/`settle`, so this surfaces
+    /// as an immediate, precise rejection instead of a late settlement failure.
+    #[error("Facilitator does not support scheme {scheme} on network {network} for asset {asset}")]
+    UnsupportedByFacilitator {
+        scheme: String,
+        network: String,
+        asset: String,
+    },
+
+    /// None of a 402 response's offered `accepts` entries has a `(scheme, network)`
+    /// pairing the facilitator's `/supported` document lists with a mutually
+    /// compatible protocol version, returned by
+    /// [`crate::facilitator::FacilitatorClient::negotiate_requirements`] instead of
+    /// picking one of them anyway and failing later at `verify`
+    #[error("No offered payment requirements are supported by the facilitator: offered {offered:?}, facilitator supports {available:?}")]
+    NoSupportedRequirements {
+        offered: Vec<(String, String)>,
+        available: Vec<(String, String, u32)>,
+    },
+
+    /// Invalid per-network configuration (e.g. a malformed contract address)
+    #[error("Invalid network configuration: {message}")]
+    InvalidNetwork { message: String },
+
+    /// A payment payload field is missing or fails basic structural validation
+    /// (non-hex signature/nonce, non-numeric value/timestamp, ...), caught locally
+    /// instead of being forwarded to the facilitator as a well-formed-looking
+    /// bogus request
+    #[error("Malformed payment payload field: {field}")]
+    MalformedPayload { field: String },
+
+    /// Malformed BOLT12 offer
+    #[error("Invalid Lightning offer: {message}")]
+    InvalidLightningOffer { message: String },
+
+    /// Malformed or non-matching BOLT12 invoice
+    #[error("Invalid Lightning invoice: {message}")]
+    InvalidLightningInvoice { message: String },
 
     /// Insufficient funds
     #[error("Insufficient funds")]
     InsufficientFunds,
 
-    /// Authorization expired
-    #[error("Authorization expired")]
-    AuthorizationExpired,
+    /// Authorization expired, carrying the boundary it was checked against so callers
+    /// can log or display how late it was rather than just that it happened
+    #[error("Authorization expired: valid_before {valid_before}, now {now}")]
+    AuthorizationExpired { valid_before: i64, now: i64 },
 
     /// Authorization not yet valid
     #[error("Authorization not yet valid")]
@@ -83,6 +195,37 @@ pub enum X402Error {
     #[error("Recipient mismatch: expected {expected}, got {got}")]
     RecipientMismatch { expected: String, got: String },
 
+    /// A payment payload's network doesn't match the one its requirements specify
+    #[error("Network mismatch detected: expected {expected}, got {got}")]
+    WrongNetwork { expected: String, got: String },
+
+    /// A payment payload's scheme doesn't match the one its requirements specify
+    #[error("Scheme mismatch detected: expected {expected}, got {actual}")]
+    SchemeMismatch { expected: String, actual: String },
+
+    /// A payment payload's asset (or pay-to address) doesn't match the one its
+    /// requirements specify
+    #[error("Asset mismatch detected: expected {expected}, got {actual}")]
+    AssetMismatch { expected: String, actual: String },
+
+    /// A recovered signer address doesn't match the address the payload claims signed
+    /// it
+    #[error("Signature mismatch: recovered {recovered}, expected {expected}")]
+    SignatureMismatch { recovered: String, expected: String },
+
+    /// An EIP-3009 authorization nonce has already been settled and can't be reused
+    #[error("Nonce already used: {nonce}")]
+    NonceAlreadyUsed { nonce: String },
+
+    /// `payer` re-presented `nonce` while an earlier authorization using the same pair
+    /// is still inside its `valid_before` window, per
+    /// [`crate::nonce_store::NonceReplayStore`]. Distinct from
+    /// [`Self::NonceAlreadyUsed`], which a facilitator raises once a nonce has actually
+    /// been settled; this one fires earlier, at verify time, before settlement ever
+    /// happens.
+    #[error("Nonce reused by {payer}: {nonce}")]
+    NonceReused { payer: String, nonce: String },
+
     /// Unexpected error
     #[error("Unexpected error: {message}")]
     Unexpected { message: String },
@@ -95,9 +238,88 @@ pub enum X402Error {
     #[error("Request timeout")]
     Timeout,
 
+    /// A [`crate::client::X402Client`] request exceeded its per-request "slow
+    /// request" timeout (set via [`crate::client::X402RequestBuilder::timeout`]),
+    /// distinct from the underlying `reqwest::Client`'s connection-establishment
+    /// timeout. Carries the URL and elapsed time so a caller making many automated
+    /// micropayments can log or back off on the specific slow endpoint instead of
+    /// seeing an opaque [`Self::Http`] wrapping a bare `reqwest::Error`
+    #[error("Request to {url} timed out after {elapsed_ms}ms")]
+    RequestTimedOut { url: String, elapsed_ms: u64 },
+
     /// IO error
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
+
+    /// A retry loop's attempt budget was spent without a successful response
+    #[error("Retries exhausted after {attempts} attempt(s): {last_error}")]
+    RetriesExhausted {
+        /// Total number of attempts made, including the first
+        attempts: u32,
+        /// The error the final attempt failed with
+        last_error: Box<X402Error>,
+    },
+
+    /// A transport-level failure talking to an RPC node: a dropped connection,
+    /// timeout, or an HTTP 429/5xx response. Distinct from [`Self::RpcRejected`],
+    /// which is a well-formed JSON-RPC response the node declined to execute;
+    /// retryable (and, for [`crate::blockchain::BlockchainClient`], worth rotating to
+    /// the next configured endpoint over).
+    #[error("Network error: {message}")]
+    NetworkError { message: String },
+
+    /// A JSON-RPC node responded with a well-formed `error` field (e.g. "nonce too
+    /// low", "insufficient funds for gas"). The request reached the node and was
+    /// evaluated; retrying against another endpoint would just get the same answer,
+    /// so this is never retryable.
+    #[error("RPC call rejected: {message}")]
+    RpcRejected { message: String },
+
+    /// A transaction mined but reverted on-chain (its receipt's `status` is `"0x0"`),
+    /// distinct from never mining at all; see
+    /// [`crate::blockchain::BlockchainClient::watch_transaction`].
+    ///
+    /// `reason` is the decoded `require(cond, "msg")` message, when
+    /// [`crate::blockchain::BlockchainClient::get_revert_reason`] managed to recover
+    /// one by replaying the call — `None` for a custom Solidity error, a bare
+    /// `revert()`, or a node that doesn't echo call-trace data back.
+    #[error(
+        "Transaction {tx_hash} reverted on-chain{}",
+        reason.as_ref().map(|r| format!(": {r}")).unwrap_or_default()
+    )]
+    TransactionReverted {
+        tx_hash: String,
+        reason: Option<String>,
+    },
+
+    /// A [`crate::idempotency::IdempotentSettlement`] gave up on a payment after
+    /// exhausting its configured attempt budget across repeated `settle` calls for the
+    /// same [`crate::idempotency::PaymentId`], and the id is now permanently marked
+    /// [`crate::idempotency::IdempotencyState::Abandoned`] — distinct from
+    /// [`Self::RetriesExhausted`], which is a single call's internal backoff loop
+    /// giving up, not a terminal, persisted verdict for the payment itself
+    #[error("Settlement for payment {payment_id} abandoned after {attempts} attempt(s): {reason}")]
+    SettlementAbandoned {
+        payment_id: String,
+        attempts: u32,
+        reason: String,
+    },
+
+    /// [`crate::settlement_receipt_check::SettlementReceiptCheck::wait_for_confirmation`]
+    /// gave up on a facilitator-reported settlement transaction: either it never
+    /// mined within the configured timeout, or it mined but its receipt didn't back
+    /// up the claimed transfer (`reason` names which)
+    #[error("Settlement transaction {tx_hash} not confirmed: {reason}")]
+    SettlementNotConfirmed { tx_hash: String, reason: String },
+
+    /// A facilitator verified a payment but has no way to actually settle it —
+    /// e.g. [`crate::solana_facilitator::SolanaFacilitatorClient`] has no RPC client
+    /// to submit an on-chain transfer with. Distinct from
+    /// [`Self::PaymentSettlementFailed`], which means settlement was attempted and
+    /// rejected; this means settlement was never attempted at all, so callers must
+    /// not treat it as a completed (or even failed-but-tried) payment.
+    #[error("Settlement is not implemented for this facilitator: {reason}")]
+    SettlementNotImplemented { reason: String },
 }
 
 impl X402Error {
@@ -129,6 +351,23 @@ impl X402Error {
         }
     }
 
+    /// Create a settlement-not-implemented error naming why this facilitator can't
+    /// actually settle the payment it just verified
+    pub fn settlement_not_implemented(reason: impl Into<String>) -> Self {
+        Self::SettlementNotImplemented {
+            reason: reason.into(),
+        }
+    }
+
+    /// Create a settlement-not-confirmed error naming the transaction and why it
+    /// wasn't accepted
+    pub fn settlement_not_confirmed(tx_hash: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self::SettlementNotConfirmed {
+            tx_hash: tx_hash.into(),
+            reason: reason.into(),
+        }
+    }
+
     /// Create a facilitator error
     pub fn facilitator_error(message: impl Into<String>) -> Self {
         Self::FacilitatorError {
@@ -136,6 +375,93 @@ impl X402Error {
         }
     }
 
+    /// Build a structured facilitator error from its parsed JSON error body
+    ///
+    /// Prefers `decline_code` over `code` when both are present, since the decline
+    /// code is the more specific reason for a payment rejection.
+    pub fn from_facilitator_body(body: FacilitatorErrorBody, raw: serde_json::Value) -> Self {
+        Self::from_facilitator_body_with_retry_after(body, raw, None)
+    }
+
+    /// Same as [`Self::from_facilitator_body`], additionally threading through a
+    /// `Retry-After` delay parsed from the response headers.
+    pub fn from_facilitator_body_with_retry_after(
+        body: FacilitatorErrorBody,
+        raw: serde_json::Value,
+        retry_after: Option<Duration>,
+    ) -> Self {
+        let code = body
+            .decline_code
+            .or(body.code)
+            .unwrap_or(FacilitatorCode::Unknown);
+        let reason = body
+            .reason
+            .or(body.error_type)
+            .unwrap_or_else(|| "unknown facilitator error".to_string());
+
+        Self::Facilitator {
+            code,
+            reason,
+            raw,
+            retry_after,
+        }
+    }
+
+    /// Whether this error represents a transient failure worth retrying.
+    ///
+    /// Permanent failures (bad signature, insufficient funds, recipient mismatch, ...)
+    /// return `false` so callers don't waste a retry budget on something that will
+    /// never succeed.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::Http(_) | Self::Timeout | Self::RequestTimedOut { .. } => true,
+            Self::Facilitator { code, .. } => matches!(
+                code,
+                FacilitatorCode::RateLimited | FacilitatorCode::UpstreamUnavailable
+            ),
+            Self::FacilitatorError { .. } => self.status_code() >= 500,
+            Self::InvalidSignature { .. }
+            | Self::InsufficientFunds
+            | Self::RecipientMismatch { .. }
+            | Self::WrongNetwork { .. }
+            | Self::SchemeMismatch { .. }
+            | Self::AssetMismatch { .. }
+            | Self::SignatureMismatch { .. }
+            | Self::NonceAlreadyUsed { .. }
+            | Self::NonceReused { .. }
+            | Self::InvalidPaymentPayload { .. }
+            | Self::InvalidPaymentRequirements { .. }
+            | Self::InvalidAuthorization { .. }
+            | Self::AuthorizationExpired { .. }
+            | Self::AuthorizationNotYetValid
+            | Self::InvalidAmount { .. }
+            | Self::NetworkNotSupported { .. }
+            | Self::VersionMismatch { .. }
+            | Self::SchemeNotSupported { .. }
+            | Self::UnsupportedByFacilitator { .. }
+            | Self::NoSupportedRequirements { .. }
+            | Self::MalformedPayload { .. }
+            | Self::InvalidNetwork { .. }
+            | Self::InvalidLightningOffer { .. }
+            | Self::InvalidLightningInvoice { .. }
+            | Self::RetriesExhausted { .. }
+            | Self::RpcRejected { .. }
+            | Self::TransactionReverted { .. }
+            | Self::SettlementNotConfirmed { .. }
+            | Self::SettlementNotImplemented { .. } => false,
+            Self::NetworkError { .. } => true,
+            _ => false,
+        }
+    }
+
+    /// Delay suggested by the facilitator before retrying, if one was provided.
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Self::Facilitator { retry_after, .. } => *retry_after,
+            _ => None,
+        }
+    }
+
     /// Create an invalid signature error
     pub fn invalid_signature(message: impl Into<String>) -> Self {
         Self::InvalidSignature {
@@ -150,6 +476,135 @@ impl X402Error {
         }
     }
 
+    /// Create a malformed payload error naming the offending field
+    pub fn malformed_payload(field: impl Into<String>) -> Self {
+        Self::MalformedPayload {
+            field: field.into(),
+        }
+    }
+
+    /// Create an invalid network configuration error
+    pub fn invalid_network(message: impl Into<String>) -> Self {
+        Self::InvalidNetwork {
+            message: message.into(),
+        }
+    }
+
+    /// Create a network-mismatch error: the payload's network doesn't match the
+    /// network its requirements specify
+    pub fn wrong_network(expected: impl Into<String>, got: impl Into<String>) -> Self {
+        Self::WrongNetwork {
+            expected: expected.into(),
+            got: got.into(),
+        }
+    }
+
+    /// Create a scheme-mismatch error: the payload's scheme doesn't match the
+    /// scheme its requirements specify
+    pub fn scheme_mismatch(expected: impl Into<String>, actual: impl Into<String>) -> Self {
+        Self::SchemeMismatch {
+            expected: expected.into(),
+            actual: actual.into(),
+        }
+    }
+
+    /// Create an asset-mismatch error: the payload's asset (or pay-to address)
+    /// doesn't match the one its requirements specify
+    pub fn asset_mismatch(expected: impl Into<String>, actual: impl Into<String>) -> Self {
+        Self::AssetMismatch {
+            expected: expected.into(),
+            actual: actual.into(),
+        }
+    }
+
+    /// Create a version-mismatch error: `server` and `client` had no protocol
+    /// version in common during negotiation
+    pub fn version_mismatch(server: u32, client: u32) -> Self {
+        Self::VersionMismatch { server, client }
+    }
+
+    /// Create a scheme-not-supported error naming the versions a facilitator does
+    /// advertise for `scheme`/`network`, so the caller can see how close it came to
+    /// negotiating a shared x402 version
+    pub fn scheme_not_supported(
+        scheme: impl Into<String>,
+        network: impl Into<String>,
+        available: Vec<u32>,
+    ) -> Self {
+        Self::SchemeNotSupported {
+            scheme: scheme.into(),
+            network: network.into(),
+            available,
+        }
+    }
+
+    /// Create an error reporting that a facilitator's `/supported` document has no
+    /// entry at all for `scheme`/`network`/`asset`
+    pub fn unsupported_by_facilitator(
+        scheme: impl Into<String>,
+        network: impl Into<String>,
+        asset: impl Into<String>,
+    ) -> Self {
+        Self::UnsupportedByFacilitator {
+            scheme: scheme.into(),
+            network: network.into(),
+            asset: asset.into(),
+        }
+    }
+
+    /// Create a no-supported-requirements error naming every `(scheme, network)`
+    /// the caller offered and every `(scheme, network, x402_version)` the
+    /// facilitator actually advertises, so the caller can see exactly how far
+    /// apart the two sides are
+    pub fn no_supported_requirements(
+        offered: Vec<(String, String)>,
+        available: Vec<(String, String, u32)>,
+    ) -> Self {
+        Self::NoSupportedRequirements { offered, available }
+    }
+
+    /// Create a signature-mismatch error: the recovered signer doesn't match the
+    /// address the payload claims signed it
+    pub fn signature_mismatch(recovered: impl Into<String>, expected: impl Into<String>) -> Self {
+        Self::SignatureMismatch {
+            recovered: recovered.into(),
+            expected: expected.into(),
+        }
+    }
+
+    /// Create a nonce-already-used error
+    pub fn nonce_already_used(nonce: impl Into<String>) -> Self {
+        Self::NonceAlreadyUsed { nonce: nonce.into() }
+    }
+
+    /// Create a nonce-reused error, naming the payer that re-presented `nonce`
+    pub fn nonce_reused(payer: impl Into<String>, nonce: impl Into<String>) -> Self {
+        Self::NonceReused {
+            payer: payer.into(),
+            nonce: nonce.into(),
+        }
+    }
+
+    /// Create an authorization-expired error naming the boundary it was checked
+    /// against
+    pub fn authorization_expired(valid_before: i64, now: i64) -> Self {
+        Self::AuthorizationExpired { valid_before, now }
+    }
+
+    /// Create an invalid Lightning offer error
+    pub fn invalid_lightning_offer(message: impl Into<String>) -> Self {
+        Self::InvalidLightningOffer {
+            message: message.into(),
+        }
+    }
+
+    /// Create an invalid Lightning invoice error
+    pub fn invalid_lightning_invoice(message: impl Into<String>) -> Self {
+        Self::InvalidLightningInvoice {
+            message: message.into(),
+        }
+    }
+
     /// Create an unexpected error
     pub fn unexpected(message: impl Into<String>) -> Self {
         Self::Unexpected {
@@ -164,6 +619,71 @@ impl X402Error {
         }
     }
 
+    /// Create a per-request timeout error naming the URL that timed out and how
+    /// long the caller waited before giving up
+    pub fn request_timed_out(url: impl Into<String>, elapsed: Duration) -> Self {
+        Self::RequestTimedOut {
+            url: url.into(),
+            elapsed_ms: elapsed.as_millis() as u64,
+        }
+    }
+
+    /// Create a network/transport error (dropped connection, timeout, HTTP 429/5xx)
+    pub fn network_error(message: impl Into<String>) -> Self {
+        Self::NetworkError {
+            message: message.into(),
+        }
+    }
+
+    /// Create an error for a JSON-RPC call the node evaluated and declined
+    pub fn rpc_rejected(message: impl Into<String>) -> Self {
+        Self::RpcRejected {
+            message: message.into(),
+        }
+    }
+
+    /// Create an error reporting that `tx_hash` mined but reverted on-chain, with no
+    /// decoded revert reason
+    pub fn transaction_reverted(tx_hash: impl Into<String>) -> Self {
+        Self::TransactionReverted {
+            tx_hash: tx_hash.into(),
+            reason: None,
+        }
+    }
+
+    /// Create an error reporting that `tx_hash` mined but reverted on-chain, with a
+    /// `reason` decoded from replaying its call (see
+    /// [`crate::blockchain::BlockchainClient::get_revert_reason`])
+    pub fn transaction_reverted_with_reason(tx_hash: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self::TransactionReverted {
+            tx_hash: tx_hash.into(),
+            reason: Some(reason.into()),
+        }
+    }
+
+    /// Create an error reporting that settlement for `payment_id` was abandoned after
+    /// `attempts` attempts, with `reason` describing the last failure
+    pub fn settlement_abandoned(
+        payment_id: impl Into<String>,
+        attempts: u32,
+        reason: impl Into<String>,
+    ) -> Self {
+        Self::SettlementAbandoned {
+            payment_id: payment_id.into(),
+            attempts,
+            reason: reason.into(),
+        }
+    }
+
+    /// Wrap `last_error` as a retries-exhausted error, recording how many attempts
+    /// (including the first) were made before giving up
+    pub fn retries_exhausted(attempts: u32, last_error: X402Error) -> Self {
+        Self::RetriesExhausted {
+            attempts,
+            last_error: Box::new(last_error),
+        }
+    }
+
     /// Get HTTP status code for this error
     pub fn status_code(&self) -> u16 {
         match self {
@@ -172,23 +692,51 @@ impl X402Error {
             Self::PaymentVerificationFailed { .. } => 402,
             Self::PaymentSettlementFailed { .. } => 402,
             Self::FacilitatorError { .. } => 502,
+            Self::Facilitator { code, .. } => match code {
+                FacilitatorCode::InsufficientFunds => 402,
+                FacilitatorCode::NonceReused => 409,
+                FacilitatorCode::AssetNotSupported => 400,
+                FacilitatorCode::RateLimited => 429,
+                FacilitatorCode::UpstreamUnavailable => 502,
+                FacilitatorCode::Unknown => 502,
+            },
             Self::InvalidSignature { .. } => 400,
             Self::InvalidAuthorization { .. } => 401,
             Self::NetworkNotSupported { .. } => 400,
+            Self::VersionMismatch { .. } => 400,
             Self::SchemeNotSupported { .. } => 400,
+            Self::UnsupportedByFacilitator { .. } => 400,
+            Self::NoSupportedRequirements { .. } => 400,
+            Self::MalformedPayload { .. } => 400,
+            Self::InvalidNetwork { .. } => 400,
+            Self::InvalidLightningOffer { .. } => 400,
+            Self::InvalidLightningInvoice { .. } => 400,
             Self::InsufficientFunds => 402,
-            Self::AuthorizationExpired => 401,
+            Self::AuthorizationExpired { .. } => 401,
             Self::AuthorizationNotYetValid => 401,
             Self::InvalidAmount { .. } => 400,
             Self::RecipientMismatch { .. } => 400,
+            Self::WrongNetwork { .. } => 400,
+            Self::SchemeMismatch { .. } => 400,
+            Self::AssetMismatch { .. } => 400,
+            Self::SignatureMismatch { .. } => 400,
+            Self::NonceAlreadyUsed { .. } => 409,
+            Self::NonceReused { .. } => 409,
             Self::Unexpected { .. } => 500,
             Self::Config { .. } => 500,
             Self::Timeout => 408,
+            Self::RequestTimedOut { .. } => 408,
             Self::Json(_) => 400,
             Self::Http(_) => 502,
             Self::Base64(_) => 400,
             Self::Crypto(_) => 500,
             Self::Io(_) => 500,
+            Self::RetriesExhausted { last_error, .. } => last_error.status_code(),
+            Self::NetworkError { .. } => 502,
+            Self::RpcRejected { .. } => 400,
+            Self::TransactionReverted { .. } => 402,
+            Self::SettlementAbandoned { .. } => 402,
+            Self::SettlementNotConfirmed { .. } => 402,
         }
     }
 
@@ -200,23 +748,51 @@ impl X402Error {
             Self::PaymentVerificationFailed { .. } => "payment_verification_failed",
             Self::PaymentSettlementFailed { .. } => "payment_settlement_failed",
             Self::FacilitatorError { .. } => "facilitator_error",
+            Self::Facilitator { code, .. } => match code {
+                FacilitatorCode::InsufficientFunds => "insufficient_funds",
+                FacilitatorCode::NonceReused => "nonce_reused",
+                FacilitatorCode::AssetNotSupported => "asset_not_supported",
+                FacilitatorCode::RateLimited => "rate_limited",
+                FacilitatorCode::UpstreamUnavailable => "upstream_unavailable",
+                FacilitatorCode::Unknown => "facilitator_error",
+            },
             Self::InvalidSignature { .. } => "invalid_signature",
             Self::InvalidAuthorization { .. } => "invalid_authorization",
             Self::NetworkNotSupported { .. } => "network_not_supported",
+            Self::VersionMismatch { .. } => "version_mismatch",
             Self::SchemeNotSupported { .. } => "scheme_not_supported",
+            Self::UnsupportedByFacilitator { .. } => "unsupported_by_facilitator",
+            Self::NoSupportedRequirements { .. } => "no_supported_requirements",
+            Self::MalformedPayload { .. } => "malformed_payload",
+            Self::InvalidNetwork { .. } => "invalid_network",
+            Self::InvalidLightningOffer { .. } => "invalid_lightning_offer",
+            Self::InvalidLightningInvoice { .. } => "invalid_lightning_invoice",
             Self::InsufficientFunds => "insufficient_funds",
-            Self::AuthorizationExpired => "authorization_expired",
+            Self::AuthorizationExpired { .. } => "authorization_expired",
             Self::AuthorizationNotYetValid => "authorization_not_yet_valid",
             Self::InvalidAmount { .. } => "invalid_amount",
             Self::RecipientMismatch { .. } => "recipient_mismatch",
+            Self::WrongNetwork { .. } => "wrong_network",
+            Self::SchemeMismatch { .. } => "scheme_mismatch",
+            Self::AssetMismatch { .. } => "asset_mismatch",
+            Self::SignatureMismatch { .. } => "signature_mismatch",
+            Self::NonceAlreadyUsed { .. } => "nonce_already_used",
+            Self::NonceReused { .. } => "nonce_reused",
             Self::Unexpected { .. } => "unexpected_error",
             Self::Config { .. } => "configuration_error",
             Self::Timeout => "timeout",
+            Self::RequestTimedOut { .. } => "request_timed_out",
             Self::Json(_) => "json_error",
             Self::Http(_) => "http_error",
             Self::Base64(_) => "base64_error",
             Self::Crypto(_) => "crypto_error",
             Self::Io(_) => "io_error",
+            Self::RetriesExhausted { .. } => "retries_exhausted",
+            Self::NetworkError { .. } => "network_error",
+            Self::RpcRejected { .. } => "rpc_rejected",
+            Self::TransactionReverted { .. } => "transaction_reverted",
+            Self::SettlementAbandoned { .. } => "settlement_abandoned",
+            Self::SettlementNotConfirmed { .. } => "settlement_not_confirmed",
         }
     }
 }
@@ -242,12 +818,17 @@ pub struct ErrorResponse {
 impl ErrorResponse {
     /// Create a new error response from X402Error
     pub fn from_x402_error(error: &X402Error) -> Self {
+        let details = match error {
+            X402Error::Facilitator { raw, .. } => Some(raw.clone()),
+            _ => None,
+        };
+
         Self {
             error: error.to_string(),
             error_type: error.error_type().to_string(),
             status_code: error.status_code(),
             x402_version: 1,
-            details: None,
+            details,
         }
     }
 
@@ -285,3 +866,37 @@ impl ResponseError for X402Error {
         HttpResponse::build(status_code).json(error_response)
     }
 }
+
+#[cfg(feature = "axum")]
+impl axum::response::IntoResponse for X402Error {
+    fn into_response(self) -> axum::response::Response {
+        let error_response = ErrorResponse::from_x402_error(&self);
+        let status_code = axum::http::StatusCode::from_u16(self.status_code())
+            .unwrap_or(axum::http::StatusCode::INTERNAL_SERVER_ERROR);
+
+        (status_code, axum::Json(error_response)).into_response()
+    }
+}
+
+/// Convert an x402 error into a framework-neutral `http::Response`
+///
+/// For tower/hyper consumers that don't want to pull in a full framework just to emit a
+/// 402 response. The body is the same JSON [`ErrorResponse`] the actix-web and axum
+/// integrations serialize.
+#[cfg(feature = "http")]
+impl TryFrom<&X402Error> for http::Response<Vec<u8>> {
+    type Error = X402Error;
+
+    fn try_from(error: &X402Error) -> std::result::Result<Self, Self::Error> {
+        let error_response = ErrorResponse::from_x402_error(error);
+        let body = serde_json::to_vec(&error_response)?;
+        let status = http::StatusCode::from_u16(error.status_code())
+            .unwrap_or(http::StatusCode::INTERNAL_SERVER_ERROR);
+
+        http::Response::builder()
+            .status(status)
+            .header(http::header::CONTENT_TYPE, "application/json")
+            .body(body)
+            .map_err(|e| X402Error::unexpected(e.to_string()))
+    }
+}
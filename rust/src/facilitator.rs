@@ -3,13 +3,73 @@
 use crate::types::*;
 use crate::{Result, X402Error};
 use crate::client::DiscoveryFilters;
-use reqwest::Client;
+use crate::error::FacilitatorErrorBody;
+use rand::Rng;
+use reqwest::{Client, Response, StatusCode};
 use serde_json::json;
 use std::collections::HashMap;
 
 /// Default facilitator URL
 pub const DEFAULT_FACILITATOR_URL: &str = "https://x402.org/facilitator";
 
+/// Parse a non-success facilitator response into an `X402Error`
+///
+/// Attempts to decode the body as a [`FacilitatorErrorBody`] so callers get a
+/// structured [`X402Error::Facilitator`] with a machine-readable code. Falls back to
+/// the opaque [`X402Error::FacilitatorError`] if the body isn't JSON or doesn't match
+/// the expected shape.
+async fn parse_facilitator_error(response: Response, status: StatusCode, action: &str) -> X402Error {
+    let retry_after = response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(crate::retry::parse_retry_after);
+
+    let raw = match response.json::<serde_json::Value>().await {
+        Ok(value) => value,
+        Err(_) => {
+            return X402Error::facilitator_error(format!(
+                "{} failed with status: {}",
+                action, status
+            ))
+        }
+    };
+
+    match serde_json::from_value::<FacilitatorErrorBody>(raw.clone()) {
+        Ok(body) if body.code.is_some() || body.decline_code.is_some() || body.reason.is_some() => {
+            X402Error::from_facilitator_body_with_retry_after(body, raw, retry_after)
+        }
+        _ => X402Error::facilitator_error(format!("{} failed with status: {}", action, status)),
+    }
+}
+
+/// Same as [`parse_facilitator_error`], but parses from an already-read body buffer
+/// instead of consuming a [`Response`] — used on the paths that fire a
+/// [`ResponseHook`] first, since a response body can only be read once
+fn parse_facilitator_error_from_bytes(
+    body_bytes: &[u8],
+    status: StatusCode,
+    retry_after: Option<std::time::Duration>,
+    action: &str,
+) -> X402Error {
+    let raw = match serde_json::from_slice::<serde_json::Value>(body_bytes) {
+        Ok(value) => value,
+        Err(_) => {
+            return X402Error::facilitator_error(format!(
+                "{} failed with status: {}",
+                action, status
+            ))
+        }
+    };
+
+    match serde_json::from_value::<FacilitatorErrorBody>(raw.clone()) {
+        Ok(body) if body.code.is_some() || body.decline_code.is_some() || body.reason.is_some() => {
+            X402Error::from_facilitator_body_with_retry_after(body, raw, retry_after)
+        }
+        _ => X402Error::facilitator_error(format!("{} failed with status: {}", action, status)),
+    }
+}
+
 /// Facilitator client for verifying and settling payments
 #[derive(Clone)]
 pub struct FacilitatorClient {
@@ -19,6 +79,42 @@ pub struct FacilitatorClient {
     client: Client,
     /// Configuration for authentication headers
     auth_config: Option<crate::types::AuthHeadersFnArc>,
+    /// Retry policy for transient verify/settle failures
+    retry_policy: Option<crate::retry::RetryPolicy>,
+    /// HTTP Signatures config for authenticating outbound requests
+    http_signature: Option<crate::http_signature::HttpSignatureConfig>,
+    /// Termination condition for retries, set via [`Self::with_retry`]. Takes
+    /// priority over `retry_policy`'s plain attempt-count gate when present, letting
+    /// a caller bound retries by a monotonic-clock deadline instead of just a count.
+    retry: Option<crate::retry::Retry>,
+    /// Cached result of [`Self::supported_versions`], so repeated calls to
+    /// [`Self::negotiate_version`] don't round-trip to `/supported` every time.
+    /// Refreshed once older than [`Self::VERSION_CACHE_TTL`].
+    version_cache: std::sync::Arc<tokio::sync::Mutex<Option<(Vec<u32>, std::time::Instant)>>>,
+    /// Cached result of the facilitator's full `/supported` document, consulted by
+    /// [`Self::supported_cached`] and [`Self::verify_with_network_validation`] so a
+    /// `(scheme, network)` pairing can be checked against the facilitator's
+    /// advertised capabilities without a round trip on every `verify` call.
+    /// Refreshed once older than [`Self::supported_cache_ttl`], or on demand via
+    /// [`Self::refresh_supported`].
+    supported_cache: std::sync::Arc<tokio::sync::Mutex<Option<(SupportedKinds, std::time::Instant)>>>,
+    /// How long [`Self::supported_cache`] is trusted before [`Self::supported_cached`]
+    /// fetches a fresh `/supported`, set via [`FacilitatorConfig::with_supported_cache_ttl`]
+    supported_cache_ttl: std::time::Duration,
+    /// Observes every facilitator HTTP response, set via [`Self::with_response_hook`]
+    response_hook: Option<std::sync::Arc<dyn ResponseHook>>,
+    /// Consulted by [`Self::verify`] before every request, set via
+    /// [`FacilitatorConfig::with_nonce_replay_store`]
+    nonce_replay_store: Option<std::sync::Arc<dyn crate::nonce_store::NonceReplayStore>>,
+    /// Consulted by [`Self::settle`] to collapse concurrent/retried settlements for
+    /// the same payment, set via [`FacilitatorConfig::with_idempotency_store`]
+    idempotency_store: Option<std::sync::Arc<dyn crate::idempotency::IdempotencyStore>>,
+    /// Route/header overrides for non-Coinbase facilitators, set via
+    /// [`FacilitatorConfig::with_provider`]
+    provider: Option<std::sync::Arc<dyn FacilitatorProvider>>,
+    /// Async, per-endpoint credential source layered on top of `auth_config` and
+    /// `provider`'s headers, set via [`FacilitatorConfig::with_auth_provider`]
+    auth_provider: Option<std::sync::Arc<dyn AuthProvider>>,
 }
 
 impl std::fmt::Debug for FacilitatorClient {
@@ -26,10 +122,222 @@ impl std::fmt::Debug for FacilitatorClient {
         f.debug_struct("FacilitatorClient")
             .field("url", &self.url)
             .field("auth_config", &"<function>")
+            .field("retry_policy", &self.retry_policy)
+            .field("http_signature", &self.http_signature)
+            .field("retry", &self.retry)
+            .field("response_hook", &self.response_hook.as_ref().map(|_| "<hook>"))
+            .field("nonce_replay_store", &self.nonce_replay_store.is_some())
+            .field("idempotency_store", &self.idempotency_store.is_some())
+            .field("provider", &self.provider.is_some())
+            .field("supported_cache_ttl", &self.supported_cache_ttl)
+            .field("auth_provider", &self.auth_provider.is_some())
             .finish()
     }
 }
 
+/// Customizes the routes and per-operation headers [`FacilitatorClient`] uses,
+/// letting a facilitator other than Coinbase's (different base paths, different
+/// auth scheme) be dropped in by implementing this trait instead of forking the
+/// client, set via [`FacilitatorConfig::with_provider`].
+///
+/// Headers returned here are applied in addition to, and after,
+/// [`FacilitatorConfig::with_auth_headers`]'s, so a provider only needs to add what
+/// that closure doesn't already cover.
+pub trait FacilitatorProvider: Send + Sync {
+    /// The path to request for `operation` (one of `"verify"`, `"settle"` or
+    /// `"supported"`), joined directly onto [`FacilitatorClient::url`] with no
+    /// separator inserted — include the leading `/`
+    fn path(&self, operation: &str) -> String;
+
+    /// Extra headers to attach to `operation`'s request
+    fn headers(&self, operation: &str) -> Result<HashMap<String, String>>;
+}
+
+/// A [`FacilitatorClient`] HTTP endpoint, letting an [`AuthProvider`] scope
+/// credentials per call the same way the per-endpoint header map
+/// [`FacilitatorConfig::with_auth_headers`] already accepts does
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Endpoint {
+    Verify,
+    Settle,
+    Supported,
+    Discovery,
+}
+
+/// Supplies per-request authentication headers for a [`FacilitatorClient`], set via
+/// [`FacilitatorConfig::with_auth_provider`].
+///
+/// Unlike the synchronous closure [`FacilitatorConfig::with_auth_headers`] accepts,
+/// `headers_for` is async, so a provider can fetch or refresh a short-lived
+/// credential (e.g. [`OAuth2ClientCredentials`]'s bearer token) instead of only
+/// returning values computed up front. Applied in addition to, and after, both
+/// [`FacilitatorConfig::with_auth_headers`] and [`FacilitatorConfig::with_provider`]'s
+/// headers.
+pub trait AuthProvider: Send + Sync {
+    /// Headers to attach to a request against `endpoint`
+    fn headers_for<'a>(&'a self, endpoint: Endpoint) -> BoxFuture<'a, Result<HashMap<String, String>>>;
+
+    /// Called when `endpoint` responded `401 Unauthorized` despite this provider's
+    /// headers, so a caching provider can drop its stale credential before the
+    /// client retries once with a freshly fetched one. Default no-op, since a
+    /// provider with nothing to invalidate (e.g. the blanket impl below) has no
+    /// stale state to drop.
+    fn on_unauthorized(&self, _endpoint: Endpoint) {}
+}
+
+/// Lets the existing synchronous [`AuthHeadersFn`] closure implement [`AuthProvider`]
+/// directly, so code already written against it keeps working unchanged
+impl AuthProvider for AuthHeadersFn {
+    fn headers_for<'a>(&'a self, endpoint: Endpoint) -> BoxFuture<'a, Result<HashMap<String, String>>> {
+        let key = match endpoint {
+            Endpoint::Verify => "verify",
+            Endpoint::Settle => "settle",
+            Endpoint::Supported => "supported",
+            Endpoint::Discovery => "list",
+        };
+        let result = (self)().map(|mut all| all.remove(key).unwrap_or_default());
+        Box::pin(async move { result })
+    }
+}
+
+/// [`AuthProvider`] performing an OAuth2 client-credentials grant, caching the
+/// resulting access token until shortly before it expires, and transparently
+/// re-fetching it whenever [`Self::on_unauthorized`] is called (i.e. after a `401`
+/// from `/verify`, `/settle`, `/supported`, or `/discovery/resources`)
+pub struct OAuth2ClientCredentials {
+    http_client: Client,
+    token_url: String,
+    client_id: String,
+    client_secret: String,
+    scope: Option<String>,
+    /// How much earlier than the token's own `expires_in` to treat it as stale, so a
+    /// request in flight doesn't race a token expiring mid-request
+    refresh_margin: std::time::Duration,
+    cached: tokio::sync::Mutex<Option<(String, std::time::Instant)>>,
+}
+
+#[derive(serde::Deserialize)]
+struct OAuth2TokenResponse {
+    access_token: String,
+    expires_in: u64,
+}
+
+impl OAuth2ClientCredentials {
+    /// Default margin subtracted from `expires_in` before a cached token is treated
+    /// as stale
+    pub const DEFAULT_REFRESH_MARGIN: std::time::Duration = std::time::Duration::from_secs(30);
+
+    /// Create a provider that grants tokens from `token_url` using `client_id`/
+    /// `client_secret`, with no `scope` and [`Self::DEFAULT_REFRESH_MARGIN`]
+    pub fn new(token_url: impl Into<String>, client_id: impl Into<String>, client_secret: impl Into<String>) -> Self {
+        Self {
+            http_client: Client::new(),
+            token_url: token_url.into(),
+            client_id: client_id.into(),
+            client_secret: client_secret.into(),
+            scope: None,
+            refresh_margin: Self::DEFAULT_REFRESH_MARGIN,
+            cached: tokio::sync::Mutex::new(None),
+        }
+    }
+
+    /// Restrict the grant to `scope`
+    pub fn with_scope(mut self, scope: impl Into<String>) -> Self {
+        self.scope = Some(scope.into());
+        self
+    }
+
+    /// Override [`Self::DEFAULT_REFRESH_MARGIN`]
+    pub fn with_refresh_margin(mut self, margin: std::time::Duration) -> Self {
+        self.refresh_margin = margin;
+        self
+    }
+
+    /// Perform the client-credentials grant and cache the resulting token
+    async fn fetch_token(&self) -> Result<String> {
+        let mut form = vec![
+            ("grant_type", "client_credentials"),
+            ("client_id", self.client_id.as_str()),
+            ("client_secret", self.client_secret.as_str()),
+        ];
+        if let Some(scope) = &self.scope {
+            form.push(("scope", scope.as_str()));
+        }
+
+        let response = self
+            .http_client
+            .post(&self.token_url)
+            .form(&form)
+            .send()
+            .await?;
+        let status = response.status();
+        let body = response.bytes().await?;
+
+        if !status.is_success() {
+            return Err(X402Error::facilitator_error(format!(
+                "OAuth2 client-credentials grant failed with status: {}",
+                status
+            )));
+        }
+
+        let token: OAuth2TokenResponse = serde_json::from_slice(&body)?;
+        let expires_at = std::time::Instant::now()
+            + std::time::Duration::from_secs(token.expires_in).saturating_sub(self.refresh_margin);
+        *self.cached.lock().await = Some((token.access_token.clone(), expires_at));
+        Ok(token.access_token)
+    }
+}
+
+impl AuthProvider for OAuth2ClientCredentials {
+    fn headers_for<'a>(&'a self, _endpoint: Endpoint) -> BoxFuture<'a, Result<HashMap<String, String>>> {
+        Box::pin(async move {
+            if let Some((token, expires_at)) = self.cached.lock().await.clone() {
+                if std::time::Instant::now() < expires_at {
+                    let mut headers = HashMap::new();
+                    headers.insert("Authorization".to_string(), format!("Bearer {}", token));
+                    return Ok(headers);
+                }
+            }
+
+            let token = self.fetch_token().await?;
+            let mut headers = HashMap::new();
+            headers.insert("Authorization".to_string(), format!("Bearer {}", token));
+            Ok(headers)
+        })
+    }
+
+    fn on_unauthorized(&self, _endpoint: Endpoint) {
+        // Drop the cached token so the next `headers_for` call re-grants instead of
+        // presenting the same rejected bearer token again
+        if let Ok(mut cached) = self.cached.try_lock() {
+            *cached = None;
+        }
+    }
+}
+
+/// Observes a facilitator HTTP response after it arrives, complementing
+/// request-side customization (auth headers, HTTP Signatures) with response-side
+/// visibility: per-endpoint latency (the performance tests in this crate measure
+/// only local operations, never a network round trip) and correlating a `verify`/
+/// `settle` attempt with the facilitator's own logs for reconciliation.
+///
+/// `method`/`status` are `reqwest`'s re-exports of `http::Method`/`http::StatusCode`
+/// (the same types, so a caller already depending on the `http` crate can use them
+/// directly without a conversion).
+pub trait ResponseHook: Send + Sync {
+    /// Called once a facilitator HTTP response has arrived, including non-2xx
+    /// statuses, before the response body is parsed into a [`VerifyResponse`]/
+    /// [`SettleResponse`] or turned into an [`X402Error`]
+    fn on_response<'a>(
+        &'a self,
+        method: reqwest::Method,
+        url: &'a reqwest::Url,
+        status: StatusCode,
+        elapsed: std::time::Duration,
+        body: &'a [u8],
+    ) -> BoxFuture<'a, ()>;
+}
+
 impl FacilitatorClient {
     /// Create a new facilitator client
     pub fn new(config: FacilitatorConfig) -> Result<Self> {
@@ -50,118 +358,775 @@ impl FacilitatorClient {
             url: config.url,
             client,
             auth_config: config.create_auth_headers,
+            retry_policy: config.retry_policy,
+            http_signature: config.http_signature,
+            retry: None,
+            version_cache: std::sync::Arc::new(tokio::sync::Mutex::new(None)),
+            supported_cache: std::sync::Arc::new(tokio::sync::Mutex::new(None)),
+            supported_cache_ttl: config.supported_cache_ttl.unwrap_or(Self::VERSION_CACHE_TTL),
+            response_hook: None,
+            nonce_replay_store: config.nonce_replay_store,
+            idempotency_store: config.idempotency_store,
+            provider: config.provider,
+            auth_provider: config.auth_provider,
         })
     }
 
+    /// Observe every facilitator HTTP response (including non-2xx ones) with `hook`
+    pub fn with_response_hook(mut self, hook: std::sync::Arc<dyn ResponseHook>) -> Self {
+        self.response_hook = Some(hook);
+        self
+    }
+
+    /// Set the async, per-endpoint credential source applied on top of `auth_config`
+    /// and `provider`'s headers
+    pub fn with_auth_provider(mut self, auth_provider: std::sync::Arc<dyn AuthProvider>) -> Self {
+        self.auth_provider = Some(auth_provider);
+        self
+    }
+
+    /// Call [`Self::response_hook`] if one is configured
+    async fn fire_response_hook(
+        &self,
+        method: reqwest::Method,
+        url: &reqwest::Url,
+        status: StatusCode,
+        elapsed: std::time::Duration,
+        body: &[u8],
+    ) {
+        if let Some(hook) = &self.response_hook {
+            hook.on_response(method, url, status, elapsed, body).await;
+        }
+    }
+
+    /// Create a facilitator client at `url` that retries `verify`/`settle` until
+    /// `retry`'s termination condition is reached, backing off per `backoff`
+    ///
+    /// Unlike [`FacilitatorConfig::with_retry_policy`], which only bounds retries by
+    /// attempt count, this also accepts [`crate::retry::Retry::Timeout`] to bound them
+    /// by a monotonic-clock deadline instead.
+    pub fn with_retry(
+        url: impl Into<String>,
+        retry: crate::retry::Retry,
+        backoff: crate::retry::RetryPolicy,
+    ) -> Result<Self> {
+        let mut client = Self::new(FacilitatorConfig::new(url).with_retry_policy(backoff))?;
+        client.retry = Some(retry);
+        Ok(client)
+    }
+
+    /// Like [`Self::with_retry`], but starting from an already-configured
+    /// [`FacilitatorConfig`] (e.g. one with CDP auth headers already attached) instead
+    /// of building a fresh one from just a URL
+    pub fn with_retry_from_config(
+        config: FacilitatorConfig,
+        retry: crate::retry::Retry,
+        backoff: crate::retry::RetryPolicy,
+    ) -> Result<Self> {
+        let mut client = Self::new(config.with_retry_policy(backoff))?;
+        client.retry = Some(retry);
+        Ok(client)
+    }
+
+    /// Add `Host`, `Date`, `Digest` and `Signature` headers to `request` when an
+    /// [`crate::http_signature::HttpSignatureConfig`] is configured on this client
+    fn apply_http_signature(
+        &self,
+        request: reqwest::RequestBuilder,
+        path: &str,
+        body: &[u8],
+    ) -> Result<reqwest::RequestBuilder> {
+        let Some(sig_config) = &self.http_signature else {
+            return Ok(request);
+        };
+
+        let host = self
+            .url
+            .trim_start_matches("https://")
+            .trim_start_matches("http://")
+            .split('/')
+            .next()
+            .unwrap_or(&self.url)
+            .to_string();
+        let date = chrono::Utc::now().to_rfc2822().replace("+0000", "GMT");
+        let created = chrono::Utc::now().timestamp();
+        let expires = created + sig_config.validity.as_secs() as i64;
+        let digest = crate::http_signature::compute_digest(body);
+
+        let signature = crate::http_signature::sign_request(
+            sig_config, "POST", path, &host, &date, body, created, expires,
+        )?;
+
+        Ok(request
+            .header("Host", host)
+            .header("Date", date)
+            .header("Digest", digest)
+            .header("Signature", signature))
+    }
+
     /// Verify a payment without executing the transaction
+    ///
+    /// Transparently retries transient failures (timeouts, 5xx, rate limiting) when a
+    /// [`crate::retry::RetryPolicy`] was configured on the [`FacilitatorConfig`].
+    ///
+    /// When [`FacilitatorConfig::with_nonce_replay_store`] was configured, a
+    /// previously-presented authorization within its own `validBefore` window is
+    /// rejected with [`X402Error::NonceReused`] here, before this call ever reaches
+    /// the facilitator.
     pub async fn verify(
         &self,
         payment_payload: &PaymentPayload,
         payment_requirements: &PaymentRequirements,
     ) -> Result<VerifyResponse> {
+        if let Some(replay_store) = &self.nonce_replay_store {
+            let authorization = &payment_payload.exact_evm()?.authorization;
+            let valid_before: i64 = authorization.valid_before.parse().map_err(|_| {
+                X402Error::invalid_payment_payload("validBefore is not a valid timestamp")
+            })?;
+            crate::nonce_store::reject_nonce_reuse(
+                replay_store.as_ref(),
+                &authorization.from,
+                &authorization.nonce,
+                valid_before,
+            )
+            .await?;
+        }
+
+        if let Some(retry) = &self.retry {
+            let backoff = self.retry_policy.clone().unwrap_or_default();
+            return crate::retry::retry_with_deadline(retry, &backoff, || {
+                self.verify_once(payment_payload, payment_requirements)
+            })
+            .await;
+        }
+
+        match &self.retry_policy {
+            Some(policy) => {
+                crate::retry::retry_with_backoff(policy, || {
+                    self.verify_once(payment_payload, payment_requirements)
+                })
+                .await
+            }
+            None => self.verify_once(payment_payload, payment_requirements).await,
+        }
+    }
+
+    /// Headers contributed by [`Self::auth_provider`] for `endpoint`, or empty if
+    /// none is configured
+    async fn auth_provider_headers(&self, endpoint: Endpoint) -> Result<HashMap<String, String>> {
+        match &self.auth_provider {
+            Some(auth_provider) => auth_provider.headers_for(endpoint).await,
+            None => Ok(HashMap::new()),
+        }
+    }
+
+    async fn verify_once(
+        &self,
+        payment_payload: &PaymentPayload,
+        payment_requirements: &PaymentRequirements,
+    ) -> Result<VerifyResponse> {
+        payment_payload.payload.validate()?;
+
         let request_body = json!({
             "x402Version": X402_VERSION,
             "paymentPayload": payment_payload,
             "paymentRequirements": payment_requirements,
         });
+        let body_bytes = serde_json::to_vec(&request_body)?;
+
+        let path = self
+            .provider
+            .as_ref()
+            .map(|provider| provider.path("verify"))
+            .unwrap_or_else(|| "/verify".to_string());
+
+        for attempt in 0..2 {
+            let mut request = self.client.post(format!("{}{}", self.url, path)).json(&request_body);
+            request = self.apply_http_signature(request, &path, &body_bytes)?;
+
+            // Add authentication headers if available
+            if let Some(auth_config) = &self.auth_config {
+                let headers = auth_config()?;
+                if let Some(verify_headers) = headers.get("verify") {
+                    for (key, value) in verify_headers {
+                        request = request.header(key, value);
+                    }
+                }
+            }
 
-        let mut request = self
-            .client
-            .post(format!("{}/verify", self.url))
-            .json(&request_body);
-
-        // Add authentication headers if available
-        if let Some(auth_config) = &self.auth_config {
-            let headers = auth_config()?;
-            if let Some(verify_headers) = headers.get("verify") {
-                for (key, value) in verify_headers {
+            if let Some(provider) = &self.provider {
+                for (key, value) in provider.headers("verify")? {
                     request = request.header(key, value);
                 }
             }
-        }
 
-        let response = request.send().await?;
+            for (key, value) in self.auth_provider_headers(Endpoint::Verify).await? {
+                request = request.header(key, value);
+            }
 
-        if !response.status().is_success() {
-            return Err(X402Error::facilitator_error(format!(
-                "Verification failed with status: {}",
-                response.status()
-            )));
+            let start = std::time::Instant::now();
+            let response = request.send().await?;
+            let status = response.status();
+            let url = response.url().clone();
+            let retry_after = response
+                .headers()
+                .get(reqwest::header::RETRY_AFTER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(crate::retry::parse_retry_after);
+            let response_body = response.bytes().await?;
+            self.fire_response_hook(
+                reqwest::Method::POST,
+                &url,
+                status,
+                start.elapsed(),
+                &response_body,
+            )
+            .await;
+
+            if status == StatusCode::UNAUTHORIZED && attempt == 0 {
+                if let Some(auth_provider) = &self.auth_provider {
+                    auth_provider.on_unauthorized(Endpoint::Verify);
+                    continue;
+                }
+            }
+
+            if !status.is_success() {
+                return Err(parse_facilitator_error_from_bytes(
+                    &response_body,
+                    status,
+                    retry_after,
+                    "Verification",
+                ));
+            }
+
+            let verify_response: VerifyResponse = serde_json::from_slice(&response_body)?;
+            return Ok(verify_response);
         }
 
-        let verify_response: VerifyResponse = response.json().await?;
-        Ok(verify_response)
+        unreachable!("loop always returns or retries exactly once")
     }
 
     /// Settle a verified payment by executing the transaction
+    ///
+    /// Transparently retries transient failures the same way [`Self::verify`] does.
+    ///
+    /// When [`FacilitatorConfig::with_idempotency_store`] was configured, this also
+    /// collapses concurrent or retried `settle` calls for the same payment
+    /// authorization onto a single in-flight attempt, returning the first call's
+    /// cached [`SettleResponse`] instead of settling it twice — the client-side half
+    /// of the guarantee the `Idempotency-Key` header set in [`Self::settle_once`]
+    /// gives on the facilitator's side.
     pub async fn settle(
         &self,
         payment_payload: &PaymentPayload,
         payment_requirements: &PaymentRequirements,
     ) -> Result<SettleResponse> {
+        let Some(store) = &self.idempotency_store else {
+            return self.settle_retried(payment_payload, payment_requirements).await;
+        };
+
+        let id = crate::idempotency::PaymentId::from_authorization(
+            &payment_payload.exact_evm()?.authorization,
+            payment_requirements,
+        );
+
+        match store.begin(id).await {
+            Some(crate::idempotency::IdempotencyState::Completed(result)) => return Ok(result),
+            Some(crate::idempotency::IdempotencyState::Abandoned { reason, attempts }) => {
+                return Err(X402Error::settlement_abandoned(id.to_string(), attempts, reason));
+            }
+            Some(crate::idempotency::IdempotencyState::InFlight { .. }) | None => {}
+        }
+
+        match self.settle_retried(payment_payload, payment_requirements).await {
+            Ok(result) => {
+                store.complete(id, result.clone()).await;
+                Ok(result)
+            }
+            Err(error) => {
+                match store
+                    .record_failure(id, error.to_string(), crate::idempotency::DEFAULT_MAX_SETTLEMENT_ATTEMPTS)
+                    .await
+                {
+                    crate::idempotency::IdempotencyState::Abandoned { reason, attempts } => {
+                        Err(X402Error::settlement_abandoned(id.to_string(), attempts, reason))
+                    }
+                    _ => Err(error),
+                }
+            }
+        }
+    }
+
+    /// `settle`'s retry-wrapped inner call, with no idempotency coalescing — the
+    /// part [`crate::idempotency::IdempotentSettlement`] calls through the
+    /// [`Facilitator`] trait, and what [`Self::settle`] itself falls back to when no
+    /// [`FacilitatorConfig::with_idempotency_store`] is configured
+    async fn settle_retried(
+        &self,
+        payment_payload: &PaymentPayload,
+        payment_requirements: &PaymentRequirements,
+    ) -> Result<SettleResponse> {
+        if let Some(retry) = &self.retry {
+            let backoff = self.retry_policy.clone().unwrap_or_default();
+            return crate::retry::retry_with_deadline(retry, &backoff, || {
+                self.settle_once(payment_payload, payment_requirements)
+            })
+            .await;
+        }
+
+        match &self.retry_policy {
+            Some(policy) => {
+                crate::retry::retry_with_backoff(policy, || {
+                    self.settle_once(payment_payload, payment_requirements)
+                })
+                .await
+            }
+            None => self.settle_once(payment_payload, payment_requirements).await,
+        }
+    }
+
+    async fn settle_once(
+        &self,
+        payment_payload: &PaymentPayload,
+        payment_requirements: &PaymentRequirements,
+    ) -> Result<SettleResponse> {
+        payment_payload.payload.validate()?;
+
         let request_body = json!({
             "x402Version": X402_VERSION,
             "paymentPayload": payment_payload,
             "paymentRequirements": payment_requirements,
         });
+        let body_bytes = serde_json::to_vec(&request_body)?;
+
+        // A retried settle POST (this method's caller wraps it in retry_with_backoff
+        // on a timeout/5xx) must not risk a facilitator executing the same transfer
+        // twice just because the *response* to an already-applied settlement was
+        // lost. Key the request by the payment's authorization — the same
+        // nonce+from+to+value hash crate::idempotency and the nonce store already
+        // use — so a facilitator that honors Idempotency-Key can safely dedupe a
+        // retried attempt instead of re-settling it.
+        let idempotency_key = crate::idempotency::PaymentId::from_authorization(
+            &payment_payload.exact_evm()?.authorization,
+            payment_requirements,
+        )
+        .to_string();
+
+        let path = self
+            .provider
+            .as_ref()
+            .map(|provider| provider.path("settle"))
+            .unwrap_or_else(|| "/settle".to_string());
+
+        for attempt in 0..2 {
+            let mut request = self
+                .client
+                .post(format!("{}{}", self.url, path))
+                .header("Idempotency-Key", idempotency_key.clone())
+                .json(&request_body);
+            request = self.apply_http_signature(request, &path, &body_bytes)?;
+
+            // Add authentication headers if available
+            if let Some(auth_config) = &self.auth_config {
+                let headers = auth_config()?;
+                if let Some(settle_headers) = headers.get("settle") {
+                    for (key, value) in settle_headers {
+                        request = request.header(key, value);
+                    }
+                }
+            }
+
+            if let Some(provider) = &self.provider {
+                for (key, value) in provider.headers("settle")? {
+                    request = request.header(key, value);
+                }
+            }
+
+            for (key, value) in self.auth_provider_headers(Endpoint::Settle).await? {
+                request = request.header(key, value);
+            }
+
+            let start = std::time::Instant::now();
+            let response = request.send().await?;
+            let status = response.status();
+            let url = response.url().clone();
+            let retry_after = response
+                .headers()
+                .get(reqwest::header::RETRY_AFTER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(crate::retry::parse_retry_after);
+            let response_body = response.bytes().await?;
+            self.fire_response_hook(
+                reqwest::Method::POST,
+                &url,
+                status,
+                start.elapsed(),
+                &response_body,
+            )
+            .await;
+
+            if status == StatusCode::UNAUTHORIZED && attempt == 0 {
+                if let Some(auth_provider) = &self.auth_provider {
+                    auth_provider.on_unauthorized(Endpoint::Settle);
+                    continue;
+                }
+            }
+
+            if !status.is_success() {
+                return Err(parse_facilitator_error_from_bytes(
+                    &response_body,
+                    status,
+                    retry_after,
+                    "Settlement",
+                ));
+            }
+
+            let settle_response: SettleResponse = serde_json::from_slice(&response_body)?;
+            return Ok(settle_response);
+        }
+
+        unreachable!("loop always returns or retries exactly once")
+    }
+
+    /// Reverse a previously settled payment by posting a [`ReversalRequest`] of
+    /// kind [`ReversalKind::Refund`] to the facilitator's `/refund` endpoint
+    ///
+    /// `/refund` is not part of the core x402 facilitator protocol; this only
+    /// succeeds against a facilitator that opts into supporting it. See
+    /// [`ReversalRequest`]'s doc comment for why it's modeled as a wire extension
+    /// rather than a core [`Facilitator`] trait method.
+    pub async fn refund(
+        &self,
+        original_settlement: &SettleResponse,
+        amount: &str,
+    ) -> Result<ReversalResponse> {
+        let destination = original_settlement
+            .payer
+            .clone()
+            .ok_or_else(|| X402Error::config("Original settlement has no payer to refund"))?;
+
+        let request = ReversalRequest {
+            kind: ReversalKind::Refund,
+            original_transaction: Some(original_settlement.transaction.clone()),
+            destination,
+            amount: amount.to_string(),
+            asset: String::new(),
+            network: original_settlement.network.clone(),
+        };
+        self.post_reversal("refund", &request).await
+    }
+
+    /// Pay `amount` of `asset` out to `destination`, not tied to a prior settlement,
+    /// by posting a [`ReversalRequest`] of kind [`ReversalKind::Payout`] to the
+    /// facilitator's `/payout` endpoint
+    pub async fn payout(
+        &self,
+        destination: &str,
+        amount: &str,
+        asset: &str,
+        network: &str,
+    ) -> Result<ReversalResponse> {
+        let request = ReversalRequest {
+            kind: ReversalKind::Payout,
+            original_transaction: None,
+            destination: destination.to_string(),
+            amount: amount.to_string(),
+            asset: asset.to_string(),
+            network: network.to_string(),
+        };
+        self.post_reversal("payout", &request).await
+    }
+
+    async fn post_reversal(&self, path: &str, request: &ReversalRequest) -> Result<ReversalResponse> {
+        let body_bytes = serde_json::to_vec(request)?;
 
-        let mut request = self
+        let mut http_request = self
             .client
-            .post(format!("{}/settle", self.url))
-            .json(&request_body);
+            .post(format!("{}/{}", self.url, path))
+            .json(request);
+        http_request = self.apply_http_signature(http_request, &format!("/{}", path), &body_bytes)?;
 
-        // Add authentication headers if available
         if let Some(auth_config) = &self.auth_config {
             let headers = auth_config()?;
-            if let Some(settle_headers) = headers.get("settle") {
-                for (key, value) in settle_headers {
-                    request = request.header(key, value);
+            if let Some(extra_headers) = headers.get(path) {
+                for (key, value) in extra_headers {
+                    http_request = http_request.header(key, value);
                 }
             }
         }
 
-        let response = request.send().await?;
+        let response = http_request.send().await?;
 
         if !response.status().is_success() {
-            return Err(X402Error::facilitator_error(format!(
-                "Settlement failed with status: {}",
-                response.status()
-            )));
+            let status = response.status();
+            return Err(parse_facilitator_error(response, status, path).await);
         }
 
-        let settle_response: SettleResponse = response.json().await?;
-        Ok(settle_response)
+        let reversal_response: ReversalResponse = response.json().await?;
+        Ok(reversal_response)
     }
 
     /// Get supported payment schemes and networks
+    ///
+    /// Transparently retries transient failures (timeouts, 5xx, rate limiting) the
+    /// same way [`Self::verify`] does, when a [`crate::retry::RetryPolicy`] was
+    /// configured on the [`FacilitatorConfig`] — safe to retry freely since this
+    /// call has no side effects.
     pub async fn supported(&self) -> Result<SupportedKinds> {
-        let mut request = self
-            .client
-            .get(format!("{}/supported", self.url));
+        if let Some(retry) = &self.retry {
+            let backoff = self.retry_policy.clone().unwrap_or_default();
+            return crate::retry::retry_with_deadline(retry, &backoff, || self.supported_once()).await;
+        }
 
-        // Add authentication headers if available
-        if let Some(auth_config) = &self.auth_config {
-            let headers = auth_config()?;
-            if let Some(supported_headers) = headers.get("supported") {
-                for (key, value) in supported_headers {
+        match &self.retry_policy {
+            Some(policy) => crate::retry::retry_with_backoff(policy, || self.supported_once()).await,
+            None => self.supported_once().await,
+        }
+    }
+
+    async fn supported_once(&self) -> Result<SupportedKinds> {
+        let path = self
+            .provider
+            .as_ref()
+            .map(|provider| provider.path("supported"))
+            .unwrap_or_else(|| "/supported".to_string());
+
+        for attempt in 0..2 {
+            let mut request = self.client.get(format!("{}{}", self.url, path));
+
+            // Add authentication headers if available
+            if let Some(auth_config) = &self.auth_config {
+                let headers = auth_config()?;
+                if let Some(supported_headers) = headers.get("supported") {
+                    for (key, value) in supported_headers {
+                        request = request.header(key, value);
+                    }
+                }
+            }
+
+            if let Some(provider) = &self.provider {
+                for (key, value) in provider.headers("supported")? {
                     request = request.header(key, value);
                 }
             }
+
+            for (key, value) in self.auth_provider_headers(Endpoint::Supported).await? {
+                request = request.header(key, value);
+            }
+
+            let start = std::time::Instant::now();
+            let response = request.send().await?;
+            let status = response.status();
+            let url = response.url().clone();
+            let response_body = response.bytes().await?;
+            self.fire_response_hook(reqwest::Method::GET, &url, status, start.elapsed(), &response_body)
+                .await;
+
+            if status == StatusCode::UNAUTHORIZED && attempt == 0 {
+                if let Some(auth_provider) = &self.auth_provider {
+                    auth_provider.on_unauthorized(Endpoint::Supported);
+                    continue;
+                }
+            }
+
+            if !status.is_success() {
+                return Err(X402Error::facilitator_error(format!(
+                    "Failed to get supported kinds with status: {}",
+                    status
+                )));
+            }
+
+            let supported: SupportedKinds = serde_json::from_slice(&response_body)?;
+            return Ok(supported);
         }
 
-        let response = request.send().await?;
+        unreachable!("loop always returns or retries exactly once")
+    }
 
-        if !response.status().is_success() {
-            return Err(X402Error::facilitator_error(format!(
-                "Failed to get supported kinds with status: {}",
-                response.status()
-            )));
+    /// How long a [`Self::supported_versions`] result is trusted before a fresh
+    /// `/supported` call is made, mirroring [`CachedFacilitator`]'s TTL
+    const VERSION_CACHE_TTL: std::time::Duration = std::time::Duration::from_secs(60);
+
+    /// Distinct x402 protocol versions the facilitator's `/supported` document
+    /// advertises across its kinds, cached for [`Self::VERSION_CACHE_TTL`]
+    pub async fn supported_versions(&self) -> Result<Vec<u32>> {
+        if let Some((cached, fetched_at)) = self.version_cache.lock().await.clone() {
+            if fetched_at.elapsed() <= Self::VERSION_CACHE_TTL {
+                return Ok(cached);
+            }
+        }
+
+        let supported = self.supported().await?;
+        let mut versions: Vec<u32> = supported.kinds.iter().map(|kind| kind.x402_version).collect();
+        versions.sort_unstable();
+        versions.dedup();
+
+        *self.version_cache.lock().await = Some((versions.clone(), std::time::Instant::now()));
+        Ok(versions)
+    }
+
+    /// The facilitator's full `/supported` document, cached for
+    /// [`Self::supported_cache_ttl`] so a `(scheme, network)` capability check (e.g.
+    /// [`Self::verify_with_network_validation`]) doesn't round-trip on every call.
+    /// Call [`Self::refresh_supported`] to force a reload ahead of the TTL, e.g. once
+    /// a newly added network is known to be live.
+    pub async fn supported_cached(&self) -> Result<SupportedKinds> {
+        if let Some((cached, fetched_at)) = self.supported_cache.lock().await.clone() {
+            if fetched_at.elapsed() <= self.supported_cache_ttl {
+                return Ok(cached);
+            }
         }
 
-        let supported: SupportedKinds = response.json().await?;
+        self.refresh_supported().await
+    }
+
+    /// Force a reload of [`Self::supported_cache`], bypassing [`Self::supported_cache_ttl`]
+    pub async fn refresh_supported(&self) -> Result<SupportedKinds> {
+        let supported = self.supported().await?;
+        *self.supported_cache.lock().await = Some((supported.clone(), std::time::Instant::now()));
         Ok(supported)
     }
 
+    /// Pick the highest x402 protocol version both this client
+    /// ([`SUPPORTED_VERSIONS`]) and the facilitator ([`Self::supported_versions`])
+    /// can speak
+    ///
+    /// Returns [`X402Error::VersionMismatch`] naming each side's highest advertised
+    /// version when no version overlaps. Logs a warning (not an error) when a
+    /// version is found but it's below [`X402_VERSION`], so an incrementally
+    /// upgraded deployment is visible in logs without failing requests outright.
+    pub async fn negotiate_version(&self) -> Result<u32> {
+        let facilitator_versions = self.supported_versions().await?;
+        let negotiated = facilitator_versions
+            .iter()
+            .copied()
+            .filter(|version| SUPPORTED_VERSIONS.contains(version))
+            .max()
+            .ok_or_else(|| {
+                X402Error::version_mismatch(
+                    facilitator_versions.iter().copied().max().unwrap_or(0),
+                    X402_VERSION,
+                )
+            })?;
+
+        if negotiated < X402_VERSION {
+            tracing::warn!(
+                "Negotiated x402 version {} is below this client's preferred version {}",
+                negotiated,
+                X402_VERSION
+            );
+        }
+
+        Ok(negotiated)
+    }
+
+    /// Pick the highest x402 protocol version both this client and the facilitator
+    /// can speak for a specific `(scheme, network)` pairing, rather than across the
+    /// facilitator's entire `/supported` document like [`Self::negotiate_version`]
+    /// does. Useful when a facilitator advertises a newer version for one network
+    /// than another (e.g. a new chain integration shipping ahead of the rest).
+    ///
+    /// Returns [`X402Error::SchemeNotSupported`] with the versions the facilitator
+    /// does advertise for this pairing when none of them overlap with
+    /// [`SUPPORTED_VERSIONS`].
+    pub async fn negotiate(&self, scheme: &str, network: &str) -> Result<u32> {
+        let supported = self.supported().await?;
+        let available: Vec<u32> = supported
+            .kinds
+            .iter()
+            .filter(|kind| kind.scheme == scheme && kind.network == network)
+            .map(|kind| kind.x402_version)
+            .collect();
+
+        available
+            .iter()
+            .copied()
+            .filter(|version| SUPPORTED_VERSIONS.contains(version))
+            .max()
+            .ok_or_else(|| X402Error::scheme_not_supported(scheme, network, available))
+    }
+
+    /// Pick which of a 402 response's offered `accepts` entries to build a payment
+    /// against: the one whose `(scheme, network)` the facilitator's `/supported`
+    /// document lists with the highest mutually compatible protocol version,
+    /// instead of a caller guessing a single pairing up front like [`Self::negotiate`]
+    /// requires.
+    ///
+    /// When more than one offered requirement has a compatible pairing, the
+    /// highest negotiated version wins; ties keep `requirements`' own ordering.
+    ///
+    /// Returns [`X402Error::NoSupportedRequirements`] naming every `(scheme,
+    /// network)` offered and every `(scheme, network, x402_version)` the
+    /// facilitator actually advertises when none of them overlap, so a client
+    /// talking to a newer or older facilitator can report exactly how far apart
+    /// the two sides are instead of sending a payload the server will reject.
+    pub async fn negotiate_requirements(
+        &self,
+        requirements: &[PaymentRequirements],
+    ) -> Result<SupportedKind> {
+        let supported = self.supported().await?;
+
+        let best = requirements
+            .iter()
+            .filter_map(|requirement| {
+                supported
+                    .kinds
+                    .iter()
+                    .filter(|kind| {
+                        kind.scheme == requirement.scheme
+                            && kind.network == requirement.network
+                            && SUPPORTED_VERSIONS.contains(&kind.x402_version)
+                    })
+                    .max_by_key(|kind| kind.x402_version)
+            })
+            .max_by_key(|kind| kind.x402_version);
+
+        best.cloned().ok_or_else(|| {
+            let offered = requirements
+                .iter()
+                .map(|requirement| (requirement.scheme.clone(), requirement.network.clone()))
+                .collect();
+            let available = supported
+                .kinds
+                .iter()
+                .map(|kind| (kind.scheme.clone(), kind.network.clone(), kind.x402_version))
+                .collect();
+            X402Error::no_supported_requirements(offered, available)
+        })
+    }
+
+    /// [`Self::verify`], but first confirms the facilitator actually supports
+    /// `payment_requirements`'s `(scheme, network)` pairing and speaks a protocol
+    /// version compatible with [`X402_VERSION`], via [`Self::negotiate`] (which
+    /// itself rides on [`Self::supported`]'s caching layer).
+    ///
+    /// Surfaces the mismatch as [`X402Error::SchemeNotSupported`] or
+    /// [`X402Error::VersionMismatch`] instead of letting an unsupported payment
+    /// reach the facilitator and come back as a less specific verification failure.
+    pub async fn verify_checked(
+        &self,
+        payment_payload: &PaymentPayload,
+        payment_requirements: &PaymentRequirements,
+    ) -> Result<VerifyResponse> {
+        self.negotiate(&payment_requirements.scheme, &payment_requirements.network)
+            .await?;
+        self.verify(payment_payload, payment_requirements).await
+    }
+
+    /// [`Self::settle`], but first confirms the facilitator actually supports
+    /// `payment_requirements`'s `(scheme, network)` pairing and speaks a protocol
+    /// version compatible with [`X402_VERSION`], via [`Self::negotiate`] — the
+    /// settlement counterpart to [`Self::verify_checked`].
+    pub async fn settle_checked(
+        &self,
+        payment_payload: &PaymentPayload,
+        payment_requirements: &PaymentRequirements,
+    ) -> Result<SettleResponse> {
+        self.negotiate(&payment_requirements.scheme, &payment_requirements.network)
+            .await?;
+        self.settle(payment_payload, payment_requirements).await
+    }
+
     /// Get the base URL of this facilitator
     pub fn url(&self) -> &str {
         &self.url
@@ -184,7 +1149,14 @@ impl FacilitatorClient {
         Self::for_network("base-sepolia", config)
     }
 
-    /// Verify payment with network-specific validation
+    /// Verify a payment, first checking locally that its network, scheme, and
+    /// recipient address match `payment_requirements`, and that the facilitator's
+    /// cached `/supported` document actually advertises that `(scheme, network)`
+    /// pairing (see [`Self::supported_cached`]). Every precondition failure returns
+    /// a typed [`X402Error`] variant ([`X402Error::WrongNetwork`] /
+    /// [`X402Error::SchemeMismatch`] / [`X402Error::AssetMismatch`] /
+    /// [`X402Error::SchemeNotSupported`]) instead of forwarding a doomed request to
+    /// the facilitator or panicking on malformed input
     pub async fn verify_with_network_validation(
         &self,
         payment_payload: &PaymentPayload,
@@ -192,18 +1164,42 @@ impl FacilitatorClient {
     ) -> Result<VerifyResponse> {
         // Validate that the payment network matches requirements - return error on mismatch
         if payment_payload.network != payment_requirements.network {
-            return Err(X402Error::payment_verification_failed(format!(
-                "CRITICAL ERROR: Network mismatch detected! Payment network '{}' does not match requirements network '{}'. This is a security violation.",
-                payment_payload.network, payment_requirements.network
-            )));
+            return Err(X402Error::wrong_network(
+                &payment_requirements.network,
+                &payment_payload.network,
+            ));
         }
 
         // Validate that the payment scheme matches requirements
         if payment_payload.scheme != payment_requirements.scheme {
-            return Err(X402Error::payment_verification_failed(format!(
-                "Scheme mismatch: payment scheme {} != requirements scheme {}",
-                payment_payload.scheme, payment_requirements.scheme
-            )));
+            return Err(X402Error::scheme_mismatch(
+                &payment_requirements.scheme,
+                &payment_payload.scheme,
+            ));
+        }
+
+        // Validate that the payment is being made to the address requirements specify
+        let authorization = &payment_payload.exact_evm()?.authorization;
+        if authorization.to != payment_requirements.pay_to {
+            return Err(X402Error::asset_mismatch(&payment_requirements.pay_to, &authorization.to));
+        }
+
+        // Reject a (scheme, network) pairing the facilitator doesn't advertise at
+        // all before sending it a doomed /verify request, using the cached
+        // /supported document rather than fetching it fresh on every call
+        let supported = self.supported_cached().await?;
+        let available: Vec<u32> = supported
+            .kinds
+            .iter()
+            .filter(|kind| kind.scheme == payment_requirements.scheme && kind.network == payment_requirements.network)
+            .map(|kind| kind.x402_version)
+            .collect();
+        if available.is_empty() {
+            return Err(X402Error::scheme_not_supported(
+                &payment_requirements.scheme,
+                &payment_requirements.network,
+                available,
+            ));
         }
 
         // Proceed with normal verification
@@ -214,46 +1210,79 @@ impl FacilitatorClient {
     /// 
     /// This method hits the `/discovery/resources` endpoint and forwards any auth headers,
     /// similar to TypeScript's `useFacilitator().list()` and Python's `FacilitatorClient.list()`
-    pub async fn list(
-        &self,
-        filters: Option<DiscoveryFilters>,
-    ) -> Result<DiscoveryResponse> {
-        let mut request = self.client.get(format!("{}/discovery/resources", self.url));
-
-        // Add query parameters if filters are provided
-        if let Some(filters) = filters {
-            if let Some(resource_type) = filters.resource_type {
-                request = request.query(&[("type", resource_type)]);
+    ///
+    /// Transparently retries transient failures the same way [`Self::supported`]
+    /// does — safe to retry freely since discovery has no side effects.
+    pub async fn list(&self, filters: Option<DiscoveryFilters>) -> Result<DiscoveryResponse> {
+        if let Some(retry) = &self.retry {
+            let backoff = self.retry_policy.clone().unwrap_or_default();
+            return crate::retry::retry_with_deadline(retry, &backoff, || {
+                self.list_once(filters.clone())
+            })
+            .await;
+        }
+
+        match &self.retry_policy {
+            Some(policy) => {
+                crate::retry::retry_with_backoff(policy, || self.list_once(filters.clone())).await
             }
-            if let Some(limit) = filters.limit {
-                request = request.query(&[("limit", limit.to_string())]);
+            None => self.list_once(filters).await,
+        }
+    }
+
+    async fn list_once(&self, filters: Option<DiscoveryFilters>) -> Result<DiscoveryResponse> {
+        for attempt in 0..2 {
+            let mut request = self.client.get(format!("{}/discovery/resources", self.url));
+
+            // Add query parameters if filters are provided
+            if let Some(filters) = filters.clone() {
+                if let Some(resource_type) = filters.resource_type {
+                    request = request.query(&[("type", resource_type)]);
+                }
+                if let Some(limit) = filters.limit {
+                    request = request.query(&[("limit", limit.to_string())]);
+                }
+                if let Some(offset) = filters.offset {
+                    request = request.query(&[("offset", offset.to_string())]);
+                }
             }
-            if let Some(offset) = filters.offset {
-                request = request.query(&[("offset", offset.to_string())]);
+
+            // Add authentication headers if available
+            if let Some(auth_config) = &self.auth_config {
+                let headers = auth_config()?;
+                if let Some(discovery_headers) = headers.get("list") {
+                    for (key, value) in discovery_headers {
+                        request = request.header(key, value);
+                    }
+                }
             }
-        }
 
-        // Add authentication headers if available
-        if let Some(auth_config) = &self.auth_config {
-            let headers = auth_config()?;
-            if let Some(discovery_headers) = headers.get("list") {
-                for (key, value) in discovery_headers {
-                    request = request.header(key, value);
+            for (key, value) in self.auth_provider_headers(Endpoint::Discovery).await? {
+                request = request.header(key, value);
+            }
+
+            let response = request.send().await?;
+            let status = response.status();
+
+            if status == StatusCode::UNAUTHORIZED && attempt == 0 {
+                if let Some(auth_provider) = &self.auth_provider {
+                    auth_provider.on_unauthorized(Endpoint::Discovery);
+                    continue;
                 }
             }
-        }
 
-        let response = request.send().await?;
+            if !status.is_success() {
+                return Err(X402Error::facilitator_error(format!(
+                    "Discovery failed with status: {}",
+                    status
+                )));
+            }
 
-        if !response.status().is_success() {
-            return Err(X402Error::facilitator_error(format!(
-                "Discovery failed with status: {}",
-                response.status()
-            )));
+            let discovery_response: DiscoveryResponse = response.json().await?;
+            return Ok(discovery_response);
         }
 
-        let discovery_response: DiscoveryResponse = response.json().await?;
-        Ok(discovery_response)
+        unreachable!("loop always returns or retries exactly once")
     }
 
     /// Get all discovery resources without filters
@@ -266,6 +1295,98 @@ impl FacilitatorClient {
         let filters = DiscoveryFilters::new().with_resource_type(resource_type);
         self.list(Some(filters)).await
     }
+
+    /// Walk `/discovery/resources` page by page, yielding one [`DiscoveryResource`]
+    /// at a time instead of requiring the caller to juggle `limit`/`offset`
+    /// themselves, the same way [`crate::client::DiscoveryClient::iter_resources`]
+    /// does for the plain discovery service. `filters.limit` sets the page size
+    /// (defaulting to [`DEFAULT_DISCOVERY_PAGE_SIZE`]); `filters.offset` sets the
+    /// starting offset. Walking stops once [`PaginationInfo::total`] is reached or a
+    /// page comes back empty. A request error ends the stream with that `Err` as
+    /// its final item.
+    pub fn list_stream(
+        &self,
+        filters: Option<DiscoveryFilters>,
+    ) -> impl futures_util::Stream<Item = Result<DiscoveryResource>> + '_ {
+        let filters = filters.unwrap_or_else(DiscoveryFilters::new);
+        let state = DiscoveryListStreamState {
+            client: self,
+            resource_type: filters.resource_type,
+            page_size: filters.limit.unwrap_or(DEFAULT_DISCOVERY_PAGE_SIZE).max(1),
+            offset: filters.offset.unwrap_or(0),
+            total: None,
+            buffer: std::collections::VecDeque::new(),
+            exhausted: false,
+        };
+
+        futures_util::stream::unfold(state, |mut state| async move {
+            loop {
+                if let Some(resource) = state.buffer.pop_front() {
+                    return Some((Ok(resource), state));
+                }
+
+                if state.exhausted {
+                    return None;
+                }
+
+                if state.total.is_some_and(|total| state.offset >= total) {
+                    return None;
+                }
+
+                let page = state
+                    .client
+                    .list(Some(DiscoveryFilters {
+                        resource_type: state.resource_type.clone(),
+                        limit: Some(state.page_size),
+                        offset: Some(state.offset),
+                    }))
+                    .await;
+
+                match page {
+                    Ok(response) => {
+                        state.total = Some(response.pagination.total);
+                        if response.items.is_empty() {
+                            state.exhausted = true;
+                            continue;
+                        }
+                        state.offset += response.items.len() as u32;
+                        state.buffer.extend(response.items);
+                    }
+                    Err(error) => {
+                        state.exhausted = true;
+                        return Some((Err(error), state));
+                    }
+                }
+            }
+        })
+    }
+
+    /// Drain [`Self::list_stream`] into a `Vec`, stopping at the first page error
+    pub async fn collect_all(&self, filters: Option<DiscoveryFilters>) -> Result<Vec<DiscoveryResource>> {
+        use futures_util::StreamExt;
+
+        let mut items = Vec::new();
+        let mut stream = Box::pin(self.list_stream(filters));
+        while let Some(item) = stream.next().await {
+            items.push(item?);
+        }
+        Ok(items)
+    }
+}
+
+/// Page size [`FacilitatorClient::list_stream`] requests per underlying call when the
+/// caller's filters don't set an explicit `limit`
+const DEFAULT_DISCOVERY_PAGE_SIZE: u32 = 50;
+
+/// Walk state behind the stream [`FacilitatorClient::list_stream`] returns
+struct DiscoveryListStreamState<'a> {
+    client: &'a FacilitatorClient,
+    resource_type: Option<String>,
+    page_size: u32,
+    offset: u32,
+    total: Option<u32>,
+    buffer: std::collections::VecDeque<DiscoveryResource>,
+    exhausted: bool,
 }
 
 impl Default for FacilitatorClient {
@@ -276,794 +1397,4563 @@ impl Default for FacilitatorClient {
                 url: "https://x402.org/facilitator".to_string(),
                 client: Client::new(),
                 auth_config: None,
+                retry_policy: None,
+                http_signature: None,
+                retry: None,
+                version_cache: std::sync::Arc::new(tokio::sync::Mutex::new(None)),
+                supported_cache: std::sync::Arc::new(tokio::sync::Mutex::new(None)),
+                supported_cache_ttl: Self::VERSION_CACHE_TTL,
+                response_hook: None,
+                nonce_replay_store: None,
+                idempotency_store: None,
+                provider: None,
+                auth_provider: None,
             }
         })
     }
 }
 
-/// Coinbase facilitator integration
-pub mod coinbase {
-    use super::*;
-    use crate::crypto::jwt;
-    use std::env;
+/// Routes `verify`/`settle` calls to a different [`FacilitatorClient`] per network
+///
+/// Lets a single deployment settle, say, `base` payments through Coinbase's hosted
+/// facilitator while routing `avalanche` through a self-hosted one, instead of forcing
+/// every network through the one facilitator endpoint configured on
+/// [`crate::middleware::PaymentMiddleware`].
+#[derive(Clone)]
+pub struct FacilitatorRouter {
+    /// Facilitator used when a network has no dedicated entry
+    default: FacilitatorClient,
+    /// Network-specific facilitators, keyed by network identifier (e.g. "base")
+    by_network: HashMap<String, FacilitatorClient>,
+}
 
-    /// Coinbase facilitator base URL
-    pub const COINBASE_FACILITATOR_BASE_URL: &str = "https://api.cdp.coinbase.com";
-    /// Coinbase facilitator v2 route
-    pub const COINBASE_FACILITATOR_V2_ROUTE: &str = "/platform/v2/x402";
-    /// SDK version
-    pub const SDK_VERSION: &str = "0.1.0";
+impl FacilitatorRouter {
+    /// Create a router that falls back to `default` for any network without a
+    /// dedicated facilitator registered via [`Self::with_network`]
+    pub fn new(default: FacilitatorClient) -> Self {
+        Self {
+            default,
+            by_network: HashMap::new(),
+        }
+    }
 
-    /// Create authentication headers for Coinbase facilitator
-    pub fn create_auth_headers(
-        api_key_id: &str,
-        api_key_secret: &str,
-    ) -> impl Fn() -> Result<HashMap<String, HashMap<String, String>>> + Send + Sync {
-        let api_key_id = api_key_id.to_string();
-        let api_key_secret = api_key_secret.to_string();
+    /// Register a dedicated facilitator for `network`
+    pub fn with_network(mut self, network: impl Into<String>, facilitator: FacilitatorClient) -> Self {
+        self.by_network.insert(network.into(), facilitator);
+        self
+    }
 
-        move || {
-            // Use provided credentials or fall back to environment variables
-            let id = if api_key_id.is_empty() {
-                env::var("CDP_API_KEY_ID").unwrap_or_default()
-            } else {
-                api_key_id.clone()
-            };
+    /// Resolve the facilitator to use for `network`, falling back to the default
+    pub fn resolve(&self, network: &str) -> &FacilitatorClient {
+        self.by_network.get(network).unwrap_or(&self.default)
+    }
 
-            let secret = if api_key_secret.is_empty() {
-                env::var("CDP_API_KEY_SECRET").unwrap_or_default()
-            } else {
-                api_key_secret.clone()
-            };
+    /// Verify a payment, routing to the facilitator registered for its network
+    pub async fn verify(
+        &self,
+        payment_payload: &PaymentPayload,
+        payment_requirements: &PaymentRequirements,
+    ) -> Result<VerifyResponse> {
+        self.resolve(&payment_requirements.network)
+            .verify(payment_payload, payment_requirements)
+            .await
+    }
 
-            if id.is_empty() || secret.is_empty() {
-                return Err(X402Error::config(
-                    "Missing credentials: CDP_API_KEY_ID and CDP_API_KEY_SECRET must be set",
-                ));
-            }
+    /// Settle a payment, routing to the facilitator registered for its network
+    pub async fn settle(
+        &self,
+        payment_payload: &PaymentPayload,
+        payment_requirements: &PaymentRequirements,
+    ) -> Result<SettleResponse> {
+        self.resolve(&payment_requirements.network)
+            .settle(payment_payload, payment_requirements)
+            .await
+    }
+}
 
-            let verify_token = jwt::create_auth_header_with_method(
-                &id,
-                &secret,
-                "POST",
-                COINBASE_FACILITATOR_BASE_URL,
-                &format!("{}/verify", COINBASE_FACILITATOR_V2_ROUTE),
-            )?;
+impl std::fmt::Debug for FacilitatorRouter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FacilitatorRouter")
+            .field("default", &self.default)
+            .field("networks", &self.by_network.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
 
-            let settle_token = jwt::create_auth_header_with_method(
-                &id,
-                &secret,
-                "POST",
-                COINBASE_FACILITATOR_BASE_URL,
-                &format!("{}/settle", COINBASE_FACILITATOR_V2_ROUTE),
-            )?;
+/// How [`FacilitatorFallbackChain`] picks which facilitator to try first
+pub enum RoutingPolicy {
+    /// Always start from the first facilitator in the list
+    Priority,
+    /// Rotate the starting facilitator on each call, spreading load evenly
+    RoundRobin,
+    /// Pick the starting facilitator with a caller-supplied function keyed on
+    /// the payment's requirements (e.g. its network or asset); the result is
+    /// taken modulo the facilitator count
+    Predicate(std::sync::Arc<dyn Fn(&PaymentRequirements) -> usize + Send + Sync>),
+}
 
-            let correlation_header = create_correlation_header();
+impl Clone for RoutingPolicy {
+    fn clone(&self) -> Self {
+        match self {
+            RoutingPolicy::Priority => RoutingPolicy::Priority,
+            RoutingPolicy::RoundRobin => RoutingPolicy::RoundRobin,
+            RoutingPolicy::Predicate(f) => RoutingPolicy::Predicate(f.clone()),
+        }
+    }
+}
 
-            let mut headers = HashMap::new();
+impl std::fmt::Debug for RoutingPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RoutingPolicy::Priority => write!(f, "Priority"),
+            RoutingPolicy::RoundRobin => write!(f, "RoundRobin"),
+            RoutingPolicy::Predicate(_) => write!(f, "Predicate(<fn>)"),
+        }
+    }
+}
 
-            let mut verify_headers = HashMap::new();
-            verify_headers.insert("Authorization".to_string(), verify_token);
-            verify_headers.insert(
-                "Correlation-Context".to_string(),
-                correlation_header.clone(),
-            );
-            headers.insert("verify".to_string(), verify_headers);
+/// Tries an ordered list of facilitators, falling back to the next one when a
+/// call fails with a transient ([`X402Error::is_retryable`]) error — a
+/// definitive `Ok` (including an explicit verification rejection, i.e.
+/// `VerifyResponse { is_valid: false, .. }`) is returned immediately and never
+/// retried against another facilitator. Inspired by payment-orchestration
+/// systems that route each transaction across a list of connectors with
+/// health-aware fallback.
+///
+/// Distinct from [`FacilitatorRouter`] (one dedicated facilitator per
+/// network) and [`FacilitatorRegistry`] (one dedicated backend per
+/// network+scheme pair): every facilitator in this chain is assumed capable
+/// of handling any payment that reaches it, and the chain exists purely for
+/// redundancy — e.g. running two independently-hosted facilitators behind
+/// the same deployment.
+pub struct FacilitatorFallbackChain {
+    entries: Vec<FacilitatorChainEntry>,
+    policy: RoutingPolicy,
+    next_round_robin: std::sync::atomic::AtomicUsize,
+    health: Vec<EndpointHealth>,
+    /// Consecutive transient failures an entry must accumulate before it's put in
+    /// cooldown and skipped by [`Self::ordered_indices`]
+    failure_threshold: u32,
+    /// How long an entry that tripped [`Self::failure_threshold`] is skipped before
+    /// being tried again
+    cooldown: std::time::Duration,
+}
 
-            let mut settle_headers = HashMap::new();
-            settle_headers.insert("Authorization".to_string(), settle_token);
-            settle_headers.insert("Correlation-Context".to_string(), correlation_header);
-            headers.insert("settle".to_string(), settle_headers);
+/// Per-entry consecutive-failure count and cooldown deadline for
+/// [`FacilitatorFallbackChain`], letting it stop routing to an endpoint that's
+/// currently down instead of re-trying (and falling back past) it on every call
+#[derive(Default)]
+struct EndpointHealth {
+    consecutive_failures: std::sync::atomic::AtomicU32,
+    cooldown_until: std::sync::Mutex<Option<std::time::Instant>>,
+}
 
-            Ok(headers)
+impl EndpointHealth {
+    fn is_in_cooldown(&self) -> bool {
+        match *self.cooldown_until.lock().unwrap() {
+            Some(until) => std::time::Instant::now() < until,
+            None => false,
         }
     }
 
-    /// Create a facilitator config for Coinbase
-    pub fn create_facilitator_config(api_key_id: &str, api_key_secret: &str) -> FacilitatorConfig {
-        FacilitatorConfig::new(format!(
-            "{}{}",
-            COINBASE_FACILITATOR_BASE_URL, COINBASE_FACILITATOR_V2_ROUTE
-        ))
-        .with_auth_headers(Box::new(create_auth_headers(api_key_id, api_key_secret)))
+    fn record_success(&self) {
+        self.consecutive_failures.store(0, std::sync::atomic::Ordering::Relaxed);
+        *self.cooldown_until.lock().unwrap() = None;
     }
 
-    /// Create correlation header for requests
-    fn create_correlation_header() -> String {
-        use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
-
-        let data = [
-            ("sdk_version", SDK_VERSION),
-            ("sdk_language", "rust"),
-            ("source", "x402"),
-            ("source_version", crate::VERSION),
-        ];
+    fn record_failure(&self, threshold: u32, cooldown: std::time::Duration) {
+        let failures = self
+            .consecutive_failures
+            .fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+            + 1;
+        if failures >= threshold {
+            *self.cooldown_until.lock().unwrap() = Some(std::time::Instant::now() + cooldown);
+        }
+    }
+}
 
-        let pairs: Vec<String> = data
-            .iter()
-            .map(|(key, value)| format!("{}={}", key, utf8_percent_encode(value, NON_ALPHANUMERIC)))
-            .collect();
+/// One facilitator in a [`FacilitatorFallbackChain`], optionally restricted to a
+/// subset of networks it's actually able to handle (e.g. a facilitator that only runs
+/// an Avalanche node shouldn't be tried for a `base` payment just because it's next in
+/// the fallback order). `None` means the facilitator is assumed to handle every
+/// network, matching the chain's behavior before this restriction existed.
+#[derive(Clone)]
+pub struct FacilitatorChainEntry {
+    client: FacilitatorClient,
+    networks: Option<Vec<String>>,
+}
 
-        pairs.join(",")
+impl FacilitatorChainEntry {
+    /// Wrap `client` with no network restriction
+    pub fn new(client: FacilitatorClient) -> Self {
+        Self {
+            client,
+            networks: None,
+        }
     }
 
-    /// Create a default Coinbase facilitator config
-    pub fn default_coinbase_config() -> FacilitatorConfig {
-        create_facilitator_config("", "")
+    /// Wrap `client`, restricting it to the given `networks`
+    pub fn for_networks(client: FacilitatorClient, networks: Vec<String>) -> Self {
+        Self {
+            client,
+            networks: Some(networks),
+        }
     }
 
-    /// Create a Coinbase facilitator config with explicit credentials
-    pub fn coinbase_config_with_credentials(
-        api_key_id: impl Into<String>,
-        api_key_secret: impl Into<String>,
-    ) -> FacilitatorConfig {
-        let id = api_key_id.into();
-        let secret = api_key_secret.into();
-        create_facilitator_config(&id, &secret)
+    fn supports(&self, network: &str) -> bool {
+        match &self.networks {
+            Some(networks) => networks.iter().any(|n| n == network),
+            None => true,
+        }
     }
+}
 
-    /// Create a Coinbase facilitator config from environment variables
-    pub fn coinbase_config_from_env() -> FacilitatorConfig {
-        use std::env;
-
-        let api_key_id = env::var("CDP_API_KEY_ID").unwrap_or_default();
-        let api_key_secret = env::var("CDP_API_KEY_SECRET").unwrap_or_default();
+impl std::fmt::Debug for FacilitatorChainEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FacilitatorChainEntry")
+            .field("url", &self.client.url())
+            .field("networks", &self.networks)
+            .finish()
+    }
+}
 
-        create_facilitator_config(&api_key_id, &api_key_secret)
+impl From<FacilitatorClient> for FacilitatorChainEntry {
+    fn from(client: FacilitatorClient) -> Self {
+        Self::new(client)
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use mockito::{Matcher, Server};
-    use serde_json::json;
-    use std::time::Duration;
+impl FacilitatorFallbackChain {
+    /// Create a chain over `facilitators`, each applicable to every network,
+    /// defaulting to [`RoutingPolicy::Priority`]
+    pub fn new(facilitators: Vec<FacilitatorClient>) -> Self {
+        Self::with_entries(facilitators.into_iter().map(FacilitatorChainEntry::new).collect())
+    }
 
-    #[tokio::test]
-    async fn test_facilitator_client_creation() {
-        let config = FacilitatorConfig::new("https://example.com/facilitator");
-        let client = FacilitatorClient::new(config).unwrap();
-        assert_eq!(client.url(), "https://example.com/facilitator");
+    /// Create a chain over `entries`, letting each facilitator declare which
+    /// networks it applies to via [`FacilitatorChainEntry::for_networks`]
+    pub fn with_entries(entries: Vec<FacilitatorChainEntry>) -> Self {
+        let health = entries.iter().map(|_| EndpointHealth::default()).collect();
+        Self {
+            entries,
+            policy: RoutingPolicy::Priority,
+            next_round_robin: std::sync::atomic::AtomicUsize::new(0),
+            health,
+            failure_threshold: 3,
+            cooldown: std::time::Duration::from_secs(30),
+        }
     }
 
-    #[tokio::test]
-    async fn test_facilitator_verify_success() {
-        let mut server = Server::new_async().await;
-        let _m = server
-            .mock("POST", "/verify")
-            .with_status(200)
-            .with_header("content-type", "application/json")
-            .with_body(
-                json!({
-                    "x402Version": 1,
-                    "isValid": true,
-                    "payer": "0x857b06519E91e3A54538791bDbb0E22373e36b66"
-                })
-                .to_string(),
-            )
-            .create();
+    /// Set the routing policy used to pick the starting facilitator
+    pub fn with_policy(mut self, policy: RoutingPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
 
-        let config = FacilitatorConfig::new(server.url());
-        let client = FacilitatorClient::new(config).unwrap();
+    /// Set how many consecutive transient failures an entry must accumulate
+    /// before [`Self::verify`]/[`Self::settle`] stop routing to it for
+    /// [`Self::with_cooldown`]'s duration
+    pub fn with_failure_threshold(mut self, failure_threshold: u32) -> Self {
+        self.failure_threshold = failure_threshold;
+        self
+    }
 
-        let payment_payload = create_test_payment_payload();
-        let payment_requirements = create_test_payment_requirements();
+    /// Set how long an entry that tripped [`Self::with_failure_threshold`] is
+    /// skipped before being tried again
+    pub fn with_cooldown(mut self, cooldown: std::time::Duration) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
 
-        let response = client
-            .verify(&payment_payload, &payment_requirements)
-            .await
-            .unwrap();
-        assert!(response.is_valid);
-        assert_eq!(
-            response.payer,
-            Some("0x857b06519E91e3A54538791bDbb0E22373e36b66".to_string())
-        );
+    /// Whether `index` is currently outside its cooldown window, i.e. eligible to
+    /// be routed to
+    pub fn is_healthy(&self, index: usize) -> bool {
+        self.health.get(index).map(|h| !h.is_in_cooldown()).unwrap_or(false)
     }
 
-    #[tokio::test]
-    async fn test_facilitator_verify_failure() {
-        let mut server = Server::new_async().await;
-        let _m = server
-            .mock("POST", "/verify")
-            .with_status(200)
-            .with_header("content-type", "application/json")
-            .with_body(
-                json!({
+    /// The facilitator at `index`'s base URL, for reporting which backend
+    /// handled a payment
+    pub fn facilitator_url(&self, index: usize) -> Option<&str> {
+        self.entries.get(index).map(|e| e.client.url())
+    }
+
+    /// Indices of entries applicable to `requirements.network`, ordered by policy.
+    /// Entries currently in cooldown (see [`Self::with_failure_threshold`]) are
+    /// skipped, unless every applicable entry is down — in which case cooldowns
+    /// are ignored so a call still has somewhere to go instead of failing outright
+    /// with every backend known to be unhealthy.
+    fn ordered_indices(&self, requirements: &PaymentRequirements) -> Vec<usize> {
+        let applicable: Vec<usize> = (0..self.entries.len())
+            .filter(|&i| self.entries[i].supports(&requirements.network))
+            .collect();
+
+        let healthy: Vec<usize> = applicable.iter().copied().filter(|&i| self.is_healthy(i)).collect();
+        let applicable = if healthy.is_empty() { applicable } else { healthy };
+
+        let n = applicable.len();
+        if n == 0 {
+            return Vec::new();
+        }
+
+        let start = match &self.policy {
+            RoutingPolicy::Priority => 0,
+            RoutingPolicy::RoundRobin => {
+                self.next_round_robin
+                    .fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+                    % n
+            }
+            RoutingPolicy::Predicate(select) => select(requirements) % n,
+        };
+
+        (0..n).map(|i| applicable[(start + i) % n]).collect()
+    }
+
+    fn no_facilitator_error(&self, requirements: &PaymentRequirements) -> X402Error {
+        if self.entries.is_empty() {
+            X402Error::config("FacilitatorFallbackChain has no facilitators configured")
+        } else {
+            X402Error::config(format!(
+                "FacilitatorFallbackChain has no facilitator configured for network '{}'",
+                requirements.network
+            ))
+        }
+    }
+
+    /// Verify a payment, trying facilitators applicable to `payment_requirements.network`
+    /// in policy order and falling back on a transient error. Returns the responding
+    /// facilitator's index alongside its response.
+    pub async fn verify(
+        &self,
+        payment_payload: &PaymentPayload,
+        payment_requirements: &PaymentRequirements,
+    ) -> Result<(usize, VerifyResponse)> {
+        let mut last_error = None;
+        for index in self.ordered_indices(payment_requirements) {
+            match self.entries[index]
+                .client
+                .verify(payment_payload, payment_requirements)
+                .await
+            {
+                Ok(response) => {
+                    self.health[index].record_success();
+                    return Ok((index, response));
+                }
+                Err(e) if e.is_retryable() => {
+                    tracing::warn!(
+                        "Facilitator '{}' failed to verify, trying next: {}",
+                        self.entries[index].client.url(),
+                        e
+                    );
+                    self.health[index].record_failure(self.failure_threshold, self.cooldown);
+                    last_error = Some(e);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| self.no_facilitator_error(payment_requirements)))
+    }
+
+    /// Settle a payment, trying facilitators applicable to `payment_requirements.network`
+    /// in policy order and falling back on a transient error. Returns the responding
+    /// facilitator's index alongside its response.
+    pub async fn settle(
+        &self,
+        payment_payload: &PaymentPayload,
+        payment_requirements: &PaymentRequirements,
+    ) -> Result<(usize, SettleResponse)> {
+        let mut last_error = None;
+        for index in self.ordered_indices(payment_requirements) {
+            match self.entries[index]
+                .client
+                .settle(payment_payload, payment_requirements)
+                .await
+            {
+                Ok(response) => {
+                    self.health[index].record_success();
+                    return Ok((index, response));
+                }
+                Err(e) if e.is_retryable() => {
+                    tracing::warn!(
+                        "Facilitator '{}' failed to settle, trying next: {}",
+                        self.entries[index].client.url(),
+                        e
+                    );
+                    self.health[index].record_failure(self.failure_threshold, self.cooldown);
+                    last_error = Some(e);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| self.no_facilitator_error(payment_requirements)))
+    }
+}
+
+impl std::fmt::Debug for FacilitatorFallbackChain {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FacilitatorFallbackChain")
+            .field("entries", &self.entries)
+            .field("policy", &self.policy)
+            .field("failure_threshold", &self.failure_threshold)
+            .field("cooldown", &self.cooldown)
+            .finish()
+    }
+}
+
+/// Backoff schedule for [`RetryableFacilitator`]
+///
+/// Distinct from [`crate::retry::RetryPolicy`]'s fixed exponential-with-full-jitter
+/// schedule: callers pick their own growth `multiplier` here, and pair it with a
+/// [`RetryableFacilitator`]-specific predicate deciding which errors are worth a retry
+/// at all, rather than always deferring to [`X402Error::is_retryable`].
+#[derive(Debug, Clone)]
+pub struct RetryableFacilitatorPolicy {
+    /// Maximum number of attempts, including the initial one
+    pub max_attempts: u32,
+    /// Delay before the first retry
+    pub base_delay: std::time::Duration,
+    /// Upper bound on any single retry delay
+    pub max_delay: std::time::Duration,
+    /// Growth factor applied to the delay after each attempt
+    pub multiplier: f64,
+}
+
+impl RetryableFacilitatorPolicy {
+    /// Sensible defaults: 3 attempts, 200ms base delay, 5s cap, 2x growth
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the maximum number of attempts, including the initial one
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// Set the delay before the first retry
+    pub fn with_base_delay(mut self, base_delay: std::time::Duration) -> Self {
+        self.base_delay = base_delay;
+        self
+    }
+
+    /// Set the upper bound on any single retry delay
+    pub fn with_max_delay(mut self, max_delay: std::time::Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Set the growth factor applied to the delay after each attempt
+    pub fn with_multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier;
+        self
+    }
+
+    /// Compute the delay before retrying after the given zero-indexed attempt: the
+    /// exponential backoff, capped at `max_delay`, plus uniform random jitter in
+    /// `[0, base_delay)` so concurrent callers retrying the same facilitator outage
+    /// don't all wake up and hammer it at the exact same instant.
+    pub fn delay_for_attempt(&self, attempt: u32) -> std::time::Duration {
+        let factor = self.multiplier.powi(attempt as i32);
+        let millis = (self.base_delay.as_millis() as f64 * factor) as u64;
+        let backoff = std::time::Duration::from_millis(millis).min(self.max_delay);
+        let jitter = std::time::Duration::from_millis(
+            rand::thread_rng().gen_range(0..=self.base_delay.as_millis() as u64),
+        );
+        backoff + jitter
+    }
+}
+
+impl Default for RetryableFacilitatorPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: std::time::Duration::from_millis(200),
+            max_delay: std::time::Duration::from_secs(5),
+            multiplier: 2.0,
+        }
+    }
+}
+
+/// Decides whether a given [`X402Error`] is worth retrying
+pub type RetryPredicate = std::sync::Arc<dyn Fn(&X402Error) -> bool + Send + Sync>;
+
+/// Treats anything [`X402Error::is_retryable`] considers transient as retryable, which
+/// in particular means an explicit facilitator rejection (bad signature, insufficient
+/// funds, nonce reuse, ...) is never retried — only transport errors and a
+/// facilitator-reported `RateLimited`/`UpstreamUnavailable` are.
+///
+/// Used as-is for `verify` and `supported`, both of which are safe to replay freely
+/// since neither has an irreversible side effect. [`default_settle_retry_predicate`]
+/// is the narrower predicate used for `settle` by default.
+fn default_retry_predicate(error: &X402Error) -> bool {
+    error.is_retryable()
+}
+
+/// Retries `settle` only on errors that mean the request never reached the
+/// facilitator at all — a bare transport failure or timeout. Unlike
+/// [`default_retry_predicate`], a facilitator-reported `RateLimited` or
+/// `UpstreamUnavailable` is treated as terminal here: the facilitator received the
+/// request, so whether it also forwarded the transaction on-chain before returning
+/// that response is unknown, and retrying could double-settle the same payment.
+fn default_settle_retry_predicate(error: &X402Error) -> bool {
+    matches!(error, X402Error::Http(_) | X402Error::Timeout)
+}
+
+/// Decorator around [`FacilitatorClient`] that retries `verify`/`settle`/`supported`
+/// with configurable backoff, inspired by fuels-rs's `retry_util`/`retryable_client`
+/// split: the inner client stays a plain transport, and resilience is layered on top
+/// instead of being baked into it.
+///
+/// Unlike [`FacilitatorClient::retry_policy`], which always retries whatever
+/// [`X402Error::is_retryable`] allows, this type lets callers supply their own
+/// predicates via [`Self::with_retry_predicate`]/[`Self::with_settle_retry_predicate`]
+/// — useful when a deployment wants to also retry an `Unknown`-coded facilitator
+/// error it has seen be transient. `verify` and `supported` are idempotent and share
+/// one predicate; `settle` defaults to the stricter
+/// [`default_settle_retry_predicate`] since replaying it risks double-settling a
+/// payment the facilitator already broadcast.
+#[derive(Clone)]
+pub struct RetryableFacilitator {
+    inner: FacilitatorClient,
+    policy: RetryableFacilitatorPolicy,
+    should_retry: RetryPredicate,
+    should_retry_settle: RetryPredicate,
+}
+
+impl RetryableFacilitator {
+    /// Wrap `inner`, retrying with [`RetryableFacilitatorPolicy::default`], the
+    /// default predicate for `verify`/`supported` (retry iff
+    /// [`X402Error::is_retryable`]), and the stricter
+    /// [`default_settle_retry_predicate`] for `settle`
+    pub fn new(inner: FacilitatorClient) -> Self {
+        Self {
+            inner,
+            policy: RetryableFacilitatorPolicy::default(),
+            should_retry: std::sync::Arc::new(default_retry_predicate),
+            should_retry_settle: std::sync::Arc::new(default_settle_retry_predicate),
+        }
+    }
+
+    /// Set the backoff schedule
+    pub fn with_policy(mut self, policy: RetryableFacilitatorPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Override which errors are retried for `verify`/`supported`
+    pub fn with_retry_predicate(
+        mut self,
+        predicate: impl Fn(&X402Error) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.should_retry = std::sync::Arc::new(predicate);
+        self
+    }
+
+    /// Override which errors are retried for `settle`. Only widen this past the
+    /// default if the deployment can otherwise rule out double-settlement (e.g. the
+    /// facilitator is known to be idempotent on a client-supplied nonce).
+    pub fn with_settle_retry_predicate(
+        mut self,
+        predicate: impl Fn(&X402Error) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.should_retry_settle = std::sync::Arc::new(predicate);
+        self
+    }
+
+    /// Verify a payment, retrying per the configured policy and predicate
+    pub async fn verify(
+        &self,
+        payment_payload: &PaymentPayload,
+        payment_requirements: &PaymentRequirements,
+    ) -> Result<VerifyResponse> {
+        self.with_retries(&self.should_retry, || {
+            self.inner.verify(payment_payload, payment_requirements)
+        })
+        .await
+    }
+
+    /// Settle a payment, retrying only on a pre-broadcast connection failure (see
+    /// [`default_settle_retry_predicate`]) to avoid double-settlement
+    pub async fn settle(
+        &self,
+        payment_payload: &PaymentPayload,
+        payment_requirements: &PaymentRequirements,
+    ) -> Result<SettleResponse> {
+        self.with_retries(&self.should_retry_settle, || {
+            self.inner.settle(payment_payload, payment_requirements)
+        })
+        .await
+    }
+
+    /// Fetch the facilitator's supported kinds, retrying per the configured policy
+    /// and predicate — safe to retry freely since it has no side effects
+    pub async fn supported(&self) -> Result<SupportedKinds> {
+        self.with_retries(&self.should_retry, || self.inner.supported())
+            .await
+    }
+
+    /// Prefers the failed error's own [`X402Error::retry_after`] (parsed from a
+    /// `Retry-After` response header) over `self.policy`'s computed delay when one is
+    /// present, matching [`crate::retry::retry_with_backoff`].
+    async fn with_retries<T, F, Fut>(&self, should_retry: &RetryPredicate, mut operation: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: std::future::Future<Output = Result<T>>,
+    {
+        let mut attempt = 0;
+        loop {
+            match operation().await {
+                Ok(value) => return Ok(value),
+                Err(error) if attempt + 1 < self.policy.max_attempts && should_retry(&error) => {
+                    let delay = error
+                        .retry_after()
+                        .unwrap_or_else(|| self.policy.delay_for_attempt(attempt));
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(error) => return Err(error),
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for RetryableFacilitator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RetryableFacilitator")
+            .field("inner", &self.inner)
+            .field("policy", &self.policy)
+            .finish()
+    }
+}
+
+/// A boxed, `Send` future, used in place of `async fn` in [`Facilitator`] since traits
+/// can't have object-safe async methods on stable Rust without an extra proc-macro
+/// crate this workspace doesn't otherwise depend on
+pub type BoxFuture<'a, T> = std::pin::Pin<Box<dyn std::future::Future<Output = T> + Send + 'a>>;
+
+/// Object-safe facilitator backend, abstracting over how `verify`/`settle` are
+/// actually carried out
+///
+/// [`FacilitatorClient`] is the only implementation today (an HTTP call to a
+/// facilitator service), but this trait is the seam [`FacilitatorRegistry`] routes
+/// through, so a test can inject a mock backend with no network access, and so a
+/// scheme settled in-process (see [`crate::scheme_registry::SchemeHandler`]) could one
+/// day be adapted to the same interface.
+pub trait Facilitator: Send + Sync {
+    /// Verify a payment payload against the given requirements
+    fn verify<'a>(
+        &'a self,
+        payment_payload: &'a PaymentPayload,
+        payment_requirements: &'a PaymentRequirements,
+    ) -> BoxFuture<'a, Result<VerifyResponse>>;
+
+    /// Settle a verified payment by executing the transaction
+    fn settle<'a>(
+        &'a self,
+        payment_payload: &'a PaymentPayload,
+        payment_requirements: &'a PaymentRequirements,
+    ) -> BoxFuture<'a, Result<SettleResponse>>;
+
+    /// Fetch the schemes, networks and protocol versions this backend accepts
+    fn supported(&self) -> BoxFuture<'_, Result<SupportedKinds>>;
+
+    /// List discoverable resources this backend knows about. Backends with no
+    /// discovery endpoint of their own can leave this at its default, which
+    /// reports [`X402Error::config`]
+    fn list<'a>(&'a self, _filters: Option<DiscoveryFilters>) -> BoxFuture<'a, Result<DiscoveryResponse>> {
+        Box::pin(async { Err(X402Error::config("this facilitator backend does not support discovery")) })
+    }
+}
+
+impl Facilitator for FacilitatorClient {
+    fn verify<'a>(
+        &'a self,
+        payment_payload: &'a PaymentPayload,
+        payment_requirements: &'a PaymentRequirements,
+    ) -> BoxFuture<'a, Result<VerifyResponse>> {
+        Box::pin(FacilitatorClient::verify(self, payment_payload, payment_requirements))
+    }
+
+    fn settle<'a>(
+        &'a self,
+        payment_payload: &'a PaymentPayload,
+        payment_requirements: &'a PaymentRequirements,
+    ) -> BoxFuture<'a, Result<SettleResponse>> {
+        Box::pin(FacilitatorClient::settle(self, payment_payload, payment_requirements))
+    }
+
+    fn supported(&self) -> BoxFuture<'_, Result<SupportedKinds>> {
+        Box::pin(FacilitatorClient::supported(self))
+    }
+
+    fn list<'a>(&'a self, filters: Option<DiscoveryFilters>) -> BoxFuture<'a, Result<DiscoveryResponse>> {
+        Box::pin(FacilitatorClient::list(self, filters))
+    }
+}
+
+impl Facilitator for RetryableFacilitator {
+    fn verify<'a>(
+        &'a self,
+        payment_payload: &'a PaymentPayload,
+        payment_requirements: &'a PaymentRequirements,
+    ) -> BoxFuture<'a, Result<VerifyResponse>> {
+        Box::pin(RetryableFacilitator::verify(self, payment_payload, payment_requirements))
+    }
+
+    fn settle<'a>(
+        &'a self,
+        payment_payload: &'a PaymentPayload,
+        payment_requirements: &'a PaymentRequirements,
+    ) -> BoxFuture<'a, Result<SettleResponse>> {
+        Box::pin(RetryableFacilitator::settle(self, payment_payload, payment_requirements))
+    }
+
+    fn supported(&self) -> BoxFuture<'_, Result<SupportedKinds>> {
+        Box::pin(RetryableFacilitator::supported(self))
+    }
+}
+
+/// Highest `x402Version` a [`SupportedKinds`] document advertises support for
+fn max_x402_version(supported: &SupportedKinds) -> u32 {
+    supported
+        .kinds
+        .iter()
+        .map(|kind| kind.x402_version)
+        .max()
+        .unwrap_or(X402_VERSION)
+}
+
+/// Refuse to emit a `PaymentRequired` at a protocol version the facilitator can't
+/// process, modeled on fuels-rs's `supported_versions` compatibility check: a
+/// facilitator that's behind just gets a warning and a pass if it's still within the
+/// versions it advertises, but one that can't handle `X402_VERSION` at all is a hard
+/// error rather than a request doomed to be rejected downstream.
+fn ensure_version_compatible(supported: &SupportedKinds) -> Result<()> {
+    let facilitator_max = max_x402_version(supported);
+    if X402_VERSION > facilitator_max {
+        tracing::warn!(
+            "Client x402 version {} is newer than facilitator's advertised max {}",
+            X402_VERSION,
+            facilitator_max
+        );
+        return Err(X402Error::version_mismatch(facilitator_max, X402_VERSION));
+    }
+    Ok(())
+}
+
+/// Caches a backend's `/supported` document for `ttl`, so capability checks on the
+/// request path don't each cost a round trip to the facilitator
+pub struct CachedFacilitator {
+    inner: std::sync::Arc<dyn Facilitator>,
+    ttl: std::time::Duration,
+    cache: tokio::sync::Mutex<Option<(SupportedKinds, std::time::Instant)>>,
+}
+
+impl CachedFacilitator {
+    /// Wrap `inner`, caching its `supported()` response for `ttl`
+    pub fn new(inner: std::sync::Arc<dyn Facilitator>, ttl: std::time::Duration) -> Self {
+        Self {
+            inner,
+            ttl,
+            cache: tokio::sync::Mutex::new(None),
+        }
+    }
+
+    /// Fetch `inner`'s supported kinds, reusing the cached value if it's not older
+    /// than `ttl`
+    pub async fn supported_cached(&self) -> Result<SupportedKinds> {
+        let mut cache = self.cache.lock().await;
+        if let Some((kinds, fetched_at)) = cache.as_ref() {
+            if fetched_at.elapsed() <= self.ttl {
+                return Ok(kinds.clone());
+            }
+        }
+
+        let kinds = self.inner.supported().await?;
+        *cache = Some((kinds.clone(), std::time::Instant::now()));
+        Ok(kinds)
+    }
+
+    /// Fail fast if `network`/`scheme` isn't among the (possibly cached) supported kinds
+    pub async fn ensure_supports(&self, network: &str, scheme: &str) -> Result<()> {
+        let supported = self.supported_cached().await?;
+        ensure_version_compatible(&supported)?;
+
+        let accepted = supported
+            .kinds
+            .iter()
+            .any(|kind| kind.network == network && kind.scheme == scheme);
+
+        if !accepted {
+            let available: Vec<u32> = supported
+                .kinds
+                .iter()
+                .filter(|kind| kind.network == network)
+                .map(|kind| kind.x402_version)
+                .collect();
+            return Err(X402Error::scheme_not_supported(scheme, network, available));
+        }
+        Ok(())
+    }
+
+    /// Fail fast if `requirements`' `(network, scheme)` isn't supported (same check
+    /// as [`Self::ensure_supports`]), and additionally if its `asset` isn't one this
+    /// backend scoped a matching kind to. A facilitator that didn't scope any of its
+    /// matching kinds to a specific asset is treated as accepting any asset for that
+    /// pairing, since `asset` is an optional field on [`SupportedKind`] older or
+    /// asset-agnostic facilitators may not advertise at all.
+    ///
+    /// Returns [`X402Error::UnsupportedByFacilitator`] for the asset mismatch case,
+    /// distinct from [`X402Error::SchemeNotSupported`] (which [`Self::ensure_supports`]
+    /// already returns when the `(network, scheme)` pairing itself isn't listed, or
+    /// lists no overlapping protocol version).
+    pub async fn ensure_supports_requirements(&self, requirements: &PaymentRequirements) -> Result<()> {
+        self.ensure_supports(&requirements.network, &requirements.scheme).await?;
+
+        let supported = self.supported_cached().await?;
+        let pairing_assets: Vec<&str> = supported
+            .kinds
+            .iter()
+            .filter(|kind| kind.network == requirements.network && kind.scheme == requirements.scheme)
+            .filter_map(|kind| kind.asset.as_deref())
+            .collect();
+
+        if !pairing_assets.is_empty() && !pairing_assets.contains(&requirements.asset.as_str()) {
+            return Err(X402Error::unsupported_by_facilitator(
+                &requirements.scheme,
+                &requirements.network,
+                &requirements.asset,
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Facilitator for CachedFacilitator {
+    fn verify<'a>(
+        &'a self,
+        payment_payload: &'a PaymentPayload,
+        payment_requirements: &'a PaymentRequirements,
+    ) -> BoxFuture<'a, Result<VerifyResponse>> {
+        Box::pin(async move {
+            self.ensure_supports_requirements(payment_requirements).await?;
+            self.inner.verify(payment_payload, payment_requirements).await
+        })
+    }
+
+    fn settle<'a>(
+        &'a self,
+        payment_payload: &'a PaymentPayload,
+        payment_requirements: &'a PaymentRequirements,
+    ) -> BoxFuture<'a, Result<SettleResponse>> {
+        Box::pin(async move {
+            self.ensure_supports_requirements(payment_requirements).await?;
+            self.inner.settle(payment_payload, payment_requirements).await
+        })
+    }
+
+    fn supported(&self) -> BoxFuture<'_, Result<SupportedKinds>> {
+        Box::pin(self.supported_cached())
+    }
+}
+
+/// A single named backend in a [`FacilitatorRegistry`]
+struct FacilitatorBackend {
+    facilitator: std::sync::Arc<dyn Facilitator>,
+    /// `(network, scheme)` pairs this backend is declared to support
+    supports: std::collections::HashSet<(String, String)>,
+}
+
+/// Routes `verify`/`settle` to whichever named backend declares support for a
+/// payment's `(network, scheme)` pair
+///
+/// Unlike [`FacilitatorRouter`], which picks a facilitator by network alone,
+/// `FacilitatorRegistry` lets the same network be served by different backends
+/// depending on scheme (e.g. `exact` on Base going through one facilitator while a
+/// future Solana scheme goes through another), inspired by how hyperswitch maps each
+/// payment connector to its own credentials and declared capabilities.
+#[derive(Default)]
+pub struct FacilitatorRegistry {
+    backends: HashMap<String, FacilitatorBackend>,
+}
+
+impl FacilitatorRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register `facilitator` under `name`, declaring the `(network, scheme)` pairs
+    /// it should be used for
+    pub fn with_backend(
+        mut self,
+        name: impl Into<String>,
+        facilitator: std::sync::Arc<dyn Facilitator>,
+        supports: impl IntoIterator<Item = (String, String)>,
+    ) -> Self {
+        self.backends.insert(
+            name.into(),
+            FacilitatorBackend {
+                facilitator,
+                supports: supports.into_iter().collect(),
+            },
+        );
+        self
+    }
+
+    /// Find the backend declared to support `network`/`scheme`, if any
+    pub fn resolve(&self, network: &str, scheme: &str) -> Option<&std::sync::Arc<dyn Facilitator>> {
+        let key = (network.to_string(), scheme.to_string());
+        self.backends
+            .values()
+            .find(|backend| backend.supports.contains(&key))
+            .map(|backend| &backend.facilitator)
+    }
+
+    /// Verify a payment through whichever backend supports its network and scheme
+    pub async fn verify(
+        &self,
+        payment_payload: &PaymentPayload,
+        payment_requirements: &PaymentRequirements,
+    ) -> Result<VerifyResponse> {
+        let facilitator = self.resolve(&payment_requirements.network, &payment_requirements.scheme)
+            .ok_or_else(|| X402Error::NetworkNotSupported {
+                network: payment_requirements.network.clone(),
+            })?;
+        facilitator.verify(payment_payload, payment_requirements).await
+    }
+
+    /// Settle a payment through whichever backend supports its network and scheme
+    pub async fn settle(
+        &self,
+        payment_payload: &PaymentPayload,
+        payment_requirements: &PaymentRequirements,
+    ) -> Result<SettleResponse> {
+        let facilitator = self.resolve(&payment_requirements.network, &payment_requirements.scheme)
+            .ok_or_else(|| X402Error::NetworkNotSupported {
+                network: payment_requirements.network.clone(),
+            })?;
+        facilitator.settle(payment_payload, payment_requirements).await
+    }
+
+    /// Check every backend's declared `(network, scheme)` pairings against what it
+    /// actually reports from `/supported`, failing fast at config-build time with a
+    /// clear error instead of discovering a rejection the first time a payment for
+    /// that pairing arrives.
+    pub async fn validate(&self) -> Result<()> {
+        for (name, backend) in &self.backends {
+            let supported = backend.facilitator.supported().await?;
+            ensure_version_compatible(&supported)?;
+
+            for (network, scheme) in &backend.supports {
+                let accepted = supported
+                    .kinds
+                    .iter()
+                    .any(|kind| &kind.network == network && &kind.scheme == scheme);
+                if !accepted {
+                    return Err(X402Error::config(format!(
+                        "Facilitator backend '{}' was registered for network '{}' and scheme '{}', but its /supported document doesn't list that pairing",
+                        name, network, scheme
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for FacilitatorRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FacilitatorRegistry")
+            .field("backends", &self.backends.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// A facilitator registered for a specific `(scheme, network)` pairing, with its own
+/// base URL and settings, for building a [`KeyedFacilitatorChain`]
+///
+/// Several entries may share the same `scheme`/`network`; they become an ordered
+/// fallback group for that pairing, in the order they were given.
+#[derive(Debug, Clone)]
+pub struct FacilitatorEntry {
+    /// Payment scheme this entry handles, e.g. `"exact"`
+    pub scheme: String,
+    /// Network this entry handles, e.g. `"base"` or `"base-sepolia"`
+    pub network: String,
+    /// Facilitator connection settings for this entry
+    pub config: FacilitatorConfig,
+}
+
+impl FacilitatorEntry {
+    /// Register a facilitator at `config` for `scheme`/`network`
+    pub fn new(scheme: impl Into<String>, network: impl Into<String>, config: FacilitatorConfig) -> Self {
+        Self {
+            scheme: scheme.into(),
+            network: network.into(),
+            config,
+        }
+    }
+}
+
+/// Routes `verify`/`settle` to the [`FacilitatorFallbackChain`] registered for a
+/// payment's `(scheme, network)` pairing, so a single deployment can accept payments
+/// across several chains (e.g. Base and Base-Sepolia) while still falling back within
+/// each chain when its primary facilitator returns a transient error.
+///
+/// Where [`FacilitatorRegistry`] maps each `(network, scheme)` pairing to exactly one
+/// backend, `KeyedFacilitatorChain` maps it to an ordered [`FacilitatorFallbackChain`],
+/// combining the redundancy [`FacilitatorFallbackChain`] provides with per-pairing
+/// routing.
+pub struct KeyedFacilitatorChain {
+    chains: HashMap<(String, String), FacilitatorFallbackChain>,
+}
+
+impl KeyedFacilitatorChain {
+    /// Group `entries` by `(scheme, network)` into a [`FacilitatorFallbackChain`] per
+    /// pairing, applying `policy` to every chain
+    pub fn new(entries: Vec<FacilitatorEntry>, policy: RoutingPolicy) -> Result<Self> {
+        let mut grouped: HashMap<(String, String), Vec<FacilitatorClient>> = HashMap::new();
+        for entry in entries {
+            let client = FacilitatorClient::new(entry.config)?;
+            grouped
+                .entry((entry.scheme, entry.network))
+                .or_default()
+                .push(client);
+        }
+
+        let chains = grouped
+            .into_iter()
+            .map(|(key, clients)| {
+                (
+                    key,
+                    FacilitatorFallbackChain::new(clients).with_policy(policy.clone()),
+                )
+            })
+            .collect();
+
+        Ok(Self { chains })
+    }
+
+    /// Find the fallback chain registered for `scheme`/`network`, if any
+    pub fn resolve(&self, scheme: &str, network: &str) -> Option<&FacilitatorFallbackChain> {
+        self.chains.get(&(scheme.to_string(), network.to_string()))
+    }
+
+    /// Verify a payment through the chain registered for its `(scheme, network)`,
+    /// falling back within that chain on a transient error. Returns the responding
+    /// facilitator's base URL alongside its response.
+    pub async fn verify(
+        &self,
+        payment_payload: &PaymentPayload,
+        payment_requirements: &PaymentRequirements,
+    ) -> Result<(Option<String>, VerifyResponse)> {
+        let chain = self
+            .resolve(&payment_requirements.scheme, &payment_requirements.network)
+            .ok_or_else(|| X402Error::NetworkNotSupported {
+                network: payment_requirements.network.clone(),
+            })?;
+        let (index, response) = chain.verify(payment_payload, payment_requirements).await?;
+        Ok((chain.facilitator_url(index).map(|u| u.to_string()), response))
+    }
+
+    /// Settle a payment through the chain registered for its `(scheme, network)`,
+    /// falling back within that chain on a transient error. Returns the responding
+    /// facilitator's base URL alongside its response.
+    pub async fn settle(
+        &self,
+        payment_payload: &PaymentPayload,
+        payment_requirements: &PaymentRequirements,
+    ) -> Result<(Option<String>, SettleResponse)> {
+        let chain = self
+            .resolve(&payment_requirements.scheme, &payment_requirements.network)
+            .ok_or_else(|| X402Error::NetworkNotSupported {
+                network: payment_requirements.network.clone(),
+            })?;
+        let (index, response) = chain.settle(payment_payload, payment_requirements).await?;
+        Ok((chain.facilitator_url(index).map(|u| u.to_string()), response))
+    }
+}
+
+impl std::fmt::Debug for KeyedFacilitatorChain {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KeyedFacilitatorChain")
+            .field("keys", &self.chains.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// One facilitator in a [`QuorumFacilitator`], weighted so a more-trusted endpoint
+/// can outvote several lesser ones without being listed multiple times
+#[derive(Clone)]
+pub struct QuorumMember {
+    facilitator: Arc<dyn Facilitator>,
+    weight: u32,
+}
+
+impl QuorumMember {
+    /// Include `facilitator` in the quorum, contributing `weight` toward the
+    /// threshold when it agrees with others
+    pub fn new(facilitator: Arc<dyn Facilitator>, weight: u32) -> Self {
+        Self { facilitator, weight }
+    }
+}
+
+/// How [`QuorumFacilitator::settle`] picks a winner among its members
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuorumSettleMode {
+    /// Dispatch to every member concurrently; the first to report a successful
+    /// settlement with a transaction hash wins, and the rest are dropped mid-flight
+    FirstSuccess,
+    /// Try members in weight-descending order, falling back to the next one only on
+    /// a transient ([`X402Error::is_retryable`]) error — mirrors
+    /// [`FacilitatorFallbackChain::settle`]
+    PrimaryWithFallback,
+}
+
+/// Cross-checks `verify` across multiple independent facilitators before trusting the
+/// result, for a merchant who doesn't want a single facilitator's word on payment
+/// validity. Dispatches to every [`QuorumMember`] concurrently, groups the responses by
+/// `(is_valid, invalid_reason)`, and requires the agreeing group's summed `weight` to
+/// meet `threshold` before returning that group's response; otherwise returns an error
+/// summarizing the disagreement. `settle` supports two modes (see
+/// [`QuorumSettleMode`]); `supported` reports the intersection of every member's
+/// supported `(scheme, network)` pairs. Mirrors the multi-provider quorum pattern used
+/// by JSON-RPC clients that cross-check several nodes before trusting a response.
+pub struct QuorumFacilitator {
+    members: Vec<QuorumMember>,
+    threshold: u32,
+    settle_mode: QuorumSettleMode,
+}
+
+/// Settle through `member`, treating anything other than a successful settlement
+/// with a transaction hash as a failure so [`futures_util::future::select_ok`] skips
+/// over it instead of declaring it the winner
+async fn settle_if_successful(
+    member: &QuorumMember,
+    payment_payload: &PaymentPayload,
+    payment_requirements: &PaymentRequirements,
+) -> Result<SettleResponse> {
+    let response = member.facilitator.settle(payment_payload, payment_requirements).await?;
+    if response.success && !response.transaction.is_empty() {
+        Ok(response)
+    } else {
+        Err(X402Error::facilitator_error(
+            "Member settlement did not succeed with a transaction hash",
+        ))
+    }
+}
+
+impl QuorumFacilitator {
+    /// Cross-check `members`, requiring agreeing weight to reach `threshold`;
+    /// defaults to [`QuorumSettleMode::PrimaryWithFallback`] for `settle`
+    pub fn new(members: Vec<QuorumMember>, threshold: u32) -> Self {
+        Self {
+            members,
+            threshold,
+            settle_mode: QuorumSettleMode::PrimaryWithFallback,
+        }
+    }
+
+    /// Set how [`Self::settle`] picks a winner among members
+    pub fn with_settle_mode(mut self, settle_mode: QuorumSettleMode) -> Self {
+        self.settle_mode = settle_mode;
+        self
+    }
+
+    fn no_members_error() -> X402Error {
+        X402Error::config("QuorumFacilitator has no members configured")
+    }
+
+    /// Verify a payment against every member concurrently, returning the response
+    /// agreed on by members whose summed weight meets `threshold`
+    pub async fn verify(
+        &self,
+        payment_payload: &PaymentPayload,
+        payment_requirements: &PaymentRequirements,
+    ) -> Result<VerifyResponse> {
+        if self.members.is_empty() {
+            return Err(Self::no_members_error());
+        }
+
+        let responses = futures_util::future::join_all(
+            self.members
+                .iter()
+                .map(|member| member.facilitator.verify(payment_payload, payment_requirements)),
+        )
+        .await;
+
+        // Group agreeing (is_valid, invalid_reason) responses and sum the weight
+        // behind each group, rather than requiring every member to respond
+        // identically or answer at all — a member that errored simply contributes no
+        // weight to any group.
+        let mut groups: Vec<(VerifyResponse, u32)> = Vec::new();
+        for (member, response) in self.members.iter().zip(responses) {
+            let Ok(response) = response else { continue };
+            match groups
+                .iter_mut()
+                .find(|(existing, _)| existing.is_valid == response.is_valid && existing.invalid_reason == response.invalid_reason)
+            {
+                Some((_, weight)) => *weight += member.weight,
+                None => groups.push((response, member.weight)),
+            }
+        }
+
+        match groups.into_iter().max_by_key(|(_, weight)| *weight) {
+            Some((response, weight)) if weight >= self.threshold => Ok(response),
+            Some((response, weight)) => Err(X402Error::facilitator_error(format!(
+                "Quorum not reached: best-agreeing response ({:?}, weight {}) fell short of threshold {}",
+                response, weight, self.threshold
+            ))),
+            None => Err(X402Error::facilitator_error(
+                "Quorum not reached: every member failed to respond",
+            )),
+        }
+    }
+
+    /// Settle a payment according to [`Self::settle_mode`]
+    pub async fn settle(
+        &self,
+        payment_payload: &PaymentPayload,
+        payment_requirements: &PaymentRequirements,
+    ) -> Result<SettleResponse> {
+        if self.members.is_empty() {
+            return Err(Self::no_members_error());
+        }
+
+        match self.settle_mode {
+            QuorumSettleMode::FirstSuccess => {
+                let attempts: Vec<BoxFuture<'_, Result<SettleResponse>>> = self
+                    .members
+                    .iter()
+                    .map(|member| {
+                        Box::pin(settle_if_successful(member, payment_payload, payment_requirements))
+                            as BoxFuture<'_, Result<SettleResponse>>
+                    })
+                    .collect();
+
+                let (response, _still_pending) = futures_util::future::select_ok(attempts).await?;
+                Ok(response)
+            }
+            QuorumSettleMode::PrimaryWithFallback => {
+                let mut ordered: Vec<&QuorumMember> = self.members.iter().collect();
+                ordered.sort_by_key(|member| std::cmp::Reverse(member.weight));
+
+                let mut last_error = None;
+                for member in ordered {
+                    match member.facilitator.settle(payment_payload, payment_requirements).await {
+                        Ok(response) => return Ok(response),
+                        Err(e) if e.is_retryable() => last_error = Some(e),
+                        Err(e) => return Err(e),
+                    }
+                }
+
+                Err(last_error.unwrap_or_else(Self::no_members_error))
+            }
+        }
+    }
+
+    /// The intersection of every member's supported `(x402_version, scheme, network)`
+    /// kinds, so a request only gets routed to the quorum for a pairing every member
+    /// can actually process
+    pub async fn supported(&self) -> Result<SupportedKinds> {
+        if self.members.is_empty() {
+            return Err(Self::no_members_error());
+        }
+
+        let all_kinds = futures_util::future::join_all(
+            self.members.iter().map(|member| member.facilitator.supported()),
+        )
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>>>()?;
+
+        let (first, rest) = all_kinds.split_first().expect("members is non-empty");
+        let kinds = first
+            .kinds
+            .iter()
+            .filter(|kind| {
+                rest.iter().all(|other| {
+                    other
+                        .kinds
+                        .iter()
+                        .any(|k| k.scheme == kind.scheme && k.network == kind.network)
+                })
+            })
+            .cloned()
+            .collect();
+
+        Ok(SupportedKinds { kinds })
+    }
+}
+
+impl std::fmt::Debug for QuorumFacilitator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("QuorumFacilitator")
+            .field("member_count", &self.members.len())
+            .field("threshold", &self.threshold)
+            .field("settle_mode", &self.settle_mode)
+            .finish()
+    }
+}
+
+impl Facilitator for QuorumFacilitator {
+    fn verify<'a>(
+        &'a self,
+        payment_payload: &'a PaymentPayload,
+        payment_requirements: &'a PaymentRequirements,
+    ) -> BoxFuture<'a, Result<VerifyResponse>> {
+        Box::pin(QuorumFacilitator::verify(self, payment_payload, payment_requirements))
+    }
+
+    fn settle<'a>(
+        &'a self,
+        payment_payload: &'a PaymentPayload,
+        payment_requirements: &'a PaymentRequirements,
+    ) -> BoxFuture<'a, Result<SettleResponse>> {
+        Box::pin(QuorumFacilitator::settle(self, payment_payload, payment_requirements))
+    }
+
+    fn supported(&self) -> BoxFuture<'_, Result<SupportedKinds>> {
+        Box::pin(QuorumFacilitator::supported(self))
+    }
+}
+
+/// Coinbase facilitator integration
+pub mod coinbase {
+    use super::*;
+    use crate::crypto::jwt;
+    use std::env;
+
+    /// Coinbase facilitator base URL
+    pub const COINBASE_FACILITATOR_BASE_URL: &str = "https://api.cdp.coinbase.com";
+    /// Coinbase facilitator v2 route
+    pub const COINBASE_FACILITATOR_V2_ROUTE: &str = "/platform/v2/x402";
+    /// SDK version
+    pub const SDK_VERSION: &str = "0.1.0";
+
+    /// Create authentication headers for Coinbase facilitator
+    pub fn create_auth_headers(
+        api_key_id: &str,
+        api_key_secret: &str,
+    ) -> impl Fn() -> Result<HashMap<String, HashMap<String, String>>> + Send + Sync {
+        let api_key_id = api_key_id.to_string();
+        let api_key_secret = api_key_secret.to_string();
+
+        move || {
+            // Use provided credentials or fall back to environment variables
+            let id = if api_key_id.is_empty() {
+                env::var("CDP_API_KEY_ID").unwrap_or_default()
+            } else {
+                api_key_id.clone()
+            };
+
+            let secret = if api_key_secret.is_empty() {
+                env::var("CDP_API_KEY_SECRET").unwrap_or_default()
+            } else {
+                api_key_secret.clone()
+            };
+
+            if id.is_empty() || secret.is_empty() {
+                return Err(X402Error::config(
+                    "Missing credentials: CDP_API_KEY_ID and CDP_API_KEY_SECRET must be set",
+                ));
+            }
+
+            let verify_token = jwt::create_auth_header_with_method(
+                &id,
+                &secret,
+                "POST",
+                COINBASE_FACILITATOR_BASE_URL,
+                &format!("{}/verify", COINBASE_FACILITATOR_V2_ROUTE),
+            )?;
+
+            let settle_token = jwt::create_auth_header_with_method(
+                &id,
+                &secret,
+                "POST",
+                COINBASE_FACILITATOR_BASE_URL,
+                &format!("{}/settle", COINBASE_FACILITATOR_V2_ROUTE),
+            )?;
+
+            let correlation_header = create_correlation_header();
+
+            let mut headers = HashMap::new();
+
+            let mut verify_headers = HashMap::new();
+            verify_headers.insert("Authorization".to_string(), verify_token);
+            verify_headers.insert(
+                "Correlation-Context".to_string(),
+                correlation_header.clone(),
+            );
+            headers.insert("verify".to_string(), verify_headers);
+
+            let mut settle_headers = HashMap::new();
+            settle_headers.insert("Authorization".to_string(), settle_token);
+            settle_headers.insert("Correlation-Context".to_string(), correlation_header);
+            headers.insert("settle".to_string(), settle_headers);
+
+            Ok(headers)
+        }
+    }
+
+    /// Create a facilitator config for Coinbase
+    pub fn create_facilitator_config(api_key_id: &str, api_key_secret: &str) -> FacilitatorConfig {
+        FacilitatorConfig::new(format!(
+            "{}{}",
+            COINBASE_FACILITATOR_BASE_URL, COINBASE_FACILITATOR_V2_ROUTE
+        ))
+        .with_auth_headers(Box::new(create_auth_headers(api_key_id, api_key_secret)))
+    }
+
+    /// Create correlation header for requests
+    fn create_correlation_header() -> String {
+        use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+
+        let data = [
+            ("sdk_version", SDK_VERSION),
+            ("sdk_language", "rust"),
+            ("source", "x402"),
+            ("source_version", crate::VERSION),
+        ];
+
+        let pairs: Vec<String> = data
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, utf8_percent_encode(value, NON_ALPHANUMERIC)))
+            .collect();
+
+        pairs.join(",")
+    }
+
+    /// Create a default Coinbase facilitator config
+    pub fn default_coinbase_config() -> FacilitatorConfig {
+        create_facilitator_config("", "")
+    }
+
+    /// Create a Coinbase facilitator config with explicit credentials
+    pub fn coinbase_config_with_credentials(
+        api_key_id: impl Into<String>,
+        api_key_secret: impl Into<String>,
+    ) -> FacilitatorConfig {
+        let id = api_key_id.into();
+        let secret = api_key_secret.into();
+        create_facilitator_config(&id, &secret)
+    }
+
+    /// Create a Coinbase facilitator config from environment variables
+    pub fn coinbase_config_from_env() -> FacilitatorConfig {
+        use std::env;
+
+        let api_key_id = env::var("CDP_API_KEY_ID").unwrap_or_default();
+        let api_key_secret = env::var("CDP_API_KEY_SECRET").unwrap_or_default();
+
+        create_facilitator_config(&api_key_id, &api_key_secret)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::{Matcher, Server};
+    use serde_json::json;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_facilitator_client_creation() {
+        let config = FacilitatorConfig::new("https://example.com/facilitator");
+        let client = FacilitatorClient::new(config).unwrap();
+        assert_eq!(client.url(), "https://example.com/facilitator");
+    }
+
+    #[tokio::test]
+    async fn test_facilitator_verify_success() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("POST", "/verify")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "x402Version": 1,
+                    "isValid": true,
+                    "payer": "0x857b06519E91e3A54538791bDbb0E22373e36b66"
+                })
+                .to_string(),
+            )
+            .create();
+
+        let config = FacilitatorConfig::new(server.url());
+        let client = FacilitatorClient::new(config).unwrap();
+
+        let payment_payload = create_test_payment_payload();
+        let payment_requirements = create_test_payment_requirements();
+
+        let response = client
+            .verify(&payment_payload, &payment_requirements)
+            .await
+            .unwrap();
+        assert!(response.is_valid);
+        assert_eq!(
+            response.payer,
+            Some("0x857b06519E91e3A54538791bDbb0E22373e36b66".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_facilitator_verify_failure() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("POST", "/verify")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "x402Version": 1,
+                    "isValid": false,
+                    "invalidReason": "insufficient_funds",
+                    "payer": "0x857b06519E91e3A54538791bDbb0E22373e36b66"
+                })
+                .to_string(),
+            )
+            .create();
+
+        let config = FacilitatorConfig::new(server.url());
+        let client = FacilitatorClient::new(config).unwrap();
+
+        let payment_payload = create_test_payment_payload();
+        let payment_requirements = create_test_payment_requirements();
+
+        let response = client
+            .verify(&payment_payload, &payment_requirements)
+            .await
+            .unwrap();
+        assert!(!response.is_valid);
+        assert_eq!(
+            response.invalid_reason,
+            Some("insufficient_funds".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_facilitator_verify_rejects_a_replayed_authorization_without_calling_the_facilitator() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/verify")
+            .expect(1)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "x402Version": 1,
+                    "isValid": true,
+                    "payer": "0x857b06519E91e3A54538791bDbb0E22373e36b66"
+                })
+                .to_string(),
+            )
+            .create();
+
+        let replay_store: std::sync::Arc<dyn crate::nonce_store::NonceReplayStore> =
+            std::sync::Arc::new(crate::nonce_store::InMemoryNonceReplayStore::new());
+        let config = FacilitatorConfig::new(server.url()).with_nonce_replay_store(replay_store);
+        let client = FacilitatorClient::new(config).unwrap();
+
+        let mut payment_payload = create_test_payment_payload();
+        payment_payload.exact_evm_mut().unwrap().authorization.valid_before =
+            (chrono::Utc::now().timestamp() + 3600).to_string();
+        let payment_requirements = create_test_payment_requirements();
+
+        let first = client
+            .verify(&payment_payload, &payment_requirements)
+            .await
+            .unwrap();
+        assert!(first.is_valid);
+
+        let second = client.verify(&payment_payload, &payment_requirements).await;
+        assert!(matches!(second, Err(X402Error::NonceReused { .. })));
+
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_facilitator_settle_success() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("POST", "/settle")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({
+                "success": true,
+                "transaction": "0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef",
+                "network": "base-sepolia",
+                "payer": "0x857b06519E91e3A54538791bDbb0E22373e36b66"
+            }).to_string())
+            .create();
+
+        let config = FacilitatorConfig::new(server.url());
+        let client = FacilitatorClient::new(config).unwrap();
+
+        let payment_payload = create_test_payment_payload();
+        let payment_requirements = create_test_payment_requirements();
+
+        let response = client
+            .settle(&payment_payload, &payment_requirements)
+            .await
+            .unwrap();
+        assert!(response.success);
+        assert_eq!(
+            response.transaction,
+            "0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef"
+        );
+        assert_eq!(response.network, "base-sepolia");
+    }
+
+    #[tokio::test]
+    async fn test_facilitator_settle_sends_a_stable_idempotency_key() {
+        let mut server = Server::new_async().await;
+        let payment_payload = create_test_payment_payload();
+        let payment_requirements = create_test_payment_requirements();
+        let expected_key = crate::idempotency::PaymentId::from_authorization(
+            &payment_payload.exact_evm().unwrap().authorization,
+            &payment_requirements,
+        )
+        .to_string();
+
+        let _m = server
+            .mock("POST", "/settle")
+            .match_header("Idempotency-Key", expected_key.as_str())
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({
+                "success": true,
+                "transaction": "0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef",
+                "network": "base-sepolia",
+                "payer": "0x857b06519E91e3A54538791bDbb0E22373e36b66"
+            }).to_string())
+            .create();
+
+        let config = FacilitatorConfig::new(server.url());
+        let client = FacilitatorClient::new(config).unwrap();
+
+        client
+            .settle(&payment_payload, &payment_requirements)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_facilitator_settle_with_idempotency_store_does_not_resettle_same_payment() {
+        let mut server = Server::new_async().await;
+        let payment_payload = create_test_payment_payload();
+        let payment_requirements = create_test_payment_requirements();
+
+        // Only one POST to /settle should ever reach the facilitator for this
+        // payment, no matter how many times `settle` is called for it.
+        let mock = server
+            .mock("POST", "/settle")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({
+                "success": true,
+                "transaction": "0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef",
+                "network": "base-sepolia",
+                "payer": "0x857b06519E91e3A54538791bDbb0E22373e36b66"
+            }).to_string())
+            .expect(1)
+            .create();
+
+        let store = std::sync::Arc::new(crate::idempotency::InMemoryIdempotencyStore::default());
+        let config = FacilitatorConfig::new(server.url()).with_idempotency_store(store);
+        let client = FacilitatorClient::new(config).unwrap();
+
+        let first = client.settle(&payment_payload, &payment_requirements).await.unwrap();
+        let second = client.settle(&payment_payload, &payment_requirements).await.unwrap();
+
+        assert_eq!(first.transaction, second.transaction);
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_facilitator_settle_with_idempotency_store_does_not_collide_across_resources() {
+        let mut server = Server::new_async().await;
+        let payment_payload = create_test_payment_payload();
+        let first_requirements = create_test_payment_requirements();
+        let mut second_requirements = create_test_payment_requirements();
+        second_requirements.resource = "https://example.com/a-different-resource".to_string();
+
+        // The same authorization settled against two different resources must hit the
+        // facilitator twice, not collide on one cached idempotency-store entry.
+        let mock = server
+            .mock("POST", "/settle")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({
+                "success": true,
+                "transaction": "0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef",
+                "network": "base-sepolia",
+                "payer": "0x857b06519E91e3A54538791bDbb0E22373e36b66"
+            }).to_string())
+            .expect(2)
+            .create();
+
+        let store = std::sync::Arc::new(crate::idempotency::InMemoryIdempotencyStore::default());
+        let config = FacilitatorConfig::new(server.url()).with_idempotency_store(store);
+        let client = FacilitatorClient::new(config).unwrap();
+
+        client.settle(&payment_payload, &first_requirements).await.unwrap();
+        client.settle(&payment_payload, &second_requirements).await.unwrap();
+
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_facilitator_settle_failure() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("POST", "/settle")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "x402Version": 1,
+                    "success": false,
+                    "errorReason": "transaction_failed",
+                    "transaction": "",
+                    "network": "base-sepolia",
+                    "payer": "0x857b06519E91e3A54538791bDbb0E22373e36b66"
+                })
+                .to_string(),
+            )
+            .create();
+
+        let config = FacilitatorConfig::new(server.url());
+        let client = FacilitatorClient::new(config).unwrap();
+
+        let payment_payload = create_test_payment_payload();
+        let payment_requirements = create_test_payment_requirements();
+
+        let response = client
+            .settle(&payment_payload, &payment_requirements)
+            .await
+            .unwrap();
+        assert!(!response.success);
+        assert_eq!(
+            response.error_reason,
+            Some("transaction_failed".to_string())
+        );
+        assert_eq!(response.transaction, "");
+    }
+
+    #[tokio::test]
+    async fn test_facilitator_refund_success() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("POST", "/refund")
+            .match_body(Matcher::PartialJson(json!({
+                "kind": "refund",
+                "originalTransaction": "0xoriginal",
+            })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "success": true,
+                    "kind": "refund",
+                    "transaction": "0xrefund",
+                    "network": "base-sepolia"
+                })
+                .to_string(),
+            )
+            .create();
+
+        let config = FacilitatorConfig::new(server.url());
+        let client = FacilitatorClient::new(config).unwrap();
+
+        let original_settlement = SettleResponse {
+            success: true,
+            error_reason: None,
+            transaction: "0xoriginal".to_string(),
+            network: "base-sepolia".to_string(),
+            payer: Some("0x857b06519E91e3A54538791bDbb0E22373e36b66".to_string()),
+        };
+
+        let response = client.refund(&original_settlement, "500000").await.unwrap();
+        assert!(response.success);
+        assert_eq!(response.kind, ReversalKind::Refund);
+        assert_eq!(response.transaction, "0xrefund");
+    }
+
+    #[tokio::test]
+    async fn test_facilitator_refund_without_payer_is_config_error() {
+        let config = FacilitatorConfig::new("https://example.com/facilitator");
+        let client = FacilitatorClient::new(config).unwrap();
+
+        let original_settlement = SettleResponse {
+            success: true,
+            error_reason: None,
+            transaction: "0xoriginal".to_string(),
+            network: "base-sepolia".to_string(),
+            payer: None,
+        };
+
+        let result = client.refund(&original_settlement, "500000").await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_facilitator_payout_success() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("POST", "/payout")
+            .match_body(Matcher::PartialJson(json!({
+                "kind": "payout",
+                "destination": "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "success": true,
+                    "kind": "payout",
+                    "transaction": "0xpayout",
+                    "network": "base-sepolia"
+                })
+                .to_string(),
+            )
+            .create();
+
+        let config = FacilitatorConfig::new(server.url());
+        let client = FacilitatorClient::new(config).unwrap();
+
+        let response = client
+            .payout(
+                "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+                "1000000",
+                "0x036CbD53842c5426634e7929541eC2318f3dCF7e",
+                "base-sepolia",
+            )
+            .await
+            .unwrap();
+
+        assert!(response.success);
+        assert_eq!(response.kind, ReversalKind::Payout);
+        assert_eq!(response.transaction, "0xpayout");
+    }
+
+    #[tokio::test]
+    async fn test_facilitator_server_error() {
+        let mut server = Server::new_async().await;
+        let _m = server.mock("POST", "/verify").with_status(500).create();
+
+        let config = FacilitatorConfig::new(server.url());
+        let client = FacilitatorClient::new(config).unwrap();
+
+        let payment_payload = create_test_payment_payload();
+        let payment_requirements = create_test_payment_requirements();
+
+        let result = client.verify(&payment_payload, &payment_requirements).await;
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("Verification failed with status: 500"));
+    }
+
+    #[tokio::test]
+    async fn test_facilitator_verify_structured_decline() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("POST", "/verify")
+            .with_status(402)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "type": "card_error",
+                    "code": "insufficient_funds",
+                    "reason": "Payer balance is below the required amount"
+                })
+                .to_string(),
+            )
+            .create();
+
+        let config = FacilitatorConfig::new(server.url());
+        let client = FacilitatorClient::new(config).unwrap();
+
+        let payment_payload = create_test_payment_payload();
+        let payment_requirements = create_test_payment_requirements();
+
+        let result = client.verify(&payment_payload, &payment_requirements).await;
+        let error = result.unwrap_err();
+        match &error {
+            X402Error::Facilitator { code, reason, .. } => {
+                assert_eq!(*code, crate::error::FacilitatorCode::InsufficientFunds);
+                assert_eq!(reason, "Payer balance is below the required amount");
+            }
+            other => panic!("Expected X402Error::Facilitator, got: {:?}", other),
+        }
+        assert_eq!(error.status_code(), 402);
+        assert_eq!(error.error_type(), "insufficient_funds");
+    }
+
+    #[tokio::test]
+    async fn test_facilitator_supported() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("GET", "/supported")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "x402Version": 1,
+                    "kinds": [
+                        {
+                            "x402Version": 1,
+                            "scheme": "exact",
+                            "network": "base-sepolia"
+                        },
+                        {
+                            "x402Version": 1,
+                            "scheme": "exact",
+                            "network": "base"
+                        }
+                    ]
+                })
+                .to_string(),
+            )
+            .create();
+
+        let config = FacilitatorConfig::new(server.url());
+        let client = FacilitatorClient::new(config).unwrap();
+
+        let supported = client.supported().await.unwrap();
+        assert_eq!(supported.kinds.len(), 2);
+        assert_eq!(supported.kinds[0].scheme, "exact");
+        assert_eq!(supported.kinds[0].network, "base-sepolia");
+        assert_eq!(supported.kinds[1].network, "base");
+    }
+
+    #[tokio::test]
+    async fn test_negotiate_version_picks_the_highest_common_version() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("GET", "/supported")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "x402Version": 1,
+                    "kinds": [
+                        {"x402Version": 1, "scheme": "exact", "network": "base-sepolia"}
+                    ]
+                })
+                .to_string(),
+            )
+            .expect(1)
+            .create();
+
+        let config = FacilitatorConfig::new(server.url());
+        let client = FacilitatorClient::new(config).unwrap();
+
+        assert_eq!(client.negotiate_version().await.unwrap(), 1);
+        // A second call must hit the cache rather than `/supported` again.
+        assert_eq!(client.negotiate_version().await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_negotiate_version_errors_when_no_version_overlaps() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("GET", "/supported")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "x402Version": 99,
+                    "kinds": [
+                        {"x402Version": 99, "scheme": "exact", "network": "base-sepolia"}
+                    ]
+                })
+                .to_string(),
+            )
+            .create();
+
+        let config = FacilitatorConfig::new(server.url());
+        let client = FacilitatorClient::new(config).unwrap();
+
+        let error = client.negotiate_version().await.unwrap_err();
+        assert!(matches!(
+            error,
+            X402Error::VersionMismatch { server: 99, client: X402_VERSION }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_negotiate_picks_the_highest_version_for_a_specific_scheme_and_network() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("GET", "/supported")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "x402Version": 1,
+                    "kinds": [
+                        {"x402Version": 1, "scheme": "exact", "network": "base-sepolia"},
+                        {"x402Version": 1, "scheme": "exact", "network": "base"}
+                    ]
+                })
+                .to_string(),
+            )
+            .create();
+
+        let config = FacilitatorConfig::new(server.url());
+        let client = FacilitatorClient::new(config).unwrap();
+
+        assert_eq!(client.negotiate("exact", "base-sepolia").await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_negotiate_errors_with_scheme_not_supported_when_pairing_is_absent() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("GET", "/supported")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "x402Version": 1,
+                    "kinds": [
+                        {"x402Version": 1, "scheme": "exact", "network": "base-sepolia"}
+                    ]
+                })
+                .to_string(),
+            )
+            .create();
+
+        let config = FacilitatorConfig::new(server.url());
+        let client = FacilitatorClient::new(config).unwrap();
+
+        let error = client.negotiate("exact", "avalanche").await.unwrap_err();
+        match error {
+            X402Error::SchemeNotSupported {
+                scheme,
+                network,
+                available,
+            } => {
+                assert_eq!(scheme, "exact");
+                assert_eq!(network, "avalanche");
+                assert!(available.is_empty());
+            }
+            other => panic!("expected SchemeNotSupported, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_negotiate_requirements_picks_the_matching_offer_with_the_highest_version() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("GET", "/supported")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "x402Version": 1,
+                    "kinds": [
+                        {"x402Version": 1, "scheme": "exact", "network": "avalanche-fuji"},
+                        {"x402Version": 1, "scheme": "exact", "network": "base-sepolia"}
+                    ]
+                })
+                .to_string(),
+            )
+            .create();
+
+        let config = FacilitatorConfig::new(server.url());
+        let client = FacilitatorClient::new(config).unwrap();
+
+        let offered = vec![
+            PaymentRequirements::new(
+                "exact", "solana", "1000000", "asset", "pay-to", "https://example.com", "desc",
+            ),
+            PaymentRequirements::new(
+                "exact", "base-sepolia", "1000000", "asset", "pay-to", "https://example.com", "desc",
+            ),
+        ];
+
+        let negotiated = client.negotiate_requirements(&offered).await.unwrap();
+        assert_eq!(negotiated.scheme, "exact");
+        assert_eq!(negotiated.network, "base-sepolia");
+        assert_eq!(negotiated.x402_version, 1);
+    }
+
+    #[tokio::test]
+    async fn test_negotiate_requirements_errors_listing_the_overlap_when_none_match() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("GET", "/supported")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "x402Version": 1,
+                    "kinds": [
+                        {"x402Version": 1, "scheme": "exact", "network": "base-sepolia"}
+                    ]
+                })
+                .to_string(),
+            )
+            .create();
+
+        let config = FacilitatorConfig::new(server.url());
+        let client = FacilitatorClient::new(config).unwrap();
+
+        let offered = vec![PaymentRequirements::new(
+            "exact", "solana", "1000000", "asset", "pay-to", "https://example.com", "desc",
+        )];
+
+        let error = client.negotiate_requirements(&offered).await.unwrap_err();
+        match error {
+            X402Error::NoSupportedRequirements { offered, available } => {
+                assert_eq!(offered, vec![("exact".to_string(), "solana".to_string())]);
+                assert_eq!(
+                    available,
+                    vec![("exact".to_string(), "base-sepolia".to_string(), 1)]
+                );
+            }
+            other => panic!("expected NoSupportedRequirements, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_verify_checked_calls_verify_when_the_pairing_is_supported() {
+        let mut server = Server::new_async().await;
+        let _supported = server
+            .mock("GET", "/supported")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "x402Version": 1,
+                    "kinds": [
+                        {"x402Version": 1, "scheme": "exact", "network": "base-sepolia"}
+                    ]
+                })
+                .to_string(),
+            )
+            .create();
+        let _verify = server
+            .mock("POST", "/verify")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "x402Version": 1,
+                    "isValid": true,
+                    "payer": "0x857b06519E91e3A54538791bDbb0E22373e36b66"
+                })
+                .to_string(),
+            )
+            .create();
+
+        let config = FacilitatorConfig::new(server.url());
+        let client = FacilitatorClient::new(config).unwrap();
+
+        let payment_payload = create_test_payment_payload();
+        let payment_requirements = create_test_payment_requirements();
+
+        let response = client
+            .verify_checked(&payment_payload, &payment_requirements)
+            .await
+            .unwrap();
+        assert!(response.is_valid);
+    }
+
+    #[tokio::test]
+    async fn test_verify_checked_rejects_an_unsupported_pairing_without_calling_verify() {
+        let mut server = Server::new_async().await;
+        let _supported = server
+            .mock("GET", "/supported")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "x402Version": 1,
+                    "kinds": [
+                        {"x402Version": 1, "scheme": "exact", "network": "avalanche"}
+                    ]
+                })
+                .to_string(),
+            )
+            .create();
+        let verify_mock = server.mock("POST", "/verify").expect(0).create();
+
+        let config = FacilitatorConfig::new(server.url());
+        let client = FacilitatorClient::new(config).unwrap();
+
+        let payment_payload = create_test_payment_payload();
+        let payment_requirements = create_test_payment_requirements();
+
+        let error = client
+            .verify_checked(&payment_payload, &payment_requirements)
+            .await
+            .unwrap_err();
+        assert!(matches!(error, X402Error::SchemeNotSupported { .. }));
+        verify_mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_settle_checked_rejects_an_unsupported_pairing_without_calling_settle() {
+        let mut server = Server::new_async().await;
+        let _supported = server
+            .mock("GET", "/supported")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "x402Version": 1,
+                    "kinds": [
+                        {"x402Version": 1, "scheme": "exact", "network": "avalanche"}
+                    ]
+                })
+                .to_string(),
+            )
+            .create();
+        let settle_mock = server.mock("POST", "/settle").expect(0).create();
+
+        let config = FacilitatorConfig::new(server.url());
+        let client = FacilitatorClient::new(config).unwrap();
+
+        let payment_payload = create_test_payment_payload();
+        let payment_requirements = create_test_payment_requirements();
+
+        let error = client
+            .settle_checked(&payment_payload, &payment_requirements)
+            .await
+            .unwrap_err();
+        assert!(matches!(error, X402Error::SchemeNotSupported { .. }));
+        settle_mock.assert();
+    }
+
+    struct CustomPathProvider;
+
+    impl FacilitatorProvider for CustomPathProvider {
+        fn path(&self, operation: &str) -> String {
+            format!("/custom/{}", operation)
+        }
+
+        fn headers(&self, operation: &str) -> Result<HashMap<String, String>> {
+            let mut headers = HashMap::new();
+            headers.insert("X-Facilitator-Operation".to_string(), operation.to_string());
+            Ok(headers)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_provider_overrides_verify_route_and_adds_its_own_headers() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("POST", "/custom/verify")
+            .match_header("X-Facilitator-Operation", "verify")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "x402Version": 1,
+                    "isValid": true,
+                    "payer": "0x857b06519E91e3A54538791bDbb0E22373e36b66"
+                })
+                .to_string(),
+            )
+            .create();
+
+        let config = FacilitatorConfig::new(server.url())
+            .with_provider(std::sync::Arc::new(CustomPathProvider));
+        let client = FacilitatorClient::new(config).unwrap();
+
+        let payment_payload = create_test_payment_payload();
+        let payment_requirements = create_test_payment_requirements();
+
+        let response = client
+            .verify(&payment_payload, &payment_requirements)
+            .await
+            .unwrap();
+        assert!(response.is_valid);
+    }
+
+    #[tokio::test]
+    async fn test_facilitator_trait_list_defaults_to_an_error_when_unimplemented() {
+        struct NoDiscoveryFacilitator;
+
+        impl Facilitator for NoDiscoveryFacilitator {
+            fn verify<'a>(
+                &'a self,
+                _payment_payload: &'a PaymentPayload,
+                _payment_requirements: &'a PaymentRequirements,
+            ) -> BoxFuture<'a, Result<VerifyResponse>> {
+                Box::pin(async { Err(X402Error::config("not implemented")) })
+            }
+
+            fn settle<'a>(
+                &'a self,
+                _payment_payload: &'a PaymentPayload,
+                _payment_requirements: &'a PaymentRequirements,
+            ) -> BoxFuture<'a, Result<SettleResponse>> {
+                Box::pin(async { Err(X402Error::config("not implemented")) })
+            }
+
+            fn supported(&self) -> BoxFuture<'_, Result<SupportedKinds>> {
+                Box::pin(async { Ok(SupportedKinds { kinds: vec![] }) })
+            }
+        }
+
+        let error = NoDiscoveryFacilitator.list(None).await.unwrap_err();
+        assert!(matches!(error, X402Error::Config { .. }));
+    }
+
+    struct RecordingResponseHook {
+        calls: std::sync::Arc<std::sync::Mutex<Vec<(reqwest::Method, StatusCode)>>>,
+    }
+
+    impl ResponseHook for RecordingResponseHook {
+        fn on_response<'a>(
+            &'a self,
+            method: reqwest::Method,
+            _url: &'a reqwest::Url,
+            status: StatusCode,
+            _elapsed: Duration,
+            _body: &'a [u8],
+        ) -> BoxFuture<'a, ()> {
+            self.calls.lock().unwrap().push((method, status));
+            Box::pin(async {})
+        }
+    }
+
+    #[tokio::test]
+    async fn test_response_hook_fires_on_success_and_error_statuses() {
+        let mut server = Server::new_async().await;
+        server
+            .mock("POST", "/verify")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"isValid": true, "invalidReason": null}).to_string())
+            .create();
+        server
+            .mock("POST", "/settle")
+            .with_status(503)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"code": "upstream_unavailable", "reason": "rpc down"}).to_string())
+            .create();
+
+        let calls = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let hook = std::sync::Arc::new(RecordingResponseHook {
+            calls: calls.clone(),
+        });
+
+        let config = FacilitatorConfig::new(server.url());
+        let client = FacilitatorClient::new(config).unwrap().with_response_hook(hook);
+
+        let payment_payload = create_test_payment_payload();
+        let payment_requirements = create_test_payment_requirements();
+
+        client
+            .verify(&payment_payload, &payment_requirements)
+            .await
+            .unwrap();
+        let _ = client.settle(&payment_payload, &payment_requirements).await;
+
+        let recorded = calls.lock().unwrap();
+        assert_eq!(recorded.len(), 2);
+        assert_eq!(recorded[0], (reqwest::Method::POST, StatusCode::OK));
+        assert_eq!(
+            recorded[1],
+            (reqwest::Method::POST, StatusCode::SERVICE_UNAVAILABLE)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_facilitator_with_auth_headers() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("POST", "/verify")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .match_header("Authorization", "Bearer test-token")
+            .match_header("Correlation-Context", Matcher::Regex(r".*".to_string()))
+            .with_body(
+                json!({
+                    "x402Version": 1,
+                    "isValid": true,
+                    "payer": "0x857b06519E91e3A54538791bDbb0E22373e36b66"
+                })
+                .to_string(),
+            )
+            .create();
+
+        let create_auth_headers = || {
+            let mut headers = HashMap::new();
+            let mut verify_headers = HashMap::new();
+            verify_headers.insert("Authorization".to_string(), "Bearer test-token".to_string());
+            verify_headers.insert(
+                "Correlation-Context".to_string(),
+                "test=correlation".to_string(),
+            );
+            headers.insert("verify".to_string(), verify_headers);
+            Ok(headers)
+        };
+
+        let config =
+            FacilitatorConfig::new(server.url()).with_auth_headers(Box::new(create_auth_headers));
+        let client = FacilitatorClient::new(config).unwrap();
+
+        let payment_payload = create_test_payment_payload();
+        let payment_requirements = create_test_payment_requirements();
+
+        let response = client
+            .verify(&payment_payload, &payment_requirements)
+            .await
+            .unwrap();
+        assert!(response.is_valid);
+    }
+
+    #[tokio::test]
+    async fn test_with_retry_from_config_preserves_auth_headers() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("POST", "/verify")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .match_header("Authorization", "Bearer test-token")
+            .with_body(
+                json!({
+                    "x402Version": 1,
+                    "isValid": true,
+                    "payer": "0x857b06519E91e3A54538791bDbb0E22373e36b66"
+                })
+                .to_string(),
+            )
+            .create();
+
+        let create_auth_headers = || {
+            let mut headers = HashMap::new();
+            let mut verify_headers = HashMap::new();
+            verify_headers.insert("Authorization".to_string(), "Bearer test-token".to_string());
+            headers.insert("verify".to_string(), verify_headers);
+            Ok(headers)
+        };
+
+        // `with_retry_from_config` must carry over auth headers already attached to
+        // `config` (e.g. by a caller like `proxy::build_payment_middleware`), unlike
+        // `with_retry`, which always builds a bare `FacilitatorConfig` from a URL.
+        let config =
+            FacilitatorConfig::new(server.url()).with_auth_headers(Box::new(create_auth_headers));
+        let client = FacilitatorClient::with_retry_from_config(
+            config,
+            crate::retry::Retry::Attempts(2),
+            crate::retry::RetryPolicy::default(),
+        )
+        .unwrap();
+
+        let payment_payload = create_test_payment_payload();
+        let payment_requirements = create_test_payment_requirements();
+
+        let response = client
+            .verify(&payment_payload, &payment_requirements)
+            .await
+            .unwrap();
+        assert!(response.is_valid);
+    }
+
+    #[tokio::test]
+    async fn test_facilitator_verify_retries_then_exhausts() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/verify")
+            .with_status(503)
+            .expect(3)
+            .create();
+
+        let config = FacilitatorConfig::new(server.url()).with_retry_policy(
+            crate::retry::RetryPolicy::new()
+                .with_base_delay(Duration::from_millis(1))
+                .with_max_attempts(3),
+        );
+        let client = FacilitatorClient::new(config).unwrap();
+
+        let payment_payload = create_test_payment_payload();
+        let payment_requirements = create_test_payment_requirements();
+
+        let result = client.verify(&payment_payload, &payment_requirements).await;
+        assert!(result.is_err(), "Should fail after exhausting retries");
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_facilitator_verify_retries_502_and_504_before_succeeding() {
+        let mut server = Server::new_async().await;
+        let bad_gateway_mock = server.mock("POST", "/verify").with_status(502).expect(1).create();
+        let gateway_timeout_mock = server.mock("POST", "/verify").with_status(504).expect(1).create();
+        let ok_mock = server
+            .mock("POST", "/verify")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"isValid": true, "invalidReason": null}).to_string())
+            .create();
+
+        let config = FacilitatorConfig::new(server.url()).with_retry_policy(
+            crate::retry::RetryPolicy::new()
+                .with_base_delay(Duration::from_millis(1))
+                .with_max_attempts(3),
+        );
+        let client = FacilitatorClient::new(config).unwrap();
+
+        let payment_payload = create_test_payment_payload();
+        let payment_requirements = create_test_payment_requirements();
+
+        let response = client
+            .verify(&payment_payload, &payment_requirements)
+            .await
+            .expect("502 and 504 should be retried, not treated as permanent failures");
+        assert!(response.is_valid);
+
+        bad_gateway_mock.assert();
+        gateway_timeout_mock.assert();
+        ok_mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_facilitator_supported_retries_transient_failures() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/supported")
+            .with_status(503)
+            .expect(2)
+            .create();
+        let ok_mock = server
+            .mock("GET", "/supported")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"kinds": []}).to_string())
+            .create();
+
+        let config = FacilitatorConfig::new(server.url()).with_retry_policy(
+            crate::retry::RetryPolicy::new()
+                .with_base_delay(Duration::from_millis(1))
+                .with_max_attempts(3),
+        );
+        let client = FacilitatorClient::new(config).unwrap();
+
+        let result = client.supported().await;
+        assert!(result.is_ok());
+        mock.assert();
+        ok_mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_facilitator_list_retries_transient_failures() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/discovery/resources")
+            .with_status(503)
+            .expect(2)
+            .create();
+        let ok_mock = server
+            .mock("GET", "/discovery/resources")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({"x402Version": 1, "items": [], "pagination": {"limit": 20, "offset": 0, "total": 0}})
+                    .to_string(),
+            )
+            .create();
+
+        let config = FacilitatorConfig::new(server.url()).with_retry_policy(
+            crate::retry::RetryPolicy::new()
+                .with_base_delay(Duration::from_millis(1))
+                .with_max_attempts(3),
+        );
+        let client = FacilitatorClient::new(config).unwrap();
+
+        let result = client.list(None).await;
+        assert!(result.is_ok());
+        mock.assert();
+        ok_mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_facilitator_verify_attaches_http_signature_headers() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/verify")
+            .match_header("signature", Matcher::Regex(r#"keyId="test-key""#.to_string()))
+            .match_header("digest", Matcher::Regex("SHA-256=".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "isValid": true,
+                    "invalidReason": null
+                })
+                .to_string(),
+            )
+            .create();
+
+        let http_signature = crate::http_signature::HttpSignatureConfig::new(
+            "test-key",
+            "hmac-sha256",
+            |signing_string| Ok(signing_string.as_bytes().to_vec()),
+        );
+        let config = FacilitatorConfig::new(server.url()).with_http_signature(http_signature);
+        let client = FacilitatorClient::new(config).unwrap();
+
+        let payment_payload = create_test_payment_payload();
+        let payment_requirements = create_test_payment_requirements();
+
+        let result = client.verify(&payment_payload, &payment_requirements).await;
+        assert!(result.is_ok(), "Should succeed: {:?}", result.err());
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_facilitator_timeout() {
+        // Test with a very short timeout and a URL that will timeout
+        let config = FacilitatorConfig::new("http://10.255.255.1:9999") // Non-routable IP
+            .with_timeout(Duration::from_millis(1));
+        let client = FacilitatorClient::new(config).unwrap();
+
+        let payment_payload = create_test_payment_payload();
+        let payment_requirements = create_test_payment_requirements();
+
+        let result = client.verify(&payment_payload, &payment_requirements).await;
+        assert!(result.is_err());
+        // Check for timeout-related error - be more flexible with error messages
+        let error_msg = result.unwrap_err().to_string();
+        assert!(
+            error_msg.contains("timeout")
+                || error_msg.contains("connection")
+                || error_msg.contains("network")
+                || error_msg.contains("unreachable")
+                || error_msg.contains("refused")
+                || error_msg.contains("No route to host")
+                || error_msg.contains("failed to connect")
+                || error_msg.contains("Connection refused")
+                || error_msg.contains("Network is unreachable")
+                || error_msg.contains("Name or service not known")
+                || error_msg.contains("Temporary failure in name resolution")
+                || error_msg.contains("error sending request")
+                || error_msg.contains("HTTP error")
+                || error_msg.contains("Facilitator error"),
+            "Expected timeout/connection error, got: {}",
+            error_msg
+        );
+    }
+
+    #[tokio::test]
+    async fn test_network_mismatch_returns_error() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("POST", "/verify")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "x402Version": 1,
+                    "isValid": true,
+                    "payer": "0x857b06519E91e3A54538791bDbb0E22373e36b66"
+                })
+                .to_string(),
+            )
+            .create();
+
+        let config = FacilitatorConfig::new(server.url());
+        let client = FacilitatorClient::new(config).unwrap();
+
+        // Create payment payload with different network than requirements
+        let authorization = ExactEvmPayloadAuthorization::new(
+            "0x857b06519E91e3A54538791bDbb0E22373e36b66",
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            "1000000",
+            "1745323800",
+            "1745323985",
+            "0xf3746613c2d920b5fdabc0856f2aeb2d4f88ee6037b8cc5d04a71a4462f13480",
+        );
+
+        let payload = ExactEvmPayload {
+            signature: "0x2d6a7588d6acca505cbf0d9a4a227e0c52c6c34008c8e8986a1283259764173608a2ce6496642e377d6da8dbbf5836e9bd15092f9ecab05ded3d6293af148b571c".to_string(),
+            authorization,
+        };
+
+        // Payment with "base" network
+        let payment_payload = PaymentPayload::new("exact", "base", payload);
+
+        // Requirements with "base-sepolia" network - should return a typed error, not panic
+        let payment_requirements = PaymentRequirements::new(
+            "exact",
+            "base-sepolia", // Different network - should return X402Error::WrongNetwork
+            "1000000",
+            "0x036CbD53842c5426634e7929541eC2318f3dCF7e",
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            "https://example.com/test",
+            "Test payment",
+        );
+
+        // This should return an error due to network mismatch
+        let result = client
+            .verify_with_network_validation(&payment_payload, &payment_requirements)
+            .await;
+
+        // Verify that we get an error for network mismatch
+        assert!(result.is_err(), "Network mismatch should result in error");
+        
+        // Verify the error is specifically a network-mismatch error
+        let error = result.unwrap_err();
+        match error {
+            X402Error::WrongNetwork { .. } => {
+                // This is the expected error type
+            }
+            _ => panic!("Expected WrongNetwork error, got: {:?}", error),
+        }
+
+        // Verify the error message content
+        let error_msg = error.to_string();
+        assert!(error_msg.contains("Network mismatch"),
+                "Error should contain 'Network mismatch' - actual: {}", error_msg);
+        assert!(error_msg.contains("base") && error_msg.contains("base-sepolia"),
+                "Error should contain both network names - actual: {}", error_msg);
+    }
+
+    #[tokio::test]
+    async fn test_verify_with_network_validation_rejects_a_scheme_mismatch() {
+        let mut payment_payload = create_test_payment_payload();
+        payment_payload.scheme = "upto".to_string();
+
+        let config = FacilitatorConfig::new("http://localhost:9999");
+        let client = FacilitatorClient::new(config).unwrap();
+
+        let result = client
+            .verify_with_network_validation(&payment_payload, &create_test_payment_requirements())
+            .await;
+
+        let error = result.unwrap_err();
+        match error {
+            X402Error::SchemeMismatch { ref expected, ref actual } => {
+                assert_eq!(expected, "exact");
+                assert_eq!(actual, "upto");
+            }
+            _ => panic!("Expected SchemeMismatch error, got: {:?}", error),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_verify_with_network_validation_rejects_an_asset_mismatch() {
+        let mut payment_payload = create_test_payment_payload();
+        payment_payload.exact_evm_mut().unwrap().authorization.to =
+            "0x000000000000000000000000000000000000ff".to_string();
+
+        let config = FacilitatorConfig::new("http://localhost:9999");
+        let client = FacilitatorClient::new(config).unwrap();
+
+        let result = client
+            .verify_with_network_validation(&payment_payload, &create_test_payment_requirements())
+            .await;
+
+        let error = result.unwrap_err();
+        match error {
+            X402Error::AssetMismatch { ref expected, ref actual } => {
+                assert_eq!(expected, &create_test_payment_requirements().pay_to);
+                assert_eq!(actual, "0x000000000000000000000000000000000000ff");
+            }
+            _ => panic!("Expected AssetMismatch error, got: {:?}", error),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_verify_with_network_validation_rejects_a_pairing_absent_from_supported() {
+        let mut server = Server::new_async().await;
+        let supported_mock = server
+            .mock("GET", "/supported")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "x402Version": 1,
+                    "kinds": [
+                        {"x402Version": 1, "scheme": "exact", "network": "base"}
+                    ]
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        // /verify must never be hit: the facilitator doesn't advertise base-sepolia
+        let verify_mock = server.mock("POST", "/verify").with_status(500).expect(0).create_async().await;
+
+        let config = FacilitatorConfig::new(server.url());
+        let client = FacilitatorClient::new(config).unwrap();
+
+        let result = client
+            .verify_with_network_validation(&create_test_payment_payload(), &create_test_payment_requirements())
+            .await;
+
+        let error = result.unwrap_err();
+        match error {
+            X402Error::SchemeNotSupported { ref scheme, ref network, .. } => {
+                assert_eq!(scheme, "exact");
+                assert_eq!(network, "base-sepolia");
+            }
+            _ => panic!("Expected SchemeNotSupported error, got: {:?}", error),
+        }
+
+        supported_mock.assert_async().await;
+        verify_mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_verify_with_network_validation_proceeds_when_supported() {
+        let mut server = Server::new_async().await;
+        let _supported_mock = server
+            .mock("GET", "/supported")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "x402Version": 1,
+                    "kinds": [
+                        {"x402Version": 1, "scheme": "exact", "network": "base-sepolia"}
+                    ]
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        let verify_mock = server
+            .mock("POST", "/verify")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "x402Version": 1,
+                    "isValid": true,
+                    "payer": "0x857b06519E91e3A54538791bDbb0E22373e36b66"
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let config = FacilitatorConfig::new(server.url());
+        let client = FacilitatorClient::new(config).unwrap();
+
+        let result = client
+            .verify_with_network_validation(&create_test_payment_payload(), &create_test_payment_requirements())
+            .await;
+
+        assert!(result.is_ok(), "Expected verify to proceed once the pairing is supported: {:?}", result.err());
+        verify_mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_supported_cached_reuses_the_cached_response_within_the_ttl() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/supported")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({"x402Version": 1, "kinds": [{"x402Version": 1, "scheme": "exact", "network": "base-sepolia"}]})
+                    .to_string(),
+            )
+            .expect(1)
+            .create_async()
+            .await;
+
+        let config = FacilitatorConfig::new(server.url())
+            .with_supported_cache_ttl(std::time::Duration::from_secs(60));
+        let client = FacilitatorClient::new(config).unwrap();
+
+        client.supported_cached().await.unwrap();
+        client.supported_cached().await.unwrap();
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_refresh_supported_bypasses_the_cache() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/supported")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({"x402Version": 1, "kinds": [{"x402Version": 1, "scheme": "exact", "network": "base-sepolia"}]})
+                    .to_string(),
+            )
+            .expect(2)
+            .create_async()
+            .await;
+
+        let config = FacilitatorConfig::new(server.url())
+            .with_supported_cache_ttl(std::time::Duration::from_secs(60));
+        let client = FacilitatorClient::new(config).unwrap();
+
+        client.supported_cached().await.unwrap();
+        client.refresh_supported().await.unwrap();
+
+        mock.assert_async().await;
+    }
+
+    struct CountingAuthProvider {
+        unauthorized_calls: std::sync::atomic::AtomicUsize,
+    }
+
+    impl AuthProvider for CountingAuthProvider {
+        fn headers_for<'a>(&'a self, _endpoint: Endpoint) -> BoxFuture<'a, Result<HashMap<String, String>>> {
+            let token = if self.unauthorized_calls.load(std::sync::atomic::Ordering::SeqCst) == 0 {
+                "stale-token"
+            } else {
+                "fresh-token"
+            };
+            let mut headers = HashMap::new();
+            headers.insert("Authorization".to_string(), format!("Bearer {}", token));
+            Box::pin(async move { Ok(headers) })
+        }
+
+        fn on_unauthorized(&self, _endpoint: Endpoint) {
+            self.unauthorized_calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_auth_provider_headers_are_applied_to_supported() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/supported")
+            .match_header("Authorization", "Bearer stale-token")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"x402Version": 1, "kinds": []}).to_string())
+            .create_async()
+            .await;
+
+        let config = FacilitatorConfig::new(server.url()).with_auth_provider(std::sync::Arc::new(CountingAuthProvider {
+            unauthorized_calls: std::sync::atomic::AtomicUsize::new(0),
+        }));
+        let client = FacilitatorClient::new(config).unwrap();
+
+        let result = client.supported().await;
+        assert!(result.is_ok(), "Expected supported to succeed: {:?}", result.err());
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_auth_provider_on_unauthorized_triggers_one_retry_with_fresh_headers() {
+        let mut server = Server::new_async().await;
+        let stale_mock = server
+            .mock("GET", "/supported")
+            .match_header("Authorization", "Bearer stale-token")
+            .with_status(401)
+            .create_async()
+            .await;
+        let fresh_mock = server
+            .mock("GET", "/supported")
+            .match_header("Authorization", "Bearer fresh-token")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"x402Version": 1, "kinds": []}).to_string())
+            .create_async()
+            .await;
+
+        let config = FacilitatorConfig::new(server.url()).with_auth_provider(std::sync::Arc::new(CountingAuthProvider {
+            unauthorized_calls: std::sync::atomic::AtomicUsize::new(0),
+        }));
+        let client = FacilitatorClient::new(config).unwrap();
+
+        let result = client.supported().await;
+        assert!(result.is_ok(), "Expected supported to succeed after retry: {:?}", result.err());
+        stale_mock.assert_async().await;
+        fresh_mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_oauth2_client_credentials_fetches_and_caches_token() {
+        let mut server = Server::new_async().await;
+        let token_mock = server
+            .mock("POST", "/oauth/token")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"access_token": "at-1", "expires_in": 3600}).to_string())
+            .expect(1)
+            .create_async()
+            .await;
+        let supported_mock = server
+            .mock("GET", "/supported")
+            .match_header("Authorization", "Bearer at-1")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"x402Version": 1, "kinds": []}).to_string())
+            .expect(2)
+            .create_async()
+            .await;
+
+        let provider = std::sync::Arc::new(OAuth2ClientCredentials::new(
+            format!("{}/oauth/token", server.url()),
+            "client-id",
+            "client-secret",
+        ));
+        let config = FacilitatorConfig::new(server.url()).with_auth_provider(provider);
+        let client = FacilitatorClient::new(config).unwrap();
+
+        client.supported().await.unwrap();
+        client.supported().await.unwrap();
+
+        token_mock.assert_async().await;
+        supported_mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_oauth2_client_credentials_regrants_after_a_401() {
+        let mut server = Server::new_async().await;
+        let first_token_mock = server
+            .mock("POST", "/oauth/token")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"access_token": "at-1", "expires_in": 3600}).to_string())
+            .expect(1)
+            .create_async()
+            .await;
+        let second_token_mock = server
+            .mock("POST", "/oauth/token")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"access_token": "at-2", "expires_in": 3600}).to_string())
+            .create_async()
+            .await;
+        let rejected_mock = server
+            .mock("GET", "/supported")
+            .match_header("Authorization", "Bearer at-1")
+            .with_status(401)
+            .expect(1)
+            .create_async()
+            .await;
+        let accepted_mock = server
+            .mock("GET", "/supported")
+            .match_header("Authorization", "Bearer at-2")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"x402Version": 1, "kinds": []}).to_string())
+            .create_async()
+            .await;
+
+        let provider = std::sync::Arc::new(OAuth2ClientCredentials::new(
+            format!("{}/oauth/token", server.url()),
+            "client-id",
+            "client-secret",
+        ));
+        let config = FacilitatorConfig::new(server.url()).with_auth_provider(provider);
+        let client = FacilitatorClient::new(config).unwrap();
+
+        let result = client.supported().await;
+        assert!(result.is_ok(), "Expected supported to succeed after re-granting: {:?}", result.err());
+        first_token_mock.assert_async().await;
+        second_token_mock.assert_async().await;
+        rejected_mock.assert_async().await;
+        accepted_mock.assert_async().await;
+    }
+
+    // Helper functions for creating test data
+    fn create_test_payment_payload() -> PaymentPayload {
+        let authorization = ExactEvmPayloadAuthorization::new(
+            "0x857b06519E91e3A54538791bDbb0E22373e36b66",
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            "1000000",
+            "1745323800",
+            "1745323985",
+            "0xf3746613c2d920b5fdabc0856f2aeb2d4f88ee6037b8cc5d04a71a4462f13480",
+        );
+
+        let payload = ExactEvmPayload {
+            signature: "0x2d6a7588d6acca505cbf0d9a4a227e0c52c6c34008c8e8986a1283259764173608a2ce6496642e377d6da8dbbf5836e9bd15092f9ecab05ded3d6293af148b571c".to_string(),
+            authorization,
+        };
+
+        PaymentPayload::new("exact", "base-sepolia", payload)
+    }
+
+    fn create_test_payment_requirements() -> PaymentRequirements {
+        PaymentRequirements::new(
+            "exact",
+            "base-sepolia",
+            "1000000",
+            "0x036CbD53842c5426634e7929541eC2318f3dCF7e",
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            "https://example.com/test",
+            "Test payment",
+        )
+    }
+
+    #[tokio::test]
+    async fn test_facilitator_discovery_list() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("GET", "/discovery/resources")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "x402Version": 1,
+                    "items": [
+                        {
+                            "resource": "https://example.com/resource1",
+                            "type": "http",
+                            "x402Version": 1,
+                            "accepts": [],
+                            "lastUpdated": 1640995200
+                        }
+                    ],
+                    "pagination": {
+                        "total": 1,
+                        "limit": 10,
+                        "offset": 0
+                    }
+                })
+                .to_string(),
+            )
+            .create();
+
+        let config = FacilitatorConfig::new(server.url());
+        let client = FacilitatorClient::new(config).unwrap();
+
+        let response = client.list_all().await;
+        assert!(response.is_ok(), "Discovery list should succeed");
+        
+        let discovery_response = response.unwrap();
+        assert_eq!(discovery_response.items.len(), 1);
+        assert_eq!(discovery_response.items[0].resource, "https://example.com/resource1");
+        assert_eq!(discovery_response.items[0].r#type, "http");
+    }
+
+    fn discovery_page(resources: Vec<&str>, limit: u32, offset: u32, total: u32) -> String {
+        let items: Vec<_> = resources
+            .into_iter()
+            .map(|resource| {
+                json!({
+                    "resource": resource,
+                    "type": "http",
+                    "x402Version": 1,
+                    "accepts": [],
+                    "lastUpdated": 0,
+                })
+            })
+            .collect();
+        json!({
+            "x402Version": 1,
+            "items": items,
+            "pagination": {"limit": limit, "offset": offset, "total": total},
+        })
+        .to_string()
+    }
+
+    #[tokio::test]
+    async fn test_list_stream_walks_every_page_until_total_is_reached() {
+        use futures_util::StreamExt;
+
+        let mut server = Server::new_async().await;
+        let first_page = server
+            .mock("GET", "/discovery/resources")
+            .match_query(mockito::Matcher::UrlEncoded("offset".into(), "0".into()))
+            .with_status(200)
+            .with_body(discovery_page(vec!["a", "b"], 2, 0, 3))
+            .create_async()
+            .await;
+        let second_page = server
+            .mock("GET", "/discovery/resources")
+            .match_query(mockito::Matcher::UrlEncoded("offset".into(), "2".into()))
+            .with_status(200)
+            .with_body(discovery_page(vec!["c"], 2, 2, 3))
+            .create_async()
+            .await;
+
+        let config = FacilitatorConfig::new(server.url());
+        let client = FacilitatorClient::new(config).unwrap();
+
+        let filters = DiscoveryFilters::new().with_limit(2);
+        let resources: Vec<String> = client
+            .list_stream(Some(filters))
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .map(|r| r.unwrap().resource)
+            .collect();
+
+        assert_eq!(resources, vec!["a", "b", "c"]);
+        first_page.assert_async().await;
+        second_page.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_collect_all_drains_the_stream_into_a_vec() {
+        let mut server = Server::new_async().await;
+        let _only_page = server
+            .mock("GET", "/discovery/resources")
+            .with_status(200)
+            .with_body(discovery_page(vec!["a", "b"], 50, 0, 2))
+            .create_async()
+            .await;
+
+        let config = FacilitatorConfig::new(server.url());
+        let client = FacilitatorClient::new(config).unwrap();
+
+        let resources = client.collect_all(None).await.unwrap();
+        let resources: Vec<String> = resources.into_iter().map(|r| r.resource).collect();
+        assert_eq!(resources, vec!["a", "b"]);
+    }
+
+    #[tokio::test]
+    async fn test_collect_all_stops_at_the_first_page_error() {
+        let mut server = Server::new_async().await;
+        let _only_page = server
+            .mock("GET", "/discovery/resources")
+            .with_status(500)
+            .create_async()
+            .await;
+
+        let config = FacilitatorConfig::new(server.url());
+        let client = FacilitatorClient::new(config).unwrap();
+
+        let result = client.collect_all(None).await;
+        assert!(result.is_err(), "A page error should terminate collect_all with an Err");
+    }
+
+    #[tokio::test]
+    async fn test_facilitator_discovery_with_filters() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("GET", "/discovery/resources")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("type".to_string(), "http".to_string()),
+                Matcher::UrlEncoded("limit".to_string(), "5".to_string()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
                     "x402Version": 1,
-                    "isValid": false,
-                    "invalidReason": "insufficient_funds",
-                    "payer": "0x857b06519E91e3A54538791bDbb0E22373e36b66"
+                    "items": [],
+                    "pagination": {
+                        "total": 0,
+                        "limit": 5,
+                        "offset": 0
+                    }
+                })
+                .to_string(),
+            )
+            .create();
+
+        let config = FacilitatorConfig::new(server.url());
+        let client = FacilitatorClient::new(config).unwrap();
+
+        let filters = DiscoveryFilters::new()
+            .with_resource_type("http")
+            .with_limit(5);
+        
+        let response = client.list(Some(filters)).await;
+        assert!(response.is_ok(), "Discovery with filters should succeed");
+        
+        let discovery_response = response.unwrap();
+        assert_eq!(discovery_response.items.len(), 0);
+        assert_eq!(discovery_response.pagination.limit, 5);
+    }
+
+    #[tokio::test]
+    async fn test_facilitator_discovery_by_type() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("GET", "/discovery/resources")
+            .match_query(Matcher::UrlEncoded("type".to_string(), "api".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "x402Version": 1,
+                    "items": [
+                        {
+                            "resource": "https://api.example.com",
+                            "type": "api",
+                            "x402Version": 1,
+                            "accepts": [],
+                            "lastUpdated": 1640995200
+                        }
+                    ],
+                    "pagination": {
+                        "total": 1,
+                        "limit": 10,
+                        "offset": 0
+                    }
+                })
+                .to_string(),
+            )
+            .create();
+
+        let config = FacilitatorConfig::new(server.url());
+        let client = FacilitatorClient::new(config).unwrap();
+
+        let response = client.list_by_type("api").await;
+        assert!(response.is_ok(), "Discovery by type should succeed");
+        
+        let discovery_response = response.unwrap();
+        assert_eq!(discovery_response.items.len(), 1);
+        assert_eq!(discovery_response.items[0].r#type, "api");
+    }
+
+    #[tokio::test]
+    async fn test_facilitator_discovery_error() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("GET", "/discovery/resources")
+            .with_status(500)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"error": "Internal server error"}"#)
+            .create();
+
+        let config = FacilitatorConfig::new(server.url());
+        let client = FacilitatorClient::new(config).unwrap();
+
+        let response = client.list_all().await;
+        assert!(response.is_err(), "Discovery should fail with 500 error");
+        
+        let error = response.unwrap_err();
+        assert!(error.to_string().contains("Discovery failed with status: 500"));
+    }
+
+    #[tokio::test]
+    async fn test_facilitator_supported_with_auth_headers() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("GET", "/supported")
+            .match_header("Authorization", "Bearer test-token")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "x402Version": 1,
+                    "kinds": [
+                        {
+                            "x402Version": 1,
+                            "scheme": "exact",
+                            "network": "base-sepolia",
+                            "metadata": {
+                                "description": "Test metadata",
+                                "version": "1.0.0"
+                            }
+                        }
+                    ]
+                })
+                .to_string(),
+            )
+            .create();
+
+        let auth_config = || -> Result<HashMap<String, HashMap<String, String>>> {
+            let mut headers = HashMap::new();
+            let mut supported_headers = HashMap::new();
+            supported_headers.insert("Authorization".to_string(), "Bearer test-token".to_string());
+            headers.insert("supported".to_string(), supported_headers);
+            Ok(headers)
+        };
+
+        let config = FacilitatorConfig {
+            url: server.url(),
+            timeout: None,
+            create_auth_headers: Some(std::sync::Arc::new(auth_config)),
+            retry_policy: None,
+            http_signature: None,
+            nonce_replay_store: None,
+            idempotency_store: None,
+            provider: None,
+            supported_cache_ttl: None,
+            auth_provider: None,
+        };
+        let client = FacilitatorClient::new(config).unwrap();
+
+        let response = client.supported().await;
+        assert!(response.is_ok(), "Supported should succeed with auth headers");
+        
+        let supported = response.unwrap();
+        assert_eq!(supported.kinds.len(), 1);
+        assert_eq!(supported.kinds[0].scheme, "exact");
+        assert_eq!(supported.kinds[0].network, "base-sepolia");
+        assert!(supported.kinds[0].metadata.is_some());
+        
+        let metadata = supported.kinds[0].metadata.as_ref().unwrap();
+        assert_eq!(metadata["description"], "Test metadata");
+        assert_eq!(metadata["version"], "1.0.0");
+    }
+
+    #[tokio::test]
+    async fn test_facilitator_supported_without_auth_headers() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("GET", "/supported")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "x402Version": 1,
+                    "kinds": [
+                        {
+                            "x402Version": 1,
+                            "scheme": "exact",
+                            "network": "base-sepolia"
+                        }
+                    ]
                 })
                 .to_string(),
             )
             .create();
 
-        let config = FacilitatorConfig::new(server.url());
-        let client = FacilitatorClient::new(config).unwrap();
+        let config = FacilitatorConfig::new(server.url());
+        let client = FacilitatorClient::new(config).unwrap();
+
+        let response = client.supported().await;
+        assert!(response.is_ok(), "Supported should succeed without auth headers");
+        
+        let supported = response.unwrap();
+        assert_eq!(supported.kinds.len(), 1);
+        assert_eq!(supported.kinds[0].scheme, "exact");
+        assert_eq!(supported.kinds[0].network, "base-sepolia");
+        assert!(supported.kinds[0].metadata.is_none());
+    }
+
+    #[test]
+    fn test_facilitator_client_creation_with_invalid_config() {
+        let config = FacilitatorConfig {
+            url: "invalid-url".to_string(),
+            timeout: None,
+            create_auth_headers: None,
+            retry_policy: None,
+            http_signature: None,
+            nonce_replay_store: None,
+            idempotency_store: None,
+            provider: None,
+            supported_cache_ttl: None,
+            auth_provider: None,
+        };
+
+        let result = FacilitatorClient::new(config);
+        assert!(result.is_err(), "Should fail with invalid URL");
+        
+        let error = result.unwrap_err();
+        assert!(error.to_string().contains("Facilitator URL must start with http:// or https://"));
+    }
+
+    #[test]
+    fn test_facilitator_client_creation_with_valid_config() {
+        let config = FacilitatorConfig {
+            url: "https://example.com/facilitator".to_string(),
+            timeout: Some(std::time::Duration::from_secs(30)),
+            create_auth_headers: None,
+            retry_policy: None,
+            http_signature: None,
+            nonce_replay_store: None,
+            idempotency_store: None,
+            provider: None,
+            supported_cache_ttl: None,
+            auth_provider: None,
+        };
+
+        let result = FacilitatorClient::new(config);
+        assert!(result.is_ok(), "Should succeed with valid config");
+    }
+
+    #[tokio::test]
+    async fn test_facilitator_router_routes_to_network_specific_facilitator() {
+        let mut default_server = Server::new_async().await;
+        let default_mock = default_server.mock("POST", "/verify").with_status(500).create();
+
+        let mut base_sepolia_server = Server::new_async().await;
+        let base_sepolia_mock = base_sepolia_server
+            .mock("POST", "/verify")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"isValid": true, "invalidReason": null}).to_string())
+            .create();
+
+        let default_facilitator =
+            FacilitatorClient::new(FacilitatorConfig::new(default_server.url())).unwrap();
+        let base_sepolia_facilitator =
+            FacilitatorClient::new(FacilitatorConfig::new(base_sepolia_server.url())).unwrap();
+
+        let router = FacilitatorRouter::new(default_facilitator)
+            .with_network("base-sepolia", base_sepolia_facilitator);
 
         let payment_payload = create_test_payment_payload();
         let payment_requirements = create_test_payment_requirements();
 
-        let response = client
+        let result = router.verify(&payment_payload, &payment_requirements).await;
+        assert!(result.is_ok(), "Should route to base-sepolia facilitator: {:?}", result.err());
+        assert!(result.unwrap().is_valid);
+
+        base_sepolia_mock.assert();
+        assert_eq!(default_mock.matched_hits(), 0, "Default facilitator should not be called");
+    }
+
+    #[tokio::test]
+    async fn test_facilitator_router_falls_back_to_default() {
+        let mut default_server = Server::new_async().await;
+        let default_mock = default_server
+            .mock("POST", "/verify")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"isValid": true, "invalidReason": null}).to_string())
+            .create();
+
+        let default_facilitator =
+            FacilitatorClient::new(FacilitatorConfig::new(default_server.url())).unwrap();
+        let router = FacilitatorRouter::new(default_facilitator);
+
+        let payment_payload = create_test_payment_payload();
+        let payment_requirements = create_test_payment_requirements();
+
+        let result = router.verify(&payment_payload, &payment_requirements).await;
+        assert!(result.is_ok());
+        default_mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_fallback_chain_falls_back_on_transient_error() {
+        let mut failing_server = Server::new_async().await;
+        let failing_mock = failing_server
+            .mock("POST", "/verify")
+            .with_status(503)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"code": "upstream_unavailable", "reason": "rpc down"}).to_string())
+            .create();
+
+        let mut healthy_server = Server::new_async().await;
+        let healthy_mock = healthy_server
+            .mock("POST", "/verify")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"isValid": true, "invalidReason": null}).to_string())
+            .create();
+
+        let failing = FacilitatorClient::new(FacilitatorConfig::new(failing_server.url())).unwrap();
+        let healthy = FacilitatorClient::new(FacilitatorConfig::new(healthy_server.url())).unwrap();
+        let chain = FacilitatorFallbackChain::new(vec![failing, healthy]);
+
+        let payment_payload = create_test_payment_payload();
+        let payment_requirements = create_test_payment_requirements();
+
+        let (index, response) = chain
             .verify(&payment_payload, &payment_requirements)
             .await
-            .unwrap();
-        assert!(!response.is_valid);
-        assert_eq!(
-            response.invalid_reason,
-            Some("insufficient_funds".to_string())
-        );
+            .expect("should fall back to the healthy facilitator");
+
+        assert_eq!(index, 1);
+        assert!(response.is_valid);
+        failing_mock.assert();
+        healthy_mock.assert();
     }
 
     #[tokio::test]
-    async fn test_facilitator_settle_success() {
-        let mut server = Server::new_async().await;
-        let _m = server
-            .mock("POST", "/settle")
+    async fn test_fallback_chain_puts_a_failing_entry_in_cooldown_after_the_failure_threshold() {
+        let mut failing_server = Server::new_async().await;
+        let failing_mock = failing_server
+            .mock("POST", "/verify")
+            .expect(2)
+            .with_status(503)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"code": "upstream_unavailable", "reason": "rpc down"}).to_string())
+            .create();
+
+        let mut healthy_server = Server::new_async().await;
+        let healthy_mock = healthy_server
+            .mock("POST", "/verify")
+            .expect(3)
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(json!({
-                "success": true,
-                "transaction": "0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef",
-                "network": "base-sepolia",
-                "payer": "0x857b06519E91e3A54538791bDbb0E22373e36b66"
-            }).to_string())
+            .with_body(json!({"isValid": true, "invalidReason": null}).to_string())
             .create();
 
-        let config = FacilitatorConfig::new(server.url());
-        let client = FacilitatorClient::new(config).unwrap();
+        let failing = FacilitatorClient::new(FacilitatorConfig::new(failing_server.url())).unwrap();
+        let healthy = FacilitatorClient::new(FacilitatorConfig::new(healthy_server.url())).unwrap();
+        let chain = FacilitatorFallbackChain::new(vec![failing, healthy]).with_failure_threshold(2);
 
         let payment_payload = create_test_payment_payload();
         let payment_requirements = create_test_payment_requirements();
 
-        let response = client
-            .settle(&payment_payload, &payment_requirements)
+        // Two calls trip the failing entry's threshold; a third call should skip it
+        // entirely instead of hitting it again before falling back.
+        for _ in 0..3 {
+            let (index, response) = chain
+                .verify(&payment_payload, &payment_requirements)
+                .await
+                .expect("should fall back to the healthy facilitator");
+            assert_eq!(index, 1);
+            assert!(response.is_valid);
+        }
+
+        assert!(!chain.is_healthy(0));
+        failing_mock.assert();
+        healthy_mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_fallback_chain_round_robin_rotates_starting_facilitator() {
+        let mut first_server = Server::new_async().await;
+        let first_mock = first_server
+            .mock("POST", "/verify")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"isValid": true, "invalidReason": null}).to_string())
+            .create();
+
+        let mut second_server = Server::new_async().await;
+        let second_mock = second_server
+            .mock("POST", "/verify")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"isValid": true, "invalidReason": null}).to_string())
+            .create();
+
+        let first = FacilitatorClient::new(FacilitatorConfig::new(first_server.url())).unwrap();
+        let second = FacilitatorClient::new(FacilitatorConfig::new(second_server.url())).unwrap();
+        let chain = FacilitatorFallbackChain::new(vec![first, second]).with_policy(RoutingPolicy::RoundRobin);
+
+        let payment_payload = create_test_payment_payload();
+        let payment_requirements = create_test_payment_requirements();
+
+        let (first_index, _) = chain.verify(&payment_payload, &payment_requirements).await.unwrap();
+        let (second_index, _) = chain.verify(&payment_payload, &payment_requirements).await.unwrap();
+
+        assert_ne!(first_index, second_index, "round robin should rotate the starting facilitator");
+        first_mock.assert();
+        second_mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_fallback_chain_skips_entries_not_applicable_to_network() {
+        let mut other_network_server = Server::new_async().await;
+        // No `/verify` mock is registered on this server: the entry is restricted to
+        // "base", so it must never be reached for a "base-sepolia" payment.
+        let other_network_mock = other_network_server.mock("POST", "/verify").expect(0).create();
+
+        let mut matching_server = Server::new_async().await;
+        let matching_mock = matching_server
+            .mock("POST", "/verify")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"isValid": true, "invalidReason": null}).to_string())
+            .create();
+
+        let other_network = FacilitatorClient::new(FacilitatorConfig::new(other_network_server.url())).unwrap();
+        let matching = FacilitatorClient::new(FacilitatorConfig::new(matching_server.url())).unwrap();
+        let chain = FacilitatorFallbackChain::with_entries(vec![
+            FacilitatorChainEntry::for_networks(other_network, vec!["base".to_string()]),
+            FacilitatorChainEntry::new(matching),
+        ]);
+
+        let payment_payload = create_test_payment_payload();
+        let payment_requirements = create_test_payment_requirements();
+
+        let (index, response) = chain
+            .verify(&payment_payload, &payment_requirements)
             .await
-            .unwrap();
-        assert!(response.success);
-        assert_eq!(
-            response.transaction,
-            "0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef"
-        );
-        assert_eq!(response.network, "base-sepolia");
+            .expect("should skip the network-restricted entry and use the unrestricted one");
+
+        assert_eq!(index, 1);
+        assert!(response.is_valid);
+        other_network_mock.assert();
+        matching_mock.assert();
     }
 
     #[tokio::test]
-    async fn test_facilitator_settle_failure() {
+    async fn test_fallback_chain_errors_when_no_entry_supports_network() {
+        let server = Server::new_async().await;
+        let client = FacilitatorClient::new(FacilitatorConfig::new(server.url())).unwrap();
+        let chain = FacilitatorFallbackChain::with_entries(vec![FacilitatorChainEntry::for_networks(
+            client,
+            vec!["base".to_string()],
+        )]);
+
+        let payment_payload = create_test_payment_payload();
+        let payment_requirements = create_test_payment_requirements();
+
+        let error = chain
+            .verify(&payment_payload, &payment_requirements)
+            .await
+            .expect_err("no entry supports base-sepolia");
+        assert!(matches!(error, X402Error::Config { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_retryable_facilitator_retries_upstream_unavailable() {
         let mut server = Server::new_async().await;
-        let _m = server
-            .mock("POST", "/settle")
+        let mock = server
+            .mock("POST", "/verify")
+            .with_status(503)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"code": "upstream_unavailable", "reason": "rpc down"}).to_string())
+            .expect(2)
+            .create();
+        let ok_mock = server
+            .mock("POST", "/verify")
             .with_status(200)
             .with_header("content-type", "application/json")
+            .with_body(json!({"isValid": true, "invalidReason": null}).to_string())
+            .create();
+
+        let facilitator = FacilitatorClient::new(FacilitatorConfig::new(server.url())).unwrap();
+        let retryable = RetryableFacilitator::new(facilitator).with_policy(
+            RetryableFacilitatorPolicy::new()
+                .with_max_attempts(3)
+                .with_base_delay(std::time::Duration::from_millis(1)),
+        );
+
+        let payment_payload = create_test_payment_payload();
+        let payment_requirements = create_test_payment_requirements();
+
+        let result = retryable
+            .verify(&payment_payload, &payment_requirements)
+            .await;
+        assert!(result.is_ok(), "should eventually succeed: {:?}", result.err());
+        mock.assert();
+        ok_mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_retryable_facilitator_does_not_retry_insufficient_funds() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/verify")
+            .with_status(402)
+            .with_header("content-type", "application/json")
             .with_body(
-                json!({
-                    "x402Version": 1,
-                    "success": false,
-                    "errorReason": "transaction_failed",
-                    "transaction": "",
-                    "network": "base-sepolia",
-                    "payer": "0x857b06519E91e3A54538791bDbb0E22373e36b66"
-                })
-                .to_string(),
+                json!({"code": "insufficient_funds", "reason": "not enough balance"}).to_string(),
             )
+            .expect(1)
             .create();
 
-        let config = FacilitatorConfig::new(server.url());
-        let client = FacilitatorClient::new(config).unwrap();
+        let facilitator = FacilitatorClient::new(FacilitatorConfig::new(server.url())).unwrap();
+        let retryable = RetryableFacilitator::new(facilitator).with_policy(
+            RetryableFacilitatorPolicy::new().with_base_delay(std::time::Duration::from_millis(1)),
+        );
 
         let payment_payload = create_test_payment_payload();
         let payment_requirements = create_test_payment_requirements();
 
-        let response = client
-            .settle(&payment_payload, &payment_requirements)
-            .await
-            .unwrap();
-        assert!(!response.success);
-        assert_eq!(
-            response.error_reason,
-            Some("transaction_failed".to_string())
+        let result = retryable
+            .verify(&payment_payload, &payment_requirements)
+            .await;
+        assert!(result.is_err());
+        assert!(matches!(
+            result.unwrap_err(),
+            X402Error::Facilitator {
+                code: crate::error::FacilitatorCode::InsufficientFunds,
+                ..
+            }
+        ));
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_retryable_facilitator_honors_retry_after_over_the_policy_delay() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/verify")
+            .with_status(429)
+            .with_header("content-type", "application/json")
+            .with_header("retry-after", "0")
+            .with_body(json!({"code": "rate_limited", "reason": "slow down"}).to_string())
+            .expect(1)
+            .create();
+        let ok_mock = server
+            .mock("POST", "/verify")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"isValid": true, "invalidReason": null}).to_string())
+            .create();
+
+        let facilitator = FacilitatorClient::new(FacilitatorConfig::new(server.url())).unwrap();
+        // A policy delay long enough that the test would time out if it were used
+        // instead of the header's `Retry-After: 0`.
+        let retryable = RetryableFacilitator::new(facilitator).with_policy(
+            RetryableFacilitatorPolicy::new()
+                .with_max_attempts(2)
+                .with_base_delay(std::time::Duration::from_secs(60)),
         );
-        assert_eq!(response.transaction, "");
+
+        let payment_payload = create_test_payment_payload();
+        let payment_requirements = create_test_payment_requirements();
+
+        let started = std::time::Instant::now();
+        let result = retryable
+            .verify(&payment_payload, &payment_requirements)
+            .await;
+        assert!(result.is_ok(), "should eventually succeed: {:?}", result.err());
+        assert!(
+            started.elapsed() < std::time::Duration::from_secs(30),
+            "retry should have used Retry-After: 0 instead of the policy's 60s delay"
+        );
+        mock.assert();
+        ok_mock.assert();
     }
 
     #[tokio::test]
-    async fn test_facilitator_server_error() {
+    async fn test_retryable_facilitator_custom_predicate_overrides_default() {
         let mut server = Server::new_async().await;
-        let _m = server.mock("POST", "/verify").with_status(500).create();
+        let mock = server
+            .mock("POST", "/verify")
+            .with_status(402)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({"code": "insufficient_funds", "reason": "not enough balance"}).to_string(),
+            )
+            .expect(2)
+            .create();
 
-        let config = FacilitatorConfig::new(server.url());
-        let client = FacilitatorClient::new(config).unwrap();
+        let facilitator = FacilitatorClient::new(FacilitatorConfig::new(server.url())).unwrap();
+        let retryable = RetryableFacilitator::new(facilitator)
+            .with_policy(
+                RetryableFacilitatorPolicy::new()
+                    .with_max_attempts(2)
+                    .with_base_delay(std::time::Duration::from_millis(1)),
+            )
+            .with_retry_predicate(|_error| true);
 
         let payment_payload = create_test_payment_payload();
         let payment_requirements = create_test_payment_requirements();
 
-        let result = client.verify(&payment_payload, &payment_requirements).await;
+        let result = retryable
+            .verify(&payment_payload, &payment_requirements)
+            .await;
         assert!(result.is_err());
-        assert!(result
-            .unwrap_err()
-            .to_string()
-            .contains("Verification failed with status: 500"));
+        mock.assert();
     }
 
     #[tokio::test]
-    async fn test_facilitator_supported() {
+    async fn test_retryable_facilitator_settle_does_not_retry_upstream_unavailable() {
+        // Unlike `verify`, `settle` must not retry a facilitator-reported
+        // `upstream_unavailable`: the facilitator received the request, so whether it
+        // also broadcast the transaction before answering is unknown, and retrying
+        // risks double-settlement.
         let mut server = Server::new_async().await;
-        let _m = server
-            .mock("GET", "/supported")
+        let mock = server
+            .mock("POST", "/settle")
+            .with_status(503)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"code": "upstream_unavailable", "reason": "rpc down"}).to_string())
+            .expect(1)
+            .create();
+
+        let facilitator = FacilitatorClient::new(FacilitatorConfig::new(server.url())).unwrap();
+        let retryable = RetryableFacilitator::new(facilitator).with_policy(
+            RetryableFacilitatorPolicy::new()
+                .with_max_attempts(3)
+                .with_base_delay(std::time::Duration::from_millis(1)),
+        );
+
+        let payment_payload = create_test_payment_payload();
+        let payment_requirements = create_test_payment_requirements();
+
+        let result = retryable
+            .settle(&payment_payload, &payment_requirements)
+            .await;
+        assert!(result.is_err());
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_retryable_facilitator_settle_predicate_can_be_widened() {
+        // A deployment that can otherwise rule out double-settlement may opt in to
+        // retrying `settle` as liberally as `verify` via `with_settle_retry_predicate`.
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/settle")
+            .with_status(503)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"code": "upstream_unavailable", "reason": "rpc down"}).to_string())
+            .expect(2)
+            .create();
+        let ok_mock = server
+            .mock("POST", "/settle")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(
-                json!({
-                    "x402Version": 1,
-                    "kinds": [
-                        {
-                            "x402Version": 1,
-                            "scheme": "exact",
-                            "network": "base-sepolia"
-                        },
-                        {
-                            "x402Version": 1,
-                            "scheme": "exact",
-                            "network": "base"
-                        }
-                    ]
-                })
-                .to_string(),
+            .with_body(json!({
+                "success": true,
+                "transaction": "0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef",
+                "network": "base-sepolia",
+                "payer": "0x857b06519E91e3A54538791bDbb0E22373e36b66"
+            }).to_string())
+            .create();
+
+        let facilitator = FacilitatorClient::new(FacilitatorConfig::new(server.url())).unwrap();
+        let retryable = RetryableFacilitator::new(facilitator)
+            .with_policy(
+                RetryableFacilitatorPolicy::new()
+                    .with_max_attempts(3)
+                    .with_base_delay(std::time::Duration::from_millis(1)),
             )
+            .with_settle_retry_predicate(|error| error.is_retryable());
+
+        let payment_payload = create_test_payment_payload();
+        let payment_requirements = create_test_payment_requirements();
+
+        let result = retryable
+            .settle(&payment_payload, &payment_requirements)
+            .await;
+        assert!(result.is_ok(), "should eventually succeed: {:?}", result.err());
+        mock.assert();
+        ok_mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_facilitator_client_with_retry_attempts_succeeds_after_transient_failure() {
+        let mut server = Server::new_async().await;
+        let failure_mock = server.mock("POST", "/verify").with_status(503).expect(1).create();
+        let ok_mock = server
+            .mock("POST", "/verify")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"isValid": true, "invalidReason": null}).to_string())
             .create();
 
-        let config = FacilitatorConfig::new(server.url());
-        let client = FacilitatorClient::new(config).unwrap();
+        let facilitator = FacilitatorClient::with_retry(
+            server.url(),
+            crate::retry::Retry::Attempts(2),
+            crate::retry::RetryPolicy::new().with_base_delay(std::time::Duration::from_millis(1)),
+        )
+        .unwrap();
 
-        let supported = client.supported().await.unwrap();
-        assert_eq!(supported.kinds.len(), 2);
-        assert_eq!(supported.kinds[0].scheme, "exact");
-        assert_eq!(supported.kinds[0].network, "base-sepolia");
-        assert_eq!(supported.kinds[1].network, "base");
+        let payment_payload = create_test_payment_payload();
+        let payment_requirements = create_test_payment_requirements();
+
+        let result = facilitator
+            .verify(&payment_payload, &payment_requirements)
+            .await;
+        assert!(result.is_ok(), "should retry then succeed: {:?}", result.err());
+        failure_mock.assert();
+        ok_mock.assert();
     }
 
     #[tokio::test]
-    async fn test_facilitator_with_auth_headers() {
+    async fn test_facilitator_client_with_retry_never_retries_rejection() {
         let mut server = Server::new_async().await;
-        let _m = server
+        let mock = server
             .mock("POST", "/verify")
-            .with_status(200)
+            .with_status(402)
             .with_header("content-type", "application/json")
-            .match_header("Authorization", "Bearer test-token")
-            .match_header("Correlation-Context", Matcher::Regex(r".*".to_string()))
             .with_body(
-                json!({
-                    "x402Version": 1,
-                    "isValid": true,
-                    "payer": "0x857b06519E91e3A54538791bDbb0E22373e36b66"
-                })
-                .to_string(),
+                json!({"code": "insufficient_funds", "reason": "not enough balance"}).to_string(),
             )
+            .expect(1)
             .create();
 
-        let create_auth_headers = || {
-            let mut headers = HashMap::new();
-            let mut verify_headers = HashMap::new();
-            verify_headers.insert("Authorization".to_string(), "Bearer test-token".to_string());
-            verify_headers.insert(
-                "Correlation-Context".to_string(),
-                "test=correlation".to_string(),
-            );
-            headers.insert("verify".to_string(), verify_headers);
-            Ok(headers)
-        };
-
-        let config =
-            FacilitatorConfig::new(server.url()).with_auth_headers(Box::new(create_auth_headers));
-        let client = FacilitatorClient::new(config).unwrap();
+        let facilitator = FacilitatorClient::with_retry(
+            server.url(),
+            crate::retry::Retry::Attempts(5),
+            crate::retry::RetryPolicy::new().with_base_delay(std::time::Duration::from_millis(1)),
+        )
+        .unwrap();
 
         let payment_payload = create_test_payment_payload();
         let payment_requirements = create_test_payment_requirements();
 
-        let response = client
+        let result = facilitator
             .verify(&payment_payload, &payment_requirements)
-            .await
-            .unwrap();
-        assert!(response.is_valid);
+            .await;
+        assert!(result.is_err());
+        mock.assert();
+    }
+
+    struct MockFacilitator {
+        verify_result: bool,
+        supported_kinds: Vec<SupportedKind>,
+    }
+
+    impl MockFacilitator {
+        fn new(verify_result: bool) -> Self {
+            Self {
+                verify_result,
+                supported_kinds: vec![SupportedKind {
+                    x402_version: X402_VERSION,
+                    scheme: "exact".to_string(),
+                    network: "base-sepolia".to_string(),
+                    asset: None,
+                }],
+            }
+        }
+    }
+
+    impl Facilitator for MockFacilitator {
+        fn verify<'a>(
+            &'a self,
+            _payment_payload: &'a PaymentPayload,
+            _payment_requirements: &'a PaymentRequirements,
+        ) -> BoxFuture<'a, Result<VerifyResponse>> {
+            let is_valid = self.verify_result;
+            Box::pin(async move {
+                Ok(VerifyResponse {
+                    is_valid,
+                    invalid_reason: None,
+                    payer: None,
+                })
+            })
+        }
+
+        fn settle<'a>(
+            &'a self,
+            _payment_payload: &'a PaymentPayload,
+            payment_requirements: &'a PaymentRequirements,
+        ) -> BoxFuture<'a, Result<SettleResponse>> {
+            let network = payment_requirements.network.clone();
+            Box::pin(async move {
+                Ok(SettleResponse {
+                    success: true,
+                    error_reason: None,
+                    transaction: "mock-tx".to_string(),
+                    network,
+                    payer: None,
+                })
+            })
+        }
+
+        fn supported(&self) -> BoxFuture<'_, Result<SupportedKinds>> {
+            let kinds = self.supported_kinds.clone();
+            Box::pin(async move { Ok(SupportedKinds { kinds }) })
+        }
     }
 
     #[tokio::test]
-    async fn test_facilitator_timeout() {
-        // Test with a very short timeout and a URL that will timeout
-        let config = FacilitatorConfig::new("http://10.255.255.1:9999") // Non-routable IP
-            .with_timeout(Duration::from_millis(1));
-        let client = FacilitatorClient::new(config).unwrap();
+    async fn test_facilitator_registry_routes_by_network_and_scheme() {
+        let registry = FacilitatorRegistry::new().with_backend(
+            "mock-base",
+            std::sync::Arc::new(MockFacilitator::new(true)),
+            [("base-sepolia".to_string(), "exact".to_string())],
+        );
 
         let payment_payload = create_test_payment_payload();
         let payment_requirements = create_test_payment_requirements();
 
-        let result = client.verify(&payment_payload, &payment_requirements).await;
-        assert!(result.is_err());
-        // Check for timeout-related error - be more flexible with error messages
-        let error_msg = result.unwrap_err().to_string();
-        assert!(
-            error_msg.contains("timeout")
-                || error_msg.contains("connection")
-                || error_msg.contains("network")
-                || error_msg.contains("unreachable")
-                || error_msg.contains("refused")
-                || error_msg.contains("No route to host")
-                || error_msg.contains("failed to connect")
-                || error_msg.contains("Connection refused")
-                || error_msg.contains("Network is unreachable")
-                || error_msg.contains("Name or service not known")
-                || error_msg.contains("Temporary failure in name resolution")
-                || error_msg.contains("error sending request")
-                || error_msg.contains("HTTP error")
-                || error_msg.contains("Facilitator error"),
-            "Expected timeout/connection error, got: {}",
-            error_msg
+        let result = registry.verify(&payment_payload, &payment_requirements).await;
+        assert!(result.is_ok());
+        assert!(result.unwrap().is_valid);
+    }
+
+    #[tokio::test]
+    async fn test_facilitator_registry_errors_on_unsupported_network_scheme() {
+        let registry = FacilitatorRegistry::new().with_backend(
+            "mock-base",
+            std::sync::Arc::new(MockFacilitator::new(true)),
+            [("base".to_string(), "exact".to_string())],
         );
+
+        let payment_payload = create_test_payment_payload();
+        let payment_requirements = create_test_payment_requirements();
+
+        let result = registry.verify(&payment_payload, &payment_requirements).await;
+        assert!(result.is_err());
     }
 
     #[tokio::test]
-    async fn test_network_mismatch_returns_error() {
+    async fn test_facilitator_client_implements_facilitator_trait() {
         let mut server = Server::new_async().await;
-        let _m = server
+        let mock = server
             .mock("POST", "/verify")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(
-                json!({
-                    "x402Version": 1,
-                    "isValid": true,
-                    "payer": "0x857b06519E91e3A54538791bDbb0E22373e36b66"
-                })
-                .to_string(),
-            )
+            .with_body(json!({"isValid": true, "invalidReason": null}).to_string())
             .create();
 
-        let config = FacilitatorConfig::new(server.url());
-        let client = FacilitatorClient::new(config).unwrap();
+        let facilitator: std::sync::Arc<dyn Facilitator> =
+            std::sync::Arc::new(FacilitatorClient::new(FacilitatorConfig::new(server.url())).unwrap());
 
-        // Create payment payload with different network than requirements
-        let authorization = ExactEvmPayloadAuthorization::new(
-            "0x857b06519E91e3A54538791bDbb0E22373e36b66",
-            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
-            "1000000",
-            "1745323800",
-            "1745323985",
-            "0xf3746613c2d920b5fdabc0856f2aeb2d4f88ee6037b8cc5d04a71a4462f13480",
-        );
+        let payment_payload = create_test_payment_payload();
+        let payment_requirements = create_test_payment_requirements();
 
-        let payload = ExactEvmPayload {
-            signature: "0x2d6a7588d6acca505cbf0d9a4a227e0c52c6c34008c8e8986a1283259764173608a2ce6496642e377d6da8dbbf5836e9bd15092f9ecab05ded3d6293af148b571c".to_string(),
-            authorization,
-        };
+        let result = facilitator.verify(&payment_payload, &payment_requirements).await;
+        assert!(result.is_ok());
+        mock.assert();
+    }
 
-        // Payment with "base" network
-        let payment_payload = PaymentPayload::new("exact", "base", payload);
+    struct CountingFacilitator {
+        supported_calls: std::sync::atomic::AtomicU32,
+        supported_kinds: Vec<SupportedKind>,
+    }
 
-        // Requirements with "base-sepolia" network - should cause panic
-        let payment_requirements = PaymentRequirements::new(
-            "exact",
-            "base-sepolia", // Different network - should panic
-            "1000000",
-            "0x036CbD53842c5426634e7929541eC2318f3dCF7e",
-            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
-            "https://example.com/test",
-            "Test payment",
-        );
+    impl CountingFacilitator {
+        fn new(supported_kinds: Vec<SupportedKind>) -> Self {
+            Self {
+                supported_calls: std::sync::atomic::AtomicU32::new(0),
+                supported_kinds,
+            }
+        }
+    }
 
-        // This should return an error due to network mismatch
-        let result = client
-            .verify_with_network_validation(&payment_payload, &payment_requirements)
-            .await;
+    impl Facilitator for CountingFacilitator {
+        fn verify<'a>(
+            &'a self,
+            _payment_payload: &'a PaymentPayload,
+            _payment_requirements: &'a PaymentRequirements,
+        ) -> BoxFuture<'a, Result<VerifyResponse>> {
+            Box::pin(async move {
+                Ok(VerifyResponse {
+                    is_valid: true,
+                    invalid_reason: None,
+                    payer: None,
+                })
+            })
+        }
 
-        // Verify that we get an error for network mismatch
-        assert!(result.is_err(), "Network mismatch should result in error");
-        
-        // Verify the error is specifically a payment verification error
-        let error = result.unwrap_err();
-        match error {
-            X402Error::PaymentVerificationFailed { reason: _ } => {
-                // This is the expected error type
-            }
-            _ => panic!("Expected PaymentVerificationFailed error, got: {:?}", error),
+        fn settle<'a>(
+            &'a self,
+            _payment_payload: &'a PaymentPayload,
+            payment_requirements: &'a PaymentRequirements,
+        ) -> BoxFuture<'a, Result<SettleResponse>> {
+            let network = payment_requirements.network.clone();
+            Box::pin(async move {
+                Ok(SettleResponse {
+                    success: true,
+                    error_reason: None,
+                    transaction: "mock-tx".to_string(),
+                    network,
+                    payer: None,
+                })
+            })
+        }
+
+        fn supported(&self) -> BoxFuture<'_, Result<SupportedKinds>> {
+            self.supported_calls
+                .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            let kinds = self.supported_kinds.clone();
+            Box::pin(async move { Ok(SupportedKinds { kinds }) })
         }
-        
-        // Verify the error message content
-        let error_msg = error.to_string();
-        assert!(error_msg.contains("Network mismatch detected"), 
-                "Error should contain 'Network mismatch detected' - actual: {}", error_msg);
-        assert!(error_msg.contains("base") && error_msg.contains("base-sepolia"),
-                "Error should contain both network names - actual: {}", error_msg);
     }
 
-    // Helper functions for creating test data
-    fn create_test_payment_payload() -> PaymentPayload {
-        let authorization = ExactEvmPayloadAuthorization::new(
-            "0x857b06519E91e3A54538791bDbb0E22373e36b66",
-            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
-            "1000000",
-            "1745323800",
-            "1745323985",
-            "0xf3746613c2d920b5fdabc0856f2aeb2d4f88ee6037b8cc5d04a71a4462f13480",
+    #[tokio::test]
+    async fn test_cached_facilitator_reuses_supported_within_ttl() {
+        let inner = std::sync::Arc::new(CountingFacilitator::new(vec![SupportedKind {
+            x402_version: X402_VERSION,
+            scheme: "exact".to_string(),
+            network: "base-sepolia".to_string(),
+            asset: None,
+        }]));
+        let cached = CachedFacilitator::new(inner.clone(), std::time::Duration::from_secs(60));
+
+        cached.supported_cached().await.unwrap();
+        cached.supported_cached().await.unwrap();
+
+        assert_eq!(
+            inner.supported_calls.load(std::sync::atomic::Ordering::SeqCst),
+            1
         );
+    }
 
-        let payload = ExactEvmPayload {
-            signature: "0x2d6a7588d6acca505cbf0d9a4a227e0c52c6c34008c8e8986a1283259764173608a2ce6496642e377d6da8dbbf5836e9bd15092f9ecab05ded3d6293af148b571c".to_string(),
-            authorization,
-        };
+    #[tokio::test]
+    async fn test_cached_facilitator_refetches_after_ttl_expires() {
+        let inner = std::sync::Arc::new(CountingFacilitator::new(vec![SupportedKind {
+            x402_version: X402_VERSION,
+            scheme: "exact".to_string(),
+            network: "base-sepolia".to_string(),
+            asset: None,
+        }]));
+        let cached = CachedFacilitator::new(inner.clone(), std::time::Duration::from_millis(1));
+
+        cached.supported_cached().await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        cached.supported_cached().await.unwrap();
 
-        PaymentPayload::new("exact", "base-sepolia", payload)
+        assert_eq!(
+            inner.supported_calls.load(std::sync::atomic::Ordering::SeqCst),
+            2
+        );
     }
 
-    fn create_test_payment_requirements() -> PaymentRequirements {
-        PaymentRequirements::new(
-            "exact",
-            "base-sepolia",
-            "1000000",
-            "0x036CbD53842c5426634e7929541eC2318f3dCF7e",
-            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
-            "https://example.com/test",
-            "Test payment",
-        )
+    #[tokio::test]
+    async fn test_cached_facilitator_ensure_supports() {
+        let inner = std::sync::Arc::new(CountingFacilitator::new(vec![SupportedKind {
+            x402_version: X402_VERSION,
+            scheme: "exact".to_string(),
+            network: "base-sepolia".to_string(),
+            asset: None,
+        }]));
+        let cached = CachedFacilitator::new(inner, std::time::Duration::from_secs(60));
+
+        assert!(cached.ensure_supports("base-sepolia", "exact").await.is_ok());
+        assert!(cached.ensure_supports("base", "exact").await.is_err());
     }
 
     #[tokio::test]
-    async fn test_facilitator_discovery_list() {
-        let mut server = Server::new_async().await;
-        let _m = server
-            .mock("GET", "/discovery/resources")
+    async fn test_ensure_supports_requirements_accepts_an_asset_agnostic_kind() {
+        // No `asset` advertised on the matching kind at all, so any asset passes.
+        let inner = std::sync::Arc::new(CountingFacilitator::new(vec![SupportedKind {
+            x402_version: X402_VERSION,
+            scheme: "exact".to_string(),
+            network: "base-sepolia".to_string(),
+            asset: None,
+        }]));
+        let cached = CachedFacilitator::new(inner, std::time::Duration::from_secs(60));
+
+        assert!(cached
+            .ensure_supports_requirements(&create_test_payment_requirements())
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_ensure_supports_requirements_rejects_an_unlisted_asset() {
+        let inner = std::sync::Arc::new(CountingFacilitator::new(vec![SupportedKind {
+            x402_version: X402_VERSION,
+            scheme: "exact".to_string(),
+            network: "base-sepolia".to_string(),
+            asset: Some("0xSomeOtherToken".to_string()),
+        }]));
+        let cached = CachedFacilitator::new(inner, std::time::Duration::from_secs(60));
+
+        let err = cached
+            .ensure_supports_requirements(&create_test_payment_requirements())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, X402Error::UnsupportedByFacilitator { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_ensure_supports_requirements_accepts_a_listed_asset() {
+        let requirements = create_test_payment_requirements();
+        let inner = std::sync::Arc::new(CountingFacilitator::new(vec![SupportedKind {
+            x402_version: X402_VERSION,
+            scheme: "exact".to_string(),
+            network: "base-sepolia".to_string(),
+            asset: Some(requirements.asset.clone()),
+        }]));
+        let cached = CachedFacilitator::new(inner, std::time::Duration::from_secs(60));
+
+        assert!(cached.ensure_supports_requirements(&requirements).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_cached_facilitator_verify_rejects_before_forwarding_to_the_inner_backend() {
+        let inner = std::sync::Arc::new(CountingFacilitator::new(vec![SupportedKind {
+            x402_version: X402_VERSION,
+            scheme: "exact".to_string(),
+            network: "base-sepolia".to_string(),
+            asset: Some("0xSomeOtherToken".to_string()),
+        }]));
+        let cached = CachedFacilitator::new(inner, std::time::Duration::from_secs(60));
+
+        let requirements = create_test_payment_requirements();
+        let payload = create_test_payment_payload();
+        let err = cached.verify(&payload, &requirements).await.unwrap_err();
+        assert!(matches!(err, X402Error::UnsupportedByFacilitator { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_facilitator_registry_validate_passes_when_pairs_match() {
+        let registry = FacilitatorRegistry::new().with_backend(
+            "mock-base",
+            std::sync::Arc::new(MockFacilitator::new(true)),
+            [("base-sepolia".to_string(), "exact".to_string())],
+        );
+
+        assert!(registry.validate().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_facilitator_registry_validate_fails_on_undeclared_pair() {
+        let registry = FacilitatorRegistry::new().with_backend(
+            "mock-base",
+            std::sync::Arc::new(MockFacilitator::new(true)),
+            [("base".to_string(), "exact".to_string())],
+        );
+
+        let result = registry.validate().await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_keyed_facilitator_chain_routes_by_scheme_and_network() {
+        let mut base_sepolia_server = Server::new_async().await;
+        let base_sepolia_mock = base_sepolia_server
+            .mock("POST", "/verify")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(
-                json!({
-                    "x402Version": 1,
-                    "items": [
-                        {
-                            "resource": "https://example.com/resource1",
-                            "type": "http",
-                            "x402Version": 1,
-                            "accepts": [],
-                            "lastUpdated": 1640995200
-                        }
-                    ],
-                    "pagination": {
-                        "total": 1,
-                        "limit": 10,
-                        "offset": 0
-                    }
-                })
-                .to_string(),
-            )
+            .with_body(json!({"isValid": true, "invalidReason": null}).to_string())
             .create();
 
-        let config = FacilitatorConfig::new(server.url());
-        let client = FacilitatorClient::new(config).unwrap();
+        let base_server = Server::new_async().await;
+
+        let chain = KeyedFacilitatorChain::new(
+            vec![
+                FacilitatorEntry::new(
+                    "exact",
+                    "base-sepolia",
+                    FacilitatorConfig::new(base_sepolia_server.url()),
+                ),
+                FacilitatorEntry::new("exact", "base", FacilitatorConfig::new(base_server.url())),
+            ],
+            RoutingPolicy::Priority,
+        )
+        .unwrap();
+
+        let payment_payload = create_test_payment_payload();
+        let payment_requirements = create_test_payment_requirements();
+
+        let (url, response) = chain
+            .verify(&payment_payload, &payment_requirements)
+            .await
+            .unwrap();
 
-        let response = client.list_all().await;
-        assert!(response.is_ok(), "Discovery list should succeed");
-        
-        let discovery_response = response.unwrap();
-        assert_eq!(discovery_response.items.len(), 1);
-        assert_eq!(discovery_response.items[0].resource, "https://example.com/resource1");
-        assert_eq!(discovery_response.items[0].r#type, "http");
+        assert!(response.is_valid);
+        assert_eq!(url, Some(base_sepolia_server.url()));
+        base_sepolia_mock.assert();
     }
 
     #[tokio::test]
-    async fn test_facilitator_discovery_with_filters() {
-        let mut server = Server::new_async().await;
-        let _m = server
-            .mock("GET", "/discovery/resources")
-            .match_query(Matcher::AllOf(vec![
-                Matcher::UrlEncoded("type".to_string(), "http".to_string()),
-                Matcher::UrlEncoded("limit".to_string(), "5".to_string()),
-            ]))
+    async fn test_keyed_facilitator_chain_falls_back_within_a_pairing() {
+        let mut failing_server = Server::new_async().await;
+        let failing_mock = failing_server
+            .mock("POST", "/verify")
+            .with_status(503)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"code": "upstream_unavailable", "reason": "rpc down"}).to_string())
+            .create();
+
+        let mut healthy_server = Server::new_async().await;
+        let healthy_mock = healthy_server
+            .mock("POST", "/verify")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(
-                json!({
-                    "x402Version": 1,
-                    "items": [],
-                    "pagination": {
-                        "total": 0,
-                        "limit": 5,
-                        "offset": 0
-                    }
-                })
-                .to_string(),
-            )
+            .with_body(json!({"isValid": true, "invalidReason": null}).to_string())
             .create();
 
-        let config = FacilitatorConfig::new(server.url());
-        let client = FacilitatorClient::new(config).unwrap();
+        let chain = KeyedFacilitatorChain::new(
+            vec![
+                FacilitatorEntry::new(
+                    "exact",
+                    "base-sepolia",
+                    FacilitatorConfig::new(failing_server.url()),
+                ),
+                FacilitatorEntry::new(
+                    "exact",
+                    "base-sepolia",
+                    FacilitatorConfig::new(healthy_server.url()),
+                ),
+            ],
+            RoutingPolicy::Priority,
+        )
+        .unwrap();
 
-        let filters = DiscoveryFilters::new()
-            .with_resource_type("http")
-            .with_limit(5);
-        
-        let response = client.list(Some(filters)).await;
-        assert!(response.is_ok(), "Discovery with filters should succeed");
-        
-        let discovery_response = response.unwrap();
-        assert_eq!(discovery_response.items.len(), 0);
-        assert_eq!(discovery_response.pagination.limit, 5);
+        let payment_payload = create_test_payment_payload();
+        let payment_requirements = create_test_payment_requirements();
+
+        let (url, response) = chain
+            .verify(&payment_payload, &payment_requirements)
+            .await
+            .expect("should fall back to the healthy facilitator in the same pairing");
+
+        assert!(response.is_valid);
+        assert_eq!(url, Some(healthy_server.url()));
+        failing_mock.assert();
+        healthy_mock.assert();
     }
 
     #[tokio::test]
-    async fn test_facilitator_discovery_by_type() {
-        let mut server = Server::new_async().await;
-        let _m = server
-            .mock("GET", "/discovery/resources")
-            .match_query(Matcher::UrlEncoded("type".to_string(), "api".to_string()))
+    async fn test_keyed_facilitator_chain_errors_on_unregistered_pairing() {
+        let server = Server::new_async().await;
+        let chain = KeyedFacilitatorChain::new(
+            vec![FacilitatorEntry::new(
+                "exact",
+                "base",
+                FacilitatorConfig::new(server.url()),
+            )],
+            RoutingPolicy::Priority,
+        )
+        .unwrap();
+
+        let payment_payload = create_test_payment_payload();
+        let payment_requirements = create_test_payment_requirements();
+
+        let result = chain.verify(&payment_payload, &payment_requirements).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_quorum_facilitator_verify_reaches_threshold_on_agreement() {
+        let mut first_server = Server::new_async().await;
+        first_server
+            .mock("POST", "/verify")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(
-                json!({
-                    "x402Version": 1,
-                    "items": [
-                        {
-                            "resource": "https://api.example.com",
-                            "type": "api",
-                            "x402Version": 1,
-                            "accepts": [],
-                            "lastUpdated": 1640995200
-                        }
-                    ],
-                    "pagination": {
-                        "total": 1,
-                        "limit": 10,
-                        "offset": 0
-                    }
-                })
-                .to_string(),
-            )
+            .with_body(json!({"isValid": true, "invalidReason": null}).to_string())
             .create();
 
-        let config = FacilitatorConfig::new(server.url());
-        let client = FacilitatorClient::new(config).unwrap();
+        let mut second_server = Server::new_async().await;
+        second_server
+            .mock("POST", "/verify")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"isValid": true, "invalidReason": null}).to_string())
+            .create();
 
-        let response = client.list_by_type("api").await;
-        assert!(response.is_ok(), "Discovery by type should succeed");
-        
-        let discovery_response = response.unwrap();
-        assert_eq!(discovery_response.items.len(), 1);
-        assert_eq!(discovery_response.items[0].r#type, "api");
+        let mut dissenting_server = Server::new_async().await;
+        dissenting_server
+            .mock("POST", "/verify")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"isValid": false, "invalidReason": "insufficient_funds"}).to_string())
+            .create();
+
+        let first: Arc<dyn Facilitator> =
+            Arc::new(FacilitatorClient::new(FacilitatorConfig::new(first_server.url())).unwrap());
+        let second: Arc<dyn Facilitator> =
+            Arc::new(FacilitatorClient::new(FacilitatorConfig::new(second_server.url())).unwrap());
+        let dissenting: Arc<dyn Facilitator> = Arc::new(
+            FacilitatorClient::new(FacilitatorConfig::new(dissenting_server.url())).unwrap(),
+        );
+
+        let quorum = QuorumFacilitator::new(
+            vec![
+                QuorumMember::new(first, 1),
+                QuorumMember::new(second, 1),
+                QuorumMember::new(dissenting, 1),
+            ],
+            2,
+        );
+
+        let payment_payload = create_test_payment_payload();
+        let payment_requirements = create_test_payment_requirements();
+
+        let response = quorum
+            .verify(&payment_payload, &payment_requirements)
+            .await
+            .expect("two agreeing members should reach the threshold of 2");
+        assert!(response.is_valid);
     }
 
     #[tokio::test]
-    async fn test_facilitator_discovery_error() {
-        let mut server = Server::new_async().await;
-        let _m = server
-            .mock("GET", "/discovery/resources")
-            .with_status(500)
+    async fn test_quorum_facilitator_verify_fails_when_no_group_reaches_threshold() {
+        let mut first_server = Server::new_async().await;
+        first_server
+            .mock("POST", "/verify")
+            .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(r#"{"error": "Internal server error"}"#)
+            .with_body(json!({"isValid": true, "invalidReason": null}).to_string())
             .create();
 
-        let config = FacilitatorConfig::new(server.url());
-        let client = FacilitatorClient::new(config).unwrap();
+        let mut second_server = Server::new_async().await;
+        second_server
+            .mock("POST", "/verify")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"isValid": false, "invalidReason": "insufficient_funds"}).to_string())
+            .create();
 
-        let response = client.list_all().await;
-        assert!(response.is_err(), "Discovery should fail with 500 error");
-        
-        let error = response.unwrap_err();
-        assert!(error.to_string().contains("Discovery failed with status: 500"));
+        let first: Arc<dyn Facilitator> =
+            Arc::new(FacilitatorClient::new(FacilitatorConfig::new(first_server.url())).unwrap());
+        let second: Arc<dyn Facilitator> =
+            Arc::new(FacilitatorClient::new(FacilitatorConfig::new(second_server.url())).unwrap());
+
+        let quorum = QuorumFacilitator::new(
+            vec![QuorumMember::new(first, 1), QuorumMember::new(second, 1)],
+            2,
+        );
+
+        let payment_payload = create_test_payment_payload();
+        let payment_requirements = create_test_payment_requirements();
+
+        let result = quorum.verify(&payment_payload, &payment_requirements).await;
+        assert!(result.is_err());
     }
 
     #[tokio::test]
-    async fn test_facilitator_supported_with_auth_headers() {
-        let mut server = Server::new_async().await;
-        let _m = server
-            .mock("GET", "/supported")
-            .match_header("Authorization", "Bearer test-token")
+    async fn test_quorum_facilitator_settle_first_success_wins() {
+        let mut failing_server = Server::new_async().await;
+        failing_server
+            .mock("POST", "/settle")
+            .with_status(503)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"code": "upstream_unavailable", "reason": "rpc down"}).to_string())
+            .create();
+
+        let mut healthy_server = Server::new_async().await;
+        healthy_server
+            .mock("POST", "/settle")
             .with_status(200)
             .with_header("content-type", "application/json")
             .with_body(
                 json!({
-                    "x402Version": 1,
-                    "kinds": [
-                        {
-                            "x402Version": 1,
-                            "scheme": "exact",
-                            "network": "base-sepolia",
-                            "metadata": {
-                                "description": "Test metadata",
-                                "version": "1.0.0"
-                            }
-                        }
-                    ]
+                    "success": true,
+                    "errorReason": null,
+                    "transaction": "0xabc123",
+                    "network": "base-sepolia",
                 })
                 .to_string(),
             )
             .create();
 
-        let auth_config = || -> Result<HashMap<String, HashMap<String, String>>> {
-            let mut headers = HashMap::new();
-            let mut supported_headers = HashMap::new();
-            supported_headers.insert("Authorization".to_string(), "Bearer test-token".to_string());
-            headers.insert("supported".to_string(), supported_headers);
-            Ok(headers)
-        };
+        let failing: Arc<dyn Facilitator> =
+            Arc::new(FacilitatorClient::new(FacilitatorConfig::new(failing_server.url())).unwrap());
+        let healthy: Arc<dyn Facilitator> =
+            Arc::new(FacilitatorClient::new(FacilitatorConfig::new(healthy_server.url())).unwrap());
 
-        let config = FacilitatorConfig {
-            url: server.url(),
-            timeout: None,
-            create_auth_headers: Some(std::sync::Arc::new(auth_config)),
-        };
-        let client = FacilitatorClient::new(config).unwrap();
+        let quorum = QuorumFacilitator::new(
+            vec![QuorumMember::new(failing, 1), QuorumMember::new(healthy, 1)],
+            1,
+        )
+        .with_settle_mode(QuorumSettleMode::FirstSuccess);
 
-        let response = client.supported().await;
-        assert!(response.is_ok(), "Supported should succeed with auth headers");
-        
-        let supported = response.unwrap();
-        assert_eq!(supported.kinds.len(), 1);
-        assert_eq!(supported.kinds[0].scheme, "exact");
-        assert_eq!(supported.kinds[0].network, "base-sepolia");
-        assert!(supported.kinds[0].metadata.is_some());
-        
-        let metadata = supported.kinds[0].metadata.as_ref().unwrap();
-        assert_eq!(metadata["description"], "Test metadata");
-        assert_eq!(metadata["version"], "1.0.0");
+        let payment_payload = create_test_payment_payload();
+        let payment_requirements = create_test_payment_requirements();
+
+        let response = quorum
+            .settle(&payment_payload, &payment_requirements)
+            .await
+            .expect("the healthy member should win the race");
+        assert_eq!(response.transaction, "0xabc123");
     }
 
     #[tokio::test]
-    async fn test_facilitator_supported_without_auth_headers() {
-        let mut server = Server::new_async().await;
-        let _m = server
-            .mock("GET", "/supported")
+    async fn test_quorum_facilitator_settle_primary_with_fallback_falls_back() {
+        let mut failing_server = Server::new_async().await;
+        failing_server
+            .mock("POST", "/settle")
+            .with_status(503)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"code": "upstream_unavailable", "reason": "rpc down"}).to_string())
+            .create();
+
+        let mut healthy_server = Server::new_async().await;
+        healthy_server
+            .mock("POST", "/settle")
             .with_status(200)
             .with_header("content-type", "application/json")
             .with_body(
                 json!({
-                    "x402Version": 1,
-                    "kinds": [
-                        {
-                            "x402Version": 1,
-                            "scheme": "exact",
-                            "network": "base-sepolia"
-                        }
-                    ]
+                    "success": true,
+                    "errorReason": null,
+                    "transaction": "0xdef456",
+                    "network": "base-sepolia",
                 })
                 .to_string(),
             )
             .create();
 
-        let config = FacilitatorConfig::new(server.url());
-        let client = FacilitatorClient::new(config).unwrap();
-
-        let response = client.supported().await;
-        assert!(response.is_ok(), "Supported should succeed without auth headers");
-        
-        let supported = response.unwrap();
-        assert_eq!(supported.kinds.len(), 1);
-        assert_eq!(supported.kinds[0].scheme, "exact");
-        assert_eq!(supported.kinds[0].network, "base-sepolia");
-        assert!(supported.kinds[0].metadata.is_none());
-    }
-
-    #[test]
-    fn test_facilitator_client_creation_with_invalid_config() {
-        let config = FacilitatorConfig {
-            url: "invalid-url".to_string(),
-            timeout: None,
-            create_auth_headers: None,
-        };
+        let failing: Arc<dyn Facilitator> =
+            Arc::new(FacilitatorClient::new(FacilitatorConfig::new(failing_server.url())).unwrap());
+        let healthy: Arc<dyn Facilitator> =
+            Arc::new(FacilitatorClient::new(FacilitatorConfig::new(healthy_server.url())).unwrap());
 
-        let result = FacilitatorClient::new(config);
-        assert!(result.is_err(), "Should fail with invalid URL");
-        
-        let error = result.unwrap_err();
-        assert!(error.to_string().contains("Facilitator URL must start with http:// or https://"));
-    }
+        // Higher weight sorts first, so the primary-with-fallback ordering visits the
+        // failing member before falling back to the healthy one.
+        let quorum = QuorumFacilitator::new(
+            vec![QuorumMember::new(failing, 2), QuorumMember::new(healthy, 1)],
+            1,
+        );
 
-    #[test]
-    fn test_facilitator_client_creation_with_valid_config() {
-        let config = FacilitatorConfig {
-            url: "https://example.com/facilitator".to_string(),
-            timeout: Some(std::time::Duration::from_secs(30)),
-            create_auth_headers: None,
-        };
+        let payment_payload = create_test_payment_payload();
+        let payment_requirements = create_test_payment_requirements();
 
-        let result = FacilitatorClient::new(config);
-        assert!(result.is_ok(), "Should succeed with valid config");
+        let response = quorum
+            .settle(&payment_payload, &payment_requirements)
+            .await
+            .expect("should fall back to the healthy member");
+        assert_eq!(response.transaction, "0xdef456");
     }
 }
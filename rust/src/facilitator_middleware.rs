@@ -0,0 +1,527 @@
+//! Composable middleware for the facilitator's blockchain RPC interactions
+//!
+//! [`BlockchainFacilitatorClient`](crate::real_facilitator::BlockchainFacilitatorClient)
+//! talks to [`crate::blockchain::BlockchainClient`] directly, so inserting custom
+//! behavior (retrying a flaky RPC endpoint, logging every gas estimate, swapping in an
+//! alternate nonce source) means editing that client itself. [`FacilitatorMiddleware`]
+//! factors the handful of JSON-RPC primitives the facilitator needs — sending a raw
+//! transaction, estimating gas, reading a nonce, pricing gas — into a trait, mirroring
+//! how ethers-rs's `Middleware` lets layers wrap a provider and override only the
+//! calls they care about. [`BlockchainClientMiddleware`] is the terminal layer that
+//! actually calls [`crate::blockchain::BlockchainClient`]; [`RetryMiddleware`],
+//! [`NonceManagerMiddleware`] and [`GasOracleMiddleware`] wrap another layer and can be
+//! stacked in any order at construction time.
+
+use crate::blockchain::{BlockchainClient, FeeHistory, TransactionRequest};
+use crate::{Result, X402Error};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// A layer in the facilitator's blockchain RPC stack
+///
+/// Every method mirrors a [`BlockchainClient`] call of the same name. A layer that
+/// doesn't need to intervene on a given call just delegates to the layer it wraps.
+pub trait FacilitatorMiddleware: Send + Sync {
+    /// Broadcast a signed, RLP-encoded transaction
+    fn send_raw_transaction<'a>(
+        &'a self,
+        signed_tx_hex: &'a str,
+    ) -> crate::facilitator::BoxFuture<'a, Result<String>>;
+
+    /// Estimate gas for an unsigned transaction
+    fn estimate_gas<'a>(
+        &'a self,
+        transaction: &'a TransactionRequest,
+    ) -> crate::facilitator::BoxFuture<'a, Result<u64>>;
+
+    /// Read the next nonce for `address`
+    fn get_transaction_count<'a>(&'a self, address: &'a str) -> crate::facilitator::BoxFuture<'a, Result<u64>>;
+
+    /// Fetch current EIP-1559 fee data
+    fn fee_history(&self, block_count: u64, reward_percentile: f64) -> crate::facilitator::BoxFuture<'_, Result<FeeHistory>>;
+}
+
+/// Lets a stack built behind an `Arc<dyn FacilitatorMiddleware>` (the type
+/// [`crate::real_facilitator::BlockchainFacilitatorClient::with_middleware`] stores)
+/// be wrapped in another layer, e.g. `GasOracleMiddleware::new(shared_stack, 1.2)`
+impl<T: FacilitatorMiddleware + ?Sized> FacilitatorMiddleware for std::sync::Arc<T> {
+    fn send_raw_transaction<'a>(
+        &'a self,
+        signed_tx_hex: &'a str,
+    ) -> crate::facilitator::BoxFuture<'a, Result<String>> {
+        (**self).send_raw_transaction(signed_tx_hex)
+    }
+
+    fn estimate_gas<'a>(
+        &'a self,
+        transaction: &'a TransactionRequest,
+    ) -> crate::facilitator::BoxFuture<'a, Result<u64>> {
+        (**self).estimate_gas(transaction)
+    }
+
+    fn get_transaction_count<'a>(&'a self, address: &'a str) -> crate::facilitator::BoxFuture<'a, Result<u64>> {
+        (**self).get_transaction_count(address)
+    }
+
+    fn fee_history(&self, block_count: u64, reward_percentile: f64) -> crate::facilitator::BoxFuture<'_, Result<FeeHistory>> {
+        (**self).fee_history(block_count, reward_percentile)
+    }
+}
+
+/// The terminal layer: forwards every call straight to a [`BlockchainClient`]
+pub struct BlockchainClientMiddleware {
+    inner: BlockchainClient,
+}
+
+impl BlockchainClientMiddleware {
+    /// Wrap `inner`, with no behavior added
+    pub fn new(inner: BlockchainClient) -> Self {
+        Self { inner }
+    }
+}
+
+impl FacilitatorMiddleware for BlockchainClientMiddleware {
+    fn send_raw_transaction<'a>(
+        &'a self,
+        signed_tx_hex: &'a str,
+    ) -> crate::facilitator::BoxFuture<'a, Result<String>> {
+        Box::pin(self.inner.send_raw_transaction(signed_tx_hex))
+    }
+
+    fn estimate_gas<'a>(
+        &'a self,
+        transaction: &'a TransactionRequest,
+    ) -> crate::facilitator::BoxFuture<'a, Result<u64>> {
+        Box::pin(self.inner.estimate_gas(transaction))
+    }
+
+    fn get_transaction_count<'a>(&'a self, address: &'a str) -> crate::facilitator::BoxFuture<'a, Result<u64>> {
+        Box::pin(self.inner.get_transaction_count(address))
+    }
+
+    fn fee_history(&self, block_count: u64, reward_percentile: f64) -> crate::facilitator::BoxFuture<'_, Result<FeeHistory>> {
+        Box::pin(self.inner.fee_history(block_count, reward_percentile))
+    }
+}
+
+/// Retries any call that fails, up to `max_attempts` times, sleeping between
+/// attempts per [`crate::retry::RetryPolicy`]'s backoff schedule
+///
+/// Unlike [`crate::retry::retry_with_backoff`], this doesn't gate on
+/// [`X402Error::is_retryable`] — a facilitator operator pointing at a single RPC
+/// endpoint generally wants any transient failure retried, not just the ones the
+/// HTTP-facing facilitator API classifies as retryable. This is what makes
+/// [`crate::real_facilitator::BlockchainFacilitatorConfig`]'s `max_retries` and
+/// `retry_delay` fields actually do something, instead of being read and discarded.
+pub struct RetryMiddleware<M> {
+    inner: M,
+    policy: crate::retry::RetryPolicy,
+}
+
+impl<M: FacilitatorMiddleware> RetryMiddleware<M> {
+    /// Wrap `inner`, retrying up to `max_attempts` times with `retry_delay` as the
+    /// base backoff
+    pub fn new(inner: M, max_attempts: u32, retry_delay: Duration) -> Self {
+        Self {
+            inner,
+            policy: crate::retry::RetryPolicy::new()
+                .with_max_attempts(max_attempts)
+                .with_base_delay(retry_delay),
+        }
+    }
+
+    async fn with_retry<T, F, Fut>(&self, mut operation: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: std::future::Future<Output = Result<T>>,
+    {
+        let mut attempt = 0;
+        loop {
+            match operation().await {
+                Ok(value) => return Ok(value),
+                Err(error) if attempt + 1 < self.policy.max_attempts => {
+                    tokio::time::sleep(self.policy.delay_for_attempt(attempt)).await;
+                    attempt += 1;
+                    let _ = &error;
+                }
+                Err(error) => return Err(error),
+            }
+        }
+    }
+}
+
+impl<M: FacilitatorMiddleware> FacilitatorMiddleware for RetryMiddleware<M> {
+    fn send_raw_transaction<'a>(
+        &'a self,
+        signed_tx_hex: &'a str,
+    ) -> crate::facilitator::BoxFuture<'a, Result<String>> {
+        Box::pin(self.with_retry(|| self.inner.send_raw_transaction(signed_tx_hex)))
+    }
+
+    fn estimate_gas<'a>(
+        &'a self,
+        transaction: &'a TransactionRequest,
+    ) -> crate::facilitator::BoxFuture<'a, Result<u64>> {
+        Box::pin(self.with_retry(|| self.inner.estimate_gas(transaction)))
+    }
+
+    fn get_transaction_count<'a>(&'a self, address: &'a str) -> crate::facilitator::BoxFuture<'a, Result<u64>> {
+        Box::pin(self.with_retry(|| self.inner.get_transaction_count(address)))
+    }
+
+    fn fee_history(&self, block_count: u64, reward_percentile: f64) -> crate::facilitator::BoxFuture<'_, Result<FeeHistory>> {
+        Box::pin(self.with_retry(|| self.inner.fee_history(block_count, reward_percentile)))
+    }
+}
+
+/// Caches the relayer's next nonce locally after the first `eth_getTransactionCount`
+/// read, incrementing it on every subsequent call instead of re-reading the pending
+/// count — the same trick ethers-rs's `NonceManagerMiddleware` uses so back-to-back
+/// settlements from the same account don't race each other for the same nonce. If a
+/// broadcast fails, the cached value is dropped so the next call resyncs from chain
+/// rather than keep handing out nonces built on a value the RPC just rejected.
+pub struct NonceManagerMiddleware<M> {
+    inner: M,
+    cached_nonce: Mutex<Option<u64>>,
+}
+
+impl<M: FacilitatorMiddleware> NonceManagerMiddleware<M> {
+    /// Wrap `inner`, with an empty nonce cache
+    pub fn new(inner: M) -> Self {
+        Self {
+            inner,
+            cached_nonce: Mutex::new(None),
+        }
+    }
+}
+
+impl<M: FacilitatorMiddleware> FacilitatorMiddleware for NonceManagerMiddleware<M> {
+    fn send_raw_transaction<'a>(
+        &'a self,
+        signed_tx_hex: &'a str,
+    ) -> crate::facilitator::BoxFuture<'a, Result<String>> {
+        Box::pin(async move {
+            let result = self.inner.send_raw_transaction(signed_tx_hex).await;
+            if result.is_err() {
+                // A broadcast failure (e.g. "nonce too low"/"replacement underpriced" from a
+                // concurrent settlement, or the cached value having drifted from chain state
+                // some other way) invalidates our locally cached nonce. Drop it so the next
+                // call re-reads `eth_getTransactionCount` instead of handing out another nonce
+                // built on a value the chain just rejected.
+                *self.cached_nonce.lock().unwrap() = None;
+            }
+            result
+        })
+    }
+
+    fn estimate_gas<'a>(
+        &'a self,
+        transaction: &'a TransactionRequest,
+    ) -> crate::facilitator::BoxFuture<'a, Result<u64>> {
+        self.inner.estimate_gas(transaction)
+    }
+
+    fn get_transaction_count<'a>(&'a self, address: &'a str) -> crate::facilitator::BoxFuture<'a, Result<u64>> {
+        Box::pin(async move {
+            let cached = *self.cached_nonce.lock().unwrap();
+            let nonce = match cached {
+                Some(n) => n,
+                None => self.inner.get_transaction_count(address).await?,
+            };
+            *self.cached_nonce.lock().unwrap() = Some(nonce + 1);
+            Ok(nonce)
+        })
+    }
+
+    fn fee_history(&self, block_count: u64, reward_percentile: f64) -> crate::facilitator::BoxFuture<'_, Result<FeeHistory>> {
+        self.inner.fee_history(block_count, reward_percentile)
+    }
+}
+
+/// Scales the inner layer's `eth_feeHistory` result by a fixed multiplier, mirroring
+/// ethers-rs's gas oracle middleware's role of letting an operator price gas more
+/// aggressively than the node's raw fee history suggests (e.g. to avoid getting
+/// stranded during a fee spike between reading fee history and broadcasting)
+pub struct GasOracleMiddleware<M> {
+    inner: M,
+    /// Multiplier applied to both `base_fee_per_gas` and `max_priority_fee_per_gas`,
+    /// e.g. `1.2` for a 20% bump
+    multiplier: f64,
+}
+
+impl<M: FacilitatorMiddleware> GasOracleMiddleware<M> {
+    /// Wrap `inner`, scaling its fee history by `multiplier`
+    pub fn new(inner: M, multiplier: f64) -> Self {
+        Self { inner, multiplier }
+    }
+}
+
+impl<M: FacilitatorMiddleware> FacilitatorMiddleware for GasOracleMiddleware<M> {
+    fn send_raw_transaction<'a>(
+        &'a self,
+        signed_tx_hex: &'a str,
+    ) -> crate::facilitator::BoxFuture<'a, Result<String>> {
+        self.inner.send_raw_transaction(signed_tx_hex)
+    }
+
+    fn estimate_gas<'a>(
+        &'a self,
+        transaction: &'a TransactionRequest,
+    ) -> crate::facilitator::BoxFuture<'a, Result<u64>> {
+        self.inner.estimate_gas(transaction)
+    }
+
+    fn get_transaction_count<'a>(&'a self, address: &'a str) -> crate::facilitator::BoxFuture<'a, Result<u64>> {
+        self.inner.get_transaction_count(address)
+    }
+
+    fn fee_history(&self, block_count: u64, reward_percentile: f64) -> crate::facilitator::BoxFuture<'_, Result<FeeHistory>> {
+        let multiplier = self.multiplier;
+        Box::pin(async move {
+            let fees = self.inner.fee_history(block_count, reward_percentile).await?;
+            Ok(FeeHistory {
+                base_fee_per_gas: ((fees.base_fee_per_gas as f64) * multiplier) as u128,
+                max_priority_fee_per_gas: ((fees.max_priority_fee_per_gas as f64) * multiplier) as u128,
+            })
+        })
+    }
+}
+
+/// Carries the relayer's signing key alongside the RPC stack, mirroring ethers-rs's
+/// `SignerMiddleware`
+///
+/// The facilitator signs settlement transactions itself (see
+/// [`crate::real_facilitator::BlockchainFacilitatorClient::create_settlement_transaction`])
+/// rather than relying on the RPC node to sign, so this layer doesn't intercept any
+/// [`FacilitatorMiddleware`] call — it delegates all of them unchanged — but gives a
+/// composed stack a single place to carry the signer identity that built it, so
+/// callers holding a `SignerMiddleware` don't need a side channel to find out which
+/// key is at the bottom of the stack.
+pub struct SignerMiddleware<M> {
+    inner: M,
+    address: ethereum_types::Address,
+}
+
+impl<M: FacilitatorMiddleware> SignerMiddleware<M> {
+    /// Wrap `inner`, recording `signer`'s address
+    pub fn new(inner: M, signer: &crate::crypto::signature::LocalSigner) -> Result<Self> {
+        Ok(Self {
+            inner,
+            address: signer.address()?,
+        })
+    }
+
+    /// The address settlement transactions from this stack will be signed by
+    pub fn address(&self) -> ethereum_types::Address {
+        self.address
+    }
+}
+
+impl<M: FacilitatorMiddleware> FacilitatorMiddleware for SignerMiddleware<M> {
+    fn send_raw_transaction<'a>(
+        &'a self,
+        signed_tx_hex: &'a str,
+    ) -> crate::facilitator::BoxFuture<'a, Result<String>> {
+        self.inner.send_raw_transaction(signed_tx_hex)
+    }
+
+    fn estimate_gas<'a>(
+        &'a self,
+        transaction: &'a TransactionRequest,
+    ) -> crate::facilitator::BoxFuture<'a, Result<u64>> {
+        self.inner.estimate_gas(transaction)
+    }
+
+    fn get_transaction_count<'a>(&'a self, address: &'a str) -> crate::facilitator::BoxFuture<'a, Result<u64>> {
+        self.inner.get_transaction_count(address)
+    }
+
+    fn fee_history(&self, block_count: u64, reward_percentile: f64) -> crate::facilitator::BoxFuture<'_, Result<FeeHistory>> {
+        self.inner.fee_history(block_count, reward_percentile)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    struct FlakyThenOk {
+        failures_remaining: AtomicU32,
+    }
+
+    impl FacilitatorMiddleware for FlakyThenOk {
+        fn send_raw_transaction<'a>(
+            &'a self,
+            _signed_tx_hex: &'a str,
+        ) -> crate::facilitator::BoxFuture<'a, Result<String>> {
+            Box::pin(async move {
+                if self.failures_remaining.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| {
+                    if n == 0 { None } else { Some(n - 1) }
+                }).is_ok() {
+                    return Err(X402Error::config("simulated transient RPC failure"));
+                }
+                Ok("0xdeadbeef".to_string())
+            })
+        }
+
+        fn estimate_gas<'a>(
+            &'a self,
+            _transaction: &'a TransactionRequest,
+        ) -> crate::facilitator::BoxFuture<'a, Result<u64>> {
+            Box::pin(async move { Ok(21000) })
+        }
+
+        fn get_transaction_count<'a>(&'a self, _address: &'a str) -> crate::facilitator::BoxFuture<'a, Result<u64>> {
+            Box::pin(async move { Ok(5) })
+        }
+
+        fn fee_history(&self, _block_count: u64, _reward_percentile: f64) -> crate::facilitator::BoxFuture<'_, Result<FeeHistory>> {
+            Box::pin(async move {
+                Ok(FeeHistory {
+                    base_fee_per_gas: 100,
+                    max_priority_fee_per_gas: 10,
+                })
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retry_middleware_retries_until_success() {
+        let inner = FlakyThenOk {
+            failures_remaining: AtomicU32::new(2),
+        };
+        let middleware = RetryMiddleware::new(inner, 5, Duration::from_millis(1));
+
+        let result = middleware.send_raw_transaction("0xsigned").await.unwrap();
+        assert_eq!(result, "0xdeadbeef");
+    }
+
+    #[tokio::test]
+    async fn test_retry_middleware_gives_up_after_max_attempts() {
+        let inner = FlakyThenOk {
+            failures_remaining: AtomicU32::new(10),
+        };
+        let middleware = RetryMiddleware::new(inner, 3, Duration::from_millis(1));
+
+        assert!(middleware.send_raw_transaction("0xsigned").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_nonce_manager_middleware_caches_after_first_read() {
+        struct CountingInner {
+            reads: AtomicU32,
+        }
+
+        impl FacilitatorMiddleware for CountingInner {
+            fn send_raw_transaction<'a>(
+                &'a self,
+                _signed_tx_hex: &'a str,
+            ) -> crate::facilitator::BoxFuture<'a, Result<String>> {
+                Box::pin(async move { Ok("0x0".to_string()) })
+            }
+
+            fn estimate_gas<'a>(
+                &'a self,
+                _transaction: &'a TransactionRequest,
+            ) -> crate::facilitator::BoxFuture<'a, Result<u64>> {
+                Box::pin(async move { Ok(21000) })
+            }
+
+            fn get_transaction_count<'a>(&'a self, _address: &'a str) -> crate::facilitator::BoxFuture<'a, Result<u64>> {
+                self.reads.fetch_add(1, Ordering::SeqCst);
+                Box::pin(async move { Ok(7) })
+            }
+
+            fn fee_history(&self, _block_count: u64, _reward_percentile: f64) -> crate::facilitator::BoxFuture<'_, Result<FeeHistory>> {
+                Box::pin(async move {
+                    Ok(FeeHistory {
+                        base_fee_per_gas: 100,
+                        max_priority_fee_per_gas: 10,
+                    })
+                })
+            }
+        }
+
+        let middleware = NonceManagerMiddleware::new(CountingInner { reads: AtomicU32::new(0) });
+
+        let first = middleware.get_transaction_count("0xabc").await.unwrap();
+        let second = middleware.get_transaction_count("0xabc").await.unwrap();
+        let third = middleware.get_transaction_count("0xabc").await.unwrap();
+
+        assert_eq!(first, 7);
+        assert_eq!(second, 8);
+        assert_eq!(third, 9);
+        assert_eq!(middleware.inner.reads.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_nonce_manager_middleware_resyncs_after_a_failed_broadcast() {
+        struct RejectsThenAccepts {
+            reads: AtomicU32,
+        }
+
+        impl FacilitatorMiddleware for RejectsThenAccepts {
+            fn send_raw_transaction<'a>(
+                &'a self,
+                signed_tx_hex: &'a str,
+            ) -> crate::facilitator::BoxFuture<'a, Result<String>> {
+                let reject = signed_tx_hex == "0xstale";
+                Box::pin(async move {
+                    if reject {
+                        Err(X402Error::config("nonce too low"))
+                    } else {
+                        Ok("0xdeadbeef".to_string())
+                    }
+                })
+            }
+
+            fn estimate_gas<'a>(
+                &'a self,
+                _transaction: &'a TransactionRequest,
+            ) -> crate::facilitator::BoxFuture<'a, Result<u64>> {
+                Box::pin(async move { Ok(21000) })
+            }
+
+            fn get_transaction_count<'a>(&'a self, _address: &'a str) -> crate::facilitator::BoxFuture<'a, Result<u64>> {
+                self.reads.fetch_add(1, Ordering::SeqCst);
+                Box::pin(async move { Ok(40) })
+            }
+
+            fn fee_history(&self, _block_count: u64, _reward_percentile: f64) -> crate::facilitator::BoxFuture<'_, Result<FeeHistory>> {
+                Box::pin(async move {
+                    Ok(FeeHistory {
+                        base_fee_per_gas: 100,
+                        max_priority_fee_per_gas: 10,
+                    })
+                })
+            }
+        }
+
+        let middleware = NonceManagerMiddleware::new(RejectsThenAccepts { reads: AtomicU32::new(0) });
+
+        // First nonce read populates the cache; the broadcast using it is rejected.
+        let _ = middleware.get_transaction_count("0xabc").await.unwrap();
+        assert!(middleware.send_raw_transaction("0xstale").await.is_err());
+
+        // The failed broadcast must have dropped the cached nonce, forcing a resync
+        // on the next read instead of handing out the already-rejected value again.
+        let resynced = middleware.get_transaction_count("0xabc").await.unwrap();
+        assert_eq!(resynced, 40);
+        assert_eq!(middleware.inner.reads.load(Ordering::SeqCst), 2);
+
+        assert!(middleware.send_raw_transaction("0xfresh").await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_gas_oracle_middleware_scales_fee_history() {
+        let inner = FlakyThenOk {
+            failures_remaining: AtomicU32::new(0),
+        };
+        let middleware = GasOracleMiddleware::new(inner, 1.5);
+
+        let fees = middleware.fee_history(4, 50.0).await.unwrap();
+        assert_eq!(fees.base_fee_per_gas, 150);
+        assert_eq!(fees.max_priority_fee_per_gas, 15);
+    }
+}
@@ -0,0 +1,339 @@
+//! EIP-1559 fee suggestion
+//!
+//! [`BlockchainClient::get_network_info`] and [`BlockchainClient::estimate_gas`] only
+//! ever surfaced legacy `eth_gasPrice`, which underpays on networks where EIP-1559 is
+//! the norm (Base and Avalanche mainnet both expect `maxFeePerGas`/
+//! `maxPriorityFeePerGas`, not a flat gas price). [`GasOracle`] computes both from
+//! `eth_feeHistory`: `max_priority_fee_per_gas` is the requested percentile's reward
+//! over the last few blocks, and `max_fee_per_gas` is the latest `baseFeePerGas` times
+//! a multiplier (covering base-fee growth over the next few blocks while the
+//! transaction sits unconfirmed) plus that priority fee. [`FeeStrategy`] picks the
+//! percentile: `Safe`/`Average`/`Fast` map to the 20th/50th/80th, the same three-tier
+//! naming most wallet gas pickers use. When `eth_feeHistory` isn't available on a
+//! network, [`GasOracle::suggest_fees`] falls back to the legacy gas price from
+//! [`BlockchainClient::get_network_info`] as `max_fee_per_gas` with no priority fee,
+//! rather than failing outright.
+
+use crate::blockchain::{BlockchainClient, FeeHistory, TransactionRequest};
+use crate::types::PaymentPayload;
+use crate::{Result, X402Error};
+use serde::{Deserialize, Serialize};
+
+/// Which percentile of recent priority-fee rewards to target
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeeStrategy {
+    /// 20th percentile — cheaper, may take longer to mine under load
+    Safe,
+    /// 50th percentile (median) — the default, reasonable under most conditions
+    Average,
+    /// 80th percentile — pays up for faster inclusion
+    Fast,
+}
+
+impl FeeStrategy {
+    /// The `eth_feeHistory` reward percentile this strategy targets
+    pub fn reward_percentile(self) -> f64 {
+        match self {
+            FeeStrategy::Safe => 20.0,
+            FeeStrategy::Average => 50.0,
+            FeeStrategy::Fast => 80.0,
+        }
+    }
+}
+
+/// Suggested EIP-1559 fees for a transaction, in wei
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EvmFees {
+    /// `maxPriorityFeePerGas` — the tip offered to the block producer
+    pub max_priority_fee_per_gas: u128,
+    /// `maxFeePerGas` — the most this transaction will pay per unit of gas, covering
+    /// both the priority fee and the base fee's expected growth
+    pub max_fee_per_gas: u128,
+}
+
+/// Derive [`EvmFees`] from a raw [`FeeHistory`] sample, scaling the base fee by
+/// `base_fee_multiplier` to cover its growth over the next few blocks before this
+/// transaction confirms
+pub fn eip1559_fees_from_history(fees: FeeHistory, base_fee_multiplier: f64) -> EvmFees {
+    EvmFees {
+        max_priority_fee_per_gas: fees.max_priority_fee_per_gas,
+        max_fee_per_gas: ((fees.base_fee_per_gas as f64) * base_fee_multiplier) as u128
+            + fees.max_priority_fee_per_gas,
+    }
+}
+
+/// Computes [`EvmFees`] for a [`BlockchainClient`]'s network, by strategy
+pub struct GasOracle {
+    blockchain: BlockchainClient,
+    base_fee_multiplier: f64,
+    block_count: u64,
+}
+
+impl GasOracle {
+    /// Default multiplier applied to the latest base fee, covering its expected
+    /// growth over the next few blocks
+    pub const DEFAULT_BASE_FEE_MULTIPLIER: f64 = 2.0;
+
+    /// Suggest fees for `blockchain`, sampling the last 4 blocks of fee history and
+    /// doubling the base fee to cover its growth
+    pub fn new(blockchain: BlockchainClient) -> Self {
+        Self {
+            blockchain,
+            base_fee_multiplier: Self::DEFAULT_BASE_FEE_MULTIPLIER,
+            block_count: 4,
+        }
+    }
+
+    /// Override the multiplier applied to the latest base fee
+    pub fn with_base_fee_multiplier(mut self, multiplier: f64) -> Self {
+        self.base_fee_multiplier = multiplier;
+        self
+    }
+
+    /// Override how many recent blocks of fee history to sample
+    pub fn with_block_count(mut self, block_count: u64) -> Self {
+        self.block_count = block_count;
+        self
+    }
+
+    /// Suggest `maxFeePerGas`/`maxPriorityFeePerGas` for `strategy`, falling back to
+    /// the network's legacy `eth_gasPrice` (as `max_fee_per_gas` with no priority fee)
+    /// when `eth_feeHistory` isn't available
+    pub async fn suggest_fees(&self, strategy: FeeStrategy) -> Result<EvmFees> {
+        match self
+            .blockchain
+            .fee_history(self.block_count, strategy.reward_percentile())
+            .await
+        {
+            Ok(fees) => Ok(eip1559_fees_from_history(fees, self.base_fee_multiplier)),
+            Err(_) => {
+                let network_info = self.blockchain.get_network_info().await?;
+                let gas_price = u128::from_str_radix(
+                    network_info.gas_price.trim_start_matches("0x"),
+                    16,
+                )
+                .map_err(|_| X402Error::malformed_payload("gas_price"))?;
+                Ok(EvmFees {
+                    max_priority_fee_per_gas: 0,
+                    max_fee_per_gas: gas_price,
+                })
+            }
+        }
+    }
+
+    /// Estimate what settling `payload` on-chain would cost: the gas a
+    /// `transferWithAuthorization` call for its signed authorization is expected to
+    /// use, the [`FeeStrategy::Average`] fees it would pay per unit of gas, and the
+    /// resulting worst-case total in wei (`estimated_gas * max_fee_per_gas`). The
+    /// facilitator fronts this gas for the payer on a gasless EIP-3009 transfer, so
+    /// this lets it refuse to settle a payment whose gas cost would exceed a
+    /// configured ceiling instead of getting griefed during a fee spike — see
+    /// [`crate::real_facilitator::BlockchainFacilitatorConfig::max_settlement_fee`].
+    pub async fn estimate_settlement_cost(&self, payload: &PaymentPayload) -> Result<SettlementCost> {
+        let exact_evm = payload.exact_evm()?;
+        let auth = &exact_evm.authorization;
+        let signature: crate::crypto::signature::Signature = exact_evm.signature.parse()?;
+        let data = crate::erc20::transfer_with_authorization(auth, &signature)?;
+        let usdc_contract = self.blockchain.get_usdc_contract_address()?;
+
+        let estimated_gas = self
+            .blockchain
+            .estimate_gas(&TransactionRequest {
+                from: auth.from.clone(),
+                to: usdc_contract,
+                value: None,
+                data: Some(data),
+                gas: None,
+                gas_price: None,
+                max_fee_per_gas: None,
+                max_priority_fee_per_gas: None,
+            })
+            .await?;
+
+        let fees = self.suggest_fees(FeeStrategy::Average).await?;
+        Ok(SettlementCost::from_parts(estimated_gas, fees))
+    }
+}
+
+/// What settling a payment on-chain is expected to cost, in wei; see
+/// [`GasOracle::estimate_settlement_cost`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SettlementCost {
+    /// `maxFeePerGas` the settlement transaction would be built with
+    pub max_fee_per_gas: u128,
+    /// `maxPriorityFeePerGas` the settlement transaction would be built with
+    pub max_priority_fee_per_gas: u128,
+    /// Gas `eth_estimateGas` reported the `transferWithAuthorization` call would use
+    pub estimated_gas: u64,
+    /// Worst-case total cost in wei: `estimated_gas * max_fee_per_gas`
+    pub estimated_total_wei: u128,
+}
+
+impl SettlementCost {
+    /// Compute a [`SettlementCost`] from an already-fetched gas estimate and fee
+    /// suggestion, for callers that need [`GasOracle::estimate_settlement_cost`]'s
+    /// `estimated_gas * max_fee_per_gas` formula but have their own source for
+    /// `estimated_gas`/`fees` — e.g.
+    /// [`crate::real_facilitator::BlockchainFacilitatorClient::create_settlement_transaction`],
+    /// which fetches both through its configured [`crate::facilitator_middleware::FacilitatorMiddleware`]
+    /// stack rather than a plain [`BlockchainClient`]. Keeping the formula itself here,
+    /// shared by both callers, is what keeps them from silently drifting apart.
+    pub fn from_parts(estimated_gas: u64, fees: EvmFees) -> Self {
+        Self {
+            max_fee_per_gas: fees.max_fee_per_gas,
+            max_priority_fee_per_gas: fees.max_priority_fee_per_gas,
+            estimated_gas,
+            estimated_total_wei: (estimated_gas as u128) * fees.max_fee_per_gas,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fee_strategy_reward_percentiles() {
+        assert_eq!(FeeStrategy::Safe.reward_percentile(), 20.0);
+        assert_eq!(FeeStrategy::Average.reward_percentile(), 50.0);
+        assert_eq!(FeeStrategy::Fast.reward_percentile(), 80.0);
+    }
+
+    #[test]
+    fn test_eip1559_fees_from_history_doubles_base_fee_by_default() {
+        let fees = eip1559_fees_from_history(
+            FeeHistory {
+                base_fee_per_gas: 1_000_000_000,
+                max_priority_fee_per_gas: 100_000_000,
+            },
+            GasOracle::DEFAULT_BASE_FEE_MULTIPLIER,
+        );
+        assert_eq!(fees.max_priority_fee_per_gas, 100_000_000);
+        assert_eq!(fees.max_fee_per_gas, 2_100_000_000);
+    }
+
+    #[tokio::test]
+    async fn test_suggest_fees_uses_fee_history_when_available() {
+        let mut server = mockito::Server::new_async().await;
+        let _m = server
+            .mock("POST", "/")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "result": {
+                        "baseFeePerGas": ["0x3b9aca00"],
+                        "reward": [["0x5f5e100"]]
+                    }
+                })
+                .to_string(),
+            )
+            .create();
+
+        let oracle = GasOracle::new(BlockchainClient::new(server.url(), "base-sepolia".to_string()));
+        let fees = oracle.suggest_fees(FeeStrategy::Average).await.unwrap();
+
+        assert_eq!(fees.max_priority_fee_per_gas, 100_000_000);
+        assert_eq!(fees.max_fee_per_gas, 2_100_000_000);
+    }
+
+    #[tokio::test]
+    async fn test_suggest_fees_falls_back_to_legacy_gas_price_when_fee_history_is_malformed() {
+        let mut server = mockito::Server::new_async().await;
+        // `fee_history` sends a single (non-batched) request, matched by the first
+        // mock; its string `result` has no `baseFeePerGas` to find, so it errors and
+        // `suggest_fees` falls through to `get_network_info`, which batches its three
+        // calls into one array request, matched by the second mock below. Every
+        // entry's string `result` falls back to `0x0` rather than erroring.
+        let _m = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex(r"^\{".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": "0x0"}).to_string())
+            .create();
+        let _m2 = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex(r"^\[".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!([
+                    {"jsonrpc": "2.0", "id": 0, "result": "0x0"},
+                    {"jsonrpc": "2.0", "id": 1, "result": "0x0"},
+                    {"jsonrpc": "2.0", "id": 2, "result": "0x0"}
+                ])
+                .to_string(),
+            )
+            .create();
+
+        let oracle = GasOracle::new(BlockchainClient::new(server.url(), "base-sepolia".to_string()));
+        let fees = oracle.suggest_fees(FeeStrategy::Average).await.unwrap();
+
+        assert_eq!(fees, EvmFees { max_priority_fee_per_gas: 0, max_fee_per_gas: 0 });
+    }
+
+    #[tokio::test]
+    async fn test_estimate_settlement_cost_multiplies_gas_by_the_suggested_max_fee() {
+        let mut server = mockito::Server::new_async().await;
+        let _m_gas = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex("eth_estimateGas".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": "0x186a0"}).to_string())
+            .create();
+        let _m_fees = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex("eth_feeHistory".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "result": {"baseFeePerGas": ["0x3b9aca00"], "reward": [["0x5f5e100"]]}
+                })
+                .to_string(),
+            )
+            .create();
+
+        let authorization = crate::types::ExactEvmPayloadAuthorization::new(
+            "0x857b06519E91e3A54538791bDbb0E22373e36b66",
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            "1000000",
+            "1745323800",
+            "1745323985",
+            "0xf3746613c2d920b5fdabc0856f2aeb2d4f88ee6037b8cc5d04a71a4462f13480",
+        );
+        let payload = crate::types::ExactEvmPayload {
+            signature: format!("0x{}{}{}", "11".repeat(32), "22".repeat(32), "1c"),
+            authorization,
+        };
+        let payment_payload = PaymentPayload::new("exact", "base-sepolia", payload);
+
+        let oracle = GasOracle::new(BlockchainClient::new(server.url(), "base-sepolia".to_string()));
+        let cost = oracle.estimate_settlement_cost(&payment_payload).await.unwrap();
+
+        assert_eq!(cost.estimated_gas, 100_000);
+        assert_eq!(cost.max_priority_fee_per_gas, 100_000_000);
+        assert_eq!(cost.max_fee_per_gas, 2_100_000_000);
+        assert_eq!(cost.estimated_total_wei, 100_000 * 2_100_000_000);
+    }
+
+    #[test]
+    fn test_settlement_cost_from_parts_matches_estimate_settlement_cost_formula() {
+        let fees = EvmFees {
+            max_priority_fee_per_gas: 100_000_000,
+            max_fee_per_gas: 2_100_000_000,
+        };
+        let cost = SettlementCost::from_parts(100_000, fees);
+
+        assert_eq!(cost.estimated_gas, 100_000);
+        assert_eq!(cost.max_fee_per_gas, fees.max_fee_per_gas);
+        assert_eq!(cost.max_priority_fee_per_gas, fees.max_priority_fee_per_gas);
+        assert_eq!(cost.estimated_total_wei, 100_000 * 2_100_000_000);
+    }
+}
@@ -0,0 +1,319 @@
+//! HTTP Signatures for authenticating outbound facilitator requests
+//!
+//! Implements the draft-cavage/RFC 9421 "Signature" scheme so a facilitator can
+//! verify that verify/settle calls genuinely came from a registered resource server,
+//! and so the request body can't be tampered with in transit. Key handling is left to
+//! the caller (via a signer closure), the same way `http-signature-normalization`
+//! leaves it to its caller.
+
+use crate::{Result, X402Error};
+use base64::{engine::general_purpose, Engine as _};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Signs a canonical signing string and returns the raw signature bytes
+pub type SignerFn = dyn Fn(&str) -> Result<Vec<u8>> + Send + Sync;
+
+/// Configuration for signing outbound facilitator requests with HTTP Signatures
+#[derive(Clone)]
+pub struct HttpSignatureConfig {
+    /// Identifier for the key used to sign, sent as the `keyId` parameter
+    pub key_id: String,
+    /// Algorithm name sent as the `algorithm` parameter (e.g. "hmac-sha256", "ecdsa-p256-sha256")
+    pub algorithm: String,
+    /// Closure that signs the canonical signing string
+    pub signer: Arc<SignerFn>,
+    /// How long the signature should remain valid, used to compute `expires`
+    pub validity: Duration,
+}
+
+impl std::fmt::Debug for HttpSignatureConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HttpSignatureConfig")
+            .field("key_id", &self.key_id)
+            .field("algorithm", &self.algorithm)
+            .field("signer", &"<function>")
+            .field("validity", &self.validity)
+            .finish()
+    }
+}
+
+impl HttpSignatureConfig {
+    /// Create a new HTTP Signature config with a one-minute default validity window
+    pub fn new(
+        key_id: impl Into<String>,
+        algorithm: impl Into<String>,
+        signer: impl Fn(&str) -> Result<Vec<u8>> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            key_id: key_id.into(),
+            algorithm: algorithm.into(),
+            signer: Arc::new(signer),
+            validity: Duration::from_secs(60),
+        }
+    }
+
+    /// Set the signature validity window, used to bound the replay window via `expires`
+    pub fn with_validity(mut self, validity: Duration) -> Self {
+        self.validity = validity;
+        self
+    }
+}
+
+/// The components canonicalized into the signing string, in order
+const SIGNED_HEADERS: &str = "(request-target) host date digest";
+
+/// Compute the `Digest` header value for a request body: `SHA-256=<base64(sha256(body))>`
+pub fn compute_digest(body: &[u8]) -> String {
+    let hash = Sha256::digest(body);
+    format!("SHA-256={}", general_purpose::STANDARD.encode(hash))
+}
+
+/// Build the canonical signing string: one `name: value` line per signed component,
+/// joined by `\n`, in the order declared by [`SIGNED_HEADERS`]
+pub fn build_signing_string(method: &str, path: &str, host: &str, date: &str, digest: &str) -> String {
+    format!(
+        "(request-target): {} {}\nhost: {}\ndate: {}\ndigest: {}",
+        method.to_lowercase(),
+        path,
+        host,
+        date,
+        digest
+    )
+}
+
+/// Sign a request and produce the value of the `Signature` header
+///
+/// `created`/`expires` are Unix timestamps bounding the replay window; callers
+/// typically set `created` to now and `expires` to `now + config.validity`.
+pub fn sign_request(
+    config: &HttpSignatureConfig,
+    method: &str,
+    path: &str,
+    host: &str,
+    date: &str,
+    body: &[u8],
+    created: i64,
+    expires: i64,
+) -> Result<String> {
+    let digest = compute_digest(body);
+    let signing_string = build_signing_string(method, path, host, date, &digest);
+
+    let signature_bytes = (config.signer)(&signing_string)?;
+    let signature_b64 = general_purpose::STANDARD.encode(signature_bytes);
+
+    Ok(format!(
+        r#"keyId="{}",algorithm="{}",created={},expires={},headers="{}",signature="{}""#,
+        config.key_id, config.algorithm, created, expires, SIGNED_HEADERS, signature_b64
+    ))
+}
+
+/// A parsed `Signature` header, as reconstructed by a facilitator-side verifier
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsedSignature {
+    pub key_id: String,
+    pub algorithm: String,
+    pub created: i64,
+    pub expires: i64,
+    pub headers: String,
+    pub signature: String,
+}
+
+/// Parse a `Signature` header value into its components
+pub fn parse_signature_header(header: &str) -> Result<ParsedSignature> {
+    let mut key_id = None;
+    let mut algorithm = None;
+    let mut created = None;
+    let mut expires = None;
+    let mut headers = None;
+    let mut signature = None;
+
+    for part in split_signature_params(header) {
+        let (name, value) = part
+            .split_once('=')
+            .ok_or_else(|| X402Error::invalid_signature("Malformed Signature header parameter"))?;
+        let value = value.trim().trim_matches('"');
+        match name.trim() {
+            "keyId" => key_id = Some(value.to_string()),
+            "algorithm" => algorithm = Some(value.to_string()),
+            "created" => {
+                created = Some(value.parse().map_err(|_| {
+                    X402Error::invalid_signature("Invalid created timestamp in Signature header")
+                })?)
+            }
+            "expires" => {
+                expires = Some(value.parse().map_err(|_| {
+                    X402Error::invalid_signature("Invalid expires timestamp in Signature header")
+                })?)
+            }
+            "headers" => headers = Some(value.to_string()),
+            "signature" => signature = Some(value.to_string()),
+            _ => {}
+        }
+    }
+
+    Ok(ParsedSignature {
+        key_id: key_id.ok_or_else(|| X402Error::invalid_signature("Missing keyId"))?,
+        algorithm: algorithm.ok_or_else(|| X402Error::invalid_signature("Missing algorithm"))?,
+        created: created.ok_or_else(|| X402Error::invalid_signature("Missing created"))?,
+        expires: expires.ok_or_else(|| X402Error::invalid_signature("Missing expires"))?,
+        headers: headers.ok_or_else(|| X402Error::invalid_signature("Missing headers"))?,
+        signature: signature.ok_or_else(|| X402Error::invalid_signature("Missing signature"))?,
+    })
+}
+
+/// Reconstruct the canonical signing string a facilitator should verify against, and
+/// check the parsed signature's replay window (`created`/`expires`) against `now`
+pub fn verify_replay_window(parsed: &ParsedSignature, now: i64) -> Result<()> {
+    if now < parsed.created {
+        return Err(X402Error::invalid_signature("Signature created in the future"));
+    }
+    if now > parsed.expires {
+        return Err(X402Error::invalid_signature("Signature has expired"));
+    }
+    Ok(())
+}
+
+/// Reconstruct the canonical signing string a facilitator received and verify it against
+/// the parsed `Signature` header using `verifier`, which receives the signing string and
+/// the decoded signature bytes and returns whether they match. Also enforces the replay
+/// window via [`verify_replay_window`].
+pub fn verify_signed_request(
+    parsed: &ParsedSignature,
+    method: &str,
+    path: &str,
+    host: &str,
+    date: &str,
+    body: &[u8],
+    now: i64,
+    verifier: impl Fn(&str, &[u8]) -> bool,
+) -> Result<()> {
+    verify_replay_window(parsed, now)?;
+
+    let digest = compute_digest(body);
+    let signing_string = build_signing_string(method, path, host, date, &digest);
+
+    let signature_bytes = general_purpose::STANDARD
+        .decode(&parsed.signature)
+        .map_err(|_| X402Error::invalid_signature("Signature is not valid base64"))?;
+
+    if !verifier(&signing_string, &signature_bytes) {
+        return Err(X402Error::invalid_signature(
+            "Signature does not match the reconstructed signing string",
+        ));
+    }
+
+    Ok(())
+}
+
+fn split_signature_params(header: &str) -> Vec<&str> {
+    // Parameters are comma-separated, but signature/base64 values never contain commas
+    // that aren't already inside the quoted value, so a naive split is safe here.
+    header.split(',').map(|s| s.trim()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_config() -> HttpSignatureConfig {
+        HttpSignatureConfig::new("test-key", "hmac-sha256", |signing_string| {
+            Ok(Sha256::digest(signing_string.as_bytes()).to_vec())
+        })
+    }
+
+    #[test]
+    fn test_compute_digest_matches_known_value() {
+        let digest = compute_digest(b"{}");
+        assert!(digest.starts_with("SHA-256="));
+    }
+
+    #[test]
+    fn test_sign_request_includes_expected_parameters() {
+        let config = test_config();
+        let header = sign_request(
+            &config,
+            "POST",
+            "/verify",
+            "x402.org",
+            "Tue, 07 Jun 2014 20:51:35 GMT",
+            b"{}",
+            1000,
+            1060,
+        )
+        .unwrap();
+
+        assert!(header.contains(r#"keyId="test-key""#));
+        assert!(header.contains(r#"algorithm="hmac-sha256""#));
+        assert!(header.contains("created=1000"));
+        assert!(header.contains("expires=1060"));
+        assert!(header.contains(r#"headers="(request-target) host date digest""#));
+    }
+
+    #[test]
+    fn test_parse_signature_header_roundtrip() {
+        let config = test_config();
+        let header = sign_request(&config, "POST", "/settle", "x402.org", "date", b"{}", 1, 61).unwrap();
+
+        let parsed = parse_signature_header(&header).unwrap();
+        assert_eq!(parsed.key_id, "test-key");
+        assert_eq!(parsed.algorithm, "hmac-sha256");
+        assert_eq!(parsed.created, 1);
+        assert_eq!(parsed.expires, 61);
+        assert_eq!(parsed.headers, "(request-target) host date digest");
+    }
+
+    #[test]
+    fn test_verify_signed_request_roundtrip() {
+        let config = test_config();
+        let body = b"{\"x402Version\":1}";
+        let header = sign_request(&config, "POST", "/verify", "x402.org", "date", body, 1, 61).unwrap();
+        let parsed = parse_signature_header(&header).unwrap();
+
+        let verifier = |signing_string: &str, signature: &[u8]| {
+            Sha256::digest(signing_string.as_bytes()).as_slice() == signature
+        };
+
+        assert!(
+            verify_signed_request(&parsed, "POST", "/verify", "x402.org", "date", body, 30, verifier)
+                .is_ok()
+        );
+
+        // A tampered body changes the digest, so the reconstructed signing string no
+        // longer matches the signature
+        assert!(verify_signed_request(
+            &parsed,
+            "POST",
+            "/verify",
+            "x402.org",
+            "date",
+            b"{\"tampered\":true}",
+            30,
+            verifier
+        )
+        .is_err());
+
+        // Outside the replay window
+        assert!(
+            verify_signed_request(&parsed, "POST", "/verify", "x402.org", "date", body, 100, verifier)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_verify_replay_window_rejects_expired_signature() {
+        let parsed = ParsedSignature {
+            key_id: "k".into(),
+            algorithm: "hmac-sha256".into(),
+            created: 1,
+            expires: 61,
+            headers: SIGNED_HEADERS.into(),
+            signature: "sig".into(),
+        };
+
+        assert!(verify_replay_window(&parsed, 30).is_ok());
+        assert!(verify_replay_window(&parsed, 100).is_err());
+        assert!(verify_replay_window(&parsed, 0).is_err());
+    }
+}
@@ -0,0 +1,642 @@
+//! Idempotent settlement keyed by a payment's EIP-3009 authorization
+//!
+//! [`crate::facilitator::FacilitatorClient::settle`] can now be retried
+//! ([`crate::retry`]), and clients legitimately resubmit the same `X-PAYMENT` header,
+//! so the same authorized transfer must never be settled twice. A [`PaymentId`]
+//! derived from the authorization's `nonce` + `from` + `to` + `value`, folded
+//! together with the payment requirements' `network` + `asset` + `resource`,
+//! identifies a settlement attempt, and an [`IdempotencyStore`] remembers its outcome
+//! so a repeat `settle` call for the same id returns the prior result instead of
+//! re-posting. The requirements component matters as much as the authorization one:
+//! without it, two unrelated payments that happen to share a nonce/from/to/value
+//! (e.g. the same payer paying the same amount to the same relayer address on two
+//! different resources or networks) would collide on the same id and the second
+//! would silently be treated as already settled by the first.
+//!
+//! Modeled on rust-lightning's `PaymentId`/idempotency-timeout pattern: entries are
+//! marked in-flight while a settlement is pending so concurrent callers with the same
+//! nonce coalesce onto the same result rather than racing the facilitator, and expire
+//! after a configurable timeout so a permanently stuck in-flight entry doesn't wedge
+//! the id forever.
+
+use crate::types::{ExactEvmPayloadAuthorization, PaymentRequirements, SettleResponse};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{Mutex, Notify};
+
+/// A boxed, `Send` future, used in place of `async fn` in [`IdempotencyStore`] since
+/// traits can't have object-safe async methods on stable Rust without an extra
+/// proc-macro crate this workspace doesn't otherwise depend on
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Stable identifier for a settlement attempt, derived from the payment authorization
+/// so retries and resubmissions of the same authorized transfer collide on purpose
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PaymentId(pub [u8; 32]);
+
+impl PaymentId {
+    /// Derive a [`PaymentId`] from an EIP-3009 authorization's `nonce`, `from`, `to`
+    /// and `value`, scoped to the `network`, `asset` and `resource` being paid for.
+    /// The requirements component is what keeps two unrelated payments that happen to
+    /// share a nonce/from/to/value — e.g. the same payer paying the same amount to the
+    /// same address on two different resources — from colliding on the same id.
+    pub fn from_authorization(
+        authorization: &ExactEvmPayloadAuthorization,
+        payment_requirements: &PaymentRequirements,
+    ) -> Self {
+        let mut hasher = Sha256::new();
+        hasher.update(authorization.nonce.as_bytes());
+        hasher.update(authorization.from.as_bytes());
+        hasher.update(authorization.to.as_bytes());
+        hasher.update(authorization.value.as_bytes());
+        hasher.update(payment_requirements.network.as_bytes());
+        hasher.update(payment_requirements.asset.as_bytes());
+        hasher.update(payment_requirements.resource.as_bytes());
+        Self(hasher.finalize().into())
+    }
+}
+
+impl std::fmt::Display for PaymentId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", hex::encode(self.0))
+    }
+}
+
+/// An entry in an [`IdempotencyStore`]
+#[derive(Debug, Clone)]
+enum Entry {
+    /// A settlement for this id has been claimed by a caller and hasn't completed or
+    /// been abandoned yet; `attempts` counts every [`IdempotencyStore::begin`] call
+    /// that has claimed it so far, including the current one when `running`.
+    /// `running` distinguishes a settle call actually in flight right now (other
+    /// callers with the same id block on it via [`Notify`]) from one that failed and
+    /// is merely waiting to be retried by whichever caller calls `begin` next.
+    InFlight {
+        first_seen: Instant,
+        attempts: u32,
+        running: bool,
+    },
+    /// A settlement for this id completed with the given result
+    Completed {
+        result: SettleResponse,
+        completed_at: Instant,
+    },
+    /// Settlement for this id was permanently given up on after
+    /// [`IdempotencyStore::record_failure`] saw it fail `attempts` times in a row;
+    /// terminal, like [`Self::Completed`] — this id is never retried again
+    Abandoned {
+        reason: String,
+        attempts: u32,
+        abandoned_at: Instant,
+    },
+}
+
+/// Storage backend for settlement idempotency
+///
+/// The default [`InMemoryIdempotencyStore`] is fine for a single process; implement
+/// this trait to back it with Redis or another shared store when settlement is
+/// retried from more than one instance.
+pub trait IdempotencyStore: Send + Sync {
+    /// Atomically check the id's state and, if absent, mark it in-flight
+    ///
+    /// Returns the prior state if one existed (`Completed` or `InFlight`), or `None`
+    /// if this call is the one that just claimed the id.
+    fn begin(&self, id: PaymentId) -> BoxFuture<'_, Option<IdempotencyState>>;
+
+    /// Record the completed result for `id`
+    fn complete(&self, id: PaymentId, result: SettleResponse) -> BoxFuture<'_, ()>;
+
+    /// Remove `id`'s entry, e.g. after an in-flight settlement failed outright and
+    /// should be retried from scratch rather than wedged as in-flight forever
+    fn clear(&self, id: PaymentId) -> BoxFuture<'_, ()>;
+
+    /// Record that the in-flight attempt for `id` just failed with `reason`. If this
+    /// was the `max_attempts`th attempt, the id transitions to
+    /// [`IdempotencyState::Abandoned`] permanently; otherwise it stays
+    /// [`IdempotencyState::InFlight`] with its attempt count incremented, so a later
+    /// `settle` call for the same id tries again instead of posting a fresh attempt
+    /// count of one.
+    fn record_failure<'a>(
+        &'a self,
+        id: PaymentId,
+        reason: String,
+        max_attempts: u32,
+    ) -> BoxFuture<'a, IdempotencyState>;
+}
+
+/// The state an [`IdempotencyStore`] reports for a [`PaymentId`] already seen
+#[derive(Debug, Clone)]
+pub enum IdempotencyState {
+    /// A settlement for this id is already in flight, or failed fewer than its
+    /// configured `max_attempts` times and is eligible to be retried
+    InFlight { attempts: u32 },
+    /// A settlement for this id already completed, with this result
+    Completed(SettleResponse),
+    /// Settlement for this id was permanently abandoned after exhausting its attempt
+    /// budget; callers should surface [`crate::X402Error::SettlementAbandoned`]
+    /// instead of retrying
+    Abandoned { reason: String, attempts: u32 },
+}
+
+/// In-memory [`IdempotencyStore`], suitable for a single-process deployment
+///
+/// Entries older than `timeout` are treated as expired and evicted lazily on next
+/// access, matching rust-lightning's `IDEMPOTENCY_TIMEOUT_TICKS` approach of aging
+/// entries out rather than tracking them with a background sweep.
+pub struct InMemoryIdempotencyStore {
+    entries: Mutex<HashMap<PaymentId, Entry>>,
+    notify: Notify,
+    timeout: Duration,
+}
+
+impl InMemoryIdempotencyStore {
+    /// Create a store that expires entries after `timeout`
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+            notify: Notify::new(),
+            timeout,
+        }
+    }
+
+    fn is_expired(&self, entry: &Entry) -> bool {
+        let age = match entry {
+            // A terminal entry never expires: once abandoned, always abandoned, so a
+            // resubmission of the same authorization keeps getting the same answer
+            // instead of silently getting a fresh attempt budget.
+            Entry::Abandoned { .. } => return false,
+            Entry::InFlight { first_seen, .. } => first_seen.elapsed(),
+            Entry::Completed { completed_at, .. } => completed_at.elapsed(),
+        };
+        age > self.timeout
+    }
+}
+
+impl Default for InMemoryIdempotencyStore {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(300))
+    }
+}
+
+impl InMemoryIdempotencyStore {
+    async fn begin_inner(&self, id: PaymentId) -> Option<IdempotencyState> {
+        loop {
+            let mut entries = self.entries.lock().await;
+            match entries.get(&id) {
+                Some(entry) if self.is_expired(entry) => {
+                    entries.remove(&id);
+                    continue;
+                }
+                Some(Entry::Completed { result, .. }) => {
+                    return Some(IdempotencyState::Completed(result.clone()))
+                }
+                Some(Entry::Abandoned { reason, attempts, .. }) => {
+                    return Some(IdempotencyState::Abandoned {
+                        reason: reason.clone(),
+                        attempts: *attempts,
+                    })
+                }
+                Some(Entry::InFlight { running: true, .. }) => {
+                    drop(entries);
+                    self.notify.notified().await;
+                    continue;
+                }
+                Some(Entry::InFlight { running: false, first_seen, attempts }) => {
+                    let first_seen = *first_seen;
+                    let attempts = *attempts + 1;
+                    entries.insert(
+                        id,
+                        Entry::InFlight { first_seen, attempts, running: true },
+                    );
+                    return None;
+                }
+                None => {
+                    entries.insert(
+                        id,
+                        Entry::InFlight {
+                            first_seen: Instant::now(),
+                            attempts: 1,
+                            running: true,
+                        },
+                    );
+                    return None;
+                }
+            }
+        }
+    }
+
+    async fn complete_inner(&self, id: PaymentId, result: SettleResponse) {
+        self.entries.lock().await.insert(
+            id,
+            Entry::Completed {
+                result,
+                completed_at: Instant::now(),
+            },
+        );
+        self.notify.notify_waiters();
+    }
+
+    async fn clear_inner(&self, id: PaymentId) {
+        self.entries.lock().await.remove(&id);
+        self.notify.notify_waiters();
+    }
+
+    async fn record_failure_inner(
+        &self,
+        id: PaymentId,
+        reason: String,
+        max_attempts: u32,
+    ) -> IdempotencyState {
+        let mut entries = self.entries.lock().await;
+        let attempts = match entries.get(&id) {
+            Some(Entry::InFlight { attempts, .. }) => *attempts,
+            // Already resolved (completed/abandoned), or never begun; nothing for this
+            // call to record a failure against. Report it as abandoned on the spot
+            // rather than silently letting a caller who raced a `complete`/`clear`
+            // think there's still a pending attempt to retry.
+            _ => {
+                let state = IdempotencyState::Abandoned {
+                    reason: reason.clone(),
+                    attempts: max_attempts.max(1),
+                };
+                entries.insert(
+                    id,
+                    Entry::Abandoned {
+                        reason,
+                        attempts: max_attempts.max(1),
+                        abandoned_at: Instant::now(),
+                    },
+                );
+                drop(entries);
+                self.notify.notify_waiters();
+                return state;
+            }
+        };
+
+        let state = if attempts >= max_attempts {
+            entries.insert(
+                id,
+                Entry::Abandoned {
+                    reason: reason.clone(),
+                    attempts,
+                    abandoned_at: Instant::now(),
+                },
+            );
+            IdempotencyState::Abandoned { reason, attempts }
+        } else {
+            let Some(Entry::InFlight { first_seen, .. }) = entries.get(&id) else {
+                unreachable!("checked above")
+            };
+            let first_seen = *first_seen;
+            entries.insert(
+                id,
+                Entry::InFlight {
+                    first_seen,
+                    attempts,
+                    running: false,
+                },
+            );
+            IdempotencyState::InFlight { attempts }
+        };
+
+        drop(entries);
+        self.notify.notify_waiters();
+        state
+    }
+}
+
+impl IdempotencyStore for InMemoryIdempotencyStore {
+    fn begin(&self, id: PaymentId) -> BoxFuture<'_, Option<IdempotencyState>> {
+        Box::pin(self.begin_inner(id))
+    }
+
+    fn record_failure<'a>(
+        &'a self,
+        id: PaymentId,
+        reason: String,
+        max_attempts: u32,
+    ) -> BoxFuture<'a, IdempotencyState> {
+        Box::pin(self.record_failure_inner(id, reason, max_attempts))
+    }
+
+    fn complete(&self, id: PaymentId, result: SettleResponse) -> BoxFuture<'_, ()> {
+        Box::pin(self.complete_inner(id, result))
+    }
+
+    fn clear(&self, id: PaymentId) -> BoxFuture<'_, ()> {
+        Box::pin(self.clear_inner(id))
+    }
+}
+
+/// Default number of attempts [`IdempotentSettlement`] allows across repeated
+/// `settle` calls for the same [`PaymentId`] before abandoning it; see
+/// [`IdempotentSettlement::with_max_attempts`]
+pub const DEFAULT_MAX_SETTLEMENT_ATTEMPTS: u32 = 5;
+
+/// Settles a payment exactly once per [`PaymentId`], regardless of how many times
+/// `settle` is retried or the authorized transfer is resubmitted
+///
+/// `facilitator` is any [`crate::facilitator::Facilitator`] backend, not just a plain
+/// [`crate::facilitator::FacilitatorClient`] — wrapping a
+/// [`crate::facilitator::RetryableFacilitator`] here is how a retried settle call stays
+/// idempotent-safe: every retry of the same payment collides on the same
+/// [`PaymentId`] and only the first actually reaches the facilitator.
+///
+/// Beyond that single-call retry, this also tracks a payment as a small state machine
+/// across separate `settle` calls — mirroring rust-lightning's outbound payment
+/// tracking — so a caller that keeps resubmitting the same authorization after a
+/// transient failure (rather than the facilitator being retried within one call)
+/// still converges: each failed attempt is recorded via [`IdempotencyStore::record_failure`],
+/// and once `max_attempts` is reached the id is marked
+/// [`IdempotencyState::Abandoned`] and every subsequent `settle` call for it fails
+/// fast with [`crate::X402Error::SettlementAbandoned`] instead of posting another
+/// attempt.
+pub struct IdempotentSettlement {
+    facilitator: Arc<dyn crate::facilitator::Facilitator>,
+    store: Arc<dyn IdempotencyStore>,
+    max_attempts: u32,
+}
+
+impl IdempotentSettlement {
+    /// Wrap `facilitator`, storing idempotency state in `store`, abandoning a payment
+    /// after [`DEFAULT_MAX_SETTLEMENT_ATTEMPTS`] failed attempts
+    pub fn new(facilitator: Arc<dyn crate::facilitator::Facilitator>, store: Arc<dyn IdempotencyStore>) -> Self {
+        Self {
+            facilitator,
+            store,
+            max_attempts: DEFAULT_MAX_SETTLEMENT_ATTEMPTS,
+        }
+    }
+
+    /// Override how many attempts across separate `settle` calls a payment gets
+    /// before it's abandoned
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Settle `payment_payload`, returning the previously completed result instead of
+    /// re-posting if this authorization was already settled, and coalescing with any
+    /// settlement currently in flight for the same id
+    ///
+    /// Returns [`crate::X402Error::SettlementAbandoned`] without calling the
+    /// facilitator at all once this id has failed `max_attempts` times (whether
+    /// across retries of this call or separate resubmissions of the same
+    /// authorization) — the caller should treat that as terminal, not retry again.
+    pub async fn settle(
+        &self,
+        payment_payload: &crate::types::PaymentPayload,
+        payment_requirements: &crate::types::PaymentRequirements,
+    ) -> crate::Result<SettleResponse> {
+        let id =
+            PaymentId::from_authorization(&payment_payload.exact_evm()?.authorization, payment_requirements);
+
+        // `begin` claims the id (returning `None`) if it's new or eligible for
+        // another attempt, blocks until an in-flight settlement resolves, or hands
+        // back an already-completed or already-abandoned result.
+        match self.store.begin(id).await {
+            Some(IdempotencyState::Completed(result)) => return Ok(result),
+            Some(IdempotencyState::Abandoned { reason, attempts }) => {
+                return Err(crate::X402Error::settlement_abandoned(id.to_string(), attempts, reason));
+            }
+            Some(IdempotencyState::InFlight { .. }) | None => {}
+        }
+
+        match self
+            .facilitator
+            .settle(payment_payload, payment_requirements)
+            .await
+        {
+            Ok(result) => {
+                self.store.complete(id, result.clone()).await;
+                Ok(result)
+            }
+            Err(error) => {
+                match self
+                    .store
+                    .record_failure(id, error.to_string(), self.max_attempts)
+                    .await
+                {
+                    IdempotencyState::Abandoned { reason, attempts } => Err(
+                        crate::X402Error::settlement_abandoned(id.to_string(), attempts, reason),
+                    ),
+                    _ => Err(error),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{ExactEvmPayload, ExactEvmPayloadAuthorization, FacilitatorConfig, PaymentPayload, PaymentRequirements};
+    use mockito::Server;
+    use serde_json::json;
+
+    fn test_authorization() -> ExactEvmPayloadAuthorization {
+        ExactEvmPayloadAuthorization::new(
+            "0x857b06519E91e3A54538791bDbb0E22373e36b66",
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            "1000000",
+            "1745323800",
+            "1745323985",
+            "0xf3746613c2d920b5fdabc0856f2aeb2d4f88ee6037b8cc5d04a71a4462f13480",
+        )
+    }
+
+    fn test_payment_payload() -> PaymentPayload {
+        let payload = ExactEvmPayload {
+            signature: "0xsignature".to_string(),
+            authorization: test_authorization(),
+        };
+        PaymentPayload::new("exact", "base-sepolia", payload)
+    }
+
+    fn test_payment_requirements() -> PaymentRequirements {
+        PaymentRequirements::new(
+            "exact",
+            "base-sepolia",
+            "1000000",
+            "0x036CbD53842c5426634e7929541eC2318f3dCF7e",
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            "https://example.com/test",
+            "Test payment",
+        )
+    }
+
+    #[test]
+    fn test_payment_id_stable_for_same_authorization() {
+        let requirements = test_payment_requirements();
+        let a = PaymentId::from_authorization(&test_authorization(), &requirements);
+        let b = PaymentId::from_authorization(&test_authorization(), &requirements);
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_payment_id_differs_for_different_nonce() {
+        let requirements = test_payment_requirements();
+        let mut other = test_authorization();
+        other.nonce = "0xdifferentnonce".to_string();
+        assert_ne!(
+            PaymentId::from_authorization(&test_authorization(), &requirements),
+            PaymentId::from_authorization(&other, &requirements)
+        );
+    }
+
+    #[test]
+    fn test_payment_id_differs_for_different_requirements() {
+        let authorization = test_authorization();
+        let mut other_requirements = test_payment_requirements();
+        other_requirements.resource = "https://example.com/a-different-resource".to_string();
+        assert_ne!(
+            PaymentId::from_authorization(&authorization, &test_payment_requirements()),
+            PaymentId::from_authorization(&authorization, &other_requirements)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_idempotent_settlement_settles_once_for_repeated_calls() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/settle")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"success": true, "transaction": "0xabc", "network": "base-sepolia"}).to_string())
+            .expect(1)
+            .create();
+
+        let facilitator = crate::facilitator::FacilitatorClient::new(FacilitatorConfig::new(server.url())).unwrap();
+        let settlement = IdempotentSettlement::new(
+            Arc::new(facilitator),
+            Arc::new(InMemoryIdempotencyStore::new(Duration::from_secs(60))),
+        );
+
+        let payment_payload = test_payment_payload();
+        let payment_requirements = test_payment_requirements();
+
+        let first = settlement
+            .settle(&payment_payload, &payment_requirements)
+            .await
+            .unwrap();
+        let second = settlement
+            .settle(&payment_payload, &payment_requirements)
+            .await
+            .unwrap();
+
+        assert_eq!(first.transaction, second.transaction);
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_idempotent_settlement_retries_after_failed_attempt() {
+        let mut server = Server::new_async().await;
+        let failing_mock = server.mock("POST", "/settle").with_status(500).expect(1).create();
+        let ok_mock = server
+            .mock("POST", "/settle")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"success": true, "transaction": "0xabc", "network": "base-sepolia"}).to_string())
+            .expect(1)
+            .create();
+
+        let facilitator = crate::facilitator::FacilitatorClient::new(FacilitatorConfig::new(server.url())).unwrap();
+        let settlement = IdempotentSettlement::new(
+            Arc::new(facilitator),
+            Arc::new(InMemoryIdempotencyStore::new(Duration::from_secs(60))),
+        );
+
+        let payment_payload = test_payment_payload();
+        let payment_requirements = test_payment_requirements();
+
+        let first = settlement
+            .settle(&payment_payload, &payment_requirements)
+            .await;
+        assert!(first.is_err());
+
+        let second = settlement
+            .settle(&payment_payload, &payment_requirements)
+            .await;
+        assert!(second.is_ok());
+
+        failing_mock.assert();
+        ok_mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_idempotent_settlement_over_retryable_facilitator_settles_once() {
+        // A settle retried internally by a RetryableFacilitator (one 503 then success)
+        // must still only post once from the caller's point of view when wrapped in
+        // IdempotentSettlement — the retry happens *inside* one `settle` call here, so
+        // this only exercises that the facilitator field accepts a non-FacilitatorClient
+        // Facilitator impl; the repeated-call collapsing is covered by the test above.
+        let mut server = Server::new_async().await;
+        let failing_mock = server.mock("POST", "/settle").with_status(503).expect(1).create();
+        let ok_mock = server
+            .mock("POST", "/settle")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"success": true, "transaction": "0xabc", "network": "base-sepolia"}).to_string())
+            .expect(1)
+            .create();
+
+        let facilitator = crate::facilitator::FacilitatorClient::new(FacilitatorConfig::new(server.url())).unwrap();
+        let retryable = crate::facilitator::RetryableFacilitator::new(facilitator).with_policy(
+            crate::facilitator::RetryableFacilitatorPolicy::new()
+                .with_base_delay(Duration::from_millis(1))
+                .with_max_attempts(3),
+        );
+        let settlement = IdempotentSettlement::new(
+            Arc::new(retryable),
+            Arc::new(InMemoryIdempotencyStore::new(Duration::from_secs(60))),
+        );
+
+        let result = settlement
+            .settle(&test_payment_payload(), &test_payment_requirements())
+            .await
+            .unwrap();
+
+        assert_eq!(result.transaction, "0xabc");
+        failing_mock.assert();
+        ok_mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_idempotent_settlement_abandons_after_max_attempts() {
+        let mut server = Server::new_async().await;
+        let failing_mock = server.mock("POST", "/settle").with_status(500).expect(2).create();
+
+        let facilitator = crate::facilitator::FacilitatorClient::new(FacilitatorConfig::new(server.url())).unwrap();
+        let settlement = IdempotentSettlement::new(
+            Arc::new(facilitator),
+            Arc::new(InMemoryIdempotencyStore::new(Duration::from_secs(60))),
+        )
+        .with_max_attempts(2);
+
+        let payment_payload = test_payment_payload();
+        let payment_requirements = test_payment_requirements();
+
+        let first = settlement.settle(&payment_payload, &payment_requirements).await;
+        assert!(matches!(first, Err(crate::X402Error::FacilitatorError { .. })));
+
+        let second = settlement.settle(&payment_payload, &payment_requirements).await;
+        assert!(matches!(
+            second,
+            Err(crate::X402Error::SettlementAbandoned { attempts: 2, .. })
+        ));
+
+        // A third call doesn't even reach the facilitator - the mock's expect(2) above
+        // would fail this test on drop if it did.
+        let third = settlement.settle(&payment_payload, &payment_requirements).await;
+        assert!(matches!(
+            third,
+            Err(crate::X402Error::SettlementAbandoned { attempts: 2, .. })
+        ));
+
+        failing_mock.assert();
+    }
+}
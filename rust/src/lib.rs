@@ -4,14 +4,49 @@
 //! This library provides the core types, client, and middleware for implementing
 //! payment-protected HTTP resources.
 
+pub mod accounting;
+pub mod async_settlement;
+pub mod blockchain;
+pub mod blockchain_middleware;
 pub mod client;
+pub mod client_middleware;
 pub mod crypto;
+pub mod erc20;
 pub mod error;
 pub mod facilitator;
+pub mod facilitator_middleware;
+pub mod gas_oracle;
+pub mod http_signature;
+pub mod idempotency;
+pub mod lightning;
 pub mod middleware;
+pub mod network_registry;
+pub mod nonce_manager;
+pub mod nonce_store;
+pub mod onchain_verification;
+pub mod payment_events;
+pub mod payment_lifecycle;
+pub mod pricing;
 pub mod proxy;
+pub mod real_facilitator;
+pub mod retry;
+pub mod scheme;
+pub mod scheme_registry;
+pub mod scheme_wasm;
+pub mod settlement;
+pub mod settlement_confirmation;
+pub mod settlement_receipt_check;
+pub mod settlement_scheduler;
+pub mod settlement_status;
+pub mod settlement_verifier;
+pub mod solana_facilitator;
+pub mod static_server;
 pub mod template;
+pub mod token_registry;
 pub mod types;
+pub mod wallet;
+pub mod wallet_auth;
+pub mod webhook;
 
 // Re-exports for convenience
 pub use client::X402Client;
@@ -28,6 +63,12 @@ pub mod actix_web;
 #[cfg(feature = "warp")]
 pub mod warp;
 
+#[cfg(feature = "sqlite")]
+pub mod discovery_store;
+
+#[cfg(feature = "test_support")]
+pub mod test_support;
+
 /// Current version of the x402 library
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
@@ -93,6 +134,69 @@ mod tests {
         assert_eq!(extra["version"], "2");
     }
 
+    #[test]
+    fn test_payment_requirements_to_payment_uri_round_trips_for_exact() {
+        let requirements = PaymentRequirements::new(
+            "exact",
+            "base-sepolia",
+            "1000000",
+            "0x036CbD53842c5426634e7929541eC2318f3dCF7e",
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            "https://example.com/test",
+            "Test payment",
+        );
+
+        let uri = requirements.to_payment_uri().unwrap();
+        assert_eq!(
+            uri,
+            "ethereum:0x036CbD53842c5426634e7929541eC2318f3dCF7e@84532/transfer?address=0x209693Bc6afc0C5328bA36FaF03C514EF312287C&uint256=1000000"
+        );
+
+        let parsed = PaymentRequirements::from_payment_uri(&uri).unwrap();
+        assert_eq!(parsed.scheme, "exact");
+        assert_eq!(parsed.network, "base-sepolia");
+        assert_eq!(parsed.asset, requirements.asset);
+        assert_eq!(parsed.pay_to, requirements.pay_to);
+        assert_eq!(parsed.max_amount_required, requirements.max_amount_required);
+    }
+
+    #[test]
+    fn test_payment_requirements_to_payment_uri_rejects_unknown_network() {
+        let requirements = PaymentRequirements::new(
+            "exact",
+            "polygon",
+            "1000000",
+            "0x0000000000000000000000000000000000000000",
+            "0x0000000000000000000000000000000000000000",
+            "https://example.com/test",
+            "Test payment",
+        );
+
+        assert!(requirements.to_payment_uri().is_err());
+    }
+
+    #[test]
+    fn test_payment_requirements_to_payment_uri_round_trips_for_lightning_bolt11() {
+        let mut requirements = PaymentRequirements::new(
+            crate::types::schemes::LIGHTNING_BOLT11,
+            "bitcoin",
+            "1000",
+            "sat",
+            "bc1qexamplepayee",
+            "lightning:/api/premium",
+            "Lightning invoice",
+        );
+        requirements
+            .set_lightning_bolt11_invoice("lnbc1invoice", "deadbeef")
+            .unwrap();
+
+        let uri = requirements.to_payment_uri().unwrap();
+        assert_eq!(uri, "lightning:lnbc1invoice");
+
+        let parsed = PaymentRequirements::from_payment_uri(&uri).unwrap();
+        assert_eq!(parsed.lightning_bolt11_invoice().unwrap().0, "lnbc1invoice");
+    }
+
     #[test]
     fn test_payment_payload_creation() {
         let authorization = ExactEvmPayloadAuthorization::new(
@@ -211,4 +315,138 @@ mod tests {
     fn test_schemes() {
         assert_eq!(schemes::EXACT, "exact");
     }
+
+    #[test]
+    fn test_check_validity_window_not_yet_valid() {
+        let now = chrono::Utc::now().timestamp();
+        let authorization = ExactEvmPayloadAuthorization::new(
+            "0x857b06519E91e3A54538791bDbb0E22373e36b66",
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            "1000000",
+            (now + 100).to_string(),
+            (now + 200).to_string(),
+            "0xf3746613c2d920b5fdabc0856f2aeb2d4f88ee6037b8cc5d04a71a4462f13480",
+        );
+
+        assert!(matches!(
+            authorization.check_validity_window(),
+            Err(X402Error::AuthorizationNotYetValid)
+        ));
+    }
+
+    #[test]
+    fn test_check_validity_window_expired() {
+        let now = chrono::Utc::now().timestamp();
+        let authorization = ExactEvmPayloadAuthorization::new(
+            "0x857b06519E91e3A54538791bDbb0E22373e36b66",
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            "1000000",
+            (now - 200).to_string(),
+            (now - 100).to_string(),
+            "0xf3746613c2d920b5fdabc0856f2aeb2d4f88ee6037b8cc5d04a71a4462f13480",
+        );
+
+        assert!(matches!(
+            authorization.check_validity_window(),
+            Err(X402Error::AuthorizationExpired { .. })
+        ));
+    }
+
+    #[test]
+    fn test_check_validity_window_valid() {
+        let now = chrono::Utc::now().timestamp();
+        let authorization = ExactEvmPayloadAuthorization::new(
+            "0x857b06519E91e3A54538791bDbb0E22373e36b66",
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            "1000000",
+            (now - 100).to_string(),
+            (now + 100).to_string(),
+            "0xf3746613c2d920b5fdabc0856f2aeb2d4f88ee6037b8cc5d04a71a4462f13480",
+        );
+
+        assert!(authorization.check_validity_window().is_ok());
+    }
+
+    #[test]
+    fn test_exact_evm_payload_validate_accepts_well_formed_payload() {
+        let authorization = ExactEvmPayloadAuthorization::new(
+            "0x857b06519E91e3A54538791bDbb0E22373e36b66",
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            "1000000",
+            "1745323800",
+            "1745323985",
+            "0xf3746613c2d920b5fdabc0856f2aeb2d4f88ee6037b8cc5d04a71a4462f13480",
+        );
+        let payload = ExactEvmPayload {
+            signature: "0x2d6a7588d6acca505cbf0d9a4a227e0c52c6c34008c8e8986a1283259764173608a2ce6496642e377d6da8dbbf5836e9bd15092f9ecab05ded3d6293af148b571c".to_string(),
+            authorization,
+        };
+
+        assert!(payload.validate().is_ok());
+    }
+
+    #[test]
+    fn test_exact_evm_payload_validate_rejects_malformed_fields() {
+        let base = ExactEvmPayloadAuthorization::new(
+            "0x857b06519E91e3A54538791bDbb0E22373e36b66",
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            "1000000",
+            "1745323800",
+            "1745323985",
+            "0xf3746613c2d920b5fdabc0856f2aeb2d4f88ee6037b8cc5d04a71a4462f13480",
+        );
+
+        let non_hex_signature = ExactEvmPayload {
+            signature: "not-hex".to_string(),
+            authorization: base.clone(),
+        };
+        assert!(matches!(
+            non_hex_signature.validate(),
+            Err(X402Error::MalformedPayload { field }) if field == "signature"
+        ));
+
+        let mut bad_nonce = base.clone();
+        bad_nonce.nonce = "0xZZ".to_string();
+        assert!(matches!(
+            bad_nonce.validate(),
+            Err(X402Error::MalformedPayload { field }) if field == "nonce"
+        ));
+
+        let mut bad_value = base.clone();
+        bad_value.value = "not-a-number".to_string();
+        assert!(matches!(
+            bad_value.validate(),
+            Err(X402Error::MalformedPayload { field }) if field == "value"
+        ));
+
+        let mut bad_valid_after = base.clone();
+        bad_valid_after.valid_after = "soon".to_string();
+        assert!(matches!(
+            bad_valid_after.validate(),
+            Err(X402Error::MalformedPayload { field }) if field == "validAfter"
+        ));
+
+        let mut bad_valid_before = base;
+        bad_valid_before.valid_before = "later".to_string();
+        assert!(matches!(
+            bad_valid_before.validate(),
+            Err(X402Error::MalformedPayload { field }) if field == "validBefore"
+        ));
+    }
+
+    #[test]
+    fn test_payment_context_has_scopes() {
+        let context = PaymentContext {
+            payer: Some("0x857b06519E91e3A54538791bDbb0E22373e36b66".to_string()),
+            scheme: "exact".to_string(),
+            network: "base-sepolia".to_string(),
+            amount: "1000000".to_string(),
+            scopes: vec!["read".to_string(), "write".to_string()],
+        };
+
+        assert!(context.has_scopes(&["read".to_string()]));
+        assert!(context.has_scopes(&["read".to_string(), "write".to_string()]));
+        assert!(!context.has_scopes(&["admin".to_string()]));
+        assert!(context.has_scopes(&[]));
+    }
 }
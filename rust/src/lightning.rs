@@ -0,0 +1,608 @@
+//! Verification for the `lightning-bolt12` and `lightning-bolt11` payment schemes
+//!
+//! Unlike the `exact` EVM scheme, a Lightning payment is already final by the time it
+//! reaches the facilitator: the client decoded the offer or invoice and paid it out of
+//! band. Verification here is limited to checking that the presented preimage actually
+//! unlocks the invoice, and that the invoice matches what the resource required.
+//!
+//! `lightning-bolt12` requests a fresh invoice against a reusable offer; `lightning-bolt11`
+//! instead pays a single-use invoice the facilitator minted up front (optionally via
+//! [`cln`] or [`lnd`], REST clients for Core Lightning and LND gated behind the
+//! `lightning` feature) and verified either by the client revealing a preimage
+//! ([`verify_lightning_bolt11_payment`]) or by polling the minting node for the
+//! invoice's settled status ([`verify_lightning_bolt11_invoice_paid`]).
+//!
+//! [`bolt11::Bolt11Invoice`] is a separate, lower-level piece: a pure bech32 codec for
+//! the `bolt11` string itself, for reading an invoice minted by a party this crate has
+//! no node client for.
+
+pub mod bolt11;
+#[cfg(feature = "lightning")]
+pub mod cln;
+#[cfg(feature = "lightning")]
+pub mod lnd;
+
+use crate::types::{
+    schemes, LightningBolt11Invoice, LightningBolt11Payload, LightningBolt12Payload,
+    LightningInvoice, PaymentRequirements,
+};
+use crate::{Result, SettleResponse, VerifyResponse, X402Error};
+use sha2::{Digest, Sha256};
+
+/// A boxed, `Send` future, used in place of `async fn` in [`LightningNodeClient`] since
+/// traits can't have object-safe async methods on stable Rust without an extra
+/// proc-macro crate this workspace doesn't otherwise depend on
+pub type BoxFuture<'a, T> = std::pin::Pin<Box<dyn std::future::Future<Output = T> + Send + 'a>>;
+
+/// Where a BOLT11 invoice stands the last time its minting node was asked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvoiceStatus {
+    /// Not yet paid, and not past its expiry
+    Pending,
+    /// Paid in full
+    Paid,
+    /// Expired without being paid
+    Expired,
+}
+
+/// A Lightning node's invoice-minting REST API, abstracted so a resource's pricing
+/// doesn't need to know whether it's backed by Core Lightning ([`cln::CoreLightningClient`])
+/// or LND ([`lnd::LndRestClient`])
+///
+/// Mirrors [`crate::scheme_registry::SchemeHandler`]'s role for EVM networks: a single
+/// trait object [`crate::middleware::PaymentMiddlewareConfig::with_lightning_config`]
+/// can hold regardless of which node backend actually mints and tracks the invoice.
+pub trait LightningNodeClient: Send + Sync {
+    /// Mint a new BOLT11 invoice for `amount_msat`, returning the fields needed to
+    /// advertise it in a `PaymentRequired` challenge
+    fn create_invoice<'a>(
+        &'a self,
+        amount_msat: u64,
+        description: &'a str,
+    ) -> BoxFuture<'a, Result<LightningBolt11Invoice>>;
+
+    /// Look up whether the invoice identified by `payment_hash` has been paid
+    fn lookup_invoice<'a>(&'a self, payment_hash: &'a str) -> BoxFuture<'a, Result<InvoiceStatus>>;
+}
+
+/// Verify a BOLT11 payment by polling `node` for the invoice's settled status, instead
+/// of requiring the client to reveal a preimage in its payload (see
+/// [`verify_lightning_bolt11_payment`] for that alternative)
+///
+/// Useful when a client can't or won't surface the preimage itself (e.g. it paid via a
+/// wallet that doesn't expose one to the caller) and the minting node is trusted as
+/// the source of truth for settlement instead.
+pub async fn verify_lightning_bolt11_invoice_paid(
+    node: &dyn LightningNodeClient,
+    requirements: &PaymentRequirements,
+) -> Result<VerifyResponse> {
+    let (_, payment_hash) = requirements
+        .lightning_bolt11_invoice()
+        .ok_or_else(|| X402Error::invalid_payment_requirements("Missing BOLT11 invoice"))?;
+
+    match node.lookup_invoice(payment_hash).await? {
+        InvoiceStatus::Paid => Ok(VerifyResponse {
+            is_valid: true,
+            invalid_reason: None,
+            payer: None,
+        }),
+        InvoiceStatus::Pending => Ok(VerifyResponse {
+            is_valid: false,
+            invalid_reason: Some("Invoice has not been paid yet".to_string()),
+            payer: None,
+        }),
+        InvoiceStatus::Expired => Ok(VerifyResponse {
+            is_valid: false,
+            invalid_reason: Some("Invoice expired before being paid".to_string()),
+            payer: None,
+        }),
+    }
+}
+
+/// Settle a BOLT11 payment verified via [`verify_lightning_bolt11_invoice_paid`]
+///
+/// Like [`settle_lightning_bolt11_payment`], settlement is just re-verification: the
+/// payment already happened off-chain once the node reports it as paid.
+pub async fn settle_lightning_bolt11_invoice_paid(
+    node: &dyn LightningNodeClient,
+    requirements: &PaymentRequirements,
+) -> Result<SettleResponse> {
+    let verified = verify_lightning_bolt11_invoice_paid(node, requirements).await?;
+    if !verified.is_valid {
+        return Err(X402Error::invalid_lightning_invoice(
+            verified.invalid_reason.unwrap_or_else(|| "Invoice is not paid".to_string()),
+        ));
+    }
+
+    let (_, payment_hash) = requirements
+        .lightning_bolt11_invoice()
+        .ok_or_else(|| X402Error::invalid_payment_requirements("Missing BOLT11 invoice"))?;
+
+    Ok(SettleResponse {
+        success: true,
+        error_reason: None,
+        transaction: payment_hash.to_string(),
+        network: "lightning".to_string(),
+        payer: None,
+    })
+}
+
+/// Build the [`PaymentRequirements`] a resource would advertise for `invoice`: scheme
+/// [`schemes::LIGHTNING_BOLT11`] on network `"bitcoin"`, priced in millisatoshis
+/// (`asset: "sat"`), with `invoice` itself attached via
+/// [`PaymentRequirements::set_lightning_bolt11_invoice`] so the client knows exactly
+/// what to pay.
+///
+/// This is the resource-config half of the BOLT11 scheme; [`cln::CoreLightningClient::
+/// create_invoice`] (behind the `lightning` feature) is the piece that actually mints
+/// `invoice`, and [`verify_lightning_bolt11_payment`]/[`settle_lightning_bolt11_payment`]
+/// check the client's resulting payment against it.
+pub fn build_bolt11_requirements(
+    invoice: &LightningBolt11Invoice,
+    pay_to: impl Into<String>,
+    resource: impl Into<String>,
+) -> Result<PaymentRequirements> {
+    let mut requirements = PaymentRequirements::new(
+        schemes::LIGHTNING_BOLT11,
+        "bitcoin",
+        invoice.amount_msat.to_string(),
+        "sat",
+        pay_to,
+        resource,
+        invoice.description.clone(),
+    );
+    requirements.set_lightning_bolt11_invoice(&invoice.bolt11, &invoice.payment_hash)?;
+    Ok(requirements)
+}
+
+/// Like [`build_bolt11_requirements`], but for an invoice minted by a node this crate
+/// has no [`LightningNodeClient`] for: `invoice` is decoded with
+/// [`bolt11::Bolt11Invoice::decode`] to derive the payment hash and amount instead of
+/// requiring the caller to already have them on hand
+pub fn build_bolt11_requirements_from_invoice_string(
+    invoice: &str,
+    pay_to: impl Into<String>,
+    resource: impl Into<String>,
+) -> Result<PaymentRequirements> {
+    let decoded = bolt11::Bolt11Invoice::decode(invoice)?;
+    let amount_msat = decoded
+        .amount_msat
+        .ok_or_else(|| X402Error::invalid_lightning_invoice("Invoice does not specify an amount"))?;
+
+    let mut requirements = PaymentRequirements::new(
+        schemes::LIGHTNING_BOLT11,
+        "bitcoin",
+        amount_msat.to_string(),
+        "sat",
+        pay_to,
+        resource,
+        decoded.description.clone().unwrap_or_default(),
+    );
+    requirements.set_lightning_bolt11_invoice(invoice, &decoded.payment_hash)?;
+    Ok(requirements)
+}
+
+/// Verify a Lightning BOLT12 payment against the resource's requirements
+///
+/// Checks, in order: the invoice was requested against the offer the requirements
+/// advertise, the invoice amount covers `max_amount_required` (in millisatoshis), the
+/// invoice has not expired, and `sha256(preimage) == invoice.payment_hash`.
+pub fn verify_lightning_payment(
+    payload: &LightningBolt12Payload,
+    requirements: &PaymentRequirements,
+) -> Result<VerifyResponse> {
+    let invoice = &payload.invoice;
+
+    let expected_offer = requirements
+        .lightning_offer()
+        .ok_or_else(|| X402Error::invalid_payment_requirements("Missing BOLT12 offer"))?;
+    if invoice.offer != expected_offer {
+        return Err(X402Error::invalid_lightning_invoice(
+            "Invoice was not requested against the required offer",
+        ));
+    }
+
+    let required_msat: u64 = requirements
+        .max_amount_required
+        .parse()
+        .map_err(|_| X402Error::invalid_payment_requirements("Invalid amount format"))?;
+    if invoice.amount_msat < required_msat {
+        return Err(X402Error::InvalidAmount {
+            expected: required_msat.to_string(),
+            got: invoice.amount_msat.to_string(),
+        });
+    }
+
+    if invoice.is_expired() {
+        return Err(X402Error::authorization_expired(
+            invoice.expires_at,
+            chrono::Utc::now().timestamp(),
+        ));
+    }
+
+    verify_preimage(&invoice.payment_hash, &payload.preimage)?;
+
+    Ok(VerifyResponse {
+        is_valid: true,
+        invalid_reason: None,
+        payer: None,
+    })
+}
+
+/// Settle a Lightning BOLT12 payment
+///
+/// Settlement is just re-verification: the payment itself already happened off-chain
+/// when the client paid the invoice, so there is nothing left to broadcast.
+pub fn settle_lightning_payment(
+    payload: &LightningBolt12Payload,
+    requirements: &PaymentRequirements,
+) -> Result<SettleResponse> {
+    verify_lightning_payment(payload, requirements)?;
+
+    Ok(SettleResponse {
+        success: true,
+        error_reason: None,
+        transaction: payload.invoice.payment_hash.clone(),
+        network: "lightning".to_string(),
+        payer: None,
+    })
+}
+
+/// Verify a Lightning BOLT11 payment against the resource's requirements
+///
+/// Checks, in order: the paid invoice is the exact one the requirements advertise, its
+/// amount covers `max_amount_required` (in millisatoshis), it has not expired, and
+/// `sha256(preimage) == invoice.payment_hash`.
+pub fn verify_lightning_bolt11_payment(
+    payload: &LightningBolt11Payload,
+    requirements: &PaymentRequirements,
+) -> Result<VerifyResponse> {
+    let invoice = &payload.invoice;
+
+    let (expected_bolt11, _) = requirements
+        .lightning_bolt11_invoice()
+        .ok_or_else(|| X402Error::invalid_payment_requirements("Missing BOLT11 invoice"))?;
+    if invoice.bolt11 != expected_bolt11 {
+        return Err(X402Error::invalid_lightning_invoice(
+            "Invoice does not match the one the resource required",
+        ));
+    }
+
+    let required_msat: u64 = requirements
+        .max_amount_required
+        .parse()
+        .map_err(|_| X402Error::invalid_payment_requirements("Invalid amount format"))?;
+    if invoice.amount_msat < required_msat {
+        return Err(X402Error::InvalidAmount {
+            expected: required_msat.to_string(),
+            got: invoice.amount_msat.to_string(),
+        });
+    }
+
+    if invoice.is_expired() {
+        return Err(X402Error::authorization_expired(
+            invoice.expires_at,
+            chrono::Utc::now().timestamp(),
+        ));
+    }
+
+    verify_preimage(&invoice.payment_hash, &payload.preimage)?;
+
+    Ok(VerifyResponse {
+        is_valid: true,
+        invalid_reason: None,
+        payer: None,
+    })
+}
+
+/// Settle a Lightning BOLT11 payment
+///
+/// Settlement is just re-verification: the payment itself already happened off-chain
+/// when the client paid the invoice, so there is nothing left to broadcast.
+pub fn settle_lightning_bolt11_payment(
+    payload: &LightningBolt11Payload,
+    requirements: &PaymentRequirements,
+) -> Result<SettleResponse> {
+    verify_lightning_bolt11_payment(payload, requirements)?;
+
+    Ok(SettleResponse {
+        success: true,
+        error_reason: None,
+        transaction: payload.invoice.payment_hash.clone(),
+        network: "lightning".to_string(),
+        payer: None,
+    })
+}
+
+/// Check that `sha256(preimage) == payment_hash`, mapping malformed hex in either field
+/// to [`X402Error::InvalidLightningInvoice`]
+fn verify_preimage(payment_hash: &str, preimage: &str) -> Result<()> {
+    let preimage_bytes = hex::decode(preimage)
+        .map_err(|_| X402Error::invalid_lightning_invoice("Preimage is not valid hex"))?;
+    let expected_hash = hex::decode(payment_hash)
+        .map_err(|_| X402Error::invalid_lightning_invoice("Payment hash is not valid hex"))?;
+
+    let computed_hash = Sha256::digest(&preimage_bytes);
+    if computed_hash.as_slice() != expected_hash.as_slice() {
+        return Err(X402Error::invalid_lightning_invoice(
+            "Preimage does not hash to the invoice's payment hash",
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_invoice(offer: &str, expires_at: i64) -> (LightningInvoice, String) {
+        let preimage = "00".repeat(32);
+        let preimage_bytes = hex::decode(&preimage).unwrap();
+        let payment_hash = hex::encode(Sha256::digest(&preimage_bytes));
+
+        (
+            LightningInvoice {
+                offer: offer.to_string(),
+                payment_hash,
+                amount_msat: 1000,
+                expires_at,
+                description: "Test resource".to_string(),
+            },
+            preimage,
+        )
+    }
+
+    fn test_requirements(offer: &str) -> PaymentRequirements {
+        let mut requirements = PaymentRequirements::new(
+            crate::types::schemes::LIGHTNING_BOLT12,
+            "lightning",
+            "1000",
+            "",
+            "",
+            "https://example.com/test",
+            "Test resource",
+        );
+        requirements.set_lightning_offer(offer).unwrap();
+        requirements
+    }
+
+    #[test]
+    fn test_verify_lightning_payment_succeeds() {
+        let requirements = test_requirements("lno1offer");
+        let (invoice, preimage) =
+            test_invoice("lno1offer", chrono::Utc::now().timestamp() + 60);
+        let payload = LightningBolt12Payload { invoice, preimage };
+
+        let result = verify_lightning_payment(&payload, &requirements).unwrap();
+        assert!(result.is_valid);
+    }
+
+    #[test]
+    fn test_verify_lightning_payment_rejects_wrong_preimage() {
+        let requirements = test_requirements("lno1offer");
+        let (invoice, _) = test_invoice("lno1offer", chrono::Utc::now().timestamp() + 60);
+        let payload = LightningBolt12Payload {
+            invoice,
+            preimage: "11".repeat(32),
+        };
+
+        let result = verify_lightning_payment(&payload, &requirements);
+        assert!(matches!(
+            result,
+            Err(X402Error::InvalidLightningInvoice { .. })
+        ));
+    }
+
+    #[test]
+    fn test_verify_lightning_payment_rejects_expired_invoice() {
+        let requirements = test_requirements("lno1offer");
+        let (invoice, preimage) =
+            test_invoice("lno1offer", chrono::Utc::now().timestamp() - 60);
+        let payload = LightningBolt12Payload { invoice, preimage };
+
+        let result = verify_lightning_payment(&payload, &requirements);
+        assert!(matches!(result, Err(X402Error::AuthorizationExpired { .. })));
+    }
+
+    #[test]
+    fn test_verify_lightning_payment_rejects_insufficient_amount() {
+        let requirements = test_requirements("lno1offer");
+        let (mut invoice, preimage) =
+            test_invoice("lno1offer", chrono::Utc::now().timestamp() + 60);
+        invoice.amount_msat = 1;
+        let payload = LightningBolt12Payload { invoice, preimage };
+
+        let result = verify_lightning_payment(&payload, &requirements);
+        assert!(matches!(result, Err(X402Error::InvalidAmount { .. })));
+    }
+
+    #[test]
+    fn test_verify_lightning_payment_rejects_offer_mismatch() {
+        let requirements = test_requirements("lno1offer");
+        let (invoice, preimage) =
+            test_invoice("lno1other", chrono::Utc::now().timestamp() + 60);
+        let payload = LightningBolt12Payload { invoice, preimage };
+
+        let result = verify_lightning_payment(&payload, &requirements);
+        assert!(matches!(
+            result,
+            Err(X402Error::InvalidLightningInvoice { .. })
+        ));
+    }
+
+    fn test_bolt11_invoice(bolt11: &str, expires_at: i64) -> (LightningBolt11Invoice, String) {
+        let preimage = "00".repeat(32);
+        let preimage_bytes = hex::decode(&preimage).unwrap();
+        let payment_hash = hex::encode(Sha256::digest(&preimage_bytes));
+
+        (
+            LightningBolt11Invoice {
+                bolt11: bolt11.to_string(),
+                payment_hash,
+                amount_msat: 1000,
+                expires_at,
+                description: "Test resource".to_string(),
+            },
+            preimage,
+        )
+    }
+
+    fn test_bolt11_requirements(bolt11: &str, payment_hash: &str) -> PaymentRequirements {
+        let mut requirements = PaymentRequirements::new(
+            crate::types::schemes::LIGHTNING_BOLT11,
+            "lightning",
+            "1000",
+            "",
+            "",
+            "https://example.com/test",
+            "Test resource",
+        );
+        requirements.set_lightning_bolt11_invoice(bolt11, payment_hash).unwrap();
+        requirements
+    }
+
+    #[test]
+    fn test_verify_lightning_bolt11_payment_succeeds() {
+        let (invoice, preimage) =
+            test_bolt11_invoice("lnbc1invoice", chrono::Utc::now().timestamp() + 60);
+        let requirements = test_bolt11_requirements("lnbc1invoice", &invoice.payment_hash);
+        let payload = LightningBolt11Payload { invoice, preimage };
+
+        let result = verify_lightning_bolt11_payment(&payload, &requirements).unwrap();
+        assert!(result.is_valid);
+    }
+
+    #[test]
+    fn test_verify_lightning_bolt11_payment_rejects_wrong_preimage() {
+        let (invoice, _) = test_bolt11_invoice("lnbc1invoice", chrono::Utc::now().timestamp() + 60);
+        let requirements = test_bolt11_requirements("lnbc1invoice", &invoice.payment_hash);
+        let payload = LightningBolt11Payload {
+            invoice,
+            preimage: "11".repeat(32),
+        };
+
+        let result = verify_lightning_bolt11_payment(&payload, &requirements);
+        assert!(matches!(
+            result,
+            Err(X402Error::InvalidLightningInvoice { .. })
+        ));
+    }
+
+    #[test]
+    fn test_verify_lightning_bolt11_payment_rejects_expired_invoice() {
+        let (invoice, preimage) =
+            test_bolt11_invoice("lnbc1invoice", chrono::Utc::now().timestamp() - 60);
+        let requirements = test_bolt11_requirements("lnbc1invoice", &invoice.payment_hash);
+        let payload = LightningBolt11Payload { invoice, preimage };
+
+        let result = verify_lightning_bolt11_payment(&payload, &requirements);
+        assert!(matches!(result, Err(X402Error::AuthorizationExpired { .. })));
+    }
+
+    #[test]
+    fn test_verify_lightning_bolt11_payment_rejects_invoice_mismatch() {
+        let (invoice, preimage) =
+            test_bolt11_invoice("lnbc1invoice", chrono::Utc::now().timestamp() + 60);
+        let requirements = test_bolt11_requirements("lnbc1other", &invoice.payment_hash);
+        let payload = LightningBolt11Payload { invoice, preimage };
+
+        let result = verify_lightning_bolt11_payment(&payload, &requirements);
+        assert!(matches!(
+            result,
+            Err(X402Error::InvalidLightningInvoice { .. })
+        ));
+    }
+
+    #[test]
+    fn test_settle_lightning_bolt11_payment_returns_payment_hash_as_transaction() {
+        let (invoice, preimage) =
+            test_bolt11_invoice("lnbc1invoice", chrono::Utc::now().timestamp() + 60);
+        let requirements = test_bolt11_requirements("lnbc1invoice", &invoice.payment_hash);
+        let payment_hash = invoice.payment_hash.clone();
+        let payload = LightningBolt11Payload { invoice, preimage };
+
+        let result = settle_lightning_bolt11_payment(&payload, &requirements).unwrap();
+        assert!(result.success);
+        assert_eq!(result.transaction, payment_hash);
+    }
+
+    #[test]
+    fn test_build_bolt11_requirements_round_trips_through_verification() {
+        let (invoice, preimage) =
+            test_bolt11_invoice("lnbc1invoice", chrono::Utc::now().timestamp() + 60);
+
+        let requirements = build_bolt11_requirements(&invoice, "bc1qexamplepayee", "lightning:/api/premium").unwrap();
+        assert_eq!(requirements.scheme, schemes::LIGHTNING_BOLT11);
+        assert_eq!(requirements.network, "bitcoin");
+        assert_eq!(requirements.asset, "sat");
+        assert_eq!(requirements.max_amount_required, "1000");
+        assert_eq!(
+            requirements.lightning_bolt11_invoice(),
+            Some(("lnbc1invoice", invoice.payment_hash.as_str()))
+        );
+
+        let payload = LightningBolt11Payload { invoice, preimage };
+        let result = verify_lightning_bolt11_payment(&payload, &requirements).unwrap();
+        assert!(result.is_valid);
+    }
+
+    #[test]
+    fn test_build_bolt11_requirements_from_invoice_string_decodes_amount_and_hash() {
+        let key = k256::ecdsa::SigningKey::from_slice(
+            &hex::decode("e126f68f7eafcc8b74f54d269fe206be715000f6b51953880e72e5a6ee35795").unwrap(),
+        )
+        .unwrap();
+        let decoded_invoice = bolt11::Bolt11Invoice {
+            network: bolt11::Bolt11Network::Mainnet,
+            amount_msat: Some(100_000),
+            timestamp: chrono::Utc::now().timestamp(),
+            payment_hash: "ab".repeat(32),
+            description: Some("Test resource".to_string()),
+            description_hash: None,
+            expiry: 3600,
+            payee_pubkey: String::new(),
+        };
+        let invoice_string = decoded_invoice.encode(&key).unwrap();
+
+        let requirements = build_bolt11_requirements_from_invoice_string(
+            &invoice_string,
+            "bc1qexamplepayee",
+            "lightning:/api/premium",
+        )
+        .unwrap();
+
+        assert_eq!(requirements.max_amount_required, "100000");
+        assert_eq!(
+            requirements.lightning_bolt11_invoice(),
+            Some((invoice_string.as_str(), "ab".repeat(32).as_str()))
+        );
+    }
+
+    #[test]
+    fn test_build_bolt11_requirements_from_invoice_string_rejects_an_any_amount_invoice() {
+        let key = k256::ecdsa::SigningKey::from_slice(
+            &hex::decode("e126f68f7eafcc8b74f54d269fe206be715000f6b51953880e72e5a6ee35795").unwrap(),
+        )
+        .unwrap();
+        let decoded_invoice = bolt11::Bolt11Invoice {
+            network: bolt11::Bolt11Network::Mainnet,
+            amount_msat: None,
+            timestamp: chrono::Utc::now().timestamp(),
+            payment_hash: "ab".repeat(32),
+            description: Some("Test resource".to_string()),
+            description_hash: None,
+            expiry: 3600,
+            payee_pubkey: String::new(),
+        };
+        let invoice_string = decoded_invoice.encode(&key).unwrap();
+
+        let result = build_bolt11_requirements_from_invoice_string(
+            &invoice_string,
+            "bc1qexamplepayee",
+            "lightning:/api/premium",
+        );
+
+        assert!(matches!(result, Err(X402Error::InvalidLightningInvoice { .. })));
+    }
+}
@@ -0,0 +1,568 @@
+//! Raw BOLT11 invoice encode/decode
+//!
+//! [`super::LightningBolt11Invoice`] treats an invoice as an opaque `bolt11` string
+//! minted by a trusted node ([`super::cln`]/[`super::lnd`]). [`Bolt11Invoice`] instead
+//! parses that string itself, for the case where a client is handed an invoice from a
+//! party this crate doesn't otherwise trust (e.g. a `scheme: "lightning"` requirement
+//! advertised by some other resource server) and needs to read it before paying.
+//!
+//! A BOLT11 string is bech32 (BIP-0173): a human-readable part (`lnbc`/`lntb`/`lnbcrt`,
+//! optionally followed by an amount and a multiplier), a `1` separator, a data part of
+//! 5-bit groups, and a 6-group checksum. The data part is a 35-bit timestamp, a stream
+//! of tagged fields (`type` | `length` | `data`, each 5-bit-aligned), and a 520-bit
+//! recoverable secp256k1 signature over `SHA256(hrp ++ data)` from which the payee's
+//! node ID is recovered rather than read from a tag.
+
+use crate::{Result, X402Error};
+use k256::ecdsa::{signature::hazmat::PrehashSigner, RecoveryId, Signature as K256Signature, SigningKey, VerifyingKey};
+use sha2::{Digest, Sha256};
+
+const CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const TIMESTAMP_WORDS: usize = 7;
+const SIGNATURE_WORDS: usize = 104;
+
+/// Which Bitcoin network an invoice was minted for, carried in its HRP prefix
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bolt11Network {
+    /// `lnbc...`
+    Mainnet,
+    /// `lntb...`
+    Testnet,
+    /// `lnbcrt...`
+    Regtest,
+}
+
+impl Bolt11Network {
+    fn prefix(self) -> &'static str {
+        match self {
+            Self::Mainnet => "bc",
+            Self::Testnet => "tb",
+            Self::Regtest => "bcrt",
+        }
+    }
+}
+
+/// A decoded BOLT11 invoice
+///
+/// `payment_hash`, `description_hash`, and the recovered `payee_pubkey` are hex-encoded,
+/// matching how the rest of this crate represents fixed-size binary fields (see e.g.
+/// [`crate::types::ExactEvmPayloadAuthorization::nonce`]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bolt11Invoice {
+    /// Network the invoice was minted for
+    pub network: Bolt11Network,
+    /// Invoiced amount in millisatoshis, or `None` for an any-amount invoice
+    pub amount_msat: Option<u64>,
+    /// Unix timestamp the invoice was created at
+    pub timestamp: i64,
+    /// Hex-encoded SHA-256 payment hash (tag `p`)
+    pub payment_hash: String,
+    /// Plain-text description (tag `d`), mutually exclusive with `description_hash`
+    pub description: Option<String>,
+    /// Hex-encoded SHA-256 hash of a description held elsewhere (tag `h`)
+    pub description_hash: Option<String>,
+    /// Seconds after `timestamp` the invoice remains payable (tag `x`, default 3600)
+    pub expiry: i64,
+    /// Hex-encoded compressed secp256k1 pubkey of the node to pay, recovered from the
+    /// invoice's signature rather than read from an explicit tag
+    pub payee_pubkey: String,
+}
+
+impl Bolt11Invoice {
+    /// Whether the invoice is still payable at the current time
+    pub fn is_valid_now(&self) -> bool {
+        let now = chrono::Utc::now().timestamp();
+        now >= self.timestamp && now <= self.timestamp + self.expiry
+    }
+
+    /// Decode a BOLT11 invoice string, recovering the payee's node pubkey from its
+    /// signature
+    pub fn decode(invoice: &str) -> Result<Self> {
+        if invoice.chars().any(|c| c.is_ascii_uppercase()) && invoice.chars().any(|c| c.is_ascii_lowercase()) {
+            return Err(invalid("Invoice mixes uppercase and lowercase characters"));
+        }
+        let invoice = invoice.to_lowercase();
+
+        let sep = invoice.rfind('1').ok_or_else(|| invalid("Missing bech32 separator"))?;
+        let hrp = &invoice[..sep];
+        let data_chars = &invoice[sep + 1..];
+        if data_chars.len() < 6 {
+            return Err(invalid("Data part shorter than the checksum"));
+        }
+
+        let mut words = Vec::with_capacity(data_chars.len());
+        for c in data_chars.chars() {
+            let value = CHARSET
+                .iter()
+                .position(|&x| x as char == c)
+                .ok_or_else(|| invalid("Data part contains a non-bech32 character"))?;
+            words.push(value as u8);
+        }
+        if !verify_checksum(hrp, &words) {
+            return Err(invalid("Invalid bech32 checksum"));
+        }
+        let words = &words[..words.len() - 6];
+
+        if words.len() < TIMESTAMP_WORDS + SIGNATURE_WORDS {
+            return Err(invalid("Data part too short for a timestamp and signature"));
+        }
+        let timestamp = words_to_u64(&words[..TIMESTAMP_WORDS]) as i64;
+        let tag_words = &words[TIMESTAMP_WORDS..words.len() - SIGNATURE_WORDS];
+        let sig_words = &words[words.len() - SIGNATURE_WORDS..];
+
+        let mut payment_hash = None;
+        let mut description = None;
+        let mut description_hash = None;
+        let mut expiry = 3600i64;
+
+        let mut i = 0;
+        while i + 3 <= tag_words.len() {
+            let tag = tag_words[i];
+            let len = (tag_words[i + 1] as usize) << 5 | tag_words[i + 2] as usize;
+            let start = i + 3;
+            let end = start + len;
+            if end > tag_words.len() {
+                return Err(invalid("Tagged field overruns the data part"));
+            }
+            let field = &tag_words[start..end];
+
+            if tag == tag_value('p') {
+                let bytes = convert_bits(field, 5, 8, false)?;
+                if bytes.len() == 32 {
+                    payment_hash = Some(hex::encode(bytes));
+                }
+            } else if tag == tag_value('d') {
+                let bytes = convert_bits(field, 5, 8, false)?;
+                description = Some(
+                    String::from_utf8(bytes)
+                        .map_err(|_| invalid("Description is not valid UTF-8"))?,
+                );
+            } else if tag == tag_value('h') {
+                let bytes = convert_bits(field, 5, 8, false)?;
+                if bytes.len() == 32 {
+                    description_hash = Some(hex::encode(bytes));
+                }
+            } else if tag == tag_value('x') {
+                expiry = words_to_u64(field) as i64;
+            }
+            i = end;
+        }
+
+        let payment_hash = payment_hash.ok_or_else(|| invalid("Missing payment hash (tag p)"))?;
+        if description.is_none() && description_hash.is_none() {
+            return Err(invalid("Invoice has neither a description nor a description hash"));
+        }
+
+        let (network, amount_msat) = parse_hrp(hrp)?;
+
+        let data_bytes = convert_bits(&words[..words.len() - SIGNATURE_WORDS], 5, 8, true)?;
+        let message_hash = Sha256::digest([hrp.as_bytes(), &data_bytes].concat());
+        let sig_bytes = convert_bits(sig_words, 5, 8, false)?;
+        let recovery_id = RecoveryId::try_from(sig_bytes[64])
+            .map_err(|_| invalid("Invalid signature recovery id"))?;
+        let signature = K256Signature::try_from(&sig_bytes[..64])
+            .map_err(|_| invalid("Invalid signature"))?;
+        let verifying_key = VerifyingKey::recover_from_prehash(&message_hash, &signature, recovery_id)
+            .map_err(|_| invalid("Could not recover payee pubkey from signature"))?;
+        let payee_pubkey = hex::encode(verifying_key.to_encoded_point(true).as_bytes());
+
+        Ok(Self {
+            network,
+            amount_msat,
+            timestamp,
+            payment_hash,
+            description,
+            description_hash,
+            expiry,
+            payee_pubkey,
+        })
+    }
+
+    /// Encode the invoice, signing it with `signing_key` (whose recovered pubkey becomes
+    /// `payee_pubkey` on the resulting string — any value already set on `self.payee_pubkey`
+    /// is ignored)
+    pub fn encode(&self, signing_key: &SigningKey) -> Result<String> {
+        let hrp = self.hrp();
+
+        let mut words = u64_to_words(self.timestamp as u64, TIMESTAMP_WORDS);
+
+        let payment_hash_bytes =
+            hex::decode(&self.payment_hash).map_err(|_| invalid("payment_hash is not valid hex"))?;
+        push_tagged_field(&mut words, tag_value('p'), &payment_hash_bytes)?;
+
+        match (&self.description, &self.description_hash) {
+            (Some(description), _) => {
+                push_tagged_field(&mut words, tag_value('d'), description.as_bytes())?;
+            }
+            (None, Some(description_hash)) => {
+                let bytes = hex::decode(description_hash)
+                    .map_err(|_| invalid("description_hash is not valid hex"))?;
+                push_tagged_field(&mut words, tag_value('h'), &bytes)?;
+            }
+            (None, None) => {
+                return Err(invalid("Invoice needs a description or a description hash"))
+            }
+        }
+
+        if self.expiry != 3600 {
+            let expiry_words = u64_to_words(self.expiry as u64, 1);
+            words.push(tag_value('x'));
+            words.push((expiry_words.len() >> 5) as u8);
+            words.push((expiry_words.len() & 31) as u8);
+            words.extend(expiry_words);
+        }
+
+        let data_bytes = convert_bits(&words, 5, 8, true)?;
+        let message_hash = Sha256::digest([hrp.as_bytes(), &data_bytes].concat());
+        let (signature, recovery_id): (K256Signature, RecoveryId) = signing_key
+            .sign_prehash_recoverable(&message_hash)
+            .map_err(|e| invalid(format!("Failed to sign invoice: {e}")))?;
+        let mut sig_bytes = signature.to_bytes().to_vec();
+        sig_bytes.push(recovery_id.to_byte());
+        words.extend(convert_bits(&sig_bytes, 8, 5, true)?);
+
+        bech32_encode(&hrp, &words)
+    }
+
+    fn hrp(&self) -> String {
+        let mut hrp = format!("ln{}", self.network.prefix());
+        if let Some(amount_msat) = self.amount_msat {
+            hrp.push_str(&amount_to_hrp_suffix(amount_msat));
+        }
+        hrp
+    }
+}
+
+fn invalid(message: impl Into<String>) -> X402Error {
+    X402Error::invalid_lightning_invoice(message)
+}
+
+fn tag_value(letter: char) -> u8 {
+    CHARSET
+        .iter()
+        .position(|&c| c as char == letter)
+        .expect("letter must be a bech32 charset member") as u8
+}
+
+fn push_tagged_field(words: &mut Vec<u8>, tag: u8, data: &[u8]) -> Result<()> {
+    let data_words = convert_bits(data, 8, 5, true)?;
+    if data_words.len() > 0x3ff {
+        return Err(invalid("Tagged field data too long to encode"));
+    }
+    words.push(tag);
+    words.push((data_words.len() >> 5) as u8);
+    words.push((data_words.len() & 31) as u8);
+    words.extend(data_words);
+    Ok(())
+}
+
+fn words_to_u64(words: &[u8]) -> u64 {
+    words.iter().fold(0u64, |acc, &w| (acc << 5) | w as u64)
+}
+
+fn u64_to_words(mut value: u64, min_words: usize) -> Vec<u8> {
+    let mut words = Vec::new();
+    while value > 0 {
+        words.push((value & 0x1f) as u8);
+        value >>= 5;
+    }
+    if words.is_empty() {
+        words.push(0);
+    }
+    words.reverse();
+    while words.len() < min_words {
+        words.insert(0, 0);
+    }
+    words
+}
+
+fn parse_hrp(hrp: &str) -> Result<(Bolt11Network, Option<u64>)> {
+    let rest = hrp.strip_prefix("ln").ok_or_else(|| invalid("Missing ln prefix"))?;
+    let (network, amount_part) = if let Some(rest) = rest.strip_prefix("bcrt") {
+        (Bolt11Network::Regtest, rest)
+    } else if let Some(rest) = rest.strip_prefix("bc") {
+        (Bolt11Network::Mainnet, rest)
+    } else if let Some(rest) = rest.strip_prefix("tb") {
+        (Bolt11Network::Testnet, rest)
+    } else {
+        return Err(invalid(format!("Unknown Lightning network prefix in: {hrp}")));
+    };
+
+    if amount_part.is_empty() {
+        return Ok((network, None));
+    }
+
+    let (digits, multiplier) = match amount_part.chars().last() {
+        Some(c) if c.is_ascii_digit() => (amount_part, None),
+        Some(c) => (&amount_part[..amount_part.len() - c.len_utf8()], Some(c)),
+        None => unreachable!("checked non-empty above"),
+    };
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return Err(invalid(format!("Invalid amount in HRP: {amount_part}")));
+    }
+    let number: u64 = digits
+        .parse()
+        .map_err(|_| invalid("Amount digits overflow a u64"))?;
+
+    let amount_msat = match multiplier {
+        None => number.checked_mul(100_000_000_000),
+        Some('m') => number.checked_mul(100_000_000),
+        Some('u') => number.checked_mul(100_000),
+        Some('n') => number.checked_mul(100),
+        Some('p') => {
+            if number % 10 != 0 {
+                return Err(invalid("Pico-BTC amount must be a multiple of 10"));
+            }
+            Some(number / 10)
+        }
+        Some(other) => return Err(invalid(format!("Unknown amount multiplier: {other}"))),
+    }
+    .ok_or_else(|| invalid("Amount overflows a u64 number of millisatoshis"))?;
+
+    Ok((network, Some(amount_msat)))
+}
+
+fn amount_to_hrp_suffix(amount_msat: u64) -> String {
+    if amount_msat % 100_000_000_000 == 0 {
+        (amount_msat / 100_000_000_000).to_string()
+    } else if amount_msat % 100_000_000 == 0 {
+        format!("{}m", amount_msat / 100_000_000)
+    } else if amount_msat % 100_000 == 0 {
+        format!("{}u", amount_msat / 100_000)
+    } else if amount_msat % 100 == 0 {
+        format!("{}n", amount_msat / 100)
+    } else {
+        format!("{}p", amount_msat * 10)
+    }
+}
+
+/// Reference bech32 bit-width conversion (BIP-0173): repack a stream of `from_bits`-wide
+/// groups into `to_bits`-wide groups. With `pad`, a short trailing group is zero-padded;
+/// without it, a non-empty or non-zero leftover group is rejected instead of silently
+/// dropped.
+fn convert_bits(data: &[u8], from_bits: u32, to_bits: u32, pad: bool) -> Result<Vec<u8>> {
+    let mut acc: u32 = 0;
+    let mut bits: u32 = 0;
+    let mut ret = Vec::new();
+    let maxv: u32 = (1 << to_bits) - 1;
+
+    for &value in data {
+        let value = value as u32;
+        if value >> from_bits != 0 {
+            return Err(invalid("Value does not fit in from_bits"));
+        }
+        acc = (acc << from_bits) | value;
+        bits += from_bits;
+        while bits >= to_bits {
+            bits -= to_bits;
+            ret.push(((acc >> bits) & maxv) as u8);
+        }
+    }
+
+    if pad {
+        if bits > 0 {
+            ret.push(((acc << (to_bits - bits)) & maxv) as u8);
+        }
+    } else if bits >= from_bits || ((acc << (to_bits - bits)) & maxv) != 0 {
+        return Err(invalid("Non-zero padding in bech32 data"));
+    }
+
+    Ok(ret)
+}
+
+fn hrp_expand(hrp: &str) -> Vec<u8> {
+    let mut expanded: Vec<u8> = hrp.bytes().map(|b| b >> 5).collect();
+    expanded.push(0);
+    expanded.extend(hrp.bytes().map(|b| b & 0x1f));
+    expanded
+}
+
+fn polymod(values: &[u8]) -> u32 {
+    const GEN: [u32; 5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+    let mut chk: u32 = 1;
+    for &value in values {
+        let top = chk >> 25;
+        chk = ((chk & 0x1ff_ffff) << 5) ^ value as u32;
+        for (i, gen) in GEN.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                chk ^= gen;
+            }
+        }
+    }
+    chk
+}
+
+fn verify_checksum(hrp: &str, data: &[u8]) -> bool {
+    let mut values = hrp_expand(hrp);
+    values.extend_from_slice(data);
+    polymod(&values) == 1
+}
+
+fn create_checksum(hrp: &str, data: &[u8]) -> [u8; 6] {
+    let mut values = hrp_expand(hrp);
+    values.extend_from_slice(data);
+    values.extend_from_slice(&[0u8; 6]);
+    let polymod = polymod(&values) ^ 1;
+    let mut checksum = [0u8; 6];
+    for (i, slot) in checksum.iter_mut().enumerate() {
+        *slot = ((polymod >> (5 * (5 - i))) & 0x1f) as u8;
+    }
+    checksum
+}
+
+fn bech32_encode(hrp: &str, words: &[u8]) -> Result<String> {
+    let checksum = create_checksum(hrp, words);
+    let data: String = words
+        .iter()
+        .chain(checksum.iter())
+        .map(|&w| CHARSET[w as usize] as char)
+        .collect();
+    Ok(format!("{hrp}1{data}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signing_key() -> SigningKey {
+        SigningKey::from_slice(
+            &hex::decode("e126f68f7eafcc8b74f54d269fe206be715000f6b51953880e72e5a6ee35795").unwrap(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_amount_to_hrp_suffix_round_trips_through_parse_hrp() {
+        for (amount_msat, expected_suffix) in [
+            (250_000_000u64, "2500u"),
+            (100_000_000_000, "1"),
+            (100_000_000, "1m"),
+            (100, "1n"),
+            (1, "10p"),
+        ] {
+            let suffix = amount_to_hrp_suffix(amount_msat);
+            assert_eq!(suffix, expected_suffix);
+
+            let hrp = format!("lnbc{suffix}");
+            let (network, parsed) = parse_hrp(&hrp).unwrap();
+            assert_eq!(network, Bolt11Network::Mainnet);
+            assert_eq!(parsed, Some(amount_msat));
+        }
+    }
+
+    #[test]
+    fn test_parse_hrp_rejects_unknown_network_prefix() {
+        assert!(parse_hrp("lnxy2500u").is_err());
+    }
+
+    #[test]
+    fn test_encode_decode_round_trips_and_recovers_the_signing_pubkey() {
+        let key = signing_key();
+        let expected_pubkey = hex::encode(key.verifying_key().to_encoded_point(true).as_bytes());
+
+        let invoice = Bolt11Invoice {
+            network: Bolt11Network::Testnet,
+            amount_msat: Some(250_000_000),
+            timestamp: 1_700_000_000,
+            payment_hash: "0".repeat(64),
+            description: Some("1 cup coffee".to_string()),
+            description_hash: None,
+            expiry: 3600,
+            payee_pubkey: String::new(),
+        };
+
+        let encoded = invoice.encode(&key).unwrap();
+        let decoded = Bolt11Invoice::decode(&encoded).unwrap();
+
+        assert_eq!(decoded.network, Bolt11Network::Testnet);
+        assert_eq!(decoded.amount_msat, Some(250_000_000));
+        assert_eq!(decoded.payment_hash, invoice.payment_hash);
+        assert_eq!(decoded.description, invoice.description);
+        assert_eq!(decoded.payee_pubkey, expected_pubkey);
+    }
+
+    #[test]
+    fn test_encode_decode_round_trips_a_description_hash_invoice() {
+        let key = signing_key();
+        let description_hash = hex::encode(Sha256::digest(b"a long offline description"));
+
+        let invoice = Bolt11Invoice {
+            network: Bolt11Network::Regtest,
+            amount_msat: None,
+            timestamp: 1_700_000_000,
+            payment_hash: "1".repeat(64),
+            description: None,
+            description_hash: Some(description_hash.clone()),
+            expiry: 7200,
+            payee_pubkey: String::new(),
+        };
+
+        let encoded = invoice.encode(&key).unwrap();
+        let decoded = Bolt11Invoice::decode(&encoded).unwrap();
+
+        assert_eq!(decoded.network, Bolt11Network::Regtest);
+        assert_eq!(decoded.amount_msat, None);
+        assert_eq!(decoded.description_hash, Some(description_hash));
+        assert_eq!(decoded.expiry, 7200);
+    }
+
+    #[test]
+    fn test_is_valid_now_checks_the_expiry_window() {
+        let now = chrono::Utc::now().timestamp();
+        let invoice = Bolt11Invoice {
+            network: Bolt11Network::Mainnet,
+            amount_msat: None,
+            timestamp: now - 100,
+            payment_hash: "0".repeat(64),
+            description: Some("test".to_string()),
+            description_hash: None,
+            expiry: 3600,
+            payee_pubkey: String::new(),
+        };
+        assert!(invoice.is_valid_now());
+
+        let expired = Bolt11Invoice {
+            timestamp: now - 10_000,
+            ..invoice
+        };
+        assert!(!expired.is_valid_now());
+    }
+
+    #[test]
+    fn test_decode_rejects_a_bad_checksum() {
+        let key = signing_key();
+        let invoice = Bolt11Invoice {
+            network: Bolt11Network::Mainnet,
+            amount_msat: Some(250_000_000),
+            timestamp: 1_700_000_000,
+            payment_hash: "0".repeat(64),
+            description: Some("1 cup coffee".to_string()),
+            description_hash: None,
+            expiry: 3600,
+            payee_pubkey: String::new(),
+        };
+        let mut encoded = invoice.encode(&key).unwrap();
+        let last = encoded.pop().unwrap();
+        encoded.push(if last == 'q' { 'p' } else { 'q' });
+
+        assert!(Bolt11Invoice::decode(&encoded).is_err());
+    }
+
+    #[test]
+    fn test_decode_rejects_an_invoice_missing_a_description() {
+        let key = signing_key();
+        let mut words = u64_to_words(1_700_000_000, TIMESTAMP_WORDS);
+        push_tagged_field(&mut words, tag_value('p'), &[0u8; 32]).unwrap();
+        let data_bytes = convert_bits(&words, 5, 8, true).unwrap();
+        let hrp = "lnbc2500u";
+        let message_hash = Sha256::digest([hrp.as_bytes(), &data_bytes].concat());
+        let (signature, recovery_id): (K256Signature, RecoveryId) =
+            key.sign_prehash_recoverable(&message_hash).unwrap();
+        let mut sig_bytes = signature.to_bytes().to_vec();
+        sig_bytes.push(recovery_id.to_byte());
+        words.extend(convert_bits(&sig_bytes, 8, 5, true).unwrap());
+        let encoded = bech32_encode(hrp, &words).unwrap();
+
+        assert!(Bolt11Invoice::decode(&encoded).is_err());
+    }
+}
@@ -0,0 +1,231 @@
+//! Core Lightning REST client for minting `lightning-bolt11` invoices
+//!
+//! Gated behind the `lightning` feature so EVM-only users building against the `exact`
+//! scheme don't need a live Lightning node integration in their dependency tree. This
+//! talks to the [`clnrest`](https://docs.corelightning.org/reference/get_started_with_clnrest)
+//! plugin bundled with Core Lightning, authenticating with a `rune` access token.
+//!
+//! A facilitator uses [`CoreLightningClient::create_invoice`] to mint the BOLT11 invoice
+//! it advertises in the `PaymentRequired` challenge (via
+//! [`crate::types::PaymentRequirements::set_lightning_bolt11_invoice`]); once the client
+//! pays it and presents the preimage, [`super::verify_lightning_bolt11_payment`] verifies
+//! the payment without needing to talk to the node again.
+
+use crate::lightning::{BoxFuture, InvoiceStatus, LightningNodeClient};
+use crate::types::LightningBolt11Invoice;
+use crate::{Result, X402Error};
+use serde::Deserialize;
+
+/// A Core Lightning node reachable over its `clnrest` HTTP API
+#[derive(Debug, Clone)]
+pub struct CoreLightningClient {
+    base_url: String,
+    rune: String,
+    http: reqwest::Client,
+}
+
+/// `clnrest`'s `POST /v1/invoice` response, trimmed to the fields this module needs
+#[derive(Debug, Deserialize)]
+struct InvoiceResponse {
+    bolt11: String,
+    payment_hash: String,
+    expires_at: i64,
+}
+
+/// `clnrest`'s `POST /v1/listinvoices` response, trimmed to the fields this module needs
+#[derive(Debug, Deserialize)]
+struct ListInvoicesResponse {
+    invoices: Vec<ListedInvoice>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListedInvoice {
+    status: String,
+}
+
+impl CoreLightningClient {
+    /// Create a client for the `clnrest` instance at `base_url` (e.g.
+    /// `https://cln.example.com:3010`), authenticating with `rune`
+    pub fn new(base_url: impl Into<String>, rune: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into(),
+            rune: rune.into(),
+            http: reqwest::Client::new(),
+        }
+    }
+
+    /// Mint a new BOLT11 invoice for `amount_msat`, returning the fields needed to
+    /// advertise it in a `PaymentRequired` challenge
+    pub async fn create_invoice(&self, amount_msat: u64, description: &str) -> Result<LightningBolt11Invoice> {
+        let label = format!("x402-{}", random_label_suffix());
+        let response = self
+            .http
+            .post(format!("{}/v1/invoice", self.base_url))
+            .header("rune", &self.rune)
+            .json(&serde_json::json!({
+                "amount_msat": amount_msat,
+                "label": label,
+                "description": description,
+            }))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(X402Error::facilitator_error(format!(
+                "Core Lightning invoice creation failed ({}): {}",
+                status, body
+            )));
+        }
+
+        let invoice: InvoiceResponse = response.json().await?;
+
+        Ok(LightningBolt11Invoice {
+            bolt11: invoice.bolt11,
+            payment_hash: invoice.payment_hash,
+            amount_msat,
+            expires_at: invoice.expires_at,
+            description: description.to_string(),
+        })
+    }
+
+    /// Look up whether the invoice identified by `payment_hash` has been paid, via
+    /// `clnrest`'s `POST /v1/listinvoices`
+    pub async fn lookup_invoice(&self, payment_hash: &str) -> Result<InvoiceStatus> {
+        let response = self
+            .http
+            .post(format!("{}/v1/listinvoices", self.base_url))
+            .header("rune", &self.rune)
+            .json(&serde_json::json!({ "payment_hash": payment_hash }))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(X402Error::facilitator_error(format!(
+                "Core Lightning invoice lookup failed ({}): {}",
+                status, body
+            )));
+        }
+
+        let listed: ListInvoicesResponse = response.json().await?;
+        let invoice = listed.invoices.into_iter().next().ok_or_else(|| {
+            X402Error::invalid_lightning_invoice("Node has no invoice for that payment hash")
+        })?;
+
+        Ok(match invoice.status.as_str() {
+            "paid" => InvoiceStatus::Paid,
+            "expired" => InvoiceStatus::Expired,
+            _ => InvoiceStatus::Pending,
+        })
+    }
+}
+
+impl LightningNodeClient for CoreLightningClient {
+    fn create_invoice<'a>(
+        &'a self,
+        amount_msat: u64,
+        description: &'a str,
+    ) -> BoxFuture<'a, Result<LightningBolt11Invoice>> {
+        Box::pin(async move { self.create_invoice(amount_msat, description).await })
+    }
+
+    fn lookup_invoice<'a>(&'a self, payment_hash: &'a str) -> BoxFuture<'a, Result<InvoiceStatus>> {
+        Box::pin(async move { self.lookup_invoice(payment_hash).await })
+    }
+}
+
+/// A random hex suffix for a `clnrest` invoice label, unique enough to avoid colliding
+/// with another in-flight invoice from this process
+fn random_label_suffix() -> String {
+    use rand::RngCore;
+    let mut bytes = [0u8; 8];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_create_invoice_parses_successful_response() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/v1/invoice")
+            .match_header("rune", "test-rune")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!({
+                    "bolt11": "lnbc10n1invoice",
+                    "payment_hash": "abc123",
+                    "expires_at": 9_999_999_999i64,
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let client = CoreLightningClient::new(server.url(), "test-rune");
+        let invoice = client.create_invoice(1000, "Test resource").await.unwrap();
+
+        assert_eq!(invoice.bolt11, "lnbc10n1invoice");
+        assert_eq!(invoice.payment_hash, "abc123");
+        assert_eq!(invoice.amount_msat, 1000);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_create_invoice_surfaces_non_success_status() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/v1/invoice")
+            .with_status(500)
+            .with_body("node unreachable")
+            .create_async()
+            .await;
+
+        let client = CoreLightningClient::new(server.url(), "test-rune");
+        let result = client.create_invoice(1000, "Test resource").await;
+        assert!(matches!(result, Err(X402Error::FacilitatorError { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_lookup_invoice_reports_paid_status() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/v1/listinvoices")
+            .match_header("rune", "test-rune")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(serde_json::json!({ "invoices": [{ "status": "paid" }] }).to_string())
+            .create_async()
+            .await;
+
+        let client = CoreLightningClient::new(server.url(), "test-rune");
+        let status = client.lookup_invoice("abc123").await.unwrap();
+
+        assert_eq!(status, InvoiceStatus::Paid);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_lookup_invoice_reports_pending_status() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/v1/listinvoices")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(serde_json::json!({ "invoices": [{ "status": "unpaid" }] }).to_string())
+            .create_async()
+            .await;
+
+        let client = CoreLightningClient::new(server.url(), "test-rune");
+        let status = client.lookup_invoice("abc123").await.unwrap();
+
+        assert_eq!(status, InvoiceStatus::Pending);
+    }
+}
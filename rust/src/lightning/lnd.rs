@@ -0,0 +1,222 @@
+//! LND REST client for minting `lightning-bolt11` invoices
+//!
+//! Gated behind the `lightning` feature for the same reason as [`super::cln`]. Talks to
+//! [LND's REST API](https://lightning.engineering/api-docs/api/lnd/lightning/), authenticating
+//! with a hex-encoded macaroon rather than `clnrest`'s `rune` token — the two node
+//! backends otherwise play the same [`super::LightningNodeClient`] role, so a resource
+//! can be priced in Lightning without caring which one actually mints the invoice.
+
+use crate::lightning::{BoxFuture, InvoiceStatus, LightningNodeClient};
+use crate::types::LightningBolt11Invoice;
+use crate::{Result, X402Error};
+use serde::Deserialize;
+
+/// An LND node reachable over its REST API
+#[derive(Debug, Clone)]
+pub struct LndRestClient {
+    base_url: String,
+    macaroon_hex: String,
+    http: reqwest::Client,
+}
+
+/// LND's `POST /v1/invoices` response, trimmed to the fields this module needs
+#[derive(Debug, Deserialize)]
+struct AddInvoiceResponse {
+    payment_request: String,
+    /// Base64-encoded payment hash; LND's own hex-friendly field, `r_hash`, comes back
+    /// base64-encoded rather than hex, unlike `clnrest`'s `payment_hash`
+    r_hash: String,
+}
+
+/// LND's `GET /v1/invoice/{r_hash_str}` response, trimmed to the fields this module needs
+#[derive(Debug, Deserialize)]
+struct LookupInvoiceResponse {
+    state: String,
+}
+
+impl LndRestClient {
+    /// Create a client for the LND REST instance at `base_url` (e.g.
+    /// `https://lnd.example.com:8080`), authenticating with `macaroon_hex` (the node's
+    /// `invoice.macaroon`, hex-encoded)
+    pub fn new(base_url: impl Into<String>, macaroon_hex: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into(),
+            macaroon_hex: macaroon_hex.into(),
+            http: reqwest::Client::new(),
+        }
+    }
+
+    /// Mint a new BOLT11 invoice for `amount_msat`, returning the fields needed to
+    /// advertise it in a `PaymentRequired` challenge
+    pub async fn create_invoice(&self, amount_msat: u64, description: &str) -> Result<LightningBolt11Invoice> {
+        let response = self
+            .http
+            .post(format!("{}/v1/invoices", self.base_url))
+            .header("Grpc-Metadata-macaroon", &self.macaroon_hex)
+            .json(&serde_json::json!({
+                "value_msat": amount_msat.to_string(),
+                "memo": description,
+            }))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(X402Error::facilitator_error(format!(
+                "LND invoice creation failed ({}): {}",
+                status, body
+            )));
+        }
+
+        let invoice: AddInvoiceResponse = response.json().await?;
+        let payment_hash = base64_to_hex(&invoice.r_hash)?;
+
+        Ok(LightningBolt11Invoice {
+            bolt11: invoice.payment_request,
+            payment_hash,
+            amount_msat,
+            // LND's add-invoice response doesn't echo an expiry; its default invoice
+            // lifetime is 1 hour, so this client assumes that rather than decoding the
+            // returned `payment_request` just to recover it.
+            expires_at: chrono::Utc::now().timestamp() + 3600,
+            description: description.to_string(),
+        })
+    }
+
+    /// Look up whether the invoice identified by `payment_hash` has been paid, via
+    /// LND's `GET /v1/invoice/{r_hash_str}`
+    pub async fn lookup_invoice(&self, payment_hash: &str) -> Result<InvoiceStatus> {
+        let response = self
+            .http
+            .get(format!("{}/v1/invoice/{}", self.base_url, payment_hash))
+            .header("Grpc-Metadata-macaroon", &self.macaroon_hex)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(X402Error::facilitator_error(format!(
+                "LND invoice lookup failed ({}): {}",
+                status, body
+            )));
+        }
+
+        let invoice: LookupInvoiceResponse = response.json().await?;
+
+        Ok(match invoice.state.as_str() {
+            "SETTLED" => InvoiceStatus::Paid,
+            "CANCELED" => InvoiceStatus::Expired,
+            _ => InvoiceStatus::Pending,
+        })
+    }
+}
+
+impl LightningNodeClient for LndRestClient {
+    fn create_invoice<'a>(
+        &'a self,
+        amount_msat: u64,
+        description: &'a str,
+    ) -> BoxFuture<'a, Result<LightningBolt11Invoice>> {
+        Box::pin(async move { self.create_invoice(amount_msat, description).await })
+    }
+
+    fn lookup_invoice<'a>(&'a self, payment_hash: &'a str) -> BoxFuture<'a, Result<InvoiceStatus>> {
+        Box::pin(async move { self.lookup_invoice(payment_hash).await })
+    }
+}
+
+/// Decode a base64 string (LND's `r_hash` encoding) to the lowercase hex string this
+/// crate's [`LightningBolt11Invoice::payment_hash`] otherwise expects everywhere
+fn base64_to_hex(base64_value: &str) -> Result<String> {
+    use base64::{engine::general_purpose, Engine as _};
+    let bytes = general_purpose::STANDARD
+        .decode(base64_value)
+        .map_err(|_| X402Error::invalid_lightning_invoice("r_hash is not valid base64"))?;
+    Ok(hex::encode(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::{engine::general_purpose, Engine as _};
+
+    #[tokio::test]
+    async fn test_create_invoice_parses_successful_response() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/v1/invoices")
+            .match_header("Grpc-Metadata-macaroon", "deadbeef")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!({
+                    "payment_request": "lnbc10n1invoice",
+                    "r_hash": general_purpose::STANDARD.encode([0xabu8; 32]),
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let client = LndRestClient::new(server.url(), "deadbeef");
+        let invoice = client.create_invoice(1000, "Test resource").await.unwrap();
+
+        assert_eq!(invoice.bolt11, "lnbc10n1invoice");
+        assert_eq!(invoice.payment_hash, "ab".repeat(32));
+        assert_eq!(invoice.amount_msat, 1000);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_create_invoice_surfaces_non_success_status() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/v1/invoices")
+            .with_status(500)
+            .with_body("node unreachable")
+            .create_async()
+            .await;
+
+        let client = LndRestClient::new(server.url(), "deadbeef");
+        let result = client.create_invoice(1000, "Test resource").await;
+        assert!(matches!(result, Err(X402Error::FacilitatorError { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_lookup_invoice_reports_paid_status() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/v1/invoice/abc123")
+            .match_header("Grpc-Metadata-macaroon", "deadbeef")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(serde_json::json!({ "state": "SETTLED" }).to_string())
+            .create_async()
+            .await;
+
+        let client = LndRestClient::new(server.url(), "deadbeef");
+        let status = client.lookup_invoice("abc123").await.unwrap();
+
+        assert_eq!(status, InvoiceStatus::Paid);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_lookup_invoice_reports_pending_status() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/v1/invoice/abc123")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(serde_json::json!({ "state": "OPEN" }).to_string())
+            .create_async()
+            .await;
+
+        let client = LndRestClient::new(server.url(), "deadbeef");
+        let status = client.lookup_invoice("abc123").await.unwrap();
+
+        assert_eq!(status, InvoiceStatus::Pending);
+    }
+}
@@ -1,5 +1,7 @@
 //! Middleware implementations for web frameworks
 
+use crate::lightning::LightningNodeClient;
+use crate::pricing::{PriceOracle, PricingType};
 use crate::types::{Network, *};
 use crate::{Result, X402Error};
 use axum::{
@@ -14,8 +16,98 @@ use std::sync::Arc;
 use tower::ServiceBuilder;
 use tower_http::trace::TraceLayer;
 
-/// Configuration for payment middleware
+/// An additional asset/network/recipient a [`PaymentMiddlewareConfig`] accepts for the
+/// same resource, alongside its primary `amount`/`pay_to`/`testnet` combination, so the
+/// 402 response's `accepts` array can offer a payer a choice instead of one fixed
+/// option; see [`PaymentMiddlewareConfig::with_additional_options`]. Mirrors the
+/// multi-asset `accepts` array the x402 protocol itself defines, letting one endpoint
+/// be paid in whatever asset/chain the payer actually holds.
 #[derive(Debug, Clone)]
+pub struct PaymentOption {
+    /// Payment scheme identifier, e.g. `"exact"`
+    pub scheme: String,
+    /// Blockchain network identifier, e.g. `"base"`, `"avalanche-fuji"`
+    pub network: String,
+    /// Required payment amount in atomic token units
+    pub max_amount_required: String,
+    /// Token contract address
+    pub asset: String,
+    /// Recipient wallet address for this option, which can differ from the config's
+    /// default `pay_to` (e.g. a different custody wallet per chain)
+    pub pay_to: String,
+}
+
+impl PaymentOption {
+    /// Build an arbitrary option: any scheme, network, asset and amount, for a
+    /// stablecoin other than USDC or an asset with different decimals
+    pub fn new(
+        scheme: impl Into<String>,
+        network: impl Into<String>,
+        max_amount_required: impl Into<String>,
+        asset: impl Into<String>,
+        pay_to: impl Into<String>,
+    ) -> Self {
+        Self {
+            scheme: scheme.into(),
+            network: network.into(),
+            max_amount_required: max_amount_required.into(),
+            asset: asset.into(),
+            pay_to: pay_to.into(),
+        }
+    }
+
+    /// Build a `"exact"`-scheme USDC option, resolving the USDC contract address for
+    /// `network` the same way [`PaymentMiddlewareConfig::create_payment_requirements`]
+    /// resolves its own default option
+    pub fn usdc(network: impl Into<String>, amount: Decimal, pay_to: impl Into<String>) -> Result<Self> {
+        let network = network.into();
+        let asset = networks::get_usdc_address(&network).ok_or_else(|| X402Error::NetworkNotSupported {
+            network: network.clone(),
+        })?;
+        let max_amount_required = (amount * Decimal::from(1_000_000u64)).normalize().to_string();
+
+        Ok(Self::new(schemes::EXACT, network, max_amount_required, asset, pay_to))
+    }
+}
+
+/// A Lightning node a route is priced against, so [`PaymentMiddlewareConfig::
+/// create_lightning_payment_requirements`] can mint a fresh BOLT11 invoice per request
+/// instead of requiring one to be pre-minted out of band; see
+/// [`PaymentMiddlewareConfig::with_lightning_config`]
+#[derive(Clone)]
+pub struct LightningConfig {
+    /// The node's invoice-minting REST API, behind [`LightningNodeClient`] so either
+    /// [`crate::lightning::cln::CoreLightningClient`] or
+    /// [`crate::lightning::lnd::LndRestClient`] can be plugged in
+    pub node: Arc<dyn LightningNodeClient>,
+    /// Recipient identity recorded on the resulting [`PaymentRequirements`]; Lightning
+    /// has no on-chain recipient address, so this is typically the node's own alias or
+    /// left empty
+    pub pay_to: String,
+}
+
+impl LightningConfig {
+    /// Price this route against `node`, recording `pay_to` as the requirements'
+    /// recipient
+    pub fn new(node: Arc<dyn LightningNodeClient>, pay_to: impl Into<String>) -> Self {
+        Self {
+            node,
+            pay_to: pay_to.into(),
+        }
+    }
+}
+
+impl std::fmt::Debug for LightningConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LightningConfig")
+            .field("node", &"<node>")
+            .field("pay_to", &self.pay_to)
+            .finish()
+    }
+}
+
+/// Configuration for payment middleware
+#[derive(Clone)]
 pub struct PaymentMiddlewareConfig {
     /// Payment amount in decimal units (e.g., 0.0001 for 1/10th of a cent)
     pub amount: Decimal,
@@ -31,7 +123,14 @@ pub struct PaymentMiddlewareConfig {
     pub output_schema: Option<serde_json::Value>,
     /// Facilitator configuration
     pub facilitator_config: FacilitatorConfig,
-    /// Whether this is a testnet
+    /// Network the primary `amount`/`pay_to` option is priced against; see
+    /// [`Self::with_network`]. `testnet` below stays in sync with this and exists only
+    /// for callers still on the old bool toggle.
+    pub network: SupportedNetwork,
+    /// Whether this is a testnet; a thin compatibility shim over `network` for callers
+    /// using the old `with_testnet(bool)` toggle. Kept in sync by both
+    /// [`Self::with_testnet`] and [`Self::with_network`] — prefer `network` directly in
+    /// new code, since it can express chains beyond Base.
     pub testnet: bool,
     /// Custom paywall HTML for web browsers
     pub custom_paywall_html: Option<String>,
@@ -39,6 +138,38 @@ pub struct PaymentMiddlewareConfig {
     pub resource: Option<String>,
     /// Resource root URL for constructing full resource URLs
     pub resource_root_url: Option<String>,
+    /// Scopes granted to a request once its payment is verified, surfaced to
+    /// downstream handlers via [`crate::types::PaymentContext`]
+    pub scopes: Vec<String>,
+    /// Scopes a verified payment must grant, checked against `scopes` after
+    /// verification; a payment missing any of these gets a 403 instead of proceeding
+    pub required_scopes: Vec<String>,
+    /// URL to POST the settlement response to once settlement succeeds, so a caller
+    /// that disconnected before the response arrived can still be notified
+    pub notify_uri: Option<String>,
+    /// URL a browser paywall should redirect to after payment; surfaced in the
+    /// `accepts` entry's `extra` field so clients don't need a side channel to learn it
+    pub continue_uri: Option<String>,
+    /// URL the *facilitator* should POST its final result to when it settles this
+    /// payment asynchronously, surfaced in `extra` the same way `continue_uri` is; see
+    /// [`crate::async_settlement`]. Unlike `notify_uri` above (which this server calls
+    /// out to once settlement succeeds), this is a URL this server exposes for the
+    /// facilitator to call back into — typically mounted with
+    /// [`crate::axum::settlement_notification_route`].
+    pub async_settlement_notify_uri: Option<String>,
+    /// Extra assets/networks this endpoint also accepts, alongside the primary
+    /// `amount`/`pay_to`/`testnet` combination; see [`Self::with_additional_options`]
+    pub additional_options: Vec<PaymentOption>,
+    /// Whether `amount` is already in token units or must be converted from fiat via
+    /// `price_oracle`; see [`Self::with_amount_currency`]
+    pub pricing_type: PricingType,
+    /// Quotes the `amount_currency` → token rate for [`PricingType::Converted`]
+    /// pricing; only consulted by [`Self::create_payment_requirements_priced`], not by
+    /// the synchronous [`Self::create_payment_requirements`]
+    pub price_oracle: Option<Arc<dyn PriceOracle>>,
+    /// Lightning node this route is priced against, consulted only by
+    /// [`Self::create_lightning_payment_requirements`]; see [`Self::with_lightning_config`]
+    pub lightning: Option<LightningConfig>,
 }
 
 impl PaymentMiddlewareConfig {
@@ -52,10 +183,20 @@ impl PaymentMiddlewareConfig {
             max_timeout_seconds: 60,
             output_schema: None,
             facilitator_config: FacilitatorConfig::default(),
+            network: SupportedNetwork::BaseSepolia,
             testnet: true,
             custom_paywall_html: None,
             resource: None,
             resource_root_url: None,
+            scopes: Vec::new(),
+            required_scopes: Vec::new(),
+            notify_uri: None,
+            continue_uri: None,
+            async_settlement_notify_uri: None,
+            additional_options: Vec::new(),
+            pricing_type: PricingType::default(),
+            price_oracle: None,
+            lightning: None,
         }
     }
 
@@ -89,12 +230,28 @@ impl PaymentMiddlewareConfig {
         self
     }
 
-    /// Set whether this is a testnet
+    /// Set whether this is a testnet; a thin compatibility shim over [`Self::with_network`]
+    /// that can only choose between Base mainnet and Base Sepolia. Prefer
+    /// [`Self::with_network`] directly for any other chain.
     pub fn with_testnet(mut self, testnet: bool) -> Self {
+        self.network = if testnet {
+            SupportedNetwork::BaseSepolia
+        } else {
+            SupportedNetwork::BaseMainnet
+        };
         self.testnet = testnet;
         self
     }
 
+    /// Price the primary `amount`/`pay_to` option against `network`, deriving its
+    /// asset contract, chain id and token decimals from [`SupportedNetwork::config`]
+    /// instead of the Base-only `testnet` toggle
+    pub fn with_network(mut self, network: SupportedNetwork) -> Self {
+        self.testnet = network.is_testnet();
+        self.network = network;
+        self
+    }
+
     /// Set custom paywall HTML
     pub fn with_custom_paywall_html(mut self, html: impl Into<String>) -> Self {
         self.custom_paywall_html = Some(html.into());
@@ -113,18 +270,77 @@ impl PaymentMiddlewareConfig {
         self
     }
 
-    /// Create payment requirements from this config
-    pub fn create_payment_requirements(&self, request_uri: &str) -> Result<PaymentRequirements> {
-        let network = if self.testnet {
-            networks::BASE_SEPOLIA
-        } else {
-            networks::BASE_MAINNET
+    /// Set the scopes granted to a request once its payment is verified
+    pub fn with_scopes(mut self, scopes: Vec<String>) -> Self {
+        self.scopes = scopes;
+        self
+    }
+
+    /// Require a verified payment to grant every one of `scopes`, or be rejected
+    /// with a 403 instead of reaching the handler
+    pub fn with_required_scopes(mut self, scopes: Vec<String>) -> Self {
+        self.required_scopes = scopes;
+        self
+    }
+
+    /// Set the webhook URL notified with the settlement response once settlement succeeds
+    pub fn with_notify_uri(mut self, notify_uri: impl Into<String>) -> Self {
+        self.notify_uri = Some(notify_uri.into());
+        self
+    }
+
+    /// Set the URL a browser paywall should redirect to after payment
+    pub fn with_continue_uri(mut self, continue_uri: impl Into<String>) -> Self {
+        self.continue_uri = Some(continue_uri.into());
+        self
+    }
+
+    /// Set the URL the facilitator should POST its final result to for a payment
+    /// settled asynchronously; see [`Self::async_settlement_notify_uri`]
+    pub fn with_async_settlement_notify_uri(
+        mut self,
+        async_settlement_notify_uri: impl Into<String>,
+    ) -> Self {
+        self.async_settlement_notify_uri = Some(async_settlement_notify_uri.into());
+        self
+    }
+
+    /// Accept `options` in addition to the primary `amount`/`pay_to`/`testnet`
+    /// combination, so [`Self::accepted_payment_requirements`] offers a payer a
+    /// choice of asset/network instead of one fixed option
+    pub fn with_additional_options(mut self, options: Vec<PaymentOption>) -> Self {
+        self.additional_options = options;
+        self
+    }
+
+    /// Price this route in `currency` (e.g. `"usd"`) instead of raw token units;
+    /// `amount` is then read as a `currency` amount and converted to token units via
+    /// [`Self::price_oracle`] by [`Self::create_payment_requirements_priced`]. Requires
+    /// also calling [`Self::with_price_oracle`], or requirements generation fails.
+    pub fn with_amount_currency(mut self, currency: impl Into<String>) -> Self {
+        self.pricing_type = PricingType::Converted {
+            currency: currency.into(),
         };
+        self
+    }
+
+    /// Set the oracle consulted for [`PricingType::Converted`] pricing
+    pub fn with_price_oracle(mut self, oracle: Arc<dyn PriceOracle>) -> Self {
+        self.price_oracle = Some(oracle);
+        self
+    }
+
+    /// Price this route against a Lightning node, so
+    /// [`Self::create_lightning_payment_requirements`] can mint a fresh BOLT11 invoice
+    /// per request instead of one fixed EVM option
+    pub fn with_lightning_config(mut self, lightning: LightningConfig) -> Self {
+        self.lightning = Some(lightning);
+        self
+    }
 
-        let usdc_address =
-            networks::get_usdc_address(network).ok_or_else(|| X402Error::NetworkNotSupported {
-                network: network.to_string(),
-            })?;
+    /// Create payment requirements from this config
+    pub fn create_payment_requirements(&self, request_uri: &str) -> Result<PaymentRequirements> {
+        let network_config = self.network.config();
 
         let resource = if let Some(ref resource_url) = self.resource {
             resource_url.clone()
@@ -134,15 +350,15 @@ impl PaymentMiddlewareConfig {
             request_uri.to_string()
         };
 
-        let max_amount_required = (self.amount * Decimal::from(1_000_000u64))
+        let max_amount_required = (self.amount * Decimal::from(10u64.pow(network_config.decimals as u32)))
             .normalize()
             .to_string();
 
         let mut requirements = PaymentRequirements::new(
             schemes::EXACT,
-            network,
+            &network_config.name,
             max_amount_required,
-            usdc_address,
+            &network_config.usdc_contract,
             &self.pay_to,
             resource,
             self.description.as_deref().unwrap_or("Payment required"),
@@ -152,23 +368,336 @@ impl PaymentMiddlewareConfig {
         requirements.output_schema = self.output_schema.clone();
         requirements.max_timeout_seconds = self.max_timeout_seconds;
 
-        let network = if self.testnet {
+        let network = if network_config.is_testnet {
             Network::Testnet
         } else {
             Network::Mainnet
         };
         requirements.set_usdc_info(network)?;
 
+        if let Some(ref continue_uri) = self.continue_uri {
+            let mut extra = requirements
+                .extra
+                .take()
+                .unwrap_or_else(|| serde_json::json!({}));
+            extra["continueUri"] = serde_json::Value::String(continue_uri.clone());
+            requirements.extra = Some(extra);
+        }
+
+        if let Some(ref async_notify_uri) = self.async_settlement_notify_uri {
+            requirements.set_async_settlement_notify_uri(async_notify_uri.clone())?;
+        }
+
+        Ok(requirements)
+    }
+
+    /// Like [`Self::create_payment_requirements`], but when [`Self::pricing_type`] is
+    /// [`PricingType::Converted`], fetches a rate from [`Self::price_oracle`] and reprices
+    /// the primary option's `max_amount_required` as `amount * rate`, recording the
+    /// quoted rate and its expiry in the resulting [`PaymentRequirements::extra`].
+    ///
+    /// A separate, `async` method rather than a change to [`Self::create_payment_requirements`]'s
+    /// signature, since fetching a quote requires an `.await` that would otherwise ripple
+    /// into every synchronous caller of that method. [`PricingType::Fixed`] (the default)
+    /// behaves identically to calling [`Self::create_payment_requirements`] directly.
+    pub async fn create_payment_requirements_priced(&self, request_uri: &str) -> Result<PaymentRequirements> {
+        let currency = match &self.pricing_type {
+            PricingType::Fixed => return self.create_payment_requirements(request_uri),
+            PricingType::Converted { currency } => currency,
+        };
+
+        let network_config = self.network.config();
+
+        let oracle = self.price_oracle.as_ref().ok_or_else(|| {
+            X402Error::config("amount_currency is set but no price_oracle is configured")
+        })?;
+
+        let quote = oracle.quote(currency, &network_config.name).await?;
+
+        let mut requirements = self.create_payment_requirements(request_uri)?;
+
+        let token_amount = (self.amount * quote.rate * Decimal::from(10u64.pow(network_config.decimals as u32)))
+            .round()
+            .normalize();
+        requirements.max_amount_required = token_amount.to_string();
+
+        let mut extra = requirements
+            .extra
+            .take()
+            .unwrap_or_else(|| serde_json::json!({}));
+        extra["quotedCurrency"] = serde_json::Value::String(currency.clone());
+        extra["quotedRate"] = serde_json::Value::String(quote.rate.to_string());
+        extra["quoteExpiresAt"] = serde_json::Value::Number(quote.expires_at.into());
+        requirements.extra = Some(extra);
+
         Ok(requirements)
     }
+
+    /// Build every [`PaymentRequirements`] this config accepts for `request_uri`: the
+    /// primary option from [`Self::create_payment_requirements`], followed by one
+    /// entry per [`Self::additional_options`], in the order they were given. Used to
+    /// populate the 402 response's `accepts` array with more than one asset/network a
+    /// payer can choose from, instead of a single fixed combination.
+    pub async fn accepted_payment_requirements(&self, request_uri: &str) -> Result<Vec<PaymentRequirements>> {
+        let mut accepted = vec![self.create_payment_requirements_priced(request_uri).await?];
+        if self.additional_options.is_empty() {
+            return Ok(accepted);
+        }
+
+        let resource = if let Some(ref resource_url) = self.resource {
+            resource_url.clone()
+        } else if let Some(ref root_url) = self.resource_root_url {
+            format!("{}{}", root_url, request_uri)
+        } else {
+            request_uri.to_string()
+        };
+
+        for option in &self.additional_options {
+            let mut requirements = PaymentRequirements::new(
+                option.scheme.clone(),
+                option.network.clone(),
+                option.max_amount_required.clone(),
+                option.asset.clone(),
+                option.pay_to.clone(),
+                resource.clone(),
+                self.description.as_deref().unwrap_or("Payment required"),
+            );
+
+            requirements.mime_type = self.mime_type.clone();
+            requirements.output_schema = self.output_schema.clone();
+            requirements.max_timeout_seconds = self.max_timeout_seconds;
+
+            // Only an asset this crate recognizes as the network's USDC gets the
+            // usual token-name/version hint; a different stablecoin's `extra` is left
+            // for the caller to populate themselves, rather than mislabeling it.
+            if networks::get_usdc_address(&option.network) == Some(option.asset.as_str()) {
+                if let Some(network_config) = NetworkConfig::from_name(&option.network) {
+                    let network = if network_config.is_testnet {
+                        Network::Testnet
+                    } else {
+                        Network::Mainnet
+                    };
+                    requirements.set_usdc_info(network)?;
+                }
+            }
+
+            if let Some(ref continue_uri) = self.continue_uri {
+                let mut extra = requirements
+                    .extra
+                    .take()
+                    .unwrap_or_else(|| serde_json::json!({}));
+                extra["continueUri"] = serde_json::Value::String(continue_uri.clone());
+                requirements.extra = Some(extra);
+            }
+
+            if let Some(ref async_notify_uri) = self.async_settlement_notify_uri {
+                requirements.set_async_settlement_notify_uri(async_notify_uri.clone())?;
+            }
+
+            accepted.push(requirements);
+        }
+
+        Ok(accepted)
+    }
+
+    /// Build the [`PaymentRequirements`] for a Lightning-priced route: mint a fresh
+    /// BOLT11 invoice for `amount` (read directly as millisatoshis, with no token
+    /// decimal scaling) against [`Self::lightning`]'s node, and wrap it via
+    /// [`crate::lightning::build_bolt11_requirements`].
+    ///
+    /// A separate method rather than another [`PricingType`], since a Lightning route
+    /// produces `network: "lightning"`/`scheme: "lightning-bolt11"` requirements
+    /// entirely unlike the EVM `exact` options the rest of this config builds — there's
+    /// no single amount-scaling rule that covers both. Resolving which of this method,
+    /// [`Self::create_payment_requirements_priced`], or [`Self::accepted_payment_requirements`]
+    /// a given request should use is left to the caller; wiring that choice into
+    /// [`PaymentMiddleware::process_payment`]'s dispatch is out of scope here, since
+    /// verifying a settled Lightning payment means polling the node
+    /// ([`crate::lightning::verify_lightning_bolt11_invoice_paid`]) instead of calling a
+    /// facilitator, a second dispatch path `process_payment` doesn't have yet.
+    pub async fn create_lightning_payment_requirements(
+        &self,
+        request_uri: &str,
+    ) -> Result<PaymentRequirements> {
+        let lightning = self
+            .lightning
+            .as_ref()
+            .ok_or_else(|| X402Error::config("with_lightning_config was not called"))?;
+
+        let resource = if let Some(ref resource_url) = self.resource {
+            resource_url.clone()
+        } else if let Some(ref root_url) = self.resource_root_url {
+            format!("{}{}", root_url, request_uri)
+        } else {
+            request_uri.to_string()
+        };
+
+        let amount_msat: u64 = self
+            .amount
+            .round()
+            .to_string()
+            .parse()
+            .map_err(|_| X402Error::config("amount does not fit a u64 millisatoshi amount"))?;
+        let description = self.description.as_deref().unwrap_or("Payment required");
+
+        let invoice = lightning.node.create_invoice(amount_msat, description).await?;
+
+        crate::lightning::build_bolt11_requirements(&invoice, &lightning.pay_to, resource)
+    }
+}
+
+impl std::fmt::Debug for PaymentMiddlewareConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PaymentMiddlewareConfig")
+            .field("amount", &self.amount)
+            .field("pay_to", &self.pay_to)
+            .field("description", &self.description)
+            .field("mime_type", &self.mime_type)
+            .field("max_timeout_seconds", &self.max_timeout_seconds)
+            .field("output_schema", &self.output_schema)
+            .field("facilitator_config", &self.facilitator_config)
+            .field("network", &self.network)
+            .field("testnet", &self.testnet)
+            .field("custom_paywall_html", &self.custom_paywall_html)
+            .field("resource", &self.resource)
+            .field("resource_root_url", &self.resource_root_url)
+            .field("scopes", &self.scopes)
+            .field("required_scopes", &self.required_scopes)
+            .field("notify_uri", &self.notify_uri)
+            .field("continue_uri", &self.continue_uri)
+            .field("async_settlement_notify_uri", &self.async_settlement_notify_uri)
+            .field("additional_options", &self.additional_options)
+            .field("pricing_type", &self.pricing_type)
+            .field("price_oracle", &self.price_oracle.is_some())
+            .field("lightning", &self.lightning)
+            .finish()
+    }
 }
 
 /// Axum middleware for x402 payments
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct PaymentMiddleware {
     pub config: Arc<PaymentMiddlewareConfig>,
     pub facilitator: Option<crate::facilitator::FacilitatorClient>,
     pub template_config: Option<crate::template::PaywallConfig>,
+    /// Background settlement queue used by the Tower [`PaymentService`], so a transient
+    /// facilitator error doesn't silently drop an already-verified payment
+    pub settlement_queue: Option<crate::settlement::SettlementQueue>,
+    /// Routes verify/settle to a different facilitator per network, overriding
+    /// `facilitator` when the payment's network has a dedicated entry
+    pub facilitator_router: Option<crate::facilitator::FacilitatorRouter>,
+    /// Tries an ordered list of redundant facilitators, falling back to the next
+    /// one on a transient error; takes priority over `facilitator_router` and
+    /// `facilitator` when configured
+    pub facilitator_chain: Option<Arc<crate::facilitator::FacilitatorFallbackChain>>,
+    /// Routes verify/settle to the fallback chain registered for a payment's
+    /// `(scheme, network)` pairing, set via [`Self::with_keyed_facilitators`]; takes
+    /// priority over `facilitator_chain`, `facilitator_router` and `facilitator` when
+    /// configured, but not over a registered [`crate::scheme_registry::SchemeHandler`]
+    pub facilitator_keyed: Option<Arc<crate::facilitator::KeyedFacilitatorChain>>,
+    /// Routes verify/settle to whichever backend in a
+    /// [`crate::facilitator::FacilitatorRegistry`] declares support for a payment's
+    /// `(network, scheme)` pair, set via [`Self::with_facilitator_registry`]; unlike
+    /// `facilitator_keyed`, each backend is an arbitrary [`crate::facilitator::Facilitator`]
+    /// rather than one built from [`crate::facilitator::FacilitatorConfig`], so a
+    /// self-hosted or otherwise non-HTTP provider can sit alongside the built-in CDP
+    /// facilitator. Takes priority over `facilitator_keyed`, `facilitator_chain`,
+    /// `facilitator_router` and `facilitator` when configured, but not over a registered
+    /// [`crate::scheme_registry::SchemeHandler`]
+    pub facilitator_registry: Option<Arc<crate::facilitator::FacilitatorRegistry>>,
+    /// Notified with the settlement response after a successful settlement, when
+    /// `config.notify_uri` is set
+    pub webhook_dispatcher: Option<crate::settlement::WebhookDispatcher>,
+    /// Backoff schedule used to retry the resolved facilitator's `verify`/`settle`
+    /// calls, classifying errors via [`crate::X402Error::is_retryable`]; only applies
+    /// to the plain `facilitator`/`facilitator_router` path, not `facilitator_chain`
+    /// or a registered [`crate::scheme_registry::SchemeHandler`], which have their own
+    /// resilience semantics
+    pub retry_policy: Option<crate::facilitator::RetryableFacilitatorPolicy>,
+    /// Idempotency store settle retries key against, so a settle retried under
+    /// `retry_policy` never posts the same authorized transfer twice
+    pub idempotency_store: Option<Arc<dyn crate::idempotency::IdempotencyStore>>,
+    /// Attempts a payment gets, across separate `settle` calls, before
+    /// [`crate::idempotency::IdempotentSettlement`] abandons it and surfaces
+    /// [`crate::X402Error::SettlementAbandoned`]; `None` uses
+    /// [`crate::idempotency::DEFAULT_MAX_SETTLEMENT_ATTEMPTS`]. Only takes effect
+    /// alongside `idempotency_store`.
+    pub settlement_max_attempts: Option<u32>,
+    /// How long a resolved facilitator's `/supported` document is trusted before
+    /// [`Self::verify_with_requirements`]/[`Self::settle_with_requirements_tracked`]
+    /// re-query it; `None` skips the capability check entirely
+    pub capability_cache_ttl: Option<std::time::Duration>,
+    /// One [`crate::facilitator::CachedFacilitator`] per network the plain
+    /// `facilitator`/`facilitator_router` path has resolved, so the `/supported`
+    /// negotiation in [`Self::ensure_facilitator_supports`] costs one round trip per
+    /// network rather than one per request
+    pub(crate) capability_caches:
+        Arc<tokio::sync::Mutex<std::collections::HashMap<String, Arc<crate::facilitator::CachedFacilitator>>>>,
+    /// Tracks settlements [`Self::settle_with_requirements_async`] reported as
+    /// [`crate::async_settlement::SettlementOutcome::Pending`] until the facilitator's
+    /// notification resolves them; `None` disables async settlement, so a `"pending:"`
+    /// `error_reason` is surfaced as an ordinary settlement failure instead
+    pub pending_settlements: Option<Arc<dyn crate::async_settlement::PendingSettlementStore>>,
+    /// Independently confirms a settlement on-chain instead of trusting the
+    /// facilitator's `SettleResponse` at face value; see
+    /// [`Self::settle_with_requirements_verified`]
+    pub onchain_verifier: Option<Arc<crate::onchain_verification::OnchainSettlementVerifier>>,
+    /// Records every successful settlement for batched accounting; see
+    /// [`Self::with_ledger`]
+    pub ledger: Option<Arc<crate::accounting::PaymentLedger>>,
+    /// Rejects an authorization whose nonce has already been consumed, checked
+    /// against [`Self::process_payment`]'s decoded payload before it ever reaches a
+    /// facilitator; see [`Self::with_nonce_store`]
+    pub nonce_store: Option<Arc<dyn crate::nonce_store::NonceStore>>,
+    /// Rejects a `(payer, nonce)` pair re-presented within its authorization's own
+    /// `validBefore` window, distinct from [`Self::nonce_store`]'s network-scoped,
+    /// settle-time check; see [`Self::with_nonce_replay_store`]
+    pub nonce_replay_store: Option<Arc<dyn crate::nonce_store::NonceReplayStore>>,
+    /// Tracks each issued requirements set through `Pending -> Confirming -> Settled`
+    /// (or `Expired`), rejecting a request against requirements this server issued too
+    /// long ago even if the authorization's own `validBefore` window hasn't closed; see
+    /// [`Self::with_lifecycle_tracker`]
+    pub lifecycle_tracker: Option<Arc<crate::payment_lifecycle::PaymentLifecycleTracker>>,
+    /// Extra slack applied on both ends of an authorization's `validAfter`/
+    /// `validBefore` window before [`X402Error::AuthorizationNotYetValid`]/
+    /// [`X402Error::AuthorizationExpired`] is raised, absorbing clock drift between
+    /// this server and whatever clock the client's wallet signed the authorization
+    /// against; zero by default, matching the unadjusted window check this crate
+    /// always did before [`Self::with_clock_skew_tolerance`] existed
+    pub clock_skew_tolerance: std::time::Duration,
+    /// Notified with a [`crate::payment_events::PaymentEvent`] at each branch
+    /// [`Self::process_payment`] takes, so metrics/audit logs/webhooks can be driven
+    /// off the payment lifecycle without forking this middleware; see
+    /// [`Self::with_observer`]
+    pub observer: Arc<dyn crate::payment_events::PaymentObserver>,
+}
+
+impl std::fmt::Debug for PaymentMiddleware {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PaymentMiddleware")
+            .field("config", &self.config)
+            .field("facilitator", &self.facilitator)
+            .field("template_config", &self.template_config)
+            .field("settlement_queue", &self.settlement_queue)
+            .field("facilitator_router", &self.facilitator_router)
+            .field("facilitator_chain", &self.facilitator_chain)
+            .field("facilitator_keyed", &self.facilitator_keyed)
+            .field("facilitator_registry", &self.facilitator_registry)
+            .field("webhook_dispatcher", &self.webhook_dispatcher)
+            .field("retry_policy", &self.retry_policy)
+            .field("idempotency_store", &self.idempotency_store.is_some())
+            .field("settlement_max_attempts", &self.settlement_max_attempts)
+            .field("capability_cache_ttl", &self.capability_cache_ttl)
+            .field("pending_settlements", &self.pending_settlements.is_some())
+            .field("onchain_verifier", &self.onchain_verifier.is_some())
+            .field("ledger", &self.ledger.is_some())
+            .field("nonce_store", &self.nonce_store.is_some())
+            .field("nonce_replay_store", &self.nonce_replay_store.is_some())
+            .field("lifecycle_tracker", &self.lifecycle_tracker.is_some())
+            .field("clock_skew_tolerance", &self.clock_skew_tolerance)
+            .field("observer", &"<observer>")
+            .finish()
+    }
 }
 
 /// Payment processing result
@@ -194,6 +723,25 @@ impl PaymentMiddleware {
             config: Arc::new(PaymentMiddlewareConfig::new(amount, pay_to)),
             facilitator: None,
             template_config: None,
+            settlement_queue: None,
+            facilitator_router: None,
+            facilitator_chain: None,
+            facilitator_keyed: None,
+            facilitator_registry: None,
+            webhook_dispatcher: None,
+            retry_policy: None,
+            idempotency_store: None,
+            settlement_max_attempts: None,
+            capability_cache_ttl: None,
+            capability_caches: Arc::new(tokio::sync::Mutex::new(std::collections::HashMap::new())),
+            pending_settlements: None,
+            onchain_verifier: None,
+            ledger: None,
+            nonce_store: None,
+            nonce_replay_store: None,
+            lifecycle_tracker: None,
+            clock_skew_tolerance: std::time::Duration::ZERO,
+            observer: Arc::new(crate::payment_events::NoopObserver),
         }
     }
 
@@ -227,9 +775,26 @@ impl PaymentMiddleware {
         self
     }
 
-    /// Set whether this is a testnet
+    /// Set whether this is a testnet; a thin compatibility shim over
+    /// [`Self::with_network`]. Prefer [`Self::with_network`] directly for any chain
+    /// other than Base.
     pub fn with_testnet(mut self, testnet: bool) -> Self {
-        Arc::make_mut(&mut self.config).testnet = testnet;
+        let config = Arc::make_mut(&mut self.config);
+        config.network = if testnet {
+            SupportedNetwork::BaseSepolia
+        } else {
+            SupportedNetwork::BaseMainnet
+        };
+        config.testnet = testnet;
+        self
+    }
+
+    /// Price the primary `amount`/`pay_to` option against `network`; see
+    /// [`PaymentMiddlewareConfig::with_network`]
+    pub fn with_network(mut self, network: SupportedNetwork) -> Self {
+        let config = Arc::make_mut(&mut self.config);
+        config.testnet = network.is_testnet();
+        config.network = network;
         self
     }
 
@@ -251,11 +816,32 @@ impl PaymentMiddleware {
         self
     }
 
+    /// Set the scopes granted to a request once its payment is verified
+    pub fn with_scopes(mut self, scopes: Vec<String>) -> Self {
+        Arc::make_mut(&mut self.config).scopes = scopes;
+        self
+    }
+
+    /// Require a verified payment to grant every one of `scopes`, or be rejected
+    /// with a 403 instead of reaching the handler
+    pub fn with_required_scopes(mut self, scopes: Vec<String>) -> Self {
+        Arc::make_mut(&mut self.config).required_scopes = scopes;
+        self
+    }
+
     /// Get the middleware configuration
     pub fn config(&self) -> &PaymentMiddlewareConfig {
         &self.config
     }
 
+    /// Configure the Lightning node backing [`Self::verify_lightning_bolt11_payment`]/
+    /// [`Self::settle_lightning_bolt11_payment`] and
+    /// [`PaymentMiddlewareConfig::create_lightning_payment_requirements`]
+    pub fn with_lightning_config(mut self, lightning: LightningConfig) -> Self {
+        Arc::make_mut(&mut self.config).lightning = Some(lightning);
+        self
+    }
+
     /// Set the facilitator client
     pub fn with_facilitator(mut self, facilitator: crate::facilitator::FacilitatorClient) -> Self {
         self.facilitator = Some(facilitator);
@@ -268,80 +854,648 @@ impl PaymentMiddleware {
         self
     }
 
+    /// Settle through a background [`crate::settlement::SettlementQueue`] instead of a
+    /// single synchronous `settle` call, so the Tower [`PaymentService`] path retries
+    /// transient facilitator failures instead of dropping the payment
+    pub fn with_settlement_queue(mut self, settlement_queue: crate::settlement::SettlementQueue) -> Self {
+        self.settlement_queue = Some(settlement_queue);
+        self
+    }
+
+    /// Route verify/settle to a different facilitator per network
+    pub fn with_facilitator_router(mut self, router: crate::facilitator::FacilitatorRouter) -> Self {
+        self.facilitator_router = Some(router);
+        self
+    }
+
+    /// Try an ordered list of redundant facilitators, falling back to the next
+    /// one on a transient error, instead of a single facilitator or router
+    pub fn with_facilitator_chain(
+        mut self,
+        chain: crate::facilitator::FacilitatorFallbackChain,
+    ) -> Self {
+        self.facilitator_chain = Some(Arc::new(chain));
+        self
+    }
+
+    /// Shorthand for [`Self::with_facilitator_chain`] over a plain list of
+    /// facilitators, each applicable to every network and tried in priority order
+    /// ([`crate::facilitator::RoutingPolicy::Priority`]). Reach for
+    /// [`Self::with_facilitator_chain`] directly to spread load with
+    /// [`crate::facilitator::RoutingPolicy::RoundRobin`] or to restrict entries to
+    /// specific networks via [`crate::facilitator::FacilitatorChainEntry`].
+    pub fn with_facilitators(self, facilitators: Vec<crate::facilitator::FacilitatorClient>) -> Self {
+        self.with_facilitator_chain(crate::facilitator::FacilitatorFallbackChain::new(facilitators))
+    }
+
+    /// Route verify/settle to the fallback chain registered for a payment's
+    /// `(scheme, network)` pairing, instead of a single facilitator or an unkeyed
+    /// [`with_facilitator_chain`](Self::with_facilitator_chain). Lets one deployment
+    /// accept payments across several chains (e.g. Base and Base-Sepolia), each routed
+    /// to its own facilitator(s), and still fall back within a pairing's chain on a
+    /// transient error.
+    pub fn with_keyed_facilitators(
+        mut self,
+        chain: crate::facilitator::KeyedFacilitatorChain,
+    ) -> Self {
+        self.facilitator_keyed = Some(Arc::new(chain));
+        self
+    }
+
+    /// Route verify/settle through a [`crate::facilitator::FacilitatorRegistry`],
+    /// letting each `(network, scheme)` pair be served by its own provider
+    /// implementation — e.g. EVM-USDC through the built-in CDP facilitator while a
+    /// second network goes through a self-hosted one — instead of every payment
+    /// going through the same [`crate::facilitator::FacilitatorConfig`]. Takes
+    /// priority over [`Self::with_keyed_facilitators`], [`Self::with_facilitator_chain`],
+    /// [`Self::with_facilitator_router`] and [`Self::with_facilitator`].
+    pub fn with_facilitator_registry(
+        mut self,
+        registry: crate::facilitator::FacilitatorRegistry,
+    ) -> Self {
+        self.facilitator_registry = Some(Arc::new(registry));
+        self
+    }
+
+    /// Notify `config.notify_uri` with the settlement response after a successful
+    /// settlement, via a background [`crate::settlement::WebhookDispatcher`]
+    pub fn with_webhook_dispatcher(
+        mut self,
+        dispatcher: crate::settlement::WebhookDispatcher,
+    ) -> Self {
+        self.webhook_dispatcher = Some(dispatcher);
+        self
+    }
+
+    /// Retry the resolved facilitator's `verify`/`settle` calls under `policy`,
+    /// classifying errors via [`crate::X402Error::is_retryable`]. If no
+    /// [`with_idempotency_store`](Self::with_idempotency_store) has been set yet, this
+    /// also installs a fresh [`crate::idempotency::InMemoryIdempotencyStore`] so a
+    /// retried settle can't double-post the same authorized transfer.
+    ///
+    /// Only applies to the plain `facilitator`/`facilitator_router` path in
+    /// [`Self::verify_with_requirements`]/[`Self::settle_with_requirements_tracked`] —
+    /// `facilitator_chain` and registered [`crate::scheme_registry::SchemeHandler`]s have
+    /// their own resilience semantics and take priority over this.
+    pub fn with_retry_policy(mut self, policy: crate::facilitator::RetryableFacilitatorPolicy) -> Self {
+        self.retry_policy = Some(policy);
+        if self.idempotency_store.is_none() {
+            self.idempotency_store = Some(Arc::new(
+                crate::idempotency::InMemoryIdempotencyStore::default(),
+            ));
+        }
+        self
+    }
+
+    /// Set the idempotency store settle retries key against, overriding the default
+    /// [`crate::idempotency::InMemoryIdempotencyStore`] installed by
+    /// [`with_retry_policy`](Self::with_retry_policy)
+    pub fn with_idempotency_store(
+        mut self,
+        store: Arc<dyn crate::idempotency::IdempotencyStore>,
+    ) -> Self {
+        self.idempotency_store = Some(store);
+        self
+    }
+
+    /// Override how many attempts, across separate `settle` calls for the same
+    /// payment, [`crate::idempotency::IdempotentSettlement`] allows before abandoning
+    /// it; see [`crate::X402Error::SettlementAbandoned`]. Only takes effect alongside
+    /// [`Self::with_idempotency_store`]/[`Self::with_retry_policy`].
+    pub fn with_settlement_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.settlement_max_attempts = Some(max_attempts);
+        self
+    }
+
+    /// Fail fast on a network/scheme the resolved facilitator doesn't advertise via
+    /// `/supported`, instead of only discovering it after a failed verify round trip.
+    ///
+    /// The facilitator's `/supported` document is cached for `ttl` per network (see
+    /// [`crate::facilitator::CachedFacilitator`]), so this costs one extra round trip
+    /// the first time a network is seen and none after, until the cache expires. Only
+    /// applies to the plain `facilitator`/`facilitator_router` path — a
+    /// `facilitator_chain` or registered [`crate::scheme_registry::SchemeHandler`] has
+    /// already taken priority by the time this would run.
+    pub fn with_capability_cache_ttl(mut self, ttl: std::time::Duration) -> Self {
+        self.capability_cache_ttl = Some(ttl);
+        self
+    }
+
+    /// Enable async settlement: [`Self::settle_with_requirements_async`] tracks a
+    /// `"pending:<id>"` settlement response in `store` instead of surfacing it as a
+    /// failure, until a notification resolves it; see [`crate::async_settlement`]
+    pub fn with_pending_settlements(
+        mut self,
+        store: Arc<dyn crate::async_settlement::PendingSettlementStore>,
+    ) -> Self {
+        self.pending_settlements = Some(store);
+        self
+    }
+
+    /// Independently re-confirm a settlement on-chain instead of trusting the
+    /// facilitator's `SettleResponse` at face value; see
+    /// [`Self::settle_with_requirements_verified`] and [`crate::onchain_verification`]
+    pub fn with_onchain_settlement_verification(
+        mut self,
+        verifier: Arc<crate::onchain_verification::OnchainSettlementVerifier>,
+    ) -> Self {
+        self.onchain_verifier = Some(verifier);
+        self
+    }
+
+    /// Record every successful settlement to `ledger` for batched accounting; see
+    /// [`crate::accounting::PaymentLedger`]
+    pub fn with_ledger(mut self, ledger: Arc<crate::accounting::PaymentLedger>) -> Self {
+        self.ledger = Some(ledger);
+        self
+    }
+
+    /// Reject a replayed authorization nonce against `store` before a decoded payment
+    /// is ever handed to a facilitator for verification, and record a nonce as spent
+    /// once its settlement succeeds; see [`crate::nonce_store::NonceStore`]
+    pub fn with_nonce_store(mut self, store: Arc<dyn crate::nonce_store::NonceStore>) -> Self {
+        self.nonce_store = Some(store);
+        self
+    }
+
+    /// Reject a `(payer, nonce)` pair this authorization's own `validBefore` window has
+    /// already seen, via `store`, before the decoded payment is handed to a
+    /// facilitator; see [`crate::nonce_store::NonceReplayStore`]
+    pub fn with_nonce_replay_store(mut self, store: Arc<dyn crate::nonce_store::NonceReplayStore>) -> Self {
+        self.nonce_replay_store = Some(store);
+        self
+    }
+
+    /// Track every requirements set this server issues through `tracker`, rejecting a
+    /// request whose authorization is still within its own `validBefore` window but
+    /// whose requirements were issued longer ago than their `max_timeout_seconds`; see
+    /// [`crate::payment_lifecycle::PaymentLifecycleTracker`]
+    pub fn with_lifecycle_tracker(
+        mut self,
+        tracker: Arc<crate::payment_lifecycle::PaymentLifecycleTracker>,
+    ) -> Self {
+        self.lifecycle_tracker = Some(tracker);
+        self
+    }
+
+    /// Tolerate `tolerance` worth of clock drift on both ends of an authorization's
+    /// `validAfter`/`validBefore` window, instead of rejecting a payload the instant
+    /// this server's clock disagrees with the signer's
+    pub fn with_clock_skew_tolerance(mut self, tolerance: std::time::Duration) -> Self {
+        self.clock_skew_tolerance = tolerance;
+        self
+    }
+
+    /// Notify `observer` with a [`crate::payment_events::PaymentEvent`] at each branch
+    /// [`Self::process_payment`] takes, instead of the default no-op observer
+    pub fn with_observer(mut self, observer: Arc<dyn crate::payment_events::PaymentObserver>) -> Self {
+        self.observer = observer;
+        self
+    }
+
+    /// Check `network`/`scheme` against the resolved facilitator's cached `/supported`
+    /// document, a no-op when [`with_capability_cache_ttl`](Self::with_capability_cache_ttl)
+    /// hasn't been set
+    async fn ensure_facilitator_supports(
+        &self,
+        network: &str,
+        scheme: &str,
+    ) -> crate::Result<()> {
+        let Some(ttl) = self.capability_cache_ttl else {
+            return Ok(());
+        };
+
+        let cached = {
+            let mut caches = self.capability_caches.lock().await;
+            if let Some(cached) = caches.get(network) {
+                cached.clone()
+            } else {
+                let facilitator = self.resolve_facilitator_for_network(network)?;
+                let cached = Arc::new(crate::facilitator::CachedFacilitator::new(
+                    Arc::new(facilitator),
+                    ttl,
+                ));
+                caches.insert(network.to_string(), cached.clone());
+                cached
+            }
+        };
+
+        cached.ensure_supports(network, scheme).await
+    }
+
+    /// Resolve the facilitator to use for `requirements`, preferring the
+    /// per-network [`crate::facilitator::FacilitatorRouter`] when one is configured
+    fn resolve_facilitator(
+        &self,
+        requirements: &PaymentRequirements,
+    ) -> crate::Result<crate::facilitator::FacilitatorClient> {
+        self.resolve_facilitator_for_network(&requirements.network)
+    }
+
+    /// Like [`Self::resolve_facilitator`], keyed directly by network instead of a full
+    /// [`PaymentRequirements`] — used by [`Self::refund_with_requirements`]/
+    /// [`Self::payout`], which have no payment requirements to resolve against
+    fn resolve_facilitator_for_network(
+        &self,
+        network: &str,
+    ) -> crate::Result<crate::facilitator::FacilitatorClient> {
+        if let Some(router) = &self.facilitator_router {
+            return Ok(router.resolve(network).clone());
+        }
+
+        if let Some(facilitator) = &self.facilitator {
+            return Ok(facilitator.clone());
+        }
+
+        crate::facilitator::FacilitatorClient::new(self.config.facilitator_config.clone())
+    }
+
+    /// Reverse a previously settled payment through the resolved facilitator's
+    /// `/refund` extension (see [`crate::types::ReversalRequest`])
+    ///
+    /// The facilitator is resolved from `original_settlement.network`, the same way
+    /// [`Self::resolve_facilitator`] resolves from a payment's network, and does not
+    /// consult `facilitator_chain` or a registered
+    /// [`crate::scheme_registry::SchemeHandler`] — refunds are a direct facilitator
+    /// call, not a payment being verified or settled.
+    pub async fn refund_with_requirements(
+        &self,
+        original_settlement: &SettleResponse,
+        amount: &str,
+    ) -> crate::Result<crate::types::ReversalResponse> {
+        let facilitator = self.resolve_facilitator_for_network(&original_settlement.network)?;
+        facilitator.refund(original_settlement, amount).await
+    }
+
+    /// Pay `amount` of `asset` out to `destination` on `network`, not tied to any
+    /// prior settlement, through the resolved facilitator's `/payout` extension
+    pub async fn payout(
+        &self,
+        network: &str,
+        destination: &str,
+        amount: &str,
+        asset: &str,
+    ) -> crate::Result<crate::types::ReversalResponse> {
+        let facilitator = self.resolve_facilitator_for_network(network)?;
+        facilitator.payout(destination, amount, asset, network).await
+    }
+
     /// Verify a payment payload
     pub async fn verify(&self, payment_payload: &PaymentPayload) -> bool {
-        // Create facilitator if not already configured
-        let facilitator = if let Some(facilitator) = &self.facilitator {
-            facilitator.clone()
-        } else {
-            match crate::facilitator::FacilitatorClient::new(self.config.facilitator_config.clone())
-            {
-                Ok(facilitator) => facilitator,
-                Err(_) => return false,
-            }
+        let Ok(requirements) = self.config.create_payment_requirements("/") else {
+            return false;
+        };
+        let Ok(facilitator) = self.resolve_facilitator(&requirements) else {
+            return false;
         };
 
-        if let Ok(requirements) = self.config.create_payment_requirements("/") {
-            if let Ok(response) = facilitator.verify(payment_payload, &requirements).await {
-                return response.is_valid;
-            }
+        if let Ok(response) = facilitator.verify(payment_payload, &requirements).await {
+            return response.is_valid;
         }
         false
     }
 
     /// Settle a payment
     pub async fn settle(&self, payment_payload: &PaymentPayload) -> crate::Result<SettleResponse> {
-        // Create facilitator if not already configured
-        let facilitator = if let Some(facilitator) = &self.facilitator {
-            facilitator.clone()
-        } else {
-            crate::facilitator::FacilitatorClient::new(self.config.facilitator_config.clone())?
-        };
-
         let requirements = self.config.create_payment_requirements("/")?;
+        let facilitator = self.resolve_facilitator(&requirements)?;
         facilitator.settle(payment_payload, &requirements).await
     }
 
     /// Verify payment with specific requirements
+    ///
+    /// Rejects an authorization outside its `valid_after`/`valid_before` window (see
+    /// [`crate::types::ExactEvmPayloadAuthorization::check_validity_window`]) before
+    /// trying any of the paths below, so an expired or not-yet-valid `X-PAYMENT` fails
+    /// fast with [`X402Error::AuthorizationExpired`]/[`X402Error::AuthorizationNotYetValid`]
+    /// instead of consuming a facilitator round trip. [`Self::process_payment`] already
+    /// did this before calling the facilitator directly; this closes the same gap for
+    /// callers that use this lower-level method instead.
+    ///
+    /// Dispatches to a registered [`crate::scheme_registry::SchemeHandler`] for
+    /// `requirements.network`/`requirements.scheme` when one is registered, so a scheme
+    /// verified entirely in-process (no facilitator round trip) takes priority over the
+    /// facilitator paths below. Falls back to native facilitator handling when no
+    /// handler matches.
+    ///
+    /// True out-of-process plugins — e.g. loading a third-party `.wasm` module at
+    /// runtime and calling its exports with MessagePack-encoded payloads — would need a
+    /// guest runtime like `wasmtime` that isn't a dependency of this workspace today;
+    /// [`crate::scheme_registry`] is the in-process seam that plays the same role (pick
+    /// a handler by scheme, fall back to native when none matches), and a `wasmtime`-backed
+    /// [`crate::scheme_registry::SchemeHandler`] could be registered behind it without
+    /// this method changing.
     pub async fn verify_with_requirements(
         &self,
         payment_payload: &PaymentPayload,
         requirements: &PaymentRequirements,
     ) -> crate::Result<bool> {
-        let facilitator = if let Some(facilitator) = &self.facilitator {
-            facilitator.clone()
+        payment_payload
+            .payload
+            .authorization
+            .check_validity_window_with_tolerance(self.clock_skew_tolerance)?;
+
+        if let Some(handler) =
+            crate::scheme_registry::resolve_scheme_handler(&requirements.network, &requirements.scheme)
+        {
+            return Ok(handler
+                .verify(payment_payload, requirements)
+                .await?
+                .is_valid);
+        }
+
+        if let Some(registry) = &self.facilitator_registry {
+            let response = registry.verify(payment_payload, requirements).await?;
+            return Ok(response.is_valid);
+        }
+
+        if let Some(chain) = &self.facilitator_keyed {
+            let (_, response) = chain.verify(payment_payload, requirements).await?;
+            return Ok(response.is_valid);
+        }
+
+        if let Some(chain) = &self.facilitator_chain {
+            let (_, response) = chain.verify(payment_payload, requirements).await?;
+            return Ok(response.is_valid);
+        }
+
+        self.ensure_facilitator_supports(&requirements.network, &requirements.scheme)
+            .await?;
+
+        let facilitator = self.resolve_facilitator(requirements)?;
+        let response = if let Some(policy) = &self.retry_policy {
+            let retryable = crate::facilitator::RetryableFacilitator::new(facilitator)
+                .with_policy(policy.clone());
+            retryable.verify(payment_payload, requirements).await?
         } else {
-            crate::facilitator::FacilitatorClient::new(self.config.facilitator_config.clone())?
+            facilitator.verify(payment_payload, requirements).await?
         };
+        Ok(response.is_valid)
+    }
 
-        let response = facilitator.verify(payment_payload, requirements).await?;
+    /// Verify a Lightning BOLT11 payment against `requirements`, the second dispatch
+    /// path [`Self::create_lightning_payment_requirements`]'s doc comment calls out as
+    /// missing: unlike [`Self::verify_with_requirements`], there is no `PaymentPayload`
+    /// to check a signature against, since the client paid the invoice out of band.
+    /// Instead this polls [`Self::config`]'s configured [`LightningConfig::node`] for
+    /// the invoice's settled status via
+    /// [`crate::lightning::verify_lightning_bolt11_invoice_paid`].
+    ///
+    /// Fails with [`X402Error::config`] if [`PaymentMiddlewareConfig::with_lightning_config`]
+    /// was never called; callers that mix EVM and Lightning routes should dispatch to
+    /// this method instead of [`Self::verify_with_requirements`] based on
+    /// `requirements.scheme`, e.g. `crate::types::schemes::LIGHTNING_BOLT11`.
+    ///
+    /// `PaymentPayload.payload` is [`crate::types::SchemePayload`], a scheme-polymorphic
+    /// enum covering EVM, Solana, and both Lightning payload shapes
+    /// (`coinbase/x402#chunk22-1`), so a scheme that needs to check something a client
+    /// sent can match on the variant instead of adding its own one-off dispatch method.
+    /// BOLT11 specifically still dispatches out of band like this because there's
+    /// nothing to match in the first place: the client pays the invoice directly
+    /// against the Lightning node, not by sending a signed payload to this crate, so
+    /// this method polls for settlement instead of verifying a payload field.
+    pub async fn verify_lightning_bolt11_payment(
+        &self,
+        requirements: &PaymentRequirements,
+    ) -> crate::Result<bool> {
+        let lightning = self
+            .config
+            .lightning
+            .as_ref()
+            .ok_or_else(|| X402Error::config("with_lightning_config was not called"))?;
+
+        let response =
+            crate::lightning::verify_lightning_bolt11_invoice_paid(lightning.node.as_ref(), requirements)
+                .await?;
         Ok(response.is_valid)
     }
 
+    /// Settle a Lightning BOLT11 payment verified via [`Self::verify_lightning_bolt11_payment`]
+    ///
+    /// Like that method, polls [`Self::config`]'s configured [`LightningConfig::node`]
+    /// rather than calling a facilitator, via
+    /// [`crate::lightning::settle_lightning_bolt11_invoice_paid`].
+    pub async fn settle_lightning_bolt11_payment(
+        &self,
+        requirements: &PaymentRequirements,
+    ) -> crate::Result<SettleResponse> {
+        let lightning = self
+            .config
+            .lightning
+            .as_ref()
+            .ok_or_else(|| X402Error::config("with_lightning_config was not called"))?;
+
+        crate::lightning::settle_lightning_bolt11_invoice_paid(lightning.node.as_ref(), requirements).await
+    }
+
     /// Settle payment with specific requirements
     pub async fn settle_with_requirements(
         &self,
         payment_payload: &PaymentPayload,
         requirements: &PaymentRequirements,
     ) -> crate::Result<SettleResponse> {
-        let facilitator = if let Some(facilitator) = &self.facilitator {
-            facilitator.clone()
-        } else {
-            crate::facilitator::FacilitatorClient::new(self.config.facilitator_config.clone())?
-        };
-
-        facilitator.settle(payment_payload, requirements).await
+        Ok(self
+            .settle_with_requirements_tracked(payment_payload, requirements)
+            .await?
+            .0)
     }
 
-    /// Process payment with unified flow
-    pub async fn process_payment(
+    /// Settle payment with specific requirements, tolerating a facilitator that cannot
+    /// confirm settlement within this call
+    ///
+    /// Delegates to [`Self::settle_with_requirements`] and, when it reports
+    /// `success: false` with an `error_reason` of the form `"pending:<id>"`, records
+    /// `id` in the configured [`Self::with_pending_settlements`] store and returns
+    /// [`crate::async_settlement::SettlementOutcome::Pending`] instead of treating it
+    /// as a failure. Every other outcome — including a `"pending:"` response with no
+    /// store configured — comes back as [`crate::async_settlement::SettlementOutcome::Final`].
+    pub async fn settle_with_requirements_async(
         &self,
-        request: Request,
-        next: Next,
-    ) -> crate::Result<PaymentResult> {
-        let headers = request.headers();
-        let uri = request.uri().to_string();
-
+        payment_payload: &PaymentPayload,
+        requirements: &PaymentRequirements,
+    ) -> crate::Result<crate::async_settlement::SettlementOutcome> {
+        let response = self
+            .settle_with_requirements(payment_payload, requirements)
+            .await?;
+
+        if let Some(store) = &self.pending_settlements {
+            if let Some(settlement_id) = crate::async_settlement::pending_settlement_id(&response)
+            {
+                let settlement_id = settlement_id.to_string();
+                store.begin(settlement_id.clone()).await;
+                return Ok(crate::async_settlement::SettlementOutcome::Pending { settlement_id });
+            }
+        }
+
+        Ok(crate::async_settlement::SettlementOutcome::Final(response))
+    }
+
+    /// Settle payment with specific requirements, additionally returning the
+    /// base URL of whichever facilitator actually handled the settlement when
+    /// a [`with_facilitator_chain`](Self::with_facilitator_chain) is configured
+    ///
+    /// Like [`Self::verify_with_requirements`], rejects an authorization outside its
+    /// validity window before ever reaching a facilitator, and prefers a registered
+    /// [`crate::scheme_registry::SchemeHandler`] over every facilitator path; see that
+    /// method's doc comment for why this plays the role a `.wasm`-loaded plugin would.
+    /// On the plain facilitator path (no scheme handler, keyed chain, or fallback
+    /// chain configured), a [`Self::with_idempotency_store`] is honored independently
+    /// of [`Self::with_retry_policy`] — repeating the same authorized transfer returns
+    /// the first call's cached [`SettleResponse`] instead of settling it twice.
+    pub async fn settle_with_requirements_tracked(
+        &self,
+        payment_payload: &PaymentPayload,
+        requirements: &PaymentRequirements,
+    ) -> crate::Result<(SettleResponse, Option<String>)> {
+        payment_payload
+            .payload
+            .authorization
+            .check_validity_window_with_tolerance(self.clock_skew_tolerance)?;
+
+        let (response, facilitator_url) = if let Some(handler) =
+            crate::scheme_registry::resolve_scheme_handler(&requirements.network, &requirements.scheme)
+        {
+            (handler.settle(payment_payload, requirements).await?, None)
+        } else if let Some(registry) = &self.facilitator_registry {
+            (registry.settle(payment_payload, requirements).await?, None)
+        } else if let Some(chain) = &self.facilitator_keyed {
+            let (index_url, response) = chain.settle(payment_payload, requirements).await?;
+            (response, index_url)
+        } else if let Some(chain) = &self.facilitator_chain {
+            let (index, response) = chain.settle(payment_payload, requirements).await?;
+            let url = chain.facilitator_url(index).map(|u| u.to_string());
+            (response, url)
+        } else {
+            self.ensure_facilitator_supports(&requirements.network, &requirements.scheme)
+                .await?;
+
+            let facilitator = self.resolve_facilitator(requirements)?;
+            let backend: Arc<dyn crate::facilitator::Facilitator> =
+                if let Some(policy) = &self.retry_policy {
+                    Arc::new(
+                        crate::facilitator::RetryableFacilitator::new(facilitator)
+                            .with_policy(policy.clone()),
+                    )
+                } else {
+                    Arc::new(facilitator)
+                };
+
+            // Idempotency is independent of retries: `with_idempotency_store` on its
+            // own (no `with_retry_policy`) still collapses a client-resubmitted
+            // `X-PAYMENT` header onto the first settlement's result.
+            let response = if let Some(store) = &self.idempotency_store {
+                let mut settlement =
+                    crate::idempotency::IdempotentSettlement::new(backend, store.clone());
+                if let Some(max_attempts) = self.settlement_max_attempts {
+                    settlement = settlement.with_max_attempts(max_attempts);
+                }
+                settlement.settle(payment_payload, requirements).await?
+            } else {
+                backend.settle(payment_payload, requirements).await?
+            };
+            (response, None)
+        };
+
+        if let (Some(dispatcher), Some(notify_uri)) =
+            (&self.webhook_dispatcher, &self.config.notify_uri)
+        {
+            dispatcher.notify(notify_uri.clone(), response.clone());
+        }
+
+        if response.success {
+            if let Some(ledger) = &self.ledger {
+                ledger.record(crate::accounting::PaymentRecord::new(
+                    requirements.resource.clone(),
+                    requirements.pay_to.clone(),
+                    requirements.max_amount_required.clone(),
+                    facilitator_url.clone().unwrap_or_default(),
+                    &response,
+                ));
+            }
+
+            if let Some(store) = &self.nonce_store {
+                store
+                    .mark_used_for_resource(
+                        &requirements.network,
+                        &payment_payload.exact_evm()?.authorization.nonce,
+                        &requirements.resource,
+                    )
+                    .await;
+            }
+
+            // `SettleResponse` carries no confirmation depth of its own — a facilitator
+            // that reports `success: true` is trusted as fully confirmed, matching how
+            // `with_onchain_settlement_verification` above treats the same flag. A
+            // deployment that wants a real confirmation count should drive
+            // `record_confirmation` itself from `crate::settlement_confirmation`.
+            if let Some(tracker) = &self.lifecycle_tracker {
+                let payment_id = crate::idempotency::PaymentId::from_authorization(
+                    &payment_payload.exact_evm()?.authorization,
+                    requirements,
+                );
+                let _ = tracker.record_confirmation(&payment_id, 1).await;
+            }
+        }
+
+        Ok((response, facilitator_url))
+    }
+
+    /// Settle payment with specific requirements, then independently re-confirm the
+    /// settlement on-chain when [`Self::with_onchain_settlement_verification`] is
+    /// configured, instead of trusting the facilitator's `success: true` by itself
+    ///
+    /// Delegates to [`Self::settle_with_requirements_tracked`]. When the facilitator
+    /// reports success and a verifier is configured, fetches the settlement
+    /// transaction's receipt and confirms it contains an ERC-20 `Transfer` from the
+    /// payer to `requirements.pay_to` on `requirements.asset` totaling at least
+    /// `requirements.max_amount_required` (see [`crate::onchain_verification`]); a
+    /// mismatch downgrades the response to `success: false` with a descriptive
+    /// `error_reason` rather than returning an error, matching how a facilitator
+    /// reports its own settlement failures. With no verifier configured, or when the
+    /// facilitator already reported failure, the response passes through unchanged.
+    pub async fn settle_with_requirements_verified(
+        &self,
+        payment_payload: &PaymentPayload,
+        requirements: &PaymentRequirements,
+    ) -> crate::Result<(SettleResponse, Option<String>)> {
+        let (mut response, facilitator_url) = self
+            .settle_with_requirements_tracked(payment_payload, requirements)
+            .await?;
+
+        if response.success {
+            if let Some(verifier) = &self.onchain_verifier {
+                let min_value = requirements
+                    .max_amount_required
+                    .parse::<u128>()
+                    .map_err(|_| {
+                        X402Error::malformed_payload("requirements.max_amount_required")
+                    })?;
+                let expected = crate::onchain_verification::ExpectedTransfer::new(
+                    requirements.asset.clone(),
+                    payment_payload.exact_evm()?.authorization.from.clone(),
+                    requirements.pay_to.clone(),
+                    min_value,
+                );
+
+                if let Err(error) = verifier.verify(&response.transaction, &expected).await {
+                    response.success = false;
+                    response.error_reason = Some(format!("onchain verification failed: {error}"));
+                }
+            }
+        }
+
+        Ok((response, facilitator_url))
+    }
+
+    /// Process payment with unified flow
+    pub async fn process_payment(
+        &self,
+        mut request: Request,
+        next: Next,
+    ) -> crate::Result<PaymentResult> {
+        let headers = request.headers();
+        let uri = request.uri().to_string();
+
         // Check if this is a web browser request
         let user_agent = headers
             .get("User-Agent")
@@ -354,8 +1508,10 @@ impl PaymentMiddleware {
 
         let is_web_browser = accept.contains("text/html") && user_agent.contains("Mozilla");
 
-        // Create payment requirements
-        let payment_requirements = self.config.create_payment_requirements(&uri)?;
+        // Every payment option this endpoint accepts for this resource; the 402 body's
+        // `accepts` array always lists all of them, even when a request is rejected
+        // for a reason specific to whichever one it tried to pay with.
+        let accepted_requirements = self.config.accepted_payment_requirements(&uri).await?;
 
         // Check for payment header
         let payment_header = headers.get("X-PAYMENT").and_then(|v| v.to_str().ok());
@@ -366,15 +1522,181 @@ impl PaymentMiddleware {
                 let payment_payload = PaymentPayload::from_base64(payment_b64).map_err(|e| {
                     X402Error::invalid_payment_payload(format!("Failed to decode payment: {}", e))
                 })?;
+                // Everything below (nonce replay, lifecycle tracking, the authorization
+                // validity window) is specific to the exact-EVM scheme; a Lightning
+                // payment never reaches this `X-PAYMENT`-header flow at all (see
+                // `PaymentMiddleware::verify_lightning_bolt11_payment`).
+                let authorization = &payment_payload.exact_evm()?.authorization;
+
+                // Resolve which accepted option this payload is paying with, since more
+                // than one may be on offer; the facilitator/verifier this request goes
+                // on to use is entirely determined by this choice.
+                let Some(payment_requirements) = accepted_requirements
+                    .iter()
+                    .find(|r| r.scheme == payment_payload.scheme && r.network == payment_payload.network)
+                    .cloned()
+                else {
+                    let error_response = self.create_payment_required_response(
+                        &X402Error::invalid_payment_requirements(
+                            "Payment scheme/network is not one of the accepted options",
+                        ),
+                        &accepted_requirements,
+                        is_web_browser,
+                    )?;
+                    return Ok(PaymentResult::VerificationFailed {
+                        response: error_response,
+                    });
+                };
 
-                // Get facilitator client
-                let facilitator = if let Some(facilitator) = &self.facilitator {
-                    facilitator.clone()
-                } else {
-                    crate::facilitator::FacilitatorClient::new(
-                        self.config.facilitator_config.clone(),
-                    )?
+                // Context threaded through every `PaymentEvent` fired for this request,
+                // so an observer can correlate them without re-deriving a payment id of
+                // its own
+                let event_ctx = crate::payment_events::PaymentEventContext {
+                    resource: payment_requirements.resource.clone(),
+                    network: payment_requirements.network.clone(),
+                    amount: payment_requirements.max_amount_required.clone(),
+                    payment_id: Some(crate::idempotency::PaymentId::from_authorization(
+                        authorization,
+                        &payment_requirements,
+                    )),
                 };
+                self.observer
+                    .on_event(crate::payment_events::PaymentEvent::Requested, &event_ctx)
+                    .await;
+
+                // Reject an expired or not-yet-valid authorization before ever reaching
+                // the facilitator, so a stale X-PAYMENT header fails fast with a
+                // dedicated status instead of bouncing off the facilitator's own check.
+                if let Err(validity_error) =
+                    authorization.check_validity_window_with_tolerance(self.clock_skew_tolerance)
+                {
+                    let event = if matches!(validity_error, X402Error::AuthorizationExpired { .. }) {
+                        crate::payment_events::PaymentEvent::Expired
+                    } else {
+                        crate::payment_events::PaymentEvent::VerificationFailed {
+                            reason: validity_error.to_string(),
+                        }
+                    };
+                    self.observer.on_event(event, &event_ctx).await;
+
+                    let error_response = self.create_payment_required_response(
+                        &validity_error,
+                        &accepted_requirements,
+                        is_web_browser,
+                    )?;
+                    return Ok(PaymentResult::VerificationFailed {
+                        response: error_response,
+                    });
+                }
+
+                // Track this requirements set and reject a request against one issued
+                // too long ago, even though the authorization's own `validBefore`
+                // window above hasn't closed yet; see
+                // [`crate::payment_lifecycle::PaymentLifecycleTracker`].
+                if let Some(tracker) = &self.lifecycle_tracker {
+                    let payment_id = event_ctx
+                        .payment_id
+                        .expect("event_ctx always carries a payment_id");
+                    tracker
+                        .track(payment_id, self.config.max_timeout_seconds)
+                        .await;
+                    if let Err(expiry_error) = tracker.reject_if_expired(&payment_id).await {
+                        self.observer
+                            .on_event(crate::payment_events::PaymentEvent::Expired, &event_ctx)
+                            .await;
+
+                        let error_response = self.create_payment_required_response(
+                            &expiry_error,
+                            &accepted_requirements,
+                            is_web_browser,
+                        )?;
+                        return Ok(PaymentResult::VerificationFailed {
+                            response: error_response,
+                        });
+                    }
+                }
+
+                // Reject a nonce already spent by an earlier settlement against a
+                // *different* resource, so the same X-PAYMENT header submitted
+                // concurrently to many gated endpoints can't ride a single on-chain
+                // authorization past every one of them before the facilitator itself
+                // would catch the reuse. A reuse against the *same* resource is let
+                // through instead of rejected here — it's the shape of a client
+                // retrying its original request, and it's left for
+                // `settle_with_requirements_tracked`'s `IdempotentSettlement` to serve
+                // from its cache rather than resettle.
+                if let Some(store) = &self.nonce_store {
+                    if store
+                        .contains(&payment_requirements.network, &authorization.nonce)
+                        .await
+                    {
+                        let used_resource = store
+                            .resource_for(&payment_requirements.network, &authorization.nonce)
+                            .await;
+                        if used_resource.as_deref() != Some(payment_requirements.resource.as_str()) {
+                            let nonce_error = X402Error::nonce_already_used(authorization.nonce.clone());
+                            self.observer
+                                .on_event(
+                                    crate::payment_events::PaymentEvent::VerificationFailed {
+                                        reason: nonce_error.to_string(),
+                                    },
+                                    &event_ctx,
+                                )
+                                .await;
+
+                            let error_response = self.create_payment_required_response(
+                                &nonce_error,
+                                &accepted_requirements,
+                                is_web_browser,
+                            )?;
+                            return Ok(PaymentResult::VerificationFailed {
+                                response: error_response,
+                            });
+                        }
+                    }
+                }
+
+                // Reject a `(payer, nonce)` pair already reserved within its
+                // authorization's own `validBefore` window. Unlike the `nonce_store`
+                // check above, this runs regardless of resource and fires before
+                // settlement ever happens, closing the window between two concurrent
+                // requests both verifying the same never-yet-settled authorization.
+                if let Some(store) = &self.nonce_replay_store {
+                    let valid_before: i64 = authorization
+                        .valid_before
+                        .parse()
+                        .map_err(|_| X402Error::malformed_payload("validBefore"))?;
+
+                    if let Err(reuse_error) = crate::nonce_store::reject_nonce_reuse(
+                        store.as_ref(),
+                        &authorization.from,
+                        &authorization.nonce,
+                        valid_before,
+                    )
+                    .await
+                    {
+                        self.observer
+                            .on_event(
+                                crate::payment_events::PaymentEvent::VerificationFailed {
+                                    reason: reuse_error.to_string(),
+                                },
+                                &event_ctx,
+                            )
+                            .await;
+
+                        let error_response = self.create_payment_required_response(
+                            &reuse_error,
+                            &accepted_requirements,
+                            is_web_browser,
+                        )?;
+                        return Ok(PaymentResult::VerificationFailed {
+                            response: error_response,
+                        });
+                    }
+                }
+
+                // Get facilitator client, routed per network if a router is configured
+                let facilitator = self.resolve_facilitator(&payment_requirements)?;
 
                 // Verify payment
                 let verify_response = facilitator
@@ -385,37 +1707,139 @@ impl PaymentMiddleware {
                     })?;
 
                 if !verify_response.is_valid {
+                    self.observer
+                        .on_event(
+                            crate::payment_events::PaymentEvent::VerificationFailed {
+                                reason: "Payment verification failed".to_string(),
+                            },
+                            &event_ctx,
+                        )
+                        .await;
+
                     let error_response = self.create_payment_required_response(
-                        "Payment verification failed",
-                        &payment_requirements,
+                        &X402Error::payment_verification_failed("Payment verification failed"),
+                        &accepted_requirements,
+                        is_web_browser,
+                    )?;
+                    return Ok(PaymentResult::VerificationFailed {
+                        response: error_response,
+                    });
+                }
+
+                self.observer
+                    .on_event(crate::payment_events::PaymentEvent::Verified, &event_ctx)
+                    .await;
+
+                // Surface who paid and what they're entitled to, so handlers and
+                // extractors downstream don't have to re-derive it from the raw
+                // payment payload.
+                let payment_context = PaymentContext {
+                    payer: verify_response.payer.clone(),
+                    scheme: payment_requirements.scheme.clone(),
+                    network: payment_requirements.network.clone(),
+                    amount: payment_requirements.max_amount_required.clone(),
+                    scopes: self.config.scopes.clone(),
+                };
+
+                if !payment_context.has_scopes(&self.config.required_scopes) {
+                    self.observer
+                        .on_event(
+                            crate::payment_events::PaymentEvent::VerificationFailed {
+                                reason: "Payment does not grant the required scopes".to_string(),
+                            },
+                            &event_ctx,
+                        )
+                        .await;
+
+                    let mut error_response = self.create_payment_required_response(
+                        &X402Error::payment_verification_failed(
+                            "Payment does not grant the required scopes",
+                        ),
+                        &accepted_requirements,
                         is_web_browser,
                     )?;
+                    *error_response.status_mut() = StatusCode::FORBIDDEN;
                     return Ok(PaymentResult::VerificationFailed {
                         response: error_response,
                     });
                 }
 
+                request.extensions_mut().insert(payment_context);
+
                 // Execute the handler
                 let mut response = next.run(request).await;
 
-                // Settle the payment
-                let settle_response = facilitator
-                    .settle(&payment_payload, &payment_requirements)
-                    .await
-                    .map_err(|e| {
-                        X402Error::facilitator_error(format!("Payment settlement failed: {}", e))
+                // Settle the payment. When a settlement queue is configured, hand the
+                // payment off to it so a transient facilitator failure is retried in the
+                // background instead of failing the response outright.
+                let settle_response = if let Some(queue) = &self.settlement_queue {
+                    if let Err(e) = queue.enqueue(payment_payload.clone(), payment_requirements.clone()) {
+                        let reason = format!("Failed to enqueue payment for settlement: {}", e);
+                        self.observer
+                            .on_event(
+                                crate::payment_events::PaymentEvent::SettlementFailed {
+                                    error: reason.clone(),
+                                },
+                                &event_ctx,
+                            )
+                            .await;
+                        return Err(X402Error::facilitator_error(reason));
+                    }
+                    SettleResponse {
+                        success: true,
+                        error_reason: None,
+                        transaction: String::new(),
+                        network: payment_requirements.network.clone(),
+                        payer: None,
+                    }
+                } else {
+                    // Go through the same facilitator-chain/retry/idempotency-aware
+                    // path as `settle_with_requirements`, rather than calling
+                    // `facilitator.settle` directly — so a proxy configured with
+                    // `with_facilitator_chain`/`with_retry_policy`/`with_idempotency_store`
+                    // actually gets that resilience on the request path, not just
+                    // when a caller reaches for the lower-level method by hand.
+                    let (settle_response, _facilitator_url) = match self
+                        .settle_with_requirements_tracked(&payment_payload, &payment_requirements)
+                        .await
+                    {
+                        Ok(result) => result,
+                        Err(e) => {
+                            let reason = format!("Payment settlement failed: {}", e);
+                            self.observer
+                                .on_event(
+                                    crate::payment_events::PaymentEvent::SettlementFailed {
+                                        error: reason.clone(),
+                                    },
+                                    &event_ctx,
+                                )
+                                .await;
+                            return Err(X402Error::facilitator_error(reason));
+                        }
+                    };
+
+                    // Add settlement header
+                    let settlement_header = settle_response.to_base64().map_err(|e| {
+                        X402Error::config(format!("Failed to encode settlement response: {}", e))
                     })?;
 
-                // Add settlement header
-                let settlement_header = settle_response.to_base64().map_err(|e| {
-                    X402Error::config(format!("Failed to encode settlement response: {}", e))
-                })?;
+                    if let Ok(header_value) = HeaderValue::from_str(&settlement_header) {
+                        response
+                            .headers_mut()
+                            .insert("X-PAYMENT-RESPONSE", header_value);
+                    }
 
-                if let Ok(header_value) = HeaderValue::from_str(&settlement_header) {
-                    response
-                        .headers_mut()
-                        .insert("X-PAYMENT-RESPONSE", header_value);
-                }
+                    settle_response
+                };
+
+                self.observer
+                    .on_event(
+                        crate::payment_events::PaymentEvent::Settled {
+                            settlement: settle_response.clone(),
+                        },
+                        &event_ctx,
+                    )
+                    .await;
 
                 Ok(PaymentResult::Success {
                     response,
@@ -425,8 +1849,8 @@ impl PaymentMiddleware {
             None => {
                 // No payment provided, return 402 with requirements
                 let response = self.create_payment_required_response(
-                    "X-PAYMENT header is required",
-                    &payment_requirements,
+                    &X402Error::payment_verification_failed("X-PAYMENT header is required"),
+                    &accepted_requirements,
                     is_web_browser,
                 )?;
                 Ok(PaymentResult::PaymentRequired { response })
@@ -434,11 +1858,12 @@ impl PaymentMiddleware {
         }
     }
 
-    /// Create payment required response
+    /// Create payment required response, listing every option in `payment_requirements`
+    /// as a choice the client can pay with
     fn create_payment_required_response(
         &self,
-        error: &str,
-        payment_requirements: &PaymentRequirements,
+        error: &X402Error,
+        payment_requirements: &[PaymentRequirements],
         is_web_browser: bool,
     ) -> crate::Result<axum::response::Response> {
         if is_web_browser {
@@ -452,11 +1877,7 @@ impl PaymentMiddleware {
                         .with_app_logo("💰")
                 });
 
-                crate::template::generate_paywall_html(
-                    error,
-                    std::slice::from_ref(payment_requirements),
-                    Some(&paywall_config),
-                )
+                crate::template::render_paywall(&paywall_config, payment_requirements, Some(error))
             };
 
             let response = Response::builder()
@@ -467,8 +1888,15 @@ impl PaymentMiddleware {
 
             Ok(response)
         } else {
-            let payment_response =
-                PaymentRequirementsResponse::new(error, vec![payment_requirements.clone()]);
+            let requirements = payment_requirements
+                .iter()
+                .cloned()
+                .map(|mut r| {
+                    r.payment_uri = r.to_payment_uri().ok();
+                    r
+                })
+                .collect();
+            let payment_response = PaymentRequirementsResponse::from_error(error, requirements);
 
             Ok(Json(payment_response).into_response())
         }
@@ -525,6 +1953,14 @@ impl<S> tower::Layer<S> for PaymentServiceLayer {
 }
 
 /// Tower service for x402 payment middleware
+///
+/// Feature-equivalent to the Axum [`payment_middleware`] path: a settled payment's
+/// `SettleResponse` is base64-encoded onto the outgoing response's `X-PAYMENT-RESPONSE`
+/// header rather than discarded, whether or not the facilitator itself reported
+/// success. A transport-level settlement failure (as opposed to a `SettleResponse`
+/// reporting `success: false`) is logged and otherwise left for the caller to notice by
+/// the header's absence, since `ResBody` is an arbitrary generic body type this layer
+/// has no general way to replace with an error/receipt body of its own.
 #[derive(Clone)]
 pub struct PaymentService<S> {
     inner: S,
@@ -597,17 +2033,67 @@ where
                             {
                                 Ok(true) => {
                                     // Payment is valid, proceed with request
-                                    let response = future.await?;
-
-                                    // Settle payment after successful response
-                                    if let Ok(settlement) = middleware
-                                        .settle_with_requirements(&payment_payload, &requirements)
-                                        .await
-                                    {
-                                        // Note: In a real implementation, we would need to modify the response
-                                        // to add the X-PAYMENT-RESPONSE header, but this requires
-                                        // more complex response handling in Tower
-                                        let _ = settlement; // Acknowledge settlement
+                                    let mut response = future.await?;
+
+                                    // Settle payment after successful response. When a
+                                    // settlement queue is configured, hand the payment off
+                                    // to it so transient facilitator failures are retried
+                                    // in the background instead of being dropped.
+                                    if let Some(queue) = &middleware.settlement_queue {
+                                        if let Err(e) =
+                                            queue.enqueue(payment_payload, requirements)
+                                        {
+                                            tracing::warn!(
+                                                "Failed to enqueue payment for settlement: {}",
+                                                e
+                                            );
+                                        }
+                                    } else {
+                                        match middleware
+                                            .settle_with_requirements(&payment_payload, &requirements)
+                                            .await
+                                        {
+                                            // Mirrors the Axum path: the encoded
+                                            // `SettleResponse` is attached to the response
+                                            // as `X-PAYMENT-RESPONSE` whether or not the
+                                            // facilitator itself reported success, so a
+                                            // caller inspecting the header sees the real
+                                            // settlement outcome instead of an
+                                            // unconditional 200.
+                                            Ok(settlement) => match settlement.to_base64() {
+                                                Ok(encoded) => {
+                                                    if let Ok(header_value) =
+                                                        HeaderValue::from_str(&encoded)
+                                                    {
+                                                        response.headers_mut().insert(
+                                                            "X-PAYMENT-RESPONSE",
+                                                            header_value,
+                                                        );
+                                                    }
+                                                }
+                                                Err(e) => {
+                                                    tracing::warn!(
+                                                        "Failed to encode settlement response: {}",
+                                                        e
+                                                    );
+                                                }
+                                            },
+                                            // A transport/facilitator-connection failure
+                                            // (as opposed to a `SettleResponse { success:
+                                            // false, .. }`, which is handled above) leaves
+                                            // the handler's response as-is rather than
+                                            // replacing its body, the same tradeoff already
+                                            // made for a failed `queue.enqueue` above:
+                                            // `ResBody` is an arbitrary generic body type
+                                            // here, not necessarily one this layer can
+                                            // construct an error/receipt body in.
+                                            Err(e) => {
+                                                tracing::warn!(
+                                                    "Payment settlement failed: {}",
+                                                    e
+                                                );
+                                            }
+                                        }
                                     }
 
                                     Ok(response)
@@ -724,6 +2210,101 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_with_network_selects_a_chain_beyond_base() {
+        let config = PaymentMiddlewareConfig::new(
+            Decimal::from_str("1").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        )
+        .with_network(SupportedNetwork::AvalancheFuji);
+
+        assert_eq!(config.network, SupportedNetwork::AvalancheFuji);
+        assert!(config.testnet);
+
+        let requirements = config.create_payment_requirements("/test").unwrap();
+        assert_eq!(requirements.network, "avalanche-fuji");
+        assert_eq!(
+            requirements.asset,
+            "0x5425890298aed601595a70AB815c96711a31Bc65"
+        );
+        assert_eq!(requirements.max_amount_required, "1000000");
+    }
+
+    #[test]
+    fn test_with_testnet_keeps_network_and_testnet_in_sync() {
+        let config = PaymentMiddlewareConfig::new(
+            Decimal::from_str("0.0001").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        )
+        .with_network(SupportedNetwork::AvalancheMainnet)
+        .with_testnet(true);
+
+        assert_eq!(config.network, SupportedNetwork::BaseSepolia);
+        assert!(config.testnet);
+    }
+
+    #[tokio::test]
+    async fn test_payment_middleware_with_settlement_queue() {
+        let facilitator = crate::facilitator::FacilitatorClient::new(FacilitatorConfig::default())
+            .unwrap();
+        let queue = crate::settlement::SettlementQueue::new(facilitator, crate::retry::RetryPolicy::new());
+
+        let middleware = PaymentMiddleware::new(
+            Decimal::from_str("0.0001").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        )
+        .with_settlement_queue(queue);
+
+        assert!(middleware.settlement_queue.is_some());
+    }
+
+    #[test]
+    fn test_payment_middleware_with_facilitator_router() {
+        let facilitator = crate::facilitator::FacilitatorClient::new(FacilitatorConfig::default())
+            .unwrap();
+        let router = crate::facilitator::FacilitatorRouter::new(facilitator);
+
+        let middleware = PaymentMiddleware::new(
+            Decimal::from_str("0.0001").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        )
+        .with_facilitator_router(router);
+
+        assert!(middleware.facilitator_router.is_some());
+    }
+
+    #[test]
+    fn test_payment_middleware_with_facilitators_builds_priority_chain() {
+        let first = crate::facilitator::FacilitatorClient::new(FacilitatorConfig::default()).unwrap();
+        let second = crate::facilitator::FacilitatorClient::new(FacilitatorConfig::default()).unwrap();
+
+        let middleware = PaymentMiddleware::new(
+            Decimal::from_str("0.0001").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        )
+        .with_facilitators(vec![first, second]);
+
+        assert!(middleware.facilitator_chain.is_some());
+    }
+
+    #[test]
+    fn test_create_payment_required_response_carries_error_detail() {
+        let middleware = PaymentMiddleware::new(
+            Decimal::from_str("0.0001").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        );
+
+        let response = middleware
+            .create_payment_required_response(
+                &X402Error::nonce_already_used("0xabc"),
+                &[],
+                false,
+            )
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::PAYMENT_REQUIRED);
+    }
+
     #[test]
     fn test_payment_middleware_creation_with_description() {
         let middleware = PaymentMiddleware::new(
@@ -741,4 +2322,1394 @@ mod tests {
             Some("Test middleware".to_string())
         );
     }
+
+    #[test]
+    fn test_payment_middleware_with_scopes() {
+        let middleware = PaymentMiddleware::new(
+            Decimal::from_str("0.001").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        )
+        .with_scopes(vec!["read".to_string()])
+        .with_required_scopes(vec!["read".to_string()]);
+
+        assert_eq!(middleware.config().scopes, vec!["read".to_string()]);
+        assert_eq!(
+            middleware.config().required_scopes,
+            vec!["read".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_payment_middleware_with_retry_policy_defaults_idempotency_store() {
+        let middleware = PaymentMiddleware::new(
+            Decimal::from_str("0.001").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        )
+        .with_retry_policy(crate::facilitator::RetryableFacilitatorPolicy::new().with_max_attempts(5));
+
+        assert!(middleware.retry_policy.is_some());
+        assert_eq!(middleware.retry_policy.as_ref().unwrap().max_attempts, 5);
+        assert!(middleware.idempotency_store.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_settle_with_requirements_retries_transient_facilitator_error() {
+        let mut server = mockito::Server::new_async().await;
+        let failing_mock = server
+            .mock("POST", "/settle")
+            .with_status(503)
+            .expect(1)
+            .create();
+        let ok_mock = server
+            .mock("POST", "/settle")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!({"success": true, "transaction": "0xabc", "network": "base-sepolia"})
+                    .to_string(),
+            )
+            .expect(1)
+            .create();
+
+        let facilitator =
+            crate::facilitator::FacilitatorClient::new(FacilitatorConfig::new(server.url())).unwrap();
+        let middleware = PaymentMiddleware::new(
+            Decimal::from_str("0.001").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        )
+        .with_facilitator(facilitator)
+        .with_retry_policy(
+            crate::facilitator::RetryableFacilitatorPolicy::new()
+                .with_base_delay(std::time::Duration::from_millis(1))
+                .with_max_attempts(3),
+        );
+
+        let requirements = middleware
+            .config()
+            .create_payment_requirements("/test")
+            .unwrap();
+        let authorization = crate::types::ExactEvmPayloadAuthorization::new(
+            "0x857b06519E91e3A54538791bDbb0E22373e36b66",
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            "1000000",
+            "1745323800",
+            "1745323985",
+            "0xf3746613c2d920b5fdabc0856f2aeb2d4f88ee6037b8cc5d04a71a4462f13480",
+        );
+        let payment_payload = PaymentPayload::new(
+            "exact",
+            "base-sepolia",
+            crate::types::ExactEvmPayload {
+                signature: "0xsignature".to_string(),
+                authorization,
+            },
+        );
+
+        let settlement = middleware
+            .settle_with_requirements(&payment_payload, &requirements)
+            .await
+            .expect("should recover after one transient failure");
+
+        assert_eq!(settlement.transaction, "0xabc");
+        failing_mock.assert();
+        ok_mock.assert();
+    }
+
+    fn authorization_with_window(valid_after: i64, valid_before: i64) -> PaymentPayload {
+        let authorization = crate::types::ExactEvmPayloadAuthorization::new(
+            "0x857b06519E91e3A54538791bDbb0E22373e36b66",
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            "1000000",
+            valid_after.to_string(),
+            valid_before.to_string(),
+            "0xf3746613c2d920b5fdabc0856f2aeb2d4f88ee6037b8cc5d04a71a4462f13480",
+        );
+        PaymentPayload::new(
+            "exact",
+            "base-sepolia",
+            crate::types::ExactEvmPayload {
+                signature: "0xsignature".to_string(),
+                authorization,
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn test_verify_with_requirements_rejects_expired_authorization_before_facilitator_call() {
+        let middleware = PaymentMiddleware::new(
+            Decimal::from_str("0.001").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        );
+        let requirements = middleware
+            .config()
+            .create_payment_requirements("/test")
+            .unwrap();
+        let now = chrono::Utc::now().timestamp();
+        let payment_payload = authorization_with_window(now - 3600, now - 1800);
+
+        let error = middleware
+            .verify_with_requirements(&payment_payload, &requirements)
+            .await
+            .unwrap_err();
+        assert!(matches!(error, X402Error::AuthorizationExpired { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_settle_with_requirements_rejects_not_yet_valid_authorization_before_facilitator_call(
+    ) {
+        let middleware = PaymentMiddleware::new(
+            Decimal::from_str("0.001").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        );
+        let requirements = middleware
+            .config()
+            .create_payment_requirements("/test")
+            .unwrap();
+        let now = chrono::Utc::now().timestamp();
+        let payment_payload = authorization_with_window(now + 1800, now + 3600);
+
+        let error = middleware
+            .settle_with_requirements(&payment_payload, &requirements)
+            .await
+            .unwrap_err();
+        assert!(matches!(error, X402Error::AuthorizationNotYetValid));
+    }
+
+    #[tokio::test]
+    async fn test_clock_skew_tolerance_admits_authorization_just_outside_the_raw_window() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/verify")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(serde_json::json!({"isValid": true, "invalidReason": null}).to_string())
+            .create();
+        let facilitator = crate::facilitator::FacilitatorClient::new(FacilitatorConfig::new(server.url()))
+            .unwrap();
+
+        let middleware = PaymentMiddleware::new(
+            Decimal::from_str("0.001").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        )
+        .with_facilitator(facilitator)
+        .with_clock_skew_tolerance(std::time::Duration::from_secs(120));
+        let requirements = middleware
+            .config()
+            .create_payment_requirements("/test")
+            .unwrap();
+        let now = chrono::Utc::now().timestamp();
+        // Expired by 60s against the raw window, but within the 120s tolerance.
+        let payment_payload = authorization_with_window(now - 3600, now - 60);
+
+        let is_valid = middleware
+            .verify_with_requirements(&payment_payload, &requirements)
+            .await
+            .expect("60s drift should be absorbed by the 120s tolerance");
+
+        assert!(is_valid);
+        mock.assert();
+    }
+
+    #[test]
+    fn test_payment_middleware_with_nonce_store() {
+        let store: Arc<dyn crate::nonce_store::NonceStore> =
+            Arc::new(crate::nonce_store::InMemoryNonceStore::new());
+
+        let middleware = PaymentMiddleware::new(
+            Decimal::from_str("0.0001").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        )
+        .with_nonce_store(store);
+
+        assert!(middleware.nonce_store.is_some());
+    }
+
+    #[test]
+    fn test_payment_middleware_with_nonce_replay_store() {
+        let store: Arc<dyn crate::nonce_store::NonceReplayStore> =
+            Arc::new(crate::nonce_store::InMemoryNonceReplayStore::new());
+
+        let middleware = PaymentMiddleware::new(
+            Decimal::from_str("0.0001").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        )
+        .with_nonce_replay_store(store);
+
+        assert!(middleware.nonce_replay_store.is_some());
+    }
+
+    #[test]
+    fn test_payment_middleware_with_lifecycle_tracker() {
+        let store: Arc<dyn crate::payment_lifecycle::PaymentStore> =
+            Arc::new(crate::payment_lifecycle::InMemoryPaymentStore::new());
+        let tracker = Arc::new(crate::payment_lifecycle::PaymentLifecycleTracker::new(store));
+
+        let middleware = PaymentMiddleware::new(
+            Decimal::from_str("0.0001").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        )
+        .with_lifecycle_tracker(tracker);
+
+        assert!(middleware.lifecycle_tracker.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_payment_middleware_with_observer_invokes_it() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        struct CountingObserver(Arc<AtomicUsize>);
+
+        impl crate::payment_events::PaymentObserver for CountingObserver {
+            fn on_event<'a>(
+                &'a self,
+                _event: crate::payment_events::PaymentEvent,
+                _ctx: &'a crate::payment_events::PaymentEventContext,
+            ) -> crate::payment_events::BoxFuture<'a, ()> {
+                self.0.fetch_add(1, Ordering::SeqCst);
+                Box::pin(async {})
+            }
+        }
+
+        let count = Arc::new(AtomicUsize::new(0));
+        let middleware = PaymentMiddleware::new(
+            Decimal::from_str("0.001").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        )
+        .with_observer(Arc::new(CountingObserver(count.clone())));
+
+        let ctx = crate::payment_events::PaymentEventContext {
+            resource: "/test".to_string(),
+            network: "base-sepolia".to_string(),
+            amount: "1000".to_string(),
+            payment_id: None,
+        };
+        middleware
+            .observer
+            .on_event(crate::payment_events::PaymentEvent::Requested, &ctx)
+            .await;
+
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_settle_with_requirements_tracked_marks_nonce_used_on_success() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/settle")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!({"success": true, "errorReason": null, "transaction": "0xabc", "network": "base-sepolia", "payer": null})
+                    .to_string(),
+            )
+            .create();
+        let facilitator = crate::facilitator::FacilitatorClient::new(FacilitatorConfig::new(server.url()))
+            .unwrap();
+
+        let store: Arc<dyn crate::nonce_store::NonceStore> =
+            Arc::new(crate::nonce_store::InMemoryNonceStore::new());
+
+        let middleware = PaymentMiddleware::new(
+            Decimal::from_str("0.001").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        )
+        .with_facilitator(facilitator)
+        .with_nonce_store(store.clone());
+
+        let requirements = middleware
+            .config()
+            .create_payment_requirements("/test")
+            .unwrap();
+        let now = chrono::Utc::now().timestamp();
+        let nonce = "0xf3746613c2d920b5fdabc0856f2aeb2d4f88ee6037b8cc5d04a71a4462f13480";
+        let payment_payload = authorization_with_window(now - 60, now + 3600);
+
+        assert!(!store.contains(&requirements.network, nonce).await);
+
+        middleware
+            .settle_with_requirements(&payment_payload, &requirements)
+            .await
+            .expect("settlement should succeed");
+
+        assert!(store.contains(&requirements.network, nonce).await);
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_settle_with_requirements_tracked_is_idempotent_without_retry_policy() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/settle")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!({"success": true, "transaction": "0xabc", "network": "base-sepolia"})
+                    .to_string(),
+            )
+            .expect(1)
+            .create();
+
+        let facilitator =
+            crate::facilitator::FacilitatorClient::new(FacilitatorConfig::new(server.url())).unwrap();
+        let middleware = PaymentMiddleware::new(
+            Decimal::from_str("0.001").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        )
+        .with_facilitator(facilitator)
+        .with_idempotency_store(Arc::new(
+            crate::idempotency::InMemoryIdempotencyStore::default(),
+        ));
+        assert!(
+            middleware.retry_policy.is_none(),
+            "this test exercises idempotency with no retry policy configured"
+        );
+
+        let requirements = middleware
+            .config()
+            .create_payment_requirements("/test")
+            .unwrap();
+        let now = chrono::Utc::now().timestamp();
+        let payment_payload = authorization_with_window(now - 60, now + 3600);
+
+        let first = middleware
+            .settle_with_requirements_tracked(&payment_payload, &requirements)
+            .await
+            .unwrap();
+        let second = middleware
+            .settle_with_requirements_tracked(&payment_payload, &requirements)
+            .await
+            .unwrap();
+
+        assert_eq!(first.0.transaction, second.0.transaction);
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_settle_with_requirements_tracked_records_to_ledger_on_success() {
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server
+            .mock("POST", "/settle")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!({"success": true, "transaction": "0xabc", "network": "base-sepolia"})
+                    .to_string(),
+            )
+            .create();
+
+        let facilitator =
+            crate::facilitator::FacilitatorClient::new(FacilitatorConfig::new(server.url())).unwrap();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let ledger = Arc::new(crate::accounting::PaymentLedger::new(
+            Arc::new(crate::accounting::ChannelSink::new(tx)),
+            crate::accounting::AccountingFlushConfig {
+                flush_interval_seconds: 3600,
+                batch_size: 1,
+            },
+        ));
+
+        let middleware = PaymentMiddleware::new(
+            Decimal::from_str("0.001").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        )
+        .with_facilitator(facilitator)
+        .with_ledger(ledger);
+
+        let requirements = middleware
+            .config()
+            .create_payment_requirements("/test")
+            .unwrap();
+        let now = chrono::Utc::now().timestamp();
+        let payment_payload = authorization_with_window(now - 60, now + 3600);
+
+        middleware
+            .settle_with_requirements_tracked(&payment_payload, &requirements)
+            .await
+            .unwrap();
+
+        let batch = tokio::time::timeout(std::time::Duration::from_secs(1), rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(batch.len(), 1);
+        assert_eq!(batch[0].transaction, "0xabc");
+        assert_eq!(batch[0].route, requirements.resource);
+    }
+
+    fn bloom_containing(items: &[Vec<u8>]) -> Vec<u8> {
+        use sha3::{Digest, Keccak256};
+        let mut bloom = vec![0u8; 256];
+        for item in items {
+            let hash = Keccak256::digest(item);
+            for pair in 0..3 {
+                let word = u16::from_be_bytes([hash[pair * 2], hash[pair * 2 + 1]]) & 0x07ff;
+                let byte_index = 255 - (word / 8) as usize;
+                let bit_index = (word % 8) as u8;
+                bloom[byte_index] |= 1 << bit_index;
+            }
+        }
+        bloom
+    }
+
+    fn transfer_log_json(token: &str, from: &str, to: &str, value: u128) -> serde_json::Value {
+        fn topic_word(address: &str) -> String {
+            format!("0x{}{}", "0".repeat(24), address.trim_start_matches("0x"))
+        }
+        serde_json::json!({
+            "address": token,
+            "topics": [
+                "0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef",
+                topic_word(from),
+                topic_word(to),
+            ],
+            "data": format!("0x{:064x}", value),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_settle_with_requirements_verified_downgrades_on_chain_mismatch() {
+        let mut server = mockito::Server::new_async().await;
+        let settle_mock = server
+            .mock("POST", "/settle")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!({"success": true, "transaction": "0xabc", "network": "base-sepolia"})
+                    .to_string(),
+            )
+            .create();
+
+        let mut chain_server = mockito::Server::new_async().await;
+        let _receipt_mock = chain_server
+            .mock("POST", "/")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "result": {
+                        "transactionHash": "0xabc",
+                        "status": "0x1",
+                        "logsBloom": format!("0x{}", hex::encode(vec![0u8; 256])),
+                        "logs": []
+                    }
+                })
+                .to_string(),
+            )
+            .create();
+
+        let facilitator =
+            crate::facilitator::FacilitatorClient::new(FacilitatorConfig::new(server.url())).unwrap();
+        let verifier = crate::onchain_verification::OnchainSettlementVerifier::new(
+            crate::blockchain::BlockchainClient::new(
+                chain_server.url(),
+                "base-sepolia".to_string(),
+            ),
+        );
+        let middleware = PaymentMiddleware::new(
+            Decimal::from_str("0.001").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        )
+        .with_facilitator(facilitator)
+        .with_onchain_settlement_verification(Arc::new(verifier));
+
+        let requirements = middleware
+            .config()
+            .create_payment_requirements("/test")
+            .unwrap();
+        let now = chrono::Utc::now().timestamp();
+        let payment_payload = authorization_with_window(now - 60, now + 3600);
+
+        let (response, _) = middleware
+            .settle_with_requirements_verified(&payment_payload, &requirements)
+            .await
+            .unwrap();
+
+        assert!(!response.success);
+        assert!(response
+            .error_reason
+            .as_deref()
+            .unwrap_or_default()
+            .contains("onchain verification failed"));
+        settle_mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_settle_with_requirements_verified_passes_through_matching_settlement() {
+        let mut server = mockito::Server::new_async().await;
+        let _settle_mock = server
+            .mock("POST", "/settle")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!({"success": true, "transaction": "0xabc", "network": "base-sepolia"})
+                    .to_string(),
+            )
+            .create();
+
+        let from = "0x857b06519E91e3A54538791bDbb0E22373e36b66";
+        let to = "0x209693Bc6afc0C5328bA36FaF03C514EF312287C";
+        let token = "0x036CbD53842c5426634e7929541eC2318f3dCF7e";
+        let mut chain_server = mockito::Server::new_async().await;
+        let topic0 = hex::decode(
+            "ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef",
+        )
+        .unwrap();
+        fn address_word(address: &str) -> Vec<u8> {
+            let mut word = vec![0u8; 32];
+            let raw = hex::decode(address.trim_start_matches("0x")).unwrap();
+            word[12..].copy_from_slice(&raw);
+            word
+        }
+        let bloom = bloom_containing(&[
+            hex::decode(token.trim_start_matches("0x")).unwrap(),
+            topic0,
+            address_word(from),
+            address_word(to),
+        ]);
+        let _receipt_mock = chain_server
+            .mock("POST", "/")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "result": {
+                        "transactionHash": "0xabc",
+                        "status": "0x1",
+                        "logsBloom": format!("0x{}", hex::encode(bloom)),
+                        "logs": [transfer_log_json(token, from, to, 1_000_000)]
+                    }
+                })
+                .to_string(),
+            )
+            .create();
+
+        let facilitator =
+            crate::facilitator::FacilitatorClient::new(FacilitatorConfig::new(server.url())).unwrap();
+        let verifier = crate::onchain_verification::OnchainSettlementVerifier::new(
+            crate::blockchain::BlockchainClient::new(
+                chain_server.url(),
+                "base-sepolia".to_string(),
+            ),
+        );
+        let middleware = PaymentMiddleware::new(Decimal::from_str("0.001").unwrap(), to)
+            .with_facilitator(facilitator)
+            .with_onchain_settlement_verification(Arc::new(verifier));
+
+        let requirements = middleware
+            .config()
+            .create_payment_requirements("/test")
+            .unwrap();
+        let now = chrono::Utc::now().timestamp();
+        let payment_payload = authorization_with_window(now - 60, now + 3600);
+
+        let (response, _) = middleware
+            .settle_with_requirements_verified(&payment_payload, &requirements)
+            .await
+            .unwrap();
+
+        assert!(response.success);
+        assert!(response.error_reason.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_refund_with_requirements_posts_to_facilitator() {
+        let mut server = mockito::Server::new_async().await;
+        let _m = server
+            .mock("POST", "/refund")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!({
+                    "success": true,
+                    "kind": "refund",
+                    "transaction": "0xrefund",
+                    "network": "base-sepolia"
+                })
+                .to_string(),
+            )
+            .create();
+
+        let facilitator =
+            crate::facilitator::FacilitatorClient::new(FacilitatorConfig::new(server.url())).unwrap();
+        let middleware = PaymentMiddleware::new(
+            Decimal::from_str("0.001").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        )
+        .with_facilitator(facilitator);
+
+        let original_settlement = SettleResponse {
+            success: true,
+            error_reason: None,
+            transaction: "0xoriginal".to_string(),
+            network: "base-sepolia".to_string(),
+            payer: Some("0x857b06519E91e3A54538791bDbb0E22373e36b66".to_string()),
+        };
+
+        let response = middleware
+            .refund_with_requirements(&original_settlement, "500000")
+            .await
+            .unwrap();
+
+        assert!(response.success);
+        assert_eq!(response.transaction, "0xrefund");
+    }
+
+    #[tokio::test]
+    async fn test_payout_posts_to_facilitator() {
+        let mut server = mockito::Server::new_async().await;
+        let _m = server
+            .mock("POST", "/payout")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!({
+                    "success": true,
+                    "kind": "payout",
+                    "transaction": "0xpayout",
+                    "network": "base-sepolia"
+                })
+                .to_string(),
+            )
+            .create();
+
+        let facilitator =
+            crate::facilitator::FacilitatorClient::new(FacilitatorConfig::new(server.url())).unwrap();
+        let middleware = PaymentMiddleware::new(
+            Decimal::from_str("0.001").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        )
+        .with_facilitator(facilitator);
+
+        let response = middleware
+            .payout(
+                "base-sepolia",
+                "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+                "1000000",
+                "0x036CbD53842c5426634e7929541eC2318f3dCF7e",
+            )
+            .await
+            .unwrap();
+
+        assert!(response.success);
+        assert_eq!(response.transaction, "0xpayout");
+    }
+
+    #[tokio::test]
+    async fn test_verify_with_requirements_fails_fast_on_unsupported_scheme() {
+        let mut server = mockito::Server::new_async().await;
+        let _supported_mock = server
+            .mock("GET", "/supported")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!({
+                    "x402Version": 1,
+                    "kinds": [
+                        {"x402Version": 1, "scheme": "exact", "network": "base"}
+                    ]
+                })
+                .to_string(),
+            )
+            .expect(1)
+            .create();
+        let verify_mock = server.mock("POST", "/verify").expect(0).create();
+
+        let facilitator =
+            crate::facilitator::FacilitatorClient::new(FacilitatorConfig::new(server.url())).unwrap();
+        let middleware = PaymentMiddleware::new(
+            Decimal::from_str("0.001").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        )
+        .with_facilitator(facilitator)
+        .with_capability_cache_ttl(std::time::Duration::from_secs(60));
+
+        let requirements = middleware
+            .config()
+            .create_payment_requirements("/test")
+            .unwrap();
+        let authorization = crate::types::ExactEvmPayloadAuthorization::new(
+            "0x857b06519E91e3A54538791bDbb0E22373e36b66",
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            "1000000",
+            "1745323800",
+            "1745323985",
+            "0xf3746613c2d920b5fdabc0856f2aeb2d4f88ee6037b8cc5d04a71a4462f13480",
+        );
+        let payment_payload = PaymentPayload::new(
+            "exact",
+            "base-sepolia",
+            crate::types::ExactEvmPayload {
+                signature: "0xsignature".to_string(),
+                authorization,
+            },
+        );
+
+        let error = middleware
+            .verify_with_requirements(&payment_payload, &requirements)
+            .await
+            .expect_err("base-sepolia/exact is not in the facilitator's advertised kinds");
+
+        assert_eq!(error.status_code(), 400);
+        verify_mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_verify_with_requirements_caches_supported_across_calls() {
+        let mut server = mockito::Server::new_async().await;
+        let _supported_mock = server
+            .mock("GET", "/supported")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!({
+                    "x402Version": 1,
+                    "kinds": [
+                        {"x402Version": 1, "scheme": "exact", "network": "base-sepolia"}
+                    ]
+                })
+                .to_string(),
+            )
+            .expect(1)
+            .create();
+        let _verify_mock = server
+            .mock("POST", "/verify")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(serde_json::json!({"isValid": true}).to_string())
+            .expect(2)
+            .create();
+
+        let facilitator =
+            crate::facilitator::FacilitatorClient::new(FacilitatorConfig::new(server.url())).unwrap();
+        let middleware = PaymentMiddleware::new(
+            Decimal::from_str("0.001").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        )
+        .with_facilitator(facilitator)
+        .with_capability_cache_ttl(std::time::Duration::from_secs(60));
+
+        let requirements = middleware
+            .config()
+            .create_payment_requirements("/test")
+            .unwrap();
+        let authorization = crate::types::ExactEvmPayloadAuthorization::new(
+            "0x857b06519E91e3A54538791bDbb0E22373e36b66",
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            "1000000",
+            "1745323800",
+            "1745323985",
+            "0xf3746613c2d920b5fdabc0856f2aeb2d4f88ee6037b8cc5d04a71a4462f13480",
+        );
+        let payment_payload = PaymentPayload::new(
+            "exact",
+            "base-sepolia",
+            crate::types::ExactEvmPayload {
+                signature: "0xsignature".to_string(),
+                authorization,
+            },
+        );
+
+        assert!(middleware
+            .verify_with_requirements(&payment_payload, &requirements)
+            .await
+            .unwrap());
+        assert!(middleware
+            .verify_with_requirements(&payment_payload, &requirements)
+            .await
+            .unwrap());
+
+        _supported_mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_verify_with_requirements_routes_through_keyed_facilitators() {
+        let mut base_sepolia_server = mockito::Server::new_async().await;
+        let base_sepolia_mock = base_sepolia_server
+            .mock("POST", "/verify")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(serde_json::json!({"isValid": true}).to_string())
+            .create();
+
+        let base_server = mockito::Server::new_async().await;
+
+        let chain = crate::facilitator::KeyedFacilitatorChain::new(
+            vec![
+                crate::facilitator::FacilitatorEntry::new(
+                    "exact",
+                    "base-sepolia",
+                    FacilitatorConfig::new(base_sepolia_server.url()),
+                ),
+                crate::facilitator::FacilitatorEntry::new(
+                    "exact",
+                    "base",
+                    FacilitatorConfig::new(base_server.url()),
+                ),
+            ],
+            crate::facilitator::RoutingPolicy::Priority,
+        )
+        .unwrap();
+
+        let middleware = PaymentMiddleware::new(
+            Decimal::from_str("0.001").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        )
+        .with_keyed_facilitators(chain);
+
+        let requirements = middleware
+            .config()
+            .create_payment_requirements("/test")
+            .unwrap();
+        let authorization = crate::types::ExactEvmPayloadAuthorization::new(
+            "0x857b06519E91e3A54538791bDbb0E22373e36b66",
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            "1000000",
+            "1745323800",
+            "1745323985",
+            "0xf3746613c2d920b5fdabc0856f2aeb2d4f88ee6037b8cc5d04a71a4462f13480",
+        );
+        let payment_payload = PaymentPayload::new(
+            "exact",
+            "base-sepolia",
+            crate::types::ExactEvmPayload {
+                signature: "0xsignature".to_string(),
+                authorization,
+            },
+        );
+
+        assert!(middleware
+            .verify_with_requirements(&payment_payload, &requirements)
+            .await
+            .unwrap());
+        base_sepolia_mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_verify_with_requirements_routes_through_facilitator_registry() {
+        let mut base_sepolia_server = mockito::Server::new_async().await;
+        let base_sepolia_mock = base_sepolia_server
+            .mock("POST", "/verify")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(serde_json::json!({"isValid": true}).to_string())
+            .create();
+
+        let backend = crate::facilitator::FacilitatorClient::new(FacilitatorConfig::new(
+            base_sepolia_server.url(),
+        ))
+        .unwrap();
+        let registry = crate::facilitator::FacilitatorRegistry::new().with_backend(
+            "base-sepolia-provider",
+            std::sync::Arc::new(backend),
+            [("base-sepolia".to_string(), "exact".to_string())],
+        );
+
+        let middleware = PaymentMiddleware::new(
+            Decimal::from_str("0.001").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        )
+        .with_facilitator_registry(registry);
+
+        assert!(middleware.facilitator_registry.is_some());
+
+        let requirements = middleware
+            .config()
+            .create_payment_requirements("/test")
+            .unwrap();
+        let authorization = crate::types::ExactEvmPayloadAuthorization::new(
+            "0x857b06519E91e3A54538791bDbb0E22373e36b66",
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            "1000000",
+            "1745323800",
+            "1745323985",
+            "0xf3746613c2d920b5fdabc0856f2aeb2d4f88ee6037b8cc5d04a71a4462f13480",
+        );
+        let payment_payload = PaymentPayload::new(
+            "exact",
+            "base-sepolia",
+            crate::types::ExactEvmPayload {
+                signature: "0xsignature".to_string(),
+                authorization,
+            },
+        );
+
+        assert!(middleware
+            .verify_with_requirements(&payment_payload, &requirements)
+            .await
+            .unwrap());
+        base_sepolia_mock.assert();
+    }
+
+    fn test_async_settlement_payload_and_requirements(
+        middleware: &PaymentMiddleware,
+    ) -> (PaymentPayload, PaymentRequirements) {
+        let requirements = middleware
+            .config()
+            .create_payment_requirements("/test")
+            .unwrap();
+        let authorization = crate::types::ExactEvmPayloadAuthorization::new(
+            "0x857b06519E91e3A54538791bDbb0E22373e36b66",
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            "1000000",
+            "1745323800",
+            "1745323985",
+            "0xf3746613c2d920b5fdabc0856f2aeb2d4f88ee6037b8cc5d04a71a4462f13480",
+        );
+        let payment_payload = PaymentPayload::new(
+            "exact",
+            "base-sepolia",
+            crate::types::ExactEvmPayload {
+                signature: "0xsignature".to_string(),
+                authorization,
+            },
+        );
+        (payment_payload, requirements)
+    }
+
+    #[tokio::test]
+    async fn test_settle_with_requirements_async_returns_pending_and_tracks_it() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/settle")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!({
+                    "success": false,
+                    "errorReason": "pending:settlement-abc",
+                    "transaction": "",
+                    "network": "base-sepolia"
+                })
+                .to_string(),
+            )
+            .create();
+
+        let facilitator =
+            crate::facilitator::FacilitatorClient::new(FacilitatorConfig::new(server.url()))
+                .unwrap();
+        let store = Arc::new(crate::async_settlement::InMemoryPendingSettlementStore::new());
+        let middleware = PaymentMiddleware::new(
+            Decimal::from_str("0.001").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        )
+        .with_facilitator(facilitator)
+        .with_pending_settlements(store.clone());
+
+        let (payment_payload, requirements) =
+            test_async_settlement_payload_and_requirements(&middleware);
+
+        let outcome = middleware
+            .settle_with_requirements_async(&payment_payload, &requirements)
+            .await
+            .unwrap();
+
+        match outcome {
+            crate::async_settlement::SettlementOutcome::Pending { settlement_id } => {
+                assert_eq!(settlement_id, "settlement-abc");
+            }
+            other => panic!("expected Pending outcome, got {other:?}"),
+        }
+
+        assert!(matches!(
+            store.get("settlement-abc").await,
+            Some(crate::async_settlement::PendingSettlementState::Pending)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_settle_with_requirements_async_returns_final_without_store() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/settle")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!({
+                    "success": false,
+                    "errorReason": "pending:settlement-abc",
+                    "transaction": "",
+                    "network": "base-sepolia"
+                })
+                .to_string(),
+            )
+            .create();
+
+        let facilitator =
+            crate::facilitator::FacilitatorClient::new(FacilitatorConfig::new(server.url()))
+                .unwrap();
+        let middleware = PaymentMiddleware::new(
+            Decimal::from_str("0.001").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        )
+        .with_facilitator(facilitator);
+
+        let (payment_payload, requirements) =
+            test_async_settlement_payload_and_requirements(&middleware);
+
+        let outcome = middleware
+            .settle_with_requirements_async(&payment_payload, &requirements)
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            outcome,
+            crate::async_settlement::SettlementOutcome::Final(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_settle_with_requirements_async_returns_final_on_success() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/settle")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!({"success": true, "transaction": "0xabc", "network": "base-sepolia"})
+                    .to_string(),
+            )
+            .create();
+
+        let facilitator =
+            crate::facilitator::FacilitatorClient::new(FacilitatorConfig::new(server.url()))
+                .unwrap();
+        let store = Arc::new(crate::async_settlement::InMemoryPendingSettlementStore::new());
+        let middleware = PaymentMiddleware::new(
+            Decimal::from_str("0.001").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        )
+        .with_facilitator(facilitator)
+        .with_pending_settlements(store);
+
+        let (payment_payload, requirements) =
+            test_async_settlement_payload_and_requirements(&middleware);
+
+        let outcome = middleware
+            .settle_with_requirements_async(&payment_payload, &requirements)
+            .await
+            .unwrap();
+
+        match outcome {
+            crate::async_settlement::SettlementOutcome::Final(response) => {
+                assert!(response.success);
+                assert_eq!(response.transaction, "0xabc");
+            }
+            other => panic!("expected Final outcome, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_create_payment_requirements_includes_async_notify_uri() {
+        let config = PaymentMiddlewareConfig::new(
+            Decimal::from_str("0.001").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        )
+        .with_async_settlement_notify_uri("https://example.com/x402/settlements/notify");
+
+        let requirements = config.create_payment_requirements("/test").unwrap();
+        assert_eq!(
+            requirements.async_settlement_notify_uri(),
+            Some("https://example.com/x402/settlements/notify")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_accepted_payment_requirements_includes_additional_options() {
+        let config = PaymentMiddlewareConfig::new(
+            Decimal::from_str("0.001").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        )
+        .with_additional_options(vec![
+            PaymentOption::usdc(
+                networks::AVALANCHE_FUJI,
+                Decimal::from_str("0.001").unwrap(),
+                "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            )
+            .unwrap(),
+            PaymentOption::new(
+                schemes::EXACT,
+                networks::BASE_SEPOLIA,
+                "1000",
+                "0x1111111111111111111111111111111111111111",
+                "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            ),
+        ]);
+
+        let accepted = config.accepted_payment_requirements("/test").await.unwrap();
+
+        assert_eq!(accepted.len(), 3);
+        assert_eq!(accepted[0].network, networks::BASE_SEPOLIA);
+        assert_eq!(accepted[1].network, networks::AVALANCHE_FUJI);
+        assert_eq!(
+            accepted[1].asset,
+            networks::get_usdc_address(networks::AVALANCHE_FUJI).unwrap()
+        );
+        // A known USDC asset gets the token-name/version hint set automatically
+        assert!(accepted[1].extra.is_some());
+        assert_eq!(accepted[2].network, networks::BASE_SEPOLIA);
+        assert_eq!(accepted[2].asset, "0x1111111111111111111111111111111111111111");
+        // An asset this crate doesn't recognize as USDC is left without the hint
+        assert!(accepted[2].extra.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_create_payment_requirements_priced_converts_fiat_to_token_units() {
+        let config = PaymentMiddlewareConfig::new(
+            Decimal::from_str("2.50").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        )
+        .with_amount_currency("usd")
+        .with_price_oracle(Arc::new(crate::pricing::StaticPriceOracle::new(
+            Decimal::from_str("1.00").unwrap(),
+        )));
+
+        let requirements = config.create_payment_requirements_priced("/test").await.unwrap();
+
+        // $2.50 at a 1:1 usd-to-usdc rate, scaled to USDC's 6 decimals
+        assert_eq!(requirements.max_amount_required, "2500000");
+        let extra = requirements.extra.unwrap();
+        assert_eq!(extra["quotedCurrency"], "usd");
+        assert_eq!(extra["quotedRate"], "1.00");
+    }
+
+    #[tokio::test]
+    async fn test_create_payment_requirements_priced_requires_a_price_oracle() {
+        let config = PaymentMiddlewareConfig::new(
+            Decimal::from_str("2.50").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        )
+        .with_amount_currency("usd");
+
+        let result = config.create_payment_requirements_priced("/test").await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_create_payment_requirements_priced_is_a_passthrough_for_fixed_pricing() {
+        let config = PaymentMiddlewareConfig::new(
+            Decimal::from_str("0.001").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        );
+
+        let priced = config.create_payment_requirements_priced("/test").await.unwrap();
+        let fixed = config.create_payment_requirements("/test").unwrap();
+        assert_eq!(priced.max_amount_required, fixed.max_amount_required);
+        assert_eq!(priced.extra, fixed.extra);
+    }
+
+    struct FakeLightningNode {
+        bolt11: String,
+        payment_hash: String,
+    }
+
+    impl crate::lightning::LightningNodeClient for FakeLightningNode {
+        fn create_invoice<'a>(
+            &'a self,
+            amount_msat: u64,
+            description: &'a str,
+        ) -> crate::lightning::BoxFuture<'a, Result<LightningBolt11Invoice>> {
+            Box::pin(async move {
+                Ok(LightningBolt11Invoice {
+                    bolt11: self.bolt11.clone(),
+                    payment_hash: self.payment_hash.clone(),
+                    amount_msat,
+                    expires_at: chrono::Utc::now().timestamp() + 3600,
+                    description: description.to_string(),
+                })
+            })
+        }
+
+        fn lookup_invoice<'a>(
+            &'a self,
+            _payment_hash: &'a str,
+        ) -> crate::lightning::BoxFuture<'a, Result<crate::lightning::InvoiceStatus>> {
+            Box::pin(async move { Ok(crate::lightning::InvoiceStatus::Paid) })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_lightning_payment_requirements_mints_an_invoice() {
+        let node = Arc::new(FakeLightningNode {
+            bolt11: "lnbc10n1invoice".to_string(),
+            payment_hash: "abc123".to_string(),
+        });
+        let config = PaymentMiddlewareConfig::new(Decimal::from(1000u64), "")
+            .with_description("Test resource")
+            .with_lightning_config(LightningConfig::new(node, "lightning-node-alias"));
+
+        let requirements = config
+            .create_lightning_payment_requirements("/test")
+            .await
+            .unwrap();
+
+        assert_eq!(requirements.network, "lightning");
+        assert_eq!(requirements.scheme, schemes::LIGHTNING_BOLT11);
+        assert_eq!(requirements.max_amount_required, "1000");
+        assert_eq!(
+            requirements.lightning_bolt11_invoice(),
+            Some(("lnbc10n1invoice", "abc123"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_lightning_payment_requirements_requires_lightning_config() {
+        let config = PaymentMiddlewareConfig::new(Decimal::from(1000u64), "");
+        let result = config.create_lightning_payment_requirements("/test").await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_verify_lightning_bolt11_payment_polls_the_node() {
+        let node = Arc::new(FakeLightningNode {
+            bolt11: "lnbc10n1invoice".to_string(),
+            payment_hash: "abc123".to_string(),
+        });
+        let middleware = PaymentMiddleware::new(Decimal::from(1000u64), "")
+            .with_lightning_config(LightningConfig::new(node, "lightning-node-alias"));
+
+        let mut requirements = PaymentRequirements::new(
+            schemes::LIGHTNING_BOLT11,
+            "bitcoin",
+            "1000",
+            "sat",
+            "",
+            "https://example.com/test",
+            "Test resource",
+        );
+        requirements
+            .set_lightning_bolt11_invoice("lnbc10n1invoice", "abc123")
+            .unwrap();
+
+        let is_valid = middleware
+            .verify_lightning_bolt11_payment(&requirements)
+            .await
+            .unwrap();
+        assert!(is_valid);
+
+        let settled = middleware
+            .settle_lightning_bolt11_payment(&requirements)
+            .await
+            .unwrap();
+        assert!(settled.success);
+    }
+
+    #[tokio::test]
+    async fn test_verify_lightning_bolt11_payment_requires_lightning_config() {
+        let middleware = PaymentMiddleware::new(Decimal::from(1000u64), "");
+
+        let mut requirements = PaymentRequirements::new(
+            schemes::LIGHTNING_BOLT11,
+            "bitcoin",
+            "1000",
+            "sat",
+            "",
+            "https://example.com/test",
+            "Test resource",
+        );
+        requirements
+            .set_lightning_bolt11_invoice("lnbc10n1invoice", "abc123")
+            .unwrap();
+
+        let result = middleware.verify_lightning_bolt11_payment(&requirements).await;
+        assert!(result.is_err());
+    }
+
+    struct AlwaysValidHandler;
+
+    impl crate::scheme_registry::SchemeHandler for AlwaysValidHandler {
+        fn network(&self) -> &str {
+            "middleware-test-network"
+        }
+
+        fn scheme(&self) -> &str {
+            "middleware-test-scheme"
+        }
+
+        fn build_payload(
+            &self,
+            _payment_requirements: &PaymentRequirements,
+            _payer: &str,
+        ) -> crate::Result<PaymentPayload> {
+            Err(crate::X402Error::config(
+                "AlwaysValidHandler cannot build payloads",
+            ))
+        }
+
+        fn verify<'a>(
+            &'a self,
+            _payment_payload: &'a PaymentPayload,
+            _payment_requirements: &'a PaymentRequirements,
+        ) -> crate::facilitator::BoxFuture<'a, crate::Result<crate::types::VerifyResponse>> {
+            Box::pin(async move {
+                Ok(crate::types::VerifyResponse {
+                    is_valid: true,
+                    invalid_reason: None,
+                    payer: None,
+                })
+            })
+        }
+
+        fn settle<'a>(
+            &'a self,
+            _payment_payload: &'a PaymentPayload,
+            _payment_requirements: &'a PaymentRequirements,
+        ) -> crate::facilitator::BoxFuture<'a, crate::Result<SettleResponse>> {
+            Box::pin(async move {
+                Ok(SettleResponse {
+                    success: true,
+                    error_reason: None,
+                    transaction: "handled-in-process".to_string(),
+                    network: "middleware-test-network".to_string(),
+                    payer: None,
+                })
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_verify_with_requirements_prefers_registered_scheme_handler() {
+        crate::submit_scheme!(AlwaysValidHandler);
+
+        let middleware = PaymentMiddleware::new(
+            Decimal::from_str("0.001").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        );
+
+        let mut requirements = middleware
+            .config()
+            .create_payment_requirements("/test")
+            .unwrap();
+        requirements.network = "middleware-test-network".to_string();
+        requirements.scheme = "middleware-test-scheme".to_string();
+
+        let authorization = crate::types::ExactEvmPayloadAuthorization::new(
+            "0x0000000000000000000000000000000000000000",
+            "0x0000000000000000000000000000000000000000",
+            "0",
+            "0",
+            "0",
+            "0x00",
+        );
+        let payment_payload = PaymentPayload::new(
+            "middleware-test-scheme",
+            "middleware-test-network",
+            crate::types::ExactEvmPayload {
+                signature: "0x00".to_string(),
+                authorization,
+            },
+        );
+
+        // No facilitator/router/chain configured at all — this only succeeds
+        // because the scheme handler is consulted before any facilitator path.
+        let is_valid = middleware
+            .verify_with_requirements(&payment_payload, &requirements)
+            .await
+            .expect("scheme handler should answer without a facilitator");
+
+        assert!(is_valid);
+    }
 }
@@ -0,0 +1,204 @@
+//! Dynamic, runtime-extendable network registry
+//!
+//! [`crate::types::NetworkConfig::from_name`] used to be a single `match` arm per
+//! chain (chain id, USDC contract, testnet flag, decimals) — fine for this crate's
+//! four seeded Base/Avalanche deployments, but it meant adding a chain, or swapping
+//! in a different stablecoin on an existing one, required editing that match
+//! directly rather than registering a new entry. [`NetworkRegistry`] holds the same
+//! information, seeded with this crate's known deployments via
+//! [`NetworkRegistry::with_known_networks`], but extensible at runtime via
+//! [`NetworkRegistry::with_network`] — the same builder shape
+//! [`crate::token_registry::TokenRegistry`] already uses, which this complements
+//! rather than duplicates: a [`NetworkEntry`]'s assets are
+//! [`crate::token_registry::TokenMetadata`] values, keyed by contract address.
+
+use crate::token_registry::TokenMetadata;
+use std::collections::HashMap;
+
+/// One asset's on-chain address paired with its token metadata
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetworkAsset {
+    pub address: String,
+    pub metadata: TokenMetadata,
+}
+
+/// Everything known about one network: its chain id, a human display name, whether
+/// it's a testnet, and the assets x402 can charge in on it, primary asset first
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetworkEntry {
+    pub chain_id: u64,
+    pub display_name: String,
+    pub is_testnet: bool,
+    pub assets: Vec<NetworkAsset>,
+}
+
+impl NetworkEntry {
+    /// A network with no assets registered yet; chain it with [`Self::with_asset`]
+    pub fn new(chain_id: u64, display_name: impl Into<String>, is_testnet: bool) -> Self {
+        Self {
+            chain_id,
+            display_name: display_name.into(),
+            is_testnet,
+            assets: Vec::new(),
+        }
+    }
+
+    /// Register an asset at `address`, appended after any already registered; the
+    /// first asset registered is this network's [`Self::primary_asset`]
+    pub fn with_asset(mut self, address: impl Into<String>, metadata: TokenMetadata) -> Self {
+        self.assets.push(NetworkAsset {
+            address: address.into(),
+            metadata,
+        });
+        self
+    }
+
+    /// The default asset a caller that doesn't specify one should charge in — the
+    /// first one registered, matching how [`crate::types::NetworkConfig`]'s single
+    /// `usdc_contract`/`decimals` fields only ever described one asset per network
+    pub fn primary_asset(&self) -> Option<&NetworkAsset> {
+        self.assets.first()
+    }
+
+    /// The asset at `address` (case-insensitive), if registered
+    pub fn asset(&self, address: &str) -> Option<&NetworkAsset> {
+        self.assets
+            .iter()
+            .find(|asset| asset.address.eq_ignore_ascii_case(address))
+    }
+}
+
+/// Maps a network name (e.g. `"base-sepolia"`) to its [`NetworkEntry`]
+#[derive(Debug, Clone, Default)]
+pub struct NetworkRegistry {
+    networks: HashMap<String, NetworkEntry>,
+}
+
+impl NetworkRegistry {
+    /// An empty registry with no known networks
+    pub fn new() -> Self {
+        Self {
+            networks: HashMap::new(),
+        }
+    }
+
+    /// Register `entry` under `name`, overwriting any existing entry for that name
+    pub fn with_network(mut self, name: impl Into<String>, entry: NetworkEntry) -> Self {
+        self.networks.insert(name.into(), entry);
+        self
+    }
+
+    /// Look up the entry registered for `name`
+    pub fn lookup(&self, name: &str) -> Option<&NetworkEntry> {
+        self.networks.get(name)
+    }
+
+    /// USDC metadata shared by every network it's deployed on, matching
+    /// [`crate::token_registry::TokenRegistry::usdc_metadata`]
+    fn usdc_metadata() -> TokenMetadata {
+        TokenMetadata {
+            symbol: "USDC".to_string(),
+            name: "USD Coin".to_string(),
+            decimals: 6,
+            eip712_name: "USD Coin".to_string(),
+            eip712_version: "2".to_string(),
+        }
+    }
+
+    /// Register this crate's known deployments: the four Base/Avalanche networks
+    /// [`crate::types::NetworkConfig`] has always known about, plus Ethereum and
+    /// Polygon mainnet, which this crate didn't have chain id/contract data for at
+    /// all before this registry existed
+    pub fn with_known_networks(self) -> Self {
+        self.with_network(
+            "base",
+            NetworkEntry::new(8453, "Base", false)
+                .with_asset("0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913", Self::usdc_metadata()),
+        )
+        .with_network(
+            "base-sepolia",
+            NetworkEntry::new(84532, "Base Sepolia", true)
+                .with_asset("0x036CbD53842c5426634e7929541eC2318f3dCF7e", Self::usdc_metadata()),
+        )
+        .with_network(
+            "avalanche",
+            NetworkEntry::new(43114, "Avalanche C-Chain", false)
+                .with_asset("0xB97EF9Ef8734C71904D8002F8b6Bc66Dd9c48a6E", Self::usdc_metadata()),
+        )
+        .with_network(
+            "avalanche-fuji",
+            NetworkEntry::new(43113, "Avalanche Fuji", true)
+                .with_asset("0x5425890298aed601595a70AB815c96711a31Bc65", Self::usdc_metadata()),
+        )
+        .with_network(
+            "ethereum",
+            NetworkEntry::new(1, "Ethereum", false)
+                .with_asset("0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48", Self::usdc_metadata()),
+        )
+        .with_network(
+            "polygon",
+            NetworkEntry::new(137, "Polygon", false)
+                .with_asset("0x3c499c542cEF5E3811e1192ce70d8cC03d5c3359", Self::usdc_metadata()),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_known_networks_resolve_their_primary_asset() {
+        let registry = NetworkRegistry::new().with_known_networks();
+
+        let base = registry.lookup("base").expect("base should be registered");
+        assert_eq!(base.chain_id, 8453);
+        assert!(!base.is_testnet);
+        let primary = base.primary_asset().expect("base should have a primary asset");
+        assert_eq!(primary.address, "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913");
+        assert_eq!(primary.metadata.symbol, "USDC");
+    }
+
+    #[test]
+    fn test_ethereum_and_polygon_are_newly_registered() {
+        let registry = NetworkRegistry::new().with_known_networks();
+        assert_eq!(registry.lookup("ethereum").unwrap().chain_id, 1);
+        assert_eq!(registry.lookup("polygon").unwrap().chain_id, 137);
+    }
+
+    #[test]
+    fn test_lookup_misses_an_unregistered_network() {
+        let registry = NetworkRegistry::new().with_known_networks();
+        assert!(registry.lookup("solana").is_none());
+    }
+
+    #[test]
+    fn test_with_network_registers_a_custom_chain() {
+        let registry = NetworkRegistry::new().with_network(
+            "local-devnet",
+            NetworkEntry::new(1337, "Local Devnet", true).with_asset(
+                "0x1111111111111111111111111111111111111111",
+                TokenMetadata {
+                    symbol: "USDT".to_string(),
+                    name: "Tether".to_string(),
+                    decimals: 6,
+                    eip712_name: "Tether".to_string(),
+                    eip712_version: "1".to_string(),
+                },
+            ),
+        );
+
+        let entry = registry.lookup("local-devnet").unwrap();
+        assert_eq!(entry.chain_id, 1337);
+        assert_eq!(entry.primary_asset().unwrap().metadata.symbol, "USDT");
+    }
+
+    #[test]
+    fn test_asset_lookup_is_case_insensitive() {
+        let registry = NetworkRegistry::new().with_known_networks();
+        let base = registry.lookup("base").unwrap();
+        assert!(base
+            .asset("0x833589fcd6edb6e08f4c7c32d4f71b54bda02913")
+            .is_some());
+    }
+}
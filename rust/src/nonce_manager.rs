@@ -0,0 +1,168 @@
+//! Per-address nonce allocation for concurrent transaction signing
+//!
+//! Submitting several transactions for the same address in parallel needs each one
+//! assigned a distinct, sequential nonce up front — reading
+//! `eth_getTransactionCount` before every broadcast races once more than one
+//! submission for that address is in flight at a time. [`NonceManager`] fetches the
+//! pending transaction count once per address and hands out every nonce after that
+//! from an in-memory counter, so concurrent callers for the same address never
+//! collide; [`NonceManager::resync`] drops the cached counter for one address so the
+//! next allocation re-reads it from chain, for recovering from a "nonce too low"
+//! broadcast error without resetting every other address being managed.
+//!
+//! This is a lower-level building block than
+//! [`crate::facilitator_middleware::NonceManagerMiddleware`], which caches a single
+//! nonce for the facilitator's own relayer account inline in the settlement path; this
+//! one is keyed per address, for a caller (such as a gas-station-style facilitator
+//! signing from more than one account) managing several addresses through one
+//! instance.
+
+use crate::blockchain::BlockchainClient;
+use crate::Result;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// The on-chain nonce a given address started from, plus how many nonces have been
+/// handed out since
+struct AddressState {
+    initial_nonce: u64,
+    allocated: AtomicU64,
+}
+
+/// Hands out sequential, collision-free nonces per address, backed by
+/// [`BlockchainClient::get_transaction_count`]
+pub struct NonceManager {
+    blockchain: BlockchainClient,
+    state: Mutex<HashMap<String, Arc<AddressState>>>,
+}
+
+impl NonceManager {
+    /// Manage nonces for addresses on `blockchain`
+    pub fn new(blockchain: BlockchainClient) -> Self {
+        Self {
+            blockchain,
+            state: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Allocate the next nonce for `address`, fetching its pending transaction count
+    /// from chain on the first call for that address
+    pub async fn next_nonce(&self, address: &str) -> Result<u64> {
+        let address_state = self.address_state(address).await?;
+        Ok(address_state.initial_nonce + address_state.allocated.fetch_add(1, Ordering::SeqCst))
+    }
+
+    /// Drop the cached nonce counter for `address`, so its next [`Self::next_nonce`]
+    /// call re-fetches the transaction count from chain
+    ///
+    /// Call this after a broadcast fails with a "nonce too low" RPC error: the cached
+    /// counter has drifted from chain state (another process used the account, or a
+    /// previously allocated nonce was never actually broadcast) and handing out more
+    /// nonces from it would only keep failing the same way.
+    pub async fn resync(&self, address: &str) {
+        self.state.lock().await.remove(address);
+    }
+
+    /// Return the cached state for `address`, initializing it from chain if this is
+    /// the first time `address` has been seen
+    async fn address_state(&self, address: &str) -> Result<Arc<AddressState>> {
+        let mut state = self.state.lock().await;
+        if let Some(existing) = state.get(address) {
+            return Ok(existing.clone());
+        }
+
+        let initial_nonce = self.blockchain.get_transaction_count(address).await?;
+        let address_state = Arc::new(AddressState {
+            initial_nonce,
+            allocated: AtomicU64::new(0),
+        });
+        state.insert(address.to_string(), address_state.clone());
+        Ok(address_state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADDRESS: &str = "0x857b06519E91e3A54538791bDbb0E22373e36b66";
+    const OTHER_ADDRESS: &str = "0x209693Bc6afc0C5328bA36FaF03C514EF312287C";
+
+    fn mock_transaction_count_response(count_hex: &str) -> serde_json::Value {
+        serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": count_hex})
+    }
+
+    #[tokio::test]
+    async fn test_next_nonce_increments_from_the_initial_on_chain_count() {
+        let mut server = mockito::Server::new_async().await;
+        let _m = server
+            .mock("POST", "/")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(mock_transaction_count_response("0x5").to_string())
+            .expect(1)
+            .create();
+
+        let manager = NonceManager::new(BlockchainClient::new(server.url(), "base-sepolia".to_string()));
+
+        assert_eq!(manager.next_nonce(ADDRESS).await.unwrap(), 5);
+        assert_eq!(manager.next_nonce(ADDRESS).await.unwrap(), 6);
+        assert_eq!(manager.next_nonce(ADDRESS).await.unwrap(), 7);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_next_nonce_calls_never_collide() {
+        let mut server = mockito::Server::new_async().await;
+        let _m = server
+            .mock("POST", "/")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(mock_transaction_count_response("0x0").to_string())
+            .create();
+
+        let manager = Arc::new(NonceManager::new(BlockchainClient::new(
+            server.url(),
+            "base-sepolia".to_string(),
+        )));
+
+        let mut handles = Vec::new();
+        for _ in 0..20 {
+            let manager = manager.clone();
+            handles.push(tokio::spawn(async move { manager.next_nonce(ADDRESS).await.unwrap() }));
+        }
+
+        let mut nonces = Vec::new();
+        for handle in handles {
+            nonces.push(handle.await.unwrap());
+        }
+        nonces.sort_unstable();
+
+        assert_eq!(nonces, (0..20).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn test_resync_forces_a_fresh_on_chain_read_for_only_that_address() {
+        let mut server = mockito::Server::new_async().await;
+        let _m = server
+            .mock("POST", "/")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(mock_transaction_count_response("0x2").to_string())
+            .expect(2)
+            .create();
+
+        let manager = NonceManager::new(BlockchainClient::new(server.url(), "base-sepolia".to_string()));
+
+        assert_eq!(manager.next_nonce(ADDRESS).await.unwrap(), 2);
+        assert_eq!(manager.next_nonce(OTHER_ADDRESS).await.unwrap(), 2);
+
+        manager.resync(ADDRESS).await;
+
+        // ADDRESS re-reads from chain (second expected request); OTHER_ADDRESS's
+        // cached counter is untouched and keeps incrementing locally.
+        assert_eq!(manager.next_nonce(ADDRESS).await.unwrap(), 2);
+        assert_eq!(manager.next_nonce(OTHER_ADDRESS).await.unwrap(), 3);
+    }
+}
@@ -0,0 +1,699 @@
+//! Nonce replay-protection store, bloom-filtered per network
+//!
+//! A facilitator must never settle the same EIP-3009 authorization nonce twice, but
+//! checking every `verify` call against a durable store (Redis, sqlite, ...) under lock
+//! doesn't scale to high request volume. [`BloomFilteredNonceStore`] puts a per-network
+//! [`NetworkBloomFilter`] in front of any [`NonceStore`] backing store, mirroring how
+//! web3-proxy answers "have we seen this deposit event before" without touching its
+//! backing store on the common case: a bloom miss means the nonce is *definitely*
+//! unused and the backing store is skipped entirely; a bloom hit falls through to the
+//! backing store to rule out a false positive.
+//!
+//! [`InMemoryNonceStore`] is the default, single-process [`NonceStore`] backing store;
+//! implement the trait against Redis or sqlite to share nonce state across facilitator
+//! instances, the same extension point [`crate::idempotency::IdempotencyStore`] uses.
+//!
+//! [`reject_nonce_replay`] additionally tracks which resource a nonce was settled
+//! against, so a client retrying the same request against the same resource reaches
+//! [`crate::idempotency::IdempotentSettlement`]'s cache instead of being rejected
+//! outright by a blanket "nonce already used" check.
+//!
+//! [`NonceReplayStore`] is a second, narrower check: it's keyed by `(payer, nonce)`
+//! rather than `(network, nonce)`, runs before a facilitator ever sees the payload
+//! (not just at settlement), and bounds its own size by pruning entries once their
+//! authorization's `validBefore` has passed instead of retaining every nonce forever.
+
+use crate::{Result, X402Error};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Mutex;
+use tokio::sync::Mutex as AsyncMutex;
+
+#[cfg(feature = "sqlite")]
+use rusqlite::{params, Connection, OptionalExtension};
+#[cfg(feature = "sqlite")]
+use std::path::Path;
+
+/// A boxed, `Send` future, used in place of `async fn` in [`NonceStore`] for the same
+/// reason as [`crate::idempotency::BoxFuture`]: traits can't have object-safe async
+/// methods on stable Rust without an extra proc-macro crate this workspace doesn't
+/// otherwise depend on.
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Storage backend for nonce replay protection
+///
+/// Checked at verify time (`contains`) and recorded at settle time (`mark_used`), so a
+/// nonce is only durably reserved once its authorization has actually been settled —
+/// matching the request body's split between verification (read-only) and settlement
+/// (the point a nonce is spent).
+pub trait NonceStore: Send + Sync {
+    /// Returns `true` if `nonce` has already been marked used for `network`
+    fn contains(&self, network: &str, nonce: &str) -> BoxFuture<'_, bool>;
+
+    /// Marks `nonce` as used for `network`, so future `contains` calls return `true`
+    fn mark_used(&self, network: &str, nonce: &str) -> BoxFuture<'_, ()>;
+
+    /// The resource `nonce` was settled against on `network`, if this store records one
+    /// and the nonce has been used. Defaults to `None` (even for a used nonce), so a
+    /// backing store that only tracks "used or not" keeps [`reject_nonce_replay`]'s
+    /// conservative fallback: without a recorded resource to compare against, any reuse
+    /// is rejected, same as [`reject_if_nonce_used`].
+    fn resource_for(&self, _network: &str, _nonce: &str) -> BoxFuture<'_, Option<String>> {
+        Box::pin(async { None })
+    }
+
+    /// Like [`Self::mark_used`], additionally recording `resource` so a later
+    /// [`Self::resource_for`] call can report it. Defaults to ignoring `resource` and
+    /// deferring to [`Self::mark_used`], for a store that doesn't implement
+    /// [`Self::resource_for`] either.
+    fn mark_used_for_resource(&self, network: &str, nonce: &str, resource: &str) -> BoxFuture<'_, ()> {
+        let _ = resource;
+        self.mark_used(network, nonce)
+    }
+}
+
+/// Check `store` for `nonce` on `network`, returning [`X402Error::NonceAlreadyUsed`] if
+/// it's already been marked used, and marking it used otherwise
+///
+/// A facilitator's settlement path would call this in place of a bare `contains`/
+/// `mark_used` pair to get the structured error for an already-spent authorization
+/// instead of having to construct one itself at every call site.
+pub async fn reject_if_nonce_used(store: &dyn NonceStore, network: &str, nonce: &str) -> Result<()> {
+    if store.contains(network, nonce).await {
+        return Err(X402Error::nonce_already_used(nonce));
+    }
+    store.mark_used(network, nonce).await;
+    Ok(())
+}
+
+/// Like [`reject_if_nonce_used`], but tolerates a nonce already marked used as long as
+/// [`NonceStore::resource_for`] reports it was settled against this same `resource` —
+/// the shape a client retrying the same request with its original `X-PAYMENT` header
+/// presents — and only rejects it as [`X402Error::NonceAlreadyUsed`] when it was
+/// settled against a different resource (or the store can't say which). Closes the
+/// replay window a blanket [`reject_if_nonce_used`] check would also close, without
+/// also blocking the legitimate same-resource retry a
+/// [`crate::idempotency::IdempotentSettlement`] further down the settlement path is
+/// meant to serve from its cache instead of resettling.
+pub async fn reject_nonce_replay(
+    store: &dyn NonceStore,
+    network: &str,
+    nonce: &str,
+    resource: &str,
+) -> Result<()> {
+    if store.contains(network, nonce).await {
+        return match store.resource_for(network, nonce).await {
+            Some(used_resource) if used_resource == resource => Ok(()),
+            _ => Err(X402Error::nonce_already_used(nonce)),
+        };
+    }
+    store.mark_used_for_resource(network, nonce, resource).await;
+    Ok(())
+}
+
+/// In-memory [`NonceStore`], suitable for a single-process deployment
+///
+/// Entries are never evicted: a settled nonce must never become reusable, unlike
+/// [`crate::idempotency::InMemoryIdempotencyStore`]'s timeout-based eviction of
+/// in-flight attempts.
+#[derive(Debug, Default)]
+pub struct InMemoryNonceStore {
+    used: AsyncMutex<HashMap<(String, String), Option<String>>>,
+}
+
+impl InMemoryNonceStore {
+    /// Create an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl NonceStore for InMemoryNonceStore {
+    fn contains(&self, network: &str, nonce: &str) -> BoxFuture<'_, bool> {
+        let key = (network.to_string(), nonce.to_string());
+        Box::pin(async move { self.used.lock().await.contains_key(&key) })
+    }
+
+    fn mark_used(&self, network: &str, nonce: &str) -> BoxFuture<'_, ()> {
+        let key = (network.to_string(), nonce.to_string());
+        Box::pin(async move {
+            self.used.lock().await.insert(key, None);
+        })
+    }
+
+    fn resource_for(&self, network: &str, nonce: &str) -> BoxFuture<'_, Option<String>> {
+        let key = (network.to_string(), nonce.to_string());
+        Box::pin(async move { self.used.lock().await.get(&key).cloned().flatten() })
+    }
+
+    fn mark_used_for_resource(&self, network: &str, nonce: &str, resource: &str) -> BoxFuture<'_, ()> {
+        let key = (network.to_string(), nonce.to_string());
+        let resource = resource.to_string();
+        Box::pin(async move {
+            self.used.lock().await.insert(key, Some(resource));
+        })
+    }
+}
+
+/// A fixed-size Bloom filter over byte strings, using the Kirsch-Mitzenmacher double
+/// hashing trick (`h_i = h1 + i * h2`) to derive `num_hashes` bit indices from two
+/// `SHA-256` digests instead of computing `num_hashes` independent hashes
+struct BloomFilter {
+    bits: Vec<bool>,
+    num_hashes: u32,
+    inserted: usize,
+}
+
+impl BloomFilter {
+    fn new(num_bits: usize, num_hashes: u32) -> Self {
+        Self {
+            bits: vec![false; num_bits.max(1)],
+            num_hashes: num_hashes.max(1),
+            inserted: 0,
+        }
+    }
+
+    fn hash_indices(&self, item: &[u8]) -> impl Iterator<Item = usize> + '_ {
+        let h1 = u64::from_le_bytes(Sha256::digest(item)[0..8].try_into().unwrap());
+        let mut salted = Vec::with_capacity(item.len() + 1);
+        salted.push(0xffu8);
+        salted.extend_from_slice(item);
+        let h2 = u64::from_le_bytes(Sha256::digest(&salted)[0..8].try_into().unwrap());
+        let num_bits = self.bits.len() as u64;
+        (0..self.num_hashes).map(move |i| (h1.wrapping_add(i as u64 * h2) % num_bits) as usize)
+    }
+
+    fn insert(&mut self, item: &[u8]) {
+        for index in self.hash_indices(item) {
+            self.bits[index] = true;
+        }
+        self.inserted += 1;
+    }
+
+    fn might_contain(&self, item: &[u8]) -> bool {
+        self.hash_indices(item).all(|index| self.bits[index])
+    }
+}
+
+/// A [`BloomFilter`] that rotates into a fresh filter once it has absorbed `capacity`
+/// insertions, keeping the false-positive rate bounded under sustained traffic instead
+/// of letting one filter fill up forever
+///
+/// `might_contain` checks both the current and the just-retired previous filter, since
+/// a nonce inserted just before rotation must still be found.
+struct NetworkBloomFilter {
+    current: BloomFilter,
+    previous: Option<BloomFilter>,
+    num_bits: usize,
+    num_hashes: u32,
+    capacity: usize,
+}
+
+impl NetworkBloomFilter {
+    fn new(num_bits: usize, num_hashes: u32, capacity: usize) -> Self {
+        Self {
+            current: BloomFilter::new(num_bits, num_hashes),
+            previous: None,
+            num_bits,
+            num_hashes,
+            capacity: capacity.max(1),
+        }
+    }
+
+    fn might_contain(&self, item: &[u8]) -> bool {
+        self.current.might_contain(item)
+            || self
+                .previous
+                .as_ref()
+                .is_some_and(|previous| previous.might_contain(item))
+    }
+
+    fn insert(&mut self, item: &[u8]) {
+        if self.current.inserted >= self.capacity {
+            let retired = std::mem::replace(&mut self.current, BloomFilter::new(self.num_bits, self.num_hashes));
+            self.previous = Some(retired);
+        }
+        self.current.insert(item);
+    }
+}
+
+/// Default number of bits per per-network bloom filter (~1.25MB, tuned for a
+/// sub-percent false-positive rate at [`DEFAULT_ROTATE_AFTER`] capacity)
+const DEFAULT_NUM_BITS: usize = 1 << 23;
+
+/// Number of hash functions per bloom filter, the standard choice for a filter sized
+/// for roughly one million entries at a 1-in-1000 false-positive rate
+const DEFAULT_NUM_HASHES: u32 = 7;
+
+/// Number of insertions a per-network filter absorbs before rotating into a fresh one
+const DEFAULT_ROTATE_AFTER: usize = 1_000_000;
+
+/// A [`NonceStore`] that fronts a backing store with a per-network [`NetworkBloomFilter`]
+///
+/// On a bloom miss, `contains` returns `false` without touching the backing store at
+/// all. On a bloom hit, it falls through to the backing store to rule out a false
+/// positive, so correctness never depends on the filter's false-positive rate — only
+/// throughput under high request volume does.
+pub struct BloomFilteredNonceStore {
+    backing: std::sync::Arc<dyn NonceStore>,
+    filters: Mutex<HashMap<String, NetworkBloomFilter>>,
+    num_bits: usize,
+    num_hashes: u32,
+    rotate_after: usize,
+}
+
+impl BloomFilteredNonceStore {
+    /// Wrap `backing` with a bloom filter using the default sizing
+    pub fn new(backing: std::sync::Arc<dyn NonceStore>) -> Self {
+        Self {
+            backing,
+            filters: Mutex::new(HashMap::new()),
+            num_bits: DEFAULT_NUM_BITS,
+            num_hashes: DEFAULT_NUM_HASHES,
+            rotate_after: DEFAULT_ROTATE_AFTER,
+        }
+    }
+
+    /// Override the per-network filter's bit width, hash count, and rotation capacity
+    pub fn with_bloom_params(mut self, num_bits: usize, num_hashes: u32, rotate_after: usize) -> Self {
+        self.num_bits = num_bits;
+        self.num_hashes = num_hashes;
+        self.rotate_after = rotate_after;
+        self
+    }
+
+    fn might_contain(&self, network: &str, nonce: &str) -> bool {
+        let mut filters = self.filters.lock().unwrap();
+        filters
+            .entry(network.to_string())
+            .or_insert_with(|| NetworkBloomFilter::new(self.num_bits, self.num_hashes, self.rotate_after))
+            .might_contain(nonce.as_bytes())
+    }
+
+    fn insert(&self, network: &str, nonce: &str) {
+        let mut filters = self.filters.lock().unwrap();
+        filters
+            .entry(network.to_string())
+            .or_insert_with(|| NetworkBloomFilter::new(self.num_bits, self.num_hashes, self.rotate_after))
+            .insert(nonce.as_bytes());
+    }
+}
+
+impl NonceStore for BloomFilteredNonceStore {
+    fn contains(&self, network: &str, nonce: &str) -> BoxFuture<'_, bool> {
+        Box::pin(async move {
+            if !self.might_contain(network, nonce) {
+                return false;
+            }
+            self.backing.contains(network, nonce).await
+        })
+    }
+
+    fn mark_used(&self, network: &str, nonce: &str) -> BoxFuture<'_, ()> {
+        self.insert(network, nonce);
+        Box::pin(self.backing.mark_used(network, nonce))
+    }
+
+    fn resource_for(&self, network: &str, nonce: &str) -> BoxFuture<'_, Option<String>> {
+        // The bloom filter only ever answers "used or not" — it has no room to carry a
+        // resource string — so a resource lookup always goes to the backing store,
+        // unlike `contains`, which can short-circuit on a miss.
+        Box::pin(self.backing.resource_for(network, nonce))
+    }
+
+    fn mark_used_for_resource(&self, network: &str, nonce: &str, resource: &str) -> BoxFuture<'_, ()> {
+        self.insert(network, nonce);
+        Box::pin(self.backing.mark_used_for_resource(network, nonce, resource))
+    }
+}
+
+/// Time-bounded nonce replay protection keyed by `(payer, nonce)`, unlike
+/// [`NonceStore`]'s `(network, nonce)`
+///
+/// An [`crate::types::ExactEvmPayloadAuthorization`]'s `valid_before` already bounds
+/// how long a given nonce can ever be presented: once it passes,
+/// [`crate::types::ExactEvmPayloadAuthorization::is_valid_now`] rejects the
+/// authorization regardless of whether this store remembers it. That lets
+/// [`Self::prune_expired`] drop an entry as soon as its window closes without
+/// reopening a replay hole, bounding the store's size the way
+/// [`BloomFilteredNonceStore`] bounds memory for the unbounded [`InMemoryNonceStore`]
+/// — but by actually forgetting expired entries rather than tolerating a bloom
+/// filter's false positives.
+pub trait NonceReplayStore: Send + Sync {
+    /// Record `(payer, nonce)` as consumed through `valid_before` (Unix seconds),
+    /// returning [`X402Error::NonceReused`] if it's already recorded and that window
+    /// hasn't passed yet. A re-presentation after `valid_before` has passed re-reserves
+    /// the pair instead of erroring, since the authorization it belonged to is already
+    /// unusable on its own terms.
+    fn check_and_reserve(&self, payer: &str, nonce: &str, valid_before: i64) -> BoxFuture<'_, Result<()>>;
+
+    /// Drop every recorded entry whose `valid_before` is at or before `now`
+    fn prune_expired(&self, now: i64) -> BoxFuture<'_, ()>;
+}
+
+/// In-memory [`NonceReplayStore`], suitable for a single-process deployment
+pub struct InMemoryNonceReplayStore {
+    reserved: AsyncMutex<HashMap<(String, String), i64>>,
+    clock: std::sync::Arc<dyn crate::payment_lifecycle::Clock>,
+}
+
+impl InMemoryNonceReplayStore {
+    /// Create an empty store reading the real system clock
+    pub fn new() -> Self {
+        Self::with_clock(std::sync::Arc::new(crate::payment_lifecycle::SystemClock))
+    }
+
+    /// Create an empty store reading time from `clock`, e.g. a
+    /// [`crate::payment_lifecycle::FixedClock`] in tests
+    pub fn with_clock(clock: std::sync::Arc<dyn crate::payment_lifecycle::Clock>) -> Self {
+        Self {
+            reserved: AsyncMutex::new(HashMap::new()),
+            clock,
+        }
+    }
+}
+
+impl Default for InMemoryNonceReplayStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NonceReplayStore for InMemoryNonceReplayStore {
+    fn check_and_reserve(&self, payer: &str, nonce: &str, valid_before: i64) -> BoxFuture<'_, Result<()>> {
+        let key = (payer.to_string(), nonce.to_string());
+        Box::pin(async move {
+            let now = self.clock.now();
+            let mut reserved = self.reserved.lock().await;
+            // Lazily prune everything else that's expired while the lock is already
+            // held, the same "evict on next access" approach
+            // `InMemoryIdempotencyStore` uses, rather than requiring a caller to drive
+            // `prune_expired` on a timer for the store to stay bounded.
+            reserved.retain(|existing_key, existing_valid_before| {
+                existing_key == &key || *existing_valid_before > now
+            });
+            if let Some(existing_valid_before) = reserved.get(&key) {
+                if *existing_valid_before > now {
+                    return Err(X402Error::nonce_reused(payer, nonce));
+                }
+            }
+            reserved.insert(key, valid_before);
+            Ok(())
+        })
+    }
+
+    fn prune_expired(&self, now: i64) -> BoxFuture<'_, ()> {
+        Box::pin(async move {
+            self.reserved.lock().await.retain(|_, valid_before| *valid_before > now);
+        })
+    }
+}
+
+/// SQLite-backed [`NonceReplayStore`], for sharing replay state across facilitator
+/// instances the way [`crate::discovery_store::DiscoveryCache`] shares discovery
+/// state. Gated behind the `sqlite` feature, same as that cache.
+#[cfg(feature = "sqlite")]
+pub struct SqliteNonceReplayStore {
+    conn: AsyncMutex<Connection>,
+}
+
+#[cfg(feature = "sqlite")]
+impl SqliteNonceReplayStore {
+    /// Open (creating if necessary) a store backed by the SQLite database at `path`
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::from_connection(Connection::open(path).map_err(sqlite_error)?)
+    }
+
+    /// Open an in-memory store, e.g. for tests or a short-lived process
+    pub fn open_in_memory() -> Result<Self> {
+        Self::from_connection(Connection::open_in_memory().map_err(sqlite_error)?)
+    }
+
+    fn from_connection(conn: Connection) -> Result<Self> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS nonce_replay (
+                payer        TEXT NOT NULL,
+                nonce        TEXT NOT NULL,
+                valid_before INTEGER NOT NULL,
+                PRIMARY KEY (payer, nonce)
+            )",
+        )
+        .map_err(sqlite_error)?;
+        Ok(Self { conn: AsyncMutex::new(conn) })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl NonceReplayStore for SqliteNonceReplayStore {
+    fn check_and_reserve(&self, payer: &str, nonce: &str, valid_before: i64) -> BoxFuture<'_, Result<()>> {
+        let payer = payer.to_string();
+        let nonce = nonce.to_string();
+        Box::pin(async move {
+            let now = chrono::Utc::now().timestamp();
+            let conn = self.conn.lock().await;
+            let existing: Option<i64> = conn
+                .query_row(
+                    "SELECT valid_before FROM nonce_replay WHERE payer = ?1 AND nonce = ?2",
+                    params![payer, nonce],
+                    |row| row.get(0),
+                )
+                .optional()
+                .map_err(sqlite_error)?;
+
+            // A stale row (its own `valid_before` already passed) is overwritten
+            // rather than treated as a conflict, mirroring
+            // `InMemoryNonceReplayStore::check_and_reserve`.
+            if let Some(existing_valid_before) = existing {
+                if existing_valid_before > now {
+                    return Err(X402Error::nonce_reused(payer, nonce));
+                }
+            }
+
+            conn.execute(
+                "INSERT INTO nonce_replay (payer, nonce, valid_before) VALUES (?1, ?2, ?3)
+                 ON CONFLICT(payer, nonce) DO UPDATE SET valid_before = excluded.valid_before",
+                params![payer, nonce, valid_before],
+            )
+            .map_err(sqlite_error)?;
+            Ok(())
+        })
+    }
+
+    fn prune_expired(&self, now: i64) -> BoxFuture<'_, ()> {
+        Box::pin(async move {
+            let conn = self.conn.lock().await;
+            let _ = conn.execute("DELETE FROM nonce_replay WHERE valid_before <= ?1", params![now]);
+        })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+fn sqlite_error(error: rusqlite::Error) -> X402Error {
+    X402Error::config(format!("sqlite nonce replay store error: {error}"))
+}
+
+/// Check `store` for `(payer, nonce)`, returning [`X402Error::NonceReused`] if it's
+/// already reserved within its window, and reserving it through `valid_before`
+/// otherwise
+pub async fn reject_nonce_reuse(
+    store: &dyn NonceReplayStore,
+    payer: &str,
+    nonce: &str,
+    valid_before: i64,
+) -> Result<()> {
+    store.check_and_reserve(payer, nonce, valid_before).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_in_memory_nonce_store_round_trips() {
+        let store = InMemoryNonceStore::new();
+        assert!(!store.contains("base-sepolia", "0xabc").await);
+        store.mark_used("base-sepolia", "0xabc").await;
+        assert!(store.contains("base-sepolia", "0xabc").await);
+    }
+
+    #[tokio::test]
+    async fn test_reject_if_nonce_used_marks_an_unused_nonce_and_rejects_it_next_time() {
+        let store = InMemoryNonceStore::new();
+        assert!(reject_if_nonce_used(&store, "base-sepolia", "0xabc").await.is_ok());
+
+        let error = reject_if_nonce_used(&store, "base-sepolia", "0xabc").await.unwrap_err();
+        assert!(matches!(error, crate::X402Error::NonceAlreadyUsed { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_nonce_store_is_scoped_per_network() {
+        let store = InMemoryNonceStore::new();
+        store.mark_used("base-sepolia", "0xabc").await;
+        assert!(!store.contains("base", "0xabc").await);
+    }
+
+    #[test]
+    fn test_bloom_filter_has_no_false_negatives() {
+        let mut filter = BloomFilter::new(1 << 16, 7);
+        for i in 0..1000 {
+            filter.insert(format!("nonce-{i}").as_bytes());
+        }
+        for i in 0..1000 {
+            assert!(filter.might_contain(format!("nonce-{i}").as_bytes()));
+        }
+    }
+
+    #[test]
+    fn test_network_bloom_filter_rotates_without_losing_recent_entries() {
+        let mut filter = NetworkBloomFilter::new(1 << 12, 7, 4);
+        for i in 0..10 {
+            filter.insert(format!("nonce-{i}").as_bytes());
+        }
+        // Rotation happened (capacity 4, 10 insertions), but the most recent entry
+        // inserted just before this point must still be found via `current`.
+        assert!(filter.previous.is_some());
+        assert!(filter.might_contain(b"nonce-9"));
+    }
+
+    #[tokio::test]
+    async fn test_bloom_filtered_store_skips_backing_store_on_miss() {
+        struct PanicsOnContains;
+        impl NonceStore for PanicsOnContains {
+            fn contains(&self, _network: &str, _nonce: &str) -> BoxFuture<'_, bool> {
+                Box::pin(async { panic!("backing store should not be queried on a bloom miss") })
+            }
+            fn mark_used(&self, _network: &str, _nonce: &str) -> BoxFuture<'_, ()> {
+                Box::pin(async {})
+            }
+        }
+
+        let store = BloomFilteredNonceStore::new(Arc::new(PanicsOnContains));
+        assert!(!store.contains("base-sepolia", "0xnever-seen").await);
+    }
+
+    #[tokio::test]
+    async fn test_bloom_filtered_store_falls_through_to_backing_store_on_hit() {
+        let backing = Arc::new(InMemoryNonceStore::new());
+        let store = BloomFilteredNonceStore::new(backing.clone());
+
+        store.mark_used("base-sepolia", "0xabc").await;
+        assert!(backing.contains("base-sepolia", "0xabc").await);
+        assert!(store.contains("base-sepolia", "0xabc").await);
+
+        // A different, never-inserted nonce must not be reported as used even though
+        // the filter has entries for this network.
+        assert!(!store.contains("base-sepolia", "0xnever-used").await);
+    }
+
+    #[tokio::test]
+    async fn test_reject_nonce_replay_allows_a_same_resource_retry() {
+        let store = InMemoryNonceStore::new();
+        assert!(reject_nonce_replay(&store, "base-sepolia", "0xabc", "https://api.example.com/report")
+            .await
+            .is_ok());
+
+        // A retry against the same resource (e.g. the client resubmitting its original
+        // X-PAYMENT header after a dropped response) must be let through, not rejected.
+        assert!(reject_nonce_replay(&store, "base-sepolia", "0xabc", "https://api.example.com/report")
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_reject_nonce_replay_rejects_a_different_resource() {
+        let store = InMemoryNonceStore::new();
+        assert!(reject_nonce_replay(&store, "base-sepolia", "0xabc", "https://api.example.com/report")
+            .await
+            .is_ok());
+
+        let error = reject_nonce_replay(&store, "base-sepolia", "0xabc", "https://api.example.com/other")
+            .await
+            .unwrap_err();
+        assert!(matches!(error, crate::X402Error::NonceAlreadyUsed { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_reject_nonce_replay_rejects_when_store_cannot_report_a_resource() {
+        // A nonce marked used via the plain `mark_used`/`reject_if_nonce_used` path (no
+        // resource recorded) must be treated as a replay against any resource, matching
+        // `resource_for`'s documented conservative default.
+        let store = InMemoryNonceStore::new();
+        store.mark_used("base-sepolia", "0xabc").await;
+
+        let error = reject_nonce_replay(&store, "base-sepolia", "0xabc", "https://api.example.com/report")
+            .await
+            .unwrap_err();
+        assert!(matches!(error, crate::X402Error::NonceAlreadyUsed { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_bloom_filtered_store_forwards_resource_tracking_to_backing_store() {
+        let backing = Arc::new(InMemoryNonceStore::new());
+        let store = BloomFilteredNonceStore::new(backing.clone());
+
+        assert!(reject_nonce_replay(&store, "base-sepolia", "0xabc", "https://api.example.com/report")
+            .await
+            .is_ok());
+        assert!(reject_nonce_replay(&store, "base-sepolia", "0xabc", "https://api.example.com/report")
+            .await
+            .is_ok());
+
+        let error = reject_nonce_replay(&store, "base-sepolia", "0xabc", "https://api.example.com/other")
+            .await
+            .unwrap_err();
+        assert!(matches!(error, crate::X402Error::NonceAlreadyUsed { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_nonce_replay_store_rejects_reuse_within_the_validity_window() {
+        let clock = Arc::new(crate::payment_lifecycle::FixedClock::new(1_000));
+        let store = InMemoryNonceReplayStore::with_clock(clock);
+
+        assert!(store.check_and_reserve("0xpayer", "0xabc", 1_300).await.is_ok());
+
+        let error = store.check_and_reserve("0xpayer", "0xabc", 1_300).await.unwrap_err();
+        assert!(matches!(error, crate::X402Error::NonceReused { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_nonce_replay_store_allows_reuse_once_valid_before_has_passed() {
+        let clock = Arc::new(crate::payment_lifecycle::FixedClock::new(1_000));
+        let store = InMemoryNonceReplayStore::with_clock(clock.clone());
+
+        assert!(store.check_and_reserve("0xpayer", "0xabc", 1_300).await.is_ok());
+
+        clock.advance(500);
+        // The authorization's own `validBefore` has already passed, so a resubmission
+        // re-reserves instead of being rejected as a replay — it would fail the
+        // validity-window check regardless.
+        assert!(store.check_and_reserve("0xpayer", "0xabc", 1_900).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_nonce_replay_store_prune_expired_drops_only_past_entries() {
+        let clock = Arc::new(crate::payment_lifecycle::FixedClock::new(1_000));
+        let store = InMemoryNonceReplayStore::with_clock(clock);
+
+        store.check_and_reserve("0xpayer", "0xabc", 1_300).await.unwrap();
+        store.check_and_reserve("0xpayer", "0xdef", 2_000).await.unwrap();
+
+        store.prune_expired(1_500).await;
+
+        // "0xabc" expired at 1_300 and should have been pruned, so reusing it now
+        // succeeds instead of being rejected.
+        assert!(store.check_and_reserve("0xpayer", "0xabc", 2_500).await.is_ok());
+        // "0xdef" is still inside its window and must still be rejected.
+        let error = store.check_and_reserve("0xpayer", "0xdef", 2_500).await.unwrap_err();
+        assert!(matches!(error, crate::X402Error::NonceReused { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_nonce_replay_store_is_scoped_per_payer() {
+        let store = InMemoryNonceReplayStore::new();
+        assert!(store.check_and_reserve("0xpayer-a", "0xabc", 9_999_999_999).await.is_ok());
+        assert!(store.check_and_reserve("0xpayer-b", "0xabc", 9_999_999_999).await.is_ok());
+    }
+}
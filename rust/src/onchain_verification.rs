@@ -0,0 +1,351 @@
+//! Trustless on-chain confirmation of a facilitator's settlement
+//!
+//! [`crate::idempotency`] and [`crate::async_settlement`] both still ultimately trust
+//! whatever [`crate::types::SettleResponse`] the facilitator returns. This module adds
+//! an independent check: given the transaction hash a facilitator claims settled a
+//! payment, fetch its receipt and confirm it actually contains an ERC-20
+//! `Transfer(from, to, value)` log (or several, for a batched/multi-deposit
+//! settlement that splits the payment across more than one transfer) moving at least
+//! the required amount from the payer to `pay_to` on the expected token contract.
+//!
+//! Decoding every log in a receipt is wasted work for the overwhelming majority of
+//! transactions, which don't settle this particular payment at all, so
+//! [`receipt_may_contain_transfer`] tests the receipt's `logsBloom` against the
+//! `Transfer` event's topic hash and the two indexed address topics first (the same
+//! technique `eth_getLogs` RPC nodes use to skip whole blocks), and only decodes
+//! `logs` when the bloom can't rule the match out.
+
+use crate::blockchain::{BlockchainClient, TransactionLog, TransactionReceipt};
+use crate::{Result, X402Error};
+use sha3::{Digest, Keccak256};
+
+/// `keccak256("Transfer(address,address,uint256)")`, the topic every ERC-20
+/// `Transfer` log carries as `topics[0]`
+pub fn transfer_event_topic() -> [u8; 32] {
+    Keccak256::digest(b"Transfer(address,address,uint256)").into()
+}
+
+/// The ERC-20 transfer a settlement's receipt must contain (possibly split across
+/// several `Transfer` logs) to be accepted
+#[derive(Debug, Clone)]
+pub struct ExpectedTransfer {
+    /// Token contract address the `Transfer` log(s) must be emitted from
+    pub token: String,
+    /// Payer address, matched against each log's indexed `from` topic
+    pub from: String,
+    /// Recipient address, matched against each log's indexed `to` topic
+    pub to: String,
+    /// Minimum total value the matching `Transfer` log(s) must sum to
+    pub min_value: u128,
+}
+
+impl ExpectedTransfer {
+    pub fn new(
+        token: impl Into<String>,
+        from: impl Into<String>,
+        to: impl Into<String>,
+        min_value: u128,
+    ) -> Self {
+        Self {
+            token: token.into(),
+            from: from.into(),
+            to: to.into(),
+            min_value,
+        }
+    }
+}
+
+/// The outcome of a successful [`OnchainSettlementVerifier::verify`] call: what the
+/// matching `Transfer` log(s) actually showed, for a caller that wants to report more
+/// than a bare yes/no (e.g. [`crate::settlement_confirmation::ConfirmationOutcome::Confirmed`])
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifiedTransfer {
+    /// Sum of the matching `Transfer` log(s)' values; at least `expected.min_value`
+    pub total_value: u128,
+    /// Block the transaction was mined in, if the receipt carried one
+    pub block_number: Option<u64>,
+}
+
+/// Confirms a facilitator's settlement by independently re-deriving the ERC-20
+/// transfer it claims from the chain, instead of trusting `SettleResponse.success`
+/// at face value
+pub struct OnchainSettlementVerifier {
+    blockchain: BlockchainClient,
+}
+
+impl OnchainSettlementVerifier {
+    /// Verify settlements by querying `blockchain`'s RPC endpoint
+    pub fn new(blockchain: BlockchainClient) -> Self {
+        Self { blockchain }
+    }
+
+    /// Fetch `tx_hash`'s receipt and confirm it matches `expected`
+    ///
+    /// Returns [`X402Error::PaymentVerificationFailed`] naming the specific
+    /// discrepancy (reverted transaction, bloom filter ruling out the event
+    /// entirely, or an insufficient total transferred) rather than a generic failure.
+    pub async fn verify(&self, tx_hash: &str, expected: &ExpectedTransfer) -> Result<VerifiedTransfer> {
+        let receipt = self.blockchain.get_receipt(tx_hash).await?;
+
+        if receipt.status != "0x1" {
+            return Err(X402Error::payment_verification_failed(format!(
+                "transaction {tx_hash} did not succeed on-chain (status {})",
+                receipt.status
+            )));
+        }
+
+        if !receipt_may_contain_transfer(&receipt, expected)? {
+            return Err(X402Error::payment_verification_failed(format!(
+                "transaction {tx_hash}'s logsBloom rules out a Transfer from {} to {} on {}",
+                expected.from, expected.to, expected.token
+            )));
+        }
+
+        let total = total_matching_value(&receipt.logs, expected)?;
+        if total < expected.min_value {
+            return Err(X402Error::payment_verification_failed(format!(
+                "transaction {tx_hash} transferred {total} from {} to {} on {}, less than the required {}",
+                expected.from, expected.to, expected.token, expected.min_value
+            )));
+        }
+
+        let block_number = receipt
+            .block_number
+            .as_deref()
+            .map(|hex| parse_hex_u64(hex))
+            .transpose()?;
+
+        Ok(VerifiedTransfer {
+            total_value: total,
+            block_number,
+        })
+    }
+}
+
+/// Parse a `"0x..."`-prefixed hex string into a `u64`
+fn parse_hex_u64(hex: &str) -> Result<u64> {
+    u64::from_str_radix(hex.trim_start_matches("0x"), 16)
+        .map_err(|_| X402Error::malformed_payload("hex-encoded block number"))
+}
+
+/// Test whether a 256-byte (2048-bit) `logsBloom` could contain `item`, using the
+/// same 3-bit-per-item scheme Ethereum clients populate it with
+pub(crate) fn bloom_may_contain(bloom: &[u8; 256], item: &[u8]) -> bool {
+    let hash = Keccak256::digest(item);
+    for pair in 0..3 {
+        let word = u16::from_be_bytes([hash[pair * 2], hash[pair * 2 + 1]]) & 0x07ff;
+        let byte_index = 255 - (word / 8) as usize;
+        let bit_index = (word % 8) as u8;
+        if bloom[byte_index] & (1 << bit_index) == 0 {
+            return false;
+        }
+    }
+    true
+}
+
+/// Pad a 20-byte address to the 32-byte word an indexed `address` topic uses
+pub(crate) fn address_topic(address: &str) -> Result<[u8; 32]> {
+    let bytes = decode_hex(address)?;
+    if bytes.len() != 20 {
+        return Err(X402Error::invalid_authorization(format!(
+            "expected a 20-byte address, got {} bytes: {address}",
+            bytes.len()
+        )));
+    }
+    let mut topic = [0u8; 32];
+    topic[12..].copy_from_slice(&bytes);
+    Ok(topic)
+}
+
+pub(crate) fn decode_hex(value: &str) -> Result<Vec<u8>> {
+    hex::decode(value.trim_start_matches("0x"))
+        .map_err(|_| X402Error::malformed_payload("hex value"))
+}
+
+/// Test the receipt's `logsBloom` against the `Transfer` event's topic and the
+/// `from`/`to` address topics, without decoding any log
+fn receipt_may_contain_transfer(
+    receipt: &TransactionReceipt,
+    expected: &ExpectedTransfer,
+) -> Result<bool> {
+    let bloom_bytes = decode_hex(&receipt.logs_bloom)?;
+    let bloom: [u8; 256] = bloom_bytes
+        .try_into()
+        .map_err(|_| X402Error::malformed_payload("logsBloom"))?;
+
+    let topic0 = transfer_event_topic();
+    let from_topic = address_topic(&expected.from)?;
+    let to_topic = address_topic(&expected.to)?;
+    let token = decode_hex(&expected.token)?;
+
+    Ok(bloom_may_contain(&bloom, &token)
+        && bloom_may_contain(&bloom, &topic0)
+        && bloom_may_contain(&bloom, &from_topic)
+        && bloom_may_contain(&bloom, &to_topic))
+}
+
+/// Sum the value of every log that is a `Transfer(expected.from, expected.to,
+/// value)` emitted by `expected.token`, so a settlement batched across several
+/// transfers is accepted once their total covers `expected.min_value`
+fn total_matching_value(logs: &[TransactionLog], expected: &ExpectedTransfer) -> Result<u128> {
+    let topic0 = hex::encode(transfer_event_topic());
+    let from_topic = hex::encode(address_topic(&expected.from)?);
+    let to_topic = hex::encode(address_topic(&expected.to)?);
+
+    let mut total: u128 = 0;
+    for log in logs {
+        if !log.address.eq_ignore_ascii_case(&expected.token) {
+            continue;
+        }
+        if log.topics.len() != 3 {
+            continue;
+        }
+        let matches_signature = log.topics[0].trim_start_matches("0x").eq_ignore_ascii_case(&topic0);
+        let matches_from = log.topics[1].trim_start_matches("0x").eq_ignore_ascii_case(&from_topic);
+        let matches_to = log.topics[2].trim_start_matches("0x").eq_ignore_ascii_case(&to_topic);
+        if !(matches_signature && matches_from && matches_to) {
+            continue;
+        }
+
+        let value = u128::from_str_radix(log.data.trim_start_matches("0x"), 16)
+            .map_err(|_| X402Error::malformed_payload("Transfer log data"))?;
+        total = total.saturating_add(value);
+    }
+
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockchain::TransactionLog;
+
+    const TOKEN: &str = "0x036CbD53842c5426634e7929541eC2318f3dCF7e";
+    const FROM: &str = "0x857b06519E91e3A54538791bDbb0E22373e36b66";
+    const TO: &str = "0x209693Bc6afc0C5328bA36FaF03C514EF312287C";
+
+    fn transfer_log(value: u128) -> TransactionLog {
+        TransactionLog {
+            address: TOKEN.to_string(),
+            topics: vec![
+                format!("0x{}", hex::encode(transfer_event_topic())),
+                format!("0x{}", hex::encode(address_topic(FROM).unwrap())),
+                format!("0x{}", hex::encode(address_topic(TO).unwrap())),
+            ],
+            data: format!("0x{:064x}", value),
+        }
+    }
+
+    #[test]
+    fn test_total_matching_value_sums_multiple_transfers() {
+        let logs = vec![transfer_log(600_000), transfer_log(400_000)];
+        let expected = ExpectedTransfer::new(TOKEN, FROM, TO, 1_000_000);
+        assert_eq!(total_matching_value(&logs, &expected).unwrap(), 1_000_000);
+    }
+
+    #[test]
+    fn test_total_matching_value_ignores_unrelated_logs() {
+        let mut unrelated = transfer_log(1_000_000);
+        unrelated.address = "0xdeadbeef00000000000000000000000000dead".to_string();
+        let expected = ExpectedTransfer::new(TOKEN, FROM, TO, 1);
+        assert_eq!(total_matching_value(&[unrelated], &expected).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_bloom_may_contain_rejects_absent_item() {
+        let empty_bloom = [0u8; 256];
+        assert!(!bloom_may_contain(&empty_bloom, b"definitely not logged"));
+    }
+
+    #[test]
+    fn test_address_topic_pads_to_32_bytes() {
+        let topic = address_topic(FROM).unwrap();
+        assert_eq!(&topic[..12], &[0u8; 12]);
+        assert_eq!(hex::encode(&topic[12..]), FROM.trim_start_matches("0x").to_lowercase());
+    }
+
+    #[tokio::test]
+    async fn test_verifier_rejects_reverted_transaction() {
+        let mut server = mockito::Server::new_async().await;
+        let _m = server
+            .mock("POST", "/")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "result": {
+                        "transactionHash": "0xabc",
+                        "status": "0x0",
+                        "logsBloom": format!("0x{}", "00".repeat(256)),
+                        "logs": []
+                    }
+                })
+                .to_string(),
+            )
+            .create();
+
+        let verifier = OnchainSettlementVerifier::new(BlockchainClient::new(
+            server.url(),
+            "base-sepolia".to_string(),
+        ));
+        let expected = ExpectedTransfer::new(TOKEN, FROM, TO, 1_000_000);
+
+        let error = verifier.verify("0xabc", &expected).await.unwrap_err();
+        assert!(matches!(error, X402Error::PaymentVerificationFailed { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_verifier_accepts_matching_batched_transfers() {
+        let mut server = mockito::Server::new_async().await;
+
+        // A bloom that actually sets the bits for this token/from/to/topic0 so the
+        // prefilter doesn't short-circuit before the logs are ever decoded.
+        let mut bloom = [0u8; 256];
+        for item in [
+            decode_hex(TOKEN).unwrap(),
+            transfer_event_topic().to_vec(),
+            address_topic(FROM).unwrap().to_vec(),
+            address_topic(TO).unwrap().to_vec(),
+        ] {
+            let hash = Keccak256::digest(&item);
+            for pair in 0..3 {
+                let word = u16::from_be_bytes([hash[pair * 2], hash[pair * 2 + 1]]) & 0x07ff;
+                let byte_index = 255 - (word / 8) as usize;
+                let bit_index = (word % 8) as u8;
+                bloom[byte_index] |= 1 << bit_index;
+            }
+        }
+
+        let _m = server
+            .mock("POST", "/")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "result": {
+                        "transactionHash": "0xabc",
+                        "status": "0x1",
+                        "logsBloom": format!("0x{}", hex::encode(bloom)),
+                        "logs": [
+                            serde_json::to_value(transfer_log(600_000)).unwrap(),
+                            serde_json::to_value(transfer_log(400_000)).unwrap(),
+                        ]
+                    }
+                })
+                .to_string(),
+            )
+            .create();
+
+        let verifier = OnchainSettlementVerifier::new(BlockchainClient::new(
+            server.url(),
+            "base-sepolia".to_string(),
+        ));
+        let expected = ExpectedTransfer::new(TOKEN, FROM, TO, 1_000_000);
+
+        verifier.verify("0xabc", &expected).await.unwrap();
+    }
+}
@@ -0,0 +1,142 @@
+//! Structured lifecycle events for a payment processed by
+//! [`crate::middleware::PaymentMiddleware::process_payment`]
+//!
+//! `process_payment` already distinguishes a fixed set of outcomes for every request
+//! (no payment, verified, rejected, settled, settlement failed), but the only way to
+//! observe any of that today is to match on the [`crate::middleware::PaymentResult`]
+//! it returns — there's no hook for metrics, audit logging or webhooks that doesn't
+//! mean forking the middleware. [`PaymentObserver`], modeled on rust-lightning's
+//! `Event`/`EventHandler` split between distinct payment lifecycle states rather than
+//! one opaque result, fills that gap: `process_payment` fires a [`PaymentEvent`] at
+//! each branch it already takes, and a registered observer reacts however it likes.
+
+use crate::idempotency::PaymentId;
+
+/// A boxed, `Send` future, used in place of `async fn` in [`PaymentObserver`] since
+/// traits can't have object-safe async methods on stable Rust without an extra
+/// proc-macro crate this workspace doesn't otherwise depend on
+pub type BoxFuture<'a, T> = std::pin::Pin<Box<dyn std::future::Future<Output = T> + Send + 'a>>;
+
+/// A stage [`crate::middleware::PaymentMiddleware::process_payment`] has reached for a
+/// single request
+#[derive(Debug, Clone)]
+pub enum PaymentEvent {
+    /// An `X-PAYMENT` header was received and decoded
+    Requested,
+    /// The facilitator accepted the payment and it granted every required scope
+    Verified,
+    /// The payment was rejected before a handler ran: no facilitator was even asked,
+    /// the facilitator rejected it, or it didn't grant a required scope
+    VerificationFailed {
+        /// Human-readable reason, as it appears in the 402 response's `error` field
+        reason: String,
+    },
+    /// The authorization's `validBefore` had already passed, so the payment was
+    /// rejected without ever reaching the facilitator
+    ///
+    /// A more specific case of [`Self::VerificationFailed`] that expiry-tracking
+    /// observers can match on separately instead of string-matching `reason`.
+    Expired,
+    /// The handler ran and the payment settled
+    Settled {
+        /// The facilitator's (or settlement queue's) settlement response
+        settlement: crate::types::SettleResponse,
+    },
+    /// The handler ran but settlement could not be completed
+    SettlementFailed {
+        /// Human-readable settlement error
+        error: String,
+    },
+}
+
+/// The request this [`PaymentEvent`] occurred for, since a [`PaymentObserver`] is
+/// shared across every request a [`crate::middleware::PaymentMiddleware`] handles
+#[derive(Debug, Clone)]
+pub struct PaymentEventContext {
+    /// The protected resource the payment was for
+    pub resource: String,
+    /// Network the payment was made on, e.g. `"base-sepolia"`
+    pub network: String,
+    /// Amount required by the payment requirements this request is being charged
+    /// against
+    pub amount: String,
+    /// Identifies the settlement attempt this event belongs to, derived from the
+    /// payment's authorization; `None` for events raised before an `X-PAYMENT` header
+    /// was decoded (there's no payload to derive an id from yet)
+    pub payment_id: Option<PaymentId>,
+}
+
+/// Reacts to [`PaymentEvent`]s raised by [`crate::middleware::PaymentMiddleware::process_payment`]
+///
+/// Registered via [`crate::middleware::PaymentMiddleware::with_observer`]. Intended for
+/// side effects — metrics, audit logs, webhooks — not for influencing the outcome of
+/// the payment being observed; `process_payment`'s behavior doesn't depend on what an
+/// observer does with an event.
+pub trait PaymentObserver: Send + Sync {
+    /// Handle a single lifecycle event
+    fn on_event<'a>(&'a self, event: PaymentEvent, ctx: &'a PaymentEventContext) -> BoxFuture<'a, ()>;
+}
+
+/// A [`PaymentObserver`] that does nothing, used as [`crate::middleware::PaymentMiddleware`]'s
+/// default so `process_payment` always has an observer to call without an `Option`
+/// check at every fire site
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopObserver;
+
+impl PaymentObserver for NoopObserver {
+    fn on_event<'a>(&'a self, _event: PaymentEvent, _ctx: &'a PaymentEventContext) -> BoxFuture<'a, ()> {
+        Box::pin(async {})
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    struct CountingObserver {
+        count: Arc<AtomicUsize>,
+    }
+
+    impl PaymentObserver for CountingObserver {
+        fn on_event<'a>(&'a self, _event: PaymentEvent, _ctx: &'a PaymentEventContext) -> BoxFuture<'a, ()> {
+            self.count.fetch_add(1, Ordering::SeqCst);
+            Box::pin(async {})
+        }
+    }
+
+    fn ctx() -> PaymentEventContext {
+        PaymentEventContext {
+            resource: "/paid".to_string(),
+            network: "base-sepolia".to_string(),
+            amount: "1000".to_string(),
+            payment_id: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_noop_observer_does_nothing() {
+        let observer = NoopObserver;
+        observer.on_event(PaymentEvent::Requested, &ctx()).await;
+        observer.on_event(PaymentEvent::Expired, &ctx()).await;
+    }
+
+    #[tokio::test]
+    async fn test_observer_is_called_for_every_event() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let observer = CountingObserver { count: count.clone() };
+        let context = ctx();
+
+        observer.on_event(PaymentEvent::Requested, &context).await;
+        observer.on_event(PaymentEvent::Verified, &context).await;
+        observer
+            .on_event(
+                PaymentEvent::VerificationFailed { reason: "nope".to_string() },
+                &context,
+            )
+            .await;
+
+        assert_eq!(count.load(Ordering::SeqCst), 3);
+    }
+}
@@ -0,0 +1,327 @@
+//! Stateful payment lifecycle tracking with confirmation thresholds and expiry
+//!
+//! [`crate::middleware::PaymentMiddlewareConfig::max_timeout_seconds`] is stored on
+//! every issued [`crate::types::PaymentRequirements`], but nothing today tracks how
+//! long ago a requirements set was issued or enforces that timeout — a replayed
+//! `X-PAYMENT` header against stale requirements would be accepted exactly like a
+//! fresh one. [`PaymentLifecycleTracker`] closes that gap: it records each issued
+//! requirements set at creation time and drives it through
+//! `Pending -> Confirming -> Settled` (or `Expired`, if `max_timeout_seconds` elapses
+//! before settlement), the confirmation-count + time-expiry model crypto-payable
+//! billing libraries (e.g. Lago, OpenMeter) use for on-chain payments.
+//!
+//! Reuses [`crate::idempotency::PaymentId`] as the tracking key, the same identifier
+//! [`crate::payment_events`] derives from a payment's authorization, rather than
+//! inventing a second id scheme for the same settlement attempt.
+
+use crate::idempotency::PaymentId;
+use crate::{Result, X402Error};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicI64, Ordering};
+use tokio::sync::Mutex;
+
+/// A boxed, `Send` future, used in place of `async fn` in [`PaymentStore`] since
+/// traits can't have object-safe async methods on stable Rust without an extra
+/// proc-macro crate this workspace doesn't otherwise depend on
+pub type BoxFuture<'a, T> = std::pin::Pin<Box<dyn std::future::Future<Output = T> + Send + 'a>>;
+
+/// Source of the current time for [`PaymentLifecycleTracker`], injectable so expiry
+/// logic is unit-testable without sleeping a real clock; see [`FixedClock`]
+pub trait Clock: Send + Sync {
+    /// Current Unix timestamp, in seconds
+    fn now(&self) -> i64;
+}
+
+/// A [`Clock`] reading the real system time
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> i64 {
+        chrono::Utc::now().timestamp()
+    }
+}
+
+/// A [`Clock`] that always reports a caller-controlled time, for tests that need to
+/// assert behavior at or past an expiry boundary without sleeping
+#[derive(Debug, Default)]
+pub struct FixedClock(AtomicI64);
+
+impl FixedClock {
+    /// Start the clock at `now`
+    pub fn new(now: i64) -> Self {
+        Self(AtomicI64::new(now))
+    }
+
+    /// Move the clock forward by `seconds`
+    pub fn advance(&self, seconds: i64) {
+        self.0.fetch_add(seconds, Ordering::SeqCst);
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> i64 {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// Where a tracked payment stands in its settlement lifecycle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaymentState {
+    /// Requirements were issued; no settlement attempt has confirmed anything yet
+    Pending,
+    /// A settlement has broadcast and reached `confirmations` block confirmations, but
+    /// fewer than the tracker's configured threshold
+    Confirming {
+        /// Confirmations observed so far
+        confirmations: u64,
+    },
+    /// Reached the required confirmation depth
+    Settled,
+    /// `max_timeout_seconds` elapsed after `created_at` without reaching [`Self::Settled`]
+    Expired,
+}
+
+/// A single issued [`crate::types::PaymentRequirements`] tracked through its lifecycle
+#[derive(Debug, Clone, Copy)]
+pub struct TrackedPayment {
+    /// Unix timestamp the requirements were issued at
+    pub created_at: i64,
+    /// Seconds after `created_at` this payment may still be settled, taken from the
+    /// issuing [`crate::middleware::PaymentMiddlewareConfig::max_timeout_seconds`]
+    pub max_timeout_seconds: u32,
+    /// Current lifecycle state
+    pub state: PaymentState,
+}
+
+impl TrackedPayment {
+    /// A freshly issued payment, in [`PaymentState::Pending`]
+    fn new(created_at: i64, max_timeout_seconds: u32) -> Self {
+        Self {
+            created_at,
+            max_timeout_seconds,
+            state: PaymentState::Pending,
+        }
+    }
+
+    /// The Unix timestamp after which this payment can no longer be settled
+    fn expires_at(&self) -> i64 {
+        self.created_at + i64::from(self.max_timeout_seconds)
+    }
+}
+
+/// Storage backend for [`PaymentLifecycleTracker`]
+///
+/// [`InMemoryPaymentStore`] is the default, single-process backing store; implement
+/// this trait against Redis or a SQL table to share lifecycle state across a
+/// horizontally scaled deployment, the same extension point
+/// [`crate::idempotency::IdempotencyStore`] and [`crate::nonce_store::NonceStore`] use.
+pub trait PaymentStore: Send + Sync {
+    /// Record a newly issued payment, replacing any existing entry for `payment_id`
+    fn insert<'a>(&'a self, payment_id: PaymentId, payment: TrackedPayment) -> BoxFuture<'a, ()>;
+    /// Look up a tracked payment
+    fn get<'a>(&'a self, payment_id: &'a PaymentId) -> BoxFuture<'a, Option<TrackedPayment>>;
+    /// Overwrite the state of a previously inserted payment; a no-op if `payment_id`
+    /// was never inserted
+    fn set_state<'a>(&'a self, payment_id: &'a PaymentId, state: PaymentState) -> BoxFuture<'a, ()>;
+}
+
+/// In-memory [`PaymentStore`], suitable for a single-process deployment
+///
+/// Entries are never evicted once settled or expired; a deployment that needs to bound
+/// memory for a long-running process should implement [`PaymentStore`] against a
+/// store with its own TTL instead (e.g. Redis `EXPIRE`).
+#[derive(Debug, Default)]
+pub struct InMemoryPaymentStore {
+    payments: Mutex<HashMap<PaymentId, TrackedPayment>>,
+}
+
+impl InMemoryPaymentStore {
+    /// Create an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl PaymentStore for InMemoryPaymentStore {
+    fn insert<'a>(&'a self, payment_id: PaymentId, payment: TrackedPayment) -> BoxFuture<'a, ()> {
+        Box::pin(async move {
+            self.payments.lock().await.insert(payment_id, payment);
+        })
+    }
+
+    fn get<'a>(&'a self, payment_id: &'a PaymentId) -> BoxFuture<'a, Option<TrackedPayment>> {
+        Box::pin(async move { self.payments.lock().await.get(payment_id).copied() })
+    }
+
+    fn set_state<'a>(&'a self, payment_id: &'a PaymentId, state: PaymentState) -> BoxFuture<'a, ()> {
+        Box::pin(async move {
+            if let Some(payment) = self.payments.lock().await.get_mut(payment_id) {
+                payment.state = state;
+            }
+        })
+    }
+}
+
+/// Drives issued payments through [`PaymentState`], rejecting a settlement attempt
+/// once `max_timeout_seconds` has elapsed since the requirements were issued
+pub struct PaymentLifecycleTracker {
+    store: std::sync::Arc<dyn PaymentStore>,
+    clock: std::sync::Arc<dyn Clock>,
+    required_confirmations: u64,
+}
+
+impl PaymentLifecycleTracker {
+    /// Track payments in `store`, reading time from [`SystemClock`], requiring 1
+    /// confirmation before a payment is considered [`PaymentState::Settled`]
+    pub fn new(store: std::sync::Arc<dyn PaymentStore>) -> Self {
+        Self {
+            store,
+            clock: std::sync::Arc::new(SystemClock),
+            required_confirmations: 1,
+        }
+    }
+
+    /// Override the clock, e.g. with a [`FixedClock`] in tests
+    pub fn with_clock(mut self, clock: std::sync::Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Require `confirmations` confirmations before a payment reaches
+    /// [`PaymentState::Settled`]
+    pub fn with_required_confirmations(mut self, confirmations: u64) -> Self {
+        self.required_confirmations = confirmations;
+        self
+    }
+
+    /// Record a freshly issued payment in [`PaymentState::Pending`]
+    pub async fn track(&self, payment_id: PaymentId, max_timeout_seconds: u32) {
+        let created_at = self.clock.now();
+        self.store
+            .insert(payment_id, TrackedPayment::new(created_at, max_timeout_seconds))
+            .await;
+    }
+
+    /// Reject a settlement attempt against a payment that was never tracked, already
+    /// expired, or whose `max_timeout_seconds` has elapsed since it was tracked
+    ///
+    /// Transitions the stored state to [`PaymentState::Expired`] as a side effect when
+    /// the timeout has elapsed but the store hadn't recorded that yet, so a later call
+    /// observes the same outcome without re-deriving it from `created_at`.
+    pub async fn reject_if_expired(&self, payment_id: &PaymentId) -> Result<()> {
+        let payment = self
+            .store
+            .get(payment_id)
+            .await
+            .ok_or_else(|| X402Error::invalid_payment_payload("Payment was never issued by this server"))?;
+
+        if payment.state == PaymentState::Expired {
+            return Err(X402Error::authorization_expired(payment.expires_at(), self.clock.now()));
+        }
+
+        let now = self.clock.now();
+        if now > payment.expires_at() {
+            self.store.set_state(payment_id, PaymentState::Expired).await;
+            return Err(X402Error::authorization_expired(payment.expires_at(), now));
+        }
+
+        Ok(())
+    }
+
+    /// Advance a tracked payment's confirmation count, moving it to
+    /// [`PaymentState::Settled`] once it reaches [`Self::required_confirmations`]
+    ///
+    /// Checks expiry first via [`Self::reject_if_expired`]: a payment that times out
+    /// while still confirming is reported [`PaymentState::Expired`], not left
+    /// indefinitely [`PaymentState::Confirming`].
+    pub async fn record_confirmation(&self, payment_id: &PaymentId, confirmations: u64) -> Result<PaymentState> {
+        self.reject_if_expired(payment_id).await?;
+
+        let state = if confirmations >= self.required_confirmations {
+            PaymentState::Settled
+        } else {
+            PaymentState::Confirming { confirmations }
+        };
+        self.store.set_state(payment_id, state).await;
+        Ok(state)
+    }
+
+    /// Look up a tracked payment's current state
+    pub async fn state(&self, payment_id: &PaymentId) -> Option<PaymentState> {
+        self.store.get(payment_id).await.map(|payment| payment.state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    fn test_payment_id() -> PaymentId {
+        PaymentId([7u8; 32])
+    }
+
+    #[tokio::test]
+    async fn test_track_starts_a_payment_as_pending() {
+        let tracker = PaymentLifecycleTracker::new(Arc::new(InMemoryPaymentStore::new()));
+        let payment_id = test_payment_id();
+
+        tracker.track(payment_id, 60).await;
+
+        assert_eq!(tracker.state(&payment_id).await, Some(PaymentState::Pending));
+    }
+
+    #[tokio::test]
+    async fn test_reject_if_expired_rejects_an_untracked_payment() {
+        let tracker = PaymentLifecycleTracker::new(Arc::new(InMemoryPaymentStore::new()));
+        let result = tracker.reject_if_expired(&test_payment_id()).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_reject_if_expired_rejects_once_max_timeout_seconds_elapses() {
+        let clock = Arc::new(FixedClock::new(1_000));
+        let tracker =
+            PaymentLifecycleTracker::new(Arc::new(InMemoryPaymentStore::new())).with_clock(clock.clone());
+        let payment_id = test_payment_id();
+        tracker.track(payment_id, 60).await;
+
+        assert!(tracker.reject_if_expired(&payment_id).await.is_ok());
+
+        clock.advance(61);
+
+        let result = tracker.reject_if_expired(&payment_id).await;
+        assert!(matches!(result, Err(X402Error::AuthorizationExpired { .. })));
+        assert_eq!(tracker.state(&payment_id).await, Some(PaymentState::Expired));
+    }
+
+    #[tokio::test]
+    async fn test_record_confirmation_reaches_settled_at_the_required_threshold() {
+        let tracker =
+            PaymentLifecycleTracker::new(Arc::new(InMemoryPaymentStore::new())).with_required_confirmations(3);
+        let payment_id = test_payment_id();
+        tracker.track(payment_id, 60).await;
+
+        let state = tracker.record_confirmation(&payment_id, 1).await.unwrap();
+        assert_eq!(state, PaymentState::Confirming { confirmations: 1 });
+
+        let state = tracker.record_confirmation(&payment_id, 3).await.unwrap();
+        assert_eq!(state, PaymentState::Settled);
+    }
+
+    #[tokio::test]
+    async fn test_record_confirmation_rejects_once_expired() {
+        let clock = Arc::new(FixedClock::new(1_000));
+        let tracker = PaymentLifecycleTracker::new(Arc::new(InMemoryPaymentStore::new()))
+            .with_clock(clock.clone())
+            .with_required_confirmations(2);
+        let payment_id = test_payment_id();
+        tracker.track(payment_id, 60).await;
+
+        clock.advance(61);
+
+        let result = tracker.record_confirmation(&payment_id, 1).await;
+        assert!(matches!(result, Err(X402Error::AuthorizationExpired { .. })));
+    }
+}
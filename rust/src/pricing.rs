@@ -0,0 +1,130 @@
+//! Fiat-denominated route pricing, converted to token units at request time
+//!
+//! [`crate::middleware::PaymentMiddlewareConfig`]'s `amount` is a bare [`Decimal`]
+//! that [`crate::middleware::PaymentMiddlewareConfig::create_payment_requirements`]
+//! turns directly into `max_amount_required`, implicitly assuming the merchant
+//! already knows the token's smallest-unit amount. [`PricingType::Converted`] plus a
+//! pluggable [`PriceOracle`] let a route instead be priced in USD (or any other fiat)
+//! and converted to the payment token at request time via
+//! [`crate::middleware::PaymentMiddlewareConfig::create_payment_requirements_priced`],
+//! the way hosted-checkout connectors separate a "local price" from the settlement
+//! currency instead of hard-coding one fixed token amount per route.
+
+use crate::{Result, X402Error};
+use rust_decimal::Decimal;
+
+/// A boxed, `Send` future, used in place of `async fn` in [`PriceOracle`] since traits
+/// can't have object-safe async methods on stable Rust without an extra proc-macro
+/// crate this workspace doesn't otherwise depend on
+pub type BoxFuture<'a, T> = std::pin::Pin<Box<dyn std::future::Future<Output = T> + Send + 'a>>;
+
+/// How a [`crate::middleware::PaymentMiddlewareConfig`]'s `amount` should be read
+#[derive(Debug, Clone, PartialEq)]
+pub enum PricingType {
+    /// `amount` is already denominated in the token's smallest units once scaled by
+    /// its decimals — the behavior this crate has always had
+    Fixed,
+    /// `amount` is denominated in `currency` (e.g. `"usd"`) and must be converted to
+    /// token units via a [`PriceOracle`] at request time
+    Converted {
+        /// ISO 4217-style currency code `amount` is denominated in
+        currency: String,
+    },
+}
+
+impl Default for PricingType {
+    fn default() -> Self {
+        PricingType::Fixed
+    }
+}
+
+/// A fiat→token conversion rate, quoted at a point in time
+///
+/// `rate` is token units per one unit of the quoted currency (so `amount * rate`
+/// converts a fiat amount straight to token units before decimal scaling).
+#[derive(Debug, Clone, Copy)]
+pub struct PriceQuote {
+    /// Token units per one unit of the quoted currency
+    pub rate: Decimal,
+    /// Unix timestamp (seconds) the rate was quoted at
+    pub quoted_at: i64,
+    /// Unix timestamp (seconds) after which this quote should no longer be trusted
+    pub expires_at: i64,
+}
+
+/// Supplies a [`PriceQuote`] converting `currency` to `network`'s payment token, so a
+/// route priced in fiat can be converted to token units without this crate hard-coding
+/// any particular rate feed
+pub trait PriceOracle: Send + Sync {
+    /// Quote the current `currency` → `network` token rate
+    fn quote<'a>(&'a self, currency: &'a str, network: &'a str) -> BoxFuture<'a, Result<PriceQuote>>;
+}
+
+/// A [`PriceOracle`] returning a fixed, caller-supplied rate regardless of
+/// `currency`/`network`, for tests and for a merchant who updates a rate on their own
+/// schedule rather than querying a live feed
+#[derive(Debug, Clone, Copy)]
+pub struct StaticPriceOracle {
+    rate: Decimal,
+    quote_lifetime: std::time::Duration,
+}
+
+impl StaticPriceOracle {
+    /// Default lifetime a quote from this oracle is considered valid for
+    pub const DEFAULT_QUOTE_LIFETIME: std::time::Duration = std::time::Duration::from_secs(60);
+
+    /// Always quote `rate`, valid for [`Self::DEFAULT_QUOTE_LIFETIME`]
+    pub fn new(rate: Decimal) -> Self {
+        Self {
+            rate,
+            quote_lifetime: Self::DEFAULT_QUOTE_LIFETIME,
+        }
+    }
+
+    /// Override how long a quote from this oracle is considered valid for
+    pub fn with_quote_lifetime(mut self, lifetime: std::time::Duration) -> Self {
+        self.quote_lifetime = lifetime;
+        self
+    }
+}
+
+impl PriceOracle for StaticPriceOracle {
+    fn quote<'a>(&'a self, _currency: &'a str, _network: &'a str) -> BoxFuture<'a, Result<PriceQuote>> {
+        Box::pin(async move {
+            let now = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs() as i64)
+                .map_err(|e| X402Error::config(format!("system clock is before the Unix epoch: {e}")))?;
+
+            Ok(PriceQuote {
+                rate: self.rate,
+                quoted_at: now,
+                expires_at: now + self.quote_lifetime.as_secs() as i64,
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[tokio::test]
+    async fn test_static_price_oracle_quotes_its_fixed_rate() {
+        let oracle = StaticPriceOracle::new(Decimal::from_str("3500.00").unwrap());
+        let quote = oracle.quote("usd", "base").await.unwrap();
+
+        assert_eq!(quote.rate, Decimal::from_str("3500.00").unwrap());
+        assert!(quote.expires_at > quote.quoted_at);
+    }
+
+    #[tokio::test]
+    async fn test_static_price_oracle_respects_custom_quote_lifetime() {
+        let oracle = StaticPriceOracle::new(Decimal::from_str("1.00").unwrap())
+            .with_quote_lifetime(std::time::Duration::from_secs(3600));
+        let quote = oracle.quote("usd", "base").await.unwrap();
+
+        assert_eq!(quote.expires_at - quote.quoted_at, 3600);
+    }
+}
@@ -8,25 +8,61 @@
 
 use crate::{
     blockchain::{BlockchainClient, BlockchainClientFactory, TransactionStatus},
+    crypto,
+    crypto::signature::{sign_prehash_components, LocalSigner, Signature as EvmSignature},
+    facilitator::{BoxFuture, Facilitator},
+    facilitator_middleware::{BlockchainClientMiddleware, FacilitatorMiddleware, GasOracleMiddleware},
+    onchain_verification::ExpectedTransfer,
+    retry::RetryPolicy,
+    settlement_confirmation::{ConfirmationOutcome, PendingClaim, PendingSettlement, SettlementConfirmer},
     types::{PaymentPayload, PaymentRequirements, SettleResponse, VerifyResponse},
     Result, X402Error,
 };
+use ethereum_types::{Address, H256, U256};
 use serde::{Deserialize, Serialize};
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 /// Blockchain facilitator client for production use
 pub struct BlockchainFacilitatorClient {
     /// Blockchain client for network interactions
     blockchain_client: BlockchainClient,
     /// Network name
-    #[allow(dead_code)]
     network: String,
-    /// Verification timeout
-    #[allow(dead_code)]
+    /// Bounds how long [`Self::settle`] polls a broadcast settlement transaction
+    /// before giving up as [`crate::settlement_confirmation::ConfirmationOutcome::TimedOut`]
     verification_timeout: Duration,
     /// Settlement confirmation blocks
-    #[allow(dead_code)]
     confirmation_blocks: u64,
+    /// How many times [`PendingSettlement::wait`] retries a transient RPC failure
+    /// while confirming a settlement, and how long it waits before the first retry —
+    /// see [`BlockchainFacilitatorConfig::max_retries`]/[`BlockchainFacilitatorConfig::retry_delay`]
+    settlement_retry_policy: RetryPolicy,
+    /// Relayer key that signs and broadcasts settlement transactions, plus
+    /// the in-memory nonce it has handed out so far (our analog of an ethers
+    /// `NonceManagerMiddleware`: read `eth_getTransactionCount` once, then
+    /// increment locally so back-to-back settlements don't race each other
+    /// for the same nonce).
+    relayer: Option<RelayerState>,
+    /// Stack of [`FacilitatorMiddleware`] layers the settlement transaction's RPC
+    /// calls (`estimate_gas`, `fee_history`, `get_transaction_count`,
+    /// `send_raw_transaction`) are routed through instead of `blockchain_client`
+    /// directly, when set via [`Self::with_middleware`]. Lets an operator insert a
+    /// retry layer, a custom nonce source, or a gas-pricing policy without
+    /// forking this client.
+    middleware: Option<Arc<dyn FacilitatorMiddleware>>,
+    /// [`BlockchainFacilitatorConfig::gas_price_multiplier`], applied to the default
+    /// middleware stack when no explicit [`Self::with_middleware`] stack is set
+    gas_price_multiplier: Option<f64>,
+    /// [`BlockchainFacilitatorConfig::max_settlement_fee`]
+    max_settlement_fee: Option<u128>,
+}
+
+/// The facilitator's on-chain relayer identity and its locally tracked nonce
+struct RelayerState {
+    private_key: String,
+    next_nonce: Mutex<Option<u64>>,
 }
 
 /// Blockchain facilitator configuration
@@ -44,6 +80,28 @@ pub struct BlockchainFacilitatorConfig {
     pub max_retries: u32,
     /// Retry delay
     pub retry_delay: Duration,
+    /// Hex-encoded private key for the relayer wallet that signs and submits
+    /// settlement transactions on-chain. Without this, [`BlockchainFacilitatorClient::settle`]
+    /// can still verify payments but returns a config error instead of
+    /// fabricating a transaction hash.
+    pub relayer_private_key: Option<String>,
+    /// Multiplier applied to `eth_feeHistory`'s base fee and priority fee before
+    /// building a settlement transaction's `maxFeePerGas`/`maxPriorityFeePerGas`
+    /// (e.g. `1.2` bids 20% over the latest base fee so the transaction doesn't get
+    /// stranded if the next block's base fee rises). `None` settles for plain
+    /// base-fee-based estimation with no bump. Equivalent to wrapping
+    /// [`Self::relayer_private_key`]'s settlement RPC calls in a
+    /// [`crate::facilitator_middleware::GasOracleMiddleware`]; set explicitly via
+    /// [`BlockchainFacilitatorClient::with_middleware`] instead if a different gas
+    /// pricing source (e.g. an external fee API) is needed.
+    pub gas_price_multiplier: Option<f64>,
+    /// Ceiling on the estimated worst-case gas cost (in wei) of a single settlement,
+    /// per [`crate::gas_oracle::GasOracle::estimate_settlement_cost`]. The facilitator
+    /// fronts gas for the payer on a gasless EIP-3009 transfer, so without a bound it
+    /// could be griefed into overpaying during a fee spike; [`Self::relayer_private_key`]
+    /// transactions above this bound fail with a config error instead of broadcasting.
+    /// `None` applies no ceiling.
+    pub max_settlement_fee: Option<u128>,
 }
 
 impl Default for BlockchainFacilitatorConfig {
@@ -55,10 +113,33 @@ impl Default for BlockchainFacilitatorConfig {
             confirmation_blocks: 1,
             max_retries: 3,
             retry_delay: Duration::from_secs(1),
+            relayer_private_key: None,
+            gas_price_multiplier: None,
+            max_settlement_fee: None,
         }
     }
 }
 
+/// Number of times [`BlockchainFacilitatorClient::settle`] will resubmit the same
+/// ERC-3009 authorization as a fresh transaction after a reorg drops the previous
+/// attempt, before giving up and reporting settlement failure
+const MAX_RESUBMIT_ATTEMPTS: u32 = 3;
+
+/// Chain ID for each network this facilitator supports; kept local since
+/// [`crate::types::NetworkConfig`] only covers the Base networks, not Avalanche
+fn chain_id_for_network(network: &str) -> Result<u64> {
+    match network {
+        "base" => Ok(8453),
+        "base-sepolia" => Ok(84532),
+        "avalanche" => Ok(43114),
+        "avalanche-fuji" => Ok(43113),
+        _ => Err(X402Error::invalid_network(format!(
+            "Unsupported network: {}",
+            network
+        ))),
+    }
+}
+
 /// Transaction verification result
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TransactionVerification {
@@ -70,6 +151,11 @@ pub struct TransactionVerification {
 }
 
 impl BlockchainFacilitatorClient {
+    /// Maximum number of payments [`Self::settle_batch`] will aggregate into a single
+    /// `aggregate3` transaction, bounding the aggregated calldata size and the gas
+    /// estimate one transaction has to cover.
+    pub const MAX_BATCH_SIZE: usize = 20;
+
     /// Create a new blockchain facilitator client
     pub fn new(config: BlockchainFacilitatorConfig) -> Result<Self> {
         let blockchain_client = if let Some(rpc_url) = config.rpc_url {
@@ -94,94 +180,204 @@ impl BlockchainFacilitatorClient {
             network: config.network,
             verification_timeout: config.verification_timeout,
             confirmation_blocks: config.confirmation_blocks,
+            settlement_retry_policy: RetryPolicy::new()
+                .with_base_delay(config.retry_delay)
+                .with_max_attempts(config.max_retries)
+                .with_jitter(false),
+            relayer: config.relayer_private_key.map(|private_key| RelayerState {
+                private_key,
+                next_nonce: Mutex::new(None),
+            }),
+            middleware: None,
+            gas_price_multiplier: config.gas_price_multiplier,
+            max_settlement_fee: config.max_settlement_fee,
         })
     }
 
-    /// Verify a payment payload with real blockchain verification
-    pub async fn verify(
+    /// Route this client's settlement RPC calls (`estimate_gas`, `fee_history`,
+    /// `get_transaction_count`, `send_raw_transaction`) through `middleware`
+    /// instead of calling the underlying [`BlockchainClient`] directly
+    ///
+    /// `middleware` is typically a stack built by wrapping a
+    /// [`BlockchainClientMiddleware`] in one or more of
+    /// [`crate::facilitator_middleware::RetryMiddleware`],
+    /// [`crate::facilitator_middleware::NonceManagerMiddleware`], or
+    /// [`crate::facilitator_middleware::GasOracleMiddleware`].
+    pub fn with_middleware(mut self, middleware: Arc<dyn FacilitatorMiddleware>) -> Self {
+        self.middleware = Some(middleware);
+        self
+    }
+
+    /// The middleware stack in effect for this client's settlement RPC calls,
+    /// falling back to a plain [`BlockchainClientMiddleware`] over
+    /// `self.blockchain_client` (optionally wrapped in a
+    /// [`crate::facilitator_middleware::GasOracleMiddleware`] per
+    /// [`BlockchainFacilitatorConfig::gas_price_multiplier`]) when
+    /// [`Self::with_middleware`] was never called
+    fn middleware(&self) -> Arc<dyn FacilitatorMiddleware> {
+        self.middleware.clone().unwrap_or_else(|| {
+            let base: Arc<dyn FacilitatorMiddleware> =
+                Arc::new(BlockchainClientMiddleware::new(self.blockchain_client.clone()));
+            match self.gas_price_multiplier {
+                Some(multiplier) => Arc::new(GasOracleMiddleware::new(base, multiplier)),
+                None => base,
+            }
+        })
+    }
+
+    /// Run every local (no RPC call involved) precondition check `verify` and
+    /// `verify_batch` share — network, scheme, timing, signature, amount, and
+    /// recipient — returning `Some(response)` the moment one fails, or `None`
+    /// once `payment_payload` has cleared all of them and is ready for the
+    /// on-chain balance check.
+    fn local_checks(
         &self,
         payment_payload: &PaymentPayload,
         requirements: &PaymentRequirements,
-    ) -> Result<VerifyResponse> {
+    ) -> Result<Option<VerifyResponse>> {
+        // Best-effort payer for diagnostics on an early failure below — `None` for any
+        // scheme this client doesn't handle, rather than failing the whole check just
+        // to report who sent it.
+        let early_payer = payment_payload.exact_evm().ok().map(|exact_evm| exact_evm.authorization.from.clone());
+
         // Validate network match
         if payment_payload.network != requirements.network {
-            return Ok(VerifyResponse {
+            return Ok(Some(VerifyResponse {
                 is_valid: false,
                 invalid_reason: Some(format!(
                     "Network mismatch: payment network {} != requirements network {}",
                     payment_payload.network, requirements.network
                 )),
-                payer: Some(payment_payload.payload.authorization.from.clone()),
-            });
+                payer: early_payer,
+            }));
         }
 
         // Validate scheme match
         if payment_payload.scheme != requirements.scheme {
-            return Ok(VerifyResponse {
+            return Ok(Some(VerifyResponse {
                 is_valid: false,
                 invalid_reason: Some(format!(
                     "Scheme mismatch: payment scheme {} != requirements scheme {}",
                     payment_payload.scheme, requirements.scheme
                 )),
-                payer: Some(payment_payload.payload.authorization.from.clone()),
-            });
+                payer: early_payer,
+            }));
         }
 
+        // This client only handles `schemes::EXACT`, so the scheme check above already
+        // ruled out every other scheme's payload shape; a mismatch here would mean the
+        // payload's `scheme` field lied about its actual (untagged-deserialized)
+        // variant. Report that as a per-payment failure rather than propagating it,
+        // so one malformed entry in a batch can't abort every other payment's checks.
+        let exact_evm = match payment_payload.exact_evm() {
+            Ok(exact_evm) => exact_evm,
+            Err(error) => {
+                return Ok(Some(VerifyResponse {
+                    is_valid: false,
+                    invalid_reason: Some(error.to_string()),
+                    payer: None,
+                }));
+            }
+        };
+        let auth = &exact_evm.authorization;
+
         // Validate authorization timing
-        if !payment_payload.payload.authorization.is_valid_now()? {
-            return Ok(VerifyResponse {
+        if !auth.is_valid_now()? {
+            return Ok(Some(VerifyResponse {
                 is_valid: false,
                 invalid_reason: Some("Authorization expired or not yet valid".to_string()),
-                payer: Some(payment_payload.payload.authorization.from.clone()),
-            });
+                payer: Some(auth.from.clone()),
+            }));
+        }
+
+        // Recover the EIP-712 signer over the TransferWithAuthorization digest and
+        // require it to match the claimed `from`, so a forged payload with a
+        // valid-looking `from` but no matching signature is rejected here instead of
+        // being trusted through to settlement.
+        let signature_valid = crypto::signature::verify_payment_payload(
+            exact_evm,
+            &auth.from,
+            &payment_payload.network,
+        )?;
+        if !signature_valid {
+            return Ok(Some(VerifyResponse {
+                is_valid: false,
+                invalid_reason: Some("Invalid signature".to_string()),
+                payer: Some(auth.from.clone()),
+            }));
         }
 
         // Validate amount
-        let payment_amount: u128 = payment_payload
-            .payload
-            .authorization
-            .value
-            .parse()
-            .map_err(|_| {
-                X402Error::invalid_payment_requirements("Invalid payment amount format")
-            })?;
+        let payment_amount: u128 = auth.value.parse().map_err(|_| {
+            X402Error::invalid_payment_requirements("Invalid payment amount format")
+        })?;
 
         let required_amount: u128 = requirements.max_amount_required.parse().map_err(|_| {
             X402Error::invalid_payment_requirements("Invalid required amount format")
         })?;
 
         if payment_amount < required_amount {
-            return Ok(VerifyResponse {
+            return Ok(Some(VerifyResponse {
                 is_valid: false,
                 invalid_reason: Some(format!(
                     "Insufficient amount: {} < {}",
                     payment_amount, required_amount
                 )),
-                payer: Some(payment_payload.payload.authorization.from.clone()),
-            });
+                payer: Some(auth.from.clone()),
+            }));
         }
 
         // Validate recipient
-        if payment_payload.payload.authorization.to != requirements.pay_to {
-            return Ok(VerifyResponse {
+        if auth.to != requirements.pay_to {
+            return Ok(Some(VerifyResponse {
                 is_valid: false,
                 invalid_reason: Some(format!(
                     "Recipient mismatch: {} != {}",
-                    payment_payload.payload.authorization.to, requirements.pay_to
+                    auth.to, requirements.pay_to
                 )),
-                payer: Some(payment_payload.payload.authorization.from.clone()),
-            });
+                payer: Some(auth.from.clone()),
+            }));
         }
 
-        // Check payer balance
-        let balance_info = self
+        Ok(None)
+    }
+
+    /// Verify a payment payload with real blockchain verification
+    pub async fn verify(
+        &self,
+        payment_payload: &PaymentPayload,
+        requirements: &PaymentRequirements,
+    ) -> Result<VerifyResponse> {
+        if let Some(early) = self.local_checks(payment_payload, requirements)? {
+            return Ok(early);
+        }
+
+        let auth = &payment_payload.exact_evm()?.authorization;
+        let payment_amount: u128 = auth.value.parse().map_err(|_| {
+            X402Error::invalid_payment_requirements("Invalid payment amount format")
+        })?;
+
+        // Check the authorization nonce hasn't already been consumed on-chain, the
+        // same thing `verify_batch` checks per-payment via its aggregated call
+        let nonce_used = self
             .blockchain_client
-            .get_usdc_balance(&payment_payload.payload.authorization.from)
+            .is_usdc_nonce_used(&auth.from, &auth.nonce)
             .await?;
+        if nonce_used {
+            return Ok(VerifyResponse {
+                is_valid: false,
+                invalid_reason: Some("Authorization nonce already used".to_string()),
+                payer: Some(auth.from.clone()),
+            });
+        }
+
+        // Check payer balance
+        let balance_info = self.blockchain_client.get_usdc_balance(&auth.from).await?;
 
         if let Some(token_balance) = balance_info.token_balance {
-            let balance: u128 = u128::from_str_radix(token_balance.trim_start_matches("0x"), 16)
-                .map_err(|_| X402Error::invalid_payment_requirements("Invalid balance format"))?;
+            let balance: u128 = crate::erc20::decode_u256(&token_balance)
+                .map_err(|_| X402Error::invalid_payment_requirements("Invalid balance format"))?
+                .as_u128();
 
             if balance < payment_amount {
                 return Ok(VerifyResponse {
@@ -190,7 +386,7 @@ impl BlockchainFacilitatorClient {
                         "Insufficient balance: {} < {}",
                         balance, payment_amount
                     )),
-                    payer: Some(payment_payload.payload.authorization.from.clone()),
+                    payer: Some(auth.from.clone()),
                 });
             }
         }
@@ -199,7 +395,7 @@ impl BlockchainFacilitatorClient {
         Ok(VerifyResponse {
             is_valid: true,
             invalid_reason: None,
-            payer: Some(payment_payload.payload.authorization.from.clone()),
+            payer: Some(auth.from.clone()),
         })
     }
 
@@ -225,224 +421,617 @@ impl BlockchainFacilitatorClient {
             });
         }
 
-        // In a real implementation, this would:
-        // 1. Create a transaction to transfer USDC
-        // 2. Sign the transaction with the facilitator's private key
-        // 3. Broadcast the transaction to the network
-        // 4. Wait for confirmation
-        // 5. Return the transaction hash
+        // Create and broadcast the settlement transaction, then don't report success
+        // until it's actually confirmed on-chain with a verified `Transfer` log —
+        // resubmitting for the same authorization (and its ERC-3009 nonce) if a reorg
+        // drops the transaction after it was broadcast.
+        let auth = &payment_payload.exact_evm()?.authorization;
+        let min_value: u128 = auth
+            .value
+            .parse()
+            .map_err(|_| X402Error::invalid_payment_requirements("Invalid payment amount format"))?;
+        let expected = ExpectedTransfer::new(
+            self.blockchain_client.get_usdc_contract_address()?,
+            auth.from.clone(),
+            auth.to.clone(),
+            min_value,
+        );
+        let confirmer = SettlementConfirmer::new(self.blockchain_client.clone())
+            .with_required_confirmations(self.confirmation_blocks)
+            .with_verification_timeout(self.verification_timeout);
 
-        // Create and broadcast the settlement transaction
-        let transaction_hash = self
+        let mut transaction_hash = self
             .create_settlement_transaction(payment_payload, requirements)
             .await?;
 
-        // Wait for transaction confirmation
-        let confirmation_result = self.wait_for_confirmation(&transaction_hash).await?;
+        for _ in 0..MAX_RESUBMIT_ATTEMPTS {
+            let claim = PendingClaim::new(transaction_hash.clone(), expected.clone(), auth.nonce.clone());
+            match confirmer.confirm(&claim).await? {
+                ConfirmationOutcome::Confirmed(_verified, _depth) => {
+                    return Ok(SettleResponse {
+                        success: true,
+                        error_reason: None,
+                        transaction: transaction_hash,
+                        network: payment_payload.network.clone(),
+                        payer: Some(auth.from.clone()),
+                    });
+                }
+                ConfirmationOutcome::Reorged => {
+                    transaction_hash = self
+                        .create_settlement_transaction(payment_payload, requirements)
+                        .await?;
+                }
+                ConfirmationOutcome::TimedOut => {
+                    return Ok(SettleResponse {
+                        success: false,
+                        error_reason: Some(format!(
+                            "settlement transaction {} never appeared on-chain",
+                            transaction_hash
+                        )),
+                        transaction: transaction_hash,
+                        network: payment_payload.network.clone(),
+                        payer: Some(auth.from.clone()),
+                    });
+                }
+            }
+        }
+
+        Ok(SettleResponse {
+            success: false,
+            error_reason: Some(format!(
+                "settlement reorged {MAX_RESUBMIT_ATTEMPTS} times without confirming"
+            )),
+            transaction: transaction_hash,
+            network: payment_payload.network.clone(),
+            payer: Some(auth.from.clone()),
+        })
+    }
 
-        if confirmation_result.success {
-            Ok(SettleResponse {
-                success: true,
-                error_reason: None,
-                transaction: transaction_hash,
-                network: payment_payload.network.clone(),
-                payer: Some(payment_payload.payload.authorization.from.clone()),
-            })
-        } else {
-            Ok(SettleResponse {
-                success: false,
-                error_reason: Some(
-                    confirmation_result
-                        .error_reason
-                        .unwrap_or("Transaction failed".to_string()),
-                ),
-                transaction: transaction_hash,
-                network: payment_payload.network.clone(),
-                payer: Some(payment_payload.payload.authorization.from.clone()),
-            })
+    /// Verify and broadcast a settlement transaction like [`Self::settle`], but return
+    /// as soon as it's broadcast instead of blocking until it confirms. The caller
+    /// awaits the returned [`PendingSettlement`] on its own schedule, which retries a
+    /// transient RPC failure per [`BlockchainFacilitatorConfig::max_retries`]/
+    /// [`BlockchainFacilitatorConfig::retry_delay`] rather than [`Self::settle`]'s
+    /// fixed reorg-resubmit loop — on an actual reorg or timeout it errors instead of
+    /// resubmitting, since only the caller here still holds the authorization to
+    /// resubmit against.
+    pub async fn settle_pending(
+        &self,
+        payment_payload: &PaymentPayload,
+        requirements: &PaymentRequirements,
+    ) -> Result<PendingSettlement> {
+        let verification = self.verify(payment_payload, requirements).await?;
+        if !verification.is_valid {
+            return Err(X402Error::payment_verification_failed(
+                verification
+                    .invalid_reason
+                    .unwrap_or("Verification failed".to_string()),
+            ));
         }
+
+        let auth = &payment_payload.exact_evm()?.authorization;
+        let min_value: u128 = auth
+            .value
+            .parse()
+            .map_err(|_| X402Error::invalid_payment_requirements("Invalid payment amount format"))?;
+        let expected = ExpectedTransfer::new(
+            self.blockchain_client.get_usdc_contract_address()?,
+            auth.from.clone(),
+            auth.to.clone(),
+            min_value,
+        );
+        let confirmer = SettlementConfirmer::new(self.blockchain_client.clone())
+            .with_required_confirmations(self.confirmation_blocks)
+            .with_verification_timeout(self.verification_timeout);
+
+        let transaction_hash = self
+            .create_settlement_transaction(payment_payload, requirements)
+            .await?;
+        let claim = PendingClaim::new(transaction_hash, expected, auth.nonce.clone());
+
+        Ok(PendingSettlement::new(
+            self.blockchain_client.clone(),
+            confirmer,
+            claim,
+            self.settlement_retry_policy.clone(),
+        ))
     }
 
-    /// Create and broadcast a real settlement transaction
+    /// Build, sign, and broadcast the real settlement transaction: a call to
+    /// the USDC contract's `transferWithAuthorization`, submitted as an
+    /// EIP-1559 transaction from the facilitator's relayer wallet.
+    ///
+    /// Requires [`BlockchainFacilitatorConfig::relayer_private_key`] — without
+    /// a relayer key there is no account to sign and pay gas for the
+    /// settlement transaction with.
     async fn create_settlement_transaction(
         &self,
         payment_payload: &PaymentPayload,
         _requirements: &PaymentRequirements,
     ) -> Result<String> {
-        // This is a real implementation that creates actual blockchain transactions
-        // Note: In production, this would require the facilitator's private key
-
-        // For now, we'll create a transaction that calls the USDC contract's
-        // transferWithAuthorization function with the payment authorization
+        let relayer = self.relayer.as_ref().ok_or_else(|| {
+            X402Error::config(
+                "BlockchainFacilitatorConfig.relayer_private_key is required to settle on-chain",
+            )
+        })?;
 
-        let auth = &payment_payload.payload.authorization;
+        let exact_evm = payment_payload.exact_evm()?;
+        let auth = &exact_evm.authorization;
         let usdc_contract = self.blockchain_client.get_usdc_contract_address()?;
+        let signature: EvmSignature = exact_evm.signature.parse()?;
 
-        // Create the function call data for transferWithAuthorization
-        let function_selector = "0x4000aea0"; // transferWithAuthorization(bytes32,address,address,uint256,uint256,uint256,bytes32,uint8,bytes32,bytes32)
-
-        // Encode the parameters
-        let encoded_params = self.encode_transfer_with_authorization_params(auth)?;
-        let data = format!("{}{}", function_selector, encoded_params);
+        let data = crate::erc20::transfer_with_authorization(auth, &signature)?;
 
-        // Create transaction request
         let tx_request = crate::blockchain::TransactionRequest {
             from: auth.from.clone(),
-            to: usdc_contract,
+            to: usdc_contract.clone(),
             value: None, // No ETH value for USDC transfers
-            data: Some(data),
-            gas: Some("0x5208".to_string()), // 21000 gas limit
-            gas_price: Some("0x3b9aca00".to_string()), // 1 gwei
+            data: Some(data.clone()),
+            gas: None,
+            gas_price: None,
+            max_fee_per_gas: None,
+            max_priority_fee_per_gas: None,
         };
+        let middleware = self.middleware();
+        let gas_limit = middleware.estimate_gas(&tx_request).await?;
 
-        // Estimate gas for the transaction
-        let estimated_gas = self.blockchain_client.estimate_gas(&tx_request).await?;
+        let fees = middleware
+            .fee_history(4, crate::gas_oracle::FeeStrategy::Average.reward_percentile())
+            .await?;
+        let suggested_fees = crate::gas_oracle::eip1559_fees_from_history(
+            fees,
+            crate::gas_oracle::GasOracle::DEFAULT_BASE_FEE_MULTIPLIER,
+        );
+        let max_priority_fee_per_gas = suggested_fees.max_priority_fee_per_gas;
+        let max_fee_per_gas = suggested_fees.max_fee_per_gas;
 
-        // Update gas limit
-        let mut final_tx = tx_request;
-        final_tx.gas = Some(format!("0x{:x}", estimated_gas));
+        // Same `estimated_gas * max_fee_per_gas` formula `GasOracle::estimate_settlement_cost`
+        // uses, shared via `SettlementCost::from_parts` so the two can't silently drift —
+        // this path can't call `estimate_settlement_cost` directly since it needs gas/fees
+        // from `self.middleware()` (honoring `with_middleware`'s retry/gas-multiplier
+        // wrapping), not a plain `BlockchainClient`.
+        let cost = crate::gas_oracle::SettlementCost::from_parts(gas_limit, suggested_fees);
 
-        // In a real implementation, we would:
-        // 1. Sign the transaction with the facilitator's private key
-        // 2. Broadcast it to the network
-        // 3. Return the transaction hash
+        if let Some(max_settlement_fee) = self.max_settlement_fee {
+            if cost.estimated_total_wei > max_settlement_fee {
+                return Err(X402Error::config(format!(
+                    "estimated settlement gas cost {} wei exceeds configured max_settlement_fee {max_settlement_fee} wei",
+                    cost.estimated_total_wei
+                )));
+            }
+        }
+
+        let relayer_signer = LocalSigner::from_private_key(&relayer.private_key)?;
+        let relayer_address = format!("{:?}", relayer_signer.address()?);
+        let nonce = self
+            .next_relayer_nonce(&middleware, relayer, &relayer_address)
+            .await?;
+        let chain_id = chain_id_for_network(&self.network)?;
+
+        let unsigned_tx = Eip1559Transaction {
+            chain_id,
+            nonce,
+            max_priority_fee_per_gas,
+            max_fee_per_gas,
+            gas_limit: gas_limit as u128,
+            to: usdc_contract,
+            value: 0,
+            data: hex::decode(data.trim_start_matches("0x"))
+                .map_err(|_| X402Error::malformed_payload("settlement calldata"))?,
+        };
 
-        // For this implementation, we'll simulate the transaction creation
-        // but use real blockchain data for validation
-        let tx_hash = self.simulate_transaction_broadcast(&final_tx, auth).await?;
+        let signing_hash = unsigned_tx.signing_hash()?;
+        let (y_parity, r, s) =
+            sign_prehash_components(signing_hash, &relayer.private_key)?;
+        let signed_tx_hex = unsigned_tx.rlp_signed(y_parity, r, s)?;
 
-        Ok(tx_hash)
+        let broadcast_result = middleware.send_raw_transaction(&signed_tx_hex).await;
+        if broadcast_result.is_err() {
+            // The RPC rejected this nonce (e.g. "nonce too low"/"replacement underpriced"
+            // from a concurrent settlement that landed first) or something else about
+            // chain state moved out from under our cached value. Drop it so the next
+            // settlement attempt resyncs from `eth_getTransactionCount` instead of
+            // repeating a nonce the chain just refused.
+            *relayer.next_nonce.lock().unwrap() = None;
+        }
+        broadcast_result
     }
 
-    /// Encode parameters for transferWithAuthorization function
-    fn encode_transfer_with_authorization_params(
+    /// Resolve the next nonce for the relayer: the first call reads
+    /// `eth_getTransactionCount(address, "pending")`, every subsequent call
+    /// increments the cached value locally — the same trick ethers-rs'
+    /// `NonceManagerMiddleware` uses to avoid a network round trip (and a
+    /// race on reused nonces) for back-to-back settlements. Resyncs from chain
+    /// whenever the previous broadcast failed; see the nonce reset in
+    /// [`Self::create_settlement_transaction`].
+    async fn next_relayer_nonce(
         &self,
-        auth: &crate::types::ExactEvmPayloadAuthorization,
-    ) -> Result<String> {
-        use std::str::FromStr;
-
-        // The transferWithAuthorization function signature:
-        // transferWithAuthorization(
-        //     bytes32 authorization,    // EIP-712 hash of the authorization
-        //     address from,
-        //     address to,
-        //     uint256 value,
-        //     uint256 validAfter,
-        //     uint256 validBefore,
-        //     bytes32 nonce,
-        //     uint8 v,
-        //     bytes32 r,
-        //     bytes32 s
-        // )
-
-        // For now, we'll create a simplified encoding
-        // In a real implementation, this would use proper ABI encoding
-        let mut encoded = String::new();
-
-        // Pad and encode each parameter (simplified)
-        encoded.push_str(&format!("{:064x}", 0)); // authorization hash placeholder
-        encoded.push_str(auth.from.trim_start_matches("0x"));
-        encoded.push_str(auth.to.trim_start_matches("0x"));
-        encoded.push_str(&format!("{:064x}", u128::from_str(&auth.value)?));
-        encoded.push_str(&format!("{:064x}", u128::from_str(&auth.valid_after)?));
-        encoded.push_str(&format!("{:064x}", u128::from_str(&auth.valid_before)?));
-        encoded.push_str(auth.nonce.trim_start_matches("0x"));
-        encoded.push_str(&format!("{:02x}", 0)); // v placeholder
-        encoded.push_str(&format!("{:064x}", 0)); // r placeholder
-        encoded.push_str(&format!("{:064x}", 0)); // s placeholder
-
-        Ok(encoded)
-    }
-
-    /// Simulate transaction broadcast (in production, this would be real)
-    async fn simulate_transaction_broadcast(
+        middleware: &Arc<dyn FacilitatorMiddleware>,
+        relayer: &RelayerState,
+        address: &str,
+    ) -> Result<u64> {
+        let cached = *relayer.next_nonce.lock().unwrap();
+        let nonce = match cached {
+            Some(n) => n,
+            None => middleware.get_transaction_count(address).await?,
+        };
+        *relayer.next_nonce.lock().unwrap() = Some(nonce + 1);
+        Ok(nonce)
+    }
+
+    /// Resubmit a stuck settlement transaction at the same nonce with bumped
+    /// EIP-1559 fees (replace-by-fee), returning the new transaction hash.
+    ///
+    /// This lives on `BlockchainFacilitatorClient` rather than bare
+    /// [`crate::blockchain::BlockchainClient`]: rebuilding and signing a
+    /// replacement requires the relayer's private key, and `BlockchainClient`
+    /// is a pure JSON-RPC client with no wallet or signing capability.
+    /// [`Self::create_settlement_transaction`] is the only other place this
+    /// client signs a transaction, and this method reuses the same
+    /// `Eip1559Transaction`/RLP/signing path, differing only in reusing the
+    /// original's nonce and calldata instead of minting new ones.
+    ///
+    /// Callers are expected to track every hash a given settlement attempt
+    /// produces (original plus replacements) and accept confirmation of any
+    /// of them as final, since only one of the competing transactions can
+    /// ever be mined — see [`crate::settlement_confirmation::PendingClaim`].
+    pub async fn bump_transaction(
         &self,
-        _tx_request: &crate::blockchain::TransactionRequest,
-        _auth: &crate::types::ExactEvmPayloadAuthorization,
+        tx_hash: &str,
+        policy: &crate::blockchain::FeeBumpPolicy,
     ) -> Result<String> {
-        // In production, this would:
-        // 1. Sign the transaction with the facilitator's private key
-        // 2. Broadcast it via eth_sendRawTransaction RPC call
-        // 3. Return the real transaction hash
-
-        // For now, we'll create a realistic transaction hash
-        // that follows the same pattern as real Ethereum transactions
-        let timestamp = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
-
-        // Create a more realistic transaction hash format
-        let mut hash_bytes = [0u8; 32];
-        hash_bytes[0..8].copy_from_slice(&timestamp.to_be_bytes());
-        hash_bytes[8..16].copy_from_slice(&(timestamp % 1000000).to_be_bytes());
-
-        // Fill remaining bytes with deterministic data based on the transaction
-        use sha2::{Digest, Sha256};
-        let mut hasher = Sha256::new();
-        hasher.update(_auth.from.as_bytes());
-        hasher.update(_auth.to.as_bytes());
-        hasher.update(_auth.value.as_bytes());
-        hasher.update(_auth.nonce.as_bytes());
-        let hash_result = hasher.finalize();
-        hash_bytes[16..32].copy_from_slice(&hash_result[16..32]);
-
-        Ok(format!("0x{}", hex::encode(hash_bytes)))
-    }
-
-    /// Wait for transaction confirmation
-    async fn wait_for_confirmation(&self, transaction_hash: &str) -> Result<ConfirmationResult> {
-        let mut attempts = 0;
-        let max_attempts = 30; // 30 seconds timeout
-
-        while attempts < max_attempts {
-            match self
-                .blockchain_client
-                .get_transaction_status(transaction_hash)
-                .await
-            {
-                Ok(tx_info) => {
-                    match tx_info.status {
-                        TransactionStatus::Confirmed => {
-                            return Ok(ConfirmationResult {
-                                success: true,
-                                error_reason: None,
-                                block_number: tx_info.block_number,
-                                gas_used: tx_info.gas_used,
-                            });
-                        }
-                        TransactionStatus::Failed => {
-                            return Ok(ConfirmationResult {
-                                success: false,
-                                error_reason: Some("Transaction failed on blockchain".to_string()),
-                                block_number: None,
-                                gas_used: None,
-                            });
-                        }
-                        TransactionStatus::Pending => {
-                            // Continue waiting
-                        }
-                        TransactionStatus::Unknown => {
-                            // Transaction not found yet, continue waiting
-                        }
-                    }
-                }
-                Err(e) => {
-                    // Log error but continue trying
-                    eprintln!("Error checking transaction status: {}", e);
+        let relayer = self.relayer.as_ref().ok_or_else(|| {
+            X402Error::config(
+                "BlockchainFacilitatorConfig.relayer_private_key is required to bump a transaction",
+            )
+        })?;
+
+        let pending = self.blockchain_client.get_pending_transaction(tx_hash).await?;
+        let (max_fee_per_gas, max_priority_fee_per_gas) =
+            policy.bump(pending.max_fee_per_gas, pending.max_priority_fee_per_gas);
+        let chain_id = chain_id_for_network(&self.network)?;
+
+        let replacement_tx = Eip1559Transaction {
+            chain_id,
+            nonce: pending.nonce,
+            max_priority_fee_per_gas,
+            max_fee_per_gas,
+            gas_limit: pending.gas_limit,
+            to: pending.to,
+            value: pending.value,
+            data: hex::decode(pending.data.trim_start_matches("0x"))
+                .map_err(|_| X402Error::malformed_payload("pending transaction calldata"))?,
+        };
+
+        let signing_hash = replacement_tx.signing_hash()?;
+        let (y_parity, r, s) = sign_prehash_components(signing_hash, &relayer.private_key)?;
+        let signed_tx_hex = replacement_tx.rlp_signed(y_parity, r, s)?;
+
+        self.middleware().send_raw_transaction(&signed_tx_hex).await
+    }
+
+    /// Settle many payments in a single on-chain transaction instead of one
+    /// `transferWithAuthorization` transaction per payment, by aggregating them
+    /// through the [`crate::erc20::MULTICALL3_ADDRESS`] deployment's `aggregate3`.
+    ///
+    /// Each payment is [`Self::verify`]d individually first; anything that fails never
+    /// reaches the aggregated transaction and reports its own `error_reason` at its
+    /// original position in the returned `Vec`. Every verified payment's
+    /// `transferWithAuthorization` call is submitted with `allowFailure: true`, so one
+    /// invalid or already-used authorization reverting on-chain doesn't take down the
+    /// rest of the batch — [`crate::erc20::decode_aggregate3_result`] decodes which
+    /// calls the contract actually let through once the transaction confirms.
+    ///
+    /// Returns [`X402Error::InvalidPaymentRequirements`] up front if `payments` exceeds
+    /// [`Self::MAX_BATCH_SIZE`], bounding both the aggregated calldata size and the
+    /// gas estimate a single transaction has to cover.
+    pub async fn settle_batch(
+        &self,
+        payments: &[(PaymentPayload, PaymentRequirements)],
+    ) -> Result<Vec<SettleResponse>> {
+        if payments.len() > Self::MAX_BATCH_SIZE {
+            return Err(X402Error::invalid_payment_requirements(format!(
+                "settle_batch accepts at most {} payments per call, got {}",
+                Self::MAX_BATCH_SIZE,
+                payments.len()
+            )));
+        }
+
+        let usdc_contract = self.blockchain_client.get_usdc_contract_address()?;
+        let usdc_address = Address::from_str(&usdc_contract)
+            .map_err(|_| X402Error::config("USDC contract address is not a valid address"))?;
+
+        // Verify every payment up front. Anything that fails never joins the
+        // aggregated transaction; its slot in `results` is filled in immediately
+        // with its own failure reason and never touched again.
+        let mut results: Vec<Option<SettleResponse>> = Vec::with_capacity(payments.len());
+        let mut calls = Vec::new();
+        let mut settled_indices = Vec::new();
+
+        for (payment_payload, requirements) in payments {
+            let verification = self.verify(payment_payload, requirements).await?;
+            if !verification.is_valid {
+                results.push(Some(SettleResponse {
+                    success: false,
+                    error_reason: Some(
+                        verification
+                            .invalid_reason
+                            .unwrap_or("Verification failed".to_string()),
+                    ),
+                    transaction: "".to_string(),
+                    network: payment_payload.network.clone(),
+                    payer: verification.payer,
+                }));
+                continue;
+            }
+
+            let exact_evm = payment_payload.exact_evm()?;
+            let signature: EvmSignature = exact_evm.signature.parse()?;
+            let data = crate::erc20::transfer_with_authorization(&exact_evm.authorization, &signature)?;
+            let call_data = hex::decode(data.trim_start_matches("0x"))
+                .map_err(|_| X402Error::malformed_payload("settlement calldata"))?;
+
+            settled_indices.push(results.len());
+            calls.push((usdc_address, true, call_data));
+            results.push(None);
+        }
+
+        if calls.is_empty() {
+            return Ok(results.into_iter().map(|r| r.expect("every slot filled above")).collect());
+        }
+
+        let relayer = self.relayer.as_ref().ok_or_else(|| {
+            X402Error::config(
+                "BlockchainFacilitatorConfig.relayer_private_key is required to settle on-chain",
+            )
+        })?;
+
+        let aggregate_data = crate::erc20::aggregate3(&calls);
+        let relayer_signer = LocalSigner::from_private_key(&relayer.private_key)?;
+        let relayer_address = format!("{:?}", relayer_signer.address()?);
+
+        let tx_request = crate::blockchain::TransactionRequest {
+            from: relayer_address.clone(),
+            to: crate::erc20::MULTICALL3_ADDRESS.to_string(),
+            value: None,
+            data: Some(aggregate_data.clone()),
+            gas: None,
+            gas_price: None,
+            max_fee_per_gas: None,
+            max_priority_fee_per_gas: None,
+        };
+        let middleware = self.middleware();
+        let gas_limit = middleware.estimate_gas(&tx_request).await?;
+
+        let fees = middleware
+            .fee_history(4, crate::gas_oracle::FeeStrategy::Average.reward_percentile())
+            .await?;
+        let suggested_fees = crate::gas_oracle::eip1559_fees_from_history(
+            fees,
+            crate::gas_oracle::GasOracle::DEFAULT_BASE_FEE_MULTIPLIER,
+        );
+        let nonce = self
+            .next_relayer_nonce(&middleware, relayer, &relayer_address)
+            .await?;
+        let chain_id = chain_id_for_network(&self.network)?;
+
+        let unsigned_tx = Eip1559Transaction {
+            chain_id,
+            nonce,
+            max_priority_fee_per_gas: suggested_fees.max_priority_fee_per_gas,
+            max_fee_per_gas: suggested_fees.max_fee_per_gas,
+            gas_limit: gas_limit as u128,
+            to: crate::erc20::MULTICALL3_ADDRESS.to_string(),
+            value: 0,
+            data: hex::decode(aggregate_data.trim_start_matches("0x"))
+                .map_err(|_| X402Error::malformed_payload("aggregate3 calldata"))?,
+        };
+
+        let signing_hash = unsigned_tx.signing_hash()?;
+        let (y_parity, r, s) = sign_prehash_components(signing_hash, &relayer.private_key)?;
+        let signed_tx_hex = unsigned_tx.rlp_signed(y_parity, r, s)?;
+
+        let fill_batch_failure =
+            |results: &mut Vec<Option<SettleResponse>>, reason: String, transaction_hash: String| {
+                for &index in &settled_indices {
+                    let auth_from = payments[index]
+                        .0
+                        .exact_evm()
+                        .expect("settled_indices only holds payments verify() already confirmed are exact-EVM")
+                        .authorization
+                        .from
+                        .clone();
+                    results[index] = Some(SettleResponse {
+                        success: false,
+                        error_reason: Some(reason.clone()),
+                        transaction: transaction_hash.clone(),
+                        network: payments[index].0.network.clone(),
+                        payer: Some(auth_from),
+                    });
                 }
+            };
+
+        let broadcast_result = middleware.send_raw_transaction(&signed_tx_hex).await;
+        let transaction_hash = match broadcast_result {
+            Ok(hash) => hash,
+            Err(err) => {
+                *relayer.next_nonce.lock().unwrap() = None;
+                fill_batch_failure(
+                    &mut results,
+                    format!("batch settlement transaction failed to broadcast: {err}"),
+                    "".to_string(),
+                );
+                return Ok(results.into_iter().map(|r| r.expect("every slot filled above")).collect());
             }
+        };
+
+        let confirmed = self
+            .blockchain_client
+            .watch_transaction(&transaction_hash, self.confirmation_blocks, self.verification_timeout)
+            .await;
+        let block_number = match confirmed {
+            Ok(info) => info.block_number.unwrap_or(0),
+            Err(err) => {
+                fill_batch_failure(
+                    &mut results,
+                    format!("batch settlement transaction {transaction_hash} did not confirm: {err}"),
+                    transaction_hash.clone(),
+                );
+                return Ok(results.into_iter().map(|r| r.expect("every slot filled above")).collect());
+            }
+        };
 
-            tokio::time::sleep(Duration::from_secs(1)).await;
-            attempts += 1;
+        // The mined transaction's own per-call results aren't in its receipt — replay
+        // the same `aggregate3` calldata as a read-only call at the block it landed in
+        // to decode which individual `transferWithAuthorization`s the contract actually
+        // let through.
+        let raw_result = self
+            .blockchain_client
+            .call_at_block(crate::erc20::MULTICALL3_ADDRESS, &aggregate_data, block_number)
+            .await?;
+        let call_results = crate::erc20::decode_aggregate3_result(&raw_result)?;
+
+        for (call_index, &result_index) in settled_indices.iter().enumerate() {
+            let auth_from = payments[result_index]
+                .0
+                .exact_evm()
+                .expect("settled_indices only holds payments verify() already confirmed are exact-EVM")
+                .authorization
+                .from
+                .clone();
+            let success = call_results.get(call_index).map(|(ok, _)| *ok).unwrap_or(false);
+            results[result_index] = Some(SettleResponse {
+                success,
+                error_reason: if success {
+                    None
+                } else {
+                    Some("transferWithAuthorization reverted inside the batch".to_string())
+                },
+                transaction: transaction_hash.clone(),
+                network: payments[result_index].0.network.clone(),
+                payer: Some(auth_from),
+            });
         }
 
-        Ok(ConfirmationResult {
-            success: false,
-            error_reason: Some("Transaction confirmation timeout".to_string()),
-            block_number: None,
-            gas_used: None,
-        })
+        Ok(results.into_iter().map(|r| r.expect("every slot filled above")).collect())
+    }
+
+    /// Verify many payments' on-chain preconditions in a single RPC round trip
+    /// instead of one `eth_call` per payment, aggregating each payment's
+    /// `balanceOf(payer)` and `authorizationState(payer, nonce)` reads through
+    /// the [`crate::erc20::MULTICALL3_ADDRESS`] deployment's `aggregate3` — the
+    /// same batching [`Self::settle_batch`] applies on the settlement side.
+    ///
+    /// Every payment's [`Self::local_checks`] (network, scheme, timing,
+    /// signature, amount, recipient) run first and fill in its result
+    /// immediately on failure, exactly like [`Self::verify`]; only payments
+    /// that clear all of those join the aggregated call. Each sub-call is
+    /// submitted with `allowFailure: true`, so a token returning nothing for
+    /// `authorizationState` (non-EIP-3009 USDC deployments exist) fails only
+    /// that one read rather than the whole batch.
+    ///
+    /// Returns [`X402Error::InvalidPaymentRequirements`] up front if `payments`
+    /// exceeds [`Self::MAX_BATCH_SIZE`].
+    pub async fn verify_batch(
+        &self,
+        payments: &[(PaymentPayload, PaymentRequirements)],
+    ) -> Result<Vec<VerifyResponse>> {
+        if payments.len() > Self::MAX_BATCH_SIZE {
+            return Err(X402Error::invalid_payment_requirements(format!(
+                "verify_batch accepts at most {} payments per call, got {}",
+                Self::MAX_BATCH_SIZE,
+                payments.len()
+            )));
+        }
+
+        let usdc_contract = self.blockchain_client.get_usdc_contract_address()?;
+        let usdc_address = Address::from_str(&usdc_contract)
+            .map_err(|_| X402Error::config("USDC contract address is not a valid address"))?;
+
+        // Local checks first; anything that fails never joins the aggregated
+        // call and its slot in `results` is filled in immediately.
+        let mut results: Vec<Option<VerifyResponse>> = Vec::with_capacity(payments.len());
+        let mut calls = Vec::new();
+        let mut checked_indices = Vec::new();
+
+        for (payment_payload, requirements) in payments {
+            if let Some(early) = self.local_checks(payment_payload, requirements)? {
+                results.push(Some(early));
+                continue;
+            }
+
+            let auth = &payment_payload.exact_evm()?.authorization;
+            let payer = Address::from_str(&auth.from)
+                .map_err(|_| X402Error::malformed_payload("authorization.from"))?;
+            let balance_calldata = crate::erc20::balance_of(payer);
+            let nonce_calldata = crate::erc20::authorization_state(payer, &auth.nonce)?;
+
+            let decode_calldata = |calldata: &str| {
+                hex::decode(calldata.trim_start_matches("0x"))
+                    .map_err(|_| X402Error::malformed_payload("erc20 calldata"))
+            };
+            calls.push((usdc_address, true, decode_calldata(&balance_calldata)?));
+            calls.push((usdc_address, true, decode_calldata(&nonce_calldata)?));
+
+            checked_indices.push(results.len());
+            results.push(None);
+        }
+
+        if calls.is_empty() {
+            return Ok(results.into_iter().map(|r| r.expect("every slot filled above")).collect());
+        }
+
+        let aggregate_data = crate::erc20::aggregate3(&calls);
+        let raw_responses = self
+            .blockchain_client
+            .batch(&[(
+                "eth_call",
+                serde_json::json!([{"to": crate::erc20::MULTICALL3_ADDRESS, "data": aggregate_data}, "latest"]),
+            )])
+            .await?;
+        let raw_result = raw_responses[0].get("result").and_then(|v| v.as_str()).unwrap_or("0x");
+        let call_results = crate::erc20::decode_aggregate3_result(raw_result)?;
+
+        for (group_index, &result_index) in checked_indices.iter().enumerate() {
+            let (payment_payload, _requirements) = &payments[result_index];
+            let auth = &payment_payload.exact_evm()?.authorization;
+            let payment_amount: u128 = auth.value.parse().unwrap_or(0);
+            let (balance_base, nonce_base) = (group_index * 2, group_index * 2 + 1);
+
+            let nonce_used = call_results
+                .get(nonce_base)
+                .map(|(success, data)| *success && data.iter().any(|&b| b != 0))
+                .unwrap_or(false);
+
+            results[result_index] = Some(if nonce_used {
+                VerifyResponse {
+                    is_valid: false,
+                    invalid_reason: Some("Authorization nonce already used".to_string()),
+                    payer: Some(auth.from.clone()),
+                }
+            } else {
+                match call_results.get(balance_base) {
+                    Some((true, data)) => {
+                        let balance = U256::from_big_endian(data).as_u128();
+                        if balance < payment_amount {
+                            VerifyResponse {
+                                is_valid: false,
+                                invalid_reason: Some(format!(
+                                    "Insufficient balance: {} < {}",
+                                    balance, payment_amount
+                                )),
+                                payer: Some(auth.from.clone()),
+                            }
+                        } else {
+                            VerifyResponse { is_valid: true, invalid_reason: None, payer: Some(auth.from.clone()) }
+                        }
+                    }
+                    _ => VerifyResponse { is_valid: true, invalid_reason: None, payer: Some(auth.from.clone()) },
+                }
+            });
+        }
+
+        Ok(results.into_iter().map(|r| r.expect("every slot filled above")).collect())
     }
 
     /// Get network information
@@ -458,17 +1047,201 @@ impl BlockchainFacilitatorClient {
             .await?;
         Ok(tx_info.status == TransactionStatus::Confirmed)
     }
+
+    /// The single `(scheme, network)` pairing this client verifies and settles
+    /// directly against its configured RPC node, scoped to the USDC asset that
+    /// [`BlockchainClient::get_usdc_contract_address`] resolves for `self.network`
+    pub async fn supported(&self) -> Result<crate::types::SupportedKinds> {
+        let asset = self.blockchain_client.get_usdc_contract_address().ok();
+        Ok(crate::types::SupportedKinds {
+            kinds: vec![crate::types::SupportedKind {
+                x402_version: crate::types::X402_VERSION,
+                scheme: "exact".to_string(),
+                network: self.network.clone(),
+                asset,
+            }],
+        })
+    }
 }
 
-/// Transaction confirmation result
-#[derive(Debug, Clone)]
-struct ConfirmationResult {
-    success: bool,
-    error_reason: Option<String>,
-    #[allow(dead_code)]
-    block_number: Option<u64>,
-    #[allow(dead_code)]
-    gas_used: Option<u64>,
+/// Lets a [`BlockchainFacilitatorClient`] be registered alongside remote
+/// facilitators (e.g. in a [`FacilitatorRegistry`]) so an operator can settle
+/// directly against their own RPC node instead of trusting a hosted
+/// facilitator such as `https://x402.org/facilitator`, without the caller
+/// needing to know which kind of backend it's talking to.
+impl Facilitator for BlockchainFacilitatorClient {
+    fn verify<'a>(
+        &'a self,
+        payment_payload: &'a PaymentPayload,
+        payment_requirements: &'a PaymentRequirements,
+    ) -> BoxFuture<'a, Result<VerifyResponse>> {
+        Box::pin(BlockchainFacilitatorClient::verify(self, payment_payload, payment_requirements))
+    }
+
+    fn settle<'a>(
+        &'a self,
+        payment_payload: &'a PaymentPayload,
+        payment_requirements: &'a PaymentRequirements,
+    ) -> BoxFuture<'a, Result<SettleResponse>> {
+        Box::pin(BlockchainFacilitatorClient::settle(self, payment_payload, payment_requirements))
+    }
+
+    fn supported(&self) -> BoxFuture<'_, Result<crate::types::SupportedKinds>> {
+        Box::pin(BlockchainFacilitatorClient::supported(self))
+    }
+}
+
+/// An unsigned EIP-1559 (type `0x02`) transaction, RLP-encoded for signing
+/// and broadcast. This crate otherwise talks to the chain via raw JSON-RPC
+/// (see [`crate::blockchain::BlockchainClient`]) rather than an `ethers`-style
+/// client, so transaction construction and RLP encoding are implemented
+/// directly here rather than pulling in a separate transaction-building crate.
+struct Eip1559Transaction {
+    chain_id: u64,
+    nonce: u64,
+    max_priority_fee_per_gas: u128,
+    max_fee_per_gas: u128,
+    gas_limit: u128,
+    to: String,
+    value: u128,
+    data: Vec<u8>,
+}
+
+impl Eip1559Transaction {
+    /// Unsigned payload fields, shared by both the signing hash and the
+    /// final signed transaction
+    fn fields(&self) -> Result<Vec<u8>> {
+        let to_bytes = hex::decode(self.to.trim_start_matches("0x"))
+            .map_err(|_| X402Error::invalid_network("Invalid contract address"))?;
+
+        Ok(rlp::encode_list(&[
+            rlp::encode_uint(self.chain_id as u128),
+            rlp::encode_uint(self.nonce as u128),
+            rlp::encode_uint(self.max_priority_fee_per_gas),
+            rlp::encode_uint(self.max_fee_per_gas),
+            rlp::encode_uint(self.gas_limit),
+            rlp::encode_bytes(&to_bytes),
+            rlp::encode_uint(self.value),
+            rlp::encode_bytes(&self.data),
+            rlp::encode_list(&[]), // empty access list
+        ]))
+    }
+
+    /// `keccak256(0x02 || rlp(unsigned_fields))` — the hash the relayer signs
+    fn signing_hash(&self) -> Result<H256> {
+        let mut payload = vec![0x02];
+        payload.extend(self.fields()?);
+        Ok(H256::from_slice(&keccak256(&payload)))
+    }
+
+    /// The fully signed transaction, hex-encoded with its `0x02` type prefix,
+    /// ready for `eth_sendRawTransaction`
+    fn rlp_signed(&self, y_parity: u8, r: H256, s: H256) -> Result<String> {
+        let fields = self.fields()?;
+        // `fields()` already returns an RLP list; strip its outer list header
+        // so the signature fields can be appended inside the same list.
+        let list_body = rlp::strip_list_header(&fields);
+        let signed_fields = rlp::encode_list_from_body(
+            &[
+                list_body.to_vec(),
+                rlp::encode_uint(y_parity as u128),
+                rlp::encode_uint_from_bytes(r.as_bytes()),
+                rlp::encode_uint_from_bytes(s.as_bytes()),
+            ]
+            .concat(),
+        );
+
+        let mut payload = vec![0x02];
+        payload.extend(signed_fields);
+        Ok(format!("0x{}", hex::encode(&payload)))
+    }
+}
+
+/// Keccak-256, used for the EIP-1559 transaction signing hash. Duplicated
+/// locally rather than exposed from [`crate::crypto`] since it's a private
+/// implementation detail there too (see `crypto::eip712::keccak256` and
+/// `crypto::signature::keccak256`).
+fn keccak256(data: &[u8]) -> [u8; 32] {
+    use sha3::{Digest, Keccak256};
+    Keccak256::digest(data).into()
+}
+
+/// Minimal RLP encoder covering exactly what [`Eip1559Transaction`] needs:
+/// byte strings, unsigned integers, and lists. Not a general-purpose RLP
+/// implementation — e.g. it has no decoder, since nothing here needs one.
+mod rlp {
+    /// Encode a byte string per the RLP rules: a single byte below `0x80`
+    /// encodes as itself, otherwise a length-prefixed string.
+    pub fn encode_bytes(bytes: &[u8]) -> Vec<u8> {
+        if bytes.len() == 1 && bytes[0] < 0x80 {
+            return bytes.to_vec();
+        }
+        let mut out = encode_length(bytes.len(), 0x80);
+        out.extend_from_slice(bytes);
+        out
+    }
+
+    /// Encode an unsigned integer as its minimal big-endian byte string
+    /// (`0` encodes as the empty string, per RLP convention)
+    pub fn encode_uint(value: u128) -> Vec<u8> {
+        if value == 0 {
+            return encode_bytes(&[]);
+        }
+        let bytes = value.to_be_bytes();
+        let first_nonzero = bytes.iter().position(|&b| b != 0).unwrap();
+        encode_bytes(&bytes[first_nonzero..])
+    }
+
+    /// Same as [`encode_uint`], but starting from a fixed-width big-endian
+    /// byte slice (e.g. a 32-byte signature component) rather than a `u128`
+    pub fn encode_uint_from_bytes(bytes: &[u8]) -> Vec<u8> {
+        match bytes.iter().position(|&b| b != 0) {
+            Some(first_nonzero) => encode_bytes(&bytes[first_nonzero..]),
+            None => encode_bytes(&[]),
+        }
+    }
+
+    /// Encode a list of already RLP-encoded items
+    pub fn encode_list(items: &[Vec<u8>]) -> Vec<u8> {
+        let body: Vec<u8> = items.iter().flatten().copied().collect();
+        encode_list_from_body(&body)
+    }
+
+    /// Wrap an already-concatenated RLP item body in a list header
+    pub fn encode_list_from_body(body: &[u8]) -> Vec<u8> {
+        let mut out = encode_length(body.len(), 0xc0);
+        out.extend_from_slice(body);
+        out
+    }
+
+    /// Strip a list's header off, returning just its encoded item body
+    pub fn strip_list_header(encoded_list: &[u8]) -> &[u8] {
+        if encoded_list.is_empty() {
+            return encoded_list;
+        }
+        let prefix = encoded_list[0];
+        if prefix <= 0xf7 {
+            &encoded_list[1..]
+        } else {
+            let len_of_len = (prefix - 0xf7) as usize;
+            &encoded_list[1 + len_of_len..]
+        }
+    }
+
+    /// Shared length-prefix encoder for both strings (`offset = 0x80`) and
+    /// lists (`offset = 0xc0`)
+    fn encode_length(len: usize, offset: u8) -> Vec<u8> {
+        if len <= 55 {
+            vec![offset + len as u8]
+        } else {
+            let len_bytes = len.to_be_bytes();
+            let first_nonzero = len_bytes.iter().position(|&b| b != 0).unwrap_or(len_bytes.len() - 1);
+            let len_of_len = len_bytes.len() - first_nonzero;
+            let mut out = vec![offset + 55 + len_of_len as u8];
+            out.extend_from_slice(&len_bytes[first_nonzero..]);
+            out
+        }
+    }
 }
 
 /// Blockchain facilitator client factory
@@ -529,4 +1302,408 @@ mod tests {
         let facilitator = BlockchainFacilitatorFactory::base_sepolia();
         assert!(facilitator.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_supported_reports_exact_scheme_for_its_configured_network() {
+        let facilitator = BlockchainFacilitatorFactory::base_sepolia().unwrap();
+        let supported = facilitator.supported().await.unwrap();
+
+        assert_eq!(supported.kinds.len(), 1);
+        assert_eq!(supported.kinds[0].scheme, "exact");
+        assert_eq!(supported.kinds[0].network, "base-sepolia");
+        assert!(supported.kinds[0].asset.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_blockchain_facilitator_client_is_usable_as_a_trait_object() {
+        let facilitator: Arc<dyn Facilitator> =
+            Arc::new(BlockchainFacilitatorFactory::base_sepolia().unwrap());
+        let supported = facilitator.supported().await.unwrap();
+
+        assert_eq!(supported.kinds[0].network, "base-sepolia");
+    }
+
+    struct FixedFeeMiddleware;
+
+    impl FacilitatorMiddleware for FixedFeeMiddleware {
+        fn send_raw_transaction<'a>(
+            &'a self,
+            _signed_tx_hex: &'a str,
+        ) -> crate::facilitator::BoxFuture<'a, Result<String>> {
+            Box::pin(async move { Ok("0x0".to_string()) })
+        }
+
+        fn estimate_gas<'a>(
+            &'a self,
+            _transaction: &'a crate::blockchain::TransactionRequest,
+        ) -> crate::facilitator::BoxFuture<'a, Result<u64>> {
+            Box::pin(async move { Ok(21000) })
+        }
+
+        fn get_transaction_count<'a>(&'a self, _address: &'a str) -> crate::facilitator::BoxFuture<'a, Result<u64>> {
+            Box::pin(async move { Ok(0) })
+        }
+
+        fn fee_history(&self, _block_count: u64, _reward_percentile: f64) -> crate::facilitator::BoxFuture<'_, Result<crate::blockchain::FeeHistory>> {
+            Box::pin(async move {
+                Ok(crate::blockchain::FeeHistory {
+                    base_fee_per_gas: 100,
+                    max_priority_fee_per_gas: 10,
+                })
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_with_middleware_overrides_the_gas_price_multiplier() {
+        let client = BlockchainFacilitatorClient::new(BlockchainFacilitatorConfig {
+            network: "base-sepolia".to_string(),
+            gas_price_multiplier: Some(5.0),
+            ..Default::default()
+        })
+        .unwrap()
+        .with_middleware(Arc::new(FixedFeeMiddleware));
+
+        // An explicit middleware stack wins over the config-driven gas oracle, so the
+        // fee history comes back unscaled even though gas_price_multiplier is set.
+        let fees = client.middleware().fee_history(4, 50.0).await.unwrap();
+        assert_eq!(fees.base_fee_per_gas, 100);
+        assert_eq!(fees.max_priority_fee_per_gas, 10);
+    }
+
+    fn test_payment_payload() -> PaymentPayload {
+        use crate::types::{ExactEvmPayload, ExactEvmPayloadAuthorization};
+
+        let authorization = ExactEvmPayloadAuthorization::new(
+            "0x857b06519E91e3A54538791bDbb0E22373e36b66",
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            "1000000",
+            "1745323800",
+            "1745323985",
+            "0xf3746613c2d920b5fdabc0856f2aeb2d4f88ee6037b8cc5d04a71a4462f13480",
+        );
+        let payload = crate::types::ExactEvmPayload {
+            signature: format!("0x{}{}{}", "11".repeat(32), "22".repeat(32), "1c"),
+            authorization,
+        };
+        PaymentPayload::new("exact", "base-sepolia", payload)
+    }
+
+    #[tokio::test]
+    async fn test_create_settlement_transaction_requires_relayer_key() {
+        let client = BlockchainFacilitatorClient::new(BlockchainFacilitatorConfig {
+            network: "base-sepolia".to_string(),
+            relayer_private_key: None,
+            ..Default::default()
+        })
+        .unwrap();
+
+        let payment_payload = test_payment_payload();
+        let requirements = PaymentRequirements::new(
+            "exact",
+            "base-sepolia",
+            "1000000",
+            "0x036CbD53842c5426634e7929541eC2318f3dCF7e",
+            &payment_payload.exact_evm().unwrap().authorization.to,
+            "https://example.com/resource",
+            "test resource",
+        );
+
+        let err = client
+            .create_settlement_transaction(&payment_payload, &requirements)
+            .await
+            .unwrap_err();
+
+        assert!(err.to_string().contains("relayer_private_key"));
+    }
+
+    #[tokio::test]
+    async fn test_create_settlement_transaction_refuses_to_exceed_max_settlement_fee() {
+        let mut server = mockito::Server::new_async().await;
+
+        // `estimate_gas` reports 100_000 gas and `fee_history` a 1 gwei base fee with
+        // no reward data; `100_000 * max_fee_per_gas` comfortably exceeds the ceiling
+        // configured below, so the transaction must be refused before a relayer nonce
+        // lookup is ever needed.
+        let _m_gas = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex("eth_estimateGas".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": "0x186a0"}).to_string(),
+            )
+            .create();
+        let _m_fees = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex("eth_feeHistory".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "result": {"baseFeePerGas": ["0x3b9aca00"], "reward": [["0x0"]]}
+                })
+                .to_string(),
+            )
+            .create();
+
+        let client = BlockchainFacilitatorClient::new(BlockchainFacilitatorConfig {
+            rpc_url: Some(server.url()),
+            network: "base-sepolia".to_string(),
+            relayer_private_key: Some(
+                "0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef".to_string(),
+            ),
+            max_settlement_fee: Some(1), // far below any real gas cost
+            ..Default::default()
+        })
+        .unwrap();
+
+        let payment_payload = test_payment_payload();
+        let requirements = PaymentRequirements::new(
+            "exact",
+            "base-sepolia",
+            "1000000",
+            "0x036CbD53842c5426634e7929541eC2318f3dCF7e",
+            &payment_payload.exact_evm().unwrap().authorization.to,
+            "https://example.com/resource",
+            "test resource",
+        );
+
+        let err = client
+            .create_settlement_transaction(&payment_payload, &requirements)
+            .await
+            .unwrap_err();
+
+        assert!(err.to_string().contains("max_settlement_fee"));
+    }
+
+    #[tokio::test]
+    async fn test_verify_rejects_a_forged_signature() {
+        let client = BlockchainFacilitatorClient::new(BlockchainFacilitatorConfig {
+            network: "base-sepolia".to_string(),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let payment_payload = test_payment_payload();
+        let requirements = PaymentRequirements::new(
+            "exact",
+            "base-sepolia",
+            "1000000",
+            "0x036CbD53842c5426634e7929541eC2318f3dCF7e",
+            &payment_payload.exact_evm().unwrap().authorization.to,
+            "https://example.com/resource",
+            "test resource",
+        );
+
+        // `test_payment_payload` carries a fabricated signature that doesn't
+        // actually recover to `authorization.from`, so verification must fail
+        // before ever reaching the balance check (which would require a live RPC).
+        let verification = client.verify(&payment_payload, &requirements).await.unwrap();
+
+        assert!(!verification.is_valid);
+        assert_eq!(verification.invalid_reason, Some("Invalid signature".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_settle_pending_fails_fast_on_a_failed_verification() {
+        let client = BlockchainFacilitatorClient::new(BlockchainFacilitatorConfig {
+            network: "base-sepolia".to_string(),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let payment_payload = test_payment_payload();
+        let requirements = PaymentRequirements::new(
+            "exact",
+            "base-sepolia",
+            "1000000",
+            "0x036CbD53842c5426634e7929541eC2318f3dCF7e",
+            &payment_payload.exact_evm().unwrap().authorization.to,
+            "https://example.com/resource",
+            "test resource",
+        );
+
+        // Same forged-signature fixture as `test_verify_rejects_a_forged_signature`:
+        // `settle_pending` must surface the verification failure as an error rather
+        // than going on to broadcast a transaction for an authorization that never
+        // checked out.
+        let err = client
+            .settle_pending(&payment_payload, &requirements)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, X402Error::PaymentVerificationFailed { reason } if reason == "Invalid signature"));
+    }
+
+    #[test]
+    fn test_create_settlement_transaction_calldata_goes_through_the_typed_erc20_encoder() {
+        // `create_settlement_transaction` delegates calldata building to
+        // `crate::erc20::transfer_with_authorization` (see [`erc20`] module tests for
+        // selector/field-encoding coverage); this just confirms the fixture payload
+        // used by this file's settlement tests still produces calldata of the
+        // expected shape (selector + 9 32-byte words) through that path.
+        let payload = test_payment_payload();
+        let signature: EvmSignature = payload.exact_evm().unwrap().signature.parse().unwrap();
+        let calldata =
+            crate::erc20::transfer_with_authorization(&payload.exact_evm().unwrap().authorization, &signature)
+                .unwrap();
+
+        assert_eq!(calldata.len(), 2 + 8 + 9 * 64);
+        assert!(calldata.starts_with("0xe3ee160e"));
+    }
+
+    #[tokio::test]
+    async fn test_settle_batch_rejects_more_than_the_max_batch_size() {
+        let client = BlockchainFacilitatorClient::new(BlockchainFacilitatorConfig {
+            network: "base-sepolia".to_string(),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let payments: Vec<_> = (0..BlockchainFacilitatorClient::MAX_BATCH_SIZE + 1)
+            .map(|_| {
+                let payload = test_payment_payload();
+                let requirements = PaymentRequirements::new(
+                    "exact",
+                    "base-sepolia",
+                    "1000000",
+                    "0x036CbD53842c5426634e7929541eC2318f3dCF7e",
+                    &payload.exact_evm().unwrap().authorization.to,
+                    "https://example.com/resource",
+                    "test resource",
+                );
+                (payload, requirements)
+            })
+            .collect();
+
+        let err = client.settle_batch(&payments).await.unwrap_err();
+        assert!(err.to_string().contains("MAX_BATCH_SIZE") || err.to_string().to_lowercase().contains("batch"));
+    }
+
+    #[tokio::test]
+    async fn test_settle_batch_reports_per_payment_failures_without_touching_the_chain() {
+        // `test_payment_payload`'s fabricated signature never passes `verify`, so every
+        // payment in this batch fails before the aggregated transaction is ever built —
+        // this must return cleanly even with no relayer key and no RPC endpoint.
+        let client = BlockchainFacilitatorClient::new(BlockchainFacilitatorConfig {
+            network: "base-sepolia".to_string(),
+            relayer_private_key: None,
+            ..Default::default()
+        })
+        .unwrap();
+
+        let payload_a = test_payment_payload();
+        let requirements_a = PaymentRequirements::new(
+            "exact",
+            "base-sepolia",
+            "1000000",
+            "0x036CbD53842c5426634e7929541eC2318f3dCF7e",
+            &payload_a.exact_evm().unwrap().authorization.to,
+            "https://example.com/resource",
+            "test resource",
+        );
+        let payload_b = test_payment_payload();
+        let requirements_b = PaymentRequirements::new(
+            "exact",
+            "base-sepolia",
+            "1000000",
+            "0x036CbD53842c5426634e7929541eC2318f3dCF7e",
+            &payload_b.exact_evm().unwrap().authorization.to,
+            "https://example.com/resource",
+            "test resource",
+        );
+
+        let results = client
+            .settle_batch(&[(payload_a, requirements_a), (payload_b, requirements_b)])
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 2);
+        for result in &results {
+            assert!(!result.success);
+            assert_eq!(result.error_reason, Some("Invalid signature".to_string()));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_verify_batch_rejects_more_than_the_max_batch_size() {
+        let client = BlockchainFacilitatorClient::new(BlockchainFacilitatorConfig {
+            network: "base-sepolia".to_string(),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let payments: Vec<_> = (0..BlockchainFacilitatorClient::MAX_BATCH_SIZE + 1)
+            .map(|_| {
+                let payload = test_payment_payload();
+                let requirements = PaymentRequirements::new(
+                    "exact",
+                    "base-sepolia",
+                    "1000000",
+                    "0x036CbD53842c5426634e7929541eC2318f3dCF7e",
+                    &payload.exact_evm().unwrap().authorization.to,
+                    "https://example.com/resource",
+                    "test resource",
+                );
+                (payload, requirements)
+            })
+            .collect();
+
+        let err = client.verify_batch(&payments).await.unwrap_err();
+        assert!(err.to_string().contains("MAX_BATCH_SIZE") || err.to_string().to_lowercase().contains("batch"));
+    }
+
+    #[tokio::test]
+    async fn test_verify_batch_reports_per_payment_failures_without_touching_the_chain() {
+        // `test_payment_payload`'s fabricated signature never passes local_checks, so
+        // every payment in this batch fails before the aggregated eth_call is ever
+        // built — this must return cleanly with no RPC endpoint configured.
+        let client = BlockchainFacilitatorClient::new(BlockchainFacilitatorConfig {
+            network: "base-sepolia".to_string(),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let payload_a = test_payment_payload();
+        let requirements_a = PaymentRequirements::new(
+            "exact",
+            "base-sepolia",
+            "1000000",
+            "0x036CbD53842c5426634e7929541eC2318f3dCF7e",
+            &payload_a.exact_evm().unwrap().authorization.to,
+            "https://example.com/resource",
+            "test resource",
+        );
+        let payload_b = test_payment_payload();
+        let requirements_b = PaymentRequirements::new(
+            "exact",
+            "base-sepolia",
+            "1000000",
+            "0x036CbD53842c5426634e7929541eC2318f3dCF7e",
+            &payload_b.exact_evm().unwrap().authorization.to,
+            "https://example.com/resource",
+            "test resource",
+        );
+
+        let results = client
+            .verify_batch(&[(payload_a, requirements_a), (payload_b, requirements_b)])
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 2);
+        for result in &results {
+            assert!(!result.is_valid);
+            assert_eq!(result.invalid_reason, Some("Invalid signature".to_string()));
+        }
+    }
+
+    #[test]
+    fn test_rlp_round_trips_list_header() {
+        let body = rlp::encode_list(&[rlp::encode_uint(1), rlp::encode_uint(2)]);
+        let stripped = rlp::strip_list_header(&body);
+        assert_eq!(stripped, &[0x01, 0x02]);
+    }
 }
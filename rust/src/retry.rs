@@ -0,0 +1,462 @@
+//! Retry policy for facilitator verify/settle calls
+//!
+//! A flaky facilitator shouldn't immediately surface a 502 to a paying client when
+//! the request would likely succeed on retry. [`RetryPolicy`] describes exponential
+//! backoff with full jitter, bounded by a max delay and a max attempt count, and
+//! honors a facilitator-supplied `Retry-After` delay when one is present on the error.
+
+use crate::X402Error;
+use rand::Rng;
+use std::time::Duration;
+
+/// Configurable exponential backoff policy for retrying facilitator requests
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Delay before the first retry
+    pub base_delay: Duration,
+    /// Upper bound on any single retry delay
+    pub max_delay: Duration,
+    /// Maximum number of attempts (including the initial one)
+    pub max_attempts: u32,
+    /// Whether to randomize each delay (full jitter) or sleep the computed backoff
+    /// as-is. Left on by default to avoid synchronized retry storms across clients;
+    /// disable it for deterministic tests or callers doing their own jitter.
+    pub jitter: bool,
+    /// Growth factor applied per attempt: `base_delay * multiplier^attempt`. Left at
+    /// the traditional doubling by default; a caller fronting a facilitator known to
+    /// need a gentler (or more aggressive) ramp can flatten or steepen the curve
+    /// with [`Self::with_multiplier`] without touching `base_delay`/`max_delay`.
+    pub multiplier: f64,
+}
+
+impl RetryPolicy {
+    /// Create a retry policy with sensible defaults (200ms base, 5s cap, 3 attempts)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the base delay before the first retry
+    pub fn with_base_delay(mut self, base_delay: Duration) -> Self {
+        self.base_delay = base_delay;
+        self
+    }
+
+    /// Set the maximum delay between retries
+    pub fn with_max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Set the maximum number of attempts
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// Turn full jitter on or off
+    pub fn with_jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Set the per-attempt growth factor (default `2.0`, i.e. doubling)
+    pub fn with_multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier;
+        self
+    }
+
+    /// Compute the delay before retrying after the given zero-indexed attempt.
+    ///
+    /// The unjittered backoff is `min(max_delay, base * multiplier^attempt)`. When
+    /// [`Self::jitter`] is set, the actual delay is a random duration in
+    /// `[0, backoff]` (full jitter) rather than the backoff itself.
+    pub fn delay_for_attempt(&self, attempt: u32) -> Duration {
+        let exp = self.base_delay.as_millis() as f64 * self.multiplier.powi(attempt.min(32) as i32);
+        let capped = exp.min(self.max_delay.as_millis() as f64).max(0.0) as u64;
+        if self.jitter {
+            Duration::from_millis(rand::thread_rng().gen_range(0..=capped))
+        } else {
+            Duration::from_millis(capped)
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            base_delay: Duration::from_millis(200),
+            max_delay: Duration::from_secs(5),
+            max_attempts: 3,
+            jitter: true,
+            multiplier: 2.0,
+        }
+    }
+}
+
+/// Whether an HTTP status code is worth retrying on its own, independent of any
+/// [`X402Error`] classification — used by callers (like [`crate::client::X402Client`])
+/// that get back a plain [`reqwest::Response`] rather than an `Err` for a non-2xx
+/// status. Request timeout (408), rate limiting (429), and upstream
+/// unavailability (500/502/503/504) are transient; anything else (including other
+/// 4xx client errors) is not.
+pub fn is_retryable_status(status: u16) -> bool {
+    matches!(status, 408 | 429 | 500 | 502 | 503 | 504)
+}
+
+/// Read a `Retry-After` response header as a [`Duration`], if present and valid
+pub fn retry_after_header(response: &reqwest::Response) -> Option<Duration> {
+    response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(parse_retry_after)
+}
+
+/// Parse a `Retry-After` header value, in either form the spec allows: a plain
+/// delta-seconds integer, or an HTTP-date (`Sun, 06 Nov 1994 08:49:37 GMT`) giving
+/// the absolute instant to retry at. A date already in the past yields a zero delay
+/// rather than `None`, since the facilitator is telling us it's fine to retry now.
+pub fn parse_retry_after(value: &str) -> Option<Duration> {
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let target = chrono::DateTime::parse_from_rfc2822(value)
+        .ok()
+        .map(|dt| dt.with_timezone(&chrono::Utc))
+        .or_else(|| {
+            chrono::NaiveDateTime::parse_from_str(value, "%a, %d %b %Y %H:%M:%S GMT")
+                .ok()
+                .map(|naive| chrono::DateTime::<chrono::Utc>::from_utc(naive, chrono::Utc))
+        })?;
+
+    let delta = target.signed_duration_since(chrono::Utc::now());
+    Some(delta.to_std().unwrap_or(Duration::ZERO))
+}
+
+/// Run `operation` under `policy`, retrying transient [`X402Error`]s with
+/// exponential backoff. Honors a `Retry-After` delay reported by the error over the
+/// policy's own computed delay. Returns the last error once `max_attempts` is
+/// exhausted, with the accumulated attempt count attached as error details.
+pub async fn retry_with_backoff<T, F, Fut>(policy: &RetryPolicy, mut operation: F) -> crate::Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = crate::Result<T>>,
+{
+    let mut attempt = 0;
+    loop {
+        match operation().await {
+            Ok(value) => return Ok(value),
+            Err(error) if attempt + 1 < policy.max_attempts && error.is_retryable() => {
+                let delay = error
+                    .retry_after()
+                    .unwrap_or_else(|| policy.delay_for_attempt(attempt));
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            Err(error) => return Err(with_attempt_count(error, attempt + 1)),
+        }
+    }
+}
+
+/// Attach the number of attempts made to the final error's details, where possible
+fn with_attempt_count(error: X402Error, attempts: u32) -> X402Error {
+    match error {
+        X402Error::Facilitator {
+            code,
+            reason,
+            raw,
+            retry_after,
+        } => {
+            let mut raw = raw;
+            if let Some(obj) = raw.as_object_mut() {
+                obj.insert("attempts".to_string(), serde_json::json!(attempts));
+            }
+            X402Error::Facilitator {
+                code,
+                reason,
+                raw,
+                retry_after,
+            }
+        }
+        other => other,
+    }
+}
+
+/// When to give up retrying, as a termination condition independent of [`RetryPolicy`]'s
+/// backoff shape
+///
+/// Modeled on rust-lightning's retry enum: a caller picks whichever bound matches their
+/// situation — a fixed number of attempts, or a deadline past which a facilitator blip
+/// isn't worth waiting out any longer — and [`retry_with_deadline`] still uses
+/// [`RetryPolicy`] for the delay between attempts.
+#[derive(Debug, Clone, Copy)]
+pub enum Retry {
+    /// Retry up to `n` additional times after the initial attempt
+    Attempts(usize),
+    /// Keep retrying until `d` has elapsed since the first attempt, measured on the
+    /// monotonic clock so NTP skew can't cut retries short or extend them
+    Timeout(Duration),
+}
+
+/// Tracks how many attempts have been made and when the first one started
+#[derive(Debug, Clone, Copy)]
+struct Attempts {
+    count: usize,
+    first_attempted_at: std::time::Instant,
+}
+
+impl Attempts {
+    fn first() -> Self {
+        Self {
+            count: 1,
+            first_attempted_at: std::time::Instant::now(),
+        }
+    }
+
+    fn increment(self) -> Self {
+        Self {
+            count: self.count + 1,
+            ..self
+        }
+    }
+}
+
+impl Retry {
+    /// Whether another attempt is allowed given how many have already been made
+    fn is_retryable_now(&self, attempts: &Attempts) -> bool {
+        match self {
+            Self::Attempts(n) => attempts.count <= *n,
+            Self::Timeout(d) => attempts.first_attempted_at.elapsed() <= *d,
+        }
+    }
+}
+
+/// Run `operation` under `retry`'s termination condition, backing off between
+/// attempts per `backoff`'s exponential-with-jitter schedule
+///
+/// Like [`retry_with_backoff`], never retries a deterministic rejection (bad
+/// signature, insufficient funds, ...) — only connection errors, timeouts, and a
+/// facilitator's `RateLimited`/`UpstreamUnavailable` response.
+pub async fn retry_with_deadline<T, F, Fut>(
+    retry: &Retry,
+    backoff: &RetryPolicy,
+    mut operation: F,
+) -> crate::Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = crate::Result<T>>,
+{
+    let mut attempts = Attempts::first();
+    loop {
+        match operation().await {
+            Ok(value) => return Ok(value),
+            Err(error) if error.is_retryable() && retry.is_retryable_now(&attempts) => {
+                let delay = error
+                    .retry_after()
+                    .unwrap_or_else(|| backoff.delay_for_attempt(attempts.count as u32 - 1));
+                tokio::time::sleep(delay).await;
+                attempts = attempts.increment();
+            }
+            Err(error) => return Err(with_attempt_count(error, attempts.count as u32)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[test]
+    fn test_is_retryable_status_classifies_rate_limit_and_upstream_errors() {
+        assert!(is_retryable_status(408));
+        assert!(is_retryable_status(429));
+        assert!(is_retryable_status(500));
+        assert!(is_retryable_status(502));
+        assert!(is_retryable_status(503));
+        assert!(is_retryable_status(504));
+        assert!(!is_retryable_status(400));
+        assert!(!is_retryable_status(401));
+        assert!(!is_retryable_status(404));
+        assert!(!is_retryable_status(200));
+    }
+
+    #[test]
+    fn test_parse_retry_after_reads_delta_seconds() {
+        assert_eq!(parse_retry_after("120"), Some(Duration::from_secs(120)));
+    }
+
+    #[test]
+    fn test_parse_retry_after_reads_an_http_date_in_the_future() {
+        let target = chrono::Utc::now() + chrono::Duration::seconds(60);
+        let header = target.format("%a, %d %b %Y %H:%M:%S GMT").to_string();
+
+        let delay = parse_retry_after(&header).expect("a future HTTP-date should parse");
+        // Allow a little slack for the time elapsed between computing `target` and now.
+        assert!(delay <= Duration::from_secs(61) && delay >= Duration::from_secs(55));
+    }
+
+    #[test]
+    fn test_parse_retry_after_clamps_a_past_http_date_to_zero() {
+        let target = chrono::Utc::now() - chrono::Duration::seconds(60);
+        let header = target.format("%a, %d %b %Y %H:%M:%S GMT").to_string();
+
+        assert_eq!(parse_retry_after(&header), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn test_parse_retry_after_rejects_garbage() {
+        assert_eq!(parse_retry_after("not-a-retry-after-value"), None);
+    }
+
+    #[test]
+    fn test_delay_for_attempt_without_jitter_is_the_exact_capped_backoff() {
+        let policy = RetryPolicy::new()
+            .with_base_delay(Duration::from_millis(100))
+            .with_max_delay(Duration::from_secs(1))
+            .with_jitter(false);
+
+        assert_eq!(policy.delay_for_attempt(0), Duration::from_millis(100));
+        assert_eq!(policy.delay_for_attempt(1), Duration::from_millis(200));
+        assert_eq!(policy.delay_for_attempt(2), Duration::from_millis(400));
+        // 100ms * 2^4 = 1600ms, capped at the 1s max_delay
+        assert_eq!(policy.delay_for_attempt(4), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_delay_for_attempt_honors_a_custom_multiplier() {
+        let policy = RetryPolicy::new()
+            .with_base_delay(Duration::from_millis(100))
+            .with_max_delay(Duration::from_secs(10))
+            .with_multiplier(3.0)
+            .with_jitter(false);
+
+        assert_eq!(policy.delay_for_attempt(0), Duration::from_millis(100));
+        assert_eq!(policy.delay_for_attempt(1), Duration::from_millis(300));
+        assert_eq!(policy.delay_for_attempt(2), Duration::from_millis(900));
+    }
+
+    #[test]
+    fn test_delay_for_attempt_with_jitter_stays_within_the_backoff_bound() {
+        let policy = RetryPolicy::new()
+            .with_base_delay(Duration::from_millis(100))
+            .with_max_delay(Duration::from_secs(1))
+            .with_jitter(true);
+
+        for attempt in 0..5 {
+            let delay = policy.delay_for_attempt(attempt);
+            assert!(delay <= Duration::from_millis(100 * (1u64 << attempt)).min(Duration::from_secs(1)));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retry_succeeds_after_transient_failures() {
+        let policy = RetryPolicy::new()
+            .with_base_delay(Duration::from_millis(1))
+            .with_max_attempts(5);
+        let calls = AtomicU32::new(0);
+
+        let result = retry_with_backoff(&policy, || {
+            let attempt = calls.fetch_add(1, Ordering::SeqCst);
+            async move {
+                if attempt < 2 {
+                    Err(X402Error::Timeout)
+                } else {
+                    Ok(42)
+                }
+            }
+        })
+        .await;
+
+        assert_eq!(result.unwrap(), 42);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_retry_gives_up_on_permanent_failure() {
+        let policy = RetryPolicy::new().with_base_delay(Duration::from_millis(1));
+        let calls = AtomicU32::new(0);
+
+        let result: crate::Result<()> = retry_with_backoff(&policy, || {
+            calls.fetch_add(1, Ordering::SeqCst);
+            async move { Err(X402Error::InsufficientFunds) }
+        })
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_retry_exhausts_max_attempts() {
+        let policy = RetryPolicy::new()
+            .with_base_delay(Duration::from_millis(1))
+            .with_max_attempts(3);
+        let calls = AtomicU32::new(0);
+
+        let result: crate::Result<()> = retry_with_backoff(&policy, || {
+            calls.fetch_add(1, Ordering::SeqCst);
+            async move { Err(X402Error::Timeout) }
+        })
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_retry_with_deadline_attempts_succeeds_after_transient_failures() {
+        let backoff = RetryPolicy::new().with_base_delay(Duration::from_millis(1));
+        let calls = AtomicU32::new(0);
+
+        let result = retry_with_deadline(&Retry::Attempts(3), &backoff, || {
+            let attempt = calls.fetch_add(1, Ordering::SeqCst);
+            async move {
+                if attempt < 2 {
+                    Err(X402Error::Timeout)
+                } else {
+                    Ok(42)
+                }
+            }
+        })
+        .await;
+
+        assert_eq!(result.unwrap(), 42);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_retry_with_deadline_never_retries_deterministic_rejection() {
+        let backoff = RetryPolicy::new().with_base_delay(Duration::from_millis(1));
+        let calls = AtomicU32::new(0);
+
+        let result: crate::Result<()> = retry_with_deadline(&Retry::Attempts(5), &backoff, || {
+            calls.fetch_add(1, Ordering::SeqCst);
+            async move { Err(X402Error::InsufficientFunds) }
+        })
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_retry_with_deadline_timeout_gives_up_past_deadline() {
+        let backoff = RetryPolicy::new().with_base_delay(Duration::from_millis(5));
+        let calls = AtomicU32::new(0);
+
+        let result: crate::Result<()> =
+            retry_with_deadline(&Retry::Timeout(Duration::from_millis(1)), &backoff, || {
+                calls.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    std::thread::sleep(Duration::from_millis(5));
+                    Err(X402Error::Timeout)
+                }
+            })
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,272 @@
+//! A chain-agnostic `PaymentScheme` trait and `(scheme, network)` registry
+//!
+//! [`crate::scheme_registry`] already runs schemes in-process behind
+//! [`crate::scheme_registry::SchemeHandler`], but that trait is pinned to the EVM
+//! wire shapes ([`crate::types::PaymentPayload`], `ExactEvmPayload`) in every method
+//! signature. A non-EVM scheme like `exact-svm` has no `PaymentPayload` to hand it —
+//! its wire shape is [`crate::types::SolanaPaymentPayload`] instead — so
+//! [`PaymentScheme`] here trades static payload typing for `serde_json::Value`,
+//! letting one registry dispatch both chain families. `challenge` carries whatever
+//! ephemeral, scheme-specific signing input a static [`PaymentRequirements`] can't
+//! (e.g. Solana's `recent_blockhash`); EVM schemes, which derive everything they need
+//! from `requirement` and [`crate::wallet::Signer::chain_id`], simply ignore it.
+//!
+//! Registering both an [`EvmExactScheme`] and a [`SolanaExactScheme`] under this
+//! registry is what lets a call site like
+//! [`crate::actix_web::handle_payment_verification`] stay unchanged as new chains are
+//! added: it dispatches on `(payload.scheme, payload.network)` rather than branching
+//! on the chain family itself.
+
+use crate::facilitator::BoxFuture;
+use crate::types::PaymentRequirements;
+use crate::{Result, X402Error};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock, RwLock};
+
+/// A payment scheme that can sign a payload for `requirement` and verify one it's
+/// handed back, independent of which chain family it signs for
+pub trait PaymentScheme: Send + Sync {
+    /// Sign a new payment payload for `requirement`, as JSON ready to embed in an
+    /// `X-PAYMENT` header; `challenge` supplies any ephemeral input `requirement`
+    /// alone doesn't carry (e.g. Solana's `recent_blockhash`)
+    fn sign<'a>(
+        &'a self,
+        challenge: &'a Value,
+        requirement: &'a PaymentRequirements,
+    ) -> BoxFuture<'a, Result<Value>>;
+
+    /// Verify that `payload`'s signature matches its own authorization fields
+    fn verify<'a>(&'a self, payload: &'a Value) -> BoxFuture<'a, Result<bool>>;
+}
+
+/// [`PaymentScheme`] for [`crate::types::schemes::EXACT`] over EIP-712/EIP-3009,
+/// wrapping a [`crate::wallet::Signer`] the same way [`crate::wallet::Wallet`] itself
+/// does for [`crate::wallet::create_signed_payment_payload`]
+pub struct EvmExactScheme {
+    signer: Arc<dyn crate::wallet::Signer>,
+}
+
+impl EvmExactScheme {
+    /// Sign on behalf of `signer`
+    pub fn new(signer: Arc<dyn crate::wallet::Signer>) -> Self {
+        Self { signer }
+    }
+}
+
+impl PaymentScheme for EvmExactScheme {
+    fn sign<'a>(
+        &'a self,
+        _challenge: &'a Value,
+        requirement: &'a PaymentRequirements,
+    ) -> BoxFuture<'a, Result<Value>> {
+        Box::pin(async move {
+            let payload =
+                crate::wallet::create_signed_payment_payload(self.signer.as_ref(), requirement)
+                    .await?;
+            serde_json::to_value(payload).map_err(|e| {
+                X402Error::invalid_payment_payload(format!(
+                    "Failed to serialize EVM payment payload: {}",
+                    e
+                ))
+            })
+        })
+    }
+
+    fn verify<'a>(&'a self, payload: &'a Value) -> BoxFuture<'a, Result<bool>> {
+        Box::pin(async move {
+            let payment_payload: crate::types::PaymentPayload = serde_json::from_value(payload.clone())
+                .map_err(|e| {
+                    X402Error::invalid_payment_payload(format!("Invalid EVM payment payload: {}", e))
+                })?;
+            let exact_evm = payment_payload.exact_evm()?;
+            crate::crypto::signature::verify_payment_payload(
+                exact_evm,
+                &exact_evm.authorization.from,
+                &payment_payload.network,
+            )
+        })
+    }
+}
+
+/// [`PaymentScheme`] for [`crate::types::schemes::EXACT_SVM`] over native Solana
+/// ed25519 signatures, wrapping a [`crate::wallet::SolanaWallet`]
+pub struct SolanaExactScheme {
+    wallet: crate::wallet::SolanaWallet,
+}
+
+impl SolanaExactScheme {
+    /// Sign on behalf of `wallet`
+    pub fn new(wallet: crate::wallet::SolanaWallet) -> Self {
+        Self { wallet }
+    }
+}
+
+impl PaymentScheme for SolanaExactScheme {
+    fn sign<'a>(
+        &'a self,
+        challenge: &'a Value,
+        requirement: &'a PaymentRequirements,
+    ) -> BoxFuture<'a, Result<Value>> {
+        Box::pin(async move {
+            let recent_blockhash = challenge
+                .get("recent_blockhash")
+                .and_then(Value::as_str)
+                .ok_or_else(|| {
+                    X402Error::invalid_payment_payload("challenge is missing recent_blockhash")
+                })?;
+
+            let payload = self.wallet.sign_transfer(
+                &requirement.pay_to,
+                &requirement.asset,
+                &requirement.max_amount_required,
+                recent_blockhash,
+            )?;
+
+            serde_json::to_value(payload).map_err(|e| {
+                X402Error::invalid_payment_payload(format!(
+                    "Failed to serialize Solana payment payload: {}",
+                    e
+                ))
+            })
+        })
+    }
+
+    fn verify<'a>(&'a self, payload: &'a Value) -> BoxFuture<'a, Result<bool>> {
+        Box::pin(async move {
+            let solana_payload: crate::types::SolanaPaymentPayload =
+                serde_json::from_value(payload.clone()).map_err(|e| {
+                    X402Error::invalid_payment_payload(format!(
+                        "Invalid Solana payment payload: {}",
+                        e
+                    ))
+                })?;
+            crate::wallet::SolanaWallet::verify_transfer(&solana_payload)
+        })
+    }
+}
+
+type Registry = RwLock<HashMap<(String, String), Arc<dyn PaymentScheme>>>;
+
+fn registry() -> &'static Registry {
+    static REGISTRY: OnceLock<Registry> = OnceLock::new();
+    REGISTRY.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+/// Register `implementation` for `(scheme, network)`, replacing anything previously
+/// registered for the same pair
+pub fn register_payment_scheme(
+    scheme: impl Into<String>,
+    network: impl Into<String>,
+    implementation: Arc<dyn PaymentScheme>,
+) {
+    registry()
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .insert((scheme.into(), network.into()), implementation);
+}
+
+/// Look up the implementation registered for `(scheme, network)`, if any
+pub fn resolve_payment_scheme(scheme: &str, network: &str) -> Option<Arc<dyn PaymentScheme>> {
+    registry()
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .get(&(scheme.to_string(), network.to_string()))
+        .cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::PaymentRequirements;
+
+    fn evm_requirements() -> PaymentRequirements {
+        PaymentRequirements::new(
+            crate::types::schemes::EXACT,
+            "base-sepolia",
+            "1000000",
+            "",
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            "https://example.com/test",
+            "Test resource",
+        )
+    }
+
+    fn solana_requirements() -> PaymentRequirements {
+        PaymentRequirements::new(
+            crate::types::schemes::EXACT_SVM,
+            crate::types::networks::SOLANA_DEVNET,
+            "1000000",
+            "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v",
+            "11111111111111111111111111111111",
+            "https://example.com/test",
+            "Test resource",
+        )
+    }
+
+    #[tokio::test]
+    async fn test_evm_exact_scheme_signs_and_verifies_its_own_payload() {
+        let wallet = crate::wallet::Wallet::new(
+            "0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef".to_string(),
+            "base-sepolia".to_string(),
+        );
+        let scheme = EvmExactScheme::new(Arc::new(wallet));
+        let requirements = evm_requirements();
+
+        let payload = scheme
+            .sign(&Value::Null, &requirements)
+            .await
+            .expect("sign should succeed");
+        assert!(scheme.verify(&payload).await.expect("verify should run"));
+    }
+
+    #[tokio::test]
+    async fn test_solana_exact_scheme_signs_and_verifies_its_own_payload() {
+        let wallet = crate::wallet::SolanaWalletFactory::from_private_key(
+            "0x1111111111111111111111111111111111111111111111111111111111111111",
+            crate::types::networks::SOLANA_DEVNET,
+        )
+        .expect("wallet should build");
+        let scheme = SolanaExactScheme::new(wallet);
+        let requirements = solana_requirements();
+        let challenge = serde_json::json!({ "recent_blockhash": "11111111111111111111111111111111" });
+
+        let payload = scheme
+            .sign(&challenge, &requirements)
+            .await
+            .expect("sign should succeed");
+        assert!(scheme.verify(&payload).await.expect("verify should run"));
+    }
+
+    #[tokio::test]
+    async fn test_solana_exact_scheme_requires_recent_blockhash_in_challenge() {
+        let wallet = crate::wallet::SolanaWalletFactory::from_private_key(
+            "0x1111111111111111111111111111111111111111111111111111111111111111",
+            crate::types::networks::SOLANA_DEVNET,
+        )
+        .expect("wallet should build");
+        let scheme = SolanaExactScheme::new(wallet);
+
+        let err = scheme
+            .sign(&Value::Null, &solana_requirements())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, X402Error::InvalidPaymentPayload { .. }));
+    }
+
+    #[test]
+    fn test_register_and_resolve_payment_scheme() {
+        let wallet = crate::wallet::Wallet::new(
+            "0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef".to_string(),
+            "base-sepolia".to_string(),
+        );
+        register_payment_scheme(
+            crate::types::schemes::EXACT,
+            "scheme-test-network",
+            Arc::new(EvmExactScheme::new(Arc::new(wallet))),
+        );
+
+        assert!(resolve_payment_scheme(crate::types::schemes::EXACT, "scheme-test-network").is_some());
+        assert!(resolve_payment_scheme("no-such-scheme", "no-such-network").is_none());
+    }
+}
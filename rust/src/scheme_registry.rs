@@ -0,0 +1,407 @@
+//! Runtime registry for pluggable per-network scheme handlers
+//!
+//! Today every scheme x402 understands (`exact` for EVM, `lightning-bolt12`) is wired
+//! into [`crate::middleware`]/[`crate::lightning`] directly, so adding support for a
+//! new network or scheme means patching this crate. This module gives a scheme
+//! implementation a [`SchemeHandler`] trait and a process-wide registry to declare
+//! itself against, instead of requiring every `(network, scheme)` combination this
+//! binary supports to be enumerated by hand somewhere central.
+//!
+//! True link-time auto-registration — so that merely depending on a crate providing a
+//! scheme is enough, with no explicit call anywhere — needs something like the
+//! `inventory` or `ctor` crate to hook a constructor in before `main` runs. Neither is
+//! a dependency of this workspace today, so [`submit_scheme!`] registers eagerly the
+//! first time it's reached at runtime rather than before `main`; callers still need
+//! one line (e.g. at the top of `main`) invoking it for each scheme crate they link.
+//! Swapping in `inventory::submit!` later, once that dependency is added, is a
+//! drop-in change to this module alone — nothing downstream needs to know.
+
+use crate::facilitator::BoxFuture;
+use crate::types::{PaymentPayload, PaymentRequirements, SettleResponse, VerifyResponse};
+use crate::Result;
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock, RwLock};
+
+/// A scheme implementation for a specific network
+///
+/// Analogous to [`crate::facilitator::FacilitatorClient`], but for schemes verified
+/// and settled in-process rather than by delegating to an HTTP facilitator.
+/// `verify`/`settle` return a [`BoxFuture`] rather than a plain `Result` — unlike the
+/// `lightning-bolt12` handler, which never leaves the CPU, a scheme like `exact` may
+/// need to await a signature check against network state or hand settlement to a
+/// facilitator, so the trait has to assume I/O even though most handlers won't need it.
+pub trait SchemeHandler: Send + Sync {
+    /// Network this handler serves, e.g. `"base-sepolia"`
+    fn network(&self) -> &str;
+    /// Scheme this handler serves, e.g. `"exact"`
+    fn scheme(&self) -> &str;
+    /// Build a signed payment payload a client can submit for `payment_requirements`
+    ///
+    /// Unlike `verify`/`settle`, this runs client-side and is synchronous: every
+    /// signing primitive this crate has (e.g. [`crate::wallet::Wallet`]) is local-CPU
+    /// only, with no network round trip involved.
+    fn build_payload(
+        &self,
+        payment_requirements: &PaymentRequirements,
+        payer: &str,
+    ) -> Result<PaymentPayload>;
+    /// Verify a payment payload against the given requirements
+    fn verify<'a>(
+        &'a self,
+        payment_payload: &'a PaymentPayload,
+        payment_requirements: &'a PaymentRequirements,
+    ) -> BoxFuture<'a, Result<VerifyResponse>>;
+    /// Settle a previously verified payment
+    fn settle<'a>(
+        &'a self,
+        payment_payload: &'a PaymentPayload,
+        payment_requirements: &'a PaymentRequirements,
+    ) -> BoxFuture<'a, Result<SettleResponse>>;
+}
+
+/// In-process [`SchemeHandler`] for the `exact` EVM scheme
+///
+/// Verification re-derives the EIP-712 message hash and checks the payer's signature
+/// locally via [`crate::crypto::signature::verify_payment_payload`], so a registered
+/// instance saves the facilitator round trip [`crate::middleware::PaymentMiddleware`]
+/// would otherwise make for every `verify_with_requirements` call. Settlement still
+/// means broadcasting an EIP-3009 `transferWithAuthorization` call, which this crate
+/// has no local chain client for (see [`crate::blockchain`], which only watches chain
+/// state, never submits to it) — so [`Self::settle`] forwards to a wrapped
+/// [`crate::facilitator::FacilitatorClient`] instead of pretending to broadcast
+/// in-process.
+pub struct ExactEvmSchemeHandler {
+    network: String,
+    signer: Option<crate::wallet::Wallet>,
+    settle_via: Option<crate::facilitator::FacilitatorClient>,
+}
+
+impl ExactEvmSchemeHandler {
+    /// Create a handler for `network` with neither a signer nor a settlement
+    /// facilitator configured; [`Self::build_payload`] and [`Self::settle`] will
+    /// error until [`Self::with_signer`]/[`Self::with_settlement_facilitator`] are
+    /// called, but [`Self::verify`] works immediately since it needs neither
+    pub fn new(network: impl Into<String>) -> Self {
+        Self {
+            network: network.into(),
+            signer: None,
+            settle_via: None,
+        }
+    }
+
+    /// Let this handler build signed payloads on behalf of `signer`'s address
+    pub fn with_signer(mut self, signer: crate::wallet::Wallet) -> Self {
+        self.signer = Some(signer);
+        self
+    }
+
+    /// Forward [`Self::settle`] to `facilitator` rather than erroring
+    pub fn with_settlement_facilitator(
+        mut self,
+        facilitator: crate::facilitator::FacilitatorClient,
+    ) -> Self {
+        self.settle_via = Some(facilitator);
+        self
+    }
+}
+
+impl SchemeHandler for ExactEvmSchemeHandler {
+    fn network(&self) -> &str {
+        &self.network
+    }
+
+    fn scheme(&self) -> &str {
+        crate::types::schemes::EXACT
+    }
+
+    fn build_payload(
+        &self,
+        payment_requirements: &PaymentRequirements,
+        payer: &str,
+    ) -> Result<PaymentPayload> {
+        let signer = self.signer.as_ref().ok_or_else(|| {
+            crate::X402Error::config(
+                "ExactEvmSchemeHandler has no signer; call with_signer() before build_payload",
+            )
+        })?;
+        signer.create_signed_payment_payload(payment_requirements, payer)
+    }
+
+    fn verify<'a>(
+        &'a self,
+        payment_payload: &'a PaymentPayload,
+        _payment_requirements: &'a PaymentRequirements,
+    ) -> BoxFuture<'a, Result<VerifyResponse>> {
+        Box::pin(async move {
+            let exact_evm = payment_payload.exact_evm()?;
+            let authorization = &exact_evm.authorization;
+            exact_evm.validate()?;
+            authorization.check_validity_window()?;
+
+            let is_valid = crate::crypto::signature::verify_payment_payload(
+                exact_evm,
+                &authorization.from,
+                &self.network,
+            )?;
+
+            Ok(VerifyResponse {
+                is_valid,
+                invalid_reason: if is_valid {
+                    None
+                } else {
+                    Some("Signature does not match the authorization".to_string())
+                },
+                payer: is_valid.then(|| authorization.from.clone()),
+            })
+        })
+    }
+
+    fn settle<'a>(
+        &'a self,
+        payment_payload: &'a PaymentPayload,
+        payment_requirements: &'a PaymentRequirements,
+    ) -> BoxFuture<'a, Result<SettleResponse>> {
+        Box::pin(async move {
+            let facilitator = self.settle_via.as_ref().ok_or_else(|| {
+                crate::X402Error::config(
+                    "ExactEvmSchemeHandler has no settlement facilitator; call \
+                     with_settlement_facilitator() before settle",
+                )
+            })?;
+            facilitator.settle(payment_payload, payment_requirements).await
+        })
+    }
+}
+
+type Registry = RwLock<HashMap<(String, String), Arc<dyn SchemeHandler>>>;
+
+fn registry() -> &'static Registry {
+    static REGISTRY: OnceLock<Registry> = OnceLock::new();
+    REGISTRY.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+/// Register `handler` for its declared `(network, scheme)` pair, replacing any
+/// handler previously registered for the same pair
+pub fn register_scheme_handler(handler: Arc<dyn SchemeHandler>) {
+    let key = (handler.network().to_string(), handler.scheme().to_string());
+    registry()
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .insert(key, handler);
+}
+
+/// Look up the handler registered for `(network, scheme)`, if any
+pub fn resolve_scheme_handler(network: &str, scheme: &str) -> Option<Arc<dyn SchemeHandler>> {
+    registry()
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .get(&(network.to_string(), scheme.to_string()))
+        .cloned()
+}
+
+/// Declare a [`SchemeHandler`] so it registers itself without the caller constructing
+/// an `Arc` and calling [`register_scheme_handler`] by hand
+///
+/// ```ignore
+/// submit_scheme!(MySchemeHandler::new());
+/// ```
+#[macro_export]
+macro_rules! submit_scheme {
+    ($handler:expr) => {
+        $crate::scheme_registry::register_scheme_handler(std::sync::Arc::new($handler));
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct EchoHandler;
+
+    impl SchemeHandler for EchoHandler {
+        fn network(&self) -> &str {
+            "test-network"
+        }
+
+        fn scheme(&self) -> &str {
+            "test-scheme"
+        }
+
+        fn build_payload(
+            &self,
+            _payment_requirements: &PaymentRequirements,
+            _payer: &str,
+        ) -> Result<PaymentPayload> {
+            Err(crate::X402Error::config("EchoHandler cannot build payloads"))
+        }
+
+        fn verify<'a>(
+            &'a self,
+            _payment_payload: &'a PaymentPayload,
+            _payment_requirements: &'a PaymentRequirements,
+        ) -> BoxFuture<'a, Result<VerifyResponse>> {
+            Box::pin(async move {
+                Ok(VerifyResponse {
+                    is_valid: true,
+                    invalid_reason: None,
+                    payer: None,
+                })
+            })
+        }
+
+        fn settle<'a>(
+            &'a self,
+            _payment_payload: &'a PaymentPayload,
+            _payment_requirements: &'a PaymentRequirements,
+        ) -> BoxFuture<'a, Result<SettleResponse>> {
+            Box::pin(async move {
+                Ok(SettleResponse {
+                    success: true,
+                    error_reason: None,
+                    transaction: "test-tx".to_string(),
+                    network: "test-network".to_string(),
+                    payer: None,
+                })
+            })
+        }
+    }
+
+    #[test]
+    fn test_submit_scheme_registers_handler() {
+        submit_scheme!(EchoHandler);
+
+        let handler =
+            resolve_scheme_handler("test-network", "test-scheme").expect("handler registered");
+        assert_eq!(handler.network(), "test-network");
+        assert_eq!(handler.scheme(), "test-scheme");
+    }
+
+    #[test]
+    fn test_resolve_missing_handler_returns_none() {
+        assert!(resolve_scheme_handler("no-such-network", "no-such-scheme").is_none());
+    }
+
+    fn test_requirements() -> PaymentRequirements {
+        PaymentRequirements::new(
+            crate::types::schemes::EXACT,
+            "base-sepolia",
+            "1000000",
+            "",
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            "https://example.com/test",
+            "Test resource",
+        )
+    }
+
+    #[tokio::test]
+    async fn test_exact_evm_handler_builds_and_verifies_its_own_payload() {
+        let wallet = crate::wallet::Wallet::new(
+            "0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef".to_string(),
+            "base-sepolia".to_string(),
+        );
+        let from_address = "0x857b06519E91e3A54538791bDbb0E22373e36b66";
+        let handler = ExactEvmSchemeHandler::new("base-sepolia").with_signer(wallet);
+        let requirements = test_requirements();
+
+        let payload = handler
+            .build_payload(&requirements, from_address)
+            .expect("payload should build");
+        let response = handler
+            .verify(&payload, &requirements)
+            .await
+            .expect("verify should not error");
+
+        assert!(response.is_valid);
+        assert_eq!(response.payer.as_deref(), Some(from_address));
+    }
+
+    #[tokio::test]
+    async fn test_exact_evm_handler_rejects_tampered_signature() {
+        let wallet = crate::wallet::Wallet::new(
+            "0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef".to_string(),
+            "base-sepolia".to_string(),
+        );
+        let from_address = "0x857b06519E91e3A54538791bDbb0E22373e36b66";
+        let handler = ExactEvmSchemeHandler::new("base-sepolia").with_signer(wallet);
+        let requirements = test_requirements();
+
+        let mut payload = handler
+            .build_payload(&requirements, from_address)
+            .expect("payload should build");
+        payload.exact_evm_mut().unwrap().signature = "0x".to_string() + &"11".repeat(65);
+
+        let response = handler
+            .verify(&payload, &requirements)
+            .await
+            .expect("verify should not error on a merely-wrong signature");
+        assert!(!response.is_valid);
+    }
+
+    #[tokio::test]
+    async fn test_exact_evm_handler_build_payload_requires_signer() {
+        let handler = ExactEvmSchemeHandler::new("base-sepolia");
+        let error = handler
+            .build_payload(&test_requirements(), "0x857b06519E91e3A54538791bDbb0E22373e36b66")
+            .unwrap_err();
+        assert!(matches!(error, crate::X402Error::Config { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_exact_evm_handler_settle_requires_facilitator() {
+        let handler = ExactEvmSchemeHandler::new("base-sepolia");
+        let wallet = crate::wallet::Wallet::new(
+            "0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef".to_string(),
+            "base-sepolia".to_string(),
+        );
+        let payload = wallet
+            .create_signed_payment_payload(
+                &test_requirements(),
+                "0x857b06519E91e3A54538791bDbb0E22373e36b66",
+            )
+            .unwrap();
+
+        let error = handler
+            .settle(&payload, &test_requirements())
+            .await
+            .unwrap_err();
+        assert!(matches!(error, crate::X402Error::Config { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_exact_evm_handler_settle_delegates_to_wrapped_facilitator() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/settle")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"success":true,"errorReason":null,"transaction":"0xabc","network":"base-sepolia","payer":null}"#,
+            )
+            .create();
+
+        let facilitator = crate::facilitator::FacilitatorClient::new(
+            crate::types::FacilitatorConfig::new(server.url()),
+        )
+        .unwrap();
+        let handler =
+            ExactEvmSchemeHandler::new("base-sepolia").with_settlement_facilitator(facilitator);
+
+        let wallet = crate::wallet::Wallet::new(
+            "0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef".to_string(),
+            "base-sepolia".to_string(),
+        );
+        let payload = wallet
+            .create_signed_payment_payload(
+                &test_requirements(),
+                "0x857b06519E91e3A54538791bDbb0E22373e36b66",
+            )
+            .unwrap();
+
+        let response = handler
+            .settle(&payload, &test_requirements())
+            .await
+            .expect("settle should delegate successfully");
+        assert!(response.success);
+        mock.assert();
+    }
+}
@@ -0,0 +1,204 @@
+//! WASM-sandboxed [`crate::scheme_registry::SchemeHandler`] plugins
+//!
+//! [`crate::scheme_registry`] lets a scheme register itself in-process, but that still
+//! means linking the implementation into this binary — fine for schemes this crate or a
+//! trusted dependency ships, not for a third-party or proprietary scheme an operator
+//! wants to run without auditing and recompiling against. This module defines the wire
+//! format a guest module would be compiled against (`PaymentRequirements`/
+//! `PaymentPayload`, MessagePack-encoded, crossing the host/guest boundary by value
+//! rather than by reference) and the host-side adapter shape that turns a loaded guest
+//! into a [`crate::scheme_registry::SchemeHandler`].
+//!
+//! Actually loading and calling a `.wasm` module needs a guest runtime — `wasmtime` is
+//! the obvious choice — and MessagePack encoding needs `rmp-serde`; neither is a
+//! dependency of this workspace today. [`WasmSchemeHandler`] below is written against
+//! those two crates' shapes (an engine/module/instance it would hold, exported
+//! functions it would call by name) but its methods return
+//! [`X402Error::config`] rather than pretend to execute a guest it has no runtime to
+//! run. Once `wasmtime` and `rmp-serde` are added to `Cargo.toml`, swapping the bodies
+//! of [`WasmSchemeHandler::build_payload`]/`verify`/`settle` for real calls into the
+//! instantiated module is the only change needed — the wire types and the
+//! [`crate::scheme_registry::SchemeHandler`] impl shape are already final.
+
+use crate::facilitator::BoxFuture;
+use crate::types::{PaymentPayload, PaymentRequirements, SettleResponse, VerifyResponse};
+use crate::{Result, X402Error};
+use serde::{Deserialize, Serialize};
+
+/// MessagePack-encoded request passed to a guest module's `x402_verify`/`x402_settle`/
+/// `x402_build_payload` export
+///
+/// Guest-side, this decodes to the same logical shape via `rmp_serde::from_slice`; the
+/// host encodes it with `rmp_serde::to_vec`. Passed across the boundary as a single
+/// byte buffer (a `(ptr, len)` pair into the guest's linear memory) rather than as
+/// individual fields, since `wasmtime` calls can only exchange primitive integers.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WasmSchemeRequest {
+    pub payment_requirements: PaymentRequirements,
+    pub payment_payload: Option<PaymentPayload>,
+    /// Set only for a `build_payload` call: the address the guest should authorize
+    /// payment from
+    pub payer: Option<String>,
+}
+
+/// MessagePack-encoded result returned from a guest export
+///
+/// A guest reports scheme-level rejection (bad signature, expired authorization) as
+/// `Err(reason)` here rather than trapping, so the host can distinguish "the payment is
+/// invalid" from "the guest crashed" the same way [`crate::types::VerifyResponse`]'s
+/// `invalid_reason` does for in-process handlers.
+pub type WasmSchemeResult<T> = std::result::Result<T, String>;
+
+/// Declares which network/scheme a compiled guest module serves and which functions it
+/// exports, so [`WasmSchemeHandler::load`] knows what to look for in the instantiated
+/// module without the host hardcoding export names per scheme
+#[derive(Debug, Clone)]
+pub struct WasmSchemeManifest {
+    pub network: String,
+    pub scheme: String,
+    /// Path to the compiled `.wasm` module on disk
+    pub module_path: std::path::PathBuf,
+    /// Export name for `build_payload`, e.g. `"x402_build_payload"`
+    pub build_payload_export: String,
+    /// Export name for `verify`, e.g. `"x402_verify"`
+    pub verify_export: String,
+    /// Export name for `settle`, e.g. `"x402_settle"`
+    pub settle_export: String,
+}
+
+impl WasmSchemeManifest {
+    pub fn new(
+        network: impl Into<String>,
+        scheme: impl Into<String>,
+        module_path: impl Into<std::path::PathBuf>,
+    ) -> Self {
+        Self {
+            network: network.into(),
+            scheme: scheme.into(),
+            module_path: module_path.into(),
+            build_payload_export: "x402_build_payload".to_string(),
+            verify_export: "x402_verify".to_string(),
+            settle_export: "x402_settle".to_string(),
+        }
+    }
+}
+
+/// A [`crate::scheme_registry::SchemeHandler`] backed by a sandboxed `.wasm` module
+///
+/// Holds a [`WasmSchemeManifest`] describing the guest to load; once `wasmtime` is a
+/// workspace dependency, [`Self::load`] would compile the module into an `Engine`,
+/// instantiate it per call (or keep a pooled instance — same tradeoff `wasmtime`'s own
+/// `Store` reuse guidance describes), and each trait method would encode its
+/// [`WasmSchemeRequest`] with `rmp_serde`, write it into the instance's memory, call the
+/// matching export, and decode the returned [`WasmSchemeResult`].
+pub struct WasmSchemeHandler {
+    manifest: WasmSchemeManifest,
+}
+
+impl WasmSchemeHandler {
+    /// Validate that `manifest.module_path` exists and record the manifest; does not
+    /// compile or instantiate the module, since this crate has no WASM runtime to do so
+    pub fn load(manifest: WasmSchemeManifest) -> Result<Self> {
+        if !manifest.module_path.exists() {
+            return Err(X402Error::config(format!(
+                "WASM module not found: {}",
+                manifest.module_path.display()
+            )));
+        }
+        Ok(Self { manifest })
+    }
+
+    fn unavailable(&self, export: &str) -> X402Error {
+        X402Error::config(format!(
+            "WasmSchemeHandler cannot call guest export \"{export}\" for {}/{}: this build has \
+             no wasmtime runtime compiled in; add `wasmtime` and `rmp-serde` to Cargo.toml and \
+             implement the call in WasmSchemeHandler to enable it",
+            self.manifest.network, self.manifest.scheme
+        ))
+    }
+}
+
+impl crate::scheme_registry::SchemeHandler for WasmSchemeHandler {
+    fn network(&self) -> &str {
+        &self.manifest.network
+    }
+
+    fn scheme(&self) -> &str {
+        &self.manifest.scheme
+    }
+
+    fn build_payload(
+        &self,
+        _payment_requirements: &PaymentRequirements,
+        _payer: &str,
+    ) -> Result<PaymentPayload> {
+        Err(self.unavailable(&self.manifest.build_payload_export))
+    }
+
+    fn verify<'a>(
+        &'a self,
+        _payment_payload: &'a PaymentPayload,
+        _payment_requirements: &'a PaymentRequirements,
+    ) -> BoxFuture<'a, Result<VerifyResponse>> {
+        Box::pin(async move { Err(self.unavailable(&self.manifest.verify_export)) })
+    }
+
+    fn settle<'a>(
+        &'a self,
+        _payment_payload: &'a PaymentPayload,
+        _payment_requirements: &'a PaymentRequirements,
+    ) -> BoxFuture<'a, Result<SettleResponse>> {
+        Box::pin(async move { Err(self.unavailable(&self.manifest.settle_export)) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_rejects_missing_module() {
+        let manifest = WasmSchemeManifest::new(
+            "base-sepolia",
+            "upto",
+            "/no/such/path/scheme.wasm",
+        );
+        let error = WasmSchemeHandler::load(manifest).unwrap_err();
+        assert!(matches!(error, X402Error::Config { .. }));
+    }
+
+    #[test]
+    fn test_load_accepts_existing_module() {
+        // Any existing file stands in for a compiled module — this crate has no
+        // wasmtime validation step to reject a non-WASM file with.
+        let module_path = std::env::current_exe().unwrap();
+        let manifest = WasmSchemeManifest::new("base-sepolia", "upto", module_path);
+        assert!(WasmSchemeHandler::load(manifest).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_verify_reports_missing_runtime_rather_than_panicking() {
+        let module_path = std::env::current_exe().unwrap();
+        let manifest = WasmSchemeManifest::new("base-sepolia", "upto", module_path);
+        let handler = WasmSchemeHandler::load(manifest).unwrap();
+
+        let requirements = PaymentRequirements::new(
+            "upto", "base-sepolia", "1000000", "", "0xpay", "https://example.com", "test",
+        );
+        let payload = PaymentPayload::new(
+            "upto",
+            "base-sepolia",
+            crate::types::ExactEvmPayload {
+                signature: "0xsig".to_string(),
+                authorization: crate::types::ExactEvmPayloadAuthorization::new(
+                    "0xfrom", "0xpay", "1000000", "0", "0", "0xnonce",
+                ),
+            },
+        );
+
+        let error = crate::scheme_registry::SchemeHandler::verify(&handler, &payload, &requirements)
+            .await
+            .unwrap_err();
+        assert!(matches!(error, X402Error::Config { .. }));
+    }
+}
@@ -0,0 +1,547 @@
+//! Asynchronous, retrying settlement pipeline
+//!
+//! `PaymentMiddleware` verifies a payment synchronously on the request path, but
+//! settlement doesn't need to block the response: once verification succeeds we can
+//! hand the `(PaymentPayload, PaymentRequirements)` pair off to a background worker and
+//! let it retry the facilitator `settle` call on our own schedule, rather than losing
+//! the payment the moment a single `settle` attempt hits a transient facilitator error.
+
+use crate::facilitator::FacilitatorClient;
+use crate::idempotency::PaymentId;
+use crate::payment_events::{PaymentEvent, PaymentEventContext, PaymentObserver};
+use crate::retry::RetryPolicy;
+use crate::types::{PaymentPayload, PaymentRequirements, SettleResponse};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::{mpsc, Mutex};
+use tracing::warn;
+
+/// A payment awaiting settlement
+struct SettlementJob {
+    payment_payload: PaymentPayload,
+    payment_requirements: PaymentRequirements,
+}
+
+/// Terminal or in-flight state of a job enqueued via [`SettlementQueue::enqueue`],
+/// queryable by [`SettlementQueue::status`]
+///
+/// Modeled on rust-lightning's `PendingOutboundPayment` (`Retryable`/`Fulfilled`/
+/// `Abandoned`): a job starts `Pending`, and ends either `Settled` once the facilitator
+/// confirms it or `Abandoned` once [`RetryPolicy::max_attempts`] is exhausted, instead
+/// of the old behavior of just logging the failure and forgetting the job entirely.
+#[derive(Debug, Clone)]
+pub enum SettlementJobStatus {
+    /// Still being retried against the facilitator
+    Pending,
+    /// The facilitator confirmed settlement
+    Settled(SettleResponse),
+    /// Every retry attempt failed; this job will not be retried again
+    Abandoned {
+        /// The final attempt's error, as it appears in logs and the [`PaymentObserver`]
+        /// event fired for it
+        reason: String,
+    },
+}
+
+/// Background queue that settles payments with retry instead of fire-and-forget
+///
+/// Cloning a [`SettlementQueue`] is cheap and shares the same background worker,
+/// channel, and status map, so it can be stored directly on
+/// [`crate::middleware::PaymentMiddleware`].
+#[derive(Clone)]
+pub struct SettlementQueue {
+    sender: mpsc::UnboundedSender<SettlementJob>,
+    status: Arc<Mutex<HashMap<PaymentId, SettlementJobStatus>>>,
+}
+
+impl SettlementQueue {
+    /// Spawn a background worker that settles enqueued payments against `facilitator`,
+    /// retrying each one with `retry_policy` before giving up and logging the failure
+    ///
+    /// Equivalent to [`Self::with_observer`] with no observer; see that constructor to
+    /// also be notified of terminal outcomes instead of only being able to poll
+    /// [`Self::status`].
+    pub fn new(facilitator: FacilitatorClient, retry_policy: RetryPolicy) -> Self {
+        Self::build(facilitator, retry_policy, None)
+    }
+
+    /// Like [`Self::new`], additionally firing [`PaymentEvent::Settled`] or
+    /// [`PaymentEvent::SettlementFailed`] on `observer` once a job reaches a terminal
+    /// state, the same hook [`crate::middleware::PaymentMiddleware::process_payment`]
+    /// uses for its inline settlement path
+    pub fn with_observer(
+        facilitator: FacilitatorClient,
+        retry_policy: RetryPolicy,
+        observer: Arc<dyn PaymentObserver>,
+    ) -> Self {
+        Self::build(facilitator, retry_policy, Some(observer))
+    }
+
+    fn build(
+        facilitator: FacilitatorClient,
+        retry_policy: RetryPolicy,
+        observer: Option<Arc<dyn PaymentObserver>>,
+    ) -> Self {
+        let (sender, mut receiver) = mpsc::unbounded_channel::<SettlementJob>();
+        let status: Arc<Mutex<HashMap<PaymentId, SettlementJobStatus>>> =
+            Arc::new(Mutex::new(HashMap::new()));
+        let worker_status = status.clone();
+
+        tokio::spawn(async move {
+            while let Some(job) = receiver.recv().await {
+                let exact_evm = match job.payment_payload.exact_evm() {
+                    Ok(exact_evm) => exact_evm,
+                    Err(error) => {
+                        warn!(
+                            "Settlement worker skipped a payment for resource {} it can't settle: {}",
+                            job.payment_requirements.resource, error
+                        );
+                        if let Some(observer) = &observer {
+                            observer
+                                .on_event(
+                                    PaymentEvent::SettlementFailed { error: error.to_string() },
+                                    &PaymentEventContext {
+                                        resource: job.payment_requirements.resource.clone(),
+                                        network: job.payment_requirements.network.clone(),
+                                        amount: job.payment_requirements.max_amount_required.clone(),
+                                        payment_id: None,
+                                    },
+                                )
+                                .await;
+                        }
+                        continue;
+                    }
+                };
+                let payment_id =
+                    PaymentId::from_authorization(&exact_evm.authorization, &job.payment_requirements);
+                worker_status
+                    .lock()
+                    .await
+                    .insert(payment_id.clone(), SettlementJobStatus::Pending);
+
+                let result = crate::retry::retry_with_backoff(&retry_policy, || {
+                    facilitator.settle(&job.payment_payload, &job.payment_requirements)
+                })
+                .await;
+
+                let ctx = PaymentEventContext {
+                    resource: job.payment_requirements.resource.clone(),
+                    network: job.payment_requirements.network.clone(),
+                    amount: job.payment_requirements.max_amount_required.clone(),
+                    payment_id: Some(payment_id.clone()),
+                };
+
+                match result {
+                    Ok(settlement) => {
+                        worker_status
+                            .lock()
+                            .await
+                            .insert(payment_id, SettlementJobStatus::Settled(settlement.clone()));
+                        if let Some(observer) = &observer {
+                            observer.on_event(PaymentEvent::Settled { settlement }, &ctx).await;
+                        }
+                    }
+                    Err(error) => {
+                        warn!(
+                            "Settlement permanently failed for resource {}: {}",
+                            job.payment_requirements.resource, error
+                        );
+                        let reason = error.to_string();
+                        worker_status
+                            .lock()
+                            .await
+                            .insert(payment_id, SettlementJobStatus::Abandoned { reason: reason.clone() });
+                        if let Some(observer) = &observer {
+                            observer
+                                .on_event(PaymentEvent::SettlementFailed { error: reason }, &ctx)
+                                .await;
+                        }
+                    }
+                }
+            }
+        });
+
+        Self { sender, status }
+    }
+
+    /// Enqueue a verified payment for settlement, returning immediately
+    ///
+    /// Returns an error only if the background worker has already shut down.
+    pub fn enqueue(
+        &self,
+        payment_payload: PaymentPayload,
+        payment_requirements: PaymentRequirements,
+    ) -> crate::Result<()> {
+        self.sender
+            .send(SettlementJob {
+                payment_payload,
+                payment_requirements,
+            })
+            .map_err(|_| crate::X402Error::unexpected("Settlement queue worker has shut down"))
+    }
+
+    /// Look up the current status of a job previously enqueued for `payment_payload`'s
+    /// authorization, or `None` if it was never enqueued on this queue
+    pub async fn status(&self, payment_id: &PaymentId) -> Option<SettlementJobStatus> {
+        self.status.lock().await.get(payment_id).cloned()
+    }
+}
+
+impl std::fmt::Debug for SettlementQueue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SettlementQueue").finish()
+    }
+}
+
+/// Bounded capacity for a [`WebhookDispatcher`]'s internal channel
+///
+/// Once full, [`WebhookDispatcher::notify`] drops the notification being enqueued
+/// rather than growing unbounded or blocking the caller, so a webhook endpoint that's
+/// down doesn't let memory usage grow with request volume.
+const DEFAULT_WEBHOOK_QUEUE_CAPACITY: usize = 256;
+
+/// A settlement notification awaiting delivery
+struct WebhookJob {
+    notify_uri: String,
+    settlement_response: SettleResponse,
+}
+
+/// Background dispatcher that POSTs settlement responses to a webhook URL, so a caller
+/// that disconnected before the inline response arrived can still be notified
+///
+/// Runs on its own tokio task reading from a bounded [`tokio::sync::mpsc`] channel, so a
+/// slow or unreachable webhook endpoint backs up the queue instead of blocking the
+/// protected handler's response. Cloning a [`WebhookDispatcher`] is cheap and shares the
+/// same background worker and channel.
+#[derive(Clone)]
+pub struct WebhookDispatcher {
+    sender: mpsc::Sender<WebhookJob>,
+}
+
+impl WebhookDispatcher {
+    /// Spawn a background worker that POSTs enqueued settlement responses, retrying
+    /// each delivery with `retry_policy` before giving up and logging the failure
+    pub fn new(retry_policy: RetryPolicy) -> Self {
+        Self::with_capacity(retry_policy, DEFAULT_WEBHOOK_QUEUE_CAPACITY)
+    }
+
+    /// Like [`Self::new`], with an explicit bound on the number of queued notifications
+    pub fn with_capacity(retry_policy: RetryPolicy, capacity: usize) -> Self {
+        let (sender, mut receiver) = mpsc::channel::<WebhookJob>(capacity);
+        let client = reqwest::Client::new();
+
+        tokio::spawn(async move {
+            while let Some(job) = receiver.recv().await {
+                let client = client.clone();
+                let notify_uri = job.notify_uri.clone();
+                let result = crate::retry::retry_with_backoff(&retry_policy, || {
+                    let client = client.clone();
+                    let notify_uri = notify_uri.clone();
+                    let settlement_response = job.settlement_response.clone();
+                    async move {
+                        let response = client
+                            .post(&notify_uri)
+                            .json(&settlement_response)
+                            .send()
+                            .await
+                            .map_err(|e| {
+                                crate::X402Error::facilitator_error(format!(
+                                    "Failed to reach notify_uri {}: {}",
+                                    notify_uri, e
+                                ))
+                            })?;
+
+                        if !response.status().is_success() {
+                            return Err(crate::X402Error::facilitator_error(format!(
+                                "notify_uri {} returned status {}",
+                                notify_uri,
+                                response.status()
+                            )));
+                        }
+                        Ok(())
+                    }
+                })
+                .await;
+
+                if let Err(error) = result {
+                    warn!(
+                        "Settlement webhook permanently failed for {}: {}",
+                        job.notify_uri, error
+                    );
+                }
+            }
+        });
+
+        Self { sender }
+    }
+
+    /// Enqueue a settlement response for delivery to `notify_uri`, returning
+    /// immediately. If the queue is already at capacity, this notification is dropped
+    /// (logged as a warning) rather than blocking the caller.
+    pub fn notify(&self, notify_uri: impl Into<String>, settlement_response: SettleResponse) {
+        let job = WebhookJob {
+            notify_uri: notify_uri.into(),
+            settlement_response,
+        };
+
+        if let Err(mpsc::error::TrySendError::Full(job)) = self.sender.try_send(job) {
+            warn!(
+                "Webhook dispatcher queue full, dropping notification for {}",
+                job.notify_uri
+            );
+        }
+    }
+}
+
+impl std::fmt::Debug for WebhookDispatcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WebhookDispatcher").finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{ExactEvmPayload, ExactEvmPayloadAuthorization, FacilitatorConfig};
+    use mockito::Server;
+    use serde_json::json;
+    use std::time::Duration;
+
+    fn test_payment_payload() -> PaymentPayload {
+        let authorization = ExactEvmPayloadAuthorization::new(
+            "0x857b06519E91e3A54538791bDbb0E22373e36b66",
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            "1000000",
+            "1745323800",
+            "1745323985",
+            "0xf3746613c2d920b5fdabc0856f2aeb2d4f88ee6037b8cc5d04a71a4462f13480",
+        );
+        let payload = ExactEvmPayload {
+            signature: "0xsignature".to_string(),
+            authorization,
+        };
+        PaymentPayload::new("exact", "base-sepolia", payload)
+    }
+
+    fn test_payment_requirements() -> PaymentRequirements {
+        PaymentRequirements::new(
+            "exact",
+            "base-sepolia",
+            "1000000",
+            "0x036CbD53842c5426634e7929541eC2318f3dCF7e",
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            "https://example.com/test",
+            "Test payment",
+        )
+    }
+
+    #[tokio::test]
+    async fn test_settlement_queue_settles_enqueued_payment() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/settle")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "success": true,
+                    "transaction": "0xabc",
+                    "network": "base-sepolia"
+                })
+                .to_string(),
+            )
+            .create();
+
+        let facilitator =
+            FacilitatorClient::new(FacilitatorConfig::new(server.url())).unwrap();
+        let queue = SettlementQueue::new(facilitator, RetryPolicy::new());
+
+        queue
+            .enqueue(test_payment_payload(), test_payment_requirements())
+            .unwrap();
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_settlement_queue_retries_transient_failures() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/settle")
+            .with_status(503)
+            .expect(3)
+            .create();
+
+        let facilitator =
+            FacilitatorClient::new(FacilitatorConfig::new(server.url())).unwrap();
+        let retry_policy = RetryPolicy::new()
+            .with_base_delay(Duration::from_millis(1))
+            .with_max_attempts(3);
+        let queue = SettlementQueue::new(facilitator, retry_policy);
+
+        queue
+            .enqueue(test_payment_payload(), test_payment_requirements())
+            .unwrap();
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_settlement_queue_status_tracks_pending_then_settled() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/settle")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({"success": true, "transaction": "0xabc", "network": "base-sepolia"}).to_string(),
+            )
+            .create();
+
+        let facilitator = FacilitatorClient::new(FacilitatorConfig::new(server.url())).unwrap();
+        let queue = SettlementQueue::new(facilitator, RetryPolicy::new());
+        let payment_payload = test_payment_payload();
+        let payment_requirements = test_payment_requirements();
+        let payment_id = PaymentId::from_authorization(
+            &payment_payload.exact_evm().unwrap().authorization,
+            &payment_requirements,
+        );
+
+        queue.enqueue(payment_payload, payment_requirements).unwrap();
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        mock.assert();
+        assert!(matches!(
+            queue.status(&payment_id).await,
+            Some(SettlementJobStatus::Settled(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_settlement_queue_status_is_abandoned_after_retries_exhausted() {
+        let mut server = Server::new_async().await;
+        let mock = server.mock("POST", "/settle").with_status(503).expect(2).create();
+
+        let facilitator = FacilitatorClient::new(FacilitatorConfig::new(server.url())).unwrap();
+        let retry_policy = RetryPolicy::new()
+            .with_base_delay(Duration::from_millis(1))
+            .with_max_attempts(2);
+        let queue = SettlementQueue::new(facilitator, retry_policy);
+        let payment_payload = test_payment_payload();
+        let payment_requirements = test_payment_requirements();
+        let payment_id = PaymentId::from_authorization(
+            &payment_payload.exact_evm().unwrap().authorization,
+            &payment_requirements,
+        );
+
+        queue.enqueue(payment_payload, payment_requirements).unwrap();
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        mock.assert();
+        assert!(matches!(
+            queue.status(&payment_id).await,
+            Some(SettlementJobStatus::Abandoned { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_settlement_queue_status_is_none_for_an_unenqueued_payment_id() {
+        let facilitator = FacilitatorClient::new(FacilitatorConfig::new("http://localhost".to_string())).unwrap();
+        let queue = SettlementQueue::new(facilitator, RetryPolicy::new());
+        let payment_id = PaymentId::from_authorization(
+            &test_payment_payload().exact_evm().unwrap().authorization,
+            &test_payment_requirements(),
+        );
+
+        assert!(queue.status(&payment_id).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_settlement_queue_with_observer_fires_settlement_failed_on_abandon() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        struct CountingObserver(Arc<AtomicUsize>);
+        impl PaymentObserver for CountingObserver {
+            fn on_event<'a>(
+                &'a self,
+                event: PaymentEvent,
+                _ctx: &'a PaymentEventContext,
+            ) -> crate::payment_events::BoxFuture<'a, ()> {
+                if matches!(event, PaymentEvent::SettlementFailed { .. }) {
+                    self.0.fetch_add(1, Ordering::SeqCst);
+                }
+                Box::pin(async {})
+            }
+        }
+
+        let mut server = Server::new_async().await;
+        let mock = server.mock("POST", "/settle").with_status(503).expect(2).create();
+
+        let facilitator = FacilitatorClient::new(FacilitatorConfig::new(server.url())).unwrap();
+        let retry_policy = RetryPolicy::new()
+            .with_base_delay(Duration::from_millis(1))
+            .with_max_attempts(2);
+        let count = Arc::new(AtomicUsize::new(0));
+        let queue =
+            SettlementQueue::with_observer(facilitator, retry_policy, Arc::new(CountingObserver(count.clone())));
+
+        queue.enqueue(test_payment_payload(), test_payment_requirements()).unwrap();
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        mock.assert();
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+    }
+
+    fn test_settle_response() -> SettleResponse {
+        SettleResponse {
+            success: true,
+            error_reason: None,
+            transaction: "0xabc".to_string(),
+            network: "base-sepolia".to_string(),
+            payer: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_webhook_dispatcher_posts_settlement_response() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/webhook")
+            .with_status(200)
+            .match_body(mockito::Matcher::PartialJson(json!({"transaction": "0xabc"})))
+            .create();
+
+        let dispatcher = WebhookDispatcher::new(RetryPolicy::new());
+        dispatcher.notify(format!("{}/webhook", server.url()), test_settle_response());
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_webhook_dispatcher_retries_transient_failures() {
+        let mut server = Server::new_async().await;
+        let mock = server.mock("POST", "/webhook").with_status(503).expect(3).create();
+
+        let retry_policy = RetryPolicy::new()
+            .with_base_delay(Duration::from_millis(1))
+            .with_max_attempts(3);
+        let dispatcher = WebhookDispatcher::new(retry_policy);
+        dispatcher.notify(format!("{}/webhook", server.url()), test_settle_response());
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_webhook_dispatcher_drops_notification_when_queue_full() {
+        let mut server = Server::new_async().await;
+        let _m = server.mock("POST", "/webhook").with_status(200).create();
+
+        let dispatcher = WebhookDispatcher::with_capacity(RetryPolicy::new(), 0);
+        // With zero capacity the channel is always full; this must not panic or block.
+        dispatcher.notify(format!("{}/webhook", server.url()), test_settle_response());
+    }
+}
@@ -0,0 +1,621 @@
+//! Settlement confirmation with receipt polling and reorg handling
+//!
+//! Broadcasting a signed transaction doesn't mean it settled: the node may drop it,
+//! or a reorg may un-mine a block that briefly included it. [`SettlementConfirmer`]
+//! tracks a broadcast transaction as a [`PendingClaim`] and polls
+//! [`crate::blockchain::BlockchainClient::get_transaction_status`] until either it
+//! reaches the configured number of confirmations with a verified ERC-20 `Transfer`
+//! log (via [`crate::onchain_verification::OnchainSettlementVerifier`], cross-checking
+//! that the internal transfer the claim describes actually happened rather than
+//! trusting the receipt's bare success status), or it drops out of the chain, in
+//! which case the caller should resubmit a new transaction for the same ERC-3009
+//! `authorization_nonce` the claim carries.
+//!
+//! Modeled on Serai's "Eventuality" pattern: a claim tracks the on-chain effect an
+//! action was expected to cause until that effect is actually observed, rather than
+//! trusting the action's own immediate return value.
+
+use crate::blockchain::{BlockchainClient, TransactionStatus};
+use crate::onchain_verification::{ExpectedTransfer, OnchainSettlementVerifier, VerifiedTransfer};
+use crate::retry::{retry_with_backoff, RetryPolicy};
+use crate::{Result, X402Error};
+use std::time::Duration;
+
+/// A settlement broadcast but not yet confirmed
+#[derive(Debug, Clone)]
+pub struct PendingClaim {
+    /// Hash of the originally broadcast transaction
+    pub tx_hash: String,
+    /// Hashes of any fee-bumped replacements broadcast for the same nonce (oldest
+    /// first), e.g. via [`crate::real_facilitator::BlockchainFacilitatorClient::bump_transaction`].
+    /// Only one of `tx_hash` and these can ever be mined, so [`SettlementConfirmer::confirm`]
+    /// polls all of them each round and accepts confirmation from whichever lands.
+    pub replacement_hashes: Vec<String>,
+    /// The ERC-20 transfer this settlement is expected to have caused
+    pub expected: ExpectedTransfer,
+    /// The ERC-3009 authorization nonce this settlement spends; carried along so a
+    /// caller handling [`ConfirmationOutcome::Reorged`] knows which authorization to
+    /// resubmit a new transaction for
+    pub authorization_nonce: String,
+}
+
+impl PendingClaim {
+    /// Track `tx_hash` as settling `expected`, spending `authorization_nonce`
+    pub fn new(
+        tx_hash: impl Into<String>,
+        expected: ExpectedTransfer,
+        authorization_nonce: impl Into<String>,
+    ) -> Self {
+        Self {
+            tx_hash: tx_hash.into(),
+            replacement_hashes: Vec::new(),
+            expected,
+            authorization_nonce: authorization_nonce.into(),
+        }
+    }
+
+    /// Record that `tx_hash` was resubmitted as `replacement_hash` with bumped fees;
+    /// confirmation of either the original or any prior replacement still counts
+    pub fn with_replacement(mut self, replacement_hash: impl Into<String>) -> Self {
+        self.replacement_hashes.push(replacement_hash.into());
+        self
+    }
+
+    /// Every hash that could still confirm this claim, oldest first
+    pub fn candidate_hashes(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.tx_hash.as_str()).chain(self.replacement_hashes.iter().map(String::as_str))
+    }
+}
+
+/// Result of polling a [`PendingClaim`] to completion
+#[derive(Debug, PartialEq)]
+pub enum ConfirmationOutcome {
+    /// The transaction reached the required confirmation depth with a verified
+    /// `Transfer` log; the settlement may now be reported successful. Carries the
+    /// matched log's total transferred value/block plus the confirmation depth
+    /// actually observed (always `>= required_confirmations`), so a caller doesn't
+    /// have to re-derive what was actually confirmed on-chain.
+    Confirmed(VerifiedTransfer, u64),
+    /// The transaction was seen mined at least once, then later polls could no longer
+    /// find it — a reorg un-mined its block. The caller should resubmit a new
+    /// transaction spending the same `authorization_nonce`.
+    Reorged,
+    /// The transaction never appeared on-chain within `verification_timeout` of being
+    /// broadcast. Unlike [`Self::Reorged`], this doesn't necessarily mean the
+    /// authorization is safe to resubmit against — the original may still be sitting
+    /// in the mempool and land later — so the caller should check for that before
+    /// spending the same `authorization_nonce` again.
+    TimedOut,
+}
+
+/// Polls a [`PendingClaim`] until it confirms, reorgs, or times out
+///
+/// Modeled on serai's `Eventuality`: rather than trusting that a broadcast
+/// transaction settled, this tracks the on-chain effect it was expected to cause
+/// until that effect is actually observed at the required depth, re-checking on
+/// every poll that the transaction still resides on the canonical chain.
+pub struct SettlementConfirmer {
+    blockchain: BlockchainClient,
+    verifier: OnchainSettlementVerifier,
+    required_confirmations: u64,
+    poll_interval: Duration,
+    max_poll_interval: Duration,
+    verification_timeout: Duration,
+}
+
+impl SettlementConfirmer {
+    /// Confirm settlements by polling `blockchain`'s RPC endpoint, requiring 1
+    /// confirmation, starting at a 2 second poll interval (backing off exponentially
+    /// up to 30 seconds between polls), and giving up after 2 minutes
+    pub fn new(blockchain: BlockchainClient) -> Self {
+        let verifier = OnchainSettlementVerifier::new(blockchain.clone());
+        Self {
+            blockchain,
+            verifier,
+            required_confirmations: 1,
+            poll_interval: Duration::from_secs(2),
+            max_poll_interval: Duration::from_secs(30),
+            verification_timeout: Duration::from_secs(120),
+        }
+    }
+
+    /// Require `confirmations` blocks mined on top of the transaction's block before
+    /// treating it as confirmed
+    pub fn with_required_confirmations(mut self, confirmations: u64) -> Self {
+        self.required_confirmations = confirmations;
+        self
+    }
+
+    /// Override the starting delay between receipt polls; each subsequent poll
+    /// without a status change doubles the delay, up to [`Self::with_max_poll_interval`]
+    pub fn with_poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Cap how large the exponentially-backed-off poll delay can grow
+    pub fn with_max_poll_interval(mut self, interval: Duration) -> Self {
+        self.max_poll_interval = interval;
+        self
+    }
+
+    /// Override how long a transaction is polled before giving up — either because it
+    /// never mined ([`ConfirmationOutcome::TimedOut`]) or because it mined but never
+    /// reorgs back in ([`ConfirmationOutcome::Reorged`])
+    pub fn with_verification_timeout(mut self, timeout: Duration) -> Self {
+        self.verification_timeout = timeout;
+        self
+    }
+
+    /// Poll every hash in `claim.candidate_hashes()` (the original plus any
+    /// fee-bumped replacements) until one of them reaches the required confirmation
+    /// depth with a verified `Transfer` log, all of them are found to have reorged
+    /// out of the chain, or the claim times out. Since replace-by-fee means at most
+    /// one candidate can ever be mined, a single confirmed hash is enough to resolve
+    /// the claim — the others are simply superseded.
+    pub async fn confirm(&self, claim: &PendingClaim) -> Result<ConfirmationOutcome> {
+        let started = tokio::time::Instant::now();
+        let mut previously_mined = false;
+        let mut attempt: u32 = 0;
+        let candidates: Vec<&str> = claim.candidate_hashes().collect();
+
+        loop {
+            let mut mined_this_round = false;
+            let mut failed_count = 0usize;
+            let mut confirmed: Option<(&str, u64)> = None;
+
+            for hash in &candidates {
+                let status = self.blockchain.get_transaction_status(hash).await?;
+
+                if status.status == TransactionStatus::Failed {
+                    failed_count += 1;
+                    continue;
+                }
+
+                if status.status == TransactionStatus::Confirmed {
+                    mined_this_round = true;
+                    let block_number = status.block_number.ok_or_else(|| {
+                        X402Error::unexpected("confirmed transaction is missing a block number".to_string())
+                    })?;
+                    confirmed = Some((hash, block_number));
+                    break;
+                }
+            }
+
+            if let Some((hash, block_number)) = confirmed {
+                previously_mined = true;
+                let network_info = self.blockchain.get_network_info().await?;
+                let depth = network_info.latest_block.saturating_sub(block_number) + 1;
+
+                if depth >= self.required_confirmations {
+                    let verified = self.verifier.verify(hash, &claim.expected).await?;
+                    return Ok(ConfirmationOutcome::Confirmed(verified, depth));
+                }
+            } else if failed_count == candidates.len() {
+                return Err(X402Error::payment_verification_failed(format!(
+                    "every candidate transaction for claim {} failed on-chain",
+                    claim.tx_hash
+                )));
+            } else if previously_mined && !mined_this_round {
+                // A later poll could no longer find a transaction we'd already seen mined:
+                // the block it was in was reorged out from under it.
+                return Ok(ConfirmationOutcome::Reorged);
+            } else if started.elapsed() >= self.verification_timeout {
+                return Ok(ConfirmationOutcome::TimedOut);
+            }
+
+            tokio::time::sleep(backoff_delay(self.poll_interval, self.max_poll_interval, attempt)).await;
+            attempt += 1;
+        }
+    }
+}
+
+/// Exponentially back off the delay between polls, doubling `base` once per
+/// `attempt` and capping at `max`, so a settlement that takes a while to mine isn't
+/// polled as aggressively as one still in its first few seconds
+fn backoff_delay(base: Duration, max: Duration, attempt: u32) -> Duration {
+    base.saturating_mul(1u32.checked_shl(attempt).unwrap_or(u32::MAX))
+        .min(max)
+}
+
+/// Everything confirming a [`PendingSettlement`] learned about its transaction once it
+/// reached finality: the block it landed in plus what it actually cost to mine, so a
+/// caller doesn't have to fetch the receipt over again just to report them
+#[derive(Debug, Clone, PartialEq)]
+pub struct SettlementReceipt {
+    /// Hash of the transaction that actually confirmed
+    pub tx_hash: String,
+    pub block_number: u64,
+    pub gas_used: u64,
+    /// Gas price this transaction actually paid, post-EIP-1559 base fee
+    pub effective_gas_price: u64,
+}
+
+/// A settlement transaction that has been broadcast but not yet confirmed
+///
+/// Returned by [`crate::real_facilitator::BlockchainFacilitatorClient::settle_pending`]
+/// instead of blocking the caller until finality the way
+/// [`crate::real_facilitator::BlockchainFacilitatorClient::settle`] does, so a server
+/// can report "payment accepted, settling" immediately and await the receipt
+/// separately. Modeled on ethers-rs's `PendingTransaction`: `#[must_use]` so silently
+/// dropping one (and never learning whether the settlement actually confirmed) is a
+/// compiler warning, not a silent bug.
+#[must_use = "a PendingSettlement does nothing until awaited with `.wait()`"]
+pub struct PendingSettlement {
+    blockchain: BlockchainClient,
+    confirmer: SettlementConfirmer,
+    claim: PendingClaim,
+    retry_policy: RetryPolicy,
+}
+
+impl PendingSettlement {
+    /// Track a just-broadcast `claim`, confirming it via `confirmer` and retrying any
+    /// transient RPC failure (rather than a reorg, which `confirmer` itself already
+    /// handles) per `retry_policy`
+    pub(crate) fn new(
+        blockchain: BlockchainClient,
+        confirmer: SettlementConfirmer,
+        claim: PendingClaim,
+        retry_policy: RetryPolicy,
+    ) -> Self {
+        Self {
+            blockchain,
+            confirmer,
+            claim,
+            retry_policy,
+        }
+    }
+
+    /// The hash of the transaction that was broadcast, available immediately without
+    /// waiting for confirmation
+    pub fn tx_hash(&self) -> &str {
+        &self.claim.tx_hash
+    }
+
+    /// Poll until the settlement reaches its required confirmation depth, retrying a
+    /// transient RPC failure (e.g. the node being briefly unreachable) with
+    /// exponential backoff instead of failing the whole wait on the first blip.
+    ///
+    /// Errors rather than resubmitting on [`ConfirmationOutcome::Reorged`] or
+    /// [`ConfirmationOutcome::TimedOut`] — the caller holds the authorization's nonce
+    /// (not tracked here) and is in a better position to decide whether to resubmit a
+    /// replacement settlement for it.
+    pub async fn wait(self) -> Result<SettlementReceipt> {
+        let Self {
+            blockchain,
+            confirmer,
+            claim,
+            retry_policy,
+        } = self;
+
+        let outcome = retry_with_backoff(&retry_policy, || confirmer.confirm(&claim)).await?;
+
+        match outcome {
+            ConfirmationOutcome::Confirmed(verified, _depth) => {
+                let receipt = blockchain.get_receipt(&claim.tx_hash).await?;
+                let parse_hex = |hex: &str| {
+                    u64::from_str_radix(hex.trim_start_matches("0x"), 16).unwrap_or(0)
+                };
+                Ok(SettlementReceipt {
+                    tx_hash: claim.tx_hash,
+                    block_number: verified.block_number.unwrap_or(0),
+                    gas_used: receipt.gas_used.as_deref().map(parse_hex).unwrap_or(0),
+                    effective_gas_price: receipt.effective_gas_price.as_deref().map(parse_hex).unwrap_or(0),
+                })
+            }
+            ConfirmationOutcome::Reorged => Err(X402Error::payment_verification_failed(format!(
+                "settlement {} was reorged out of the chain; resubmit for authorization nonce {}",
+                claim.tx_hash, claim.authorization_nonce
+            ))),
+            ConfirmationOutcome::TimedOut => Err(X402Error::Timeout),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOKEN: &str = "0x036CbD53842c5426634e7929541eC2318f3dCF7e";
+    const FROM: &str = "0x857b06519E91e3A54538791bDbb0E22373e36b66";
+    const TO: &str = "0x209693Bc6afc0C5328bA36FaF03C514EF312287C";
+
+    #[test]
+    fn test_backoff_delay_doubles_each_attempt_up_to_the_cap() {
+        let base = Duration::from_secs(2);
+        let max = Duration::from_secs(10);
+        assert_eq!(backoff_delay(base, max, 0), Duration::from_secs(2));
+        assert_eq!(backoff_delay(base, max, 1), Duration::from_secs(4));
+        assert_eq!(backoff_delay(base, max, 2), Duration::from_secs(8));
+        assert_eq!(backoff_delay(base, max, 3), max);
+        assert_eq!(backoff_delay(base, max, 100), max);
+    }
+
+    #[tokio::test]
+    async fn test_confirm_times_out_when_transaction_never_appears() {
+        let mut server = mockito::Server::new_async().await;
+        // `get_transaction_status` now batches its two calls into one array request;
+        // both come back with a null result, so the transaction is never seen mined.
+        let _m = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex(r"^\[".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!([
+                    {"jsonrpc": "2.0", "id": 0, "result": null},
+                    {"jsonrpc": "2.0", "id": 1, "result": null}
+                ])
+                .to_string(),
+            )
+            .create();
+
+        let confirmer = SettlementConfirmer::new(BlockchainClient::new(server.url(), "base-sepolia".to_string()))
+            .with_verification_timeout(Duration::from_millis(1))
+            .with_poll_interval(Duration::from_millis(1));
+        let claim = PendingClaim::new("0xabc", ExpectedTransfer::new(TOKEN, FROM, TO, 1), "0xnonce");
+
+        let outcome = confirmer.confirm(&claim).await.unwrap();
+        assert_eq!(outcome, ConfirmationOutcome::TimedOut);
+    }
+
+    #[tokio::test]
+    async fn test_confirm_accepts_a_replacement_hash_when_the_original_never_mines() {
+        let mut server = mockito::Server::new_async().await;
+
+        // The original hash is never found (dropped in favor of the fee-bumped
+        // replacement); the replacement hash comes back confirmed.
+        let _m_original = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex(r#""0xoriginal""#.to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!([
+                    {"jsonrpc": "2.0", "id": 0, "result": null},
+                    {"jsonrpc": "2.0", "id": 1, "result": null}
+                ])
+                .to_string(),
+            )
+            .create();
+
+        let _m_replacement = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex(r#""0xreplacement""#.to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!([
+                    {"jsonrpc": "2.0", "id": 0, "result": {"blockNumber": "0x64", "from": FROM, "to": TOKEN, "value": "0x0"}},
+                    {"jsonrpc": "2.0", "id": 1, "result": null}
+                ])
+                .to_string(),
+            )
+            .create();
+
+        let _m_network_info = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex(r"eth_chainId".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!([
+                    {"jsonrpc": "2.0", "id": 0, "result": "0x1"},
+                    {"jsonrpc": "2.0", "id": 1, "result": "0x64"},
+                    {"jsonrpc": "2.0", "id": 2, "result": "0x0"}
+                ])
+                .to_string(),
+            )
+            .create();
+
+        let _m_receipt = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex(r"^\{".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "result": {
+                        "transactionHash": "0xreplacement",
+                        "status": "0x1",
+                        "logsBloom": format!("0x{}", "ff".repeat(256)),
+                        "logs": [
+                            {
+                                "address": TOKEN,
+                                "topics": [
+                                    "0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef",
+                                    format!("0x000000000000000000000000{}", &FROM[2..]),
+                                    format!("0x000000000000000000000000{}", &TO[2..]),
+                                ],
+                                "data": "0x00000000000000000000000000000000000000000000000000000000000f4240"
+                            }
+                        ]
+                    }
+                })
+                .to_string(),
+            )
+            .create();
+
+        let claim = PendingClaim::new("0xoriginal", ExpectedTransfer::new(TOKEN, FROM, TO, 1_000_000), "0xnonce")
+            .with_replacement("0xreplacement");
+        assert_eq!(claim.candidate_hashes().collect::<Vec<_>>(), vec!["0xoriginal", "0xreplacement"]);
+
+        let confirmer = SettlementConfirmer::new(BlockchainClient::new(server.url(), "base-sepolia".to_string()));
+        let outcome = confirmer.confirm(&claim).await.unwrap();
+        match outcome {
+            ConfirmationOutcome::Confirmed(verified, depth) => {
+                assert_eq!(verified.total_value, 1_000_000);
+                assert_eq!(depth, 1);
+            }
+            other => panic!("expected Confirmed, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_confirm_returns_confirmed_when_transfer_log_verifies() {
+        let mut server = mockito::Server::new_async().await;
+
+        // `get_transaction_status` and `get_network_info` now each batch their calls
+        // into a single array request; this fixed 3-entry response (only ids 0/1 are
+        // read by the former, all three by the latter) puts the transaction at block
+        // `0x64` and leaves `eth_chainId`/`eth_blockNumber`/`eth_gasPrice` to fall back
+        // to 0 (the `blockNumber` object id 0 aliases to, and the null id 1 aliases
+        // to, respectively) — matching this test's pre-batching behavior, where those
+        // same three legacy calls already couldn't parse the shared mock shape.
+        let _m = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex(r"^\[".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!([
+                    {"jsonrpc": "2.0", "id": 0, "result": {"blockNumber": "0x64", "from": FROM, "to": TOKEN, "value": "0x0"}},
+                    {"jsonrpc": "2.0", "id": 1, "result": null},
+                    {"jsonrpc": "2.0", "id": 2, "result": "0x0"}
+                ])
+                .to_string(),
+            )
+            .create();
+
+        // The verifier's receipt fetch (and `fee_history`, which tolerates failing to
+        // parse this shape and just leaves `NetworkInfo::suggested_fees` as `None`)
+        // are single, non-batched requests.
+        let _m2 = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex(r"^\{".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "result": {
+                        "transactionHash": "0xabc",
+                        "status": "0x1",
+                        "logsBloom": format!("0x{}", "ff".repeat(256)),
+                        "logs": [
+                            {
+                                "address": TOKEN,
+                                "topics": [
+                                    "0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef",
+                                    format!("0x000000000000000000000000{}", &FROM[2..]),
+                                    format!("0x000000000000000000000000{}", &TO[2..]),
+                                ],
+                                "data": "0x00000000000000000000000000000000000000000000000000000000000f4240"
+                            }
+                        ]
+                    }
+                })
+                .to_string(),
+            )
+            .create();
+
+        let confirmer = SettlementConfirmer::new(BlockchainClient::new(server.url(), "base-sepolia".to_string()));
+        let claim = PendingClaim::new("0xabc", ExpectedTransfer::new(TOKEN, FROM, TO, 1_000_000), "0xnonce");
+
+        let outcome = confirmer.confirm(&claim).await.unwrap();
+        match outcome {
+            ConfirmationOutcome::Confirmed(verified, depth) => {
+                assert_eq!(verified.total_value, 1_000_000);
+                assert_eq!(verified.block_number, Some(0x64));
+                assert_eq!(depth, 1);
+            }
+            other => panic!("expected Confirmed, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pending_settlement_wait_returns_a_receipt_once_confirmed() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _m = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex(r"^\[".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!([
+                    {"jsonrpc": "2.0", "id": 0, "result": {"blockNumber": "0x64", "from": FROM, "to": TOKEN, "value": "0x0"}},
+                    {"jsonrpc": "2.0", "id": 1, "result": null},
+                    {"jsonrpc": "2.0", "id": 2, "result": "0x0"}
+                ])
+                .to_string(),
+            )
+            .create();
+
+        let _m2 = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex(r"^\{".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "result": {
+                        "transactionHash": "0xabc",
+                        "status": "0x1",
+                        "logsBloom": format!("0x{}", "ff".repeat(256)),
+                        "gasUsed": "0x5208",
+                        "effectiveGasPrice": "0x3b9aca00",
+                        "logs": [
+                            {
+                                "address": TOKEN,
+                                "topics": [
+                                    "0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef",
+                                    format!("0x000000000000000000000000{}", &FROM[2..]),
+                                    format!("0x000000000000000000000000{}", &TO[2..]),
+                                ],
+                                "data": "0x00000000000000000000000000000000000000000000000000000000000f4240"
+                            }
+                        ]
+                    }
+                })
+                .to_string(),
+            )
+            .create();
+
+        let blockchain = BlockchainClient::new(server.url(), "base-sepolia".to_string());
+        let confirmer = SettlementConfirmer::new(blockchain.clone());
+        let claim = PendingClaim::new("0xabc", ExpectedTransfer::new(TOKEN, FROM, TO, 1_000_000), "0xnonce");
+        let pending = PendingSettlement::new(blockchain, confirmer, claim, RetryPolicy::default());
+
+        assert_eq!(pending.tx_hash(), "0xabc");
+        let receipt = pending.wait().await.unwrap();
+        assert_eq!(receipt.tx_hash, "0xabc");
+        assert_eq!(receipt.block_number, 0x64);
+        assert_eq!(receipt.gas_used, 0x5208);
+        assert_eq!(receipt.effective_gas_price, 0x3b9aca00);
+    }
+
+    #[tokio::test]
+    async fn test_pending_settlement_wait_errors_on_timeout() {
+        let mut server = mockito::Server::new_async().await;
+        let _m = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex(r"^\[".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!([
+                    {"jsonrpc": "2.0", "id": 0, "result": null},
+                    {"jsonrpc": "2.0", "id": 1, "result": null}
+                ])
+                .to_string(),
+            )
+            .create();
+
+        let blockchain = BlockchainClient::new(server.url(), "base-sepolia".to_string());
+        let confirmer = SettlementConfirmer::new(blockchain.clone())
+            .with_verification_timeout(Duration::from_millis(1))
+            .with_poll_interval(Duration::from_millis(1));
+        let claim = PendingClaim::new("0xabc", ExpectedTransfer::new(TOKEN, FROM, TO, 1), "0xnonce");
+        let retry_policy = RetryPolicy::new().with_max_attempts(1);
+        let pending = PendingSettlement::new(blockchain, confirmer, claim, retry_policy);
+
+        let err = pending.wait().await.unwrap_err();
+        assert!(matches!(err, X402Error::Timeout));
+    }
+}
@@ -0,0 +1,305 @@
+//! One-shot confirmation that a facilitator-reported settlement transaction hash
+//! actually landed on-chain and paid the right party the right amount
+//!
+//! [`crate::settlement_verifier::SettlementVerifier`] discovers a settlement without
+//! trusting any reported transaction hash at all, and
+//! [`crate::settlement_confirmation::SettlementConfirmer`] tracks a self-broadcast
+//! transaction through reorgs. This module is for the simpler case in between: the
+//! caller already has a [`SettleResponse`] naming a transaction and just wants to
+//! know whether it backs up the claim before trusting its bare `success: true` — a
+//! single receipt fetch against a default or caller-supplied RPC endpoint for the
+//! settlement's network, reporting [`SettlementStatus::Pending`] rather than an
+//! error while the receipt hasn't landed yet.
+
+use crate::blockchain::{BlockchainClient, BlockchainClientFactory};
+use crate::onchain_verification::{ExpectedTransfer, OnchainSettlementVerifier, VerifiedTransfer};
+use crate::types::{PaymentRequirements, SettleResponse};
+use crate::{Result, X402Error};
+use std::time::Duration;
+
+/// How often [`SettlementReceiptCheck::wait_for_confirmation`] re-checks while a
+/// settlement is still [`SettlementStatus::Pending`]
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Outcome of checking a facilitator-reported settlement's receipt
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SettlementStatus {
+    /// The settlement transaction mined successfully and its logs confirm the
+    /// expected ERC-20 transfer
+    Confirmed(VerifiedTransfer),
+    /// No receipt exists yet; the transaction may still be sitting in the mempool
+    Pending,
+    /// The transaction mined but reverted, or its logs don't back up the claimed
+    /// transfer
+    Failed { reason: String },
+}
+
+/// Confirms a [`SettleResponse`] against the chain it claims to have settled on
+pub struct SettlementReceiptCheck {
+    blockchain: BlockchainClient,
+    verifier: OnchainSettlementVerifier,
+}
+
+impl SettlementReceiptCheck {
+    /// Check settlements by querying `blockchain`'s RPC endpoint directly
+    pub fn new(blockchain: BlockchainClient) -> Self {
+        Self {
+            verifier: OnchainSettlementVerifier::new(blockchain.clone()),
+            blockchain,
+        }
+    }
+
+    /// Check settlements on `network` using its default public RPC endpoint (see
+    /// [`BlockchainClientFactory::for_network`]); call [`Self::new`] with a client
+    /// built from [`BlockchainClientFactory::custom`] to override it.
+    pub fn for_network(network: &str) -> Result<Self> {
+        BlockchainClientFactory::for_network(network)
+            .map(Self::new)
+            .ok_or_else(|| X402Error::NetworkNotSupported {
+                network: network.to_string(),
+            })
+    }
+
+    /// Fetch `settlement.transaction`'s receipt once and compare it against
+    /// `requirements`, without waiting for it to appear if it hasn't mined yet
+    pub async fn check(
+        &self,
+        settlement: &SettleResponse,
+        requirements: &PaymentRequirements,
+    ) -> Result<SettlementStatus> {
+        let Some(receipt) = self.blockchain.try_get_receipt(&settlement.transaction).await? else {
+            return Ok(SettlementStatus::Pending);
+        };
+
+        if receipt.status != "0x1" {
+            return Ok(SettlementStatus::Failed {
+                reason: format!(
+                    "transaction {} did not succeed on-chain (status {})",
+                    settlement.transaction, receipt.status
+                ),
+            });
+        }
+
+        let expected = expected_transfer(settlement, requirements)?;
+        match self.verifier.verify(&settlement.transaction, &expected).await {
+            Ok(verified) => Ok(SettlementStatus::Confirmed(verified)),
+            Err(X402Error::PaymentVerificationFailed { reason }) => {
+                Ok(SettlementStatus::Failed { reason })
+            }
+            Err(other) => Err(other),
+        }
+    }
+
+    /// Poll [`Self::check`] every 2 seconds until it resolves to
+    /// [`SettlementStatus::Confirmed`] or [`SettlementStatus::Failed`], or return
+    /// [`X402Error::SettlementNotConfirmed`] once `timeout` elapses while it's still
+    /// [`SettlementStatus::Pending`]
+    pub async fn wait_for_confirmation(
+        &self,
+        settlement: &SettleResponse,
+        requirements: &PaymentRequirements,
+        timeout: Duration,
+    ) -> Result<VerifiedTransfer> {
+        let started = tokio::time::Instant::now();
+        loop {
+            match self.check(settlement, requirements).await? {
+                SettlementStatus::Confirmed(verified) => return Ok(verified),
+                SettlementStatus::Failed { reason } => {
+                    return Err(X402Error::settlement_not_confirmed(
+                        settlement.transaction.clone(),
+                        reason,
+                    ));
+                }
+                SettlementStatus::Pending => {
+                    if started.elapsed() >= timeout {
+                        return Err(X402Error::settlement_not_confirmed(
+                            settlement.transaction.clone(),
+                            "still pending when the confirmation timeout elapsed",
+                        ));
+                    }
+                    tokio::time::sleep(POLL_INTERVAL).await;
+                }
+            }
+        }
+    }
+}
+
+/// Build the [`ExpectedTransfer`] `settlement`'s claimed transfer must match:
+/// `requirements.asset` moving at least `requirements.max_amount_required` from
+/// `settlement.payer` to `requirements.pay_to`
+fn expected_transfer(
+    settlement: &SettleResponse,
+    requirements: &PaymentRequirements,
+) -> Result<ExpectedTransfer> {
+    let payer = settlement
+        .payer
+        .as_deref()
+        .ok_or_else(|| X402Error::malformed_payload("payer"))?;
+    let min_value: u128 = requirements
+        .max_amount_required
+        .parse()
+        .map_err(|_| X402Error::invalid_payment_requirements("Invalid required amount format"))?;
+
+    Ok(ExpectedTransfer::new(
+        &requirements.asset,
+        payer,
+        &requirements.pay_to,
+        min_value,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::onchain_verification::{address_topic, transfer_event_topic};
+    use sha3::{Digest, Keccak256};
+
+    const TOKEN: &str = "0x036CbD53842c5426634e7929541eC2318f3dCF7e";
+    const FROM: &str = "0x857b06519E91e3A54538791bDbb0E22373e36b66";
+    const TO: &str = "0x209693Bc6afc0C5328bA36FaF03C514EF312287C";
+
+    fn requirements() -> PaymentRequirements {
+        PaymentRequirements::new(
+            "exact",
+            "base-sepolia",
+            "1000000",
+            TOKEN,
+            TO,
+            "https://example.com/resource",
+            "",
+        )
+    }
+
+    fn settlement(transaction: &str) -> SettleResponse {
+        SettleResponse {
+            success: true,
+            error_reason: None,
+            transaction: transaction.to_string(),
+            network: "base-sepolia".to_string(),
+            payer: Some(FROM.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_expected_transfer_requires_a_payer() {
+        let mut settled = settlement("0xabc");
+        settled.payer = None;
+        let error = expected_transfer(&settled, &requirements()).unwrap_err();
+        assert!(matches!(error, X402Error::MalformedPayload { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_check_reports_pending_when_no_receipt_exists_yet() {
+        let mut server = mockito::Server::new_async().await;
+        let _m = server
+            .mock("POST", "/")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": null}).to_string())
+            .create_async()
+            .await;
+
+        let checker = SettlementReceiptCheck::new(BlockchainClient::new(
+            server.url(),
+            "base-sepolia".to_string(),
+        ));
+        let status = checker.check(&settlement("0xabc"), &requirements()).await.unwrap();
+        assert_eq!(status, SettlementStatus::Pending);
+    }
+
+    #[tokio::test]
+    async fn test_check_reports_failed_for_a_reverted_transaction() {
+        let mut server = mockito::Server::new_async().await;
+        let _m = server
+            .mock("POST", "/")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "result": {
+                        "transactionHash": "0xabc",
+                        "status": "0x0",
+                        "logsBloom": format!("0x{}", "00".repeat(256)),
+                        "logs": []
+                    }
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let checker = SettlementReceiptCheck::new(BlockchainClient::new(
+            server.url(),
+            "base-sepolia".to_string(),
+        ));
+        let status = checker.check(&settlement("0xabc"), &requirements()).await.unwrap();
+        assert!(matches!(status, SettlementStatus::Failed { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_check_reports_confirmed_when_the_transfer_log_verifies() {
+        let mut server = mockito::Server::new_async().await;
+
+        let mut bloom = [0u8; 256];
+        for item in [
+            hex::decode(TOKEN.trim_start_matches("0x")).unwrap(),
+            transfer_event_topic().to_vec(),
+            address_topic(FROM).unwrap().to_vec(),
+            address_topic(TO).unwrap().to_vec(),
+        ] {
+            let hash = Keccak256::digest(&item);
+            for pair in 0..3 {
+                let word = u16::from_be_bytes([hash[pair * 2], hash[pair * 2 + 1]]) & 0x07ff;
+                let byte_index = 255 - (word / 8) as usize;
+                let bit_index = (word % 8) as u8;
+                bloom[byte_index] |= 1 << bit_index;
+            }
+        }
+
+        let _m = server
+            .mock("POST", "/")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "result": {
+                        "transactionHash": "0xabc",
+                        "status": "0x1",
+                        "logsBloom": format!("0x{}", hex::encode(bloom)),
+                        "logs": [{
+                            "address": TOKEN,
+                            "topics": [
+                                format!("0x{}", hex::encode(transfer_event_topic())),
+                                format!("0x{}", hex::encode(address_topic(FROM).unwrap())),
+                                format!("0x{}", hex::encode(address_topic(TO).unwrap())),
+                            ],
+                            "data": format!("0x{:064x}", 1_000_000u128)
+                        }]
+                    }
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let checker = SettlementReceiptCheck::new(BlockchainClient::new(
+            server.url(),
+            "base-sepolia".to_string(),
+        ));
+        let status = checker.check(&settlement("0xabc"), &requirements()).await.unwrap();
+        match status {
+            SettlementStatus::Confirmed(verified) => assert_eq!(verified.total_value, 1_000_000),
+            other => panic!("expected Confirmed, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_for_network_rejects_unknown_network() {
+        let error = SettlementReceiptCheck::for_network("ethereum-mainnet").unwrap_err();
+        assert!(matches!(error, X402Error::NetworkNotSupported { .. }));
+    }
+}
@@ -0,0 +1,256 @@
+//! Throughput-oriented settlement scheduler
+//!
+//! [`crate::real_facilitator::BlockchainFacilitatorClient::settle`] is one-shot: a
+//! caller settling many payments concurrently against the facilitator's single
+//! signing account gets no help ordering them, so a payer whose payments are
+//! submitted out of order (or a burst of broadcasts that all land in the same poll
+//! window) can strand later settlements behind earlier ones. [`SettlementScheduler`]
+//! sits in front of the facilitator and, inspired by serai's account `Scheduler`:
+//! - serializes settlements for the same payer so they land in submission order
+//!   (different payers still settle concurrently — `BlockchainFacilitatorClient`'s own
+//!   nonce manager already orders the underlying relayer nonce correctly across them)
+//! - bounds how many settlements are in flight at once via a semaphore
+//! - retries a transient facilitator error with [`crate::retry::retry_with_backoff`]
+//!   before giving up on a payment
+//!
+//! `submit` is the "future per payment" the caller awaits; nothing here queues work
+//! the caller doesn't ask for — a burst of `submit` calls is this scheduler's queue.
+//!
+//! [`SettlementScheduler`] implements [`crate::facilitator::Facilitator`] so it can be
+//! registered anywhere a [`BlockchainFacilitatorClient`] is (a
+//! [`crate::facilitator::FacilitatorRegistry`], `with_facilitator_registry`, ...)
+//! instead of sitting unreachable outside its own tests. Retry handling here overlaps
+//! with [`crate::retry`]/[`crate::facilitator::RetryableFacilitator`]/
+//! [`crate::facilitator_middleware::RetryMiddleware`] — each retries a different layer
+//! (this scheduler's own facilitator call, an HTTP `FacilitatorClient`, and the RPC
+//! middleware stack, respectively) rather than duplicating one concern, but whether the
+//! crate wants three separate retry call sites long-term instead of one shared
+//! abstraction is a maintainer call, not something to resolve by picking a backend here.
+
+use crate::facilitator::{BoxFuture, Facilitator};
+use crate::real_facilitator::BlockchainFacilitatorClient;
+use crate::retry::{retry_with_backoff, RetryPolicy};
+use crate::types::{PaymentPayload, PaymentRequirements, SettleResponse, SupportedKinds, VerifyResponse};
+use crate::Result;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::Semaphore;
+
+/// Schedules settlements against a shared [`BlockchainFacilitatorClient`], ordering
+/// same-payer submissions and bounding concurrency
+pub struct SettlementScheduler {
+    facilitator: Arc<BlockchainFacilitatorClient>,
+    in_flight: Arc<Semaphore>,
+    retry_policy: RetryPolicy,
+    payer_locks: Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>,
+}
+
+impl SettlementScheduler {
+    /// Schedule settlements against `facilitator`, allowing 8 broadcasts in flight at
+    /// once and retrying transient failures with the default [`RetryPolicy`]
+    pub fn new(facilitator: Arc<BlockchainFacilitatorClient>) -> Self {
+        Self {
+            facilitator,
+            in_flight: Arc::new(Semaphore::new(8)),
+            retry_policy: RetryPolicy::new(),
+            payer_locks: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Cap how many settlements may be broadcast at the same time
+    pub fn with_max_in_flight(mut self, max_in_flight: usize) -> Self {
+        self.in_flight = Arc::new(Semaphore::new(max_in_flight.max(1)));
+        self
+    }
+
+    /// Override the retry policy applied to a transient facilitator failure
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Settle `payment_payload`, resolving once it's broadcast (and retried through
+    /// any transient failures) or definitively fails
+    ///
+    /// Settlements for the same payer (`payment_payload.exact_evm()?.authorization.from`)
+    /// are serialized against each other in the order `submit` is called, so a payer
+    /// submitting several payments back-to-back can't have them race and land out of
+    /// order; settlements for different payers proceed concurrently, up to the
+    /// configured in-flight limit.
+    pub async fn submit(
+        &self,
+        payment_payload: PaymentPayload,
+        requirements: PaymentRequirements,
+    ) -> Result<SettleResponse> {
+        let payer = payment_payload.exact_evm()?.authorization.from.clone();
+        let payer_lock = self.payer_lock_for(&payer);
+        let _payer_guard = payer_lock.lock().await;
+        let _in_flight_permit = self
+            .in_flight
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("semaphore is never closed");
+
+        retry_with_backoff(&self.retry_policy, || {
+            self.facilitator.settle(&payment_payload, &requirements)
+        })
+        .await
+    }
+
+    /// The per-payer ordering lock for `payer`, creating one if this is the first
+    /// settlement seen for that payer
+    fn payer_lock_for(&self, payer: &str) -> Arc<tokio::sync::Mutex<()>> {
+        let mut locks = self.payer_locks.lock().unwrap();
+        locks
+            .entry(payer.to_string())
+            .or_insert_with(|| Arc::new(tokio::sync::Mutex::new(())))
+            .clone()
+    }
+}
+
+/// Lets a [`SettlementScheduler`] be registered anywhere a plain
+/// [`BlockchainFacilitatorClient`] is — a [`crate::facilitator::FacilitatorRegistry`],
+/// [`crate::middleware::PaymentMiddleware::with_facilitator_registry`], or similar —
+/// so an operator settling many payments through one relayer account can opt into
+/// payer-ordered, concurrency-bounded settlement without changing how the rest of the
+/// stack talks to its facilitator backend. `verify` passes straight through since
+/// there's nothing to schedule about a read-only check; only `settle` goes through
+/// [`Self::submit`].
+impl Facilitator for SettlementScheduler {
+    fn verify<'a>(
+        &'a self,
+        payment_payload: &'a PaymentPayload,
+        payment_requirements: &'a PaymentRequirements,
+    ) -> BoxFuture<'a, Result<VerifyResponse>> {
+        Box::pin(BlockchainFacilitatorClient::verify(
+            &self.facilitator,
+            payment_payload,
+            payment_requirements,
+        ))
+    }
+
+    fn settle<'a>(
+        &'a self,
+        payment_payload: &'a PaymentPayload,
+        payment_requirements: &'a PaymentRequirements,
+    ) -> BoxFuture<'a, Result<SettleResponse>> {
+        Box::pin(self.submit(payment_payload.clone(), payment_requirements.clone()))
+    }
+
+    fn supported(&self) -> BoxFuture<'_, Result<SupportedKinds>> {
+        Box::pin(BlockchainFacilitatorClient::supported(&self.facilitator))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::real_facilitator::BlockchainFacilitatorConfig;
+    use crate::types::{ExactEvmPayload, ExactEvmPayloadAuthorization};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    fn test_payment_payload(from: &str) -> PaymentPayload {
+        let authorization = ExactEvmPayloadAuthorization::new(
+            from,
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            "1000000",
+            "0",
+            "9999999999",
+            "0xnonce",
+        );
+        let payload = ExactEvmPayload {
+            signature: format!("0x{}{}{}", "11".repeat(32), "22".repeat(32), "1c"),
+            authorization,
+        };
+        PaymentPayload::new("exact", "base-sepolia", payload)
+    }
+
+    fn test_requirements() -> PaymentRequirements {
+        PaymentRequirements::new(
+            "exact",
+            "base-sepolia",
+            "1000000",
+            "0x036CbD53842c5426634e7929541eC2318f3dCF7e",
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            "https://example.com/resource",
+            "test resource",
+        )
+    }
+
+    #[tokio::test]
+    async fn test_submit_settles_without_a_relayer_key_reports_failure() {
+        let facilitator = Arc::new(
+            BlockchainFacilitatorClient::new(BlockchainFacilitatorConfig {
+                network: "base-sepolia".to_string(),
+                relayer_private_key: None,
+                ..Default::default()
+            })
+            .unwrap(),
+        );
+        let scheduler = SettlementScheduler::new(facilitator);
+
+        let response = scheduler
+            .submit(test_payment_payload("0x857b06519E91e3A54538791bDbb0E22373e36b66"), test_requirements())
+            .await
+            .unwrap();
+
+        // The forged test signature fails verification before the missing relayer key
+        // would otherwise matter; either way this is a definitive (non-retryable)
+        // failure rather than an error from the scheduler itself.
+        assert!(!response.success);
+    }
+
+    #[tokio::test]
+    async fn test_distinct_payers_settle_concurrently() {
+        let facilitator = Arc::new(
+            BlockchainFacilitatorClient::new(BlockchainFacilitatorConfig {
+                network: "base-sepolia".to_string(),
+                relayer_private_key: None,
+                ..Default::default()
+            })
+            .unwrap(),
+        );
+        let scheduler = Arc::new(SettlementScheduler::new(facilitator).with_max_in_flight(4));
+        let completed = Arc::new(AtomicUsize::new(0));
+
+        let mut handles = Vec::new();
+        for i in 0..4 {
+            let scheduler = scheduler.clone();
+            let completed = completed.clone();
+            let from = format!("0x00000000000000000000000000000000000{:03}", i);
+            handles.push(tokio::spawn(async move {
+                let _ = scheduler.submit(test_payment_payload(&from), test_requirements()).await;
+                completed.fetch_add(1, Ordering::SeqCst);
+            }));
+        }
+
+        for handle in handles {
+            tokio::time::timeout(Duration::from_secs(5), handle).await.unwrap().unwrap();
+        }
+
+        assert_eq!(completed.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn test_settlement_scheduler_is_usable_as_a_facilitator() {
+        let facilitator = Arc::new(
+            BlockchainFacilitatorClient::new(BlockchainFacilitatorConfig {
+                network: "base-sepolia".to_string(),
+                relayer_private_key: None,
+                ..Default::default()
+            })
+            .unwrap(),
+        );
+        let scheduler: Arc<dyn Facilitator> = Arc::new(SettlementScheduler::new(facilitator));
+
+        let payload = test_payment_payload("0x857b06519E91e3A54538791bDbb0E22373e36b66");
+        let requirements = test_requirements();
+
+        // Registered as a `Facilitator` the same way `BlockchainFacilitatorClient`
+        // itself is, `settle` still goes through `submit`'s scheduling.
+        let response = scheduler.settle(&payload, &requirements).await.unwrap();
+        assert!(!response.success);
+    }
+}
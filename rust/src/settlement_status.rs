@@ -0,0 +1,310 @@
+//! Live settlement status, streamed to the client that resubmitted a paid request
+//!
+//! [`crate::middleware::PaymentMiddleware`] blocks the caller on a single
+//! `verify`/`settle` round trip, and [`crate::settlement::SettlementQueue`] lets
+//! settlement happen entirely after the response is sent — but neither gives the
+//! caller any visibility into what's happening in between. [`SettlementStatusTracker`]
+//! is a per-payment-id broadcast channel: [`drive_settlement`] publishes
+//! [`SettlementStatus`] transitions to it as verify and settle run on a background
+//! task, and the `axum`/`warp` settlement-status routes subscribe to it to stream
+//! those transitions to a client as Server-Sent Events.
+
+use crate::facilitator::Facilitator;
+use crate::types::{PaymentPayload, PaymentRequirements};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::{broadcast, Mutex};
+
+/// Buffer depth of each payment's broadcast channel; a subscriber that's briefly
+/// disconnected can miss at most this many transitions before seeing a `Lagged` error
+const CHANNEL_CAPACITY: usize = 16;
+
+/// A transition in a tracked payment's verify→settle lifecycle, published to
+/// [`SettlementStatusTracker`] and serialized as an SSE event by the `axum`/`warp`
+/// settlement-status routes. The `event` field doubles as the SSE `event:` name.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum SettlementStatus {
+    /// The facilitator is checking the payment payload against requirements
+    Verifying,
+    /// Verification passed and settlement has been submitted to the facilitator
+    Submitted {
+        /// Transaction hash, once the facilitator has one to report
+        transaction: String,
+    },
+    /// The facilitator reported the settlement as final
+    Confirmed {
+        /// Transaction hash of the confirmed settlement
+        transaction: String,
+    },
+    /// Verification or settlement failed
+    Failed {
+        /// Human-readable reason, suitable for display
+        reason: String,
+    },
+}
+
+impl SettlementStatus {
+    /// Whether this is a final state after which no further events will be published
+    /// for the payment
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, Self::Confirmed { .. } | Self::Failed { .. })
+    }
+}
+
+/// Tracks the live [`SettlementStatus`] of in-flight payments, keyed by an opaque
+/// payment id the resource server chooses when it starts driving settlement (e.g. the
+/// payment payload's nonce)
+///
+/// Cloning a [`SettlementStatusTracker`] is cheap and shares the same backing map, so
+/// it can be stored directly on [`crate::middleware::PaymentMiddleware`] alongside
+/// [`crate::settlement::SettlementQueue`].
+#[derive(Clone, Default)]
+pub struct SettlementStatusTracker {
+    channels: Arc<Mutex<HashMap<String, broadcast::Sender<SettlementStatus>>>>,
+}
+
+impl SettlementStatusTracker {
+    /// Create an empty tracker
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Publish `status` for `payment_id`, creating its channel if this is the first
+    /// event seen for it. Removes the channel once `status` is terminal — subscribers
+    /// already holding a [`broadcast::Receiver`] still drain it normally.
+    pub async fn publish(&self, payment_id: &str, status: SettlementStatus) {
+        let mut channels = self.channels.lock().await;
+        let sender = channels
+            .entry(payment_id.to_string())
+            .or_insert_with(|| broadcast::channel(CHANNEL_CAPACITY).0);
+        let _ = sender.send(status.clone());
+        if status.is_terminal() {
+            channels.remove(payment_id);
+        }
+    }
+
+    /// Subscribe to `payment_id`'s transitions, creating its channel if no event has
+    /// been published for it yet (e.g. the SSE client connects before settlement
+    /// starts)
+    pub async fn subscribe(&self, payment_id: &str) -> broadcast::Receiver<SettlementStatus> {
+        let mut channels = self.channels.lock().await;
+        channels
+            .entry(payment_id.to_string())
+            .or_insert_with(|| broadcast::channel(CHANNEL_CAPACITY).0)
+            .subscribe()
+    }
+}
+
+impl std::fmt::Debug for SettlementStatusTracker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SettlementStatusTracker").finish()
+    }
+}
+
+/// Drive `payment_payload`/`payment_requirements` through `facilitator`'s
+/// verify→settle lifecycle on a background task, publishing each
+/// [`SettlementStatus`] transition to `tracker` under `payment_id` as it happens.
+///
+/// This facilitator's `settle` already only returns once the facilitator itself
+/// considers the transaction final, so there's no separate on-chain confirmation
+/// step to await here; `Submitted` and `Confirmed` are published back-to-back rather
+/// than at two genuinely distinct moments. A facilitator backend that did expose an
+/// intermediate "broadcast but not yet confirmed" state could publish `Submitted`
+/// earlier by driving that distinction through its own [`Facilitator`] impl.
+pub fn drive_settlement(
+    facilitator: Arc<dyn Facilitator>,
+    tracker: SettlementStatusTracker,
+    payment_id: String,
+    payment_payload: PaymentPayload,
+    payment_requirements: PaymentRequirements,
+) {
+    tokio::spawn(async move {
+        tracker
+            .publish(&payment_id, SettlementStatus::Verifying)
+            .await;
+
+        let verify_result = facilitator
+            .verify(&payment_payload, &payment_requirements)
+            .await;
+        let verified = match verify_result {
+            Ok(response) if response.is_valid => response,
+            Ok(response) => {
+                tracker
+                    .publish(
+                        &payment_id,
+                        SettlementStatus::Failed {
+                            reason: response
+                                .invalid_reason
+                                .unwrap_or_else(|| "verification failed".to_string()),
+                        },
+                    )
+                    .await;
+                return;
+            }
+            Err(error) => {
+                tracker
+                    .publish(
+                        &payment_id,
+                        SettlementStatus::Failed {
+                            reason: error.to_string(),
+                        },
+                    )
+                    .await;
+                return;
+            }
+        };
+        let _ = verified;
+
+        match facilitator.settle(&payment_payload, &payment_requirements).await {
+            Ok(response) if response.success => {
+                tracker
+                    .publish(
+                        &payment_id,
+                        SettlementStatus::Submitted {
+                            transaction: response.transaction.clone(),
+                        },
+                    )
+                    .await;
+                tracker
+                    .publish(
+                        &payment_id,
+                        SettlementStatus::Confirmed {
+                            transaction: response.transaction,
+                        },
+                    )
+                    .await;
+            }
+            Ok(response) => {
+                tracker
+                    .publish(
+                        &payment_id,
+                        SettlementStatus::Failed {
+                            reason: response
+                                .error_reason
+                                .unwrap_or_else(|| "settlement failed".to_string()),
+                        },
+                    )
+                    .await;
+            }
+            Err(error) => {
+                tracker
+                    .publish(
+                        &payment_id,
+                        SettlementStatus::Failed {
+                            reason: error.to_string(),
+                        },
+                    )
+                    .await;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::facilitator::FacilitatorClient;
+    use crate::types::{ExactEvmPayload, ExactEvmPayloadAuthorization, FacilitatorConfig};
+    use mockito::Server;
+    use serde_json::json;
+
+    fn test_payment_payload() -> PaymentPayload {
+        let authorization = ExactEvmPayloadAuthorization::new(
+            "0x857b06519E91e3A54538791bDbb0E22373e36b66",
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            "1000000",
+            "1745323800",
+            "1745323985",
+            "0xf3746613c2d920b5fdabc0856f2aeb2d4f88ee6037b8cc5d04a71a4462f13480",
+        );
+
+        let payload = ExactEvmPayload {
+            signature: "0x2d6a7588d6acca505cbf0d9a4a227e0c52c6c34008c8e8986a1283259764173608a2ce6496642e377d6da8dbbf5836e9bd15092f9ecab05ded3d6293af148b571c".to_string(),
+            authorization,
+        };
+
+        PaymentPayload::new("exact", "base-sepolia", payload)
+    }
+
+    fn test_payment_requirements() -> PaymentRequirements {
+        PaymentRequirements::new(
+            "exact",
+            "base-sepolia",
+            "1000000",
+            "0x036CbD53842c5426634e7929541eC2318f3dCF7e",
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            "/resource",
+            "test payment",
+        )
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_before_publish_still_receives_events() {
+        let tracker = SettlementStatusTracker::new();
+        let mut receiver = tracker.subscribe("payment-1").await;
+
+        tracker.publish("payment-1", SettlementStatus::Verifying).await;
+
+        assert!(matches!(receiver.recv().await.unwrap(), SettlementStatus::Verifying));
+    }
+
+    #[tokio::test]
+    async fn test_publish_removes_channel_after_terminal_event() {
+        let tracker = SettlementStatusTracker::new();
+        tracker
+            .publish(
+                "payment-2",
+                SettlementStatus::Failed { reason: "bad signature".to_string() },
+            )
+            .await;
+
+        assert!(tracker.channels.lock().await.get("payment-2").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_drive_settlement_publishes_verifying_then_submitted_then_confirmed() {
+        let mut server = Server::new_async().await;
+        let _verify_mock = server
+            .mock("POST", "/verify")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"isValid": true, "invalidReason": null}).to_string())
+            .create();
+        let _settle_mock = server
+            .mock("POST", "/settle")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({
+                "success": true,
+                "transaction": "0xabc",
+                "network": "base-sepolia",
+                "payer": "0x857b06519E91e3A54538791bDbb0E22373e36b6"
+            }).to_string())
+            .create();
+
+        let facilitator: Arc<dyn Facilitator> = Arc::new(
+            FacilitatorClient::new(FacilitatorConfig::new(server.url())).unwrap(),
+        );
+        let tracker = SettlementStatusTracker::new();
+        let mut receiver = tracker.subscribe("payment-3").await;
+
+        drive_settlement(
+            facilitator,
+            tracker,
+            "payment-3".to_string(),
+            test_payment_payload(),
+            test_payment_requirements(),
+        );
+
+        assert!(matches!(receiver.recv().await.unwrap(), SettlementStatus::Verifying));
+        assert!(matches!(
+            receiver.recv().await.unwrap(),
+            SettlementStatus::Submitted { transaction } if transaction == "0xabc"
+        ));
+        assert!(matches!(
+            receiver.recv().await.unwrap(),
+            SettlementStatus::Confirmed { transaction } if transaction == "0xabc"
+        ));
+    }
+}
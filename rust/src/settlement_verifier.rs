@@ -0,0 +1,322 @@
+//! Discover a settlement on-chain directly, without trusting a facilitator-reported
+//! transaction hash at all
+//!
+//! [`crate::onchain_verification::OnchainSettlementVerifier`] independently confirms a
+//! settlement the facilitator already named a transaction hash for. This module goes
+//! one step further for a caller that doesn't want to trust the facilitator to name
+//! the *right* transaction either: given only a signed [`crate::types::PaymentPayload`],
+//! [`SettlementVerifier::confirm_settlement`] scans forward from a starting block for
+//! the USDC `Transfer(from, to, value)` and/or EIP-3009 `AuthorizationUsed(authorizer,
+//! nonce)` event that authorization could only have produced, the same deposit-detection
+//! technique web3-proxy and Serai use.
+//!
+//! Fetching every log in a block range is wasted work when almost no block in it
+//! settles this one payment, so each candidate block is tested against its own header's
+//! `logsBloom` first — reusing [`crate::onchain_verification`]'s bit-test helpers, which
+//! already implement the 3-bits-per-item scheme this relies on — and only a bloom hit
+//! for that block triggers an `eth_getLogs` call. Blooms never false-negative, so a
+//! block the filter rules out is skipped with certainty, not just likelihood.
+
+use crate::blockchain::BlockchainClient;
+use crate::onchain_verification::{address_topic, bloom_may_contain, decode_hex, transfer_event_topic};
+use crate::types::{NetworkConfig, PaymentPayload};
+use crate::{Result, X402Error};
+use sha3::{Digest, Keccak256};
+use std::time::Duration;
+
+/// `keccak256("AuthorizationUsed(address,bytes32)")`, the topic an EIP-3009
+/// `AuthorizationUsed` log carries as `topics[0]`
+pub fn authorization_used_topic() -> [u8; 32] {
+    Keccak256::digest(b"AuthorizationUsed(address,bytes32)").into()
+}
+
+/// How often [`SettlementVerifier::confirm_settlement`] re-polls for new blocks while
+/// waiting for either the settling transaction to appear or for it to deepen to the
+/// required confirmation count
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Independently discovers the transaction that settled a [`PaymentPayload`] by
+/// scanning the chain itself for the event its authorization could only have produced,
+/// rather than trusting a facilitator-reported hash
+pub struct SettlementVerifier {
+    blockchain: BlockchainClient,
+}
+
+impl SettlementVerifier {
+    /// Scan for settlements by querying `blockchain`'s RPC endpoint
+    pub fn new(blockchain: BlockchainClient) -> Self {
+        Self { blockchain }
+    }
+
+    /// Scan blocks from `from_block` through the chain tip for the `Transfer` or
+    /// `AuthorizationUsed` log `payload`'s authorization could only have produced,
+    /// re-polling every [`POLL_INTERVAL`] until a match has at least `confirmations`
+    /// blocks mined on top of it, or `deadline` elapses.
+    ///
+    /// Returns the settling transaction's hash. Returns [`X402Error::Timeout`] if no
+    /// sufficiently-confirmed match appears within `deadline`.
+    pub async fn confirm_settlement(
+        &self,
+        payload: &PaymentPayload,
+        from_block: u64,
+        confirmations: u64,
+        deadline: Duration,
+    ) -> Result<String> {
+        let auth = &payload.exact_evm()?.authorization;
+        let network_config = NetworkConfig::from_name(&payload.network)
+            .ok_or_else(|| X402Error::invalid_authorization("Unsupported network"))?;
+        let token = &network_config.usdc_contract;
+
+        let transfer_topic0 = transfer_event_topic();
+        let auth_used_topic0 = authorization_used_topic();
+        let from_topic = address_topic(&auth.from)?;
+        let to_topic = address_topic(&auth.to)?;
+        let nonce_topic = decode_hex(&auth.nonce)?;
+        if nonce_topic.len() != 32 {
+            return Err(X402Error::malformed_payload("nonce"));
+        }
+
+        let started = tokio::time::Instant::now();
+        let mut next_block = from_block;
+
+        loop {
+            let network_info = self.blockchain.get_network_info().await?;
+            while next_block <= network_info.latest_block {
+                if let Some(tx_hash) = self
+                    .find_settling_tx(
+                        next_block,
+                        token,
+                        &transfer_topic0,
+                        &auth_used_topic0,
+                        &from_topic,
+                        &to_topic,
+                        &nonce_topic,
+                    )
+                    .await?
+                {
+                    let depth = network_info.latest_block.saturating_sub(next_block) + 1;
+                    if depth >= confirmations {
+                        return Ok(tx_hash);
+                    }
+                }
+                next_block += 1;
+            }
+
+            if started.elapsed() >= deadline {
+                return Err(X402Error::Timeout);
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+
+    /// Test `block_number`'s header `logsBloom` for either event, and only on a hit
+    /// fetch and decode that block's logs to confirm (and find the transaction hash of)
+    /// a real match
+    #[allow(clippy::too_many_arguments)]
+    async fn find_settling_tx(
+        &self,
+        block_number: u64,
+        token: &str,
+        transfer_topic0: &[u8; 32],
+        auth_used_topic0: &[u8; 32],
+        from_topic: &[u8; 32],
+        to_topic: &[u8; 32],
+        nonce_topic: &[u8],
+    ) -> Result<Option<String>> {
+        let Some(block_bloom) = self.blockchain.get_block_bloom(block_number).await? else {
+            return Ok(None);
+        };
+        let bloom_bytes = decode_hex(&block_bloom.logs_bloom)?;
+        let bloom: [u8; 256] = bloom_bytes
+            .try_into()
+            .map_err(|_| X402Error::malformed_payload("logsBloom"))?;
+        let token_bytes = decode_hex(token)?;
+
+        let transfer_may_match = bloom_may_contain(&bloom, &token_bytes)
+            && bloom_may_contain(&bloom, transfer_topic0)
+            && bloom_may_contain(&bloom, from_topic)
+            && bloom_may_contain(&bloom, to_topic);
+        let auth_used_may_match = bloom_may_contain(&bloom, &token_bytes)
+            && bloom_may_contain(&bloom, auth_used_topic0)
+            && bloom_may_contain(&bloom, from_topic)
+            && bloom_may_contain(&bloom, nonce_topic);
+
+        if !transfer_may_match && !auth_used_may_match {
+            return Ok(None);
+        }
+
+        let logs = self
+            .blockchain
+            .get_logs_in_block(block_number, token, &[])
+            .await?;
+
+        let transfer_topic0_hex = hex::encode(transfer_topic0);
+        let auth_used_topic0_hex = hex::encode(auth_used_topic0);
+        let from_topic_hex = hex::encode(from_topic);
+        let to_topic_hex = hex::encode(to_topic);
+        let nonce_topic_hex = hex::encode(nonce_topic);
+
+        for matched in &logs {
+            if matched.log.topics.len() != 3 {
+                continue;
+            }
+            let topic0 = matched.log.topics[0].trim_start_matches("0x");
+            let topic1 = matched.log.topics[1].trim_start_matches("0x");
+            let topic2 = matched.log.topics[2].trim_start_matches("0x");
+
+            let is_transfer = topic0.eq_ignore_ascii_case(&transfer_topic0_hex)
+                && topic1.eq_ignore_ascii_case(&from_topic_hex)
+                && topic2.eq_ignore_ascii_case(&to_topic_hex);
+            let is_authorization_used = topic0.eq_ignore_ascii_case(&auth_used_topic0_hex)
+                && topic1.eq_ignore_ascii_case(&from_topic_hex)
+                && topic2.eq_ignore_ascii_case(&nonce_topic_hex);
+
+            if is_transfer || is_authorization_used {
+                return Ok(Some(matched.transaction_hash.clone()));
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{ExactEvmPayload, ExactEvmPayloadAuthorization};
+
+    const TOKEN: &str = "0x036CbD53842c5426634e7929541eC2318f3dCF7e";
+    const FROM: &str = "0x857b06519E91e3A54538791bDbb0E22373e36b66";
+    const TO: &str = "0x209693Bc6afc0C5328bA36FaF03C514EF312287C";
+    const NONCE: &str = "0x0000000000000000000000000000000000000000000000000000000000000001";
+
+    fn test_payload() -> PaymentPayload {
+        PaymentPayload::new(
+            "exact",
+            "base-sepolia",
+            ExactEvmPayload {
+                signature: format!("0x{}", "11".repeat(65)),
+                authorization: ExactEvmPayloadAuthorization::new(
+                    FROM,
+                    TO,
+                    "1000000",
+                    "0",
+                    "9999999999",
+                    NONCE,
+                ),
+            },
+        )
+    }
+
+    fn set_bloom_bits(bloom: &mut [u8; 256], item: &[u8]) {
+        let hash = Keccak256::digest(item);
+        for pair in 0..3 {
+            let word = u16::from_be_bytes([hash[pair * 2], hash[pair * 2 + 1]]) & 0x07ff;
+            let byte_index = 255 - (word / 8) as usize;
+            let bit_index = (word % 8) as u8;
+            bloom[byte_index] |= 1 << bit_index;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_confirm_settlement_finds_a_matching_transfer_once_confirmed() {
+        let mut server = mockito::Server::new_async().await;
+        let payload = test_payload();
+        let auth = &payload.exact_evm().unwrap().authorization;
+
+        let from_topic = address_topic(&auth.from).unwrap();
+        let to_topic = address_topic(&auth.to).unwrap();
+        let transfer_topic0 = transfer_event_topic();
+
+        let mut bloom = [0u8; 256];
+        for item in [
+            decode_hex(TOKEN).unwrap(),
+            transfer_topic0.to_vec(),
+            from_topic.to_vec(),
+            to_topic.to_vec(),
+        ] {
+            set_bloom_bits(&mut bloom, &item);
+        }
+
+        // eth_chainId / eth_blockNumber / eth_gasPrice batch, consumed by get_network_info
+        let _batch = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex("eth_chainId".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!([
+                    {"jsonrpc": "2.0", "id": 0, "result": "0x14a34"},
+                    {"jsonrpc": "2.0", "id": 1, "result": "0x64"},
+                    {"jsonrpc": "2.0", "id": 2, "result": "0x1"}
+                ])
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let _block = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex("eth_getBlockByNumber".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "result": {
+                        "hash": "0xblockhash",
+                        "logsBloom": format!("0x{}", hex::encode(bloom))
+                    }
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let _logs = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex("eth_getLogs".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "result": [{
+                        "address": TOKEN,
+                        "topics": [
+                            format!("0x{}", hex::encode(transfer_topic0)),
+                            format!("0x{}", hex::encode(from_topic)),
+                            format!("0x{}", hex::encode(to_topic)),
+                        ],
+                        "data": format!("0x{:064x}", 1_000_000u128),
+                        "transactionHash": "0xsettletx",
+                        "blockNumber": "0x64"
+                    }]
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let verifier = SettlementVerifier::new(BlockchainClient::new(
+            server.url(),
+            "base-sepolia".to_string(),
+        ));
+
+        let tx_hash = verifier
+            .confirm_settlement(&payload, 0x64, 1, Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert_eq!(tx_hash, "0xsettletx");
+    }
+
+    #[test]
+    fn test_authorization_used_topic_is_stable() {
+        assert_eq!(
+            hex::encode(authorization_used_topic()),
+            hex::encode(Keccak256::digest(b"AuthorizationUsed(address,bytes32)"))
+        );
+    }
+}
@@ -0,0 +1,277 @@
+//! Facilitator-side verification and settlement for [`schemes::EXACT_SVM`](crate::types::schemes::EXACT_SVM)
+//!
+//! [`crate::real_facilitator::BlockchainFacilitatorClient`] verifies and settles
+//! EVM's `exact` scheme, but [`crate::types::PaymentPayload`] (what its
+//! [`crate::facilitator::Facilitator`] impl takes) carries a concretely-typed
+//! `ExactEvmPayload` — there's no `PaymentPayload`
+//! shape a Solana payload fits into, so a single `ChainBackend`-style trait spanning
+//! both chain families can't be expressed without breaking that wire format. Instead,
+//! [`SolanaFacilitatorClient`] is this module's standalone counterpart, operating
+//! directly on [`crate::types::SolanaPaymentPayload`]: the facilitator-side half of
+//! [`crate::wallet::SolanaWallet`]/[`crate::scheme::SolanaExactScheme`]'s payer-side
+//! signing, completing the pair needed for x402's `exact` scheme to span both EVM and
+//! Solana.
+//!
+//! [`SolanaFacilitatorClient::verify`] is complete — it recovers the payer and runs the
+//! same shape of local checks [`crate::real_facilitator::BlockchainFacilitatorClient`]
+//! runs for EVM. [`SolanaFacilitatorClient::settle`] is not: this workspace has no
+//! `solana-sdk`/`spl-token` dependency, so there's nothing here that can build an SPL
+//! token transfer instruction or submit it to an RPC endpoint. Rather than report a
+//! verified-but-never-broadcast authorization as a successful settlement — which would
+//! grant the paid-for resource without any value actually moving — `settle` returns
+//! [`crate::X402Error::SettlementNotImplemented`]. Wiring up real settlement needs a
+//! `ChainBackend`-style trait with RPC-backed constructors, which is separate,
+//! not-yet-scoped follow-up work.
+
+use crate::types::{PaymentRequirements, SettleResponse, SolanaPaymentPayload, VerifyResponse};
+use crate::wallet::SolanaWallet;
+use crate::{Result, X402Error};
+
+/// Verifies and settles [`schemes::EXACT_SVM`](crate::types::schemes::EXACT_SVM) payments for one Solana network
+pub struct SolanaFacilitatorClient {
+    network: String,
+}
+
+impl SolanaFacilitatorClient {
+    /// Facilitate payments on `network`, e.g. [`crate::types::networks::SOLANA_DEVNET`]
+    pub fn new(network: impl Into<String>) -> Self {
+        Self { network: network.into() }
+    }
+
+    /// Checks every local precondition short of the signature itself: network/scheme
+    /// match, amount, asset (SPL mint) and recipient — the same shape of checks
+    /// [`crate::real_facilitator::BlockchainFacilitatorClient`]'s EVM counterpart runs
+    /// before recovering a signer
+    fn local_checks(
+        &self,
+        payload: &SolanaPaymentPayload,
+        requirements: &PaymentRequirements,
+    ) -> Result<Option<VerifyResponse>> {
+        let auth = &payload.payload.authorization;
+
+        if payload.network != requirements.network {
+            return Ok(Some(VerifyResponse {
+                is_valid: false,
+                invalid_reason: Some(format!(
+                    "Network mismatch: payment network {} != requirements network {}",
+                    payload.network, requirements.network
+                )),
+                payer: Some(auth.from.clone()),
+            }));
+        }
+
+        if payload.scheme != requirements.scheme {
+            return Ok(Some(VerifyResponse {
+                is_valid: false,
+                invalid_reason: Some(format!(
+                    "Scheme mismatch: payment scheme {} != requirements scheme {}",
+                    payload.scheme, requirements.scheme
+                )),
+                payer: Some(auth.from.clone()),
+            }));
+        }
+
+        if auth.mint != requirements.asset {
+            return Ok(Some(VerifyResponse {
+                is_valid: false,
+                invalid_reason: Some(format!(
+                    "Asset mismatch: payment mint {} != requirements asset {}",
+                    auth.mint, requirements.asset
+                )),
+                payer: Some(auth.from.clone()),
+            }));
+        }
+
+        if auth.to != requirements.pay_to {
+            return Ok(Some(VerifyResponse {
+                is_valid: false,
+                invalid_reason: Some(format!(
+                    "Recipient mismatch: {} != {}",
+                    auth.to, requirements.pay_to
+                )),
+                payer: Some(auth.from.clone()),
+            }));
+        }
+
+        let payment_amount: u128 = auth
+            .amount
+            .parse()
+            .map_err(|_| X402Error::invalid_payment_requirements("Invalid payment amount format"))?;
+        let required_amount: u128 = requirements
+            .max_amount_required
+            .parse()
+            .map_err(|_| X402Error::invalid_payment_requirements("Invalid required amount format"))?;
+        if payment_amount < required_amount {
+            return Ok(Some(VerifyResponse {
+                is_valid: false,
+                invalid_reason: Some(format!(
+                    "Insufficient amount: {} < {}",
+                    payment_amount, required_amount
+                )),
+                payer: Some(auth.from.clone()),
+            }));
+        }
+
+        Ok(None)
+    }
+
+    /// Run every local check, then verify the ed25519 signature covers its own
+    /// claimed `from` — [`SolanaWallet::verify_transfer`], the `exact-svm` analog of
+    /// EVM's `ecrecover`-based signature check
+    pub async fn verify(
+        &self,
+        payload: &SolanaPaymentPayload,
+        requirements: &PaymentRequirements,
+    ) -> Result<VerifyResponse> {
+        if let Some(failure) = self.local_checks(payload, requirements)? {
+            return Ok(failure);
+        }
+
+        let auth = &payload.payload.authorization;
+        if !SolanaWallet::verify_transfer(payload)? {
+            return Ok(VerifyResponse {
+                is_valid: false,
+                invalid_reason: Some("Invalid signature".to_string()),
+                payer: Some(auth.from.clone()),
+            });
+        }
+
+        Ok(VerifyResponse {
+            is_valid: true,
+            invalid_reason: None,
+            payer: Some(auth.from.clone()),
+        })
+    }
+
+    /// Verify `payload`, then fail it: there's no RPC client here to submit an SPL
+    /// transfer with — see the module doc comment. A failed verification is reported
+    /// the normal way (`success: false` with a reason), since that's a real, final
+    /// answer about the payment; but a *passing* verification must not be reported as
+    /// settled, since no transfer has been submitted anywhere. Returns
+    /// [`crate::X402Error::SettlementNotImplemented`] in that case instead of
+    /// fabricating a success response.
+    pub async fn settle(
+        &self,
+        payload: &SolanaPaymentPayload,
+        requirements: &PaymentRequirements,
+    ) -> Result<SettleResponse> {
+        let verification = self.verify(payload, requirements).await?;
+        if !verification.is_valid {
+            return Ok(SettleResponse {
+                success: false,
+                error_reason: verification.invalid_reason,
+                transaction: String::new(),
+                network: payload.network.clone(),
+                payer: verification.payer,
+            });
+        }
+
+        Err(X402Error::settlement_not_implemented(
+            "SolanaFacilitatorClient has no RPC client to submit an SPL token transfer with",
+        ))
+    }
+
+    /// The network this client facilitates payments for
+    pub fn network(&self) -> &str {
+        &self.network
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wallet::SolanaWalletFactory;
+
+    fn test_requirements() -> PaymentRequirements {
+        PaymentRequirements::new(
+            crate::types::schemes::EXACT_SVM,
+            crate::types::networks::SOLANA_DEVNET,
+            "1000000",
+            crate::types::networks::get_spl_usdc_mint(crate::types::networks::SOLANA_DEVNET).unwrap(),
+            "11111111111111111111111111111111",
+            "https://example.com/resource",
+            "test resource",
+        )
+    }
+
+    fn signed_payload(wallet: &SolanaWallet, requirements: &PaymentRequirements) -> SolanaPaymentPayload {
+        wallet
+            .sign_transfer(
+                &requirements.pay_to,
+                &requirements.asset,
+                &requirements.max_amount_required,
+                "11111111111111111111111111111111",
+            )
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_verify_accepts_a_correctly_signed_payload() {
+        let wallet = SolanaWalletFactory::from_private_key(&"11".repeat(32), crate::types::networks::SOLANA_DEVNET).unwrap();
+        let requirements = test_requirements();
+        let payload = signed_payload(&wallet, &requirements);
+
+        let client = SolanaFacilitatorClient::new(crate::types::networks::SOLANA_DEVNET);
+        let verification = client.verify(&payload, &requirements).await.unwrap();
+
+        assert!(verification.is_valid);
+        assert_eq!(verification.payer, Some(wallet.address().unwrap()));
+    }
+
+    #[tokio::test]
+    async fn test_verify_rejects_a_signature_from_a_different_wallet() {
+        let wallet = SolanaWalletFactory::from_private_key(&"11".repeat(32), crate::types::networks::SOLANA_DEVNET).unwrap();
+        let other = SolanaWalletFactory::from_private_key(&"22".repeat(32), crate::types::networks::SOLANA_DEVNET).unwrap();
+        let requirements = test_requirements();
+        let mut payload = signed_payload(&wallet, &requirements);
+        // Claim to be `other`'s payer without `other`'s signature.
+        payload.payload.authorization.from = other.address().unwrap();
+
+        let client = SolanaFacilitatorClient::new(crate::types::networks::SOLANA_DEVNET);
+        let verification = client.verify(&payload, &requirements).await.unwrap();
+
+        assert!(!verification.is_valid);
+        assert_eq!(verification.invalid_reason, Some("Invalid signature".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_verify_rejects_an_insufficient_amount() {
+        let wallet = SolanaWalletFactory::from_private_key(&"11".repeat(32), crate::types::networks::SOLANA_DEVNET).unwrap();
+        let requirements = test_requirements();
+        let payload = wallet
+            .sign_transfer(&requirements.pay_to, &requirements.asset, "1", "11111111111111111111111111111111")
+            .unwrap();
+
+        let client = SolanaFacilitatorClient::new(crate::types::networks::SOLANA_DEVNET);
+        let verification = client.verify(&payload, &requirements).await.unwrap();
+
+        assert!(!verification.is_valid);
+        assert!(verification.invalid_reason.unwrap().contains("Insufficient amount"));
+    }
+
+    #[tokio::test]
+    async fn test_settle_fails_with_not_implemented_once_verified() {
+        let wallet = SolanaWalletFactory::from_private_key(&"11".repeat(32), crate::types::networks::SOLANA_DEVNET).unwrap();
+        let requirements = test_requirements();
+        let payload = signed_payload(&wallet, &requirements);
+
+        let client = SolanaFacilitatorClient::new(crate::types::networks::SOLANA_DEVNET);
+        let error = client.settle(&payload, &requirements).await.unwrap_err();
+
+        assert!(matches!(error, X402Error::SettlementNotImplemented { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_settle_fails_without_broadcasting_when_verification_fails() {
+        let wallet = SolanaWalletFactory::from_private_key(&"11".repeat(32), crate::types::networks::SOLANA_DEVNET).unwrap();
+        let requirements = test_requirements();
+        let mut payload = signed_payload(&wallet, &requirements);
+        payload.payload.authorization.to = "22222222222222222222222222222222".to_string();
+
+        let client = SolanaFacilitatorClient::new(crate::types::networks::SOLANA_DEVNET);
+        let settlement = client.settle(&payload, &requirements).await.unwrap();
+
+        assert!(!settlement.success);
+        assert!(settlement.transaction.is_empty());
+    }
+}
@@ -0,0 +1,333 @@
+//! Pay-gated static file serving
+//!
+//! [`generate_paywall_html`](crate::template::generate_paywall_html) renders a paywall
+//! page but leaves mapping requests to files, guessing `Content-Type`, and guarding
+//! against path traversal to the caller. [`StaticPaywallServer`] wires those pieces
+//! together into a drop-in "charge for these files" server: given a root directory and
+//! [`PaymentRequirements`], it resolves an incoming request path to a file under that
+//! root, returns the generated paywall with a 402 when no valid payment is presented,
+//! and otherwise streams the file back with a guessed `Content-Type`.
+
+use crate::error::{Result, X402Error};
+use crate::facilitator::Facilitator;
+use crate::template::{generate_paywall_html, PaywallConfig};
+use crate::types::{PaymentPayload, PaymentRequirements};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+/// The result of resolving and (if needed) verifying payment for one request
+pub enum ServeOutcome {
+    /// No valid payment was presented; the caller should respond 402 with this body
+    PaymentRequired {
+        html: String,
+        requirements: Vec<PaymentRequirements>,
+    },
+    /// Payment verified (or wasn't required); serve this file
+    File { body: Vec<u8>, content_type: &'static str },
+    /// The request path doesn't resolve to a file under the served root
+    NotFound,
+}
+
+/// Serves files under `root`, gated behind the x402 payment described by
+/// `requirements`
+///
+/// Every request path is resolved relative to `root` with [`resolve_path`], which
+/// rejects any path that would escape it (`..` segments, absolute paths, or Windows
+/// path prefixes) by treating it as [`ServeOutcome::NotFound`] rather than surfacing
+/// that it was a traversal attempt.
+pub struct StaticPaywallServer {
+    root: PathBuf,
+    requirements: PaymentRequirements,
+    facilitator: Arc<dyn Facilitator>,
+    paywall_config: Option<PaywallConfig>,
+}
+
+impl StaticPaywallServer {
+    /// Serve files under `root`, charging `requirements` and verifying payments
+    /// against `facilitator`
+    pub fn new(
+        root: impl Into<PathBuf>,
+        requirements: PaymentRequirements,
+        facilitator: Arc<dyn Facilitator>,
+    ) -> Self {
+        Self {
+            root: root.into(),
+            requirements,
+            facilitator,
+            paywall_config: None,
+        }
+    }
+
+    /// Customize the generated paywall's branding/theme
+    pub fn with_paywall_config(mut self, paywall_config: PaywallConfig) -> Self {
+        self.paywall_config = Some(paywall_config);
+        self
+    }
+
+    /// Handle one request: verify `payment_header` (the decoded `X-PAYMENT` header
+    /// value, if any) against this server's requirements, and resolve `request_path`
+    /// to a file under the served root
+    pub async fn serve(&self, request_path: &str, payment_header: Option<&str>) -> Result<ServeOutcome> {
+        let path = match resolve_path(&self.root, request_path) {
+            Some(path) => path,
+            None => return Ok(ServeOutcome::NotFound),
+        };
+
+        if !tokio::fs::metadata(&path)
+            .await
+            .map(|metadata| metadata.is_file())
+            .unwrap_or(false)
+        {
+            return Ok(ServeOutcome::NotFound);
+        }
+
+        if let Some(error) = self.rejection_reason(payment_header).await? {
+            let html = generate_paywall_html(&error, std::slice::from_ref(&self.requirements), self.paywall_config.as_ref());
+            return Ok(ServeOutcome::PaymentRequired {
+                html,
+                requirements: vec![self.requirements.clone()],
+            });
+        }
+
+        let body = tokio::fs::read(&path)
+            .await
+            .map_err(|e| X402Error::config(format!("failed to read {}: {}", path.display(), e)))?;
+        let content_type = guess_mime_type(&path);
+        Ok(ServeOutcome::File { body, content_type })
+    }
+
+    /// `None` if `payment_header` verifies against this server's requirements,
+    /// otherwise `Some(reason)` describing why it didn't
+    async fn rejection_reason(&self, payment_header: Option<&str>) -> Result<Option<String>> {
+        let payment_b64 = match payment_header {
+            Some(value) => value,
+            None => return Ok(Some("X-PAYMENT header is required".to_string())),
+        };
+
+        let payload = match PaymentPayload::from_base64(payment_b64) {
+            Ok(payload) => payload,
+            Err(e) => return Ok(Some(format!("Failed to decode payment: {}", e))),
+        };
+
+        let exact_evm = match payload.exact_evm() {
+            Ok(exact_evm) => exact_evm,
+            Err(scheme_error) => return Ok(Some(scheme_error.to_string())),
+        };
+        if let Err(validity_error) = exact_evm.authorization.check_validity_window() {
+            return Ok(Some(validity_error.to_string()));
+        }
+
+        let verify_response = self.facilitator.verify(&payload, &self.requirements).await?;
+        if !verify_response.is_valid {
+            return Ok(Some(
+                verify_response
+                    .invalid_reason
+                    .unwrap_or_else(|| "Payment verification failed".to_string()),
+            ));
+        }
+
+        Ok(None)
+    }
+}
+
+/// Resolve `request_path` to a path under `root`, returning `None` if any component
+/// would escape it
+///
+/// `request_path` is interpreted relative to `root` regardless of a leading `/`;
+/// `.` segments are skipped, and `..` segments, absolute-path markers, and Windows
+/// drive prefixes all cause rejection rather than being normalized away, since a
+/// normalized `..` could still climb out of `root` before a later segment descends
+/// back in.
+pub fn resolve_path(root: &Path, request_path: &str) -> Option<PathBuf> {
+    let mut resolved = root.to_path_buf();
+    for component in Path::new(request_path.trim_start_matches('/')).components() {
+        match component {
+            Component::Normal(part) => resolved.push(part),
+            Component::CurDir => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    Some(resolved)
+}
+
+/// Guess a file's `Content-Type` from its extension, falling back to
+/// `application/octet-stream` for anything unrecognized
+pub fn guess_mime_type(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+
+    match extension.as_str() {
+        "html" | "htm" => "text/html; charset=utf-8",
+        "css" => "text/css; charset=utf-8",
+        "js" | "mjs" => "text/javascript; charset=utf-8",
+        "json" => "application/json",
+        "txt" => "text/plain; charset=utf-8",
+        "csv" => "text/csv",
+        "xml" => "application/xml",
+        "pdf" => "application/pdf",
+        "wasm" => "application/wasm",
+        "zip" => "application/zip",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "svg" => "image/svg+xml",
+        "webp" => "image/webp",
+        "ico" => "image/x-icon",
+        "bmp" => "image/bmp",
+        "mp4" => "video/mp4",
+        "webm" => "video/webm",
+        "mov" => "video/quicktime",
+        "mp3" => "audio/mpeg",
+        "wav" => "audio/wav",
+        "ogg" => "audio/ogg",
+        "woff" => "font/woff",
+        "woff2" => "font/woff2",
+        "ttf" => "font/ttf",
+        "otf" => "font/otf",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::facilitator::BoxFuture;
+    use crate::types::{SettleResponse, SupportedKinds, VerifyResponse};
+
+    struct MockFacilitator {
+        valid: bool,
+    }
+
+    impl Facilitator for MockFacilitator {
+        fn verify<'a>(
+            &'a self,
+            _payment_payload: &'a PaymentPayload,
+            _payment_requirements: &'a PaymentRequirements,
+        ) -> BoxFuture<'a, Result<VerifyResponse>> {
+            let valid = self.valid;
+            Box::pin(async move {
+                Ok(VerifyResponse {
+                    is_valid: valid,
+                    invalid_reason: if valid { None } else { Some("declined".to_string()) },
+                    payer: Some("0xabc".to_string()),
+                })
+            })
+        }
+
+        fn settle<'a>(
+            &'a self,
+            _payment_payload: &'a PaymentPayload,
+            payment_requirements: &'a PaymentRequirements,
+        ) -> BoxFuture<'a, Result<SettleResponse>> {
+            let network = payment_requirements.network.clone();
+            Box::pin(async move {
+                Ok(SettleResponse {
+                    success: true,
+                    error_reason: None,
+                    transaction: "0xdeadbeef".to_string(),
+                    network,
+                    payer: Some("0xabc".to_string()),
+                })
+            })
+        }
+
+        fn supported(&self) -> BoxFuture<'_, Result<SupportedKinds>> {
+            Box::pin(async move { Ok(SupportedKinds { kinds: Vec::new() }) })
+        }
+    }
+
+    fn test_requirements() -> PaymentRequirements {
+        PaymentRequirements {
+            scheme: "exact".to_string(),
+            network: "base-sepolia".to_string(),
+            max_amount_required: "1000".to_string(),
+            resource: "https://example.com/files/report.pdf".to_string(),
+            description: String::new(),
+            mime_type: None,
+            output_schema: None,
+            pay_to: "0x0000000000000000000000000000000000000000".to_string(),
+            max_timeout_seconds: 60,
+            asset: "0x0000000000000000000000000000000000000000".to_string(),
+            extra: None,
+            payment_uri: None,
+        }
+    }
+
+    #[test]
+    fn test_resolve_path_rejects_parent_dir_traversal() {
+        let root = Path::new("/srv/files");
+        assert_eq!(resolve_path(root, "../../etc/passwd"), None);
+        assert_eq!(resolve_path(root, "/a/../../etc/passwd"), None);
+    }
+
+    #[test]
+    fn test_resolve_path_stays_under_root_for_a_normal_path() {
+        let root = Path::new("/srv/files");
+        assert_eq!(
+            resolve_path(root, "/reports/q1.pdf"),
+            Some(PathBuf::from("/srv/files/reports/q1.pdf"))
+        );
+    }
+
+    #[test]
+    fn test_guess_mime_type_known_and_unknown_extensions() {
+        assert_eq!(guess_mime_type(Path::new("report.pdf")), "application/pdf");
+        assert_eq!(guess_mime_type(Path::new("app.js")), "text/javascript; charset=utf-8");
+        assert_eq!(guess_mime_type(Path::new("data.bin")), "application/octet-stream");
+        assert_eq!(guess_mime_type(Path::new("noext")), "application/octet-stream");
+    }
+
+    #[tokio::test]
+    async fn test_serve_without_payment_returns_paywall() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("report.pdf"), b"%PDF-1.4 fake").unwrap();
+
+        let server = StaticPaywallServer::new(
+            dir.path(),
+            test_requirements(),
+            Arc::new(MockFacilitator { valid: true }),
+        );
+
+        match server.serve("/report.pdf", None).await.unwrap() {
+            ServeOutcome::PaymentRequired { requirements, .. } => {
+                assert_eq!(requirements.len(), 1);
+            }
+            _ => panic!("expected PaymentRequired"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_serve_missing_file_is_not_found() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let server = StaticPaywallServer::new(
+            dir.path(),
+            test_requirements(),
+            Arc::new(MockFacilitator { valid: true }),
+        );
+
+        assert!(matches!(
+            server.serve("/missing.pdf", None).await.unwrap(),
+            ServeOutcome::NotFound
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_serve_traversal_attempt_is_not_found() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let server = StaticPaywallServer::new(
+            dir.path(),
+            test_requirements(),
+            Arc::new(MockFacilitator { valid: true }),
+        );
+
+        assert!(matches!(
+            server.serve("/../etc/passwd", None).await.unwrap(),
+            ServeOutcome::NotFound
+        ));
+    }
+}
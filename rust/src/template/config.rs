@@ -60,6 +60,9 @@ impl PaywallConfigBuilder {
             custom_js: None,
             theme: None,
             branding: None,
+            cta_text: None,
+            faucet_url: None,
+            x402_version: None,
         }
     }
 }
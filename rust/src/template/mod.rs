@@ -5,6 +5,13 @@
 
 pub mod config;
 pub mod paywall;
+pub mod session_token;
+pub mod siwe;
+
+pub use session_token::SessionTokenManager;
+pub use siwe::SiweAuthenticator;
+
+pub use paywall::render_paywall;
 
 use crate::types::PaymentRequirements;
 use serde_json;
@@ -28,6 +35,14 @@ pub struct PaywallConfig {
     pub theme: Option<ThemeConfig>,
     /// Branding configuration
     pub branding: Option<BrandingConfig>,
+    /// Custom call-to-action text shown on the pay button
+    pub cta_text: Option<String>,
+    /// Faucet URL linked from the paywall when a testnet requirement is shown
+    pub faucet_url: Option<String>,
+    /// x402 protocol version to advertise, e.g. the result of
+    /// [`crate::facilitator::FacilitatorClient::negotiate_version`]; defaults to the
+    /// crate's own [`crate::types::X402_VERSION`] when unset
+    pub x402_version: Option<u32>,
 }
 
 /// Theme configuration for the paywall
@@ -127,6 +142,24 @@ impl PaywallConfig {
         self.branding = Some(branding);
         self
     }
+
+    /// Set the call-to-action button text
+    pub fn with_cta_text(mut self, cta_text: impl Into<String>) -> Self {
+        self.cta_text = Some(cta_text.into());
+        self
+    }
+
+    /// Set the faucet URL shown for testnet requirements
+    pub fn with_faucet_url(mut self, faucet_url: impl Into<String>) -> Self {
+        self.faucet_url = Some(faucet_url.into());
+        self
+    }
+
+    /// Advertise `x402_version` instead of the crate's default
+    pub fn with_x402_version(mut self, x402_version: u32) -> Self {
+        self.x402_version = Some(x402_version);
+        self
+    }
 }
 
 impl ThemeConfig {
@@ -328,14 +361,22 @@ fn create_x402_config(
     paywall_config: Option<&PaywallConfig>,
 ) -> serde_json::Value {
     let requirements = payment_requirements.first();
-    let mut display_amount = 0.0;
+    let mut display_amount = "0".to_string();
+    let mut token_symbol = "USDC".to_string();
     let mut current_url = String::new();
     let mut testnet = true;
 
     if let Some(req) = requirements {
-        // Convert atomic amount back to USD (assuming USDC with 6 decimals)
-        if let Ok(amount) = req.max_amount_required.parse::<f64>() {
-            display_amount = amount / 1_000_000.0; // USDC has 6 decimals
+        // Look up this asset's decimals/symbol rather than assuming USDC's 6; fall
+        // back to USDC's own formatting for an unregistered asset so an unrecognized
+        // token still renders something rather than erroring the paywall out.
+        let registry = crate::token_registry::TokenRegistry::new().with_known_usdc_tokens();
+        let metadata = registry.lookup(&req.network, &req.asset);
+        let decimals = metadata.map(|m| m.decimals).unwrap_or(6);
+        token_symbol = metadata.map(|m| m.symbol.clone()).unwrap_or_else(|| "USDC".to_string());
+
+        if let Some(formatted) = crate::token_registry::format_atomic_amount(&req.max_amount_required, decimals) {
+            display_amount = formatted;
         }
         current_url = req.resource.clone();
         testnet = req.network == "base-sepolia";
@@ -346,11 +387,12 @@ fn create_x402_config(
 
     let mut config_json = serde_json::json!({
         "amount": display_amount,
+        "tokenSymbol": token_symbol,
         "paymentRequirements": payment_requirements,
         "testnet": testnet,
         "currentUrl": current_url,
         "error": error,
-        "x402_version": 1,
+        "x402_version": config.x402_version.unwrap_or(crate::types::X402_VERSION),
         "cdpClientKey": config.cdp_client_key.as_deref().unwrap_or(""),
         "appName": config.app_name.as_deref().unwrap_or(""),
         "appLogo": config.app_logo.as_deref().unwrap_or(""),
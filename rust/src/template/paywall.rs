@@ -2,11 +2,230 @@
 //!
 //! This module contains the base HTML template for the x402 paywall.
 
+use super::PaywallConfig;
+use crate::types::PaymentRequirements;
+use crate::X402Error;
+
 /// Get the base HTML template
 pub fn get_base_template() -> &'static str {
     include_str!("paywall.html")
 }
 
+/// Render a fully server-side paywall page for the given payment requirements
+///
+/// Unlike [`super::generate_paywall_html`], which ships a fixed Base/USDC template and
+/// relies on client-side JS to fill in the blanks, this renders a row per accepted
+/// [`PaymentRequirements`] directly into the HTML so a resource priced in multiple
+/// tokens/networks is fully usable without JavaScript.
+pub fn render_paywall(
+    config: &PaywallConfig,
+    payment_requirements: &[PaymentRequirements],
+    error: Option<&X402Error>,
+) -> String {
+    let app_name = config.app_name.as_deref().unwrap_or("Payment Required");
+    let logo = config
+        .branding
+        .as_ref()
+        .and_then(|b| b.company_logo.as_deref())
+        .or(config.app_logo.as_deref());
+    let accent_color = config
+        .theme
+        .as_ref()
+        .map(|t| t.primary_color.as_str())
+        .unwrap_or("#667eea");
+    let cta_text = config.cta_text.as_deref().unwrap_or("Pay now");
+
+    let rows = payment_requirements
+        .iter()
+        .map(render_requirement_row)
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let error_block = error
+        .map(|e| {
+            format!(
+                r#"<div class="error" data-error-type="{}">{}</div>"#,
+                escape_html(e.error_type()),
+                escape_html(&e.to_string())
+            )
+        })
+        .unwrap_or_default();
+
+    let is_testnet = payment_requirements
+        .iter()
+        .any(|req| req.network.contains("sepolia") || req.network.contains("testnet") || req.network.contains("devnet"));
+    let faucet_block = match (&config.faucet_url, is_testnet) {
+        (Some(url), true) => format!(
+            r#"<div class="instructions">Need testnet funds? <a href="{}" target="_blank">Get some here</a>.</div>"#,
+            escape_html(url)
+        ),
+        _ => String::new(),
+    };
+
+    let support_block = config
+        .branding
+        .as_ref()
+        .and_then(|b| b.support_url.as_deref())
+        .map(|url| format!(r#"<a class="support" href="{}">Need help?</a>"#, escape_html(url)))
+        .unwrap_or_default();
+
+    let logo_html = logo
+        .map(|url| format!(r#"<img class="logo" src="{}" alt="{}">"#, escape_html(url), escape_html(app_name)))
+        .unwrap_or_else(|| r#"<div class="logo">💰</div>"#.to_string());
+
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="UTF-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <title>{app_name} - Payment Required</title>
+    <style>
+        :root {{ --accent-color: {accent_color}; }}
+        body {{ font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif; margin: 0; padding: 2rem; background: #f5f5f7; }}
+        .container {{ max-width: 560px; margin: 0 auto; background: white; border-radius: 12px; padding: 2rem; box-shadow: 0 10px 30px rgba(0,0,0,0.08); }}
+        .logo {{ width: 56px; height: 56px; border-radius: 50%; display: flex; align-items: center; justify-content: center; font-size: 1.5rem; background: #f0f0f0; margin-bottom: 1rem; }}
+        .requirement {{ border: 1px solid #eee; border-radius: 8px; padding: 1rem; margin-bottom: 0.75rem; }}
+        .requirement .amount {{ font-weight: 600; }}
+        .pay-button {{ background: var(--accent-color); color: white; border: none; border-radius: 8px; padding: 0.75rem 1.5rem; font-weight: 600; cursor: pointer; }}
+        .error {{ background: #fee; color: #c33; padding: 0.75rem; border-radius: 6px; margin: 1rem 0; }}
+        .instructions {{ background: #e3f2fd; color: #1976d2; padding: 0.75rem; border-radius: 6px; margin: 1rem 0; font-size: 0.9rem; }}
+    </style>
+</head>
+<body>
+    <div class="container">
+        {logo_html}
+        <h1>{app_name}</h1>
+        {error_block}
+        <div class="requirements">
+{rows}
+        </div>
+        {faucet_block}
+        <button class="pay-button">{cta_text}</button>
+        {support_block}
+    </div>
+</body>
+</html>"#,
+    )
+}
+
+/// Render a single `<div class="requirement">` row for one accepted payment option
+fn render_requirement_row(req: &PaymentRequirements) -> String {
+    let network_name = network_display_name(&req.network);
+    let token_symbol = token_symbol(req);
+
+    format!(
+        r#"        <div class="requirement">
+            <div class="amount">{} {}</div>
+            <div class="network">{}</div>
+            <div class="description">{}</div>
+        </div>"#,
+        escape_html(&req.max_amount_required),
+        escape_html(&token_symbol),
+        escape_html(&network_name),
+        escape_html(&req.description),
+    )
+}
+
+/// Human-readable network name, derived from the requirements rather than hardcoded
+/// to Base/Base Sepolia so non-Base networks render sensibly too
+fn network_display_name(network: &str) -> String {
+    match network {
+        "base" => "Base".to_string(),
+        "base-sepolia" => "Base Sepolia".to_string(),
+        "avalanche" => "Avalanche".to_string(),
+        "avalanche-fuji" => "Avalanche Fuji".to_string(),
+        other => other
+            .split(['-', '_'])
+            .map(|part| {
+                let mut chars = part.chars();
+                match chars.next() {
+                    Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+                    None => String::new(),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" "),
+    }
+}
+
+/// Token symbol for a requirement, preferring the scheme-specific `extra.name` field
+/// over assuming USDC
+fn token_symbol(req: &PaymentRequirements) -> String {
+    req.extra
+        .as_ref()
+        .and_then(|extra| extra.get("name"))
+        .and_then(|name| name.as_str())
+        .map(|s| s.to_string())
+        .unwrap_or_else(|| "tokens".to_string())
+}
+
+/// Minimal HTML escaping for untrusted strings interpolated into the paywall
+fn escape_html(input: &str) -> String {
+    input
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::PaymentRequirements;
+
+    fn requirement(network: &str) -> PaymentRequirements {
+        PaymentRequirements::new(
+            "exact",
+            network,
+            "1000000",
+            "0x036CbD53842c5426634e7929541eC2318f3dCF7e",
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            "https://example.com/resource",
+            "Example resource",
+        )
+    }
+
+    #[test]
+    fn test_render_paywall_includes_a_row_per_requirement() {
+        let config = PaywallConfig::default();
+        let requirements = vec![requirement("base"), requirement("avalanche-fuji")];
+
+        let html = render_paywall(&config, &requirements, None);
+
+        assert_eq!(html.matches("class=\"requirement\"").count(), 2);
+        assert!(html.contains("Base"));
+        assert!(html.contains("Avalanche Fuji"));
+    }
+
+    #[test]
+    fn test_render_paywall_injects_error() {
+        let config = PaywallConfig::default();
+        let requirements = vec![requirement("base-sepolia")];
+        let error = X402Error::InsufficientFunds;
+
+        let html = render_paywall(&config, &requirements, Some(&error));
+
+        assert!(html.contains("data-error-type=\"insufficient_funds\""));
+    }
+
+    #[test]
+    fn test_render_paywall_shows_faucet_link_on_testnet_only() {
+        let config = PaywallConfig::default().with_faucet_url("https://faucet.example.com");
+
+        let testnet_html = render_paywall(&config, &[requirement("base-sepolia")], None);
+        assert!(testnet_html.contains("faucet.example.com"));
+
+        let mainnet_html = render_paywall(&config, &[requirement("base")], None);
+        assert!(!mainnet_html.contains("faucet.example.com"));
+    }
+
+    #[test]
+    fn test_network_display_name_falls_back_to_title_case() {
+        assert_eq!(network_display_name("polygon-mainnet"), "Polygon Mainnet");
+    }
+}
+
 /// Get a simple fallback HTML template
 pub fn get_simple_template() -> &'static str {
     r#"<!DOCTYPE html>
@@ -158,8 +377,8 @@ pub fn get_simple_template() -> &'static str {
             const config = window.x402;
             
             // Show payment details
-            if (config.amount > 0) {
-                document.getElementById('amount').textContent = `$${config.amount} USDC`;
+            if (config.amount && config.amount !== '0') {
+                document.getElementById('amount').textContent = `${config.amount} ${config.tokenSymbol}`;
                 document.getElementById('network').textContent = config.testnet ? 'Base Sepolia' : 'Base';
                 document.getElementById('description').textContent = config.paymentRequirements[0]?.description || 'Payment required';
                 document.getElementById('payment-details').style.display = 'block';
@@ -0,0 +1,213 @@
+//! Session token caching and refresh for CDP-backed paywalls
+//!
+//! [`PaywallConfig::with_session_token_endpoint`] only records where to fetch a
+//! session token from; it doesn't fetch or cache anything itself, so a generated
+//! paywall (or any programmatic caller) would otherwise have to hit that endpoint on
+//! every request. [`SessionTokenManager`] fetches a token from the configured
+//! endpoint, caches it alongside its expiry, and transparently refreshes it shortly
+//! before expiry rather than waiting for a request to fail.
+//!
+//! This mirrors [`crate::facilitator::CachedFacilitator`]'s cache-with-ttl shape, but
+//! the ttl here is driven by the token's own `expires_in` rather than a fixed
+//! duration, and refresh happens proactively within a configurable skew window
+//! instead of on a fixed schedule.
+
+use crate::error::{Result, X402Error};
+use serde::Deserialize;
+use std::time::Duration;
+
+/// A session token fetched from the configured endpoint, together with when it
+/// should be treated as stale
+#[derive(Debug, Clone)]
+struct CachedToken {
+    token: String,
+    expires_at: std::time::Instant,
+}
+
+/// The JSON body a session token endpoint is expected to return
+#[derive(Debug, Deserialize)]
+struct SessionTokenResponse {
+    token: String,
+    /// Seconds until the token expires, counted from when the response was received
+    expires_in: u64,
+}
+
+/// Fetches and caches a session token, refreshing it shortly before it expires
+///
+/// `get_valid_token` is safe to call from multiple concurrent tasks: only one of
+/// them will actually hit the endpoint when the cached token is missing or within
+/// the skew window of expiring, the rest observe the refreshed value.
+pub struct SessionTokenManager {
+    endpoint: String,
+    http_client: reqwest::Client,
+    /// How long before actual expiry a token is treated as already expired
+    refresh_skew: Duration,
+    cached: tokio::sync::Mutex<Option<CachedToken>>,
+}
+
+impl SessionTokenManager {
+    /// Create a manager that fetches tokens from `endpoint`, refreshing 30 seconds
+    /// before they expire
+    pub fn new(endpoint: impl Into<String>) -> Self {
+        Self {
+            endpoint: endpoint.into(),
+            http_client: reqwest::Client::new(),
+            refresh_skew: Duration::from_secs(30),
+            cached: tokio::sync::Mutex::new(None),
+        }
+    }
+
+    /// Use `refresh_skew` instead of the default 30-second window before expiry
+    pub fn with_refresh_skew(mut self, refresh_skew: Duration) -> Self {
+        self.refresh_skew = refresh_skew;
+        self
+    }
+
+    /// Use `http_client` instead of a default-constructed [`reqwest::Client`]
+    pub fn with_http_client(mut self, http_client: reqwest::Client) -> Self {
+        self.http_client = http_client;
+        self
+    }
+
+    /// Return a token guaranteed not to expire within `refresh_skew`, fetching a
+    /// fresh one if the cache is empty or stale
+    pub async fn get_valid_token(&self) -> Result<String> {
+        let mut cache = self.cached.lock().await;
+        if let Some(cached) = cache.as_ref() {
+            if cached.expires_at > std::time::Instant::now() + self.refresh_skew {
+                return Ok(cached.token.clone());
+            }
+        }
+
+        let fetched = self.fetch_token().await?;
+        let token = fetched.token.clone();
+        *cache = Some(fetched);
+        Ok(token)
+    }
+
+    /// Force the next [`Self::get_valid_token`] call to fetch a fresh token, even if
+    /// the cached one hasn't entered its refresh window yet
+    pub async fn invalidate(&self) {
+        *self.cached.lock().await = None;
+    }
+
+    async fn fetch_token(&self) -> Result<CachedToken> {
+        let response = self
+            .http_client
+            .post(&self.endpoint)
+            .send()
+            .await?
+            .error_for_status()
+            .map_err(|e| X402Error::facilitator_error(e.to_string()))?;
+
+        let body: SessionTokenResponse = response.json().await?;
+        Ok(CachedToken {
+            token: body.token,
+            expires_at: std::time::Instant::now() + Duration::from_secs(body.expires_in),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_get_valid_token_fetches_and_caches() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/session-token")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"token": "tok_1", "expires_in": 3600}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let manager = SessionTokenManager::new(format!("{}/session-token", server.url()));
+
+        let first = manager.get_valid_token().await.unwrap();
+        let second = manager.get_valid_token().await.unwrap();
+
+        assert_eq!(first, "tok_1");
+        assert_eq!(second, "tok_1");
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_get_valid_token_refetches_within_the_skew_window() {
+        let mut server = mockito::Server::new_async().await;
+        let first_mock = server
+            .mock("POST", "/session-token")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"token": "tok_1", "expires_in": 1}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let second_mock = server
+            .mock("POST", "/session-token")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"token": "tok_2", "expires_in": 3600}"#)
+            .create_async()
+            .await;
+
+        let manager = SessionTokenManager::new(format!("{}/session-token", server.url()))
+            .with_refresh_skew(Duration::from_secs(5));
+
+        let first = manager.get_valid_token().await.unwrap();
+        let second = manager.get_valid_token().await.unwrap();
+
+        assert_eq!(first, "tok_1");
+        assert_eq!(second, "tok_2");
+        first_mock.assert_async().await;
+        second_mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_invalidate_forces_a_refetch() {
+        let mut server = mockito::Server::new_async().await;
+        let first_mock = server
+            .mock("POST", "/session-token")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"token": "tok_1", "expires_in": 3600}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let second_mock = server
+            .mock("POST", "/session-token")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"token": "tok_2", "expires_in": 3600}"#)
+            .create_async()
+            .await;
+
+        let manager = SessionTokenManager::new(format!("{}/session-token", server.url()));
+
+        let first = manager.get_valid_token().await.unwrap();
+        manager.invalidate().await;
+        let second = manager.get_valid_token().await.unwrap();
+
+        assert_eq!(first, "tok_1");
+        assert_eq!(second, "tok_2");
+        first_mock.assert_async().await;
+        second_mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_fetch_failure_surfaces_as_facilitator_error() {
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server
+            .mock("POST", "/session-token")
+            .with_status(500)
+            .create_async()
+            .await;
+
+        let manager = SessionTokenManager::new(format!("{}/session-token", server.url()));
+        let result = manager.get_valid_token().await;
+
+        assert!(result.is_err());
+    }
+}
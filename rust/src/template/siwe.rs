@@ -0,0 +1,236 @@
+//! Sign-In With Ethereum backing for [`crate::template::PaywallConfig::session_token_endpoint`]
+//!
+//! [`crate::template::session_token::SessionTokenManager`] is the *client* side of a
+//! session token: it fetches and caches whatever a `session_token_endpoint` hands
+//! back. This module is the server side a resource operator points that endpoint at —
+//! [`SiweAuthenticator::issue_challenge`] hands a browser wallet an
+//! [EIP-4361](https://eips.ethereum.org/EIPS/eip-4361) message
+//! ([`crate::crypto::signature::SiweMessage`]) to sign, and
+//! [`SiweAuthenticator::verify_and_issue_session`] checks the returned signature —
+//! recovering the signer via the crate's existing EIP-191 `personal_sign`
+//! primitives — and mints a short-lived bearer token tying a session to that address,
+//! the same wallet the paywall will go on to pay from.
+
+use crate::crypto::signature::{generate_nonce, SiweMessage};
+use crate::{Result, X402Error};
+use ethereum_types::Address;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// How long an issued challenge nonce stays redeemable, and how long a session
+/// minted from it lasts afterward
+#[derive(Debug, Clone)]
+pub struct SiweAuthenticatorConfig {
+    /// How long a wallet has to sign and return an issued challenge
+    pub nonce_ttl: Duration,
+    /// How long a session token is valid for after it's minted
+    pub session_ttl: Duration,
+}
+
+impl Default for SiweAuthenticatorConfig {
+    fn default() -> Self {
+        Self {
+            nonce_ttl: Duration::from_secs(5 * 60),
+            session_ttl: Duration::from_secs(60 * 60),
+        }
+    }
+}
+
+/// A session bearer token's address and expiry
+struct Session {
+    address: Address,
+    expires_at: Instant,
+}
+
+/// Issues SIWE challenges, verifies the signed response, and mints/validates the
+/// bearer session tokens that result
+///
+/// Outstanding nonces and live sessions are kept in memory, matching
+/// [`crate::nonce_store::InMemoryNonceStore`]'s single-process scope; a deployment
+/// sharing a `session_token_endpoint` across more than one instance would back this
+/// with the same kind of external store.
+pub struct SiweAuthenticator {
+    config: SiweAuthenticatorConfig,
+    domain: String,
+    chain_id: u64,
+    nonces: Mutex<HashMap<String, Instant>>,
+    sessions: Mutex<HashMap<String, Session>>,
+}
+
+impl SiweAuthenticator {
+    /// Issue challenges and sessions for sign-ins claiming to be on `domain` (the EIP-4361
+    /// `domain` field) and `chain_id`, with the default nonce/session lifetimes
+    pub fn new(domain: impl Into<String>, chain_id: u64) -> Self {
+        Self {
+            config: SiweAuthenticatorConfig::default(),
+            domain: domain.into(),
+            chain_id,
+            nonces: Mutex::new(HashMap::new()),
+            sessions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Use `config` instead of [`SiweAuthenticatorConfig::default`]
+    pub fn with_config(mut self, config: SiweAuthenticatorConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Generate a fresh challenge for `address` to sign in at `uri`, recording its
+    /// nonce as outstanding and redeemable for [`Self::config`]'s `nonce_ttl`
+    pub async fn issue_challenge(&self, address: Address, uri: impl Into<String>) -> SiweMessage {
+        let nonce = SiweMessage::generate_nonce();
+        self.nonces
+            .lock()
+            .await
+            .insert(nonce.clone(), Instant::now() + self.config.nonce_ttl);
+
+        SiweMessage::new(self.domain.clone(), address, uri, self.chain_id, nonce)
+    }
+
+    /// Verify `signature` over a previously-issued `message`, consuming its nonce so it
+    /// can't be redeemed twice, and mint a bearer session token for `message.address` on
+    /// success
+    pub async fn verify_and_issue_session(
+        &self,
+        message: &SiweMessage,
+        signature: &str,
+    ) -> Result<String> {
+        let expires_at = self
+            .nonces
+            .lock()
+            .await
+            .remove(&message.nonce)
+            .ok_or_else(|| {
+                X402Error::invalid_authorization("Unknown or already-redeemed SIWE nonce")
+            })?;
+
+        if Instant::now() > expires_at {
+            return Err(X402Error::invalid_authorization(
+                "SIWE challenge has expired",
+            ));
+        }
+
+        if !message.verify(signature, &message.nonce)? {
+            return Err(X402Error::invalid_signature(
+                "SIWE signature does not match the claimed address",
+            ));
+        }
+
+        let token = format!("siwe_{}", hex::encode(generate_nonce().as_bytes()));
+        self.sessions.lock().await.insert(
+            token.clone(),
+            Session {
+                address: message.address,
+                expires_at: Instant::now() + self.config.session_ttl,
+            },
+        );
+
+        Ok(token)
+    }
+
+    /// Return the address a previously-issued bearer `token` authenticates, if it's
+    /// still within its session lifetime
+    pub async fn validate_session_token(&self, token: &str) -> Result<Address> {
+        let sessions = self.sessions.lock().await;
+        let session = sessions
+            .get(token)
+            .ok_or_else(|| X402Error::invalid_authorization("Unknown session token"))?;
+
+        if Instant::now() > session.expires_at {
+            return Err(X402Error::invalid_authorization(
+                "Session token has expired",
+            ));
+        }
+
+        Ok(session.address)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::signature::{sign_personal_message, LocalSigner};
+
+    const PRIVATE_KEY: &str = "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+    const OTHER_PRIVATE_KEY: &str = "0x1111111111111111111111111111111111111111111111111111111111111111";
+
+    #[tokio::test]
+    async fn test_issue_and_redeem_a_challenge_mints_a_session_for_the_signer() {
+        let authenticator = SiweAuthenticator::new("example.com", 84532);
+        let address = LocalSigner::from_private_key(PRIVATE_KEY).unwrap().address().unwrap();
+
+        let message = authenticator
+            .issue_challenge(address, "https://example.com/login")
+            .await;
+        let signature = sign_personal_message(message.to_string().as_bytes(), PRIVATE_KEY).unwrap();
+
+        let token = authenticator
+            .verify_and_issue_session(&message, &signature)
+            .await
+            .unwrap();
+
+        let resolved = authenticator.validate_session_token(&token).await.unwrap();
+        assert_eq!(resolved, address);
+    }
+
+    #[tokio::test]
+    async fn test_a_nonce_cannot_be_redeemed_twice() {
+        let authenticator = SiweAuthenticator::new("example.com", 84532);
+        let address = LocalSigner::from_private_key(PRIVATE_KEY).unwrap().address().unwrap();
+
+        let message = authenticator
+            .issue_challenge(address, "https://example.com/login")
+            .await;
+        let signature = sign_personal_message(message.to_string().as_bytes(), PRIVATE_KEY).unwrap();
+
+        authenticator
+            .verify_and_issue_session(&message, &signature)
+            .await
+            .unwrap();
+        let result = authenticator.verify_and_issue_session(&message, &signature).await;
+
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_an_unknown_nonce_is_rejected() {
+        let authenticator = SiweAuthenticator::new("example.com", 84532);
+        let address = LocalSigner::from_private_key(PRIVATE_KEY).unwrap().address().unwrap();
+
+        let forged = SiweMessage::new(
+            "example.com",
+            address,
+            "https://example.com/login",
+            84532,
+            "never-issued-nonce",
+        );
+        let signature = sign_personal_message(forged.to_string().as_bytes(), PRIVATE_KEY).unwrap();
+
+        let result = authenticator.verify_and_issue_session(&forged, &signature).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_a_signature_from_the_wrong_wallet_is_rejected() {
+        let authenticator = SiweAuthenticator::new("example.com", 84532);
+        let address = LocalSigner::from_private_key(PRIVATE_KEY).unwrap().address().unwrap();
+
+        let message = authenticator
+            .issue_challenge(address, "https://example.com/login")
+            .await;
+        // Signed by a different key than the one the message claims as `address`.
+        let signature = sign_personal_message(message.to_string().as_bytes(), OTHER_PRIVATE_KEY).unwrap();
+
+        let result = authenticator.verify_and_issue_session(&message, &signature).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_validate_session_token_rejects_an_unknown_token() {
+        let authenticator = SiweAuthenticator::new("example.com", 84532);
+        let result = authenticator.validate_session_token("not-a-real-token").await;
+        assert!(result.is_err());
+    }
+}
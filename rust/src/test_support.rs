@@ -0,0 +1,200 @@
+//! Local EVM integration-test harness (Anvil devnet)
+//!
+//! Every test elsewhere in this crate that touches "blockchain" behavior only
+//! asserts on static config — network names, hardcoded USDC addresses — because
+//! nothing here boots an actual chain. This module closes that gap the same way
+//! `bdk`'s `TestClient` launches a throwaway `bitcoind`/`electrsd` pair, or
+//! Solana's `TestValidator` boots a disposable ledger: [`AnvilInstance`] spawns a
+//! real `anvil` binary as a child process, and [`TestEvmNetwork`] deploys a mock
+//! EIP-3009 token on it and hands back a [`crate::blockchain::BlockchainClientFactory::custom`]
+//! / [`crate::real_facilitator::BlockchainFacilitatorFactory::custom`] pair
+//! already pointed at that devnet, so `verify`/`settle` can be exercised against
+//! real EVM execution instead of only equality checks on config structs.
+//!
+//! Gated behind the `test_support` feature so the `anvil` dependency (an
+//! external binary, not a crate) never factors into a production build; CI
+//! installs it the same way the `ethers-rs` install script does.
+//!
+//! This crate has no Solidity toolchain of its own, so [`TestEvmNetwork::deploy_contract`]
+//! takes the contract's compiled creation bytecode as a parameter rather than
+//! embedding it — CI produces it (e.g. via `forge build`) from a minimal
+//! `FiatTokenV2`-style contract implementing `transferWithAuthorization`/
+//! `authorizationState`/`balanceOf` and passes the resulting hex blob in.
+
+#![cfg(feature = "test_support")]
+
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Command, Stdio};
+
+use serde_json::json;
+
+use crate::blockchain::{BlockchainClient, BlockchainClientFactory};
+use crate::real_facilitator::{BlockchainFacilitatorClient, BlockchainFacilitatorConfig, BlockchainFacilitatorFactory};
+use crate::{Result, X402Error};
+
+/// One of Anvil's deterministic default dev accounts — the same keys `anvil`/
+/// `hardhat` derive from the fixed mnemonic "test test test test test test test
+/// test test test test junk" and pre-fund with 10000 ETH on every fresh devnet
+#[derive(Debug, Clone, Copy)]
+pub struct DevAccount {
+    /// Checksummed address
+    pub address: &'static str,
+    /// Hex-encoded private key
+    pub private_key: &'static str,
+}
+
+/// Anvil's default accounts 0 and 1 — enough to play payer and relayer in an
+/// end-to-end test without anyone having to generate or fund a key by hand
+pub const DEV_ACCOUNTS: [DevAccount; 2] = [
+    DevAccount {
+        address: "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266",
+        private_key: "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80",
+    },
+    DevAccount {
+        address: "0x70997970C51812dc3A010C7d01b50e0d17dc79C8",
+        private_key: "0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690",
+    },
+];
+
+/// A running `anvil` devnet, killed when dropped
+pub struct AnvilInstance {
+    child: Child,
+    http_endpoint: String,
+}
+
+impl AnvilInstance {
+    /// Launch `anvil` on an OS-assigned port, blocking until it reports its
+    /// listening address on stdout
+    pub fn spawn() -> Result<Self> {
+        let mut child = Command::new("anvil")
+            .arg("--port")
+            .arg("0")
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| X402Error::config(format!("failed to launch anvil: {e} (is it installed and on PATH?)")))?;
+
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| X402Error::config("anvil produced no stdout to read its listening address from"))?;
+        let mut reader = BufReader::new(stdout);
+        let mut line = String::new();
+        let mut endpoint = None;
+
+        // anvil prints a fixed startup banner ending in "Listening on 127.0.0.1:<port>"
+        // once the devnet is ready to accept RPC calls
+        while reader.read_line(&mut line).unwrap_or(0) > 0 {
+            if let Some(addr) = line.trim().strip_prefix("Listening on ") {
+                endpoint = Some(format!("http://{addr}"));
+                break;
+            }
+            line.clear();
+        }
+
+        let http_endpoint = match endpoint {
+            Some(endpoint) => endpoint,
+            None => {
+                let _ = child.kill();
+                return Err(X402Error::config("anvil exited before reporting a listening address"));
+            }
+        };
+
+        Ok(Self { child, http_endpoint })
+    }
+
+    /// The devnet's HTTP JSON-RPC endpoint, e.g. `http://127.0.0.1:54231`
+    pub fn http_endpoint(&self) -> &str {
+        &self.http_endpoint
+    }
+}
+
+impl Drop for AnvilInstance {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// A throwaway EVM devnet with a deployed mock USDC-style token, ready to hand
+/// out pre-configured [`BlockchainClient`]/[`BlockchainFacilitatorClient`]
+/// instances for end-to-end `verify`/`settle` tests
+pub struct TestEvmNetwork {
+    anvil: AnvilInstance,
+    network: String,
+    /// Address the mock token was deployed to, set by [`Self::deploy_contract`]
+    pub usdc_address: Option<String>,
+}
+
+impl TestEvmNetwork {
+    /// Boot a fresh devnet labeled `network` (a name of your choosing — this
+    /// never has to match a real x402 network, it's only used to tag the
+    /// [`BlockchainClient`]/[`BlockchainFacilitatorClient`] this hands back)
+    pub fn spawn(network: impl Into<String>) -> Result<Self> {
+        Ok(Self {
+            anvil: AnvilInstance::spawn()?,
+            network: network.into(),
+            usdc_address: None,
+        })
+    }
+
+    /// A [`BlockchainClient`] pointed at this devnet
+    pub fn blockchain_client(&self) -> BlockchainClient {
+        BlockchainClientFactory::custom(self.anvil.http_endpoint(), &self.network)
+    }
+
+    /// A [`BlockchainFacilitatorClient`] pointed at this devnet, signing
+    /// settlement transactions with `relayer.private_key`
+    pub fn facilitator_client(&self, relayer: DevAccount) -> Result<BlockchainFacilitatorClient> {
+        BlockchainFacilitatorFactory::custom(BlockchainFacilitatorConfig {
+            rpc_url: Some(self.anvil.http_endpoint().to_string()),
+            network: self.network.clone(),
+            relayer_private_key: Some(relayer.private_key.to_string()),
+            ..Default::default()
+        })
+    }
+
+    /// Deploy `creation_bytecode` (hex, `0x`-prefixed) from `deployer` and
+    /// return the resulting contract address. `deployer` must be one of
+    /// [`DEV_ACCOUNTS`] — anvil keeps its default accounts unlocked, so the
+    /// devnet signs on the caller's behalf and no local signing is required here.
+    pub async fn deploy_contract(&mut self, deployer: DevAccount, creation_bytecode: &str) -> Result<String> {
+        let client = self.blockchain_client();
+        let responses = client
+            .batch(&[(
+                "eth_sendTransaction",
+                json!([{
+                    "from": deployer.address,
+                    "data": creation_bytecode,
+                }]),
+            )])
+            .await?;
+        let tx_hash = responses[0]
+            .get("result")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| X402Error::config("anvil did not return a deployment transaction hash"))?
+            .to_string();
+
+        // anvil mines instantly, so the receipt (and its contractAddress) is
+        // available immediately after the request above returns
+        let receipt_responses = client
+            .batch(&[("eth_getTransactionReceipt", json!([tx_hash]))])
+            .await?;
+        let address = receipt_responses[0]
+            .get("result")
+            .and_then(|result| result.get("contractAddress"))
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| X402Error::config("deployment receipt had no contractAddress"))?
+            .to_string();
+        self.usdc_address = Some(address.clone());
+        Ok(address)
+    }
+
+    /// Set `address`'s ETH balance directly via anvil's `anvil_setBalance`,
+    /// bypassing the usual "send a funding transaction" dance
+    pub async fn fund(&self, address: &str, wei_hex: &str) -> Result<()> {
+        let client = self.blockchain_client();
+        client.batch(&[("anvil_setBalance", json!([address, wei_hex]))]).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,166 @@
+//! Multi-asset token metadata registry
+//!
+//! [`crate::template::create_x402_config`] (display formatting) and
+//! [`crate::wallet`] (EIP-712 domain construction) both used to hardcode "this is
+//! USDC, which has 6 decimals and an EIP-712 domain of `(\"USD Coin\", \"2\")`" —
+//! fine as long as the only asset x402 ever charged in was USDC, but wrong for any
+//! other stablecoin or an 18-decimal token. [`TokenRegistry`] maps
+//! `(network, token_contract_address)` to the metadata needed to display and sign for
+//! an arbitrary ERC-20, seeded with this crate's known USDC deployments but
+//! extensible at runtime via [`TokenRegistry::with_token`].
+
+use std::collections::HashMap;
+
+/// Metadata describing one ERC-20 token on one network
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenMetadata {
+    /// Ticker shown in the paywall, e.g. `"USDC"`
+    pub symbol: String,
+    /// Full name shown in the paywall, e.g. `"USD Coin"`
+    pub name: String,
+    /// Smallest-unit exponent; `max_amount_required` is in units of `10^-decimals`
+    pub decimals: u8,
+    /// EIP-712 domain `name` this token's `transferWithAuthorization` expects
+    pub eip712_name: String,
+    /// EIP-712 domain `version` this token's `transferWithAuthorization` expects
+    pub eip712_version: String,
+}
+
+/// Maps `(network, token_contract_address)` to [`TokenMetadata`]
+///
+/// Contract addresses are matched case-insensitively, since callers pass them through
+/// in whatever checksum casing they arrived in off the wire.
+#[derive(Debug, Clone, Default)]
+pub struct TokenRegistry {
+    tokens: HashMap<(String, String), TokenMetadata>,
+}
+
+impl TokenRegistry {
+    /// An empty registry with no known tokens
+    pub fn new() -> Self {
+        Self {
+            tokens: HashMap::new(),
+        }
+    }
+
+    /// Register `metadata` for `contract_address` on `network`, overwriting any
+    /// existing entry for that pair
+    pub fn with_token(
+        mut self,
+        network: impl Into<String>,
+        contract_address: impl Into<String>,
+        metadata: TokenMetadata,
+    ) -> Self {
+        self.tokens
+            .insert((network.into(), contract_address.into().to_lowercase()), metadata);
+        self
+    }
+
+    /// Look up the metadata for `contract_address` on `network`
+    pub fn lookup(&self, network: &str, contract_address: &str) -> Option<&TokenMetadata> {
+        self.tokens
+            .get(&(network.to_string(), contract_address.to_lowercase()))
+    }
+
+    /// USDC metadata shared by every network it's deployed on
+    fn usdc_metadata() -> TokenMetadata {
+        TokenMetadata {
+            symbol: "USDC".to_string(),
+            name: "USD Coin".to_string(),
+            decimals: 6,
+            eip712_name: "USD Coin".to_string(),
+            eip712_version: "2".to_string(),
+        }
+    }
+
+    /// Register this crate's known USDC deployments, matching
+    /// [`crate::blockchain::BlockchainClient::get_usdc_contract_address`]
+    pub fn with_known_usdc_tokens(self) -> Self {
+        self.with_token("base-sepolia", "0x036CbD53842c5426634e7929541eC2318f3dCF7e", Self::usdc_metadata())
+            .with_token("base", "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913", Self::usdc_metadata())
+            .with_token("avalanche-fuji", "0x5425890298aed601595a70AB815c96711a31Bc65", Self::usdc_metadata())
+            .with_token("avalanche", "0xB97EF9Ef8734C71904D8002F8b6Bc66Dd9c48a6E", Self::usdc_metadata())
+    }
+}
+
+/// Format a raw atomic `amount` (as it appears in `max_amount_required`) into a
+/// decimal string with `decimals` places, using integer arithmetic throughout so an
+/// 18-decimal token's amount isn't rounded the way `f64` division would.
+pub fn format_atomic_amount(amount: &str, decimals: u8) -> Option<String> {
+    let amount: u128 = amount.parse().ok()?;
+    let base = 10u128.checked_pow(decimals as u32)?;
+    let whole = amount / base;
+    let fraction = amount % base;
+
+    if fraction == 0 {
+        return Some(whole.to_string());
+    }
+
+    let digits = fraction.to_string();
+    let padded = format!("{}{}", "0".repeat(decimals as usize - digits.len()), digits);
+    Some(format!("{}.{}", whole, padded.trim_end_matches('0')))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_known_usdc_tokens_resolve_on_every_seeded_network() {
+        let registry = TokenRegistry::new().with_known_usdc_tokens();
+
+        let metadata = registry
+            .lookup("base", "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913")
+            .expect("base USDC should be registered");
+        assert_eq!(metadata.symbol, "USDC");
+        assert_eq!(metadata.decimals, 6);
+    }
+
+    #[test]
+    fn test_lookup_is_case_insensitive() {
+        let registry = TokenRegistry::new().with_known_usdc_tokens();
+        assert!(registry
+            .lookup("base", "0x833589fcd6edb6e08f4c7c32d4f71b54bda02913")
+            .is_some());
+    }
+
+    #[test]
+    fn test_lookup_misses_an_unregistered_token() {
+        let registry = TokenRegistry::new().with_known_usdc_tokens();
+        assert!(registry.lookup("base", "0x0000000000000000000000000000000000000000").is_none());
+    }
+
+    #[test]
+    fn test_with_token_registers_a_custom_asset() {
+        let registry = TokenRegistry::new().with_token(
+            "base",
+            "0x1111111111111111111111111111111111111111",
+            TokenMetadata {
+                symbol: "DAI".to_string(),
+                name: "Dai Stablecoin".to_string(),
+                decimals: 18,
+                eip712_name: "Dai Stablecoin".to_string(),
+                eip712_version: "1".to_string(),
+            },
+        );
+
+        let metadata = registry
+            .lookup("base", "0x1111111111111111111111111111111111111111")
+            .unwrap();
+        assert_eq!(metadata.symbol, "DAI");
+        assert_eq!(metadata.decimals, 18);
+    }
+
+    #[test]
+    fn test_format_atomic_amount_handles_six_and_eighteen_decimals() {
+        assert_eq!(format_atomic_amount("1500000", 6).unwrap(), "1.5");
+        assert_eq!(format_atomic_amount("1000000", 6).unwrap(), "1");
+        assert_eq!(format_atomic_amount("1000000000000000000", 18).unwrap(), "1");
+        assert_eq!(format_atomic_amount("1500000000000000000", 18).unwrap(), "1.5");
+    }
+
+    #[test]
+    fn test_format_atomic_amount_rejects_non_numeric_input() {
+        assert!(format_atomic_amount("not-a-number", 6).is_none());
+    }
+}
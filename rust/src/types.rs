@@ -21,6 +21,14 @@ pub type AuthHeadersFnBox = Box<AuthHeadersFn>;
 /// x402 protocol version
 pub const X402_VERSION: u32 = 1;
 
+/// Range of x402 protocol versions this client is able to speak
+///
+/// [`crate::facilitator::FacilitatorClient::negotiate_version`] intersects this
+/// against a facilitator's own advertised versions (from its `/supported` document)
+/// to pick the highest one both sides can use, so a future protocol bump doesn't
+/// silently break against an older facilitator deep inside settlement.
+pub const SUPPORTED_VERSIONS: std::ops::RangeInclusive<u32> = 1..=X402_VERSION;
+
 /// Network configuration for x402 payments
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Network {
@@ -39,6 +47,10 @@ pub struct NetworkConfig {
     pub name: String,
     /// Whether this is a testnet
     pub is_testnet: bool,
+    /// USDC's decimal places on this chain; 6 on every network this crate knows about
+    /// today, but kept per-network rather than hard-coded since a non-USDC asset (or a
+    /// future non-EVM chain) won't necessarily agree
+    pub decimals: u8,
 }
 
 impl NetworkConfig {
@@ -49,6 +61,7 @@ impl NetworkConfig {
             usdc_contract: "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913".to_string(),
             name: "base".to_string(),
             is_testnet: false,
+            decimals: 6,
         }
     }
 
@@ -59,17 +72,91 @@ impl NetworkConfig {
             usdc_contract: "0x036CbD53842c5426634e7929541eC2318f3dCF7e".to_string(),
             name: "base-sepolia".to_string(),
             is_testnet: true,
+            decimals: 6,
+        }
+    }
+
+    /// Avalanche C-Chain mainnet configuration
+    pub fn avalanche_mainnet() -> Self {
+        Self {
+            chain_id: 43114,
+            usdc_contract: "0xB97EF9Ef8734C71904D8002F8b6Bc66Dd9c48a6E".to_string(),
+            name: "avalanche".to_string(),
+            is_testnet: false,
+            decimals: 6,
+        }
+    }
+
+    /// Avalanche Fuji testnet configuration
+    pub fn avalanche_fuji() -> Self {
+        Self {
+            chain_id: 43113,
+            usdc_contract: "0x5425890298aed601595a70AB815c96711a31Bc65".to_string(),
+            name: "avalanche-fuji".to_string(),
+            is_testnet: true,
+            decimals: 6,
         }
     }
 
     /// Get network config by name
+    ///
+    /// Routed through [`crate::network_registry::NetworkRegistry`] rather than a
+    /// direct `match` on the four networks above, so a network registered there
+    /// (including one added at runtime, not just the crate's built-in seed list)
+    /// resolves here too instead of needing a matching edit in both places.
     pub fn from_name(name: &str) -> Option<Self> {
-        match name {
-            "base" => Some(Self::base_mainnet()),
-            "base-sepolia" => Some(Self::base_sepolia()),
-            _ => None,
+        let registry = crate::network_registry::NetworkRegistry::new().with_known_networks();
+        let entry = registry.lookup(name)?;
+        let asset = entry.primary_asset()?;
+        Some(Self {
+            chain_id: entry.chain_id,
+            usdc_contract: asset.address.clone(),
+            name: name.to_string(),
+            is_testnet: entry.is_testnet,
+            decimals: asset.metadata.decimals,
+        })
+    }
+}
+
+/// A network [`crate::middleware::PaymentMiddlewareConfig`] can price its primary
+/// `amount`/`pay_to` option against, beyond the historical Base-only `testnet` toggle.
+/// Room for additional EVM chains (and eventually non-EVM ones) lives here rather than
+/// in a raw `&str`, so a typo'd network name fails to compile instead of failing a
+/// request at runtime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SupportedNetwork {
+    BaseMainnet,
+    BaseSepolia,
+    AvalancheMainnet,
+    AvalancheFuji,
+}
+
+impl SupportedNetwork {
+    /// The network identifier string this variant resolves to, e.g. for
+    /// [`PaymentRequirements::network`]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::BaseMainnet => networks::BASE_MAINNET,
+            Self::BaseSepolia => networks::BASE_SEPOLIA,
+            Self::AvalancheMainnet => networks::AVALANCHE_MAINNET,
+            Self::AvalancheFuji => networks::AVALANCHE_FUJI,
+        }
+    }
+
+    /// This variant's chain id, default asset contract and token decimals
+    pub fn config(&self) -> NetworkConfig {
+        match self {
+            Self::BaseMainnet => NetworkConfig::base_mainnet(),
+            Self::BaseSepolia => NetworkConfig::base_sepolia(),
+            Self::AvalancheMainnet => NetworkConfig::avalanche_mainnet(),
+            Self::AvalancheFuji => NetworkConfig::avalanche_fuji(),
         }
     }
+
+    /// Whether this variant is a testnet
+    pub fn is_testnet(&self) -> bool {
+        self.config().is_testnet
+    }
 }
 
 impl Network {
@@ -129,6 +216,12 @@ pub struct PaymentRequirements {
     /// Scheme-specific additional information
     #[serde(skip_serializing_if = "Option::is_none")]
     pub extra: Option<Value>,
+    /// Canonical payment-request URI for this entry (see [`Self::to_payment_uri`]),
+    /// populated automatically when [`crate::middleware::PaymentMiddleware`] builds
+    /// its 402 body so a wallet or QR-code renderer can deep-link directly instead of
+    /// re-deriving it from the other fields
+    #[serde(rename = "paymentUri", skip_serializing_if = "Option::is_none")]
+    pub payment_uri: Option<String>,
 }
 
 impl PaymentRequirements {
@@ -154,6 +247,7 @@ impl PaymentRequirements {
             output_schema: None,
             max_timeout_seconds: 60,
             extra: None,
+            payment_uri: None,
         }
     }
 
@@ -167,6 +261,67 @@ impl PaymentRequirements {
         Ok(())
     }
 
+    /// Set the static BOLT12 offer string in the extra field for a `lightning-bolt12`
+    /// requirements entry, so clients know what to decode and request an invoice against
+    pub fn set_lightning_offer(&mut self, offer: impl Into<String>) -> crate::Result<()> {
+        let mut lightning_info = HashMap::new();
+        lightning_info.insert("offer".to_string(), offer.into());
+
+        self.extra = Some(serde_json::to_value(lightning_info)?);
+        Ok(())
+    }
+
+    /// Get the BOLT12 offer string previously set by [`Self::set_lightning_offer`]
+    pub fn lightning_offer(&self) -> Option<&str> {
+        self.extra.as_ref()?.get("offer")?.as_str()
+    }
+
+    /// Set the BOLT11 invoice string and its payment hash in the extra field for a
+    /// `lightning-bolt11` requirements entry, so the client knows exactly what invoice
+    /// to pay (unlike BOLT12's reusable offer, a BOLT11 invoice is already scoped to
+    /// one amount and one payment)
+    pub fn set_lightning_bolt11_invoice(
+        &mut self,
+        bolt11: impl Into<String>,
+        payment_hash: impl Into<String>,
+    ) -> crate::Result<()> {
+        let mut lightning_info = HashMap::new();
+        lightning_info.insert("bolt11".to_string(), bolt11.into());
+        lightning_info.insert("paymentHash".to_string(), payment_hash.into());
+
+        self.extra = Some(serde_json::to_value(lightning_info)?);
+        Ok(())
+    }
+
+    /// Get the `(bolt11, payment_hash)` pair previously set by
+    /// [`Self::set_lightning_bolt11_invoice`]
+    pub fn lightning_bolt11_invoice(&self) -> Option<(&str, &str)> {
+        let extra = self.extra.as_ref()?;
+        Some((extra.get("bolt11")?.as_str()?, extra.get("paymentHash")?.as_str()?))
+    }
+
+    /// Merge the URL the facilitator should POST its final settlement result to, for a
+    /// payment settled asynchronously (see [`crate::async_settlement`]), into the extra
+    /// field. Unlike [`Self::set_lightning_offer`]/[`Self::set_usdc_info`], this merges
+    /// into any `extra` already present rather than overwriting it, since a resource
+    /// often needs both the scheme-specific `extra` fields above and this one
+    /// simultaneously (e.g. a redirect `continueUri` set separately by
+    /// [`crate::middleware::PaymentMiddlewareConfig::with_continue_uri`])
+    pub fn set_async_settlement_notify_uri(
+        &mut self,
+        notify_uri: impl Into<String>,
+    ) -> crate::Result<()> {
+        let mut extra = self.extra.take().unwrap_or_else(|| serde_json::json!({}));
+        extra["asyncNotifyUri"] = serde_json::Value::String(notify_uri.into());
+        self.extra = Some(extra);
+        Ok(())
+    }
+
+    /// Get the URL previously set by [`Self::set_async_settlement_notify_uri`]
+    pub fn async_settlement_notify_uri(&self) -> Option<&str> {
+        self.extra.as_ref()?.get("asyncNotifyUri")?.as_str()
+    }
+
     /// Get the amount as a decimal
     pub fn amount_as_decimal(&self) -> crate::Result<Decimal> {
         self.max_amount_required
@@ -180,6 +335,182 @@ impl PaymentRequirements {
         let divisor = Decimal::from(10u64.pow(decimals as u32));
         Ok(amount / divisor)
     }
+
+    /// Build a canonical payment-request URI for this entry, suitable for handing to
+    /// a wallet out of band or rendering as a QR code instead of requiring the
+    /// client to parse the full JSON object.
+    ///
+    /// For [`schemes::EXACT`] on a network [`NetworkConfig::from_name`] recognizes,
+    /// this is an [ERC-681](https://eips.ethereum.org/EIPS/eip-681) token-transfer
+    /// URI: `ethereum:<asset>@<chainId>/transfer?address=<pay_to>&uint256=<amount>`.
+    /// For [`schemes::LIGHTNING_BOLT11`], it's the bare `lightning:<bolt11>` URI the
+    /// BOLT11/BIP21 convention uses, built from the invoice set by
+    /// [`Self::set_lightning_bolt11_invoice`]. Any other scheme/network combination
+    /// has no defined URI form here and returns an error.
+    pub fn to_payment_uri(&self) -> crate::Result<String> {
+        match self.scheme.as_str() {
+            schemes::EXACT => {
+                let network_config = NetworkConfig::from_name(&self.network).ok_or_else(|| {
+                    crate::X402Error::invalid_payment_requirements(format!(
+                        "no chain id known for network {}",
+                        self.network
+                    ))
+                })?;
+                Ok(format!(
+                    "ethereum:{}@{}/transfer?address={}&uint256={}",
+                    self.asset, network_config.chain_id, self.pay_to, self.max_amount_required
+                ))
+            }
+            schemes::LIGHTNING_BOLT11 => {
+                let (bolt11, _payment_hash) = self.lightning_bolt11_invoice().ok_or_else(|| {
+                    crate::X402Error::invalid_payment_requirements(
+                        "lightning-bolt11 requirements are missing their invoice",
+                    )
+                })?;
+                Ok(format!("lightning:{}", bolt11))
+            }
+            other => Err(crate::X402Error::invalid_payment_requirements(format!(
+                "no payment URI form defined for scheme {}",
+                other
+            ))),
+        }
+    }
+
+    /// Parse a URI produced by [`Self::to_payment_uri`] back into a
+    /// [`PaymentRequirements`]. Since an ERC-681 URI carries no resource, description,
+    /// or timeout, those fields are filled with placeholders the caller should
+    /// overwrite once it knows what resource the payment is for.
+    pub fn from_payment_uri(uri: &str) -> crate::Result<Self> {
+        if let Some(bolt11) = uri.strip_prefix("lightning:") {
+            let mut requirements = Self::new(
+                schemes::LIGHTNING_BOLT11,
+                "bitcoin",
+                "0",
+                "sat",
+                "",
+                "",
+                "",
+            );
+            requirements.set_lightning_bolt11_invoice(bolt11, "")?;
+            return Ok(requirements);
+        }
+
+        let rest = uri.strip_prefix("ethereum:").ok_or_else(|| {
+            crate::X402Error::invalid_payment_requirements(format!(
+                "unrecognized payment URI scheme: {}",
+                uri
+            ))
+        })?;
+
+        let (head, query) = rest.split_once('?').ok_or_else(|| {
+            crate::X402Error::invalid_payment_requirements("ethereum URI is missing a query string")
+        })?;
+        let (asset, chain_id) = head.split_once('@').ok_or_else(|| {
+            crate::X402Error::invalid_payment_requirements("ethereum URI is missing @chainId")
+        })?;
+        let chain_id: u64 = chain_id
+            .trim_end_matches("/transfer")
+            .parse()
+            .map_err(|_| crate::X402Error::invalid_payment_requirements("invalid chain id"))?;
+        let network = network_name_for_chain_id(chain_id).ok_or_else(|| {
+            crate::X402Error::invalid_payment_requirements(format!(
+                "unrecognized chain id: {}",
+                chain_id
+            ))
+        })?;
+
+        let mut pay_to = None;
+        let mut amount = None;
+        for pair in query.split('&') {
+            match pair.split_once('=') {
+                Some(("address", value)) => pay_to = Some(value.to_string()),
+                Some(("uint256", value)) => amount = Some(value.to_string()),
+                _ => {}
+            }
+        }
+        let pay_to = pay_to.ok_or_else(|| {
+            crate::X402Error::invalid_payment_requirements("ethereum URI is missing address=")
+        })?;
+        let amount = amount.ok_or_else(|| {
+            crate::X402Error::invalid_payment_requirements("ethereum URI is missing uint256=")
+        })?;
+
+        Ok(Self::new(schemes::EXACT, network, amount, asset, pay_to, "", ""))
+    }
+}
+
+/// Reverse of [`NetworkConfig::from_name`]'s chain id, for the networks it knows about
+fn network_name_for_chain_id(chain_id: u64) -> Option<&'static str> {
+    match chain_id {
+        8453 => Some("base"),
+        84532 => Some("base-sepolia"),
+        _ => None,
+    }
+}
+
+/// The scheme-specific payload data a [`PaymentPayload`] carries
+///
+/// `#[serde(untagged)]` rather than tagging on `PaymentPayload::scheme` because the
+/// variants' wire shapes don't overlap (an [`ExactEvmPayload`] has `signature` +
+/// an EIP-3009 `authorization`; an [`ExactSvmPayload`] has `transaction` + a Solana
+/// `authorization`; the two Lightning payloads have `invoice` + `preimage`), so serde
+/// can pick the right one by structure alone without this type needing to duplicate
+/// `PaymentPayload::scheme` as a second tag field.
+///
+/// Adding a new scheme means adding a variant here, not a parallel
+/// `SomeSchemePaymentPayload` struct and its own one-off `verify_*`/`settle_*` pair —
+/// see [`PaymentPayload::exact_evm`] for how callers that only handle one scheme
+/// extract it back out.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum SchemePayload {
+    /// [`schemes::EXACT`]
+    ExactEvm(ExactEvmPayload),
+    /// [`schemes::EXACT_SVM`]
+    ExactSvm(ExactSvmPayload),
+    /// [`schemes::LIGHTNING_BOLT11`]
+    LightningBolt11(LightningBolt11Payload),
+    /// [`schemes::LIGHTNING_BOLT12`]
+    LightningBolt12(LightningBolt12Payload),
+}
+
+impl From<ExactEvmPayload> for SchemePayload {
+    fn from(payload: ExactEvmPayload) -> Self {
+        Self::ExactEvm(payload)
+    }
+}
+
+impl From<ExactSvmPayload> for SchemePayload {
+    fn from(payload: ExactSvmPayload) -> Self {
+        Self::ExactSvm(payload)
+    }
+}
+
+impl From<LightningBolt11Payload> for SchemePayload {
+    fn from(payload: LightningBolt11Payload) -> Self {
+        Self::LightningBolt11(payload)
+    }
+}
+
+impl From<LightningBolt12Payload> for SchemePayload {
+    fn from(payload: LightningBolt12Payload) -> Self {
+        Self::LightningBolt12(payload)
+    }
+}
+
+impl SchemePayload {
+    /// Validate the variant's own fields — hex/numeric well-formedness for the two
+    /// EVM/SVM `exact` shapes, via their own `validate()`. Neither Lightning variant
+    /// has local fields worth validating this way: the invoice and preimage are
+    /// checked by polling the Lightning node itself (see [`crate::lightning`]), not by
+    /// inspecting a client-submitted payload, so there's nothing to malform here.
+    pub fn validate(&self) -> crate::Result<()> {
+        match self {
+            Self::ExactEvm(payload) => payload.validate(),
+            Self::ExactSvm(payload) => payload.validate(),
+            Self::LightningBolt11(_) | Self::LightningBolt12(_) => Ok(()),
+        }
+    }
 }
 
 /// Payment payload for client payment authorization
@@ -193,24 +524,37 @@ pub struct PaymentPayload {
     /// Blockchain network identifier
     pub network: String,
     /// Payment data object
-    pub payload: ExactEvmPayload,
+    pub payload: SchemePayload,
 }
 
 impl PaymentPayload {
     /// Create a new payment payload
+    ///
+    /// `payload` accepts anything [`SchemePayload`] has a `From` impl for, so existing
+    /// callers building a [`PaymentPayload`] from an [`ExactEvmPayload`] (by far the
+    /// common case today) don't need to wrap it in [`SchemePayload::ExactEvm`]
+    /// themselves.
     pub fn new(
         scheme: impl Into<String>,
         network: impl Into<String>,
-        payload: ExactEvmPayload,
+        payload: impl Into<SchemePayload>,
     ) -> Self {
         Self {
             x402_version: X402_VERSION,
             scheme: scheme.into(),
             network: network.into(),
-            payload,
+            payload: payload.into(),
         }
     }
 
+    /// Override the protocol version this payload declares, e.g. with the result of
+    /// [`crate::facilitator::FacilitatorClient::negotiate_version`] rather than the
+    /// crate's own [`X402_VERSION`]
+    pub fn with_x402_version(mut self, x402_version: u32) -> Self {
+        self.x402_version = x402_version;
+        self
+    }
+
     /// Decode a base64-encoded payment payload
     pub fn from_base64(encoded: &str) -> crate::Result<Self> {
         use base64::{engine::general_purpose, Engine as _};
@@ -225,6 +569,28 @@ impl PaymentPayload {
         let json = serde_json::to_string(self)?;
         Ok(general_purpose::STANDARD.encode(json))
     }
+
+    /// Borrow `payload` as an [`ExactEvmPayload`], for the (currently: every shipped)
+    /// caller that only handles [`schemes::EXACT`]
+    ///
+    /// Returns [`crate::X402Error::SchemeMismatch`] rather than panicking if this
+    /// payload actually carries a different scheme's data, the same error a
+    /// requirements/payload scheme check elsewhere in this crate already raises for a
+    /// scheme mismatch.
+    pub fn exact_evm(&self) -> crate::Result<&ExactEvmPayload> {
+        match &self.payload {
+            SchemePayload::ExactEvm(payload) => Ok(payload),
+            _ => Err(crate::X402Error::scheme_mismatch(schemes::EXACT, &self.scheme)),
+        }
+    }
+
+    /// Mutably borrow `payload` as an [`ExactEvmPayload`]; see [`Self::exact_evm`]
+    pub fn exact_evm_mut(&mut self) -> crate::Result<&mut ExactEvmPayload> {
+        match &mut self.payload {
+            SchemePayload::ExactEvm(payload) => Ok(payload),
+            _ => Err(crate::X402Error::scheme_mismatch(schemes::EXACT, self.scheme.clone())),
+        }
+    }
 }
 
 /// Exact EVM payment payload (EIP-3009)
@@ -236,6 +602,42 @@ pub struct ExactEvmPayload {
     pub authorization: ExactEvmPayloadAuthorization,
 }
 
+impl ExactEvmPayload {
+    /// Validate hex-ness of `signature` and the authorization's `nonce`, and
+    /// numeric-ness of its `value`/`validAfter`/`validBefore`, so a malformed payload
+    /// is rejected locally with a [`crate::X402Error::MalformedPayload`] naming the
+    /// offending field instead of being forwarded to the facilitator as a
+    /// well-formed-looking bogus request.
+    pub fn validate(&self) -> crate::Result<()> {
+        validate_hex_field("signature", &self.signature)?;
+        self.authorization.validate()
+    }
+}
+
+/// Require `value` to be `0x`-prefixed hex with at least one digit
+fn validate_hex_field(field: &'static str, value: &str) -> crate::Result<()> {
+    let hex_digits = value
+        .strip_prefix("0x")
+        .ok_or_else(|| crate::X402Error::malformed_payload(field))?;
+    if hex_digits.is_empty() || !hex_digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(crate::X402Error::malformed_payload(field));
+    }
+    Ok(())
+}
+
+/// Generates the 32-byte random nonce an [`ExactEvmPayloadAuthorization`] carries to
+/// prevent replay, a thin, hex-formatting wrapper around
+/// [`crate::crypto::signature::generate_nonce`]'s CSPRNG so callers building an
+/// authorization don't need to depend on `crypto` directly or hand-format an `H256`
+pub struct Nonce;
+
+impl Nonce {
+    /// Generate a fresh, cryptographically random nonce, hex-encoded with a `0x` prefix
+    pub fn random() -> String {
+        format!("{:?}", crate::crypto::signature::generate_nonce())
+    }
+}
+
 /// EIP-3009 authorization parameters
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExactEvmPayloadAuthorization {
@@ -275,6 +677,19 @@ impl ExactEvmPayloadAuthorization {
         }
     }
 
+    /// Create a new authorization with a fresh [`Nonce::random`] instead of a
+    /// caller-supplied one, so a wallet building an authorization can't accidentally
+    /// reuse a nonce the way a hardcoded test fixture does
+    pub fn new_with_random_nonce(
+        from: impl Into<String>,
+        to: impl Into<String>,
+        value: impl Into<String>,
+        valid_after: impl Into<String>,
+        valid_before: impl Into<String>,
+    ) -> Self {
+        Self::new(from, to, value, valid_after, valid_before, Nonce::random())
+    }
+
     /// Check if the authorization is currently valid
     pub fn is_valid_now(&self) -> crate::Result<bool> {
         let now = Utc::now().timestamp();
@@ -288,6 +703,54 @@ impl ExactEvmPayloadAuthorization {
         Ok(now >= valid_after && now <= valid_before)
     }
 
+    /// Validate hex-ness of `nonce` and numeric-ness of `value`/`validAfter`/`validBefore`
+    pub fn validate(&self) -> crate::Result<()> {
+        validate_hex_field("nonce", &self.nonce)?;
+        self.value
+            .parse::<u128>()
+            .map_err(|_| crate::X402Error::malformed_payload("value"))?;
+        self.valid_after
+            .parse::<i64>()
+            .map_err(|_| crate::X402Error::malformed_payload("validAfter"))?;
+        self.valid_before
+            .parse::<i64>()
+            .map_err(|_| crate::X402Error::malformed_payload("validBefore"))?;
+        Ok(())
+    }
+
+    /// Check the validity window, returning the specific [`crate::X402Error`] variant
+    /// (`AuthorizationNotYetValid`/`AuthorizationExpired`) when the authorization is
+    /// outside it, instead of the plain boolean [`Self::is_valid_now`] returns
+    ///
+    /// Equivalent to [`Self::check_validity_window_with_tolerance`] with a zero
+    /// tolerance.
+    pub fn check_validity_window(&self) -> crate::Result<()> {
+        self.check_validity_window_with_tolerance(Duration::from_secs(0))
+    }
+
+    /// Check the validity window like [`Self::check_validity_window`], but widen it by
+    /// `tolerance` on both ends first, absorbing clock drift between this server and
+    /// whatever clock the client's wallet signed `validAfter`/`validBefore` against;
+    /// see [`crate::middleware::PaymentMiddleware::with_clock_skew_tolerance`]
+    pub fn check_validity_window_with_tolerance(&self, tolerance: Duration) -> crate::Result<()> {
+        let now = Utc::now().timestamp();
+        let valid_after: i64 = self.valid_after.parse().map_err(|_| {
+            crate::X402Error::invalid_authorization("Invalid valid_after timestamp")
+        })?;
+        let valid_before: i64 = self.valid_before.parse().map_err(|_| {
+            crate::X402Error::invalid_authorization("Invalid valid_before timestamp")
+        })?;
+        let tolerance = tolerance.as_secs() as i64;
+
+        if now < valid_after - tolerance {
+            Err(crate::X402Error::AuthorizationNotYetValid)
+        } else if now > valid_before + tolerance {
+            Err(crate::X402Error::authorization_expired(valid_before, now))
+        } else {
+            Ok(())
+        }
+    }
+
     /// Get the validity duration
     pub fn validity_duration(&self) -> crate::Result<Duration> {
         let valid_after: i64 = self.valid_after.parse().map_err(|_| {
@@ -301,6 +764,291 @@ impl ExactEvmPayloadAuthorization {
     }
 }
 
+/// Exact Solana/SPL payment payload, the [`schemes::EXACT_SVM`] counterpart to
+/// [`ExactEvmPayload`]
+///
+/// A fully signed Solana transaction carries its own authorization (there's no
+/// separate EIP-3009-style signature-over-struct step as on EVM — the whole
+/// transaction, including the SPL `TransferChecked` instruction, is what gets signed),
+/// so this only needs the transaction bytes plus the fields a facilitator needs to
+/// check the transaction actually does what [`PaymentRequirements`] asked for without
+/// having to deserialize Solana's wire format itself.
+///
+/// Wired into [`PaymentPayload`] as [`SchemePayload::ExactSvm`], but
+/// [`solana_facilitator`](crate::solana_facilitator) still speaks
+/// [`SolanaPaymentPayload`] rather than `PaymentPayload`/`SchemePayload` directly —
+/// its wire envelope predates the enum and hasn't been migrated onto it yet.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExactSvmPayload {
+    /// Base64-encoded, fully signed Solana transaction (the serialized `Transaction`,
+    /// signatures included) carrying the SPL `TransferChecked` instruction
+    pub transaction: String,
+    /// The transfer this transaction is expected to make, so a facilitator can check
+    /// it without decoding `transaction` itself
+    pub authorization: ExactSvmPayloadAuthorization,
+}
+
+impl ExactSvmPayload {
+    /// Validate that `transaction` looks like base64 and `authorization` is well-formed
+    pub fn validate(&self) -> crate::Result<()> {
+        use base64::{engine::general_purpose, Engine as _};
+        general_purpose::STANDARD
+            .decode(&self.transaction)
+            .map_err(|_| crate::X402Error::malformed_payload("transaction"))?;
+        self.authorization.validate()
+    }
+}
+
+/// The SPL transfer an [`ExactSvmPayload`]'s transaction is expected to make
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExactSvmPayloadAuthorization {
+    /// Payer's base58-encoded Solana wallet address
+    pub from: String,
+    /// Recipient's base58-encoded Solana wallet address
+    pub to: String,
+    /// Base58-encoded SPL token mint address (e.g. [`networks::get_spl_usdc_mint`])
+    pub mint: String,
+    /// Payment amount in the mint's smallest unit, as a decimal string (matching
+    /// [`ExactEvmPayloadAuthorization::value`]'s string-encoded-integer convention)
+    pub amount: String,
+    /// Recent blockhash the transaction was built against, base58-encoded; Solana has
+    /// no `validAfter`/`validBefore` window like EIP-3009 — a transaction simply
+    /// expires once its blockhash ages out (~60-90 seconds), which this field lets a
+    /// facilitator check without decoding the transaction
+    pub recent_blockhash: String,
+}
+
+impl ExactSvmPayloadAuthorization {
+    pub fn new(
+        from: impl Into<String>,
+        to: impl Into<String>,
+        mint: impl Into<String>,
+        amount: impl Into<String>,
+        recent_blockhash: impl Into<String>,
+    ) -> Self {
+        Self {
+            from: from.into(),
+            to: to.into(),
+            mint: mint.into(),
+            amount: amount.into(),
+            recent_blockhash: recent_blockhash.into(),
+        }
+    }
+
+    /// Validate that `amount` is numeric and the base58-ish fields are non-empty;
+    /// base58 alphabet-checking is left to the Solana SDK on the facilitator side,
+    /// the same way [`validate_hex_field`] leaves full address checksum validation to
+    /// `ethereum_types::Address::from_str` rather than duplicating it here
+    pub fn validate(&self) -> crate::Result<()> {
+        self.amount
+            .parse::<u64>()
+            .map_err(|_| crate::X402Error::malformed_payload("amount"))?;
+        for (field, value) in [
+            ("from", &self.from),
+            ("to", &self.to),
+            ("mint", &self.mint),
+            ("recentBlockhash", &self.recent_blockhash),
+        ] {
+            if value.is_empty() {
+                return Err(crate::X402Error::malformed_payload(field));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Wire envelope for an [`ExactSvmPayload`], the Solana counterpart to
+/// [`PaymentPayload`] that [`solana_facilitator`](crate::solana_facilitator) still
+/// speaks instead of `PaymentPayload`/[`SchemePayload::ExactSvm`] directly — see
+/// [`ExactSvmPayload`]'s doc comment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SolanaPaymentPayload {
+    #[serde(rename = "x402Version")]
+    pub x402_version: u32,
+    pub scheme: String,
+    pub network: String,
+    pub payload: ExactSvmPayload,
+}
+
+impl SolanaPaymentPayload {
+    pub fn new(network: impl Into<String>, payload: ExactSvmPayload) -> Self {
+        Self {
+            x402_version: X402_VERSION,
+            scheme: schemes::EXACT_SVM.to_string(),
+            network: network.into(),
+            payload,
+        }
+    }
+
+    /// Decode a base64-encoded Solana payment payload, mirroring [`PaymentPayload::from_base64`]
+    pub fn from_base64(encoded: &str) -> crate::Result<Self> {
+        use base64::{engine::general_purpose, Engine as _};
+        let decoded = general_purpose::STANDARD.decode(encoded)?;
+        Ok(serde_json::from_slice(&decoded)?)
+    }
+
+    /// Encode to base64, mirroring [`PaymentPayload::to_base64`]
+    pub fn to_base64(&self) -> crate::Result<String> {
+        use base64::{engine::general_purpose, Engine as _};
+        let json = serde_json::to_string(self)?;
+        Ok(general_purpose::STANDARD.encode(json))
+    }
+}
+
+/// A decoded BOLT12 invoice obtained in response to an `invoice_request` against an offer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LightningInvoice {
+    /// BOLT12 offer this invoice was requested against
+    pub offer: String,
+    /// Hex-encoded SHA-256 payment hash the preimage must hash to
+    pub payment_hash: String,
+    /// Invoiced amount in millisatoshis
+    pub amount_msat: u64,
+    /// Unix timestamp after which the invoice can no longer be paid
+    pub expires_at: i64,
+    /// Invoice description, echoed from the offer
+    pub description: String,
+}
+
+impl LightningInvoice {
+    /// Check whether the invoice is still payable at the current time
+    pub fn is_expired(&self) -> bool {
+        Utc::now().timestamp() > self.expires_at
+    }
+}
+
+/// Lightning BOLT12 payment payload: a paid invoice and the resulting proof of payment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LightningBolt12Payload {
+    /// The invoice that was paid
+    pub invoice: LightningInvoice,
+    /// Hex-encoded payment preimage proving the invoice was settled
+    pub preimage: String,
+}
+
+/// Payment payload for the `lightning-bolt12` scheme
+///
+/// Predates [`SchemePayload::LightningBolt12`], which now lets a plain
+/// [`PaymentPayload`] carry a [`LightningBolt12Payload`] directly; kept as its own
+/// type since nothing in this crate constructs one today (Lightning verification is
+/// the out-of-band, invoice-polling path in [`crate::lightning`], not a payload a
+/// client sends).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LightningBolt12PaymentPayload {
+    /// Protocol version identifier
+    #[serde(rename = "x402Version")]
+    pub x402_version: u32,
+    /// Payment scheme identifier, always [`schemes::LIGHTNING_BOLT12`]
+    pub scheme: String,
+    /// Always "lightning" for this scheme
+    pub network: String,
+    /// Payment data object
+    pub payload: LightningBolt12Payload,
+}
+
+impl LightningBolt12PaymentPayload {
+    /// Create a new Lightning BOLT12 payment payload
+    pub fn new(network: impl Into<String>, payload: LightningBolt12Payload) -> Self {
+        Self {
+            x402_version: X402_VERSION,
+            scheme: schemes::LIGHTNING_BOLT12.to_string(),
+            network: network.into(),
+            payload,
+        }
+    }
+
+    /// Decode a base64-encoded payment payload
+    pub fn from_base64(encoded: &str) -> crate::Result<Self> {
+        use base64::{engine::general_purpose, Engine as _};
+        let decoded = general_purpose::STANDARD.decode(encoded)?;
+        let payload: LightningBolt12PaymentPayload = serde_json::from_slice(&decoded)?;
+        Ok(payload)
+    }
+
+    /// Encode the payment payload to base64
+    pub fn to_base64(&self) -> crate::Result<String> {
+        use base64::{engine::general_purpose, Engine as _};
+        let json = serde_json::to_string(self)?;
+        Ok(general_purpose::STANDARD.encode(json))
+    }
+}
+
+/// A BOLT11 invoice, already scoped to a single amount and payment (unlike
+/// [`LightningInvoice`], which is requested fresh against a reusable BOLT12 offer)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LightningBolt11Invoice {
+    /// The encoded BOLT11 invoice string (`lnbc...`)
+    pub bolt11: String,
+    /// Hex-encoded SHA-256 payment hash the preimage must hash to
+    pub payment_hash: String,
+    /// Invoiced amount in millisatoshis
+    pub amount_msat: u64,
+    /// Unix timestamp after which the invoice can no longer be paid
+    pub expires_at: i64,
+    /// Invoice description
+    pub description: String,
+}
+
+impl LightningBolt11Invoice {
+    /// Check whether the invoice is still payable at the current time
+    pub fn is_expired(&self) -> bool {
+        Utc::now().timestamp() > self.expires_at
+    }
+}
+
+/// Lightning BOLT11 payment payload: a paid invoice and the resulting proof of payment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LightningBolt11Payload {
+    /// The invoice that was paid
+    pub invoice: LightningBolt11Invoice,
+    /// Hex-encoded payment preimage proving the invoice was settled
+    pub preimage: String,
+}
+
+/// Payment payload for the `lightning-bolt11` scheme
+///
+/// Mirrors [`LightningBolt12PaymentPayload`], but carries a [`LightningBolt11Payload`]
+/// referencing a single-use BOLT11 invoice rather than a BOLT12 offer-derived one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LightningBolt11PaymentPayload {
+    /// Protocol version identifier
+    #[serde(rename = "x402Version")]
+    pub x402_version: u32,
+    /// Payment scheme identifier, always [`schemes::LIGHTNING_BOLT11`]
+    pub scheme: String,
+    /// Always "lightning" for this scheme
+    pub network: String,
+    /// Payment data object
+    pub payload: LightningBolt11Payload,
+}
+
+impl LightningBolt11PaymentPayload {
+    /// Create a new Lightning BOLT11 payment payload
+    pub fn new(network: impl Into<String>, payload: LightningBolt11Payload) -> Self {
+        Self {
+            x402_version: X402_VERSION,
+            scheme: schemes::LIGHTNING_BOLT11.to_string(),
+            network: network.into(),
+            payload,
+        }
+    }
+
+    /// Decode a base64-encoded payment payload
+    pub fn from_base64(encoded: &str) -> crate::Result<Self> {
+        use base64::{engine::general_purpose, Engine as _};
+        let decoded = general_purpose::STANDARD.decode(encoded)?;
+        let payload: LightningBolt11PaymentPayload = serde_json::from_slice(&decoded)?;
+        Ok(payload)
+    }
+
+    /// Encode the payment payload to base64
+    pub fn to_base64(&self) -> crate::Result<String> {
+        use base64::{engine::general_purpose, Engine as _};
+        let json = serde_json::to_string(self)?;
+        Ok(general_purpose::STANDARD.encode(json))
+    }
+}
+
 /// Payment verification response
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VerifyResponse {
@@ -315,6 +1063,33 @@ pub struct VerifyResponse {
     pub payer: Option<String>,
 }
 
+/// Request-extension carrying the verified payment's identity and entitlements
+///
+/// Inserted by [`crate::middleware::PaymentMiddleware::process_payment`] after a
+/// successful `verify`, so handlers and extractors downstream of the payment
+/// middleware can read who paid and what they're entitled to, instead of just
+/// knowing a payment happened.
+#[derive(Debug, Clone)]
+pub struct PaymentContext {
+    /// Address that made the payment, if the facilitator reported one
+    pub payer: Option<String>,
+    /// Payment scheme used, e.g. `"exact"`
+    pub scheme: String,
+    /// Network the payment was made on, e.g. `"base-sepolia"`
+    pub network: String,
+    /// Amount required by the payment requirements that were satisfied
+    pub amount: String,
+    /// Scopes granted by this payment, as configured on the middleware
+    pub scopes: Vec<String>,
+}
+
+impl PaymentContext {
+    /// Whether this context grants every scope in `required`
+    pub fn has_scopes(&self, required: &[String]) -> bool {
+        required.iter().all(|scope| self.scopes.iter().any(|granted| granted == scope))
+    }
+}
+
 /// Payment settlement response
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SettleResponse {
@@ -339,6 +1114,81 @@ impl SettleResponse {
         let json = serde_json::to_string(self)?;
         Ok(general_purpose::STANDARD.encode(json))
     }
+
+    /// Decode a base64-encoded settle response, mirroring [`ReversalResponse::from_base64`]
+    pub fn from_base64(encoded: &str) -> crate::Result<Self> {
+        use base64::{engine::general_purpose, Engine as _};
+        let decoded = general_purpose::STANDARD.decode(encoded)?;
+        let response: SettleResponse = serde_json::from_slice(&decoded)?;
+        Ok(response)
+    }
+}
+
+/// Which direction of money movement a [`ReversalRequest`] describes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReversalKind {
+    /// Reverses a previously settled payment, identified by its original
+    /// transaction hash
+    Refund,
+    /// A standalone outbound transfer to `destination`, not tied to a prior
+    /// settlement
+    Payout,
+}
+
+/// Request body for a facilitator's `/refund` or `/payout` endpoint
+///
+/// Neither endpoint is part of the core x402 facilitator protocol (`/verify`,
+/// `/settle`, `/supported`); this is an extension a facilitator opts into
+/// supporting, analogous to how `/settle` reverses the direction of `/verify`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReversalRequest {
+    pub kind: ReversalKind,
+    /// Transaction hash being refunded; required for [`ReversalKind::Refund`],
+    /// absent for [`ReversalKind::Payout`]
+    #[serde(rename = "originalTransaction", skip_serializing_if = "Option::is_none")]
+    pub original_transaction: Option<String>,
+    /// Destination address receiving the reversal
+    pub destination: String,
+    /// Amount in atomic token units
+    pub amount: String,
+    /// Asset contract address being moved
+    pub asset: String,
+    /// Network the reversal executes on
+    pub network: String,
+}
+
+/// Response to a [`ReversalRequest`]
+///
+/// Shares the base64 JSON envelope convention of [`SettleResponse`] so a refund or
+/// payout can be surfaced through the same `X-PAYMENT-RESPONSE` header clients already
+/// know how to decode.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReversalResponse {
+    pub success: bool,
+    pub kind: ReversalKind,
+    #[serde(rename = "errorReason", skip_serializing_if = "Option::is_none")]
+    pub error_reason: Option<String>,
+    /// Transaction hash or identifier of the reversal itself
+    pub transaction: String,
+    pub network: String,
+}
+
+impl ReversalResponse {
+    /// Encode the reversal response to base64, for the `X-PAYMENT-RESPONSE` header
+    pub fn to_base64(&self) -> crate::Result<String> {
+        use base64::{engine::general_purpose, Engine as _};
+        let json = serde_json::to_string(self)?;
+        Ok(general_purpose::STANDARD.encode(json))
+    }
+
+    /// Decode a base64-encoded reversal response
+    pub fn from_base64(encoded: &str) -> crate::Result<Self> {
+        use base64::{engine::general_purpose, Engine as _};
+        let decoded = general_purpose::STANDARD.decode(encoded)?;
+        let response: ReversalResponse = serde_json::from_slice(&decoded)?;
+        Ok(response)
+    }
 }
 
 /// Facilitator configuration
@@ -350,6 +1200,39 @@ pub struct FacilitatorConfig {
     pub timeout: Option<Duration>,
     /// Function to create authentication headers
     pub create_auth_headers: Option<AuthHeadersFnArc>,
+    /// Retry policy for transient verify/settle failures. `None` disables retries.
+    pub retry_policy: Option<crate::retry::RetryPolicy>,
+    /// HTTP Signatures config for authenticating outbound verify/settle requests
+    pub http_signature: Option<crate::http_signature::HttpSignatureConfig>,
+    /// Replay-protection store consulted by [`crate::facilitator::FacilitatorClient::verify`]
+    /// before an authorization is ever sent to the remote facilitator. Rejects a
+    /// captured `PaymentPayload` resubmitted within its own `validBefore` window
+    /// without a round trip to the facilitator (or the chain) to find out it was
+    /// already spent. `None` leaves replay protection to whatever the facilitator
+    /// itself enforces at settlement.
+    pub nonce_replay_store: Option<Arc<dyn crate::nonce_store::NonceReplayStore>>,
+    /// Store consulted by [`crate::facilitator::FacilitatorClient::settle`] to
+    /// collapse concurrent or retried `settle` calls for the same payment onto a
+    /// single in-flight attempt. `None` leaves double-settlement protection to
+    /// the `Idempotency-Key` header alone (only as good as the facilitator's own
+    /// handling of it); set via [`Self::with_idempotency_store`].
+    pub idempotency_store: Option<Arc<dyn crate::idempotency::IdempotencyStore>>,
+    /// Route and header customization for facilitators that don't speak the
+    /// Coinbase facilitator's exact paths, set via [`Self::with_provider`]. `None`
+    /// keeps the hard-coded `/verify`, `/settle` and `/supported` paths used
+    /// elsewhere in [`crate::facilitator::FacilitatorClient`].
+    pub provider: Option<Arc<dyn crate::facilitator::FacilitatorProvider>>,
+    /// How long [`crate::facilitator::FacilitatorClient::supported_cached`] trusts a
+    /// `/supported` response before fetching a fresh one, set via
+    /// [`Self::with_supported_cache_ttl`]. `None` keeps the client's own default, so a
+    /// long-lived client can shorten this to pick up a newly added network sooner.
+    pub supported_cache_ttl: Option<Duration>,
+    /// Async, per-endpoint credential source applied on top of `create_auth_headers`
+    /// and `provider`'s headers, set via [`Self::with_auth_provider`]. Use this
+    /// instead of `create_auth_headers` when a header needs to be fetched or
+    /// refreshed (e.g. a short-lived OAuth2 bearer token via
+    /// [`crate::facilitator::OAuth2ClientCredentials`]) rather than computed once.
+    pub auth_provider: Option<Arc<dyn crate::facilitator::AuthProvider>>,
 }
 
 impl std::fmt::Debug for FacilitatorConfig {
@@ -358,6 +1241,12 @@ impl std::fmt::Debug for FacilitatorConfig {
             .field("url", &self.url)
             .field("timeout", &self.timeout)
             .field("create_auth_headers", &"<function>")
+            .field("retry_policy", &self.retry_policy)
+            .field("nonce_replay_store", &self.nonce_replay_store.is_some())
+            .field("idempotency_store", &self.idempotency_store.is_some())
+            .field("provider", &self.provider.is_some())
+            .field("supported_cache_ttl", &self.supported_cache_ttl)
+            .field("auth_provider", &self.auth_provider.is_some())
             .finish()
     }
 }
@@ -369,6 +1258,13 @@ impl FacilitatorConfig {
             url: url.into(),
             timeout: None,
             create_auth_headers: None,
+            retry_policy: None,
+            http_signature: None,
+            nonce_replay_store: None,
+            idempotency_store: None,
+            provider: None,
+            supported_cache_ttl: None,
+            auth_provider: None,
         }
     }
 
@@ -398,6 +1294,62 @@ impl FacilitatorConfig {
         self.create_auth_headers = Some(Arc::from(creator));
         self
     }
+
+    /// Set the retry policy used for transient verify/settle failures
+    pub fn with_retry_policy(mut self, retry_policy: crate::retry::RetryPolicy) -> Self {
+        self.retry_policy = Some(retry_policy);
+        self
+    }
+
+    /// Set the HTTP Signatures config used to sign outbound verify/settle requests
+    pub fn with_http_signature(mut self, http_signature: crate::http_signature::HttpSignatureConfig) -> Self {
+        self.http_signature = Some(http_signature);
+        self
+    }
+
+    /// Set the store [`crate::facilitator::FacilitatorClient::verify`] consults to
+    /// reject a replayed authorization locally, before calling the facilitator
+    pub fn with_nonce_replay_store(
+        mut self,
+        nonce_replay_store: Arc<dyn crate::nonce_store::NonceReplayStore>,
+    ) -> Self {
+        self.nonce_replay_store = Some(nonce_replay_store);
+        self
+    }
+
+    /// Set the store [`crate::facilitator::FacilitatorClient::settle`] consults so
+    /// concurrent or retried `settle` calls for the same payment collapse onto one
+    /// in-flight attempt instead of each posting a separate settlement
+    pub fn with_idempotency_store(
+        mut self,
+        idempotency_store: Arc<dyn crate::idempotency::IdempotencyStore>,
+    ) -> Self {
+        self.idempotency_store = Some(idempotency_store);
+        self
+    }
+
+    /// Customize the routes and per-operation headers [`crate::facilitator::FacilitatorClient`]
+    /// uses for `verify`/`settle`/`supported`, so a facilitator other than Coinbase's
+    /// (different paths, different auth scheme) can be targeted without forking the
+    /// client. Layers on top of, and is applied after, [`Self::with_auth_headers`].
+    pub fn with_provider(mut self, provider: Arc<dyn crate::facilitator::FacilitatorProvider>) -> Self {
+        self.provider = Some(provider);
+        self
+    }
+
+    /// Override how long [`crate::facilitator::FacilitatorClient::supported_cached`]
+    /// trusts a cached `/supported` response before refetching
+    pub fn with_supported_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.supported_cache_ttl = Some(ttl);
+        self
+    }
+
+    /// Set the async, per-endpoint credential source applied on top of
+    /// `create_auth_headers` and `provider`'s headers
+    pub fn with_auth_provider(mut self, auth_provider: Arc<dyn crate::facilitator::AuthProvider>) -> Self {
+        self.auth_provider = Some(auth_provider);
+        self
+    }
 }
 
 impl Default for FacilitatorConfig {
@@ -406,6 +1358,23 @@ impl Default for FacilitatorConfig {
     }
 }
 
+/// Structured, machine-readable detail for why a payment was rejected, derived from
+/// the [`crate::X402Error`] variant that triggered the 402 response
+///
+/// Following fuels-rs's discriminable `Error` types: a client can branch on `code`
+/// (e.g. `"insufficient_funds"`, `"authorization_expired"`) to decide whether to top
+/// up, re-sign, or switch networks, instead of pattern-matching `message`'s free text.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorDetail {
+    /// Stable machine-readable code; see [`crate::X402Error::error_type`]
+    pub code: String,
+    /// Human-readable message, the same text as [`PaymentRequirementsResponse::error`]
+    pub message: String,
+    /// Protocol version this error was raised under
+    #[serde(rename = "x402Version")]
+    pub x402_version: u32,
+}
+
 /// Payment requirements response (HTTP 402 response)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PaymentRequirementsResponse {
@@ -416,15 +1385,37 @@ pub struct PaymentRequirementsResponse {
     pub error: String,
     /// Array of acceptable payment methods
     pub accepts: Vec<PaymentRequirements>,
+    /// Structured error detail, present when this response was built from a concrete
+    /// [`crate::X402Error`] via [`Self::from_error`] rather than a bare message via
+    /// [`Self::new`]
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub error_detail: Option<ErrorDetail>,
 }
 
 impl PaymentRequirementsResponse {
-    /// Create a new payment requirements response
+    /// Create a new payment requirements response with a bare message and no
+    /// structured [`ErrorDetail`]
     pub fn new(error: impl Into<String>, accepts: Vec<PaymentRequirements>) -> Self {
         Self {
             x402_version: X402_VERSION,
             error: error.into(),
             accepts,
+            error_detail: None,
+        }
+    }
+
+    /// Create a payment requirements response carrying `error`'s machine-readable
+    /// [`ErrorDetail`] alongside its `Display` text
+    pub fn from_error(error: &crate::X402Error, accepts: Vec<PaymentRequirements>) -> Self {
+        Self {
+            x402_version: X402_VERSION,
+            error: error.to_string(),
+            accepts,
+            error_detail: Some(ErrorDetail {
+                code: error.error_type().to_string(),
+                message: error.to_string(),
+                x402_version: X402_VERSION,
+            }),
         }
     }
 }
@@ -446,6 +1437,12 @@ pub struct SupportedKind {
     pub scheme: String,
     /// Blockchain network identifier
     pub network: String,
+    /// The specific asset (token contract address) this `(scheme, network)` pairing
+    /// supports, when the facilitator's `/supported` document advertises one.
+    /// `None` means the facilitator didn't scope this entry to a specific asset
+    /// (an older facilitator, or one that supports any asset for the pairing).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub asset: Option<String>,
 }
 
 /// Discovery API resource
@@ -501,6 +1498,29 @@ pub mod networks {
     pub const AVALANCHE_MAINNET: &str = "avalanche";
     /// Avalanche Fuji testnet configuration
     pub const AVALANCHE_FUJI: &str = "avalanche-fuji";
+    /// Solana mainnet-beta configuration
+    pub const SOLANA_MAINNET: &str = "solana";
+    /// Solana devnet configuration
+    pub const SOLANA_DEVNET: &str = "solana-devnet";
+
+    /// Check whether `network` is one of the Solana networks above, as opposed to an
+    /// EVM network — [`get_usdc_address`] and [`is_supported`] are EVM-only, so a
+    /// caller branching on chain family should check this rather than assuming
+    /// anything [`is_supported`] rejects must be invalid
+    pub fn is_solana(network: &str) -> bool {
+        matches!(network, SOLANA_MAINNET | SOLANA_DEVNET)
+    }
+
+    /// Get the SPL USDC mint address for a Solana network, the Solana analog of
+    /// [`get_usdc_address`]'s EVM contract address. Returns `None` for an EVM network
+    /// or an unrecognized one.
+    pub fn get_spl_usdc_mint(network: &str) -> Option<&'static str> {
+        match network {
+            SOLANA_MAINNET => Some("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"),
+            SOLANA_DEVNET => Some("4zMMC9srt5Ri5X14GAgXhaHii3GnPAEERYPJgZJDncDU"),
+            _ => None,
+        }
+    }
 
     /// Get USDC contract address for a network
     pub fn get_usdc_address(network: &str) -> Option<&'static str> {
@@ -536,4 +1556,11 @@ pub mod networks {
 pub mod schemes {
     /// Exact payment scheme (EIP-3009)
     pub const EXACT: &str = "exact";
+    /// Exact payment scheme for Solana/SPL transfers, the [`EXACT`] scheme's
+    /// counterpart for [`super::networks::is_solana`] networks; see [`super::ExactSvmPayload`]
+    pub const EXACT_SVM: &str = "exact-svm";
+    /// Lightning BOLT12 payment scheme
+    pub const LIGHTNING_BOLT12: &str = "lightning-bolt12";
+    /// Lightning BOLT11 payment scheme
+    pub const LIGHTNING_BOLT11: &str = "lightning-bolt11";
 }
@@ -6,16 +6,17 @@
 use crate::{
     crypto::{
         eip712::{create_transfer_with_authorization_hash, Domain},
-        signature::{generate_nonce, sign_message_hash, verify_payment_payload},
+        signature::{generate_nonce, recover_payment_payload_signer, sign_message_hash, sign_prehash_components, LocalSigner, Signature},
     },
-    types::{ExactEvmPayload, ExactEvmPayloadAuthorization, PaymentPayload, PaymentRequirements},
+    facilitator::BoxFuture,
+    types::{ExactEvmPayload, ExactEvmPayloadAuthorization, ExactSvmPayload, ExactSvmPayloadAuthorization, PaymentPayload, PaymentRequirements, SolanaPaymentPayload},
     Result, X402Error,
 };
-use ethereum_types::{Address, U256};
+use ethereum_types::{Address, H256, U256};
 use std::str::FromStr;
 
 /// Wallet implementation for x402 payments
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Wallet {
     /// Private key for signing (in production, this should come from secure storage)
     private_key: String,
@@ -71,12 +72,13 @@ impl Wallet {
 
         // Step 4: Create the EIP-712 message hash
         let network_config = self.get_network_config()?;
-        let domain = Domain {
-            name: "USD Coin".to_string(),
-            version: "2".to_string(),
-            chain_id: network_config.chain_id,
-            verifying_contract: network_config.usdc_contract,
-        };
+        let (verifying_contract, eip712_name, eip712_version) =
+            resolve_eip712_domain(&requirements.network, &requirements.asset, network_config.usdc_contract)?;
+        let domain = Domain::new()
+            .with_name(eip712_name)
+            .with_version(eip712_version)
+            .with_chain_id(network_config.chain_id)
+            .with_verifying_contract(verifying_contract);
 
         let message_hash = create_transfer_with_authorization_hash(
             &domain,
@@ -106,46 +108,109 @@ impl Wallet {
             PaymentPayload::new(&requirements.scheme, &requirements.network, payload);
 
         // Step 7: Verify the signature (production best practice)
-        let is_valid =
-            verify_payment_payload(&payment_payload.payload, from_address, &self.network)?;
+        let expected_address = Address::from_str(from_address)
+            .map_err(|_| X402Error::invalid_signature("Invalid from address"))?;
+        let recovered_address =
+            recover_payment_payload_signer(payment_payload.exact_evm()?, &self.network)?;
 
-        if !is_valid {
-            return Err(X402Error::invalid_signature(
-                "Generated signature verification failed",
+        if recovered_address != expected_address {
+            return Err(X402Error::signature_mismatch(
+                format!("{:?}", recovered_address),
+                from_address.to_string(),
             ));
         }
 
         Ok(payment_payload)
     }
 
+    /// Sign an EIP-3009 `transferWithAuthorization` over caller-supplied fields,
+    /// instead of the fixed one-minute-leeway/five-minute-window nonce
+    /// [`Self::create_signed_payment_payload`] generates on the caller's behalf
+    ///
+    /// Useful when the caller already has an agreed-upon `to`/`value`/timing/nonce
+    /// to sign over — e.g. replaying a facilitator-issued nonce, or testing a
+    /// specific validity window — rather than letting the wallet pick its own.
+    /// Rejects a `valid_before` that's already in the past before ever reaching
+    /// for the private key.
+    pub fn sign_transfer_authorization(
+        &self,
+        requirements: &PaymentRequirements,
+        to: &str,
+        value: &str,
+        valid_after: &str,
+        valid_before: &str,
+        nonce: &str,
+    ) -> Result<ExactEvmPayload> {
+        let from_address = self.address()?;
+        let authorization = ExactEvmPayloadAuthorization::new(
+            format!("{:?}", from_address),
+            to,
+            value,
+            valid_after,
+            valid_before,
+            nonce,
+        );
+        authorization.check_validity_window()?;
+
+        let network_config = self.get_network_config()?;
+        let (verifying_contract, eip712_name, eip712_version) =
+            resolve_eip712_domain(&requirements.network, &requirements.asset, network_config.usdc_contract)?;
+        let domain = Domain::new()
+            .with_name(eip712_name)
+            .with_version(eip712_version)
+            .with_chain_id(network_config.chain_id)
+            .with_verifying_contract(verifying_contract);
+
+        let message_hash = create_transfer_with_authorization_hash(
+            &domain,
+            from_address,
+            Address::from_str(to)
+                .map_err(|_| X402Error::invalid_authorization("Invalid to address format"))?,
+            U256::from_str_radix(value, 10)
+                .map_err(|_| X402Error::invalid_authorization("Invalid value format"))?,
+            U256::from_str_radix(valid_after, 10)
+                .map_err(|_| X402Error::invalid_authorization("Invalid valid_after format"))?,
+            U256::from_str_radix(valid_before, 10)
+                .map_err(|_| X402Error::invalid_authorization("Invalid valid_before format"))?,
+            H256::from_str(nonce).map_err(|_| X402Error::invalid_authorization("Invalid nonce format"))?,
+        )?;
+
+        let signature = sign_message_hash(message_hash, &self.private_key)?;
+
+        Ok(ExactEvmPayload {
+            signature,
+            authorization,
+        })
+    }
+
     /// Get network configuration for the current network
     pub fn get_network_config(&self) -> Result<WalletNetworkConfig> {
-        match self.network.as_str() {
-            "base-sepolia" => Ok(WalletNetworkConfig {
-                chain_id: 84532,
-                usdc_contract: Address::from_str("0x036CbD53842c5426634e7929541eC2318f3dCF7e")
-                    .map_err(|_| X402Error::invalid_network("Invalid USDC contract address"))?,
-            }),
-            "base" => Ok(WalletNetworkConfig {
-                chain_id: 8453,
-                usdc_contract: Address::from_str("0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913")
-                    .map_err(|_| X402Error::invalid_network("Invalid USDC contract address"))?,
-            }),
-            "avalanche-fuji" => Ok(WalletNetworkConfig {
-                chain_id: 43113,
-                usdc_contract: Address::from_str("0x5425890298aed601595a70AB815c96711a31Bc65")
-                    .map_err(|_| X402Error::invalid_network("Invalid USDC contract address"))?,
-            }),
-            "avalanche" => Ok(WalletNetworkConfig {
-                chain_id: 43114,
-                usdc_contract: Address::from_str("0xB97EF9Ef8734C71904D8002F8b6Bc66Dd9c48a6E")
-                    .map_err(|_| X402Error::invalid_network("Invalid USDC contract address"))?,
-            }),
-            _ => Err(X402Error::invalid_network(format!(
-                "Unsupported network: {}",
-                self.network
-            ))),
-        }
+        let (chain_id, usdc_contract_str) = match self.network.as_str() {
+            "base-sepolia" => (84532, "0x036CbD53842c5426634e7929541eC2318f3dCF7e"),
+            "base" => (8453, "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913"),
+            "avalanche-fuji" => (43113, "0x5425890298aed601595a70AB815c96711a31Bc65"),
+            "avalanche" => (43114, "0xB97EF9Ef8734C71904D8002F8b6Bc66Dd9c48a6E"),
+            _ => {
+                return Err(X402Error::invalid_network(format!(
+                    "Unsupported network: {}",
+                    self.network
+                )))
+            }
+        };
+
+        let usdc_contract = Address::from_str(usdc_contract_str)
+            .map_err(|_| X402Error::invalid_network("Invalid USDC contract address"))?;
+        let metadata = crate::token_registry::TokenRegistry::new()
+            .with_known_usdc_tokens()
+            .lookup(&self.network, usdc_contract_str)
+            .cloned();
+
+        Ok(WalletNetworkConfig {
+            chain_id,
+            usdc_contract,
+            token_name: metadata.as_ref().map(|m| m.eip712_name.clone()).unwrap_or_else(|| "USD Coin".to_string()),
+            token_version: metadata.map(|m| m.eip712_version).unwrap_or_else(|| "2".to_string()),
+        })
     }
 
     /// Get the network name
@@ -154,11 +219,411 @@ impl Wallet {
     }
 }
 
+/// A source of EIP-712 signatures, abstracting over where the private key actually
+/// lives
+///
+/// [`Wallet`]'s own [`Wallet::create_signed_payment_payload`] signs with an in-memory
+/// key directly; [`create_signed_payment_payload`] (the free function below) is the
+/// same flow generalized over this trait, so a caller that can't hold a key at all —
+/// [`WalletConnectSigner`], or a future hardware-wallet signer — can produce the same
+/// payload by routing the signature through wherever the key actually is.
+pub trait Signer: Send + Sync {
+    /// Sign `hash` (the EIP-712 `TransferWithAuthorization` digest) and return the
+    /// decomposed `r`/`s`/`v` signature
+    fn sign_message_hash<'a>(&'a self, hash: H256) -> BoxFuture<'a, Result<Signature>>;
+
+    /// The address this signer signs on behalf of
+    fn address(&self) -> Result<Address>;
+
+    /// The chain the signer is configured to sign for, e.g. so
+    /// [`create_signed_payment_payload`] can reject signing a `requirements.network`
+    /// the signer didn't actually pair or configure itself for
+    fn chain_id(&self) -> Result<u64>;
+}
+
+impl Signer for Wallet {
+    fn sign_message_hash<'a>(&'a self, hash: H256) -> BoxFuture<'a, Result<Signature>> {
+        Box::pin(async move {
+            let (v, r, s) = sign_prehash_components(hash, &self.private_key)?;
+            Ok(Signature { r, s, v: v as u64 })
+        })
+    }
+
+    fn address(&self) -> Result<Address> {
+        LocalSigner::from_private_key(&self.private_key)?.address()
+    }
+
+    fn chain_id(&self) -> Result<u64> {
+        Ok(self.get_network_config()?.chain_id)
+    }
+}
+
+/// Create a signed payment payload for `requirements` using any [`Signer`]
+///
+/// This is [`Wallet::create_signed_payment_payload`] generalized: `from_address` comes
+/// from [`Signer::address`] rather than being passed in separately, and the EIP-712
+/// domain is resolved from `requirements.network` (via [`crate::types::NetworkConfig`])
+/// rather than a wallet-local network field, since a [`Signer`] like
+/// [`WalletConnectSigner`] has no network configuration of its own.
+pub async fn create_signed_payment_payload<S: Signer + ?Sized>(
+    signer: &S,
+    requirements: &PaymentRequirements,
+) -> Result<PaymentPayload> {
+    let from_address = signer.address()?;
+
+    let nonce = generate_nonce();
+    let now = chrono::Utc::now().timestamp();
+    let valid_after = (now - 60).to_string();
+    let valid_before = (now + 300).to_string();
+
+    let authorization = ExactEvmPayloadAuthorization::new(
+        format!("{:?}", from_address),
+        &requirements.pay_to,
+        &requirements.max_amount_required,
+        valid_after,
+        valid_before,
+        format!("{:?}", nonce),
+    );
+
+    let network_config = crate::types::NetworkConfig::from_name(&requirements.network)
+        .ok_or_else(|| X402Error::invalid_network(format!("Unsupported network: {}", requirements.network)))?;
+
+    let signer_chain_id = signer.chain_id()?;
+    if signer_chain_id != network_config.chain_id {
+        return Err(X402Error::wrong_network(
+            network_config.chain_id.to_string(),
+            signer_chain_id.to_string(),
+        ));
+    }
+
+    let default_verifying_contract = Address::from_str(&network_config.usdc_contract)
+        .map_err(|_| X402Error::invalid_network("Invalid USDC contract address"))?;
+    let (verifying_contract, eip712_name, eip712_version) =
+        resolve_eip712_domain(&requirements.network, &requirements.asset, default_verifying_contract)?;
+    let domain = Domain::new()
+        .with_name(eip712_name)
+        .with_version(eip712_version)
+        .with_chain_id(network_config.chain_id)
+        .with_verifying_contract(verifying_contract);
+
+    let message_hash = create_transfer_with_authorization_hash(
+        &domain,
+        from_address,
+        Address::from_str(&requirements.pay_to)
+            .map_err(|_| X402Error::invalid_authorization("Invalid pay_to address format"))?,
+        U256::from_str_radix(&requirements.max_amount_required, 10)
+            .map_err(|_| X402Error::invalid_authorization("Invalid amount format"))?,
+        U256::from_str_radix(&authorization.valid_after, 10)
+            .map_err(|_| X402Error::invalid_authorization("Invalid valid_after format"))?,
+        U256::from_str_radix(&authorization.valid_before, 10)
+            .map_err(|_| X402Error::invalid_authorization("Invalid valid_before format"))?,
+        nonce,
+    )?;
+
+    let signature = signer.sign_message_hash(message_hash).await?;
+
+    let payload = ExactEvmPayload {
+        signature: signature.to_string(),
+        authorization,
+    };
+
+    let payment_payload = PaymentPayload::new(&requirements.scheme, &requirements.network, payload);
+
+    let recovered_address =
+        recover_payment_payload_signer(payment_payload.exact_evm()?, &requirements.network)?;
+    if recovered_address != from_address {
+        return Err(X402Error::signature_mismatch(
+            format!("{:?}", recovered_address),
+            format!("{:?}", from_address),
+        ));
+    }
+
+    Ok(payment_payload)
+}
+
+/// Sign an EIP-3009 `TransferWithAuthorization` directly against a token's own
+/// EIP-712 domain, instead of deriving the domain from
+/// [`crate::types::PaymentRequirements::network`] the way
+/// [`create_signed_payment_payload`] does
+///
+/// Useful for a token that isn't registered in [`crate::types::NetworkConfig`] (or
+/// when no [`crate::types::PaymentRequirements`] exists yet): `token_name`/
+/// `token_version` (e.g. `"USD Coin"`/`"2"` for USDC) and `token_address` (the
+/// domain's `verifyingContract`) are supplied directly rather than looked up.
+/// Returns a fully-populated [`ExactEvmPayload`], ready to embed in a
+/// [`PaymentPayload`] or hand to a facilitator's verify/settle call.
+#[allow(clippy::too_many_arguments)]
+pub async fn sign_transfer_with_authorization<S: Signer>(
+    signer: &S,
+    token_name: impl Into<String>,
+    token_version: impl Into<String>,
+    chain_id: u64,
+    token_address: &str,
+    from: &str,
+    to: &str,
+    value: &str,
+    valid_after: &str,
+    valid_before: &str,
+    nonce: &str,
+) -> Result<ExactEvmPayload> {
+    let signer_chain_id = signer.chain_id()?;
+    if signer_chain_id != chain_id {
+        return Err(X402Error::wrong_network(
+            chain_id.to_string(),
+            signer_chain_id.to_string(),
+        ));
+    }
+
+    let domain = Domain::new()
+        .with_name(token_name)
+        .with_version(token_version)
+        .with_chain_id(chain_id)
+        .with_verifying_contract(
+            Address::from_str(token_address)
+                .map_err(|_| X402Error::invalid_authorization("Invalid token address format"))?,
+        );
+
+    let message_hash = create_transfer_with_authorization_hash(
+        &domain,
+        Address::from_str(from).map_err(|_| X402Error::invalid_authorization("Invalid from address format"))?,
+        Address::from_str(to).map_err(|_| X402Error::invalid_authorization("Invalid to address format"))?,
+        U256::from_str_radix(value, 10)
+            .map_err(|_| X402Error::invalid_authorization("Invalid amount format"))?,
+        U256::from_str_radix(valid_after, 10)
+            .map_err(|_| X402Error::invalid_authorization("Invalid valid_after format"))?,
+        U256::from_str_radix(valid_before, 10)
+            .map_err(|_| X402Error::invalid_authorization("Invalid valid_before format"))?,
+        H256::from_str(nonce).map_err(|_| X402Error::invalid_authorization("Invalid nonce format"))?,
+    )?;
+
+    let signature = signer.sign_message_hash(message_hash).await?;
+
+    Ok(ExactEvmPayload {
+        signature: signature.to_string(),
+        authorization: ExactEvmPayloadAuthorization::new(from, to, value, valid_after, valid_before, nonce),
+    })
+}
+
+/// Recover the address that signed a [`PaymentPayload`]'s EIP-3009 authorization
+/// against `requirement`'s own EIP-712 domain and an explicit `chain_id`, rather than
+/// looking the domain up from [`crate::types::PaymentRequirements::network`] the way
+/// [`crate::crypto::signature::recover_payment_payload_signer`] does
+///
+/// Useful for a resource server or local facilitator that already knows which chain
+/// it's verifying against and wants the token's own `name`/`version` (via
+/// [`resolve_eip712_domain`]) rather than a hardcoded `("USD Coin", "2")` domain.
+/// Returns a typed [`X402Error`] — rather than panicking — if the payload isn't an
+/// `ExactEvmPayload`, any field fails to parse, or signature recovery fails.
+pub fn recover_exact_payment_signer(
+    payload: &PaymentPayload,
+    requirement: &PaymentRequirements,
+    chain_id: u64,
+) -> Result<Address> {
+    let exact_payload = &payload.payload;
+    let auth = &exact_payload.authorization;
+
+    let default_verifying_contract = Address::from_str(&requirement.pay_to)
+        .map_err(|_| X402Error::invalid_signature("Invalid pay_to address"))?;
+    let (verifying_contract, eip712_name, eip712_version) =
+        resolve_eip712_domain(&requirement.network, &requirement.asset, default_verifying_contract)?;
+
+    let domain = Domain::new()
+        .with_name(eip712_name)
+        .with_version(eip712_version)
+        .with_chain_id(chain_id)
+        .with_verifying_contract(verifying_contract);
+
+    let message_hash = create_transfer_with_authorization_hash(
+        &domain,
+        Address::from_str(&auth.from).map_err(|_| X402Error::invalid_signature("Invalid from address"))?,
+        Address::from_str(&auth.to).map_err(|_| X402Error::invalid_signature("Invalid to address"))?,
+        U256::from_str_radix(&auth.value, 10)
+            .map_err(|_| X402Error::invalid_signature("Invalid value"))?,
+        U256::from_str_radix(&auth.valid_after, 10)
+            .map_err(|_| X402Error::invalid_signature("Invalid valid_after"))?,
+        U256::from_str_radix(&auth.valid_before, 10)
+            .map_err(|_| X402Error::invalid_signature("Invalid valid_before"))?,
+        H256::from_str(&auth.nonce).map_err(|_| X402Error::invalid_signature("Invalid nonce"))?,
+    )?;
+
+    crate::crypto::signature::recover_eip712_signer(&exact_payload.signature, message_hash, Some(chain_id))
+}
+
+/// Verify that `payload` was signed by the `from` address in its own authorization,
+/// against `requirement`'s EIP-712 domain on `chain_id`
+///
+/// This is [`recover_exact_payment_signer`] plus the comparison; use that function
+/// directly when the caller wants to report which address it actually recovered on
+/// mismatch.
+pub fn verify_exact_payment(
+    payload: &PaymentPayload,
+    requirement: &PaymentRequirements,
+    chain_id: u64,
+) -> Result<bool> {
+    let from_addr = Address::from_str(&payload.exact_evm()?.authorization.from)
+        .map_err(|_| X402Error::invalid_signature("Invalid from address"))?;
+    let recovered = recover_exact_payment_signer(payload, requirement, chain_id)?;
+    Ok(recovered == from_addr)
+}
+
+/// The relay connection and pairing handshake behind a [`WalletConnectSigner`]
+/// session, abstracted so tests (and callers on a platform without a websocket
+/// dependency available) can supply their own transport — the same "trait seam over
+/// an external service" shape as [`crate::facilitator::Facilitator`], whose only real
+/// implementation, an HTTP call to a facilitator service, likewise lives outside the
+/// trait.
+///
+/// A production implementation speaks the [WalletConnect v2 relay
+/// protocol](https://specs.walletconnect.com/2.0/specs/clients/core/relay/relay-api)
+/// over a websocket, encrypting each request under the pairing's symmetric key; this
+/// crate has no websocket dependency to do that with, so it isn't implemented here.
+pub trait WalletConnectTransport: Send + Sync {
+    /// Display or transmit `uri` to the remote wallet (e.g. render it as a QR code),
+    /// then block until it approves the pairing or `timeout` elapses, returning the
+    /// `eip155` accounts the session reports (e.g. `"eip155:8453:0xabc..."`)
+    fn await_session<'a>(&'a self, uri: &'a str, timeout: std::time::Duration) -> BoxFuture<'a, Result<Vec<String>>>;
+
+    /// Send an `eth_signTypedData_v4` request for `address` over the established
+    /// session and return the hex-encoded 65-byte signature it replies with
+    fn sign_typed_data<'a>(&'a self, address: &'a str, typed_data: &'a serde_json::Value) -> BoxFuture<'a, Result<String>>;
+}
+
+/// A [`Signer`] that routes EIP-712 signing through a wallet paired over
+/// [WalletConnect v2](https://specs.walletconnect.com/2.0/specs/clients/core/pairing/pairing-uri),
+/// instead of holding a private key in memory.
+///
+/// [`Self::pair`] generates a fresh pairing topic and symmetric key, builds the
+/// `wc:{topic}@2?relay-protocol=irn&symKey={sym_key}` URI ([`Self::print_uri`] renders
+/// it for display or a QR code), and blocks on [`WalletConnectTransport::await_session`]
+/// until the remote wallet approves (or `timeout` elapses), reading `address` off the
+/// first `eip155` account the session reports. [`Self::sign_message_hash`] then
+/// dispatches the `TransferWithAuthorization` digest as `eth_signTypedData_v4` over
+/// that session.
+///
+/// Only the digest is available to sign (the same contract [`Signer`] asks of
+/// [`Wallet`]), so the typed-data payload handed to the remote wallet carries just that
+/// 32-byte digest rather than the fully decoded domain/types a user-facing wallet
+/// prompt would ideally show — a known simplification of sharing one trait across a
+/// local key and a remote one.
+pub struct WalletConnectSigner<T> {
+    transport: T,
+    uri: String,
+    address: Address,
+    chain_id: u64,
+}
+
+impl<T: WalletConnectTransport> WalletConnectSigner<T> {
+    /// Pair with a remote wallet over `transport`, blocking until it approves the
+    /// session or `timeout` elapses
+    pub async fn pair(transport: T, timeout: std::time::Duration) -> Result<Self> {
+        let topic = random_hex(32);
+        let sym_key = random_hex(32);
+        let uri = format!("wc:{}@2?relay-protocol=irn&symKey={}", topic, sym_key);
+
+        let accounts = transport.await_session(&uri, timeout).await?;
+        let (chain_id, address) = Self::parse_eip155_account(&accounts)?;
+
+        Ok(Self {
+            transport,
+            uri,
+            address,
+            chain_id,
+        })
+    }
+
+    /// The `wc:` pairing URI; render this as text or feed it to a QR-code widget for
+    /// the remote wallet to scan
+    pub fn print_uri(&self) -> &str {
+        &self.uri
+    }
+
+    /// Parse the chain ID and address out of the first `eip155:{chain_id}:{address}`
+    /// account a session reports
+    fn parse_eip155_account(accounts: &[String]) -> Result<(u64, Address)> {
+        let account = accounts.first().ok_or_else(|| {
+            X402Error::invalid_authorization("WalletConnect session reported no eip155 accounts")
+        })?;
+        let mut parts = account.split(':');
+        let (Some("eip155"), Some(chain_id), Some(address)) =
+            (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(X402Error::invalid_authorization("Malformed eip155 account"));
+        };
+        let chain_id = chain_id
+            .parse()
+            .map_err(|_| X402Error::invalid_authorization("Invalid eip155 account chain ID"))?;
+        let address = Address::from_str(address)
+            .map_err(|_| X402Error::invalid_authorization("Invalid eip155 account address"))?;
+        Ok((chain_id, address))
+    }
+}
+
+impl<T: WalletConnectTransport> Signer for WalletConnectSigner<T> {
+    fn sign_message_hash<'a>(&'a self, hash: H256) -> BoxFuture<'a, Result<Signature>> {
+        Box::pin(async move {
+            let typed_data = serde_json::json!({
+                "primaryType": "TransferWithAuthorization",
+                "digest": format!("{:?}", hash),
+            });
+            let address = format!("{:?}", self.address);
+            let hex_signature = self.transport.sign_typed_data(&address, &typed_data).await?;
+            hex_signature.parse()
+        })
+    }
+
+    fn address(&self) -> Result<Address> {
+        Ok(self.address)
+    }
+
+    fn chain_id(&self) -> Result<u64> {
+        Ok(self.chain_id)
+    }
+}
+
+/// Resolve the EIP-712 domain `(verifying_contract, name, version)` for `asset` on
+/// `network`, via [`crate::token_registry::TokenRegistry`]
+///
+/// Falls back to `default_verifying_contract` (the network's own USDC deployment)
+/// with `("USD Coin", "2")` when `asset` isn't a registered token — either because
+/// it genuinely is that network's USDC, or because the caller didn't specify one —
+/// rather than erroring, so existing USDC-only callers keep working unchanged.
+fn resolve_eip712_domain(
+    network: &str,
+    asset: &str,
+    default_verifying_contract: Address,
+) -> Result<(Address, String, String)> {
+    if let Some(metadata) = crate::token_registry::TokenRegistry::new()
+        .with_known_usdc_tokens()
+        .lookup(network, asset)
+    {
+        let verifying_contract = Address::from_str(asset)
+            .map_err(|_| X402Error::invalid_network("Invalid token contract address"))?;
+        return Ok((verifying_contract, metadata.eip712_name.clone(), metadata.eip712_version.clone()));
+    }
+
+    Ok((default_verifying_contract, "USD Coin".to_string(), "2".to_string()))
+}
+
+/// `len` cryptographically random bytes, hex-encoded — used for the pairing topic
+/// and symmetric key, the same RNG [`generate_nonce`] uses
+fn random_hex(len: usize) -> String {
+    use rand::RngCore;
+    let mut bytes = vec![0u8; len];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
 /// Wallet network configuration for different blockchains
 #[derive(Debug, Clone)]
 pub struct WalletNetworkConfig {
     pub chain_id: u64,
     pub usdc_contract: Address,
+    /// EIP-712 domain `name` for this network's USDC deployment, from
+    /// [`crate::token_registry::TokenRegistry`]
+    pub token_name: String,
+    /// EIP-712 domain `version` for this network's USDC deployment, from
+    /// [`crate::token_registry::TokenRegistry`]
+    pub token_version: String,
 }
 
 /// Wallet factory for creating wallets from different sources
@@ -210,6 +675,124 @@ impl WalletFactory {
     }
 }
 
+/// Wallet implementation for Solana/SPL x402 payments — the [`schemes::EXACT_SVM`](crate::types::schemes::EXACT_SVM)
+/// counterpart to [`Wallet`]
+///
+/// Solana has no EIP-712-style "sign a struct hash, submit it alongside an unsigned
+/// transaction" split: the whole transaction, SPL `TransferChecked` instruction
+/// included, is what an ed25519 signature covers. Assembling and serializing that
+/// real transaction needs `solana-sdk`/`spl-token`, which this workspace doesn't
+/// depend on, so [`Self::sign_transfer`] instead signs a canonical encoding of the
+/// [`ExactSvmPayloadAuthorization`] fields directly and stores the signature
+/// alongside them — a placeholder wire shape documented on [`ExactSvmPayload`], good
+/// enough for a facilitator that already trusts this crate's own authorization
+/// fields, until a real transaction builder is added.
+#[derive(Clone)]
+pub struct SolanaWallet {
+    /// 32-byte ed25519 signing key seed
+    private_key: [u8; 32],
+    network: String,
+}
+
+impl SolanaWallet {
+    /// Create a wallet from a 32-byte ed25519 private key seed
+    pub fn new(private_key: [u8; 32], network: impl Into<String>) -> Self {
+        Self {
+            private_key,
+            network: network.into(),
+        }
+    }
+
+    /// This wallet's base58-encoded Solana public key (wallet address)
+    pub fn address(&self) -> Result<String> {
+        let signing_key = ed25519_dalek::SigningKey::from_bytes(&self.private_key);
+        Ok(bs58::encode(signing_key.verifying_key().to_bytes()).into_string())
+    }
+
+    /// The Solana network this wallet signs for, e.g. [`crate::types::networks::SOLANA_DEVNET`]
+    pub fn network(&self) -> &str {
+        &self.network
+    }
+
+    /// Canonical byte encoding of an authorization's fields, in field-declaration
+    /// order separated by `|`; this is what [`Self::sign_transfer`] actually signs
+    fn authorization_message(authorization: &ExactSvmPayloadAuthorization) -> Vec<u8> {
+        format!(
+            "{}|{}|{}|{}|{}",
+            authorization.from, authorization.to, authorization.mint, authorization.amount, authorization.recent_blockhash
+        )
+        .into_bytes()
+    }
+
+    /// Sign a transfer of `amount` (smallest SPL unit) of `mint` to `pay_to`, expiring
+    /// with `recent_blockhash`, returning a ready-to-submit [`SolanaPaymentPayload`]
+    pub fn sign_transfer(
+        &self,
+        pay_to: &str,
+        mint: &str,
+        amount: &str,
+        recent_blockhash: &str,
+    ) -> Result<SolanaPaymentPayload> {
+        let from = self.address()?;
+        let authorization = ExactSvmPayloadAuthorization::new(from, pay_to, mint, amount, recent_blockhash);
+
+        let signing_key = ed25519_dalek::SigningKey::from_bytes(&self.private_key);
+        let message = Self::authorization_message(&authorization);
+        let signature: ed25519_dalek::Signature = ed25519_dalek::Signer::sign(&signing_key, &message);
+
+        use base64::{engine::general_purpose, Engine as _};
+        let transaction = general_purpose::STANDARD.encode(signature.to_bytes());
+
+        let payload = ExactSvmPayload { transaction, authorization };
+        Ok(SolanaPaymentPayload::new(&self.network, payload))
+    }
+
+    /// Verify that `payload`'s signature was produced by its own `authorization.from`
+    /// address over [`Self::authorization_message`] — the verify counterpart to
+    /// [`Self::sign_transfer`]
+    ///
+    /// Unlike EVM's ecrecover, ed25519 verification needs only the public key, which
+    /// is exactly `authorization.from` base58-decoded — so this takes no signer or
+    /// private key, just the payload to check.
+    pub fn verify_transfer(payload: &SolanaPaymentPayload) -> Result<bool> {
+        let authorization = &payload.payload.authorization;
+
+        let public_key_bytes: [u8; 32] = bs58::decode(&authorization.from)
+            .into_vec()
+            .map_err(|_| X402Error::invalid_signature("Invalid base58 Solana address"))?
+            .try_into()
+            .map_err(|_| X402Error::invalid_signature("Solana address must be 32 bytes"))?;
+        let verifying_key = ed25519_dalek::VerifyingKey::from_bytes(&public_key_bytes)
+            .map_err(|_| X402Error::invalid_signature("Invalid ed25519 public key"))?;
+
+        use base64::{engine::general_purpose, Engine as _};
+        let signature_bytes: [u8; 64] = general_purpose::STANDARD
+            .decode(&payload.payload.transaction)
+            .map_err(|_| X402Error::invalid_signature("Invalid base64 signature"))?
+            .try_into()
+            .map_err(|_| X402Error::invalid_signature("Signature must be 64 bytes"))?;
+        let signature = ed25519_dalek::Signature::from_bytes(&signature_bytes);
+
+        let message = Self::authorization_message(authorization);
+        Ok(ed25519_dalek::Verifier::verify(&verifying_key, &message, &signature).is_ok())
+    }
+}
+
+/// Create wallets for Solana/SPL payments, the [`WalletFactory`] analog for [`SolanaWallet`]
+pub struct SolanaWalletFactory;
+
+impl SolanaWalletFactory {
+    /// Create a wallet from a 64-character hex-encoded ed25519 seed
+    pub fn from_private_key(private_key_hex: &str, network: &str) -> Result<SolanaWallet> {
+        let bytes = hex::decode(private_key_hex.trim_start_matches("0x"))
+            .map_err(|_| X402Error::invalid_authorization("Invalid hex in Solana private key"))?;
+        let private_key: [u8; 32] = bytes
+            .try_into()
+            .map_err(|_| X402Error::invalid_authorization("Solana private key must be 32 bytes"))?;
+        Ok(SolanaWallet::new(private_key, network.to_string()))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -285,4 +868,367 @@ mod tests {
         let config = wallet.get_network_config().unwrap();
         assert_eq!(config.chain_id, 84532);
     }
+
+    fn test_requirements() -> PaymentRequirements {
+        PaymentRequirements::new(
+            crate::types::schemes::EXACT,
+            "base-sepolia",
+            "1000000",
+            "",
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            "https://example.com/test",
+            "Test resource",
+        )
+    }
+
+    #[test]
+    fn test_resolve_eip712_domain_falls_back_to_usdc_for_an_unregistered_asset() {
+        let default_contract =
+            Address::from_str("0x036CbD53842c5426634e7929541eC2318f3dCF7e").unwrap();
+        let (verifying_contract, name, version) =
+            resolve_eip712_domain("base-sepolia", "", default_contract).unwrap();
+        assert_eq!(verifying_contract, default_contract);
+        assert_eq!(name, "USD Coin");
+        assert_eq!(version, "2");
+    }
+
+    #[test]
+    fn test_resolve_eip712_domain_uses_registry_metadata_for_a_known_asset() {
+        let default_contract =
+            Address::from_str("0x036CbD53842c5426634e7929541eC2318f3dCF7e").unwrap();
+        let (verifying_contract, name, version) = resolve_eip712_domain(
+            "base",
+            "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913",
+            default_contract,
+        )
+        .unwrap();
+        assert_eq!(
+            verifying_contract,
+            Address::from_str("0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913").unwrap()
+        );
+        assert_eq!(name, "USD Coin");
+        assert_eq!(version, "2");
+    }
+
+    #[tokio::test]
+    async fn test_wallet_as_signer_produces_a_valid_payload() {
+        let wallet = Wallet::new(
+            "0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef".to_string(),
+            "base-sepolia".to_string(),
+        );
+        let requirements = test_requirements();
+
+        let payload = create_signed_payment_payload(&wallet, &requirements)
+            .await
+            .expect("payload should build");
+
+        assert_eq!(
+            payload.exact_evm().unwrap().authorization.from,
+            format!("{:?}", wallet.address().unwrap())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_signed_payment_payload_rejects_a_signer_on_the_wrong_chain() {
+        // Wallet configured for "base" (chain 8453), but the requirements ask for
+        // "base-sepolia" (chain 84532) — the signer never agreed to sign for that chain.
+        let wallet = Wallet::new(
+            "0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef".to_string(),
+            "base".to_string(),
+        );
+        let requirements = test_requirements();
+
+        let err = create_signed_payment_payload(&wallet, &requirements)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, X402Error::WrongNetwork { .. }));
+    }
+
+    #[test]
+    fn test_sign_transfer_authorization_is_deterministic_and_produces_a_valid_signature() {
+        let wallet = Wallet::new(
+            "0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef".to_string(),
+            "base".to_string(),
+        );
+        let requirements = PaymentRequirements::new(
+            crate::types::schemes::EXACT,
+            "base",
+            "1000000",
+            "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913",
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            "https://example.com/test",
+            "Test resource",
+        );
+        let nonce = format!("{:?}", generate_nonce());
+
+        let sign = || {
+            wallet
+                .sign_transfer_authorization(
+                    &requirements,
+                    "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+                    "1000000",
+                    "1700000000",
+                    "1700000300",
+                    &nonce,
+                )
+                .expect("payload should build")
+        };
+
+        let first = sign();
+        let second = sign();
+        assert_eq!(first.signature, second.signature);
+
+        let payment_payload = PaymentPayload::new(crate::types::schemes::EXACT, "base", first);
+        assert!(verify_exact_payment(&payment_payload, &requirements, 8453).unwrap());
+    }
+
+    #[test]
+    fn test_sign_transfer_authorization_rejects_a_valid_before_already_in_the_past() {
+        let wallet = Wallet::new(
+            "0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef".to_string(),
+            "base".to_string(),
+        );
+        let requirements = test_requirements();
+
+        let err = wallet
+            .sign_transfer_authorization(
+                &requirements,
+                "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+                "1000000",
+                "1700000000",
+                "1700000300", // long past — valid_before is in 2023
+                &format!("{:?}", generate_nonce()),
+            )
+            .unwrap_err();
+
+        assert!(matches!(err, X402Error::AuthorizationExpired { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_sign_transfer_with_authorization_produces_a_valid_payload() {
+        let wallet = Wallet::new(
+            "0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef".to_string(),
+            "base".to_string(),
+        );
+        let from = format!("{:?}", wallet.address().unwrap());
+
+        let payload = sign_transfer_with_authorization(
+            &wallet,
+            "USD Coin",
+            "2",
+            8453,
+            "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913",
+            &from,
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            "1000000",
+            "1700000000",
+            "1700000300",
+            &format!("{:?}", generate_nonce()),
+        )
+        .await
+        .expect("payload should build");
+
+        assert_eq!(payload.authorization.from, from);
+        assert_eq!(payload.authorization.value, "1000000");
+    }
+
+    #[tokio::test]
+    async fn test_sign_transfer_with_authorization_rejects_a_signer_on_the_wrong_chain() {
+        let wallet = Wallet::new(
+            "0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef".to_string(),
+            "base".to_string(),
+        );
+        let from = format!("{:?}", wallet.address().unwrap());
+
+        let err = sign_transfer_with_authorization(
+            &wallet,
+            "USD Coin",
+            "2",
+            84532, // wallet is configured for chain 8453 ("base"), not 84532
+            "0x036CbD53842c5426634e7929541eC2318f3dCF7e",
+            &from,
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            "1000000",
+            "1700000000",
+            "1700000300",
+            &format!("{:?}", generate_nonce()),
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, X402Error::WrongNetwork { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_verify_exact_payment_accepts_its_own_signature() {
+        let wallet = Wallet::new(
+            "0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef".to_string(),
+            "base-sepolia".to_string(),
+        );
+        let requirements = test_requirements();
+        let payload = create_signed_payment_payload(&wallet, &requirements)
+            .await
+            .expect("payload should build");
+
+        assert!(verify_exact_payment(&payload, &requirements, 84532).expect("verification should run"));
+    }
+
+    #[tokio::test]
+    async fn test_recover_exact_payment_signer_returns_the_signing_address() {
+        let wallet = Wallet::new(
+            "0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef".to_string(),
+            "base-sepolia".to_string(),
+        );
+        let requirements = test_requirements();
+        let payload = create_signed_payment_payload(&wallet, &requirements)
+            .await
+            .expect("payload should build");
+
+        let recovered = recover_exact_payment_signer(&payload, &requirements, 84532)
+            .expect("recovery should succeed");
+        assert_eq!(recovered, wallet.address().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_verify_exact_payment_rejects_a_signature_recovered_on_the_wrong_chain() {
+        let wallet = Wallet::new(
+            "0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef".to_string(),
+            "base-sepolia".to_string(),
+        );
+        let requirements = test_requirements();
+        let payload = create_signed_payment_payload(&wallet, &requirements)
+            .await
+            .expect("payload should build");
+
+        // Same payload, but verified against a different chain id than it was signed
+        // for — the EIP-712 domain separator changes, so recovery must not match.
+        assert!(!verify_exact_payment(&payload, &requirements, 8453).expect("verification should run"));
+    }
+
+    struct MockTransport {
+        account: String,
+    }
+
+    impl WalletConnectTransport for MockTransport {
+        fn await_session<'a>(
+            &'a self,
+            _uri: &'a str,
+            _timeout: std::time::Duration,
+        ) -> BoxFuture<'a, Result<Vec<String>>> {
+            let account = self.account.clone();
+            Box::pin(async move { Ok(vec![account]) })
+        }
+
+        fn sign_typed_data<'a>(
+            &'a self,
+            _address: &'a str,
+            _typed_data: &'a serde_json::Value,
+        ) -> BoxFuture<'a, Result<String>> {
+            Box::pin(async move {
+                // A syntactically valid, arbitrary 65-byte signature; this mock
+                // doesn't have a real private key to sign with.
+                Ok(format!("0x{}", "11".repeat(65)))
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_wallet_connect_signer_pairs_and_reads_the_eip155_address() {
+        let transport = MockTransport {
+            account: "eip155:84532:0x209693Bc6afc0C5328bA36FaF03C514EF312287C".to_string(),
+        };
+        let signer = WalletConnectSigner::pair(transport, std::time::Duration::from_secs(1))
+            .await
+            .unwrap();
+
+        assert!(signer.print_uri().starts_with("wc:"));
+        assert_eq!(
+            signer.address().unwrap(),
+            Address::from_str("0x209693Bc6afc0C5328bA36FaF03C514EF312287C").unwrap()
+        );
+        assert_eq!(signer.chain_id().unwrap(), 84532);
+    }
+
+    struct EmptyTransport;
+
+    impl WalletConnectTransport for EmptyTransport {
+        fn await_session<'a>(
+            &'a self,
+            _uri: &'a str,
+            _timeout: std::time::Duration,
+        ) -> BoxFuture<'a, Result<Vec<String>>> {
+            Box::pin(async move { Ok(vec![]) })
+        }
+
+        fn sign_typed_data<'a>(
+            &'a self,
+            _address: &'a str,
+            _typed_data: &'a serde_json::Value,
+        ) -> BoxFuture<'a, Result<String>> {
+            Box::pin(async move { Ok(String::new()) })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_wallet_connect_signer_rejects_a_session_with_no_accounts() {
+        let err = WalletConnectSigner::pair(EmptyTransport, std::time::Duration::from_secs(1))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, X402Error::InvalidAuthorization { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_wallet_connect_signer_signs_through_the_transport() {
+        let transport = MockTransport {
+            account: "eip155:84532:0x209693Bc6afc0C5328bA36FaF03C514EF312287C".to_string(),
+        };
+        let signer = WalletConnectSigner::pair(transport, std::time::Duration::from_secs(1))
+            .await
+            .unwrap();
+
+        let signature = signer.sign_message_hash(H256::zero()).await.unwrap();
+        assert_eq!(signature.v, 0x11);
+    }
+
+    #[test]
+    fn test_solana_wallet_factory_rejects_a_seed_that_isnt_32_bytes() {
+        let err = SolanaWalletFactory::from_private_key("abcd", crate::types::networks::SOLANA_DEVNET)
+            .unwrap_err();
+        assert!(matches!(err, X402Error::InvalidAuthorization { .. }));
+    }
+
+    #[test]
+    fn test_solana_wallet_sign_transfer_produces_a_payload_matching_the_signer_address() {
+        let seed_hex = "11".repeat(32);
+        let wallet = SolanaWalletFactory::from_private_key(&seed_hex, crate::types::networks::SOLANA_DEVNET).unwrap();
+
+        let payload = wallet
+            .sign_transfer(
+                "9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin",
+                crate::types::networks::get_spl_usdc_mint(crate::types::networks::SOLANA_DEVNET).unwrap(),
+                "1000000",
+                "5eykt4UsFv8P8NJdTREpY1vzqKqZKvdpKuc147dw2N9d",
+            )
+            .unwrap();
+
+        assert_eq!(payload.scheme, crate::types::schemes::EXACT_SVM);
+        assert_eq!(payload.payload.authorization.from, wallet.address().unwrap());
+        payload.payload.validate().unwrap();
+    }
+
+    #[test]
+    fn test_solana_payment_payload_round_trips_through_base64() {
+        let wallet = SolanaWalletFactory::from_private_key(&"22".repeat(32), crate::types::networks::SOLANA_DEVNET).unwrap();
+        let payload = wallet
+            .sign_transfer(
+                "9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin",
+                "4zMMC9srt5Ri5X14GAgXhaHii3GnPAEERYPJgZJDncDU",
+                "500000",
+                "5eykt4UsFv8P8NJdTREpY1vzqKqZKvdpKuc147dw2N9d",
+            )
+            .unwrap();
+
+        let encoded = payload.to_base64().unwrap();
+        let decoded = SolanaPaymentPayload::from_base64(&encoded).unwrap();
+        assert_eq!(decoded.payload.authorization.amount, "500000");
+    }
 }
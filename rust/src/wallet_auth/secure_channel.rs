@@ -0,0 +1,156 @@
+//! ECDH secure channel for end-to-end encrypting CDP Wallet API request/response bodies
+//!
+//! Ordinary [`super::WalletAuth`] requests send their JSON body as plaintext, protected
+//! only by TLS and the signed JWT over the `reqHash` claim. For sensitive operations
+//! (account creation, spend-permission mutations) a caller can instead opt into this
+//! module via [`super::WalletAuth::with_secure_channel`]: an ephemeral X25519 key is
+//! generated per request, ECDH'd against the server's static public key, and the shared
+//! secret is run through HKDF-SHA256 to derive a ChaCha20-Poly1305 key that seals the
+//! body. The ephemeral public key and AEAD nonce travel alongside the ciphertext as
+//! headers so the server can redo the same derivation and open it.
+
+use super::{CdpError, Result};
+use base64::{engine::general_purpose, Engine as _};
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Nonce};
+use hkdf::Hkdf;
+use rand::RngCore;
+use sha2::Sha256;
+use x25519_dalek::{EphemeralSecret, PublicKey};
+
+/// Header carrying the base64-encoded ephemeral X25519 public key for a sealed request
+pub const EPHEMERAL_KEY_HEADER: &str = "X-Wallet-Ephemeral-Key";
+/// Header carrying the base64-encoded AEAD nonce for a sealed request
+pub const NONCE_HEADER: &str = "X-Wallet-Nonce";
+
+/// Context string binding the HKDF-derived key to this module, so the same ECDH shared
+/// secret can't be reused to derive a key for an unrelated purpose
+const HKDF_INFO: &[u8] = b"x402-wallet-secure-channel-v1";
+
+/// A ciphertext produced by [`SecureChannel::seal`], along with the headers that must
+/// accompany it so the server can derive the same key and open it
+#[derive(Debug, Clone)]
+pub struct SealedBody {
+    /// Base64-encoded AEAD ciphertext; sent as the request body in place of plaintext JSON
+    pub ciphertext: String,
+    /// Base64-encoded ephemeral X25519 public key; send as [`EPHEMERAL_KEY_HEADER`]
+    pub ephemeral_public_key: String,
+    /// Base64-encoded AEAD nonce; send as [`NONCE_HEADER`]
+    pub nonce: String,
+}
+
+/// An ECDH handshake against a server's static X25519 public key, holding the derived
+/// symmetric key used to seal the outgoing body and open the (symmetrically encrypted)
+/// response
+pub struct SecureChannel {
+    cipher: ChaCha20Poly1305,
+    ephemeral_public_key: PublicKey,
+}
+
+impl std::fmt::Debug for SecureChannel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SecureChannel").finish_non_exhaustive()
+    }
+}
+
+impl SecureChannel {
+    /// Generate a fresh ephemeral X25519 key, ECDH it against `server_public_key` (the
+    /// server's raw 32-byte static public key), and derive a ChaCha20-Poly1305 key from
+    /// the shared secret via HKDF-SHA256
+    pub fn handshake(server_public_key: &[u8; 32]) -> Self {
+        let ephemeral_secret = EphemeralSecret::random_from_rng(rand::thread_rng());
+        let ephemeral_public_key = PublicKey::from(&ephemeral_secret);
+        let server_public_key = PublicKey::from(*server_public_key);
+        let shared_secret = ephemeral_secret.diffie_hellman(&server_public_key);
+
+        let hkdf = Hkdf::<Sha256>::new(None, shared_secret.as_bytes());
+        let mut key_bytes = [0u8; 32];
+        hkdf.expand(HKDF_INFO, &mut key_bytes)
+            .expect("32 bytes is a valid ChaCha20Poly1305 key length");
+
+        let cipher = ChaCha20Poly1305::new((&key_bytes).into());
+        Self { cipher, ephemeral_public_key }
+    }
+
+    /// Seal `plaintext` (the request body, after its `reqHash` has already been
+    /// computed over it) into a [`SealedBody`] ready to send
+    pub fn seal(&self, plaintext: &[u8]) -> Result<SealedBody> {
+        let mut nonce_bytes = [0u8; 12];
+        rand::thread_rng().fill_bytes(&mut nonce_bytes);
+        let nonce = Nonce::from_slice(&nonce_bytes);
+
+        let ciphertext = self
+            .cipher
+            .encrypt(nonce, plaintext)
+            .map_err(|e| CdpError::seal(e.to_string()))?;
+
+        Ok(SealedBody {
+            ciphertext: general_purpose::STANDARD.encode(ciphertext),
+            ephemeral_public_key: general_purpose::STANDARD.encode(self.ephemeral_public_key.as_bytes()),
+            nonce: general_purpose::STANDARD.encode(nonce_bytes),
+        })
+    }
+
+    /// Open a sealed response body, given its base64 ciphertext and base64 nonce (the
+    /// `X-Wallet-Nonce` header the server sent back), using this same channel's key
+    pub fn open(&self, ciphertext_b64: &str, nonce_b64: &str) -> Result<Vec<u8>> {
+        let ciphertext = general_purpose::STANDARD
+            .decode(ciphertext_b64)
+            .map_err(|e| CdpError::unseal(format!("invalid base64 ciphertext: {}", e)))?;
+        let nonce_bytes = general_purpose::STANDARD
+            .decode(nonce_b64)
+            .map_err(|e| CdpError::unseal(format!("invalid base64 nonce: {}", e)))?;
+        if nonce_bytes.len() != 12 {
+            return Err(CdpError::unseal("nonce must be exactly 12 bytes"));
+        }
+        let nonce = Nonce::from_slice(&nonce_bytes);
+
+        self.cipher
+            .decrypt(nonce, ciphertext.as_slice())
+            .map_err(|e| CdpError::unseal(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seal_then_open_round_trips_under_the_derived_key() {
+        // x25519_dalek/chacha20poly1305 are trusted to implement ECDH/AEAD correctly;
+        // this just exercises that SecureChannel wires seal/open to the same derived
+        // key and that base64 encode/decode of the envelope round-trips.
+        let server_secret = EphemeralSecret::random_from_rng(rand::thread_rng());
+        let server_public_key = PublicKey::from(&server_secret);
+
+        let channel = SecureChannel::handshake(server_public_key.as_bytes());
+        let sealed = channel.seal(b"{\"amount\":1}").unwrap();
+        let opened = channel.open(&sealed.ciphertext, &sealed.nonce).unwrap();
+        assert_eq!(opened, b"{\"amount\":1}");
+    }
+
+    #[test]
+    fn test_open_rejects_tampered_ciphertext() {
+        let server_secret = EphemeralSecret::random_from_rng(rand::thread_rng());
+        let server_public_key = PublicKey::from(&server_secret);
+        let channel = SecureChannel::handshake(server_public_key.as_bytes());
+
+        let mut sealed = channel.seal(b"hello").unwrap();
+        let mut raw = general_purpose::STANDARD.decode(&sealed.ciphertext).unwrap();
+        raw[0] ^= 0xff;
+        sealed.ciphertext = general_purpose::STANDARD.encode(raw);
+
+        assert!(channel.open(&sealed.ciphertext, &sealed.nonce).is_err());
+    }
+
+    #[test]
+    fn test_open_rejects_malformed_nonce_length() {
+        let server_secret = EphemeralSecret::random_from_rng(rand::thread_rng());
+        let server_public_key = PublicKey::from(&server_secret);
+        let channel = SecureChannel::handshake(server_public_key.as_bytes());
+
+        let sealed = channel.seal(b"hello").unwrap();
+        let short_nonce = general_purpose::STANDARD.encode([0u8; 4]);
+        assert!(matches!(channel.open(&sealed.ciphertext, &short_nonce), Err(CdpError::Unseal { .. })));
+    }
+}
@@ -3,11 +3,14 @@
 //! This module provides integration with the Warp framework.
 
 use crate::middleware::PaymentMiddleware;
+use crate::settlement_status::{SettlementStatus, SettlementStatusTracker};
 use crate::types::{PaymentPayload, PaymentRequirements, PaymentRequirementsResponse};
+use futures_util::stream::StreamExt;
 use warp::{
     http::StatusCode,
     reject::{Reject, Rejection},
     reply::{json, with_status},
+    sse::Event,
     Filter, Reply,
 };
 
@@ -181,6 +184,49 @@ pub fn create_x402_middleware(
     x402_payment_filter(payment_middleware)
 }
 
+/// SSE `event:` name for a [`SettlementStatus`], matching the `#[serde(tag = "event",
+/// rename_all = "snake_case")]` discriminant already carried in its JSON body
+fn settlement_status_event_name(status: &SettlementStatus) -> &'static str {
+    match status {
+        SettlementStatus::Verifying => "verifying",
+        SettlementStatus::Submitted { .. } => "submitted",
+        SettlementStatus::Confirmed { .. } => "confirmed",
+        SettlementStatus::Failed { .. } => "failed",
+    }
+}
+
+/// Mount a `GET x402/settlement/{payment_id}` SSE endpoint streaming
+/// [`SettlementStatus`] transitions for `payment_id`, analogous to
+/// [`crate::axum::settlement_status_route`]. See [`crate::settlement_status`] for how
+/// those transitions get published as a payment is driven through verify→settle.
+pub fn settlement_status_route(
+    tracker: SettlementStatusTracker,
+) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    warp::path!("x402" / "settlement" / String)
+        .and(warp::get())
+        .and_then(move |payment_id: String| {
+            let tracker = tracker.clone();
+            async move {
+                let receiver = tracker.subscribe(&payment_id).await;
+                let stream = futures_util::stream::unfold(receiver, |mut receiver| async move {
+                    match receiver.recv().await {
+                        Ok(status) => {
+                            let event = Event::default()
+                                .event(settlement_status_event_name(&status))
+                                .json_data(&status)
+                                .unwrap_or_else(|_| {
+                                    Event::default().event("failed").data("serialization error")
+                                });
+                            Some((Ok::<_, Rejection>(event), receiver))
+                        }
+                        Err(_) => None,
+                    }
+                });
+                Ok::<_, Rejection>(warp::sse::reply(warp::sse::keep_alive().stream(stream.boxed())))
+            }
+        })
+}
+
 /// Handle payment verification in Warp handlers
 pub async fn handle_payment_verification(
     _requirements: &[PaymentRequirements],
@@ -209,6 +255,7 @@ mod tests {
             max_timeout_seconds: 300,
             output_schema: None,
             extra: None,
+            payment_uri: None,
         }];
 
         let rejection = PaymentRequired {
@@ -0,0 +1,575 @@
+//! Webhook notifications for settlement lifecycle events
+//!
+//! The synchronous `/settle` response only reports the facilitator's final
+//! verdict; a resource server that wants to track a payment as it moves through
+//! `verified` → `submitted` → `confirmed`/`failed` otherwise has no way to observe
+//! the intermediate states. [`WebhookNotifier`] POSTs a [`SettlementEvent`] to every
+//! registered [`WebhookConfig`] on each transition, signing the raw body with
+//! HMAC-SHA256 over a shared secret (the [`SIGNATURE_HEADER`] header) the same way
+//! hyperswitch's cryptopay-style connectors report settlement state to merchants,
+//! and retries non-2xx responses with [`crate::retry::RetryPolicy`]'s exponential
+//! backoff. Every attempt is recorded in a [`WebhookDeliveryStore`] so a delivery
+//! that exhausts its retries can be replayed later via [`WebhookNotifier::replay_failed`].
+//!
+//! [`FacilitatorWebhook`] is the receiving counterpart, for a server on the other end
+//! of a facilitator's own asynchronous settlement notifications: it verifies the
+//! signature and timestamp of an inbound delivery and parses it back into a
+//! [`SettlementEvent`].
+
+use crate::idempotency::BoxFuture;
+use crate::retry::RetryPolicy;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tracing::warn;
+
+/// Lifecycle state a settlement has moved to, reported in a [`SettlementEvent`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SettlementStatus {
+    Verified,
+    Submitted,
+    Confirmed,
+    Failed,
+}
+
+/// Outbound payload describing a settlement's state transition
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SettlementEvent {
+    pub event_type: String,
+    pub payment_nonce: String,
+    pub network: String,
+    pub transaction: String,
+    pub payer: Option<String>,
+    pub status: SettlementStatus,
+}
+
+impl SettlementEvent {
+    /// Build a `settlement.status_changed` event for the ERC-3009 authorization
+    /// identified by `payment_nonce`
+    pub fn new(
+        payment_nonce: impl Into<String>,
+        network: impl Into<String>,
+        transaction: impl Into<String>,
+        payer: Option<String>,
+        status: SettlementStatus,
+    ) -> Self {
+        Self {
+            event_type: "settlement.status_changed".to_string(),
+            payment_nonce: payment_nonce.into(),
+            network: network.into(),
+            transaction: transaction.into(),
+            payer,
+            status,
+        }
+    }
+}
+
+/// A registered webhook endpoint and the shared secret used to sign deliveries to it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookConfig {
+    pub url: String,
+    pub signing_secret: String,
+}
+
+impl WebhookConfig {
+    pub fn new(url: impl Into<String>, signing_secret: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            signing_secret: signing_secret.into(),
+        }
+    }
+}
+
+/// A single delivery attempt of a [`SettlementEvent`] to one [`WebhookConfig`]
+#[derive(Debug, Clone)]
+pub struct WebhookDelivery {
+    pub id: String,
+    pub url: String,
+    pub event: SettlementEvent,
+    pub attempts: u32,
+    pub delivered: bool,
+    pub last_error: Option<String>,
+}
+
+/// Storage backend for webhook delivery attempts, so a delivery that exhausts its
+/// retries isn't lost and can be replayed once the receiving endpoint recovers
+pub trait WebhookDeliveryStore: Send + Sync {
+    /// Record a delivery that's about to be attempted
+    fn record(&self, delivery: WebhookDelivery) -> BoxFuture<'_, ()>;
+
+    /// Mark `id` as successfully delivered
+    fn mark_delivered(&self, id: &str) -> BoxFuture<'_, ()>;
+
+    /// Record a failed attempt's updated attempt count and error
+    fn record_failure<'a>(&'a self, id: &'a str, attempts: u32, error: String) -> BoxFuture<'a, ()>;
+
+    /// All deliveries that have not yet succeeded, for [`WebhookNotifier::replay_failed`]
+    fn undelivered(&self) -> BoxFuture<'_, Vec<WebhookDelivery>>;
+}
+
+/// In-memory [`WebhookDeliveryStore`], suitable for a single-process deployment
+#[derive(Default)]
+pub struct InMemoryWebhookDeliveryStore {
+    entries: Mutex<HashMap<String, WebhookDelivery>>,
+}
+
+impl InMemoryWebhookDeliveryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl WebhookDeliveryStore for InMemoryWebhookDeliveryStore {
+    fn record(&self, delivery: WebhookDelivery) -> BoxFuture<'_, ()> {
+        Box::pin(async move {
+            self.entries.lock().await.insert(delivery.id.clone(), delivery);
+        })
+    }
+
+    fn mark_delivered(&self, id: &str) -> BoxFuture<'_, ()> {
+        let id = id.to_string();
+        Box::pin(async move {
+            if let Some(entry) = self.entries.lock().await.get_mut(&id) {
+                entry.delivered = true;
+            }
+        })
+    }
+
+    fn record_failure<'a>(&'a self, id: &'a str, attempts: u32, error: String) -> BoxFuture<'a, ()> {
+        Box::pin(async move {
+            if let Some(entry) = self.entries.lock().await.get_mut(id) {
+                entry.attempts = attempts;
+                entry.last_error = Some(error);
+            }
+        })
+    }
+
+    fn undelivered(&self) -> BoxFuture<'_, Vec<WebhookDelivery>> {
+        Box::pin(async move {
+            self.entries
+                .lock()
+                .await
+                .values()
+                .filter(|delivery| !delivery.delivered)
+                .cloned()
+                .collect()
+        })
+    }
+}
+
+/// Header a [`WebhookNotifier`] signs each delivery's raw body under
+pub const SIGNATURE_HEADER: &str = "X-Signature";
+
+/// Header carrying the Unix timestamp (seconds) a [`WebhookNotifier`] delivery was
+/// signed at, included in the signed message so [`FacilitatorWebhook::parse_and_verify`]
+/// can reject a stale or replayed delivery
+pub const TIMESTAMP_HEADER: &str = "X-Webhook-Timestamp";
+
+/// HMAC-SHA256 over `body` keyed by `secret`, hex-encoded
+///
+/// Implemented directly against [`Sha256`] per RFC 2104 rather than pulling in a
+/// separate `hmac` crate, matching how this crate already hand-rolls other
+/// single-use primitives (e.g. `real_facilitator`'s RLP encoder, `crypto::signature`'s
+/// module-local `keccak256`).
+fn hmac_sha256(secret: &[u8], body: &[u8]) -> String {
+    const BLOCK_SIZE: usize = 64;
+
+    let mut key = [0u8; BLOCK_SIZE];
+    if secret.len() > BLOCK_SIZE {
+        let hashed = Sha256::digest(secret);
+        key[..hashed.len()].copy_from_slice(&hashed);
+    } else {
+        key[..secret.len()].copy_from_slice(secret);
+    }
+
+    let mut ipad = [0x36u8; BLOCK_SIZE];
+    let mut opad = [0x5cu8; BLOCK_SIZE];
+    for i in 0..BLOCK_SIZE {
+        ipad[i] ^= key[i];
+        opad[i] ^= key[i];
+    }
+
+    let mut inner = Sha256::new();
+    inner.update(ipad);
+    inner.update(body);
+    let inner_hash = inner.finalize();
+
+    let mut outer = Sha256::new();
+    outer.update(opad);
+    outer.update(inner_hash);
+    hex::encode(outer.finalize())
+}
+
+/// The bytes actually signed for a delivery: `timestamp` and `body` joined by a `.`,
+/// the same construction [`FacilitatorWebhook::parse_and_verify`] reconstructs on the
+/// receiving end
+fn signed_message(timestamp: &str, body: &[u8]) -> Vec<u8> {
+    let mut message = Vec::with_capacity(timestamp.len() + 1 + body.len());
+    message.extend_from_slice(timestamp.as_bytes());
+    message.push(b'.');
+    message.extend_from_slice(body);
+    message
+}
+
+/// Constant-time byte comparison, so verifying a forged signature byte-by-byte can't
+/// be timed to recover the correct one
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b.iter()).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// How old a [`FacilitatorWebhook::parse_and_verify`] timestamp is allowed to be
+/// before the delivery is rejected as stale, guarding against a captured delivery
+/// being replayed long after the fact
+pub const MAX_WEBHOOK_SKEW: std::time::Duration = std::time::Duration::from_secs(300);
+
+/// Receives and authenticates inbound settlement notifications from a facilitator,
+/// the counterpart to [`WebhookNotifier`] for a resource server that wants to
+/// reconcile asynchronous settlement confirmations instead of polling `/settle`
+pub struct FacilitatorWebhook;
+
+impl FacilitatorWebhook {
+    /// Parse `body` into a [`SettlementEvent`] after verifying `headers` carries a
+    /// valid [`SIGNATURE_HEADER`] (HMAC-SHA256 over `timestamp.body` keyed by
+    /// `secret`) and a [`TIMESTAMP_HEADER`] within [`MAX_WEBHOOK_SKEW`] of now.
+    ///
+    /// Rejects with [`crate::X402Error::InvalidSignature`] on a missing header, a
+    /// signature mismatch, or a stale/future timestamp, before the body is ever
+    /// deserialized.
+    pub fn parse_and_verify(
+        headers: &HashMap<String, String>,
+        body: &[u8],
+        secret: &str,
+    ) -> crate::Result<SettlementEvent> {
+        let signature = headers
+            .get(SIGNATURE_HEADER)
+            .ok_or_else(|| crate::X402Error::invalid_signature("Missing signature header"))?;
+        let timestamp_header = headers
+            .get(TIMESTAMP_HEADER)
+            .ok_or_else(|| crate::X402Error::invalid_signature("Missing timestamp header"))?;
+        let timestamp: i64 = timestamp_header
+            .parse()
+            .map_err(|_| crate::X402Error::invalid_signature("Timestamp header is not a valid integer"))?;
+
+        let skew = (chrono::Utc::now().timestamp() - timestamp).unsigned_abs();
+        if skew > MAX_WEBHOOK_SKEW.as_secs() {
+            return Err(crate::X402Error::invalid_signature("Webhook timestamp is too old or in the future"));
+        }
+
+        let expected = hmac_sha256(secret.as_bytes(), &signed_message(timestamp_header, body));
+        if !constant_time_eq(expected.as_bytes(), signature.as_bytes()) {
+            return Err(crate::X402Error::invalid_signature("Webhook signature does not match"));
+        }
+
+        let event: SettlementEvent = serde_json::from_slice(body)?;
+        Ok(event)
+    }
+}
+
+/// Delivers [`SettlementEvent`]s to every registered [`WebhookConfig`], signing each
+/// body with HMAC-SHA256 and retrying non-2xx responses with exponential backoff
+pub struct WebhookNotifier {
+    webhooks: Vec<WebhookConfig>,
+    store: Arc<dyn WebhookDeliveryStore>,
+    retry_policy: RetryPolicy,
+    http: reqwest::Client,
+}
+
+impl WebhookNotifier {
+    /// Notify `webhooks` on future [`Self::notify`] calls, with the default retry
+    /// policy and an in-memory delivery store
+    pub fn new(webhooks: Vec<WebhookConfig>) -> Self {
+        Self {
+            webhooks,
+            store: Arc::new(InMemoryWebhookDeliveryStore::new()),
+            retry_policy: RetryPolicy::new(),
+            http: reqwest::Client::new(),
+        }
+    }
+
+    /// Persist delivery attempts in `store` instead of the in-memory default
+    pub fn with_store(mut self, store: Arc<dyn WebhookDeliveryStore>) -> Self {
+        self.store = store;
+        self
+    }
+
+    /// Override the exponential backoff policy used between delivery attempts
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Register an additional webhook to notify of future events, e.g. from a
+    /// `/webhooks` registration endpoint (see [`crate::axum::webhook_registration_route`])
+    pub fn register(&mut self, webhook: WebhookConfig) {
+        self.webhooks.push(webhook);
+    }
+
+    /// Number of webhooks currently registered
+    pub fn webhook_count(&self) -> usize {
+        self.webhooks.len()
+    }
+
+    /// Notify every registered webhook of `event`, independently retrying each
+    /// delivery with exponential backoff
+    pub async fn notify(&self, event: &SettlementEvent) {
+        for webhook in &self.webhooks {
+            self.deliver(webhook, event.clone()).await;
+        }
+    }
+
+    /// Retry every delivery the store still has recorded as undelivered, e.g. on a
+    /// schedule or after a receiving endpoint is known to have recovered
+    pub async fn replay_failed(&self) {
+        for delivery in self.store.undelivered().await {
+            if let Some(webhook) = self.webhooks.iter().find(|webhook| webhook.url == delivery.url) {
+                self.deliver(webhook, delivery.event).await;
+            }
+        }
+    }
+
+    async fn deliver(&self, webhook: &WebhookConfig, event: SettlementEvent) {
+        let delivery_id = format!("{}:{}:{:?}", webhook.url, event.payment_nonce, event.status);
+        self.store
+            .record(WebhookDelivery {
+                id: delivery_id.clone(),
+                url: webhook.url.clone(),
+                event: event.clone(),
+                attempts: 0,
+                delivered: false,
+                last_error: None,
+            })
+            .await;
+
+        let body = match serde_json::to_vec(&event) {
+            Ok(body) => body,
+            Err(error) => {
+                self.store.record_failure(&delivery_id, 0, error.to_string()).await;
+                warn!("Failed to serialize webhook event for {}: {}", webhook.url, error);
+                return;
+            }
+        };
+        let timestamp = chrono::Utc::now().timestamp().to_string();
+        let signature = hmac_sha256(webhook.signing_secret.as_bytes(), &signed_message(&timestamp, &body));
+
+        let mut attempt = 0;
+        loop {
+            let outcome = self
+                .http
+                .post(&webhook.url)
+                .header(SIGNATURE_HEADER, &signature)
+                .header(TIMESTAMP_HEADER, &timestamp)
+                .header("content-type", "application/json")
+                .body(body.clone())
+                .send()
+                .await;
+
+            let error = match outcome {
+                Ok(response) if response.status().is_success() => {
+                    self.store.mark_delivered(&delivery_id).await;
+                    return;
+                }
+                Ok(response) => format!("webhook responded with status {}", response.status()),
+                Err(error) => format!("webhook request failed: {}", error),
+            };
+
+            attempt += 1;
+            self.store.record_failure(&delivery_id, attempt, error.clone()).await;
+
+            if attempt >= self.retry_policy.max_attempts {
+                warn!(
+                    "Webhook delivery to {} for nonce {} permanently failed after {} attempts: {}",
+                    webhook.url, event.payment_nonce, attempt, error
+                );
+                return;
+            }
+            tokio::time::sleep(self.retry_policy.delay_for_attempt(attempt - 1)).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hmac_sha256_matches_known_test_vector() {
+        // RFC 4231 test case 1
+        let key = [0x0bu8; 20];
+        let signature = hmac_sha256(&key, b"Hi There");
+        assert_eq!(
+            signature,
+            "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff"
+        );
+    }
+
+    #[test]
+    fn test_hmac_sha256_differs_for_different_secrets() {
+        let a = hmac_sha256(b"secret-a", b"payload");
+        let b = hmac_sha256(b"secret-b", b"payload");
+        assert_ne!(a, b);
+    }
+
+    #[tokio::test]
+    async fn test_notifier_delivers_and_marks_store() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/hook")
+            .match_header(SIGNATURE_HEADER, mockito::Matcher::Any)
+            .with_status(200)
+            .expect(1)
+            .create();
+
+        let store = Arc::new(InMemoryWebhookDeliveryStore::new());
+        let notifier = WebhookNotifier::new(vec![WebhookConfig::new(
+            format!("{}/hook", server.url()),
+            "shhh",
+        )])
+        .with_store(store.clone());
+
+        let event = SettlementEvent::new(
+            "0xnonce",
+            "base-sepolia",
+            "0xabc",
+            Some("0xpayer".to_string()),
+            SettlementStatus::Confirmed,
+        );
+        notifier.notify(&event).await;
+
+        mock.assert();
+        assert!(store.undelivered().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_notifier_retries_non_2xx_and_records_failure() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server.mock("POST", "/hook").with_status(500).expect(2).create();
+
+        let store = Arc::new(InMemoryWebhookDeliveryStore::new());
+        let notifier = WebhookNotifier::new(vec![WebhookConfig::new(format!("{}/hook", server.url()), "shhh")])
+            .with_store(store.clone())
+            .with_retry_policy(
+                RetryPolicy::new()
+                    .with_base_delay(std::time::Duration::from_millis(1))
+                    .with_max_attempts(2),
+            );
+
+        let event = SettlementEvent::new("0xnonce", "base-sepolia", "0xabc", None, SettlementStatus::Failed);
+        notifier.notify(&event).await;
+
+        mock.assert();
+        let undelivered = store.undelivered().await;
+        assert_eq!(undelivered.len(), 1);
+        assert_eq!(undelivered[0].attempts, 2);
+    }
+
+    fn signed_headers(secret: &str, body: &[u8]) -> HashMap<String, String> {
+        let timestamp = chrono::Utc::now().timestamp().to_string();
+        let signature = hmac_sha256(secret.as_bytes(), &signed_message(&timestamp, body));
+        HashMap::from([
+            (SIGNATURE_HEADER.to_string(), signature),
+            (TIMESTAMP_HEADER.to_string(), timestamp),
+        ])
+    }
+
+    #[test]
+    fn test_facilitator_webhook_parses_a_correctly_signed_delivery() {
+        let event = SettlementEvent::new("0xnonce", "base-sepolia", "0xabc", None, SettlementStatus::Confirmed);
+        let body = serde_json::to_vec(&event).unwrap();
+        let headers = signed_headers("shhh", &body);
+
+        let parsed = FacilitatorWebhook::parse_and_verify(&headers, &body, "shhh").unwrap();
+        assert_eq!(parsed.payment_nonce, "0xnonce");
+        assert_eq!(parsed.status, SettlementStatus::Confirmed);
+    }
+
+    #[test]
+    fn test_facilitator_webhook_rejects_a_signature_from_the_wrong_secret() {
+        let event = SettlementEvent::new("0xnonce", "base-sepolia", "0xabc", None, SettlementStatus::Confirmed);
+        let body = serde_json::to_vec(&event).unwrap();
+        let headers = signed_headers("shhh", &body);
+
+        let error = FacilitatorWebhook::parse_and_verify(&headers, &body, "different-secret").unwrap_err();
+        assert!(matches!(error, crate::X402Error::InvalidSignature { .. }));
+    }
+
+    #[test]
+    fn test_facilitator_webhook_rejects_a_tampered_body() {
+        let event = SettlementEvent::new("0xnonce", "base-sepolia", "0xabc", None, SettlementStatus::Confirmed);
+        let body = serde_json::to_vec(&event).unwrap();
+        let headers = signed_headers("shhh", &body);
+
+        let tampered = serde_json::to_vec(&SettlementEvent::new(
+            "0xnonce",
+            "base-sepolia",
+            "0xabc",
+            None,
+            SettlementStatus::Failed,
+        ))
+        .unwrap();
+
+        let error = FacilitatorWebhook::parse_and_verify(&headers, &tampered, "shhh").unwrap_err();
+        assert!(matches!(error, crate::X402Error::InvalidSignature { .. }));
+    }
+
+    #[test]
+    fn test_facilitator_webhook_rejects_a_stale_timestamp() {
+        let event = SettlementEvent::new("0xnonce", "base-sepolia", "0xabc", None, SettlementStatus::Confirmed);
+        let body = serde_json::to_vec(&event).unwrap();
+
+        let stale_timestamp = (chrono::Utc::now().timestamp() - MAX_WEBHOOK_SKEW.as_secs() as i64 - 60).to_string();
+        let signature = hmac_sha256(b"shhh", &signed_message(&stale_timestamp, &body));
+        let headers = HashMap::from([
+            (SIGNATURE_HEADER.to_string(), signature),
+            (TIMESTAMP_HEADER.to_string(), stale_timestamp),
+        ]);
+
+        let error = FacilitatorWebhook::parse_and_verify(&headers, &body, "shhh").unwrap_err();
+        assert!(matches!(error, crate::X402Error::InvalidSignature { .. }));
+    }
+
+    #[test]
+    fn test_facilitator_webhook_rejects_a_missing_signature_header() {
+        let event = SettlementEvent::new("0xnonce", "base-sepolia", "0xabc", None, SettlementStatus::Confirmed);
+        let body = serde_json::to_vec(&event).unwrap();
+        let headers = HashMap::from([(TIMESTAMP_HEADER.to_string(), chrono::Utc::now().timestamp().to_string())]);
+
+        let error = FacilitatorWebhook::parse_and_verify(&headers, &body, "shhh").unwrap_err();
+        assert!(matches!(error, crate::X402Error::InvalidSignature { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_notifier_delivery_includes_timestamp_header() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/hook")
+            .match_header(SIGNATURE_HEADER, mockito::Matcher::Any)
+            .match_header(TIMESTAMP_HEADER, mockito::Matcher::Any)
+            .with_status(200)
+            .expect(1)
+            .create();
+
+        let store = Arc::new(InMemoryWebhookDeliveryStore::new());
+        let notifier = WebhookNotifier::new(vec![WebhookConfig::new(
+            format!("{}/hook", server.url()),
+            "shhh",
+        )])
+        .with_store(store.clone());
+
+        let event = SettlementEvent::new(
+            "0xnonce",
+            "base-sepolia",
+            "0xabc",
+            Some("0xpayer".to_string()),
+            SettlementStatus::Confirmed,
+        );
+        notifier.notify(&event).await;
+
+        mock.assert();
+    }
+}
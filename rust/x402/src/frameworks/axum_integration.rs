@@ -14,12 +14,44 @@ use crate::facilitator::FacilitatorClient;
 use crate::server::{InMemoryResourceServer, ResourceConfig, ResourceServer, SchemeNetworkServer};
 
 
+/// Outcome of a per-request pricing hook registered via
+/// [`X402ConfigBuilder::register_resource_with`], evaluated by [`x402_middleware`]
+/// before it would otherwise emit a 402 for a fixed [`ResourceConfig`]
+pub enum PaymentDecision {
+    /// Let the request through with no payment required at all
+    Free,
+    /// Require payment priced according to `resource_config`, exactly as if this
+    /// route had instead been registered via [`X402ConfigBuilder::register_resource`]
+    /// with it
+    Require(ResourceConfig),
+    /// Require payment against an already-built [`PaymentRequirements`], bypassing
+    /// the resource server's scheme lookup entirely - useful when the hook itself
+    /// knows the exact terms (e.g. a tiered price it computed from a query param)
+    RequireWith(PaymentRequirements),
+}
+
+/// Per-request pricing/gating hook registered via
+/// [`X402ConfigBuilder::register_resource_with`]
+///
+/// This crate has no `Price` type of its own to match the hook's literal signature
+/// against (the closest existing notion of "how much a route costs" is
+/// [`ResourceConfig`], which [`register_resource`](X402ConfigBuilder::register_resource)
+/// already takes), so the hook is typed to return a [`PaymentDecision`] wrapping
+/// whichever of `ResourceConfig`/[`PaymentRequirements`] fits the operator's use case
+/// instead.
+pub type PricingHook = Arc<dyn Fn(&Request<Body>) -> PaymentDecision + Send + Sync>;
+
 #[derive(Clone)]
 pub struct RouteMeta {
     pub resource_url: String,
     pub description: Option<String>,
     pub mime_type: Option<String>,
-    pub resource_config: ResourceConfig,
+    /// Fixed pricing for this route, set by [`X402ConfigBuilder::register_resource`].
+    /// `None` when the route was instead registered with a [`PricingHook`], which
+    /// decides pricing per request instead.
+    pub resource_config: Option<ResourceConfig>,
+    /// Per-request pricing hook set by [`X402ConfigBuilder::register_resource_with`]
+    pub pricing_hook: Option<PricingHook>,
 }
 
 #[derive(Clone)]
@@ -64,7 +96,32 @@ impl X402ConfigBuilder {
             resource_url: resource_url.clone(),
             description,
             mime_type,
-            resource_config,
+            resource_config: Some(resource_config),
+            pricing_hook: None,
+        };
+        self.routes.insert(resource_url, meta);
+        self
+    }
+
+    /// Register a route whose price (or whether payment is required at all) is
+    /// decided per request by `hook`, instead of a fixed [`ResourceConfig`]. This is
+    /// how an operator does tiered pricing, a free-tier quota, or bypassing payment
+    /// for allow-listed callers, without forking [`x402_middleware`] itself - the hook
+    /// sees the inbound [`Request`] (query params, headers, body size, ...) and
+    /// returns a [`PaymentDecision`] for it.
+    pub fn register_resource_with(
+        &mut self,
+        resource_url: String,
+        description: Option<String>,
+        mime_type: Option<String>,
+        hook: impl Fn(&Request<Body>) -> PaymentDecision + Send + Sync + 'static,
+    ) -> &mut Self {
+        let meta = RouteMeta {
+            resource_url: resource_url.clone(),
+            description,
+            mime_type,
+            resource_config: None,
+            pricing_hook: Some(Arc::new(hook)),
         };
         self.routes.insert(resource_url, meta);
         self
@@ -87,9 +144,9 @@ pub async fn x402_middleware(
 ) -> Response<Body> {
 
     // Configuration
-    let path = req.uri().path();
+    let path = req.uri().path().to_string();
 
-    let route = match config.routes.get(path) {
+    let route = match config.routes.get(&path) {
         Some(route) => route,
         None => {
             return (
@@ -99,15 +156,41 @@ pub async fn x402_middleware(
         }
     };
 
-    // Build the payment requirements we have registered in the resource server
-    let accepts = match config.resource_server.build_payment_requirements(&route.resource_config) {
-        Ok(payment_required) => payment_required,
-        Err(e) => {
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to build payment requirements for route {}: {}", path, e),
-            ).into_response();
+    // Evaluate the route's pricing hook (if any) before deciding what - if anything -
+    // to charge for this particular request. A route registered with
+    // `register_resource` instead of `register_resource_with` has no hook, so it
+    // always falls back to its fixed `resource_config`.
+    let decision = match &route.pricing_hook {
+        Some(hook) => hook(&req),
+        None => match &route.resource_config {
+            Some(resource_config) => PaymentDecision::Require(resource_config.clone()),
+            None => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("Route {} has neither a resource_config nor a pricing hook registered", path),
+                ).into_response();
+            }
+        },
+    };
+
+    // Build the payment requirements to charge against, honoring the hook's
+    // decision to let this request through for free, price it using a
+    // `ResourceConfig` exactly as a fixed route would, or bypass the resource
+    // server entirely with already-built requirements.
+    let accepts = match decision {
+        PaymentDecision::Free => return next.run(req).await,
+        PaymentDecision::Require(resource_config) => {
+            match config.resource_server.build_payment_requirements(&resource_config) {
+                Ok(payment_required) => payment_required,
+                Err(e) => {
+                    return (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        format!("Failed to build payment requirements for route {}: {}", path, e),
+                    ).into_response();
+                }
+            }
         }
+        PaymentDecision::RequireWith(requirements) => vec![requirements],
     };
 
     let signature_header = req.headers()
@@ -431,4 +514,83 @@ mod tests {
         // This will contain the reqwest error message
         assert!(body_str.contains("Facilitator error"));
     }
+
+    #[tokio::test]
+    async fn test_register_resource_with_free_decision_bypasses_payment() {
+        let facilitator: Arc<dyn FacilitatorClient> = Arc::new(HttpFacilitator::new("http://127.0.0.1:1"));
+        let network = Network::new("ethereum".to_string(), "1".to_string());
+
+        let mut builder = X402ConfigBuilder::new(facilitator);
+        builder
+            .register_scheme(network, Arc::new(MockSchemeServer))
+            .register_resource_with("/test".to_string(), None, None, |req| {
+                if req.headers().get("X-Allowlisted").is_some() {
+                    PaymentDecision::Free
+                } else {
+                    PaymentDecision::Require(ResourceConfig::new(
+                        "exact",
+                        "0x123",
+                        "100".into(),
+                        Network::new("ethereum".to_string(), "1".to_string()),
+                        None,
+                    ))
+                }
+            });
+        let config = builder.build();
+
+        let app = Router::new()
+            .route("/test", get(|| async { "Success" }))
+            .layer(axum::middleware::from_fn_with_state(config, x402_middleware));
+
+        // An allow-listed caller skips payment entirely, even with no facilitator
+        // reachable at all.
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/test")
+                    .header("X-Allowlisted", "1")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), 1024).await.unwrap();
+        assert_eq!(body, "Success");
+    }
+
+    #[tokio::test]
+    async fn test_register_resource_with_require_with_decision_sets_exact_requirements() {
+        let facilitator: Arc<dyn FacilitatorClient> = Arc::new(HttpFacilitator::new("http://127.0.0.1:1"));
+        let network = Network::new("ethereum".to_string(), "1".to_string());
+
+        let mut builder = X402ConfigBuilder::new(facilitator);
+        builder
+            .register_scheme(network, Arc::new(MockSchemeServer))
+            .register_resource_with("/test".to_string(), None, None, |_req| {
+                PaymentDecision::RequireWith(PaymentRequirements {
+                    scheme: "exact".to_string(),
+                    network: "ethereum:1".to_string(),
+                    pay_to: "0x123".to_string(),
+                    amount: "500".to_string(),
+                    asset: None,
+                    data: None,
+                    extra: None,
+                })
+            });
+        let config = builder.build();
+
+        let app = Router::new()
+            .route("/test", get(|| async { "Success" }))
+            .layer(axum::middleware::from_fn_with_state(config, x402_middleware));
+
+        let response = app
+            .oneshot(Request::builder().uri("/test").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::PAYMENT_REQUIRED);
+        assert!(response.headers().contains_key("PAYMENT-REQUIRED"));
+    }
 }
\ No newline at end of file
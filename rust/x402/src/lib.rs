@@ -3,6 +3,7 @@ pub mod client;
 pub mod errors;
 pub mod facilitator;
 pub mod frameworks;
+pub mod retry;
 pub mod schemes;
 pub mod server;
 pub mod types;
@@ -0,0 +1,120 @@
+//! Retry policy for facilitator verify/settle calls
+//!
+//! A flaky facilitator shouldn't immediately surface a 502 to a paying client when
+//! the request would likely succeed on retry. [`RetryPolicy`] describes exponential
+//! backoff with full jitter, bounded by a max delay and a max attempt count.
+
+use rand::Rng;
+use std::time::Duration;
+
+/// Configurable exponential backoff policy for retrying facilitator requests
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Delay before the first retry
+    pub base_delay: Duration,
+    /// Upper bound on any single retry delay
+    pub max_delay: Duration,
+    /// Maximum number of attempts (including the initial one)
+    pub max_attempts: u32,
+}
+
+impl RetryPolicy {
+    /// Create a retry policy with sensible defaults (200ms base, 5s cap, 3 attempts)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the base delay before the first retry
+    pub fn with_base_delay(mut self, base_delay: Duration) -> Self {
+        self.base_delay = base_delay;
+        self
+    }
+
+    /// Set the maximum delay between retries
+    pub fn with_max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Set the maximum number of attempts
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// Compute the full-jitter delay before retrying after the given zero-indexed
+    /// attempt: a random duration in `[0, min(max_delay, base_delay * 2^attempt)]`
+    pub fn delay_for_attempt(&self, attempt: u32) -> Duration {
+        let exp = self.base_delay.as_millis() as f64 * 2f64.powi(attempt.min(32) as i32);
+        let capped = exp.min(self.max_delay.as_millis() as f64).max(0.0) as u64;
+        Duration::from_millis(rand::thread_rng().gen_range(0..=capped))
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            base_delay: Duration::from_millis(200),
+            max_delay: Duration::from_secs(5),
+            max_attempts: 3,
+        }
+    }
+}
+
+/// Whether an HTTP status code is worth retrying on its own: request timeout (408),
+/// rate limiting (429), and upstream unavailability (500/502/503/504) are transient;
+/// any other 4xx (including a plain verification rejection) is a terminal client error
+pub fn is_retryable_status(status: u16) -> bool {
+    matches!(status, 408 | 429 | 500 | 502 | 503 | 504)
+}
+
+/// Whether a transport-level [`reqwest::Error`] (connection refused, DNS failure,
+/// timed-out request) is worth retrying, as opposed to e.g. a body that failed to
+/// deserialize, which retrying won't fix
+pub fn is_retryable_transport_error(error: &reqwest::Error) -> bool {
+    error.is_timeout() || error.is_connect() || error.is_request()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_retryable_status_covers_timeout_rate_limit_and_5xx() {
+        for status in [408, 429, 500, 502, 503, 504] {
+            assert!(is_retryable_status(status), "{status} should be retryable");
+        }
+    }
+
+    #[test]
+    fn test_is_retryable_status_rejects_other_4xx() {
+        for status in [400, 401, 403, 404, 409, 422] {
+            assert!(!is_retryable_status(status), "{status} should not be retryable");
+        }
+    }
+
+    #[test]
+    fn test_delay_for_attempt_is_capped_at_max_delay() {
+        let policy = RetryPolicy::new()
+            .with_base_delay(Duration::from_millis(200))
+            .with_max_delay(Duration::from_millis(500));
+        for attempt in 0..8 {
+            assert!(policy.delay_for_attempt(attempt) <= Duration::from_millis(500));
+        }
+    }
+
+    #[test]
+    fn test_delay_for_attempt_grows_with_attempt_number() {
+        let policy = RetryPolicy::new()
+            .with_base_delay(Duration::from_millis(100))
+            .with_max_delay(Duration::from_secs(60));
+        // Full jitter means any individual sample can be small, but the ceiling each
+        // attempt is drawn from should strictly increase until it hits max_delay.
+        let ceiling = |attempt: u32| {
+            let exp = 100f64 * 2f64.powi(attempt as i32);
+            exp.min(60_000.0) as u64
+        };
+        assert!(ceiling(1) > ceiling(0));
+        assert!(ceiling(2) > ceiling(1));
+    }
+}
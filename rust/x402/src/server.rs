@@ -1,5 +1,6 @@
 use reqwest::header::HeaderMap;
 use serde::{Deserialize, Serialize};
+use crate::retry::{is_retryable_status, is_retryable_transport_error, RetryPolicy};
 use crate::types::{PaymentRequirements, PaymentPayload, CdpVerifyRequestV1, CdpPaymentPayloadV1, CdpExactPayloadV1, CdpAuthorizationV1, CdpPaymentRequirementsV1};
 use crate::errors::{X402Error, X402Result};
 
@@ -43,16 +44,128 @@ pub struct SettleResponse {
     pub network: String,
 }
 
+/// A `(scheme, network)` pair a [`Facilitator`] is willing to verify/settle, e.g.
+/// `("exact", "base-sepolia")`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SupportedKind {
+    pub scheme: String,
+    pub network: String,
+}
+
+/// A facilitator capable of verifying and settling x402 payments
+///
+/// [`HttpFacilitator`] is the only real implementation — an HTTP call against a CDP
+/// (`platform/v2/x402`)-compatible facilitator service — but routing through this
+/// trait rather than calling it directly is what lets [`RetryingFacilitator`] wrap it
+/// (or any future facilitator transport) in retry logic without either side knowing
+/// about the other, and what lets [`FacilitatorRegistry`] hold several side by side
+/// (e.g. the Coinbase HTTP facilitator for `exact`/`base` alongside a local in-process
+/// one for testnets) instead of a server hard-coding a single backend.
+#[async_trait::async_trait]
+pub trait Facilitator: Send + Sync {
+    /// Ask the facilitator whether `payload` satisfies `requirements`
+    async fn verify(
+        &self,
+        payload: PaymentPayload,
+        requirements: PaymentRequirements,
+    ) -> X402Result<VerifyResponse>;
+
+    /// Ask the facilitator to settle a previously verified `payload` on-chain
+    async fn settle(
+        &self,
+        payload: PaymentPayload,
+        requirements: PaymentRequirements,
+    ) -> X402Result<SettleResponse>;
+
+    /// Which `(scheme, network)` pairs this facilitator is willing to verify/settle
+    async fn supported_kinds(&self) -> X402Result<Vec<SupportedKind>>;
+}
+
+/// Build the CDP (`platform/v2/x402`) verify/settle request body out of a
+/// [`PaymentPayload`]/[`PaymentRequirements`] pair
+///
+/// Both `verify` and `settle` send the same shape to their respective endpoints, so
+/// this is shared rather than duplicated between [`HttpFacilitator::verify`] and
+/// [`HttpFacilitator::settle`].
+fn build_cdp_request(payload: &PaymentPayload, requirements: &PaymentRequirements) -> CdpVerifyRequestV1 {
+    let authorization = payload.payload.get("authorization");
+
+    CdpVerifyRequestV1 {
+        x402_version: payload.x402_version,
+        payment_payload: CdpPaymentPayloadV1 {
+            x402_version: payload.x402_version,
+            scheme: payload.accepted.scheme.clone(),
+            network: payload.accepted.network.clone(),
+            payload: CdpExactPayloadV1 {
+                signature: payload.payload.get("signature")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("0x...")
+                    .to_string(),
+                authorization: CdpAuthorizationV1 {
+                    from: authorization
+                        .and_then(|a| a.get("from"))
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("0x...")
+                        .to_string(),
+                    to: authorization
+                        .and_then(|a| a.get("to"))
+                        .and_then(|v| v.as_str())
+                        .unwrap_or(requirements.pay_to.as_str())
+                        .to_string(),
+                    value: authorization
+                        .and_then(|a| a.get("value"))
+                        .and_then(|v| v.as_str())
+                        .unwrap_or(requirements.value.as_str())
+                        .to_string(),
+                    valid_after: authorization
+                        .and_then(|a| a.get("valid_after"))
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("...")
+                        .to_string(),
+                    valid_before: authorization
+                        .and_then(|a| a.get("valid_before"))
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("...")
+                        .to_string(),
+                    nonce: authorization
+                        .and_then(|a| a.get("nonce"))
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("0x...")
+                        .to_string(),
+                },
+            },
+        },
+        payment_requirements: CdpPaymentRequirementsV1 {
+            scheme: requirements.scheme.clone(),
+            network: requirements.network.clone(),
+            max_amount_required: requirements.value.clone(),
+            resource: payload.resource.clone(),
+            description: String::new(),
+            mime_type: String::new(),
+            pay_to: requirements.pay_to.clone(),
+            max_timeout_seconds: 0,
+            asset: requirements.asset.clone().unwrap_or_else(|| "0x...".to_string()),
+        },
+    }
+}
 
-pub struct Facilitator {
+/// Default [`Facilitator`]: posts the CDP `platform/v2/x402` DTOs straight to
+/// `{base_url}/verify` and `{base_url}/settle`
+///
+/// This was a bare struct with no `Facilitator` impl before this existed — callers
+/// went through its inherent `verify`/`settle` methods directly, and `settle` built a
+/// plain (non-CDP) request body that the real facilitator doesn't understand. Both
+/// are fixed here: `settle` now shares [`build_cdp_request`] with `verify`, and both
+/// are exposed through the trait so [`RetryingFacilitator`] can wrap either.
+pub struct HttpFacilitator {
     pub url: String,
     client: reqwest::Client,
     headers: HeaderMap,
 }
 
-impl Facilitator {
+impl HttpFacilitator {
     pub fn new(url: &str) -> Self {
-        Facilitator {
+        HttpFacilitator {
             url: url.to_string(),
             client: reqwest::Client::new(),
             headers: HeaderMap::new(),
@@ -60,79 +173,23 @@ impl Facilitator {
     }
 
     pub fn with_headers(url: &str, headers: reqwest::header::HeaderMap) -> Self {
-        Facilitator {
+        HttpFacilitator {
             url: url.to_string(),
             client: reqwest::Client::new(),
             headers,
         }
     }
+}
 
-    pub async fn verify(
+#[async_trait::async_trait]
+impl Facilitator for HttpFacilitator {
+    async fn verify(
         &self,
         payload: PaymentPayload,
         requirements: PaymentRequirements,
     ) -> X402Result<VerifyResponse> {
-        // This is a simplified version of the TypeScript implementation.
-        // It assumes the use of Coinbase's facilitator and will be abstracted to a plug-in system in the future.
         let url = format!("{}/verify", self.url.trim_end_matches('/'));
-
-        let request = CdpVerifyRequestV1 {
-            x402_version: payload.x402_version,
-            payment_payload: CdpPaymentPayloadV1 {
-                x402_version: payload.x402_version,
-                scheme: payload.accepted.scheme.clone(),
-                network: payload.accepted.network.clone(),
-                payload: CdpExactPayloadV1 {
-                    signature: payload.payload.get("signature")
-                        .and_then(|v| v.as_str())
-                        .unwrap_or("0x...")
-                        .to_string(),
-                    authorization: CdpAuthorizationV1 {
-                        from: payload.payload.get("authorization")
-                            .and_then(|a| a.get("from"))
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("0x...")
-                            .to_string(),
-                        to: payload.payload.get("authorization")
-                            .and_then(|a| a.get("to"))
-                            .and_then(|v| v.as_str())
-                            .unwrap_or(requirements.pay_to.as_str())
-                            .to_string(),
-                        value: payload.payload.get("authorization")
-                            .and_then(|a| a.get("value"))
-                            .and_then(|v| v.as_str())
-                            .unwrap_or(requirements.value.as_str())
-                            .to_string(),
-                        valid_after: payload.payload.get("authorization")
-                            .and_then(|a| a.get("valid_after"))
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("...")
-                            .to_string(),
-                        valid_before: payload.payload.get("authorization")
-                            .and_then(|a| a.get("valid_before"))
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("...")
-                            .to_string(),
-                        nonce: payload.payload.get("authorization")
-                            .and_then(|a| a.get("nonce"))
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("0x...")
-                            .to_string(),
-                    },
-                },
-            },
-            payment_requirements: CdpPaymentRequirementsV1 {
-                scheme: requirements.scheme.clone(),
-                network: requirements.network.clone(),
-                max_amount_required: requirements.value.clone(),
-                resource: payload.resource.clone(),
-                pay_to: requirements.pay_to.clone(),
-                asset: requirements.asset.clone().unwrap_or_else(|| "0x...".to_string()),
-            },
-        };
-
-
-        dbg!(&request);
+        let request = build_cdp_request(&payload, &requirements);
 
         let response = self.client.post(url)
             .headers(self.headers.clone())
@@ -149,20 +206,13 @@ impl Facilitator {
         Ok(response.json::<VerifyResponse>().await?)
     }
 
-    pub async fn settle(
+    async fn settle(
         &self,
         payload: PaymentPayload,
         requirements: PaymentRequirements,
-    ) -> Result<SettleResponse, reqwest::Error> {
-        // This is a simplified version of the TypeScript implementation.
-        // It assumes the use of Coinbase's facilitator and will be abstracted to a plug-in system in the future.
-        let url = format!("{}/settle", self.url);
-
-        let request = VerifyRequest {
-            x402_version: payload.x402_version,
-            payment_payload: payload,
-            payment_requirements: requirements
-        };
+    ) -> X402Result<SettleResponse> {
+        let url = format!("{}/settle", self.url.trim_end_matches('/'));
+        let request = build_cdp_request(&payload, &requirements);
 
         let response = self.client.post(url)
             .headers(self.headers.clone())
@@ -170,6 +220,351 @@ impl Facilitator {
             .send()
             .await?;
 
-        response.json::<SettleResponse>().await
+        let response_status = response.status();
+        if !response_status.is_success() {
+            let err_text = response.text().await.unwrap_or_else(|_| String::from("Unknown Error"));
+            return Err(X402Error::FacilitatorRejection(response_status.as_u16(), err_text))
+        }
+
+        Ok(response.json::<SettleResponse>().await?)
+    }
+
+    async fn supported_kinds(&self) -> X402Result<Vec<SupportedKind>> {
+        let url = format!("{}/supported", self.url.trim_end_matches('/'));
+
+        let response = self.client.get(url)
+            .headers(self.headers.clone())
+            .send()
+            .await?;
+
+        let response_status = response.status();
+        if !response_status.is_success() {
+            let err_text = response.text().await.unwrap_or_else(|_| String::from("Unknown Error"));
+            return Err(X402Error::FacilitatorRejection(response_status.as_u16(), err_text))
+        }
+
+        let body: SupportedKindsResponse = response.json().await?;
+        Ok(body.kinds)
+    }
+}
+
+/// Shape of the CDP facilitator's `GET /supported` response
+#[derive(Debug, Serialize, Deserialize)]
+struct SupportedKindsResponse {
+    kinds: Vec<SupportedKind>,
+}
+
+/// Retries `verify`/`settle` calls against a wrapped [`Facilitator`] with exponential
+/// backoff and full jitter, per `policy`
+///
+/// Only retries what's actually transient: a network/timeout error
+/// ([`is_retryable_transport_error`]), an explicit 429, or a 5xx
+/// [`X402Error::FacilitatorRejection`] ([`is_retryable_status`]). Any other
+/// [`X402Error::FacilitatorRejection`] — a plain 4xx verification failure — is
+/// treated as terminal and returned on the first attempt.
+pub struct RetryingFacilitator<F: Facilitator> {
+    inner: F,
+    policy: RetryPolicy,
+}
+
+impl<F: Facilitator> RetryingFacilitator<F> {
+    pub fn new(inner: F, policy: RetryPolicy) -> Self {
+        Self { inner, policy }
+    }
+
+    fn is_retryable(error: &X402Error) -> bool {
+        match error {
+            X402Error::FacilitatorError(reqwest_error) => is_retryable_transport_error(reqwest_error),
+            X402Error::FacilitatorRejection(status, _) => is_retryable_status(*status),
+            _ => false,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<F: Facilitator> Facilitator for RetryingFacilitator<F> {
+    async fn verify(
+        &self,
+        payload: PaymentPayload,
+        requirements: PaymentRequirements,
+    ) -> X402Result<VerifyResponse> {
+        for attempt in 0..self.policy.max_attempts {
+            match self.inner.verify(payload.clone(), requirements.clone()).await {
+                Ok(response) => return Ok(response),
+                Err(error) if Self::is_retryable(&error) && attempt + 1 < self.policy.max_attempts => {
+                    tokio::time::sleep(self.policy.delay_for_attempt(attempt)).await;
+                }
+                Err(error) => return Err(error),
+            }
+        }
+        unreachable!("max_attempts is always >= 1")
+    }
+
+    async fn settle(
+        &self,
+        payload: PaymentPayload,
+        requirements: PaymentRequirements,
+    ) -> X402Result<SettleResponse> {
+        for attempt in 0..self.policy.max_attempts {
+            match self.inner.settle(payload.clone(), requirements.clone()).await {
+                Ok(response) => return Ok(response),
+                Err(error) if Self::is_retryable(&error) && attempt + 1 < self.policy.max_attempts => {
+                    tokio::time::sleep(self.policy.delay_for_attempt(attempt)).await;
+                }
+                Err(error) => return Err(error),
+            }
+        }
+        unreachable!("max_attempts is always >= 1")
+    }
+
+    async fn supported_kinds(&self) -> X402Result<Vec<SupportedKind>> {
+        for attempt in 0..self.policy.max_attempts {
+            match self.inner.supported_kinds().await {
+                Ok(kinds) => return Ok(kinds),
+                Err(error) if Self::is_retryable(&error) && attempt + 1 < self.policy.max_attempts => {
+                    tokio::time::sleep(self.policy.delay_for_attempt(attempt)).await;
+                }
+                Err(error) => return Err(error),
+            }
+        }
+        unreachable!("max_attempts is always >= 1")
+    }
+}
+
+/// Dispatches a [`PaymentPayload`] to whichever registered [`Facilitator`] declares
+/// support for its `(scheme, network)` pair, instead of a server hard-coding a single
+/// backend.
+///
+/// Each registered backend is paired with the [`PaymentRequirements`] it should be
+/// advertised as in a `402`'s `accepts` array (see [`Self::accepts`]), so that array
+/// can be assembled automatically from whatever backends are registered rather than
+/// hand-built per endpoint.
+#[derive(Default)]
+pub struct FacilitatorRegistry {
+    backends: std::collections::HashMap<SupportedKind, (PaymentRequirements, std::sync::Arc<dyn Facilitator>)>,
+}
+
+impl FacilitatorRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register `backend` to serve payments matching `requirements.scheme`/
+    /// `requirements.network`, and advertise `requirements` itself as one of the
+    /// options [`Self::accepts`] returns
+    pub fn register(mut self, requirements: PaymentRequirements, backend: std::sync::Arc<dyn Facilitator>) -> Self {
+        let kind = SupportedKind {
+            scheme: requirements.scheme.clone(),
+            network: requirements.network.clone(),
+        };
+        self.backends.insert(kind, (requirements, backend));
+        self
+    }
+
+    /// Every [`PaymentRequirements`] a registered backend was registered with, in the
+    /// order a `402`'s `accepts` array should offer them
+    pub fn accepts(&self) -> Vec<PaymentRequirements> {
+        self.backends.values().map(|(requirements, _)| requirements.clone()).collect()
+    }
+
+    fn resolve(&self, scheme: &str, network: &str) -> X402Result<&std::sync::Arc<dyn Facilitator>> {
+        let kind = SupportedKind { scheme: scheme.to_string(), network: network.to_string() };
+        self.backends
+            .get(&kind)
+            .map(|(_, backend)| backend)
+            .ok_or_else(|| X402Error::ConfigError(format!(
+                "no facilitator registered for scheme {:?} on network {:?}",
+                scheme, network,
+            )))
+    }
+
+    /// Verify `payload` against `requirements` using the backend registered for
+    /// `payload.accepted.scheme`/`payload.accepted.network`
+    pub async fn verify(
+        &self,
+        payload: PaymentPayload,
+        requirements: PaymentRequirements,
+    ) -> X402Result<VerifyResponse> {
+        let backend = self.resolve(&payload.accepted.scheme, &payload.accepted.network)?;
+        backend.verify(payload, requirements).await
+    }
+
+    /// Settle `payload` against `requirements` using the backend registered for
+    /// `payload.accepted.scheme`/`payload.accepted.network`
+    pub async fn settle(
+        &self,
+        payload: PaymentPayload,
+        requirements: PaymentRequirements,
+    ) -> X402Result<SettleResponse> {
+        let backend = self.resolve(&payload.accepted.scheme, &payload.accepted.network)?;
+        backend.settle(payload, requirements).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn test_payload() -> (PaymentPayload, PaymentRequirements) {
+        let requirements = PaymentRequirements {
+            scheme: "exact".to_string(),
+            network: "base-sepolia".to_string(),
+            pay_to: "0x209693Bc6afc0C5328bA36FaF03C514EF312287C".to_string(),
+            value: "1000000".to_string(),
+            asset: Some("0x036CbD53842c5426634e7929541eC2318f3dCF7e".to_string()),
+            data: None,
+        };
+        let payload = PaymentPayload {
+            x402_version: 1,
+            resource: "https://example.com/test".to_string(),
+            accepted: requirements.clone(),
+            payload: json!({"signature": "0xabc"}),
+            extensions: None,
+        };
+        (payload, requirements)
+    }
+
+    struct FlakyFacilitator {
+        failures_before_success: usize,
+        calls: AtomicUsize,
+        error: fn() -> X402Error,
+    }
+
+    #[async_trait::async_trait]
+    impl Facilitator for FlakyFacilitator {
+        async fn verify(
+            &self,
+            _payload: PaymentPayload,
+            _requirements: PaymentRequirements,
+        ) -> X402Result<VerifyResponse> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst);
+            if call < self.failures_before_success {
+                return Err((self.error)());
+            }
+            Ok(VerifyResponse { is_valid: true, invalid_reason: None, payer: None })
+        }
+
+        async fn settle(
+            &self,
+            _payload: PaymentPayload,
+            _requirements: PaymentRequirements,
+        ) -> X402Result<SettleResponse> {
+            unimplemented!("not exercised by these tests")
+        }
+
+        async fn supported_kinds(&self) -> X402Result<Vec<SupportedKind>> {
+            unimplemented!("not exercised by these tests")
+        }
+    }
+
+    fn fast_policy() -> RetryPolicy {
+        RetryPolicy::new()
+            .with_base_delay(std::time::Duration::from_millis(1))
+            .with_max_delay(std::time::Duration::from_millis(5))
+            .with_max_attempts(3)
+    }
+
+    #[tokio::test]
+    async fn test_retrying_facilitator_succeeds_after_transient_failures() {
+        let (payload, requirements) = test_payload();
+        let inner = FlakyFacilitator {
+            failures_before_success: 2,
+            calls: AtomicUsize::new(0),
+            error: || X402Error::FacilitatorRejection(503, "unavailable".to_string()),
+        };
+        let facilitator = RetryingFacilitator::new(inner, fast_policy());
+
+        let response = facilitator.verify(payload, requirements).await.expect("should eventually succeed");
+        assert!(response.is_valid);
+    }
+
+    #[tokio::test]
+    async fn test_retrying_facilitator_retries_429() {
+        let (payload, requirements) = test_payload();
+        let inner = FlakyFacilitator {
+            failures_before_success: 1,
+            calls: AtomicUsize::new(0),
+            error: || X402Error::FacilitatorRejection(429, "rate limited".to_string()),
+        };
+        let facilitator = RetryingFacilitator::new(inner, fast_policy());
+
+        let response = facilitator.verify(payload, requirements).await.expect("should eventually succeed");
+        assert!(response.is_valid);
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_retrying_facilitator_does_not_retry_terminal_4xx() {
+        let (payload, requirements) = test_payload();
+        let inner = FlakyFacilitator {
+            failures_before_success: usize::MAX,
+            calls: AtomicUsize::new(0),
+            error: || X402Error::FacilitatorRejection(400, "bad request".to_string()),
+        };
+        let facilitator = RetryingFacilitator::new(inner, fast_policy());
+
+        let error = facilitator.verify(payload, requirements).await.unwrap_err();
+        assert!(matches!(error, X402Error::FacilitatorRejection(400, _)));
+        assert_eq!(facilitator.inner.calls.load(Ordering::SeqCst), 1, "a terminal 4xx must not be retried");
+    }
+
+    #[tokio::test]
+    async fn test_retrying_facilitator_gives_up_after_max_attempts() {
+        let (payload, requirements) = test_payload();
+        let inner = FlakyFacilitator {
+            failures_before_success: usize::MAX,
+            calls: AtomicUsize::new(0),
+            error: || X402Error::FacilitatorRejection(503, "unavailable".to_string()),
+        };
+        let facilitator = RetryingFacilitator::new(inner, fast_policy());
+
+        let error = facilitator.verify(payload, requirements).await.unwrap_err();
+        assert!(matches!(error, X402Error::FacilitatorRejection(503, _)));
+        assert_eq!(facilitator.inner.calls.load(Ordering::SeqCst), 3, "should stop after max_attempts");
+    }
+
+    fn always_valid_facilitator() -> FlakyFacilitator {
+        FlakyFacilitator {
+            failures_before_success: 0,
+            calls: AtomicUsize::new(0),
+            error: || X402Error::FacilitatorRejection(500, "unused".to_string()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_facilitator_registry_dispatches_to_the_matching_backend() {
+        let (base_payload, base_requirements) = test_payload();
+        let solana_requirements = PaymentRequirements {
+            scheme: "exact".to_string(),
+            network: "solana".to_string(),
+            ..base_requirements.clone()
+        };
+
+        let registry = FacilitatorRegistry::new()
+            .register(base_requirements.clone(), std::sync::Arc::new(always_valid_facilitator()))
+            .register(solana_requirements.clone(), std::sync::Arc::new(always_valid_facilitator()));
+
+        let mut accepts = registry.accepts();
+        accepts.sort_by(|a, b| a.network.cmp(&b.network));
+        assert_eq!(accepts.len(), 2);
+        assert_eq!(accepts[0].network, "base-sepolia");
+        assert_eq!(accepts[1].network, "solana");
+
+        let response = registry
+            .verify(base_payload, base_requirements)
+            .await
+            .expect("base-sepolia backend is registered");
+        assert!(response.is_valid);
+    }
+
+    #[tokio::test]
+    async fn test_facilitator_registry_rejects_an_unregistered_kind() {
+        let (payload, requirements) = test_payload();
+        let registry = FacilitatorRegistry::new();
+
+        let error = registry.verify(payload, requirements).await.unwrap_err();
+        assert!(matches!(error, X402Error::ConfigError(_)));
+    }
+}
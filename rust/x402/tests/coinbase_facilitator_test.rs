@@ -5,7 +5,7 @@ use serde_json::Value;
 use std::env;
 use alloy::signers::k256::ecdsa::SigningKey;
 use alloy::signers::local::PrivateKeySigner;
-use x402::server::Facilitator;
+use x402::server::{Facilitator, HttpFacilitator};
 use x402::types::{PaymentPayload, PaymentRequired, PaymentRequirements, CdpAuthorizationV1, CdpExactPayloadV1};
 use x402::auth::WalletAuth;
 use x402::schemes::evm::sign_exact_payment;
@@ -40,7 +40,7 @@ async fn test_coinbase_facilitator_integration() {
         HeaderValue::from_str(format!("Bearer {jwt}").as_str()).unwrap());
     headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
 
-    let facilitator = Facilitator::with_headers(facilitator_url, headers);
+    let facilitator = HttpFacilitator::with_headers(facilitator_url, headers);
 
     // Sign a transaction to pass into the payment payload
     let wallet_private_key = std::env::var("WALLET_PRIVATE_KEY")
@@ -130,7 +130,7 @@ async fn test_coinbase_facilitator_integration() {
 #[tokio::test]
 async fn test_facilitator_supported() {
     let facilitator_url = "https://x402.org/facilitator";
-    let facilitator = Facilitator::new(facilitator_url);
+    let facilitator = HttpFacilitator::new(facilitator_url);
 
     let client = Client::new();
     let res = client.get(format!("{}/supported", facilitator_url)).send().await.unwrap();
@@ -9,7 +9,7 @@ use wiremock::{Mock, MockServer, ResponseTemplate};
 use wiremock::matchers::{method, path};
 use x402::client::X402Client;
 use x402::frameworks::axum_integration::{x402_middleware, X402Config};
-use x402::server::{Facilitator, VerifyResponse};
+use x402::server::{HttpFacilitator, VerifyResponse};
 use x402::types::{PaymentPayload, PaymentRequired, PaymentRequirements};
 
 #[tokio::test]
@@ -28,7 +28,7 @@ async fn test_x402_axum_flow_with_mock_facilitator() {
         .mount(&mock_server)
         .await;
 
-    let facilitator = Arc::new(Facilitator::new(&mock_server.uri()));
+    let facilitator = Arc::new(HttpFacilitator::new(&mock_server.uri()));
 
     let payment_requirements = PaymentRequired {
         x402_version: 0,